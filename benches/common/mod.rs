@@ -0,0 +1,114 @@
+// helpers shared between the benchmark targets, each of which only uses some of them
+#![allow(dead_code)]
+
+use std::env;
+use std::io::Write;
+
+use rand::distributions::{Distribution, Normal, Uniform};
+use rand::{SeedableRng, StdRng};
+use tempfile::NamedTempFile;
+
+use agnes::cons::Nil;
+use agnes::field::FieldData;
+use agnes::store::DataStore;
+
+tablespace![
+    pub table bench {
+        Id: u64,
+        Group: u64,
+        Value: f64,
+    }
+    pub table names {
+        Name: String,
+    }
+    pub table lookup {
+        GroupId: u64,
+        GroupName: String,
+    }
+    pub table agg {
+        Total: f64,
+    }
+];
+
+/// Number of distinct values in the `Group` field of generated tables.
+pub const NUM_GROUPS: u64 = 100;
+
+/// Default benchmark table sizes (number of rows).
+const SIZES: [usize; 3] = [100_000, 1_000_000, 10_000_000];
+
+/// Returns the table sizes to benchmark. The largest size can be capped by setting the
+/// `AGNES_BENCH_MAX_ROWS` environment variable.
+pub fn sizes() -> Vec<usize> {
+    let max_rows = env::var("AGNES_BENCH_MAX_ROWS")
+        .ok()
+        .and_then(|max_rows| max_rows.parse::<usize>().ok())
+        .unwrap_or(usize::MAX);
+    SIZES
+        .iter()
+        .cloned()
+        .filter(|&size| size <= max_rows)
+        .collect()
+}
+
+fn rng() -> StdRng {
+    StdRng::from_seed([42; 32])
+}
+
+/// Generates the `Id`, `Group`, and `Value` fields for a table with `nrows` rows.
+pub fn gen_fields(nrows: usize) -> (FieldData<u64>, FieldData<u64>, FieldData<f64>) {
+    let mut rng = rng();
+    let groups = Uniform::new(0, NUM_GROUPS);
+    let values = Normal::new(100.0, 25.0);
+    (
+        FieldData::from_vec((0..nrows as u64).collect::<Vec<_>>()),
+        FieldData::from_vec(groups.sample_iter(&mut rng).take(nrows).collect::<Vec<_>>()),
+        FieldData::from_vec(values.sample_iter(&mut rng).take(nrows).collect::<Vec<_>>()),
+    )
+}
+
+/// Generates a view with `Id`, `Group`, and `Value` fields and `nrows` rows.
+pub fn gen_view(nrows: usize) -> bench::View {
+    let (ids, groups, values) = gen_fields(nrows);
+    DataStore::<Nil>::empty()
+        .push_back_field::<bench::Id, _>(ids)
+        .push_back_field::<bench::Group, _>(groups)
+        .push_back_field::<bench::Value, _>(values)
+        .into_view()
+}
+
+/// Generates the lookup table of group names, with one row per group.
+pub fn gen_lookup_view() -> lookup::View {
+    DataStore::<Nil>::empty()
+        .push_back_field::<lookup::GroupId, _>(FieldData::from_vec(
+            (0..NUM_GROUPS).collect::<Vec<_>>(),
+        ))
+        .push_back_field::<lookup::GroupName, _>(FieldData::from_vec(
+            (0..NUM_GROUPS)
+                .map(|group| format!("Group {}", group))
+                .collect::<Vec<_>>(),
+        ))
+        .into_view()
+}
+
+/// Writes a CSV file containing a table with `Id`, `Group`, `Value`, and `Name` fields and `nrows`
+/// rows to a temporary file.
+pub fn gen_csv(nrows: usize) -> NamedTempFile {
+    let (ids, groups, values) = gen_fields(nrows);
+    let mut file = NamedTempFile::new().expect("unable to create temporary file");
+    {
+        let mut writer = ::std::io::BufWriter::new(file.as_file_mut());
+        writeln!(writer, "Id,Group,Value,Name").unwrap();
+        for idx in 0..nrows {
+            writeln!(
+                writer,
+                "{},{},{},Name {}",
+                ids.get(idx).unwrap().unwrap(),
+                groups.get(idx).unwrap().unwrap(),
+                values.get(idx).unwrap().unwrap(),
+                idx
+            )
+            .unwrap();
+        }
+    }
+    file
+}
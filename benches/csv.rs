@@ -0,0 +1,39 @@
+#[macro_use]
+extern crate agnes;
+#[macro_use]
+extern crate criterion;
+extern crate rand;
+extern crate tempfile;
+
+mod common;
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+
+use agnes::source::csv::{CsvReader, CsvSource};
+
+use common::{bench, names};
+
+fn load_csv(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load_csv");
+    group.sample_size(10);
+    for &nrows in &common::sizes() {
+        let file = common::gen_csv(nrows);
+        group.throughput(Throughput::Elements(nrows as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(nrows), &nrows, |b, _| {
+            b.iter(|| {
+                let schema = schema![
+                    fieldname bench::Id = "Id";
+                    fieldname bench::Group = "Group";
+                    fieldname bench::Value = "Value";
+                    fieldname names::Name = "Name";
+                ];
+                let source = CsvSource::new(file.path()).unwrap();
+                CsvReader::new(&source, schema).unwrap().read().unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, load_csv);
+criterion_main!(benches);
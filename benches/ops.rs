@@ -0,0 +1,69 @@
+#[macro_use]
+extern crate agnes;
+#[macro_use]
+extern crate criterion;
+extern crate rand;
+extern crate tempfile;
+
+mod common;
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+
+use agnes::select::FieldSelect;
+use agnes::stats::{Mean, Sum};
+
+use common::bench;
+
+fn scalar_ops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scalar_ops");
+    group.sample_size(10);
+    for &nrows in &common::sizes() {
+        let dv = common::gen_view(nrows);
+        let field = dv.field::<bench::Value>();
+        group.throughput(Throughput::Elements(nrows as u64));
+        group.bench_with_input(BenchmarkId::new("add", nrows), &nrows, |b, _| {
+            b.iter(|| &field + 2.0)
+        });
+        group.bench_with_input(BenchmarkId::new("mul", nrows), &nrows, |b, _| {
+            b.iter(|| &field * 2.0)
+        });
+    }
+    group.finish();
+}
+
+fn field_ops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("field_ops");
+    group.sample_size(10);
+    for &nrows in &common::sizes() {
+        let dv = common::gen_view(nrows);
+        let (ids, groups) = (dv.field::<bench::Id>(), dv.field::<bench::Group>());
+        group.throughput(Throughput::Elements(nrows as u64));
+        group.bench_with_input(BenchmarkId::new("add", nrows), &nrows, |b, _| {
+            b.iter(|| &ids + &groups)
+        });
+        group.bench_with_input(BenchmarkId::new("mul", nrows), &nrows, |b, _| {
+            b.iter(|| &ids * &groups)
+        });
+    }
+    group.finish();
+}
+
+fn stats(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stats");
+    group.sample_size(10);
+    for &nrows in &common::sizes() {
+        let dv = common::gen_view(nrows);
+        let field = dv.field::<bench::Value>();
+        group.throughput(Throughput::Elements(nrows as u64));
+        group.bench_with_input(BenchmarkId::new("sum", nrows), &nrows, |b, _| {
+            b.iter(|| field.sum())
+        });
+        group.bench_with_input(BenchmarkId::new("mean", nrows), &nrows, |b, _| {
+            b.iter(|| field.mean())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, scalar_ops, field_ops, stats);
+criterion_main!(benches);
@@ -0,0 +1,93 @@
+#[macro_use]
+extern crate agnes;
+#[macro_use]
+extern crate criterion;
+extern crate rand;
+extern crate tempfile;
+
+mod common;
+
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput};
+
+use agnes::join::{Equal, Join};
+use agnes::value::Value;
+
+use common::{agg, bench, lookup};
+
+fn filter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("filter");
+    group.sample_size(10);
+    for &nrows in &common::sizes() {
+        let dv = common::gen_view(nrows);
+        group.throughput(Throughput::Elements(nrows as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(nrows), &nrows, |b, _| {
+            b.iter_batched(
+                || dv.clone(),
+                |dv| dv.filter::<bench::Value, _>(|value: Value<&f64>| value > valref![100.0]),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn sort(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sort");
+    group.sample_size(10);
+    for &nrows in &common::sizes() {
+        let dv = common::gen_view(nrows);
+        group.throughput(Throughput::Elements(nrows as u64));
+        group.bench_with_input(BenchmarkId::new("stable", nrows), &nrows, |b, _| {
+            b.iter_batched(
+                || dv.clone(),
+                |dv| dv.sort_by_label::<bench::Group>(),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("unstable", nrows), &nrows, |b, _| {
+            b.iter_batched(
+                || dv.clone(),
+                |dv| dv.sort_unstable_by_label::<bench::Group>(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn join(c: &mut Criterion) {
+    let mut group = c.benchmark_group("join");
+    group.sample_size(10);
+    let dv_lookup = common::gen_lookup_view();
+    for &nrows in &common::sizes() {
+        let dv = common::gen_view(nrows);
+        group.throughput(Throughput::Elements(nrows as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(nrows), &nrows, |b, _| {
+            b.iter(|| dv.join::<Join<bench::Group, lookup::GroupId, Equal>, _, _>(&dv_lookup))
+        });
+    }
+    group.finish();
+}
+
+fn aggregate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("aggregate");
+    group.sample_size(10);
+    for &nrows in &common::sizes() {
+        let dv = common::gen_view(nrows);
+        group.throughput(Throughput::Elements(nrows as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(nrows), &nrows, |b, _| {
+            b.iter(|| {
+                dv.aggregate::<Labels![bench::Group], bench::Value, agg::Total, _, _, _>(
+                    0.0,
+                    |accum, value| {
+                        *accum += value.unwrap_or(&0.0);
+                    },
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, filter, sort, join, aggregate);
+criterion_main!(benches);
@@ -2,12 +2,26 @@
 Traits for accessing data within agnes data structures.
 
 The [DataIndex](trait.DataIndex.html) trait provides index-based access to a field's data as well
-as method which generates a [DataIterator](struct.DataIterator.html).
+as method which generates a [DataIterator](struct.DataIterator.html). Fields can also be
+processed in blocks using a [ChunkIterator](struct.ChunkIterator.html), which yields
+[DataChunk](struct.DataChunk.html)s of consecutive values. The missing-value mask of a field is
+available as a [NaMask](struct.NaMask.html), which supports bulk counting and combination of masks.
+The values of text fields (`String`, `ArenaStr` or `&str`) can be borrowed as `&str`s, without
+cloning each value, using the [StrDataIndex](trait.StrDataIndex.html) trait.
+
+For code outside this crate which needs direct access to a field's storage (such as converters to
+other columnar formats, or vectorized computations), the [RawColumnar](trait.RawColumnar.html)
+trait exposes the raw value buffer, missing-value mask, and active permutation of a field as a
+[RawColumn](struct.RawColumn.html).
 */
+use std::borrow::Cow;
+use std::cmp;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::rc::Rc;
 
+use bit_vec::BitVec;
+
 use error::*;
 use frame::Framed;
 use value::Value;
@@ -36,6 +50,30 @@ pub trait DataIndex: Debug {
         DataIterator::new(self)
     }
 
+    /// Returns a [DataChunk](struct.DataChunk.html) of (at most) `len` consecutive values in this
+    /// field, starting at index `start`. Returns an error if `start` is beyond the end of this
+    /// field.
+    ///
+    /// The default implementation gathers the values using `get_datum`; implementors which store
+    /// their values contiguously should override this method to provide direct slice access.
+    fn get_chunk(&self, start: usize, len: usize) -> Result<DataChunk<'_, Self::DType>> {
+        gather_chunk(self, start, len)
+    }
+
+    /// Returns an iterator over the values in this field in chunks of `chunk_size` values. The
+    /// last chunk will be shorter than `chunk_size` if the length of this field isn't evenly
+    /// divisible by `chunk_size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    fn iter_chunks(&self, chunk_size: usize) -> ChunkIterator<'_, Self::DType>
+    where
+        Self: Sized,
+    {
+        ChunkIterator::new(self, chunk_size)
+    }
+
     /// Returns a new `DataIndex`-implementing object which provides access to the values in this
     /// field as permuted by `permutation`. `permutation` is a slice of indices into this
     /// `DataIndex`.
@@ -46,6 +84,17 @@ pub trait DataIndex: Debug {
         Framed::new(Rc::new(permutation.to_vec().into()), self)
     }
 
+    /// Returns a [NaMask](struct.NaMask.html) denoting which values in this field exist (are not
+    /// missing).
+    ///
+    /// The default implementation builds the mask using `get_datum`; implementors which store a
+    /// missing-value mask should override this method to provide it directly.
+    fn na_mask(&self) -> NaMask<'_> {
+        NaMask::from_fn(self.len(), |idx| {
+            self.get_datum(idx).is_ok_and(|value| value.exists())
+        })
+    }
+
     /// Copies existing values in this field into a new `Vec`.
     ///
     /// If this field has missing values, this method will return a vector of length less than that
@@ -72,6 +121,35 @@ pub trait DataIndex: Debug {
         self.iter().map(|value| value.cloned()).collect()
     }
 }
+/// Gathers a chunk of (at most) `len` values starting at index `start` from `data` using
+/// `get_datum`.
+pub(crate) fn gather_chunk<DI>(
+    data: &DI,
+    start: usize,
+    len: usize,
+) -> Result<DataChunk<'_, DI::DType>>
+where
+    DI: DataIndex + ?Sized,
+{
+    let end = chunk_end(start, len, data.len())?;
+    let values = (start..end)
+        .map(|idx| data.get_datum(idx))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(DataChunk::from_values(start, values))
+}
+
+/// Computes the (exclusive) end index of a chunk of (at most) `len` values starting at index
+/// `start` in a field of length `field_len`.
+pub(crate) fn chunk_end(start: usize, len: usize, field_len: usize) -> Result<usize> {
+    if start > field_len {
+        return Err(AgnesError::IndexError {
+            index: start,
+            len: field_len,
+        });
+    }
+    Ok(cmp::min(start.saturating_add(len), field_len))
+}
+
 /// Trait that provides mutable access to values in a data field.
 pub trait DataIndexMut: DataIndex {
     /// Add a value to this field.
@@ -91,6 +169,66 @@ pub trait DataIndexMut: DataIndex {
     }
 }
 
+/// Iterator over the values of a text field (with values of type `T`) as borrowed `&str`s,
+/// returned by [StrDataIndex::iter_str](trait.StrDataIndex.html#method.iter_str).
+pub type StrIterator<'a, T> = ValueMap<'a, T, DataIterator<'a, T>, fn(&'a T) -> &'a str>;
+
+/// Trait that provides borrowed (`&str`) access to the values of text fields, so that values can be
+/// inspected without cloning each one. Implemented for every [DataIndex](trait.DataIndex.html)
+/// whose data type can be viewed as a `str` (such as `String` and `ArenaStr`).
+pub trait StrDataIndex: DataIndex<DType = <Self as StrDataIndex>::Text> {
+    /// The text type contained within this field.
+    type Text: AsRef<str>;
+
+    /// Returns the text (possibly NA) at the specified index, if it exists.
+    fn get_str(&self, idx: usize) -> Result<Value<&str>> {
+        self.get_datum(idx)
+            .map(|value| value.map(|value| value.as_ref()))
+    }
+
+    /// Returns an iterator over the values in this field as `&str`s.
+    fn iter_str(&self) -> StrIterator<'_, Self::Text>
+    where
+        Self: Sized,
+    {
+        self.iter()
+            .map_existing(<Self::Text as AsRef<str>>::as_ref as fn(&Self::Text) -> &str)
+    }
+
+    /// Collects references to the existing values in this field into a new `Vec`, without
+    /// cloning the values.
+    ///
+    /// If this field has missing values, this method will return a vector of length less than that
+    /// returned by the `len` method.
+    fn to_str_vec(&self) -> Vec<&str>
+    where
+        Self: Sized,
+    {
+        self.iter_str()
+            .filter_map(|value| match value {
+                Value::Exists(value) => Some(value),
+                Value::Na => None,
+            })
+            .collect()
+    }
+
+    /// Collects references to the values (missing or existing) in this field into a new `Vec`,
+    /// without cloning the values.
+    fn to_str_value_vec(&self) -> Vec<Value<&str>>
+    where
+        Self: Sized,
+    {
+        self.iter_str().collect()
+    }
+}
+impl<DI, T> StrDataIndex for DI
+where
+    DI: DataIndex<DType = T>,
+    T: AsRef<str>,
+{
+    type Text = T;
+}
+
 /// Iterator over the data in a data structure that implement DataIndex.
 pub struct DataIterator<'a, T>
 where
@@ -98,6 +236,7 @@ where
 {
     data: &'a dyn DataIndex<DType = T>,
     cur_idx: usize,
+    end_idx: usize,
     phantom: PhantomData<T>,
 }
 impl<'a, T> DataIterator<'a, T>
@@ -109,6 +248,7 @@ where
         DataIterator {
             data,
             cur_idx: 0,
+            end_idx: data.len(),
             phantom: PhantomData,
         }
     }
@@ -135,7 +275,7 @@ where
     type Item = Value<&'a T>;
 
     fn next(&mut self) -> Option<Value<&'a T>> {
-        if self.cur_idx < self.data.len() {
+        if self.cur_idx < self.end_idx {
             let out = Some(self.data.get_datum(self.cur_idx).unwrap());
             self.cur_idx += 1;
             out
@@ -143,7 +283,26 @@ where
             None
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end_idx - self.cur_idx;
+        (remaining, Some(remaining))
+    }
 }
+impl<'a, T> DoubleEndedIterator for DataIterator<'a, T>
+where
+    T: 'a,
+{
+    fn next_back(&mut self) -> Option<Value<&'a T>> {
+        if self.cur_idx < self.end_idx {
+            self.end_idx -= 1;
+            Some(self.data.get_datum(self.end_idx).unwrap())
+        } else {
+            None
+        }
+    }
+}
+impl<'a, T> ExactSizeIterator for DataIterator<'a, T> where T: 'a {}
 
 /// Mapping iterator applying function `F` to the data in a data structure that implement DataIndex.
 /// `T` is the data type held within this data structure, and `I` is the base iterator that is being
@@ -166,8 +325,473 @@ where
     fn next(&mut self) -> Option<Value<B>> {
         self.iter.next().map(|value| value.map(&mut self.f))
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+impl<'a, B, T, I, F> DoubleEndedIterator for ValueMap<'a, T, I, F>
+where
+    I: DoubleEndedIterator<Item = Value<&'a T>>,
+    F: FnMut(&'a T) -> B,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Value<B>> {
+        self.iter.next_back().map(|value| value.map(&mut self.f))
+    }
+}
+impl<'a, B, T, I, F> ExactSizeIterator for ValueMap<'a, T, I, F>
+where
+    I: ExactSizeIterator<Item = Value<&'a T>>,
+    F: FnMut(&'a T) -> B,
+{
+}
+
+/// An immutable view of the missing-value mask of a field, generated by the
+/// [na_mask](trait.DataIndex.html#method.na_mask) method of `DataIndex`. Each bit of the mask
+/// corresponds to a value in the field, and is set if that value exists (is not missing).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NaMask<'a> {
+    bits: Cow<'a, BitVec>,
 }
 
+impl<'a> NaMask<'a> {
+    /// Create a new `NaMask` referencing the bit mask `bits`, where set bits denote existing
+    /// values.
+    pub fn from_bitvec(bits: &'a BitVec) -> NaMask<'a> {
+        NaMask {
+            bits: Cow::Borrowed(bits),
+        }
+    }
+
+    /// Create a new `NaMask` of length `len` denoting that all values exist.
+    pub fn all_exist(len: usize) -> NaMask<'a> {
+        NaMask {
+            bits: Cow::Owned(BitVec::from_elem(len, true)),
+        }
+    }
+
+    /// Create a new `NaMask` of length `len`, where `f(idx)` returns whether the value at index
+    /// `idx` exists.
+    pub fn from_fn<F>(len: usize, f: F) -> NaMask<'a>
+    where
+        F: FnMut(usize) -> bool,
+    {
+        NaMask {
+            bits: Cow::Owned(BitVec::from_fn(len, f)),
+        }
+    }
+
+    /// Returns the number of values covered by this mask.
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Returns `true` if this mask covers no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns whether the value at index `idx` exists, or `None` if `idx` is out of bounds.
+    pub fn exists(&self, idx: usize) -> Option<bool> {
+        self.bits.get(idx)
+    }
+
+    /// Returns the number of set bits in this mask (i.e. the number of existing values).
+    pub fn count_ones(&self) -> usize {
+        // `BitVec` keeps bits beyond its length in the last block unset
+        self.bits
+            .blocks()
+            .map(|block| block.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns the number of unset bits in this mask (i.e. the number of missing values).
+    pub fn count_zeros(&self) -> usize {
+        self.len() - self.count_ones()
+    }
+
+    /// Returns a new mask denoting values which exist in both this mask and `other`.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::LengthMismatch` if the masks are of different lengths.
+    pub fn and(&self, other: &NaMask) -> Result<NaMask<'static>> {
+        self.combine(other, BitVec::intersect)
+    }
+
+    /// Returns a new mask denoting values which exist in either this mask or `other`.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::LengthMismatch` if the masks are of different lengths.
+    pub fn or(&self, other: &NaMask) -> Result<NaMask<'static>> {
+        self.combine(other, BitVec::union)
+    }
+
+    /// Returns a new mask with every bit of this mask inverted (i.e. denoting missing values).
+    pub fn not(&self) -> NaMask<'static> {
+        let mut bits = self.bits.clone().into_owned();
+        bits.negate();
+        NaMask {
+            bits: Cow::Owned(bits),
+        }
+    }
+
+    fn combine<F>(&self, other: &NaMask, f: F) -> Result<NaMask<'static>>
+    where
+        F: FnOnce(&mut BitVec, &BitVec) -> bool,
+    {
+        if self.len() != other.len() {
+            return Err(AgnesError::LengthMismatch {
+                expected: self.len(),
+                actual: other.len(),
+            });
+        }
+        let mut bits = self.bits.clone().into_owned();
+        f(&mut bits, &other.bits);
+        Ok(NaMask {
+            bits: Cow::Owned(bits),
+        })
+    }
+
+    /// Returns the indices of the set bits in this mask (i.e. the indices of existing values).
+    pub fn indices(&self) -> Vec<usize> {
+        self.bits
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, exists)| if exists { Some(idx) } else { None })
+            .collect()
+    }
+
+    /// Returns the underlying bit mask.
+    pub fn as_bitvec(&self) -> &BitVec {
+        &self.bits
+    }
+}
+
+/// A block of consecutive values from a field, along with information about which values are
+/// missing. Generated by the [get_chunk](trait.DataIndex.html#method.get_chunk) and
+/// [iter_chunks](trait.DataIndex.html#method.iter_chunks) methods of `DataIndex`.
+#[derive(Debug, Clone)]
+pub struct DataChunk<'a, T>
+where
+    T: 'a,
+{
+    offset: usize,
+    kind: ChunkKind<'a, T>,
+}
+
+#[derive(Debug, Clone)]
+enum ChunkKind<'a, T>
+where
+    T: 'a,
+{
+    Slice {
+        data: &'a [T],
+        mask: Option<&'a BitVec>,
+    },
+    Values(Vec<Value<&'a T>>),
+}
+
+impl<'a, T> DataChunk<'a, T>
+where
+    T: 'a,
+{
+    /// Create a new `DataChunk` starting at field index `offset` which provides direct access to
+    /// the contiguous slice `data`. If provided, `mask` denotes which values exist, and is indexed
+    /// by field index (the first value in `data` corresponds to bit `offset` of `mask`).
+    pub fn from_slice(offset: usize, data: &'a [T], mask: Option<&'a BitVec>) -> DataChunk<'a, T> {
+        DataChunk {
+            offset,
+            kind: ChunkKind::Slice { data, mask },
+        }
+    }
+
+    /// Create a new `DataChunk` starting at field index `offset` from a `Vec` of values gathered
+    /// from a field.
+    pub fn from_values(offset: usize, values: Vec<Value<&'a T>>) -> DataChunk<'a, T> {
+        DataChunk {
+            offset,
+            kind: ChunkKind::Values(values),
+        }
+    }
+
+    /// Returns the index within the originating field of the first value in this chunk.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the number of values in this chunk.
+    pub fn len(&self) -> usize {
+        match self.kind {
+            ChunkKind::Slice { data, .. } => data.len(),
+            ChunkKind::Values(ref values) => values.len(),
+        }
+    }
+
+    /// Returns `true` if this chunk contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the value at index `idx` within this chunk (*not* within the originating field), or
+    /// `None` if `idx` is out of bounds.
+    pub fn get(&self, idx: usize) -> Option<Value<&'a T>> {
+        match self.kind {
+            ChunkKind::Slice { data, mask } => data.get(idx).map(|datum| {
+                let exists = match mask {
+                    Some(mask) => mask[self.offset + idx],
+                    None => true,
+                };
+                if exists {
+                    Value::Exists(datum)
+                } else {
+                    Value::Na
+                }
+            }),
+            ChunkKind::Values(ref values) => values.get(idx).cloned(),
+        }
+    }
+
+    /// Returns `true` if any values in this chunk are missing.
+    pub fn has_na(&self) -> bool {
+        match self.kind {
+            ChunkKind::Slice { data, mask } => match mask {
+                Some(mask) => (self.offset..self.offset + data.len()).any(|idx| !mask[idx]),
+                None => false,
+            },
+            ChunkKind::Values(ref values) => values.iter().any(|value| value.is_na()),
+        }
+    }
+
+    /// Returns the missing-value mask of this chunk, indexed within this chunk (*not* within the
+    /// originating field): bit `idx` is set if the value at index `idx` of this chunk exists.
+    pub fn na_mask(&self) -> NaMask<'static> {
+        match self.kind {
+            ChunkKind::Slice { data, mask } => match mask {
+                Some(mask) => NaMask::from_fn(data.len(), |idx| mask[self.offset + idx]),
+                None => NaMask::all_exist(data.len()),
+            },
+            ChunkKind::Values(ref values) => {
+                NaMask::from_fn(values.len(), |idx| values[idx].exists())
+            }
+        }
+    }
+
+    /// Returns the underlying contiguous slice of data for this chunk, if the originating field
+    /// provides direct access to its storage. Entries in this slice which correspond to missing
+    /// values contain unspecified placeholder data; use [na_mask](#method.na_mask),
+    /// [has_na](#method.has_na) or [get](#method.get) to check for missing values.
+    pub fn as_slice(&self) -> Option<&'a [T]> {
+        match self.kind {
+            ChunkKind::Slice { data, .. } => Some(data),
+            ChunkKind::Values(_) => None,
+        }
+    }
+
+    /// Returns an iterator over the values in this chunk.
+    pub fn iter<'b>(&'b self) -> DataChunkIterator<'a, 'b, T> {
+        DataChunkIterator {
+            chunk: self,
+            cur_idx: 0,
+            end_idx: self.len(),
+        }
+    }
+
+    /// Splits this chunk into two chunks at index `idx` within this chunk; the first chunk
+    /// contains the values before `idx` and the second contains the remaining values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is greater than the length of this chunk.
+    pub fn split_at(self, idx: usize) -> (DataChunk<'a, T>, DataChunk<'a, T>) {
+        let offset = self.offset;
+        match self.kind {
+            ChunkKind::Slice { data, mask } => {
+                let (left, right) = data.split_at(idx);
+                (
+                    DataChunk::from_slice(offset, left, mask),
+                    DataChunk::from_slice(offset + idx, right, mask),
+                )
+            }
+            ChunkKind::Values(mut values) => {
+                let right = values.split_off(idx);
+                (
+                    DataChunk::from_values(offset, values),
+                    DataChunk::from_values(offset + idx, right),
+                )
+            }
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for DataChunk<'a, T>
+where
+    T: 'a,
+{
+    type Item = Value<&'a T>;
+    type IntoIter = DataChunkIntoIter<'a, T>;
+
+    fn into_iter(self) -> DataChunkIntoIter<'a, T> {
+        let end_idx = self.len();
+        DataChunkIntoIter {
+            chunk: self,
+            cur_idx: 0,
+            end_idx,
+        }
+    }
+}
+
+/// Iterator over the values in a [DataChunk](struct.DataChunk.html).
+pub struct DataChunkIterator<'a, 'b, T>
+where
+    T: 'a,
+    'a: 'b,
+{
+    chunk: &'b DataChunk<'a, T>,
+    cur_idx: usize,
+    end_idx: usize,
+}
+
+impl<'a, 'b, T> Iterator for DataChunkIterator<'a, 'b, T>
+where
+    T: 'a,
+{
+    type Item = Value<&'a T>;
+
+    fn next(&mut self) -> Option<Value<&'a T>> {
+        if self.cur_idx < self.end_idx {
+            let out = self.chunk.get(self.cur_idx);
+            self.cur_idx += 1;
+            out
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end_idx - self.cur_idx;
+        (remaining, Some(remaining))
+    }
+}
+impl<'a, 'b, T> DoubleEndedIterator for DataChunkIterator<'a, 'b, T>
+where
+    T: 'a,
+{
+    fn next_back(&mut self) -> Option<Value<&'a T>> {
+        if self.cur_idx < self.end_idx {
+            self.end_idx -= 1;
+            self.chunk.get(self.end_idx)
+        } else {
+            None
+        }
+    }
+}
+impl<'a, 'b, T> ExactSizeIterator for DataChunkIterator<'a, 'b, T> where T: 'a {}
+
+/// Iterator over the values in a [DataChunk](struct.DataChunk.html), which takes ownership of the
+/// chunk.
+#[derive(Debug)]
+pub struct DataChunkIntoIter<'a, T>
+where
+    T: 'a,
+{
+    chunk: DataChunk<'a, T>,
+    cur_idx: usize,
+    end_idx: usize,
+}
+
+impl<'a, T> Iterator for DataChunkIntoIter<'a, T>
+where
+    T: 'a,
+{
+    type Item = Value<&'a T>;
+
+    fn next(&mut self) -> Option<Value<&'a T>> {
+        if self.cur_idx < self.end_idx {
+            let out = self.chunk.get(self.cur_idx);
+            self.cur_idx += 1;
+            out
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end_idx - self.cur_idx;
+        (remaining, Some(remaining))
+    }
+}
+impl<'a, T> DoubleEndedIterator for DataChunkIntoIter<'a, T>
+where
+    T: 'a,
+{
+    fn next_back(&mut self) -> Option<Value<&'a T>> {
+        if self.cur_idx < self.end_idx {
+            self.end_idx -= 1;
+            self.chunk.get(self.end_idx)
+        } else {
+            None
+        }
+    }
+}
+impl<'a, T> ExactSizeIterator for DataChunkIntoIter<'a, T> where T: 'a {}
+
+/// Iterator over the data in a data structure that implements DataIndex, yielding
+/// [DataChunk](struct.DataChunk.html)s of consecutive values.
+pub struct ChunkIterator<'a, T>
+where
+    T: 'a,
+{
+    data: &'a dyn DataIndex<DType = T>,
+    chunk_size: usize,
+    cur_idx: usize,
+}
+
+impl<'a, T> ChunkIterator<'a, T>
+where
+    T: 'a,
+{
+    /// Create a new `ChunkIterator` from a type that implements `DataIndex`, yielding chunks of
+    /// `chunk_size` values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    pub fn new(data: &'a dyn DataIndex<DType = T>, chunk_size: usize) -> ChunkIterator<'a, T> {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        ChunkIterator {
+            data,
+            chunk_size,
+            cur_idx: 0,
+        }
+    }
+}
+
+impl<'a, T> Iterator for ChunkIterator<'a, T>
+where
+    T: 'a,
+{
+    type Item = DataChunk<'a, T>;
+
+    fn next(&mut self) -> Option<DataChunk<'a, T>> {
+        if self.cur_idx < self.data.len() {
+            let out = Some(self.data.get_chunk(self.cur_idx, self.chunk_size).unwrap());
+            self.cur_idx += self.chunk_size;
+            out
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.data.len().saturating_sub(self.cur_idx);
+        let nchunks = remaining.div_ceil(self.chunk_size);
+        (nchunks, Some(nchunks))
+    }
+}
+impl<'a, T> ExactSizeIterator for ChunkIterator<'a, T> where T: 'a {}
+
 /// Draining iterator over the data in a data structure that implements DataIndex.
 pub struct DrainIterator<'a, T>
 where
@@ -175,6 +799,7 @@ where
 {
     data: &'a mut dyn DataIndexMut<DType = T>,
     cur_idx: usize,
+    end_idx: usize,
     phantom: PhantomData<T>,
 }
 
@@ -184,9 +809,11 @@ where
 {
     /// Create a new `DrainIterator` from a type that implements `DataIndex`.
     pub fn new(data: &'a mut dyn DataIndexMut<DType = T>) -> DrainIterator<'a, T> {
+        let end_idx = data.len();
         DrainIterator {
             data,
             cur_idx: 0,
+            end_idx,
             phantom: PhantomData,
         }
     }
@@ -199,7 +826,7 @@ where
     type Item = Value<T>;
 
     fn next(&mut self) -> Option<Value<T>> {
-        if self.cur_idx < self.data.len() {
+        if self.cur_idx < self.end_idx {
             let out = Some(self.data.take_datum(self.cur_idx).unwrap());
             self.cur_idx += 1;
             out
@@ -207,6 +834,174 @@ where
             None
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end_idx - self.cur_idx;
+        (remaining, Some(remaining))
+    }
+}
+impl<'a, T> DoubleEndedIterator for DrainIterator<'a, T>
+where
+    T: 'a + Default,
+{
+    fn next_back(&mut self) -> Option<Value<T>> {
+        if self.cur_idx < self.end_idx {
+            self.end_idx -= 1;
+            Some(self.data.take_datum(self.end_idx).unwrap())
+        } else {
+            None
+        }
+    }
+}
+impl<'a, T> ExactSizeIterator for DrainIterator<'a, T> where T: 'a + Default {}
+
+/// The raw storage of a field, generated by the [raw_column](trait.RawColumnar.html#method.raw_column)
+/// method of `RawColumnar`: the buffer of values, the missing-value mask of the buffer, and the
+/// permutation (if any) through which the buffer is viewed.
+///
+/// The value of the field at (logical) index `idx` is the buffer value at index
+/// `permutation[idx]` (or at `idx` if there is no permutation), which is missing if the
+/// corresponding bit of the mask is unset. Missing values in the buffer hold an unspecified
+/// placeholder value (usually the type's default value). Note that the mask and permutation refer
+/// to positions in the buffer, not logical indices.
+#[derive(Debug, Clone)]
+pub struct RawColumn<'a, T>
+where
+    T: 'a,
+{
+    values: &'a [T],
+    na_mask: Option<NaMask<'a>>,
+    permutation: Option<Cow<'a, [usize]>>,
+}
+
+/// The parts of a [RawColumn](struct.RawColumn.html), as returned by its
+/// [into_parts](struct.RawColumn.html#method.into_parts) method: the buffer of values, the
+/// missing-value mask, and the permutation.
+pub type RawParts<'a, T> = (&'a [T], Option<NaMask<'a>>, Option<Cow<'a, [usize]>>);
+
+impl<'a, T> RawColumn<'a, T>
+where
+    T: 'a,
+{
+    /// Create a new `RawColumn` from a buffer of values, an optional mask of the values in the
+    /// buffer which exist (`None` if all values exist), and an optional permutation of the buffer.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::LengthMismatch` if the mask is not the same length as the buffer,
+    /// or with `AgnesError::IndexError` if the permutation refers to a position outside the buffer.
+    pub fn new(
+        values: &'a [T],
+        na_mask: Option<NaMask<'a>>,
+        permutation: Option<&'a [usize]>,
+    ) -> Result<RawColumn<'a, T>> {
+        if let Some(ref mask) = na_mask {
+            if mask.len() != values.len() {
+                return Err(AgnesError::LengthMismatch {
+                    expected: values.len(),
+                    actual: mask.len(),
+                });
+            }
+        }
+        if let Some(&index) =
+            permutation.and_then(|perm| perm.iter().find(|&&idx| idx >= values.len()))
+        {
+            return Err(AgnesError::IndexError {
+                index,
+                len: values.len(),
+            });
+        }
+        Ok(RawColumn::from_storage(
+            values,
+            na_mask,
+            permutation.map(Cow::Borrowed),
+        ))
+    }
+
+    // Creates a new `RawColumn` without checking that the mask and permutation are consistent
+    // with the buffer (for storage known to be consistent).
+    pub(crate) fn from_storage(
+        values: &'a [T],
+        na_mask: Option<NaMask<'a>>,
+        permutation: Option<Cow<'a, [usize]>>,
+    ) -> RawColumn<'a, T> {
+        RawColumn {
+            values,
+            na_mask,
+            permutation,
+        }
+    }
+
+    /// Returns the buffer of values, in storage order.
+    pub fn values(&self) -> &'a [T] {
+        self.values
+    }
+
+    /// Returns the missing-value mask of the buffer of values (with set bits denoting existing
+    /// values), or `None` if every value in the buffer exists. The bits are packed into `u32`
+    /// blocks (least-significant bit first), available with `as_bitvec().storage()`.
+    pub fn na_mask(&self) -> Option<&NaMask<'a>> {
+        self.na_mask.as_ref()
+    }
+
+    /// Returns the permutation of the buffer which produces the field's values in order, or
+    /// `None` if the field's values are the buffer's values in storage order.
+    pub fn permutation(&self) -> Option<&[usize]> {
+        self.permutation.as_ref().map(|perm| &perm[..])
+    }
+
+    /// Returns the (logical) number of values in the field.
+    pub fn len(&self) -> usize {
+        self.permutation
+            .as_ref()
+            .map_or(self.values.len(), |perm| perm.len())
+    }
+
+    /// Returns `true` if the field has no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the buffer value at `position` (an index into the buffer, ignoring the
+    /// permutation), or `Value::Na` if the value is missing.
+    ///
+    /// # Panics
+    /// Panics if `position` is out of bounds of the buffer.
+    pub fn buffer_value(&self, position: usize) -> Value<&'a T> {
+        let exists = match self.na_mask {
+            Some(ref mask) => mask.exists(position) == Some(true),
+            None => true,
+        };
+        if exists {
+            Value::Exists(&self.values[position])
+        } else {
+            Value::Na
+        }
+    }
+
+    /// Returns an iterator over the field's values in (logical) order, applying the permutation
+    /// and missing-value mask.
+    pub fn iter(&self) -> impl Iterator<Item = Value<&'a T>> + '_ {
+        (0..self.len())
+            .map(move |idx| self.buffer_value(self.permutation().map_or(idx, |perm| perm[idx])))
+    }
+
+    /// Consumes this `RawColumn`, returning the buffer of values, the missing-value mask, and the
+    /// permutation (borrowed from the field, or owned if the field's permutations had to be
+    /// composed).
+    pub fn into_parts(self) -> RawParts<'a, T> {
+        (self.values, self.na_mask, self.permutation)
+    }
+}
+
+/// Trait for fields which can provide direct access to their underlying storage as a
+/// [RawColumn](struct.RawColumn.html).
+pub trait RawColumnar: DataIndex {
+    /// Returns the raw storage of this field.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::DimensionMismatch` if this field is not backed by a single buffer
+    /// of values (e.g. a field produced by [melt](../view/struct.DataView.html#method.melt)).
+    fn raw_column(&self) -> Result<RawColumn<'_, Self::DType>>;
 }
 
 /// Trait to provide the number of rows of this data structure.
@@ -227,7 +1022,7 @@ where
 mod tests {
     use super::*;
 
-    use field::FieldData;
+    use field::{ArenaStr, FieldData, StrFieldData};
 
     #[test]
     fn convert() {
@@ -253,4 +1048,242 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn chunks() {
+        let field_data = FieldData::from_field_vec(vec![
+            Value::Exists(2u64),
+            Value::Exists(5),
+            Value::Na,
+            Value::Exists(1),
+            Value::Exists(8),
+        ]);
+        let chunks = field_data.iter_chunks(2).collect::<Vec<_>>();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|chunk| chunk.offset())
+                .collect::<Vec<_>>(),
+            vec![0, 2, 4]
+        );
+        assert_eq!(
+            chunks.iter().map(|chunk| chunk.len()).collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|chunk| chunk.has_na())
+                .collect::<Vec<_>>(),
+            vec![false, true, false]
+        );
+        // chunks of a `FieldData` provide direct access to the underlying data
+        assert_eq!(chunks[0].as_slice(), Some(&[2u64, 5][..]));
+        assert_eq!(chunks[1].na_mask().indices(), vec![1]);
+        assert_eq!(chunks[2].na_mask().count_ones(), 1);
+        assert_eq!(
+            chunks[1].iter().collect::<Vec<_>>(),
+            vec![Value::Na, Value::Exists(&1)]
+        );
+        assert_eq!(
+            chunks
+                .iter()
+                .flat_map(|chunk| chunk.iter())
+                .collect::<Vec<_>>(),
+            field_data.iter().collect::<Vec<_>>()
+        );
+
+        // permuted fields gather their values
+        let permuted = field_data.clone().permute(&[4, 2, 0]);
+        let chunks = permuted.iter_chunks(2).collect::<Vec<_>>();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].as_slice(), None);
+        assert_eq!(chunks[0].na_mask().indices(), vec![0]);
+        assert_eq!(
+            chunks[0].iter().collect::<Vec<_>>(),
+            vec![Value::Exists(&8), Value::Na]
+        );
+        assert_eq!(
+            chunks[1].iter().collect::<Vec<_>>(),
+            vec![Value::Exists(&2)]
+        );
+
+        // chunks past the end are an error, chunks at the end are empty
+        assert!(field_data.get_chunk(6, 2).is_err());
+        assert!(field_data.get_chunk(5, 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn na_mask() {
+        let left = FieldData::from_field_vec(vec![
+            Value::Exists(2u64),
+            Value::Na,
+            Value::Exists(1),
+            Value::Na,
+        ]);
+        let right = FieldData::from_field_vec(vec![
+            Value::Exists(2u64),
+            Value::Exists(3),
+            Value::Na,
+            Value::Na,
+        ]);
+        let (left_mask, right_mask) = (left.na_mask(), right.na_mask());
+        assert_eq!(left_mask.len(), 4);
+        assert_eq!((left_mask.count_ones(), left_mask.count_zeros()), (2, 2));
+        assert_eq!(left_mask.exists(2), Some(true));
+        assert_eq!(left_mask.exists(4), None);
+        assert_eq!(left_mask.indices(), vec![0, 2]);
+        assert_eq!(left_mask.not().indices(), vec![1, 3]);
+        assert_eq!(left_mask.and(&right_mask).unwrap().indices(), vec![0]);
+        assert_eq!(left_mask.or(&right_mask).unwrap().indices(), vec![0, 1, 2]);
+        assert!(left_mask.and(&NaMask::all_exist(3)).is_err());
+
+        // fields without missing values, and fields without a stored mask
+        let full = FieldData::<u64>::from_vec(vec![1u64, 2, 3, 4]);
+        assert_eq!(full.na_mask().count_ones(), 4);
+        let permuted = left.permute(&[2, 0, 1]);
+        assert_eq!(permuted.na_mask().indices(), vec![0, 1]);
+    }
+
+    #[test]
+    fn str_access() {
+        let field = FieldData::from_field_vec(vec![
+            Value::Exists("Sally".to_string()),
+            Value::Na,
+            Value::Exists("Bob".to_string()),
+        ]);
+        assert_eq!(field.get_str(0).unwrap(), Value::Exists("Sally"));
+        assert_eq!(field.get_str(1).unwrap(), Value::Na);
+        assert!(field.get_str(3).is_err());
+        assert_eq!(field.to_str_vec(), vec!["Sally", "Bob"]);
+        assert_eq!(
+            field.to_str_value_vec(),
+            vec![Value::Exists("Sally"), Value::Na, Value::Exists("Bob")]
+        );
+        assert_eq!(
+            field
+                .iter_str()
+                .map(|value| value.map_or(0, str::len))
+                .sum::<usize>(),
+            8
+        );
+
+        // arena-backed text fields
+        let field = FieldData::<ArenaStr>::from(
+            [Value::Exists("Sally"), Value::Na, Value::Exists("Bob")]
+                .iter()
+                .cloned()
+                .collect::<StrFieldData>(),
+        );
+        assert_eq!(field.get_str(2).unwrap(), Value::Exists("Bob"));
+        assert_eq!(field.to_str_vec(), vec!["Sally", "Bob"]);
+    }
+
+    #[test]
+    fn iterators() {
+        let field = FieldData::from_field_vec(vec![
+            Value::Exists(2u64),
+            Value::Na,
+            Value::Exists(5),
+            Value::Exists(1),
+        ]);
+        let mut iter = field.iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(Value::Exists(&2)));
+        assert_eq!(iter.next_back(), Some(Value::Exists(&1)));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next_back(), Some(Value::Exists(&5)));
+        assert_eq!(iter.next(), Some(Value::Na));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        assert_eq!(
+            field.iter().rev().collect::<Vec<_>>(),
+            vec![
+                Value::Exists(&1),
+                Value::Exists(&5),
+                Value::Na,
+                Value::Exists(&2)
+            ]
+        );
+        assert_eq!((&field).into_iter().map_existing(|&x| x * 2).rev().len(), 4);
+        let mut sum = 0;
+        for value in &field {
+            sum += value.map_or(0, |&x| x);
+        }
+        assert_eq!(sum, 8);
+
+        let chunk = field.get_chunk(1, 3).unwrap();
+        assert_eq!(chunk.iter().len(), 3);
+        assert_eq!(chunk.iter().next_back(), Some(Value::Exists(&1)));
+        assert_eq!(field.iter_chunks(3).len(), 2);
+
+        let mut field = field;
+        let mut drain = field.drain();
+        assert_eq!(drain.len(), 4);
+        assert_eq!(drain.next_back(), Some(Value::Exists(1)));
+        assert_eq!(drain.next(), Some(Value::Exists(2)));
+        assert_eq!(drain.len(), 2);
+    }
+
+    #[test]
+    fn raw_column() {
+        use store::DataRef;
+
+        let field = FieldData::from_field_vec(vec![
+            Value::Exists(2u64),
+            Value::Na,
+            Value::Exists(5),
+            Value::Exists(1),
+        ]);
+        let raw = field.raw_column().unwrap();
+        assert_eq!(raw.values().len(), 4);
+        assert_eq!(raw.values()[2], 5);
+        assert_eq!(raw.na_mask().unwrap().indices(), vec![0, 2, 3]);
+        assert_eq!(raw.permutation(), None);
+        assert_eq!(raw.buffer_value(1), Value::Na);
+        assert_eq!(
+            raw.iter().collect::<Vec<_>>(),
+            field.iter().collect::<Vec<_>>()
+        );
+
+        // no mask is provided for fields without missing values
+        let full: FieldData<u64> = vec![3u64, 4].into();
+        assert!(full.raw_column().unwrap().na_mask().is_none());
+
+        // framed fields expose their permutation over the shared buffer
+        let framed = DataRef::from(field.clone()).permute(&[3, 1, 0]);
+        let raw = framed.raw_column().unwrap();
+        assert_eq!(raw.permutation(), Some(&[3usize, 1, 0][..]));
+        assert_eq!(raw.len(), 3);
+        assert_eq!(
+            raw.iter().collect::<Vec<_>>(),
+            vec![Value::Exists(&1), Value::Na, Value::Exists(&2)]
+        );
+        let (values, na_mask, permutation) = raw.into_parts();
+        assert_eq!(values, field.raw_column().unwrap().values());
+        assert!(na_mask.is_some());
+        assert_eq!(permutation.map(|perm| perm.len()), Some(3));
+
+        // nested frames compose their permutations into a single lookup
+        let nested = framed.permute(&[2, 0]);
+        let raw = nested.raw_column().unwrap();
+        assert_eq!(raw.permutation(), Some(&[0usize, 3][..]));
+        assert_eq!(
+            raw.iter().collect::<Vec<_>>(),
+            vec![Value::Exists(&2), Value::Exists(&1)]
+        );
+        assert_eq!(
+            raw.iter().collect::<Vec<_>>(),
+            nested.iter().collect::<Vec<_>>()
+        );
+
+        // user-constructed columns are validated
+        let values = [1u64, 2, 3];
+        assert!(RawColumn::new(&values[..], None, Some(&[2, 0][..])).is_ok());
+        assert!(RawColumn::new(&values[..], None, Some(&[3][..])).is_err());
+        let mask = BitVec::from_elem(2, true);
+        assert!(RawColumn::new(&values[..], Some(NaMask::from_bitvec(&mask)), None).is_err());
+    }
 }
@@ -0,0 +1,1822 @@
+/*!
+Aggregation of several fields over the same grouping of records in a single pass.
+
+[DataView::aggregate](../view/struct.DataView.html#method.aggregate) (and its shorthands such as
+[sum_by](../view/struct.DataView.html#method.sum_by)) produce a single aggregated field. Computing
+several aggregates at once with
+[DataView::aggregate_many](../view/struct.DataView.html#method.aggregate_many) groups the records
+only once, accumulates all the aggregates in the same scan over the records, and produces a single
+`DataView` with the key fields followed by one field per aggregation.
+
+Each aggregation is described by an [Agg](struct.Agg.html) object (constructed with
+[agg](fn.agg.html) or [agg_with](fn.agg_with.html)), and aggregations are combined into a
+[cons-list](../cons/index.html).
+
+Aggregations produce groups in order of first appearance in the aggregated records. The
+[GroupOrder](enum.GroupOrder.html) options (used with
+[DataView::aggregate_ordered](../view/struct.DataView.html#method.aggregate_ordered) or
+[DataView::order_groups](../view/struct.DataView.html#method.order_groups)) instead sort the
+groups by their key values or by an aggregated value.
+
+[DataView::aggregate_subtotals](../view/struct.DataView.html#method.aggregate_subtotals) computes
+aggregations over hierarchical groupings of records, producing subtotal records for coarser
+groupings of the key fields (see [Subtotals](enum.Subtotals.html) for the rollup and cube options).
+
+[DataView::transform_by](../view/struct.DataView.html#method.transform_by) computes a statistic per
+group and broadcasts it back to every record of the group, keeping the original records, and
+[DataView::filter_groups](../view/struct.DataView.html#method.filter_groups) keeps or drops entire
+groups of records based on a predicate over each group.
+[DataView::first_record_by](../view/struct.DataView.html#method.first_record_by),
+[DataView::last_record_by](../view/struct.DataView.html#method.last_record_by), and
+[DataView::nth_record_by](../view/struct.DataView.html#method.nth_record_by) select a single record
+from each group.
+
+For categorical fields, [DataView::top_k](../view/struct.DataView.html#method.top_k) finds the most
+frequent values (with their counts) within each group.
+*/
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use access::{DataIndex, NRows};
+use cons::{Cons, Nil};
+use erased::{self, Groups, KeyIndex, KeyList, OrdKeyList};
+use error;
+use field::FieldData;
+use frame::{Framed, IntoFrame};
+use join::AddFrame;
+use label::{False, LabelSubset, LookupNatByLabel, Member, Reorder, SubsetClone, True, Typed};
+use num_traits::AsPrimitive;
+use observer::{NoObserver, Observer, Reporter, Stage};
+use permute::UpdatePermutation;
+use select::{FieldSelect, SelectFieldByLabel};
+use stats;
+use store::{
+    AssocStorage, DataStore, IntoStore, IntoView, PushBackField, PushFrontClonedFromValueIter,
+    PushFrontField,
+};
+use typenum::Unsigned;
+use value::Value;
+use view::{
+    Aggregate, DataView, FieldList, FrameIndexList, FrameLookupCons, Invariants,
+    SelectFieldFromLabels, Subview, VFieldOf, VFieldTypeOf,
+};
+
+/// A single aggregation of the values of the field labeled `ValueLabel` into a new field labeled
+/// `AggLabel`, for use with
+/// [DataView::aggregate_many](../view/struct.DataView.html#method.aggregate_many). The values of
+/// each group are accumulated into a clone of an initial accumulator of type `AccType` with
+/// `AggFunc`, and the accumulators of all groups are converted into the aggregated field (with
+/// values of type `AggType`) with `FinishFunc`.
+#[derive(Debug, Clone)]
+pub struct Agg<ValueLabel, AggLabel, AccType, AggType, AggFunc, FinishFunc> {
+    init: AccType,
+    f: AggFunc,
+    finish: FinishFunc,
+    _marker: PhantomData<(ValueLabel, AggLabel, AggType)>,
+}
+
+/// Type of the function used by [agg](fn.agg.html) to convert accumulators into an aggregated
+/// field.
+pub type IdentityFinish<AccType> = fn(Vec<AccType>) -> FieldData<AccType>;
+
+/// Creates an aggregation of the values of the field labeled `ValueLabel` into a new field labeled
+/// `AggLabel`, where each group's aggregate is computed by updating a clone of `init` with `f`
+/// (as with [DataView::aggregate](../view/struct.DataView.html#method.aggregate)).
+pub fn agg<ValueLabel, AggLabel, AccType, AggFunc>(
+    init: AccType,
+    f: AggFunc,
+) -> Agg<ValueLabel, AggLabel, AccType, AccType, AggFunc, IdentityFinish<AccType>>
+where
+    ValueLabel: Typed,
+    AggFunc: Fn(&mut AccType, Value<&ValueLabel::DType>),
+{
+    agg_with(init, f, FieldData::from as IdentityFinish<AccType>)
+}
+
+/// Creates an aggregation of the values of the field labeled `ValueLabel` into a new field labeled
+/// `AggLabel`, where each group's values are accumulated by updating a clone of `init` with `f`,
+/// and the accumulators of all groups (in order of first appearance of each group) are converted
+/// into the aggregated field with `finish` (as with
+/// [AggregateWith](../view/trait.AggregateWith.html)).
+pub fn agg_with<ValueLabel, AggLabel, AccType, AggType, AggFunc, FinishFunc>(
+    init: AccType,
+    f: AggFunc,
+    finish: FinishFunc,
+) -> Agg<ValueLabel, AggLabel, AccType, AggType, AggFunc, FinishFunc>
+where
+    ValueLabel: Typed,
+    AggFunc: Fn(&mut AccType, Value<&ValueLabel::DType>),
+    FinishFunc: FnOnce(Vec<AccType>) -> FieldData<AggType>,
+{
+    Agg {
+        init,
+        f,
+        finish,
+        _marker: PhantomData,
+    }
+}
+
+/// Trait implemented by cons-lists of [Agg](struct.Agg.html) aggregations over the records of
+/// `View`.
+pub trait AggList<View> {
+    /// Per-aggregation state during the aggregation: the selected value field and the
+    /// accumulators for each group.
+    type State;
+    /// [FieldCons](../fieldlist/type.FieldCons.html) cons-list of the aggregated fields.
+    type OutputFields: AssocStorage;
+
+    /// Selects the value fields from `view` and initializes the accumulators of `ngroups` groups.
+    fn init_state(&self, view: &View, ngroups: usize) -> Self::State;
+    /// Accumulates the values of the record at index `idx` into the accumulators for `group`.
+    fn accumulate(&self, state: &mut Self::State, group: usize, idx: usize);
+    /// Converts the accumulators of each aggregation into the aggregated fields.
+    fn finish(self, state: Self::State) -> DataStore<Self::OutputFields>;
+}
+
+impl<View> AggList<View> for Nil {
+    type State = Nil;
+    type OutputFields = Nil;
+
+    fn init_state(&self, _view: &View, _ngroups: usize) -> Nil {
+        Nil
+    }
+    fn accumulate(&self, _state: &mut Nil, _group: usize, _idx: usize) {}
+    fn finish(self, _state: Nil) -> DataStore<Nil> {
+        DataStore::<Nil>::empty()
+    }
+}
+
+impl<View, ValueLabel, AggLabel, AccType, AggType, AggFunc, FinishFunc, Tail> AggList<View>
+    for Cons<Agg<ValueLabel, AggLabel, AccType, AggType, AggFunc, FinishFunc>, Tail>
+where
+    View: SelectFieldByLabel<ValueLabel>,
+    AccType: Clone,
+    AggFunc: Fn(&mut AccType, Value<&<View as SelectFieldByLabel<ValueLabel>>::DType>),
+    FinishFunc: FnOnce(Vec<AccType>) -> FieldData<AggType>,
+    Tail: AggList<View>,
+    DataStore<Tail::OutputFields>: PushFrontField<AggLabel, AggType>,
+{
+    type State = Cons<(VFieldOf<View, ValueLabel>, Vec<AccType>), Tail::State>;
+    type OutputFields =
+        <DataStore<Tail::OutputFields> as PushFrontField<AggLabel, AggType>>::OutputFields;
+
+    fn init_state(&self, view: &View, ngroups: usize) -> Self::State {
+        Cons {
+            head: (
+                SelectFieldByLabel::<ValueLabel>::select_field(view),
+                vec![self.head.init.clone(); ngroups],
+            ),
+            tail: self.tail.init_state(view, ngroups),
+        }
+    }
+
+    fn accumulate(&self, state: &mut Self::State, group: usize, idx: usize) {
+        let (ref values, ref mut aggregates) = state.head;
+        (self.head.f)(&mut aggregates[group], values.get_datum(idx).unwrap());
+        self.tail.accumulate(&mut state.tail, group, idx);
+    }
+
+    fn finish(self, state: Self::State) -> DataStore<Self::OutputFields> {
+        let (_, aggregates) = state.head;
+        self.tail
+            .finish(state.tail)
+            .push_front_field((self.head.finish)(aggregates))
+    }
+}
+
+/// Type alias for the output of performing the aggregations `Aggs` over the records of `View`
+/// grouped by the fields labeled `KeyLabels`.
+pub type AggregateManyOutputOf<View, KeyLabels, Aggs> =
+    <View as AggregateMany<KeyLabels, Aggs>>::Output;
+
+/// Trait providing the `aggregate_many` method for computing several aggregations over the same
+/// grouping of records. See the intrinsic method
+/// [aggregate_many](../view/struct.DataView.html#method.aggregate_many) for more details.
+pub trait AggregateMany<KeyLabels, Aggs> {
+    /// Type produced by this aggregate method.
+    type Output;
+
+    /// Perform the aggregations, reporting progress to `observer`. See the intrinsic method
+    /// [aggregate_many_observed](../view/struct.DataView.html#method.aggregate_many_observed)
+    /// for more details.
+    fn aggregate_many_observed<O>(
+        &self,
+        aggs: Aggs,
+        observer: &mut O,
+    ) -> error::Result<Self::Output>
+    where
+        O: Observer;
+
+    /// Perform the aggregations over the precomputed grouping `groups` of the records (such as
+    /// one provided by an [index](../index/index.html)), reporting progress to `observer`.
+    fn aggregate_many_grouped<O>(
+        &self,
+        aggs: Aggs,
+        groups: Groups,
+        observer: &mut O,
+    ) -> error::Result<Self::Output>
+    where
+        O: Observer;
+}
+
+impl<Labels, Frames, KeyLabels, Aggs> AggregateMany<KeyLabels, Aggs> for DataView<Labels, Frames>
+where
+    Self: NRows,
+    Aggs: AggList<Self>,
+    Labels: FieldList<KeyLabels, Frames> + LabelSubset<KeyLabels> + FrameIndexList,
+    <Labels as FieldList<KeyLabels, Frames>>::Output: KeyList,
+    <Labels as LabelSubset<KeyLabels>>::Output: Reorder<KeyLabels>,
+    DataStore<Aggs::OutputFields>: IntoFrame,
+    Frames: NRows + SubsetClone<<Labels as FrameIndexList>::LabelList>,
+    <Frames as SubsetClone<<Labels as FrameIndexList>::LabelList>>::Output: UpdatePermutation,
+    DataView<
+        <<Labels as LabelSubset<KeyLabels>>::Output as Reorder<KeyLabels>>::Output,
+        <Frames as SubsetClone<<Labels as FrameIndexList>::LabelList>>::Output,
+    >: AddFrame<<DataStore<Aggs::OutputFields> as IntoFrame>::Output>,
+{
+    // output is KeyLabels, then one column per aggregation
+    type Output = <DataView<
+        <<Labels as LabelSubset<KeyLabels>>::Output as Reorder<KeyLabels>>::Output,
+        <Frames as SubsetClone<<Labels as FrameIndexList>::LabelList>>::Output,
+    > as AddFrame<<DataStore<Aggs::OutputFields> as IntoFrame>::Output>>::Output;
+
+    fn aggregate_many_observed<O>(
+        &self,
+        aggs: Aggs,
+        observer: &mut O,
+    ) -> error::Result<Self::Output>
+    where
+        O: Observer,
+    {
+        let groups = erased::group_records_observed(
+            &self.field_list::<KeyLabels>().keys(),
+            self.nrows(),
+            &*observer,
+        )?;
+        self.aggregate_many_grouped(aggs, groups, observer)
+    }
+
+    fn aggregate_many_grouped<O>(
+        &self,
+        aggs: Aggs,
+        groups: Groups,
+        observer: &mut O,
+    ) -> error::Result<Self::Output>
+    where
+        O: Observer,
+    {
+        let mut reporter = Reporter::new(observer, Stage::Aggregate, Some(self.nrows()));
+        reporter.check()?;
+        let mut state = aggs.init_state(self, groups.len());
+        for (i, &group) in groups.record_groups.iter().enumerate() {
+            aggs.accumulate(&mut state, group, i);
+            reporter.advance(1)?;
+        }
+        reporter.finish();
+        let agg_frame = aggs.finish(state).into_frame();
+
+        let record_frames = self
+            .frames
+            .subset_clone()
+            .update_permutation(&groups.first_indices);
+
+        Ok(DataView {
+            _labels: PhantomData,
+            frames: record_frames,
+            invariants: Invariants::default(),
+        }
+        .add_frame(agg_frame))
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Computes several aggregations over the same grouping of records, grouping the records by
+    /// the fields labeled `KeyLabels` only once and accumulating all the aggregations in a single
+    /// scan over the records. Each aggregation in the cons-list `aggs` (created with
+    /// [agg](../aggregate/fn.agg.html) or [agg_with](../aggregate/fn.agg_with.html)) behaves as a
+    /// call to [aggregate](#method.aggregate) (or
+    /// [AggregateWith::aggregate_with](trait.AggregateWith.html#tymethod.aggregate_with)). The
+    /// resulting `DataView` contains the `KeyLabels` fields followed by the aggregated fields in
+    /// the order of `aggs`, with one record per group (in order of first appearance).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate agnes;
+    /// use agnes::access::DataIndex;
+    /// use agnes::aggregate::agg;
+    /// use agnes::cons::{cons, Nil};
+    /// use agnes::field::FieldData;
+    /// use agnes::select::FieldSelect;
+    /// use agnes::store::{DataStore, PushBackField};
+    /// use agnes::value::Value;
+    ///
+    /// tablespace![
+    ///     table sales {
+    ///         Region: String,
+    ///         Amount: f64,
+    ///         Total: f64,
+    ///         Largest: f64,
+    ///     }
+    /// ];
+    ///
+    /// fn main() {
+    ///     let dv = DataStore::<Nil>::empty()
+    ///         .push_back_field::<sales::Region, _>(FieldData::<String>::from_vec(vec![
+    ///             "N".to_string(),
+    ///             "S".to_string(),
+    ///             "N".to_string(),
+    ///         ]))
+    ///         .push_back_field::<sales::Amount, _>(FieldData::<f64>::from_vec(vec![3.0, 2.0, 5.0]))
+    ///         .into_view();
+    ///
+    ///     let aggs = dv.aggregate_many::<Labels![sales::Region], _>(cons(
+    ///         agg::<sales::Amount, sales::Total, _, _>(0.0, |sum, val| {
+    ///             *sum += val.unwrap_or(&0.0);
+    ///         }),
+    ///         cons(
+    ///             agg::<sales::Amount, sales::Largest, _, _>(0.0, |max, val| {
+    ///                 if let Value::Exists(&val) = val {
+    ///                     *max = val.max(*max);
+    ///                 }
+    ///             }),
+    ///             Nil,
+    ///         ),
+    ///     ));
+    ///     assert_eq!(aggs.field::<sales::Total>().to_vec(), vec![8.0, 2.0]);
+    ///     assert_eq!(aggs.field::<sales::Largest>().to_vec(), vec![5.0, 2.0]);
+    /// }
+    /// ```
+    pub fn aggregate_many<KeyLabels, Aggs>(
+        &self,
+        aggs: Aggs,
+    ) -> AggregateManyOutputOf<Self, KeyLabels, Aggs>
+    where
+        Self: AggregateMany<KeyLabels, Aggs>,
+    {
+        // aggregations without an observer cannot be cancelled
+        self.aggregate_many_observed::<KeyLabels, Aggs, _>(aggs, &mut NoObserver)
+            .unwrap()
+    }
+
+    /// Computes several aggregations over the same grouping of records, reporting the number of
+    /// records accumulated to `observer` (with stage
+    /// [Stage::Aggregate](../observer/enum.Stage.html)). See
+    /// [aggregate_many](#method.aggregate_many).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation.
+    pub fn aggregate_many_observed<KeyLabels, Aggs, O>(
+        &self,
+        aggs: Aggs,
+        observer: &mut O,
+    ) -> error::Result<AggregateManyOutputOf<Self, KeyLabels, Aggs>>
+    where
+        Self: AggregateMany<KeyLabels, Aggs>,
+        O: Observer,
+    {
+        AggregateMany::<KeyLabels, Aggs>::aggregate_many_observed(self, aggs, observer)
+    }
+}
+
+/// Direction in which to sort groups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    /// Sort from lowest to highest, with missing (NA) values first.
+    Ascending,
+    /// Sort from highest to lowest, with missing (NA) values last.
+    Descending,
+}
+
+/// Order of the groups (records) in the output of an aggregation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GroupOrder {
+    /// Groups are in order of first appearance in the aggregated records (the default).
+    #[default]
+    FirstAppearance,
+    /// Groups are sorted by the values of the key fields (compared in key label order).
+    Keys(SortDirection),
+    /// Groups are sorted by the aggregated value. Values which are not comparable to themselves
+    /// (such as NaN) are ordered immediately after missing (NA) values.
+    Value(SortDirection),
+}
+
+/// Trait providing the `order_groups` method for sorting the output of an aggregation. See the
+/// intrinsic method [order_groups](../view/struct.DataView.html#method.order_groups) for more
+/// details.
+pub trait OrderGroups<KeyLabels, AggLabel> {
+    /// Sorts the groups of this aggregation output in the order `order`.
+    fn order_groups(self, order: GroupOrder) -> Self;
+}
+
+impl<Labels, Frames, KeyLabels, AggLabel> OrderGroups<KeyLabels, AggLabel>
+    for DataView<Labels, Frames>
+where
+    Self: NRows + SelectFieldByLabel<AggLabel>,
+    VFieldTypeOf<Self, AggLabel>: PartialOrd,
+    Labels: FieldList<KeyLabels, Frames>,
+    <Labels as FieldList<KeyLabels, Frames>>::Output: OrdKeyList,
+    Frames: UpdatePermutation,
+{
+    fn order_groups(mut self, order: GroupOrder) -> Self {
+        let nrows = self.nrows();
+        let sorted = match order {
+            GroupOrder::FirstAppearance => return self,
+            GroupOrder::Keys(direction) => {
+                let mut sorted =
+                    erased::sort_records(&self.field_list::<KeyLabels>().ord_keys(), nrows);
+                // keys are unique within an aggregation output, so reversing is a stable sort
+                if direction == SortDirection::Descending {
+                    sorted.reverse();
+                }
+                sorted
+            }
+            GroupOrder::Value(direction) => {
+                let values = SelectFieldByLabel::<AggLabel>::select_field(&self);
+                let mut sorted = (0..nrows).collect::<Vec<_>>();
+                sorted.sort_by(|&left, &right| {
+                    let ordering = partial_value_cmp(
+                        values.get_datum(left).unwrap(),
+                        values.get_datum(right).unwrap(),
+                    );
+                    match direction {
+                        SortDirection::Ascending => ordering,
+                        SortDirection::Descending => ordering.reverse(),
+                    }
+                });
+                sorted
+            }
+        };
+        self.frames = self.frames.update_permutation(&sorted);
+        // the view may have been sorted by another field before reordering
+        self.invariants.clear_sorted();
+        self
+    }
+}
+
+// Compares two (possibly missing) values which are only partially ordered. Missing (NA) values are
+// ordered first, followed by values which are not comparable to themselves (such as NaN), so that
+// the comparison is a total order.
+fn partial_value_cmp<T>(left: Value<&T>, right: Value<&T>) -> Ordering
+where
+    T: PartialOrd,
+{
+    let rank = |value: &Value<&T>| match *value {
+        Value::Na => 0,
+        Value::Exists(value) if value.partial_cmp(value).is_none() => 1,
+        Value::Exists(_) => 2,
+    };
+    rank(&left)
+        .cmp(&rank(&right))
+        .then_with(|| match (left, right) {
+            (Value::Exists(left), Value::Exists(right)) => {
+                left.partial_cmp(right).unwrap_or(Ordering::Equal)
+            }
+            _ => Ordering::Equal,
+        })
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Aggregate values over a specified grouping of records (see
+    /// [aggregate](#method.aggregate)), and sort the resulting groups in the order `order`.
+    pub fn aggregate_ordered<KeyLabels, ValueLabel, AggLabel, DType, AggType, AggFunc>(
+        &self,
+        init: AggType,
+        f: AggFunc,
+        order: GroupOrder,
+    ) -> <Self as Aggregate<KeyLabels, ValueLabel, AggLabel, DType, AggType>>::Output
+    where
+        Self: Aggregate<KeyLabels, ValueLabel, AggLabel, DType, AggType>,
+        <Self as Aggregate<KeyLabels, ValueLabel, AggLabel, DType, AggType>>::Output:
+            OrderGroups<KeyLabels, AggLabel>,
+        AggFunc: Fn(&mut AggType, Value<&DType>),
+    {
+        let aggregated = Aggregate::<KeyLabels, ValueLabel, AggLabel, DType, AggType>::aggregate::<
+            AggFunc,
+        >(self, init, f);
+        OrderGroups::<KeyLabels, AggLabel>::order_groups(aggregated, order)
+    }
+
+    /// Sorts the groups (records) of the output of an aggregation in the order `order`, either
+    /// by the values of the key fields labeled `KeyLabels` or by the values of the aggregated
+    /// field labeled `AggLabel`. Sorting by value uses a stable sort, so groups with equal values
+    /// remain in their existing order. This can be used to order the output
+    /// of any aggregation, such as [aggregate_many](#method.aggregate_many).
+    pub fn order_groups<KeyLabels, AggLabel>(self, order: GroupOrder) -> Self
+    where
+        Self: OrderGroups<KeyLabels, AggLabel>,
+    {
+        OrderGroups::<KeyLabels, AggLabel>::order_groups(self, order)
+    }
+}
+
+/// Type alias for the store holding the value and count fields of the output of
+/// [top_k](../view/struct.DataView.html#method.top_k).
+pub type TopKStoreOf<ValueLabel, DType, CountLabel> = DataStore<
+    <DataStore<<DataStore<Nil> as PushBackField<ValueLabel, DType>>::OutputFields> as PushBackField<
+        CountLabel,
+        u64,
+    >>::OutputFields,
+>;
+
+/// Type alias for the output of finding the most frequent values of the field labeled
+/// `ValueLabel` in each group of `View` (grouped by the fields labeled `KeyLabels`), with counts in
+/// a new field labeled `CountLabel`.
+pub type TopKOutputOf<View, KeyLabels, ValueLabel, CountLabel> =
+    <View as TopK<KeyLabels, ValueLabel, CountLabel>>::Output;
+
+/// Trait providing the `top_k` method for finding the most frequent values within each group of
+/// records. See the intrinsic method [top_k](../view/struct.DataView.html#method.top_k) for more
+/// details.
+pub trait TopK<KeyLabels, ValueLabel, CountLabel> {
+    /// Type produced by this method.
+    type Output;
+
+    /// Find the `k` most frequent values in each group. See the intrinsic method
+    /// [top_k](../view/struct.DataView.html#method.top_k) for more details.
+    fn top_k(&self, k: usize) -> Self::Output;
+}
+
+impl<Labels, Frames, KeyLabels, ValueLabel, CountLabel> TopK<KeyLabels, ValueLabel, CountLabel>
+    for DataView<Labels, Frames>
+where
+    Self: NRows + SelectFieldByLabel<ValueLabel>,
+    VFieldOf<Self, ValueLabel>: KeyIndex,
+    VFieldTypeOf<Self, ValueLabel>: Clone,
+    Labels: FieldList<KeyLabels, Frames> + LabelSubset<KeyLabels> + FrameIndexList,
+    <Labels as FieldList<KeyLabels, Frames>>::Output: KeyList,
+    <Labels as LabelSubset<KeyLabels>>::Output: Reorder<KeyLabels>,
+    DataStore<Nil>: PushBackField<ValueLabel, VFieldTypeOf<Self, ValueLabel>>,
+    DataStore<
+        <DataStore<Nil> as PushBackField<ValueLabel, VFieldTypeOf<Self, ValueLabel>>>::OutputFields,
+    >: PushBackField<CountLabel, u64>,
+    TopKStoreOf<ValueLabel, VFieldTypeOf<Self, ValueLabel>, CountLabel>: IntoFrame,
+    Frames: NRows + SubsetClone<<Labels as FrameIndexList>::LabelList>,
+    <Frames as SubsetClone<<Labels as FrameIndexList>::LabelList>>::Output: UpdatePermutation,
+    DataView<
+        <<Labels as LabelSubset<KeyLabels>>::Output as Reorder<KeyLabels>>::Output,
+        <Frames as SubsetClone<<Labels as FrameIndexList>::LabelList>>::Output,
+    >: AddFrame<
+        <TopKStoreOf<ValueLabel, VFieldTypeOf<Self, ValueLabel>, CountLabel> as IntoFrame>::Output,
+    >,
+{
+    // output is KeyLabels, then ValueLabel and CountLabel columns
+    type Output = <DataView<
+        <<Labels as LabelSubset<KeyLabels>>::Output as Reorder<KeyLabels>>::Output,
+        <Frames as SubsetClone<<Labels as FrameIndexList>::LabelList>>::Output,
+    > as AddFrame<
+        <TopKStoreOf<ValueLabel, VFieldTypeOf<Self, ValueLabel>, CountLabel> as IntoFrame>::Output,
+    >>::Output;
+
+    fn top_k(&self, k: usize) -> Self::Output {
+        let nrows = self.nrows();
+        let fl = self.field_list::<KeyLabels>();
+        let values = self.field::<ValueLabel>();
+        let key_groups = erased::group_records(&fl.keys(), nrows);
+        let mut keys = fl.keys();
+        keys.push(&values);
+        let value_groups = erased::group_records(&keys, nrows);
+
+        let mut counts = vec![0u64; value_groups.len()];
+        for &group in &value_groups.record_groups {
+            counts[group] += 1;
+        }
+        // distinct existing values of each key group, in order of first appearance
+        let mut group_values = vec![vec![]; key_groups.len()];
+        for (value_group, &first_idx) in value_groups.first_indices.iter().enumerate() {
+            if values.get_datum(first_idx).unwrap().exists() {
+                group_values[key_groups.record_groups[first_idx]].push(value_group);
+            }
+        }
+
+        let mut indices = vec![];
+        let mut top_values = vec![];
+        let mut top_counts = vec![];
+        for mut value_groups_in_key in group_values {
+            // stable sort, so equally frequent values remain in order of first appearance
+            value_groups_in_key.sort_by(|&left, &right| counts[right].cmp(&counts[left]));
+            for value_group in value_groups_in_key.into_iter().take(k) {
+                let first_idx = value_groups.first_indices[value_group];
+                indices.push(first_idx);
+                top_values.push(values.get_datum(first_idx).unwrap().unwrap().clone());
+                top_counts.push(counts[value_group]);
+            }
+        }
+        let top_frame = DataStore::<Nil>::empty()
+            .push_back_field::<ValueLabel, _>(FieldData::from_vec(top_values))
+            .push_back_field::<CountLabel, _>(FieldData::from_vec(top_counts))
+            .into_frame();
+
+        let record_frames = self.frames.subset_clone().update_permutation(&indices);
+
+        DataView {
+            _labels: PhantomData,
+            frames: record_frames,
+            invariants: Invariants::default(),
+        }
+        .add_frame(top_frame)
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Finds the `k` most frequent values of the field labeled `ValueLabel` within each group of
+    /// records (grouped by the fields labeled `KeyLabels`). The resulting `DataView` contains the
+    /// `KeyLabels` fields, the `ValueLabel` field, and a field labeled `CountLabel` containing the
+    /// number of records in the group with that value. Each group (in order of first appearance)
+    /// has up to `k` records, from most to least frequent value; equally frequent values are
+    /// ordered by first appearance. Missing (NA) values are not counted, so groups without any
+    /// existing values do not appear in the output.
+    pub fn top_k<KeyLabels, ValueLabel, CountLabel>(
+        &self,
+        k: usize,
+    ) -> TopKOutputOf<Self, KeyLabels, ValueLabel, CountLabel>
+    where
+        Self: TopK<KeyLabels, ValueLabel, CountLabel>,
+    {
+        TopK::<KeyLabels, ValueLabel, CountLabel>::top_k(self, k)
+    }
+}
+
+/// Type alias for the output of computing a weighted quantile of the field labeled `ValueLabel`
+/// (weighted by the field labeled `WeightLabel`) for each group of `View` (grouped by the fields
+/// labeled `KeyLabels`), into a new field labeled `AggLabel`.
+pub type WeightedQuantileOutputOf<View, KeyLabels, ValueLabel, WeightLabel, AggLabel> =
+    <View as WeightedQuantileBy<KeyLabels, ValueLabel, WeightLabel, AggLabel>>::Output;
+
+/// Trait providing the `weighted_quantile_by` method for computing weighted quantiles within each
+/// group of records. See the intrinsic method
+/// [weighted_quantile_by](../view/struct.DataView.html#method.weighted_quantile_by) for more
+/// details.
+pub trait WeightedQuantileBy<KeyLabels, ValueLabel, WeightLabel, AggLabel> {
+    /// Type produced by this method.
+    type Output;
+
+    /// Compute the weighted `q`-th quantile of each group. See the intrinsic method
+    /// [weighted_quantile_by](../view/struct.DataView.html#method.weighted_quantile_by) for more
+    /// details.
+    fn weighted_quantile_by(&self, q: f64) -> Self::Output;
+}
+
+impl<Labels, Frames, KeyLabels, ValueLabel, WeightLabel, AggLabel>
+    WeightedQuantileBy<KeyLabels, ValueLabel, WeightLabel, AggLabel> for DataView<Labels, Frames>
+where
+    Self: NRows + SelectFieldByLabel<ValueLabel> + SelectFieldByLabel<WeightLabel>,
+    VFieldTypeOf<Self, ValueLabel>: AsPrimitive<f64>,
+    VFieldTypeOf<Self, WeightLabel>: AsPrimitive<f64>,
+    Labels: FieldList<KeyLabels, Frames> + LabelSubset<KeyLabels> + FrameIndexList,
+    <Labels as FieldList<KeyLabels, Frames>>::Output: KeyList,
+    <Labels as LabelSubset<KeyLabels>>::Output: Reorder<KeyLabels>,
+    FieldData<f64>: IntoStore<AggLabel>,
+    <FieldData<f64> as IntoStore<AggLabel>>::Output: IntoFrame,
+    Frames: NRows + SubsetClone<<Labels as FrameIndexList>::LabelList>,
+    <Frames as SubsetClone<<Labels as FrameIndexList>::LabelList>>::Output: UpdatePermutation,
+    DataView<
+        <<Labels as LabelSubset<KeyLabels>>::Output as Reorder<KeyLabels>>::Output,
+        <Frames as SubsetClone<<Labels as FrameIndexList>::LabelList>>::Output,
+    >: AddFrame<<<FieldData<f64> as IntoStore<AggLabel>>::Output as IntoFrame>::Output>,
+{
+    // output is KeyLabels, then single AggLabel column
+    type Output = <DataView<
+        <<Labels as LabelSubset<KeyLabels>>::Output as Reorder<KeyLabels>>::Output,
+        <Frames as SubsetClone<<Labels as FrameIndexList>::LabelList>>::Output,
+    > as AddFrame<
+        <<FieldData<f64> as IntoStore<AggLabel>>::Output as IntoFrame>::Output,
+    >>::Output;
+
+    fn weighted_quantile_by(&self, q: f64) -> Self::Output {
+        assert!((0.0..=1.0).contains(&q), "quantile must be between 0 and 1");
+        let fl = self.field_list::<KeyLabels>();
+        let values = self.field::<ValueLabel>();
+        let weights = self.field::<WeightLabel>();
+        let groups = erased::group_records(&fl.keys(), self.nrows());
+        let mut group_values = vec![vec![]; groups.len()];
+        for (i, &group) in groups.record_groups.iter().enumerate() {
+            if let (Value::Exists(value), Value::Exists(weight)) =
+                (values.get_datum(i).unwrap(), weights.get_datum(i).unwrap())
+            {
+                group_values[group].push((value.as_(), weight.as_()));
+            }
+        }
+        let quantiles = FieldData::from_field_vec(
+            group_values
+                .into_iter()
+                .map(|values| stats::weighted_quantile_of(values, q).into())
+                .collect(),
+        );
+        let agg_frame = IntoStore::<AggLabel>::into_store(quantiles).into_frame();
+
+        let record_frames = self
+            .frames
+            .subset_clone()
+            .update_permutation(&groups.first_indices);
+
+        DataView {
+            _labels: PhantomData,
+            frames: record_frames,
+            invariants: Invariants::default(),
+        }
+        .add_frame(agg_frame)
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Computes the weighted `q`-th quantile (for `q` between `0.0` and `1.0`) of the values of
+    /// the field labeled `ValueLabel`, weighted by the values of the field labeled `WeightLabel`,
+    /// for each group of records with the same values in the fields labeled `KeyLabels`. The
+    /// quantiles are stored in a new field labeled `AggLabel`. Weighted quantiles are computed as
+    /// in [WeightedQuantile](../stats/trait.WeightedQuantile.html), and the quantile of a group
+    /// without any (positively-weighted) existing values is missing (NA). For unweighted
+    /// quantiles, see [quantile_by](#method.quantile_by).
+    ///
+    /// # Panics
+    /// Panics if `q` is not between `0.0` and `1.0`.
+    pub fn weighted_quantile_by<KeyLabels, ValueLabel, WeightLabel, AggLabel>(
+        &self,
+        q: f64,
+    ) -> WeightedQuantileOutputOf<Self, KeyLabels, ValueLabel, WeightLabel, AggLabel>
+    where
+        Self: WeightedQuantileBy<KeyLabels, ValueLabel, WeightLabel, AggLabel>,
+    {
+        WeightedQuantileBy::<KeyLabels, ValueLabel, WeightLabel, AggLabel>::weighted_quantile_by(
+            self, q,
+        )
+    }
+}
+
+/// Subtotals to compute in addition to the aggregates of each group, for use with
+/// [DataView::aggregate_subtotals](../view/struct.DataView.html#method.aggregate_subtotals).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subtotals {
+    /// Hierarchical subtotals: for key labels `[K1, K2, ..., Kn]`, aggregates are computed for each
+    /// group of `[K1, ..., Kn]`, then for each group of `[K1, ..., Kn-1]`, and so on, down to a
+    /// grand total over all records.
+    Rollup,
+    /// Subtotals over every combination of the key labels: aggregates are computed for each group
+    /// of every subset of the key labels (including the empty subset, the grand total).
+    Cube,
+}
+
+impl Subtotals {
+    // Returns the grouping sets (for each set, whether each of `nkeys` keys is kept) to aggregate.
+    fn grouping_sets(self, nkeys: usize) -> Vec<Vec<bool>> {
+        match self {
+            Subtotals::Rollup => (0..=nkeys)
+                .rev()
+                .map(|nkept| (0..nkeys).map(|i| i < nkept).collect())
+                .collect(),
+            Subtotals::Cube => (0..1usize << nkeys)
+                .rev()
+                .map(|set| {
+                    (0..nkeys)
+                        .map(|i| set & (1 << (nkeys - 1 - i)) != 0)
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Trait for constructing a [DataStore](../store/struct.DataStore.html) containing the fields in
+/// `Frames` whose labels (in `Self`, a `FrameLookupCons`) are found in `KeyLabels`, where each key
+/// field has its own list of indices. A `None` index produces a missing value (as for the
+/// rolled-up keys of a subtotal record). The key fields are stored in the order of `Self`.
+pub trait SubtotalKeys<KeyLabels, Frames> {
+    /// [FieldCons](../fieldlist/type.FieldCons.html) cons-list of the key fields.
+    type OutputFields: AssocStorage;
+
+    /// Constructs the key fields, using `indices[i]` as the indices into the key field labeled
+    /// with the `i`-th label in `KeyLabels`.
+    fn subtotal_keys(
+        frames: &Frames,
+        indices: &[Vec<Option<usize>>],
+    ) -> error::Result<DataStore<Self::OutputFields>>;
+}
+impl<KeyLabels, Frames> SubtotalKeys<KeyLabels, Frames> for Nil {
+    type OutputFields = Nil;
+    fn subtotal_keys(
+        _frames: &Frames,
+        _indices: &[Vec<Option<usize>>],
+    ) -> error::Result<DataStore<Nil>> {
+        Ok(DataStore::<Nil>::empty())
+    }
+}
+impl<KeyLabels, Frames, Label, FrameIndex, FrameLabel, Tail> SubtotalKeys<KeyLabels, Frames>
+    for FrameLookupCons<Label, FrameIndex, FrameLabel, Tail>
+where
+    KeyLabels: Member<Label>,
+    Self: SubtotalKeysPred<KeyLabels, Frames, <KeyLabels as Member<Label>>::IsMember>,
+{
+    type OutputFields = <Self as SubtotalKeysPred<
+        KeyLabels,
+        Frames,
+        <KeyLabels as Member<Label>>::IsMember,
+    >>::OutputFields;
+
+    fn subtotal_keys(
+        frames: &Frames,
+        indices: &[Vec<Option<usize>>],
+    ) -> error::Result<DataStore<Self::OutputFields>> {
+        Self::subtotal_keys_pred(frames, indices)
+    }
+}
+
+/// Helper trait for [SubtotalKeys](trait.SubtotalKeys.html). `IsMember` is whether or not the head
+/// of `Self` is a member of the list `KeyLabels`.
+pub trait SubtotalKeysPred<KeyLabels, Frames, IsMember> {
+    /// [FieldCons](../fieldlist/type.FieldCons.html) cons-list of the key fields.
+    type OutputFields: AssocStorage;
+
+    /// Constructs the key fields from `frames`.
+    fn subtotal_keys_pred(
+        frames: &Frames,
+        indices: &[Vec<Option<usize>>],
+    ) -> error::Result<DataStore<Self::OutputFields>>;
+}
+impl<KeyLabels, Frames, Label, FrameIndex, FrameLabel, Tail>
+    SubtotalKeysPred<KeyLabels, Frames, True>
+    for FrameLookupCons<Label, FrameIndex, FrameLabel, Tail>
+where
+    KeyLabels: LookupNatByLabel<Label>,
+    Frames: SelectFieldFromLabels<Self, Label>,
+    Tail: SubtotalKeys<KeyLabels, Frames>,
+    DataStore<Tail::OutputFields>:
+        PushFrontClonedFromValueIter<Label, <Frames as SelectFieldFromLabels<Self, Label>>::DType>,
+{
+    type OutputFields = <DataStore<Tail::OutputFields> as PushFrontClonedFromValueIter<
+        Label,
+        <Frames as SelectFieldFromLabels<Self, Label>>::DType,
+    >>::OutputFields;
+
+    fn subtotal_keys_pred(
+        frames: &Frames,
+        indices: &[Vec<Option<usize>>],
+    ) -> error::Result<DataStore<Self::OutputFields>> {
+        let field = SelectFieldFromLabels::<Self, Label>::select_field(frames);
+        let key_idx = <KeyLabels as LookupNatByLabel<Label>>::Nat::USIZE;
+        let values = indices[key_idx]
+            .iter()
+            .map(|idx| match *idx {
+                Some(idx) => field.get_datum(idx),
+                None => Ok(Value::Na),
+            })
+            .collect::<error::Result<Vec<_>>>()?;
+        Ok(Tail::subtotal_keys(frames, indices)?.push_front_cloned_from_value_iter(values))
+    }
+}
+impl<KeyLabels, Frames, Label, FrameIndex, FrameLabel, Tail>
+    SubtotalKeysPred<KeyLabels, Frames, False>
+    for FrameLookupCons<Label, FrameIndex, FrameLabel, Tail>
+where
+    Tail: SubtotalKeys<KeyLabels, Frames>,
+{
+    type OutputFields = Tail::OutputFields;
+
+    fn subtotal_keys_pred(
+        frames: &Frames,
+        indices: &[Vec<Option<usize>>],
+    ) -> error::Result<DataStore<Self::OutputFields>> {
+        Tail::subtotal_keys(frames, indices)
+    }
+}
+
+/// Type alias for the `FrameLookupCons` of the key labels `KeyLabels` (in `KeyLabels` order) of a
+/// `DataView` with labels `Labels`.
+pub type KeyLookupOf<Labels, KeyLabels> =
+    <<Labels as LabelSubset<KeyLabels>>::Output as Reorder<KeyLabels>>::Output;
+
+/// Type alias for the store holding the key fields of the output of
+/// [aggregate_subtotals](../view/struct.DataView.html#method.aggregate_subtotals) (in the order
+/// of the labels `Labels` of the aggregated `DataView`).
+pub type SubtotalKeyStoreOf<Labels, Frames, KeyLabels> =
+    DataStore<<Labels as SubtotalKeys<KeyLabels, Frames>>::OutputFields>;
+
+/// Type alias for the `DataView` of the key fields of the output of
+/// [aggregate_subtotals](../view/struct.DataView.html#method.aggregate_subtotals), with the keys
+/// in `KeyLabels` order.
+pub type SubtotalKeyViewOf<Labels, Frames, KeyLabels> = <<SubtotalKeyStoreOf<
+    Labels,
+    Frames,
+    KeyLabels,
+> as IntoView>::Output as Subview<KeyLabels>>::Output;
+
+/// Type alias for the output of performing the aggregations `Aggs` with subtotals over the records
+/// of `View` grouped by the fields labeled `KeyLabels`.
+pub type AggregateSubtotalsOutputOf<View, KeyLabels, Aggs> =
+    <View as AggregateSubtotals<KeyLabels, Aggs>>::Output;
+
+/// Trait providing the `aggregate_subtotals` method for computing aggregations over hierarchical
+/// groupings of records. See the intrinsic method
+/// [aggregate_subtotals](../view/struct.DataView.html#method.aggregate_subtotals) for more
+/// details.
+pub trait AggregateSubtotals<KeyLabels, Aggs> {
+    /// Type produced by this aggregate method.
+    type Output;
+
+    /// Perform the aggregations with the subtotals `subtotals`, reporting progress to `observer`.
+    /// See the intrinsic method
+    /// [aggregate_subtotals_observed](../view/struct.DataView.html#method.aggregate_subtotals_observed)
+    /// for more details.
+    fn aggregate_subtotals_observed<O>(
+        &self,
+        aggs: Aggs,
+        subtotals: Subtotals,
+        observer: &mut O,
+    ) -> error::Result<Self::Output>
+    where
+        O: Observer;
+}
+
+impl<Labels, Frames, KeyLabels, Aggs> AggregateSubtotals<KeyLabels, Aggs>
+    for DataView<Labels, Frames>
+where
+    Self: NRows,
+    Aggs: AggList<Self>,
+    Labels: LabelSubset<KeyLabels> + SubtotalKeys<KeyLabels, Frames>,
+    <Labels as LabelSubset<KeyLabels>>::Output: Reorder<KeyLabels>,
+    KeyLookupOf<Labels, KeyLabels>: FieldList<KeyLabels, Frames>,
+    <KeyLookupOf<Labels, KeyLabels> as FieldList<KeyLabels, Frames>>::Output: KeyList + OrdKeyList,
+    SubtotalKeyStoreOf<Labels, Frames, KeyLabels>: IntoView,
+    <SubtotalKeyStoreOf<Labels, Frames, KeyLabels> as IntoView>::Output: Subview<KeyLabels>,
+    DataStore<Aggs::OutputFields>: IntoFrame,
+    SubtotalKeyViewOf<Labels, Frames, KeyLabels>:
+        AddFrame<<DataStore<Aggs::OutputFields> as IntoFrame>::Output>,
+{
+    // output is KeyLabels, then one column per aggregation
+    type Output = <SubtotalKeyViewOf<Labels, Frames, KeyLabels> as AddFrame<
+        <DataStore<Aggs::OutputFields> as IntoFrame>::Output,
+    >>::Output;
+
+    fn aggregate_subtotals_observed<O>(
+        &self,
+        aggs: Aggs,
+        subtotals: Subtotals,
+        observer: &mut O,
+    ) -> error::Result<Self::Output>
+    where
+        O: Observer,
+    {
+        let nrows = self.nrows();
+        let fl = <KeyLookupOf<Labels, KeyLabels> as FieldList<KeyLabels, Frames>>::field_list(
+            &self.frames,
+        );
+        let keys = fl.keys();
+        let ord_keys = fl.ord_keys();
+
+        // group the records once for each grouping set; output rows are identified by their
+        // grouping set and the first record in the group
+        let sets = subtotals.grouping_sets(keys.len());
+        let mut set_groups = vec![];
+        let mut rows = vec![];
+        for (set_idx, set) in sets.iter().enumerate() {
+            let set_keys = keys
+                .iter()
+                .zip(set)
+                .filter(|&(_, &kept)| kept)
+                .map(|(&key, _)| key)
+                .collect::<Vec<_>>();
+            let groups = erased::group_records_observed(&set_keys, nrows, &*observer)?;
+            set_groups.push((rows.len(), groups.record_groups));
+            rows.extend(groups.first_indices.into_iter().map(|idx| (set_idx, idx)));
+        }
+
+        // order rows by key values, with rolled-up keys (subtotals) following the detail rows
+        let mut order = (0..rows.len()).collect::<Vec<_>>();
+        order.sort_by(|&left, &right| {
+            let ((lset, lidx), (rset, ridx)) = (rows[left], rows[right]);
+            (0..ord_keys.len())
+                .map(|i| match (sets[lset][i], sets[rset][i]) {
+                    (true, true) => ord_keys[i].cmp_at(lidx, ridx),
+                    (true, false) => Ordering::Less,
+                    (false, true) => Ordering::Greater,
+                    (false, false) => Ordering::Equal,
+                })
+                .find(|&ordering| ordering != Ordering::Equal)
+                .unwrap_or(Ordering::Equal)
+        });
+        let mut positions = vec![0; rows.len()];
+        for (position, &row) in order.iter().enumerate() {
+            positions[row] = position;
+        }
+
+        let mut reporter = Reporter::new(observer, Stage::Aggregate, Some(nrows));
+        reporter.check()?;
+        let mut state = aggs.init_state(self, rows.len());
+        for i in 0..nrows {
+            for &(offset, ref record_groups) in &set_groups {
+                aggs.accumulate(&mut state, positions[offset + record_groups[i]], i);
+            }
+            reporter.advance(1)?;
+        }
+        reporter.finish();
+        let agg_frame = aggs.finish(state).into_frame();
+
+        let indices = (0..keys.len())
+            .map(|i| {
+                order
+                    .iter()
+                    .map(|&row| {
+                        let (set_idx, idx) = rows[row];
+                        if sets[set_idx][i] {
+                            Some(idx)
+                        } else {
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        // the key fields are constructed in the order of this view's labels, so take a subview
+        // to reorder them into `KeyLabels` order
+        let key_view =
+            <Labels as SubtotalKeys<KeyLabels, Frames>>::subtotal_keys(&self.frames, &indices)?
+                .into_view();
+        let key_view = Subview::<KeyLabels>::subview(&key_view);
+        Ok(key_view.add_frame(agg_frame))
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Computes several aggregations (as in [aggregate_many](#method.aggregate_many)) over the
+    /// groups of records with the same values in the fields labeled `KeyLabels`, along with
+    /// subtotal aggregations over coarser groupings of the records as specified by `subtotals`
+    /// (e.g. totals per region, then per region and product, then a grand total). Each
+    /// combination of key labels is grouped only once, and all the aggregations are accumulated
+    /// in a single scan over the records.
+    ///
+    /// The resulting `DataView` contains the `KeyLabels` fields followed by the aggregated fields
+    /// in the order of `aggs`, with one record per group of each grouping. The key fields which
+    /// are rolled up in a subtotal record are missing (NA). Records are ordered by the values of
+    /// the key fields (compared in key label order), with each subtotal record immediately
+    /// following the records it summarizes; the grand total is the final record.
+    ///
+    /// Note that subtotal records cannot be distinguished from records whose key values are
+    /// missing by their key fields alone; a count aggregation can be used to tell them apart.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate agnes;
+    /// use agnes::access::DataIndex;
+    /// use agnes::aggregate::{agg, Subtotals};
+    /// use agnes::cons::{cons, Nil};
+    /// use agnes::field::FieldData;
+    /// use agnes::select::FieldSelect;
+    /// use agnes::store::{DataStore, PushBackField};
+    /// use agnes::value::Value;
+    ///
+    /// tablespace![
+    ///     table sales {
+    ///         Region: String,
+    ///         Product: String,
+    ///         Amount: f64,
+    ///         Total: f64,
+    ///     }
+    /// ];
+    ///
+    /// fn main() {
+    ///     let dv = DataStore::<Nil>::empty()
+    ///         .push_back_field::<sales::Region, _>(FieldData::<String>::from_vec(vec![
+    ///             "N".to_string(), "S".to_string(), "N".to_string(), "N".to_string(),
+    ///         ]))
+    ///         .push_back_field::<sales::Product, _>(FieldData::<String>::from_vec(vec![
+    ///             "A".to_string(), "A".to_string(), "B".to_string(), "A".to_string(),
+    ///         ]))
+    ///         .push_back_field::<sales::Amount, _>(FieldData::<f64>::from_vec(vec![
+    ///             3.0, 2.0, 5.0, 1.0,
+    ///         ]))
+    ///         .into_view();
+    ///
+    ///     let totals = dv.aggregate_subtotals::<Labels![sales::Region, sales::Product], _>(
+    ///         cons(
+    ///             agg::<sales::Amount, sales::Total, _, _>(0.0, |sum, val| {
+    ///                 *sum += val.unwrap_or(&0.0);
+    ///             }),
+    ///             Nil,
+    ///         ),
+    ///         Subtotals::Rollup,
+    ///     );
+    ///     let name = |s: &str| Value::Exists(s.to_string());
+    ///     assert_eq!(
+    ///         totals.field::<sales::Region>().to_value_vec(),
+    ///         vec![name("N"), name("N"), name("N"), name("S"), name("S"), Value::Na]
+    ///     );
+    ///     assert_eq!(
+    ///         totals.field::<sales::Product>().to_value_vec(),
+    ///         vec![name("A"), name("B"), Value::Na, name("A"), Value::Na, Value::Na]
+    ///     );
+    ///     assert_eq!(
+    ///         totals.field::<sales::Total>().to_vec(),
+    ///         vec![4.0, 5.0, 9.0, 2.0, 2.0, 11.0]
+    ///     );
+    /// }
+    /// ```
+    pub fn aggregate_subtotals<KeyLabels, Aggs>(
+        &self,
+        aggs: Aggs,
+        subtotals: Subtotals,
+    ) -> AggregateSubtotalsOutputOf<Self, KeyLabels, Aggs>
+    where
+        Self: AggregateSubtotals<KeyLabels, Aggs>,
+    {
+        // aggregations without an observer cannot be cancelled
+        self.aggregate_subtotals_observed::<KeyLabels, Aggs, _>(aggs, subtotals, &mut NoObserver)
+            .unwrap()
+    }
+
+    /// Computes several aggregations with subtotals, reporting the number of records accumulated
+    /// to `observer` (with stage [Stage::Aggregate](../observer/enum.Stage.html)). See
+    /// [aggregate_subtotals](#method.aggregate_subtotals).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation.
+    pub fn aggregate_subtotals_observed<KeyLabels, Aggs, O>(
+        &self,
+        aggs: Aggs,
+        subtotals: Subtotals,
+        observer: &mut O,
+    ) -> error::Result<AggregateSubtotalsOutputOf<Self, KeyLabels, Aggs>>
+    where
+        Self: AggregateSubtotals<KeyLabels, Aggs>,
+        O: Observer,
+    {
+        AggregateSubtotals::<KeyLabels, Aggs>::aggregate_subtotals_observed(
+            self, aggs, subtotals, observer,
+        )
+    }
+}
+
+// Returns the indices of the records in each group (in record order).
+fn group_indices(groups: &Groups) -> Vec<Vec<usize>> {
+    let mut indices = vec![vec![]; groups.len()];
+    for (i, &group) in groups.record_groups.iter().enumerate() {
+        indices[group].push(i);
+    }
+    indices
+}
+
+/// Type alias for the values of a single group of records passed to the statistic function of
+/// [DataView::transform_by](../view/struct.DataView.html#method.transform_by): the field labeled
+/// `ValueLabel` of `View`, restricted to the records of the group.
+pub type TransformGroupOf<View, ValueLabel> =
+    Framed<VFieldTypeOf<View, ValueLabel>, VFieldOf<View, ValueLabel>>;
+
+/// Type alias for the frame containing the field labeled `OutLabel` (with values of type
+/// `OutType`) added by [DataView::transform_by](../view/struct.DataView.html#method.transform_by).
+pub type TransformFrameOf<OutLabel, OutType> =
+    <<FieldData<OutType> as IntoStore<OutLabel>>::Output as IntoFrame>::Output;
+
+/// Type alias for the output of broadcasting a per-group statistic of the field labeled
+/// `ValueLabel` of `View` (grouped by the fields labeled `KeyLabels`) into a new field labeled
+/// `OutLabel`.
+pub type TransformByOutputOf<View, KeyLabels, ValueLabel, OutLabel, OutType> =
+    <View as TransformBy<KeyLabels, ValueLabel, OutLabel, OutType>>::Output;
+
+/// Trait providing the `transform_by` method for broadcasting a statistic computed over each group
+/// of records back to every record of the group. See the intrinsic method
+/// [transform_by](../view/struct.DataView.html#method.transform_by) for more details.
+pub trait TransformBy<KeyLabels, ValueLabel, OutLabel, OutType>:
+    SelectFieldByLabel<ValueLabel>
+{
+    /// Type produced by this method.
+    type Output;
+
+    /// Compute a statistic with `f` for each group, and add it to each record of the group. See
+    /// the intrinsic method [transform_by](../view/struct.DataView.html#method.transform_by) for
+    /// more details.
+    fn transform_by<F>(
+        &self,
+        f: F,
+    ) -> TransformByOutputOf<Self, KeyLabels, ValueLabel, OutLabel, OutType>
+    where
+        F: Fn(&TransformGroupOf<Self, ValueLabel>) -> Value<OutType>;
+}
+
+impl<Labels, Frames, KeyLabels, ValueLabel, OutLabel, OutType>
+    TransformBy<KeyLabels, ValueLabel, OutLabel, OutType> for DataView<Labels, Frames>
+where
+    Self: NRows + SelectFieldByLabel<ValueLabel> + AddFrame<TransformFrameOf<OutLabel, OutType>>,
+    Labels: FieldList<KeyLabels, Frames>,
+    <Labels as FieldList<KeyLabels, Frames>>::Output: KeyList,
+    OutType: Debug + Default + Clone,
+    FieldData<OutType>: IntoStore<OutLabel>,
+    <FieldData<OutType> as IntoStore<OutLabel>>::Output: IntoFrame,
+{
+    type Output = <Self as AddFrame<TransformFrameOf<OutLabel, OutType>>>::Output;
+
+    fn transform_by<F>(
+        &self,
+        f: F,
+    ) -> TransformByOutputOf<Self, KeyLabels, ValueLabel, OutLabel, OutType>
+    where
+        F: Fn(&TransformGroupOf<Self, ValueLabel>) -> Value<OutType>,
+    {
+        let groups = erased::group_records(&self.field_list::<KeyLabels>().keys(), self.nrows());
+        let stats = group_indices(&groups)
+            .iter()
+            .map(|indices| f(&self.field::<ValueLabel>().permute(indices)))
+            .collect::<Vec<_>>();
+        let transformed = FieldData::from_field_vec(
+            groups
+                .record_groups
+                .iter()
+                .map(|&group| stats[group].clone())
+                .collect(),
+        );
+        self.add_frame(IntoStore::<OutLabel>::into_store(transformed).into_frame())
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Computes a statistic with `f` over the values of the field labeled `ValueLabel` within each
+    /// group of records with the same values in the fields labeled `KeyLabels`, and broadcasts it
+    /// back to every record of the group in a new field labeled `OutLabel`. Unlike
+    /// [aggregate](#method.aggregate), the resulting `DataView` has the same records (in the same
+    /// order) as this `DataView`, so per-group statistics such as a group mean can be compared to
+    /// each record's own value without an aggregation followed by a join.
+    ///
+    /// `f` is called once for each group, with the values of the `ValueLabel` field for the
+    /// records of that group (in record order), and can use any of the
+    /// [statistics](../stats/index.html) methods provided for `DataIndex` types.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate agnes;
+    /// use agnes::access::DataIndex;
+    /// use agnes::cons::Nil;
+    /// use agnes::field::FieldData;
+    /// use agnes::select::FieldSelect;
+    /// use agnes::stats::Mean;
+    /// use agnes::store::{DataStore, PushBackField};
+    /// use agnes::value::Value;
+    ///
+    /// tablespace![
+    ///     table scores {
+    ///         Team: u64,
+    ///         Score: f64,
+    ///         TeamMean: f64,
+    ///     }
+    /// ];
+    ///
+    /// fn main() {
+    ///     let dv = DataStore::<Nil>::empty()
+    ///         .push_back_field::<scores::Team, _>(FieldData::<u64>::from_vec(vec![1u64, 2, 1, 2]))
+    ///         .push_back_field::<scores::Score, _>(FieldData::<f64>::from_vec(vec![
+    ///             3.0, 8.0, 5.0, 4.0,
+    ///         ]))
+    ///         .into_view();
+    ///
+    ///     let dv = dv.transform_by::<Labels![scores::Team], scores::Score, scores::TeamMean, _, _>(
+    ///         |group| Value::Exists(group.mean()),
+    ///     );
+    ///     assert_eq!(dv.field::<scores::TeamMean>().to_vec(), vec![4.0, 6.0, 4.0, 6.0]);
+    ///
+    ///     // deviation of each score from its team's mean
+    ///     let deviations = dv
+    ///         .field::<scores::Score>()
+    ///         .iter()
+    ///         .zip(dv.field::<scores::TeamMean>().iter())
+    ///         .map(|(score, mean)| score.unwrap() - mean.unwrap())
+    ///         .collect::<Vec<_>>();
+    ///     assert_eq!(deviations, vec![-1.0, 2.0, 1.0, -2.0]);
+    /// }
+    /// ```
+    pub fn transform_by<KeyLabels, ValueLabel, OutLabel, OutType, F>(
+        &self,
+        f: F,
+    ) -> TransformByOutputOf<Self, KeyLabels, ValueLabel, OutLabel, OutType>
+    where
+        Self: TransformBy<KeyLabels, ValueLabel, OutLabel, OutType>,
+        F: Fn(&TransformGroupOf<Self, ValueLabel>) -> Value<OutType>,
+    {
+        TransformBy::<KeyLabels, ValueLabel, OutLabel, OutType>::transform_by(self, f)
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Keeps or drops entire groups of records (grouped by the fields labeled `KeyLabels`) based on
+    /// `predicate`, which is called once for each group with a `DataView` containing only the
+    /// records of that group (e.g. to keep the departments with at least five employees). Returns
+    /// a new `DataView` containing the records of the groups for which `predicate` returns `true`,
+    /// in their original order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate agnes;
+    /// use agnes::access::{DataIndex, NRows};
+    /// use agnes::cons::Nil;
+    /// use agnes::field::FieldData;
+    /// use agnes::select::FieldSelect;
+    /// use agnes::store::{DataStore, PushBackField};
+    ///
+    /// tablespace![
+    ///     table emps {
+    ///         DeptId: u64,
+    ///         EmpName: String,
+    ///     }
+    /// ];
+    ///
+    /// fn main() {
+    ///     let dv = DataStore::<Nil>::empty()
+    ///         .push_back_field::<emps::DeptId, _>(FieldData::<u64>::from_vec(vec![1u64, 2, 1, 3]))
+    ///         .push_back_field::<emps::EmpName, _>(FieldData::<String>::from_vec(vec![
+    ///             "Sally".to_string(), "Jamie".to_string(), "Bob".to_string(), "Cara".to_string(),
+    ///         ]))
+    ///         .into_view();
+    ///
+    ///     let large_depts = dv.filter_groups::<Labels![emps::DeptId], _>(|dept| dept.nrows() >= 2);
+    ///     assert_eq!(large_depts.field::<emps::EmpName>().to_vec(), vec!["Sally", "Bob"]);
+    /// }
+    /// ```
+    pub fn filter_groups<KeyLabels, P>(&self, mut predicate: P) -> Self
+    where
+        Self: NRows,
+        Labels: FieldList<KeyLabels, Frames>,
+        <Labels as FieldList<KeyLabels, Frames>>::Output: KeyList,
+        Frames: Clone + UpdatePermutation,
+        P: FnMut(&Self) -> bool,
+    {
+        let groups = erased::group_records(&self.field_list::<KeyLabels>().keys(), self.nrows());
+        let keep = group_indices(&groups)
+            .iter()
+            .map(|indices| predicate(&self.subset_records(indices)))
+            .collect::<Vec<_>>();
+        let indices = groups
+            .record_groups
+            .iter()
+            .enumerate()
+            .filter(|&(_, &group)| keep[group])
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        self.subset_records(&indices)
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Returns a new `DataView` containing the first record of each group of records with the
+    /// same values in the fields labeled `KeyLabels`. Groups appear in order of first appearance
+    /// in this `DataView`. Unlike [first_by](#method.first_by), which finds the first value of a
+    /// single field, this selects entire records. See also [last_record_by](#method.last_record_by)
+    /// and [nth_record_by](#method.nth_record_by).
+    pub fn first_record_by<KeyLabels>(&self) -> Self
+    where
+        Self: NRows,
+        Labels: FieldList<KeyLabels, Frames>,
+        <Labels as FieldList<KeyLabels, Frames>>::Output: KeyList,
+        Frames: Clone + UpdatePermutation,
+    {
+        let groups = erased::group_records(&self.field_list::<KeyLabels>().keys(), self.nrows());
+        self.subset_records(&groups.first_indices)
+    }
+
+    /// Returns a new `DataView` containing the last record of each group of records with the same
+    /// values in the fields labeled `KeyLabels`. Groups appear in order of first appearance in
+    /// this `DataView`. Combined with sorting, this selects e.g. the latest record for each
+    /// customer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate agnes;
+    /// use agnes::access::DataIndex;
+    /// use agnes::cons::Nil;
+    /// use agnes::field::FieldData;
+    /// use agnes::select::FieldSelect;
+    /// use agnes::store::{DataStore, PushBackField};
+    ///
+    /// tablespace![
+    ///     table orders {
+    ///         Customer: String,
+    ///         Day: u64,
+    ///         Amount: f64,
+    ///     }
+    /// ];
+    ///
+    /// fn main() {
+    ///     let dv = DataStore::<Nil>::empty()
+    ///         .push_back_field::<orders::Customer, _>(FieldData::<String>::from_vec(vec![
+    ///             "A".to_string(), "B".to_string(), "A".to_string(), "B".to_string(),
+    ///         ]))
+    ///         .push_back_field::<orders::Day, _>(FieldData::<u64>::from_vec(vec![5u64, 2, 3, 7]))
+    ///         .push_back_field::<orders::Amount, _>(FieldData::<f64>::from_vec(vec![
+    ///             10.0, 20.0, 30.0, 40.0,
+    ///         ]))
+    ///         .into_view();
+    ///
+    ///     let latest = dv
+    ///         .sort_by_label::<orders::Day>()
+    ///         .last_record_by::<Labels![orders::Customer]>();
+    ///     assert_eq!(latest.field::<orders::Customer>().to_vec(), vec!["B", "A"]);
+    ///     assert_eq!(latest.field::<orders::Amount>().to_vec(), vec![40.0, 10.0]);
+    /// }
+    /// ```
+    pub fn last_record_by<KeyLabels>(&self) -> Self
+    where
+        Self: NRows,
+        Labels: FieldList<KeyLabels, Frames>,
+        <Labels as FieldList<KeyLabels, Frames>>::Output: KeyList,
+        Frames: Clone + UpdatePermutation,
+    {
+        let groups = erased::group_records(&self.field_list::<KeyLabels>().keys(), self.nrows());
+        let mut last_indices = groups.first_indices.clone();
+        for (i, &group) in groups.record_groups.iter().enumerate() {
+            last_indices[group] = i;
+        }
+        self.subset_records(&last_indices)
+    }
+
+    /// Returns a new `DataView` containing the `n`-th record (counting from zero) of each group of
+    /// records with the same values in the fields labeled `KeyLabels`. Groups with `n` or fewer
+    /// records are omitted. Groups appear in order of first appearance in this `DataView`.
+    pub fn nth_record_by<KeyLabels>(&self, n: usize) -> Self
+    where
+        Self: NRows,
+        Labels: FieldList<KeyLabels, Frames>,
+        <Labels as FieldList<KeyLabels, Frames>>::Output: KeyList,
+        Frames: Clone + UpdatePermutation,
+    {
+        let groups = erased::group_records(&self.field_list::<KeyLabels>().keys(), self.nrows());
+        let nth_indices = group_indices(&groups)
+            .into_iter()
+            .filter_map(|indices| indices.get(n).cloned())
+            .collect::<Vec<_>>();
+        self.subset_records(&nth_indices)
+    }
+}
+
+#[cfg(feature = "test-utils")]
+#[cfg(test)]
+mod tests {
+    use super::{agg, agg_with, GroupOrder, SortDirection, Subtotals};
+
+    use access::DataIndex;
+    use cons::{cons, Nil};
+    use error::AgnesError;
+    use field::FieldData;
+    use observer::CancellationToken;
+    use select::FieldSelect;
+    use store::DataStore;
+    use test_utils::emp_stats::*;
+    use test_utils::emp_table::*;
+    use test_utils::extra_emp::*;
+    use test_utils::sample_dept_vacation_table;
+    use value::Value;
+
+    #[test]
+    fn aggregate_many() {
+        let dv = sample_dept_vacation_table()
+            .push_back_field::<EmpId, _>(FieldData::<u64>::from_vec(vec![0u64, 1, 2, 3, 4, 5]))
+            .into_view();
+
+        let aggs = dv.aggregate_many::<Labels![DeptId], _>(cons(
+            agg::<VacationHrs, VacationHrs, _, _>(0.0, |sum, val| *sum += val.unwrap_or(&0.0)),
+            cons(
+                agg::<EmpId, NumEmps, _, _>(0u64, |count, _| *count += 1),
+                cons(
+                    agg_with::<VacationHrs, MeanVacationHrs, _, _, _, _>(
+                        (0.0, 0u64),
+                        |acc: &mut (f64, u64), val| {
+                            if let Value::Exists(&val) = val {
+                                acc.0 += f64::from(val);
+                                acc.1 += 1;
+                            }
+                        },
+                        |accs| {
+                            FieldData::from_field_vec(
+                                accs.into_iter()
+                                    .map(|(sum, n)| match n {
+                                        0 => Value::Na,
+                                        n => Value::Exists(sum / n as f64),
+                                    })
+                                    .collect(),
+                            )
+                        },
+                    ),
+                    Nil,
+                ),
+            ),
+        ));
+        assert_eq!(aggs.nrows(), 3);
+        assert_eq!(aggs.field::<DeptId>().to_vec(), vec![1u64, 2, 3]);
+        assert_eq!(
+            aggs.field::<VacationHrs>().to_vec(),
+            vec![15.0f32, 9.0, 0.0]
+        );
+        assert_eq!(aggs.field::<NumEmps>().to_vec(), vec![3u64, 2, 1]);
+        assert_eq!(
+            aggs.field::<MeanVacationHrs>().to_value_vec(),
+            vec![Value::Exists(7.5), Value::Exists(4.5), Value::Na]
+        );
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = dv.aggregate_many_observed::<Labels![DeptId], _, _>(
+            cons(
+                agg::<EmpId, NumEmps, _, _>(0u64, |count, _| *count += 1),
+                Nil,
+            ),
+            &mut token.clone(),
+        );
+        match result {
+            Err(AgnesError::Cancelled) => {}
+            _ => panic!("expected cancellation"),
+        }
+    }
+
+    #[test]
+    fn aggregate_ordered() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<DeptId, _>(FieldData::<u64>::from_vec(vec![2u64, 3, 1, 2, 3, 4]))
+            .push_back_field::<VacationHrs, _>(FieldData::from_field_vec(vec![
+                Value::Exists(1.0f32),
+                Value::Exists(5.0),
+                Value::Exists(3.0),
+                Value::Exists(1.0),
+                Value::Exists(f32::NAN),
+                Value::Na,
+            ]))
+            .into_view();
+        let sum = |sum: &mut f32, val: Value<&f32>| *sum += val.unwrap_or(&0.0);
+
+        let aggs = dv.aggregate_ordered::<Labels![DeptId], VacationHrs, VacationHrs, _, _, _>(
+            0.0,
+            sum,
+            GroupOrder::default(),
+        );
+        assert_eq!(aggs.field::<DeptId>().to_vec(), vec![2u64, 3, 1, 4]);
+
+        let aggs = dv.aggregate_ordered::<Labels![DeptId], VacationHrs, VacationHrs, _, _, _>(
+            0.0,
+            sum,
+            GroupOrder::Keys(SortDirection::Ascending),
+        );
+        assert_eq!(aggs.field::<DeptId>().to_vec(), vec![1u64, 2, 3, 4]);
+        let aggs = aggs.order_groups::<Labels![DeptId], VacationHrs>(GroupOrder::Keys(
+            SortDirection::Descending,
+        ));
+        assert_eq!(aggs.field::<DeptId>().to_vec(), vec![4u64, 3, 2, 1]);
+
+        // group 3 sums to NaN, which is ordered with the lowest values
+        let aggs = dv.aggregate_ordered::<Labels![DeptId], VacationHrs, VacationHrs, _, _, _>(
+            0.0,
+            sum,
+            GroupOrder::Value(SortDirection::Descending),
+        );
+        assert_eq!(aggs.field::<DeptId>().to_vec(), vec![1u64, 2, 4, 3]);
+
+        let counts = dv
+            .aggregate_many::<Labels![DeptId], _>(cons(
+                agg::<VacationHrs, NumEmps, _, _>(0u64, |count, _| *count += 1),
+                Nil,
+            ))
+            .order_groups::<Labels![DeptId], NumEmps>(GroupOrder::Value(SortDirection::Ascending));
+        assert_eq!(counts.field::<DeptId>().to_vec(), vec![1u64, 4, 2, 3]);
+        assert_eq!(counts.field::<NumEmps>().to_vec(), vec![1u64, 1, 2, 2]);
+
+        // reordering groups of a sorted view invalidates its sortedness (which `lookup` relies on)
+        let counts = counts
+            .sort_by_label::<DeptId>()
+            .order_groups::<Labels![DeptId], NumEmps>(GroupOrder::Value(SortDirection::Descending));
+        assert_eq!(counts.field::<DeptId>().to_vec(), vec![2u64, 3, 1, 4]);
+        assert!(!counts.invariants.is_sorted::<DeptId>());
+        assert_eq!(counts.lookup::<DeptId>(&1), vec![2]);
+        assert_eq!(counts.lookup::<DeptId>(&4), vec![3]);
+    }
+
+    #[test]
+    fn aggregate_subtotals() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<DeptId, _>(FieldData::<u64>::from_vec(vec![2u64, 1, 2, 1, 1]))
+            .push_back_field::<DidTraining, _>(FieldData::<bool>::from_vec(vec![
+                true, false, false, false, true,
+            ]))
+            .push_back_field::<EmpId, _>(FieldData::<u64>::from_vec(vec![0u64, 1, 2, 3, 4]))
+            .into_view();
+        let count = || {
+            cons(
+                agg::<EmpId, NumEmps, _, _>(0u64, |count, _| *count += 1),
+                Nil,
+            )
+        };
+
+        let rollup =
+            dv.aggregate_subtotals::<Labels![DeptId, DidTraining], _>(count(), Subtotals::Rollup);
+        assert_eq!(
+            rollup.field::<DeptId>().to_value_vec(),
+            vec![
+                Value::Exists(1u64),
+                Value::Exists(1),
+                Value::Exists(1),
+                Value::Exists(2),
+                Value::Exists(2),
+                Value::Exists(2),
+                Value::Na
+            ]
+        );
+        assert_eq!(
+            rollup.field::<DidTraining>().to_value_vec(),
+            vec![
+                Value::Exists(false),
+                Value::Exists(true),
+                Value::Na,
+                Value::Exists(false),
+                Value::Exists(true),
+                Value::Na,
+                Value::Na
+            ]
+        );
+        assert_eq!(
+            rollup.field::<NumEmps>().to_vec(),
+            vec![2u64, 1, 3, 1, 1, 2, 5]
+        );
+
+        // key order determines the hierarchy
+        let rollup =
+            dv.aggregate_subtotals::<Labels![DidTraining, DeptId], _>(count(), Subtotals::Rollup);
+        assert_eq!(
+            rollup.fieldnames(),
+            vec!["DidTraining", "DeptId", "NumEmps"]
+        );
+        assert_eq!(
+            rollup.field::<NumEmps>().to_vec(),
+            vec![2u64, 1, 3, 1, 1, 2, 5]
+        );
+
+        let cube =
+            dv.aggregate_subtotals::<Labels![DeptId, DidTraining], _>(count(), Subtotals::Cube);
+        assert_eq!(cube.nrows(), 9);
+        assert_eq!(
+            cube.field::<DidTraining>().to_value_vec()[6..],
+            [Value::Exists(false), Value::Exists(true), Value::Na]
+        );
+        assert_eq!(
+            cube.field::<NumEmps>().to_vec(),
+            vec![2u64, 1, 3, 1, 1, 2, 3, 2, 5]
+        );
+
+        let single = dv.aggregate_subtotals::<Labels![DeptId], _>(count(), Subtotals::Cube);
+        assert_eq!(single.field::<NumEmps>().to_vec(), vec![3u64, 2, 5]);
+    }
+
+    #[test]
+    fn top_k() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<DeptId, _>(FieldData::<u64>::from_vec(vec![
+                2u64, 1, 2, 2, 1, 2, 3, 2,
+            ]))
+            .push_back_field::<EmpName, _>(FieldData::from_field_vec(vec![
+                Value::Exists("a".to_string()),
+                Value::Exists("b".to_string()),
+                Value::Exists("b".to_string()),
+                Value::Exists("c".to_string()),
+                Value::Exists("b".to_string()),
+                Value::Exists("c".to_string()),
+                Value::Na,
+                Value::Na,
+            ]))
+            .into_view();
+
+        let top = dv.top_k::<Labels![DeptId], EmpName, NumEmps>(2);
+        assert_eq!(top.nrows(), 3);
+        assert_eq!(top.field::<DeptId>().to_vec(), vec![2u64, 2, 1]);
+        assert_eq!(top.field::<EmpName>().to_vec(), vec!["c", "a", "b"]);
+        assert_eq!(top.field::<NumEmps>().to_vec(), vec![2u64, 1, 2]);
+
+        let top = dv.top_k::<Labels![DeptId], EmpName, NumEmps>(0);
+        assert_eq!(top.nrows(), 0);
+    }
+
+    #[test]
+    fn quantiles() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<DeptId, _>(FieldData::<u64>::from_vec(vec![1u64, 2, 1, 1, 2, 3]))
+            .push_back_field::<VacationHrs, _>(FieldData::from_field_vec(vec![
+                Value::Exists(4.0f32),
+                Value::Exists(5.0),
+                Value::Exists(1.0),
+                Value::Exists(2.0),
+                Value::Na,
+                Value::Na,
+            ]))
+            .push_back_field::<NumEmps, _>(FieldData::<u64>::from_vec(vec![1u64, 1, 1, 5, 1, 1]))
+            .into_view();
+
+        let medians = dv.median_by::<Labels![DeptId], VacationHrs, MeanVacationHrs>();
+        assert_eq!(medians.field::<DeptId>().to_vec(), vec![1u64, 2, 3]);
+        assert_eq!(
+            medians.field::<MeanVacationHrs>().to_value_vec(),
+            vec![Value::Exists(2.0), Value::Exists(5.0), Value::Na]
+        );
+        let maxs = dv.quantile_by::<Labels![DeptId], VacationHrs, MeanVacationHrs>(1.0);
+        assert_eq!(
+            maxs.field::<MeanVacationHrs>().to_value_vec(),
+            vec![Value::Exists(4.0), Value::Exists(5.0), Value::Na]
+        );
+
+        let weighted =
+            dv.weighted_quantile_by::<Labels![DeptId], VacationHrs, NumEmps, MeanVacationHrs>(0.25);
+        assert_eq!(weighted.field::<DeptId>().to_vec(), vec![1u64, 2, 3]);
+        assert_eq!(
+            weighted.field::<MeanVacationHrs>().to_value_vec(),
+            vec![Value::Exists(2.0), Value::Exists(5.0), Value::Na]
+        );
+    }
+
+    #[test]
+    fn transform_by() {
+        use stats::{Mean, NaCount};
+
+        let dv = sample_dept_vacation_table().into_view();
+
+        let means =
+            dv.transform_by::<Labels![DeptId], VacationHrs, MeanVacationHrs, _, _>(|group| {
+                if group.num_exists() == 0 {
+                    Value::Na
+                } else {
+                    Value::Exists(group.mean())
+                }
+            });
+        assert_eq!(means.nrows(), 6);
+        assert_eq!(means.field::<DeptId>().to_vec(), vec![1u64, 2, 1, 3, 2, 1]);
+        assert_eq!(
+            means.field::<MeanVacationHrs>().to_value_vec(),
+            vec![
+                Value::Exists(7.5),
+                Value::Exists(4.5),
+                Value::Exists(7.5),
+                Value::Na,
+                Value::Exists(4.5),
+                Value::Exists(7.5),
+            ]
+        );
+
+        let counts = dv.transform_by::<Labels![DeptId], VacationHrs, NumEmps, _, _>(|group| {
+            Value::Exists(group.len() as u64)
+        });
+        assert_eq!(
+            counts.field::<NumEmps>().to_vec(),
+            vec![3u64, 2, 3, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn filter_groups() {
+        use test_utils::sample_emp_table;
+
+        let dv = sample_emp_table().into_view();
+        let large = dv.filter_groups::<Labels![DeptId], _>(|dept| dept.nrows() >= 2);
+        assert_eq!(large.nrows(), 5);
+        assert_eq!(large.field::<DeptId>().to_vec(), vec![1u64, 1, 1, 4, 4]);
+
+        // groups of a sorted view, in sorted order
+        let sorted = dv.clone().sort_by_label::<EmpName>();
+        let with_sally = sorted.filter_groups::<Labels![DeptId], _>(|dept| {
+            dept.field::<EmpName>()
+                .iter()
+                .any(|name| name == Value::Exists(&"Sally".to_string()))
+        });
+        assert_eq!(
+            with_sally.field::<EmpName>().to_vec(),
+            vec!["Bob", "Cara", "Sally"]
+        );
+        // records are kept in order, so sortedness is preserved
+        assert!(with_sally.invariants.is_sorted::<EmpName>());
+
+        let none = dv.filter_groups::<Labels![DeptId, EmpName], _>(|_| false);
+        assert_eq!(none.nrows(), 0);
+    }
+
+    #[test]
+    fn nth_record_by() {
+        use test_utils::sample_emp_table;
+
+        let dv = sample_emp_table().into_view();
+        let first = dv.first_record_by::<Labels![DeptId]>();
+        assert_eq!(first.field::<DeptId>().to_vec(), vec![1u64, 2, 3, 4]);
+        assert_eq!(
+            first.field::<EmpName>().to_vec(),
+            vec!["Sally", "Jamie", "Louis", "Louise"]
+        );
+        let last = dv.last_record_by::<Labels![DeptId]>();
+        assert_eq!(last.field::<DeptId>().to_vec(), vec![1u64, 2, 3, 4]);
+        assert_eq!(
+            last.field::<EmpName>().to_vec(),
+            vec!["Cara", "Jamie", "Louis", "Ann"]
+        );
+        let second = dv.nth_record_by::<Labels![DeptId]>(1);
+        assert_eq!(second.field::<DeptId>().to_vec(), vec![1u64, 4]);
+        assert_eq!(second.field::<EmpName>().to_vec(), vec!["Bob", "Ann"]);
+        assert_eq!(dv.nth_record_by::<Labels![DeptId]>(3).nrows(), 0);
+
+        // groups of a sorted view
+        let sorted = dv.clone().sort_by_label::<EmpName>();
+        let first = sorted.first_record_by::<Labels![DeptId]>();
+        assert_eq!(first.field::<DeptId>().to_vec(), vec![4u64, 1, 2, 3]);
+        assert_eq!(
+            first.field::<EmpName>().to_vec(),
+            vec!["Ann", "Bob", "Jamie", "Louis"]
+        );
+    }
+}
@@ -0,0 +1,162 @@
+/*!
+Alignment of `DataView`s with differing sets of fields to a common schema.
+
+Data exported over time (e.g. monthly exports) often gains or loses fields. The
+[DataView::align_to](../view/struct.DataView.html#method.align_to) method converts a `DataView`
+into one with exactly the fields of a target label list, in the order of that list: fields of the
+target which exist in the view are copied, fields of the target which do not exist in the view are
+added with all values missing (NA), and fields of the view which are not in the target are
+dropped. Since the type of the result depends only on the target labels, views with different
+(but overlapping) sets of fields become views of the same type, which can then be compared or
+combined.
+*/
+use std::fmt::Debug;
+
+use access::{DataIndex, NRows};
+use cons::Nil;
+use field::FieldData;
+use label::{False, LCons, Member, True, TypeOf, Typed};
+use select::{FieldSelect, SelectFieldByLabel};
+use store::{AssocStorage, DataStore, IntoView, PushFrontField};
+use value::Value;
+use view::DataView;
+
+/// Trait for building a [DataStore](../store/struct.DataStore.html) containing the fields labeled
+/// by the labels in `Self` (a label list), copied from `View` if they exist and filled with missing
+/// values otherwise. See the intrinsic method
+/// [align_to](../view/struct.DataView.html#method.align_to) for more details.
+pub trait AlignTo<View> {
+    /// `Fields` type parameter of the resultant `DataStore`.
+    type OutputFields: AssocStorage;
+
+    /// Returns the aligned `DataStore`.
+    fn align_store(view: &View) -> DataStore<Self::OutputFields>;
+}
+
+impl<View> AlignTo<View> for Nil {
+    type OutputFields = Nil;
+
+    fn align_store(_view: &View) -> DataStore<Nil> {
+        DataStore::<Nil>::empty()
+    }
+}
+
+impl<Labels, Frames, Label, Tail> AlignTo<DataView<Labels, Frames>> for LCons<Label, Tail>
+where
+    Labels: Member<Label>,
+    DataView<Labels, Frames>: AlignField<Label, <Labels as Member<Label>>::IsMember>,
+    Label: Typed,
+    Tail: AlignTo<DataView<Labels, Frames>>,
+    DataStore<Tail::OutputFields>: PushFrontField<Label, TypeOf<Label>>,
+{
+    type OutputFields =
+        <DataStore<Tail::OutputFields> as PushFrontField<Label, TypeOf<Label>>>::OutputFields;
+
+    fn align_store(view: &DataView<Labels, Frames>) -> DataStore<Self::OutputFields> {
+        Tail::align_store(view).push_front_field::<Label, _>(view.align_field())
+    }
+}
+
+/// Helper trait for [AlignTo](trait.AlignTo.html). `IsMember` is whether or not `Label` is a label
+/// of `Self`.
+pub trait AlignField<Label, IsMember>
+where
+    Label: Typed,
+{
+    /// Returns the aligned field labeled `Label`.
+    fn align_field(&self) -> FieldData<TypeOf<Label>>;
+}
+
+impl<Labels, Frames, Label> AlignField<Label, True> for DataView<Labels, Frames>
+where
+    Self: SelectFieldByLabel<Label, DType = TypeOf<Label>>,
+    Label: Typed,
+    TypeOf<Label>: Debug + Default + Clone,
+{
+    fn align_field(&self) -> FieldData<TypeOf<Label>> {
+        self.field::<Label>().iter().collect()
+    }
+}
+
+impl<Labels, Frames, Label> AlignField<Label, False> for DataView<Labels, Frames>
+where
+    Self: NRows,
+    Label: Typed,
+    TypeOf<Label>: Debug + Default,
+{
+    fn align_field(&self) -> FieldData<TypeOf<Label>> {
+        (0..self.nrows()).map(|_| Value::Na).collect()
+    }
+}
+
+/// Type of the `DataView` produced by [align_to](../view/struct.DataView.html#method.align_to).
+pub type AlignedViewOf<View, TargetLabels> =
+    <DataStore<<TargetLabels as AlignTo<View>>::OutputFields> as IntoView>::Output;
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Creates a new `DataView` containing exactly the fields labeled `TargetLabels`, in the order
+    /// of `TargetLabels`. Fields which exist in this `DataView` are copied, fields which do not
+    /// exist are added with all values missing (NA), and fields which are not in `TargetLabels`
+    /// are dropped. See the [align](../align/index.html) module for more details.
+    pub fn align_to<TargetLabels>(&self) -> AlignedViewOf<Self, TargetLabels>
+    where
+        TargetLabels: AlignTo<Self>,
+        DataStore<<TargetLabels as AlignTo<Self>>::OutputFields>: IntoView,
+    {
+        TargetLabels::align_store(self).into_view()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn align_to() {
+        use super::*;
+        use test_utils::*;
+
+        let dv = sample_emp_table()
+            .into_view()
+            .merge(&sample_emp_table_extra().into_view())
+            .unwrap();
+        let aligned = dv.align_to::<Labels![
+            emp_table::EmpName,
+            emp_table::EmpId,
+            dept_table::DeptName,
+            extra_emp::DidTraining
+        ]>();
+        assert_eq!(
+            aligned.fieldnames(),
+            vec!["EmpName", "EmpId", "DeptName", "DidTraining"]
+        );
+        assert_eq!(aligned.nrows(), 7);
+        assert_eq!(
+            aligned.field::<emp_table::EmpName>().to_vec()[..2],
+            ["Sally".to_string(), "Jamie".to_string()]
+        );
+        assert_eq!(
+            aligned.field::<emp_table::EmpId>().to_vec(),
+            vec![0u64, 2, 5, 6, 8, 9, 10]
+        );
+        assert_eq!(
+            aligned.field::<dept_table::DeptName>().to_value_vec(),
+            vec![Value::Na; 7]
+        );
+
+        // views with different fields align to the same type
+        let other = sample_emp_table().into_view();
+        let other_aligned = other.align_to::<Labels![
+            emp_table::EmpName,
+            emp_table::EmpId,
+            dept_table::DeptName,
+            extra_emp::DidTraining
+        ]>();
+        let aligned_views = [aligned, other_aligned];
+        assert_eq!(
+            aligned_views[1]
+                .field::<extra_emp::DidTraining>()
+                .to_value_vec(),
+            vec![Value::Na; 7]
+        );
+    }
+}
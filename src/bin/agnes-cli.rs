@@ -0,0 +1,155 @@
+//! Command-line tool for quick inspection of CSV files. Requires the `cli` feature.
+//!
+//! Usage:
+//! ```text
+//! agnes-cli head [-n <rows>] <file>
+//! agnes-cli describe <file>
+//! agnes-cli filter <query> <file>
+//! agnes-cli join <field>[=<right field>] <left file> <right file>
+//! ```
+//!
+//! `<file>` may be a local path, or (with the `net` feature) an HTTP / HTTPS URI. Files are loaded
+//! into runtime-typed tables (see the `agnes::dynamic` module), with fields referred to by their
+//! names in the file's header row. `<query>` is a query expression, such as
+//! `` state == "PA" || `Total Pop` > 1000 `` (see the `agnes::query` module for the syntax).
+//!
+//! Records and field descriptions are printed as tables with the same layout as a displayed
+//! `DataView`: a title row and separator line, followed by '|'-separated columns.
+
+extern crate agnes;
+
+use std::env;
+use std::process;
+
+use agnes::display::TextTable;
+use agnes::dynamic::DynTable;
+use agnes::error::*;
+use agnes::source::csv::CsvSource;
+use agnes::source::file::FileLocator;
+
+// Number of records displayed by `head` if not specified.
+const DEFAULT_HEAD_ROWS: usize = 10;
+
+const USAGE: &str = "usage:
+    agnes-cli head [-n <rows>] <file>
+    agnes-cli describe <file>
+    agnes-cli filter <query> <file>
+    agnes-cli join <field>[=<right field>] <left file> <right file>";
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let result = match args.first().map(|s| s.as_str()) {
+        Some("head") => parse_head_args(&args[1..]).and_then(|(nrows, file)| head(&file, nrows)),
+        Some("describe") if args.len() == 2 => describe(&args[1]),
+        Some("filter") if args.len() == 3 => filter(&args[1], &args[2]),
+        Some("join") if args.len() == 4 => join(&args[1], &args[2], &args[3]),
+        _ => Err(USAGE.to_string()),
+    };
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+// Parses the arguments to the `head` subcommand into the number of records to display and the
+// file location.
+fn parse_head_args(args: &[String]) -> ::std::result::Result<(usize, String), String> {
+    match args {
+        [file] => Ok((DEFAULT_HEAD_ROWS, file.clone())),
+        [flag, nrows, file] if flag == "-n" => nrows
+            .parse()
+            .map(|nrows| (nrows, file.clone()))
+            .map_err(|_| format!("invalid number of rows: {}", nrows)),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+// Displays the header and first `nrows` records of the file at `file`.
+fn head(file: &str, nrows: usize) -> ::std::result::Result<(), String> {
+    let table = source(file)
+        .and_then(|src| DynTable::from_csv(&src, Some(nrows)))
+        .map_err(|err| err.to_string())?;
+    print_table(&table);
+    Ok(())
+}
+
+// Displays the sniffed CSV dialect of the file at `file`, along with the name, inferred type, and
+// number of existing (non-empty) values of each field.
+fn describe(file: &str) -> ::std::result::Result<(), String> {
+    let src = source(file).map_err(|err| err.to_string())?;
+    let table = DynTable::from_csv(&src, None).map_err(|err| err.to_string())?;
+    let metadata = src.metadata();
+
+    println!("Records: {}", table.nrows());
+    println!("Fields: {}", metadata.num_fields);
+    println!(
+        "Delimiter: {:?}, header row: {}, preamble rows: {}",
+        metadata.dialect.delimiter as char,
+        metadata.dialect.header.has_header_row,
+        metadata.dialect.header.num_preamble_rows
+    );
+
+    let mut fields = TextTable::new(&["Field", "Type", "Count"]);
+    for (name, column) in table.names().iter().zip(table.columns()) {
+        fields.add_row(&[
+            name.clone(),
+            column.ty().to_string(),
+            column.num_exists().to_string(),
+        ]);
+    }
+    print!("{}", fields);
+    Ok(())
+}
+
+// Displays the records of the file at `file` matching the query expression `expr`.
+fn filter(expr: &str, file: &str) -> ::std::result::Result<(), String> {
+    let table = source(file)
+        .and_then(|src| DynTable::from_csv(&src, None))
+        .and_then(|table| table.query(expr))
+        .map_err(|err| err.to_string())?;
+    print_table(&table);
+    Ok(())
+}
+
+// Displays the inner join of the files at `left` and `right`: one record for each pair of records
+// with equal values of the join fields. `on` is either the name of the join field in both files, or
+// `<left field>=<right field>`. The right file's join field is omitted from the output.
+fn join(on: &str, left: &str, right: &str) -> ::std::result::Result<(), String> {
+    let (left_field, right_field) = match on.find('=') {
+        Some(pos) => (&on[..pos], &on[pos + 1..]),
+        None => (on, on),
+    };
+    let load = |file| source(file).and_then(|src| DynTable::from_csv(&src, None));
+    let table = load(left)
+        .and_then(|left| Ok((left, load(right)?)))
+        .and_then(|(left, right)| left.join(&right, left_field, right_field))
+        .map_err(|err| err.to_string())?;
+    print_table(&table);
+    Ok(())
+}
+
+// Prints the field names and records of `table`.
+fn print_table(table: &DynTable) {
+    let mut out = TextTable::new(table.names());
+    for idx in 0..table.nrows() {
+        out.add_row(
+            table
+                .columns()
+                .iter()
+                .map(|column| column.format_value(idx).unwrap()),
+        );
+    }
+    print!("{}", out);
+}
+
+// Creates a CSV source from a command-line file argument (a local path, or a URI if the `net`
+// feature is enabled).
+fn source(file: &str) -> Result<CsvSource> {
+    #[cfg(feature = "net")]
+    {
+        if file.contains("://") {
+            return CsvSource::new(FileLocator::from_uri(file)?);
+        }
+    }
+    CsvSource::new(FileLocator::from(&file))
+}
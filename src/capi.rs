@@ -0,0 +1,1025 @@
+/*!
+A minimal C ABI for building bindings to agnes from other languages (such as Python or R).
+Requires the `capi` feature.
+
+Since the agnes API identifies fields with type-level labels, which cannot be expressed over a C
+interface, this module provides an opaque, dynamically-typed [AgnesStore](struct.AgnesStore.html)
+whose columns are identified by name and hold either `double` (`f64`) or `int64_t` (`i64`)
+values. Columns are stored as [FieldData](../field/struct.FieldData.html), so missing values are
+supported. Stores can be created from arrays, their column buffers read back, and filtered or
+aggregated.
+
+To build a shared library exporting these functions, build agnes as a `cdylib` with the `capi`
+feature (e.g. `cargo rustc --release --features capi --crate-type cdylib`), or depend on agnes
+from a `cdylib` crate which re-exports this module.
+
+# WebAssembly
+
+The core of agnes (without the `net` feature, which depends on Tokio and an HTTP client) compiles
+for `wasm32-unknown-unknown`, and the functions of this module are exported from the resulting
+module, allowing agnes to be used for in-browser data exploration:
+
+```sh
+cargo rustc --release --target wasm32-unknown-unknown --no-default-features --features capi \
+    --crate-type cdylib
+```
+
+From JavaScript, input bytes (CSV data and column names) are copied into a buffer allocated in the
+module's memory with [agnes_alloc](fn.agnes_alloc.html) (and released with
+[agnes_dealloc](fn.agnes_dealloc.html)), and results are read back from the module's memory:
+
+```js
+const { memory, agnes_alloc, agnes_dealloc, agnes_store_from_csv, agnes_store_to_json,
+        agnes_string_free, agnes_store_free } = instance.exports;
+const csv = new TextEncoder().encode(text);
+const ptr = agnes_alloc(csv.length);
+new Uint8Array(memory.buffer, ptr, csv.length).set(csv);
+const out = agnes_alloc(8);  // space for the output pointers
+if (agnes_store_from_csv(ptr, csv.length, out) === 0) {
+    const store = new Uint32Array(memory.buffer, out, 1)[0];
+    if (agnes_store_to_json(store, out) === 0) {
+        const json = new Uint32Array(memory.buffer, out, 1)[0];
+        // ... decode the NUL-terminated string at `json`, then
+        agnes_string_free(json);
+    }
+    agnes_store_free(store);
+}
+agnes_dealloc(out, 8);
+agnes_dealloc(ptr, csv.length);
+```
+
+# Conventions
+
+**Errors.** Every function which can fail returns an [AgnesStatus](enum.AgnesStatus.html) code,
+with `AGNES_OK` (`0`) denoting success, and writes its results through output pointer arguments
+(which are left untouched on failure). On failure, a description of the error can be retrieved
+with [agnes_last_error_message](fn.agnes_last_error_message.html). Panics are caught at the
+interface boundary and reported as `AgnesStatus::Panic`.
+
+**Ownership.** Stores returned by [agnes_store_new](fn.agnes_store_new.html),
+[agnes_store_from_csv](fn.agnes_store_from_csv.html), and
+[agnes_store_filter](fn.agnes_store_filter.html) are owned by the caller, and must be released
+with [agnes_store_free](fn.agnes_store_free.html), and strings returned by
+[agnes_store_to_json](fn.agnes_store_to_json.html) must be released with
+[agnes_string_free](fn.agnes_string_free.html). Arrays and strings passed into the library are
+borrowed only for the duration of the call (their contents are copied). Other pointers returned by
+the library (column buffers and error messages) are borrowed from the library: column buffers remain
+valid until the store is modified or freed, and error messages until the next failing call on the
+same thread. Callers must not free them.
+
+**Strings.** Column names are NUL-terminated UTF-8 strings.
+
+**Missing values.** Missing values are specified and reported with validity arrays of one byte
+per row, where a non-zero byte denotes that the row's value exists. In column buffers, missing
+values hold a placeholder value of `0`.
+*/
+
+use std::cell::RefCell;
+use std::convert::TryFrom;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+use csv_sniffer::Type;
+use serde_json;
+
+use access::{DataIndex, RawColumnar};
+use error::{self, AgnesError};
+use field::FieldData;
+use permute::FilterPerm;
+use source::csv::CsvSource;
+use source::decode::{decode, parse_value};
+use stats::{Extrema, Mean, NaCount, Sum};
+use value::Value;
+
+/// Status code returned by the functions of the C API.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgnesStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// An argument was invalid (e.g. a column name which is not valid UTF-8, or already exists).
+    InvalidArgument = 2,
+    /// No column with the specified name exists.
+    NotFound = 3,
+    /// The column does not hold values of the requested type.
+    TypeMismatch = 4,
+    /// An array's length did not match the number of rows in the store.
+    LengthMismatch = 5,
+    /// The result is undefined because the column has no (existing) values.
+    Empty = 6,
+    /// The library panicked; the store involved may be left in an unspecified (but valid) state.
+    Panic = 7,
+}
+
+/// Data type of a column in an [AgnesStore](struct.AgnesStore.html).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgnesType {
+    /// 64-bit floating-point values (`double`).
+    F64 = 0,
+    /// 64-bit signed integer values (`int64_t`).
+    I64 = 1,
+}
+
+/// Comparison operator for [agnes_store_filter](fn.agnes_store_filter.html).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgnesCompare {
+    /// Values less than the operand.
+    Lt = 0,
+    /// Values less than or equal to the operand.
+    Le = 1,
+    /// Values equal to the operand.
+    Eq = 2,
+    /// Values not equal to the operand.
+    Ne = 3,
+    /// Values greater than or equal to the operand.
+    Ge = 4,
+    /// Values greater than the operand.
+    Gt = 5,
+}
+
+impl AgnesCompare {
+    fn test(self, value: f64, operand: f64) -> bool {
+        match self {
+            AgnesCompare::Lt => value < operand,
+            AgnesCompare::Le => value <= operand,
+            AgnesCompare::Eq => value == operand,
+            AgnesCompare::Ne => value != operand,
+            AgnesCompare::Ge => value >= operand,
+            AgnesCompare::Gt => value > operand,
+        }
+    }
+}
+
+/// Aggregation for [agnes_store_aggregate](fn.agnes_store_aggregate.html). All aggregations
+/// ignore missing values.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgnesAggregate {
+    /// The number of existing values.
+    Count = 0,
+    /// The sum of the values.
+    Sum = 1,
+    /// The arithmetic mean of the values (`0` if no values exist).
+    Mean = 2,
+    /// The minimum value.
+    Min = 3,
+    /// The maximum value.
+    Max = 4,
+}
+
+#[derive(Debug, Clone)]
+enum ColumnData {
+    F64(FieldData<f64>),
+    I64(FieldData<i64>),
+}
+
+impl ColumnData {
+    fn len(&self) -> usize {
+        match *self {
+            ColumnData::F64(ref data) => data.len(),
+            ColumnData::I64(ref data) => data.len(),
+        }
+    }
+
+    fn permute(&self, indices: &[usize]) -> ColumnData {
+        match *self {
+            ColumnData::F64(ref data) => ColumnData::F64(permute_field(data, indices)),
+            ColumnData::I64(ref data) => ColumnData::I64(permute_field(data, indices)),
+        }
+    }
+}
+
+fn permute_field<T: Clone + Default + ::std::fmt::Debug>(
+    data: &FieldData<T>,
+    indices: &[usize],
+) -> FieldData<T> {
+    indices
+        .iter()
+        .map(|&idx| data.get(idx).map_or(Value::Na, |value| value.cloned()))
+        .collect()
+}
+
+/// An opaque, dynamically-typed data store for use over the C API. See the
+/// [module-level documentation](index.html) for details.
+#[derive(Debug, Clone, Default)]
+pub struct AgnesStore {
+    columns: Vec<(String, ColumnData)>,
+}
+
+impl AgnesStore {
+    fn nrows(&self) -> usize {
+        self.columns.first().map_or(0, |(_, data)| data.len())
+    }
+
+    fn column(&self, name: &str) -> Result<&ColumnData, Failure> {
+        self.columns
+            .iter()
+            .find(|(column, _)| column == name)
+            .map(|(_, data)| data)
+            .ok_or_else(|| Failure::new(AgnesStatus::NotFound, format!("no column '{}'", name)))
+    }
+}
+
+// A failed call: the status code to return and a description of the error.
+struct Failure {
+    status: AgnesStatus,
+    message: String,
+}
+
+impl From<AgnesError> for Failure {
+    fn from(err: AgnesError) -> Failure {
+        Failure::new(AgnesStatus::InvalidArgument, err.to_string())
+    }
+}
+
+impl Failure {
+    fn new<S: Into<String>>(status: AgnesStatus, message: S) -> Failure {
+        Failure {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+// Runs the body of an API call, recording any failure (or panic) as the last error.
+fn run<F>(body: F) -> AgnesStatus
+where
+    F: FnOnce() -> Result<(), Failure>,
+{
+    let failure = match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => return AgnesStatus::Ok,
+        Ok(Err(failure)) => failure,
+        Err(_) => Failure::new(AgnesStatus::Panic, "agnes panicked"),
+    };
+    let Failure { status, message } = failure;
+    LAST_ERROR.with(|last| {
+        *last.borrow_mut() = CString::new(message).ok();
+    });
+    status
+}
+
+fn non_null<'a, T>(pointer: *const T, what: &str) -> Result<&'a T, Failure> {
+    // the caller guarantees a non-null pointer is valid
+    unsafe { pointer.as_ref() }
+        .ok_or_else(|| Failure::new(AgnesStatus::NullPointer, format!("{} is null", what)))
+}
+
+fn non_null_mut<'a, T>(pointer: *mut T, what: &str) -> Result<&'a mut T, Failure> {
+    // the caller guarantees a non-null pointer is valid
+    unsafe { pointer.as_mut() }
+        .ok_or_else(|| Failure::new(AgnesStatus::NullPointer, format!("{} is null", what)))
+}
+
+fn name_str<'a>(name: *const c_char) -> Result<&'a str, Failure> {
+    if name.is_null() {
+        return Err(Failure::new(
+            AgnesStatus::NullPointer,
+            "column name is null",
+        ));
+    }
+    // the caller guarantees a non-null name is a valid NUL-terminated string
+    unsafe { CStr::from_ptr(name) }.to_str().map_err(|_| {
+        Failure::new(
+            AgnesStatus::InvalidArgument,
+            "column name is not valid UTF-8",
+        )
+    })
+}
+
+fn array<'a, T>(pointer: *const T, len: usize, what: &str) -> Result<&'a [T], Failure> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if pointer.is_null() {
+        return Err(Failure::new(
+            AgnesStatus::NullPointer,
+            format!("{} is null", what),
+        ));
+    }
+    // the caller guarantees a non-null array has `len` elements
+    Ok(unsafe { slice::from_raw_parts(pointer, len) })
+}
+
+/// Creates a new, empty store. The returned store is owned by the caller, and must be released
+/// with [agnes_store_free](fn.agnes_store_free.html).
+#[no_mangle]
+pub extern "C" fn agnes_store_new() -> *mut AgnesStore {
+    Box::into_raw(Box::default())
+}
+
+/// Releases a store created by this library. Does nothing if `store` is null.
+///
+/// # Safety
+/// `store` must be null or a store returned by this library which has not yet been freed.
+#[no_mangle]
+pub unsafe extern "C" fn agnes_store_free(store: *mut AgnesStore) {
+    if !store.is_null() {
+        drop(Box::from_raw(store));
+    }
+}
+
+/// Returns a description of the last error which occurred on the calling thread, or null if no
+/// error has occurred. The returned string is owned by the library, and remains valid until the
+/// next failing call on this thread.
+#[no_mangle]
+pub extern "C" fn agnes_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+fn add_column<T, F>(
+    store: *mut AgnesStore,
+    name: *const c_char,
+    values: *const T,
+    valid: *const u8,
+    len: usize,
+    wrap: F,
+) -> AgnesStatus
+where
+    T: Copy + Default + ::std::fmt::Debug,
+    F: FnOnce(FieldData<T>) -> ColumnData,
+{
+    run(|| {
+        let store = non_null_mut(store, "store")?;
+        let name = name_str(name)?;
+        let values = array(values, len, "values")?;
+        let valid = if valid.is_null() {
+            None
+        } else {
+            Some(array(valid, len, "validity array")?)
+        };
+        if store.columns.iter().any(|(column, _)| column == name) {
+            return Err(Failure::new(
+                AgnesStatus::InvalidArgument,
+                format!("column '{}' already exists", name),
+            ));
+        }
+        if !store.columns.is_empty() && store.nrows() != len {
+            return Err(Failure::new(
+                AgnesStatus::LengthMismatch,
+                format!("expected {} values, found {}", store.nrows(), len),
+            ));
+        }
+        let data = values
+            .iter()
+            .enumerate()
+            .map(|(idx, &value)| match valid {
+                Some(valid) if valid[idx] == 0 => Value::Na,
+                _ => Value::Exists(value),
+            })
+            .collect::<Vec<_>>();
+        store
+            .columns
+            .push((name.to_string(), wrap(FieldData::from_field_vec(data))));
+        Ok(())
+    })
+}
+
+/// Adds a column of `len` `double` values named `name` to `store`, copying the values from
+/// `values`. If `valid` is non-null, it must point to `len` validity bytes, where a zero byte
+/// denotes a missing value. Every column in a store must have the same number of rows.
+///
+/// # Safety
+/// `store` must be a valid store, `name` a NUL-terminated string, and `values` (and `valid`, if
+/// non-null) must point to at least `len` elements.
+#[no_mangle]
+pub unsafe extern "C" fn agnes_store_add_f64(
+    store: *mut AgnesStore,
+    name: *const c_char,
+    values: *const f64,
+    valid: *const u8,
+    len: usize,
+) -> AgnesStatus {
+    add_column(store, name, values, valid, len, ColumnData::F64)
+}
+
+/// Adds a column of `len` `int64_t` values named `name` to `store` (see
+/// [agnes_store_add_f64](fn.agnes_store_add_f64.html)).
+///
+/// # Safety
+/// As for [agnes_store_add_f64](fn.agnes_store_add_f64.html).
+#[no_mangle]
+pub unsafe extern "C" fn agnes_store_add_i64(
+    store: *mut AgnesStore,
+    name: *const c_char,
+    values: *const i64,
+    valid: *const u8,
+    len: usize,
+) -> AgnesStatus {
+    add_column(store, name, values, valid, len, ColumnData::I64)
+}
+
+/// Writes the number of rows and columns of `store` to `out_nrows` and `out_ncols`.
+///
+/// # Safety
+/// `store` must be a valid store, and the output pointers must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn agnes_store_shape(
+    store: *const AgnesStore,
+    out_nrows: *mut usize,
+    out_ncols: *mut usize,
+) -> AgnesStatus {
+    run(|| {
+        let store = non_null(store, "store")?;
+        let out_nrows = non_null_mut(out_nrows, "output pointer")?;
+        let out_ncols = non_null_mut(out_ncols, "output pointer")?;
+        *out_nrows = store.nrows();
+        *out_ncols = store.columns.len();
+        Ok(())
+    })
+}
+
+/// Writes the data type of the column named `name` of `store` to `out_type`.
+///
+/// # Safety
+/// `store` must be a valid store, `name` a NUL-terminated string, and `out_type` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn agnes_store_column_type(
+    store: *const AgnesStore,
+    name: *const c_char,
+    out_type: *mut AgnesType,
+) -> AgnesStatus {
+    run(|| {
+        let column = non_null(store, "store")?.column(name_str(name)?)?;
+        *non_null_mut(out_type, "output pointer")? = match *column {
+            ColumnData::F64(_) => AgnesType::F64,
+            ColumnData::I64(_) => AgnesType::I64,
+        };
+        Ok(())
+    })
+}
+
+fn column_buffer<T, F>(
+    store: *const AgnesStore,
+    name: *const c_char,
+    out_values: *mut *const T,
+    out_len: *mut usize,
+    select: F,
+) -> AgnesStatus
+where
+    T: ::std::fmt::Debug,
+    F: FnOnce(&ColumnData) -> Option<&FieldData<T>>,
+{
+    run(|| {
+        let column = non_null(store, "store")?.column(name_str(name)?)?;
+        let out_values = non_null_mut(out_values, "output pointer")?;
+        let out_len = non_null_mut(out_len, "output pointer")?;
+        let data = select(column).ok_or_else(|| {
+            Failure::new(AgnesStatus::TypeMismatch, "column has a different type")
+        })?;
+        let values = data
+            .raw_column()
+            .map_err(|err| Failure::new(AgnesStatus::InvalidArgument, err.to_string()))?
+            .values();
+        *out_values = values.as_ptr();
+        *out_len = values.len();
+        Ok(())
+    })
+}
+
+/// Writes a pointer to the buffer of `double` values of the column named `name` to `out_values`,
+/// and the number of values to `out_len`. The buffer is owned by the store, and remains valid
+/// until the store is modified or freed.
+///
+/// # Safety
+/// `store` must be a valid store, `name` a NUL-terminated string, and the output pointers must be
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn agnes_store_column_f64(
+    store: *const AgnesStore,
+    name: *const c_char,
+    out_values: *mut *const f64,
+    out_len: *mut usize,
+) -> AgnesStatus {
+    column_buffer(store, name, out_values, out_len, |column| match *column {
+        ColumnData::F64(ref data) => Some(data),
+        _ => None,
+    })
+}
+
+/// Writes a pointer to the buffer of `int64_t` values of the column named `name` to
+/// `out_values` (see [agnes_store_column_f64](fn.agnes_store_column_f64.html)).
+///
+/// # Safety
+/// As for [agnes_store_column_f64](fn.agnes_store_column_f64.html).
+#[no_mangle]
+pub unsafe extern "C" fn agnes_store_column_i64(
+    store: *const AgnesStore,
+    name: *const c_char,
+    out_values: *mut *const i64,
+    out_len: *mut usize,
+) -> AgnesStatus {
+    column_buffer(store, name, out_values, out_len, |column| match *column {
+        ColumnData::I64(ref data) => Some(data),
+        _ => None,
+    })
+}
+
+/// Writes the validity of each value of the column named `name` to the `len` bytes at
+/// `out_valid` (`1` if the value exists, `0` if it is missing). `len` must be the number of rows
+/// in the store.
+///
+/// # Safety
+/// `store` must be a valid store, `name` a NUL-terminated string, and `out_valid` must be valid
+/// for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn agnes_store_column_valid(
+    store: *const AgnesStore,
+    name: *const c_char,
+    out_valid: *mut u8,
+    len: usize,
+) -> AgnesStatus {
+    run(|| {
+        let column = non_null(store, "store")?.column(name_str(name)?)?;
+        if len != column.len() {
+            return Err(Failure::new(
+                AgnesStatus::LengthMismatch,
+                format!("expected {} values, found {}", column.len(), len),
+            ));
+        }
+        if len == 0 {
+            return Ok(());
+        }
+        if out_valid.is_null() {
+            return Err(Failure::new(
+                AgnesStatus::NullPointer,
+                "output pointer is null",
+            ));
+        }
+        let out_valid = slice::from_raw_parts_mut(out_valid, len);
+        let mask = match *column {
+            ColumnData::F64(ref data) => data.na_mask(),
+            ColumnData::I64(ref data) => data.na_mask(),
+        };
+        for (idx, out) in out_valid.iter_mut().enumerate() {
+            *out = (mask.exists(idx) == Some(true)) as u8;
+        }
+        Ok(())
+    })
+}
+
+/// Creates a new store containing the rows of `store` whose values in the column named `name`
+/// compare to `operand` as specified by `compare` (with integer values compared as `double`s).
+/// Rows with missing values are excluded. The new store is written to `out_store`; it is owned by
+/// the caller, and must be released with [agnes_store_free](fn.agnes_store_free.html).
+///
+/// # Safety
+/// `store` must be a valid store, `name` a NUL-terminated string, and `out_store` valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn agnes_store_filter(
+    store: *const AgnesStore,
+    name: *const c_char,
+    compare: AgnesCompare,
+    operand: f64,
+    out_store: *mut *mut AgnesStore,
+) -> AgnesStatus {
+    run(|| {
+        let store = non_null(store, "store")?;
+        let column = store.column(name_str(name)?)?;
+        let out_store = non_null_mut(out_store, "output pointer")?;
+        let indices = match *column {
+            ColumnData::F64(ref data) => data.filter_perm(|value: Value<&f64>| match value {
+                Value::Exists(&value) => compare.test(value, operand),
+                Value::Na => false,
+            }),
+            ColumnData::I64(ref data) => data.filter_perm(|value: Value<&i64>| match value {
+                Value::Exists(&value) => compare.test(value as f64, operand),
+                Value::Na => false,
+            }),
+        };
+        let filtered = AgnesStore {
+            columns: store
+                .columns
+                .iter()
+                .map(|(name, data)| (name.clone(), data.permute(&indices)))
+                .collect(),
+        };
+        *out_store = Box::into_raw(Box::new(filtered));
+        Ok(())
+    })
+}
+
+fn aggregate_field<T>(data: &FieldData<T>, aggregate: AgnesAggregate) -> Option<f64>
+where
+    FieldData<T>: Sum<Output = T> + Mean + Extrema<Output = T> + NaCount,
+    T: ::num_traits::AsPrimitive<f64>,
+{
+    match aggregate {
+        AgnesAggregate::Count => Some(data.num_exists() as f64),
+        AgnesAggregate::Sum => Some(data.sum().as_()),
+        AgnesAggregate::Mean => Some(data.mean()),
+        AgnesAggregate::Min => data.min().map(|value| value.as_()),
+        AgnesAggregate::Max => data.max().map(|value| value.as_()),
+    }
+}
+
+/// Computes the aggregate `aggregate` of the values of the column named `name` of `store`, and
+/// writes it to `out_value`. Fails with `AgnesStatus::Empty` for the minimum or maximum of a
+/// column with no existing values.
+///
+/// # Safety
+/// `store` must be a valid store, `name` a NUL-terminated string, and `out_value` valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn agnes_store_aggregate(
+    store: *const AgnesStore,
+    name: *const c_char,
+    aggregate: AgnesAggregate,
+    out_value: *mut f64,
+) -> AgnesStatus {
+    run(|| {
+        let column = non_null(store, "store")?.column(name_str(name)?)?;
+        let out_value = non_null_mut(out_value, "output pointer")?;
+        let value = match *column {
+            ColumnData::F64(ref data) => aggregate_field(data, aggregate),
+            ColumnData::I64(ref data) => aggregate_field(data, aggregate),
+        };
+        *out_value = value
+            .ok_or_else(|| Failure::new(AgnesStatus::Empty, "column has no existing values"))?;
+        Ok(())
+    })
+}
+
+// Loads the integer and floating-point columns (as sniffed) of the CSV data in `bytes` into a new
+// store.
+fn load_csv(bytes: &[u8]) -> error::Result<AgnesStore> {
+    let src = CsvSource::from_bytes(bytes)?;
+    let empty_as_na = src.empty_as_na();
+    let mut columns = src
+        .metadata()
+        .types
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, &ty)| match ty {
+            Type::Unsigned | Type::Signed => Some((idx, ty, ColumnData::I64(FieldData::default()))),
+            Type::Float => Some((idx, ty, ColumnData::F64(FieldData::default()))),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let names = src.scan_records(None, |record_idx, record| {
+        for &mut (idx, ty, ref mut data) in &mut columns {
+            let text = decode(record.get(idx).unwrap_or(b""))?;
+            match *data {
+                ColumnData::F64(ref mut field) => field.push_val(parse_value(&text, empty_as_na)?),
+                // unsigned values too large for an `int64_t` are reported as overflowing
+                ColumnData::I64(ref mut field) if ty == Type::Unsigned => {
+                    let value = parse_value::<u64>(&text, empty_as_na)?;
+                    field.push_val(match value {
+                        Value::Exists(value) => Value::Exists(
+                            i64::try_from(value)
+                                .map_err(|_| AgnesError::Overflow { index: record_idx })?,
+                        ),
+                        Value::Na => Value::Na,
+                    })
+                }
+                ColumnData::I64(ref mut field) => field.push_val(parse_value(&text, empty_as_na)?),
+            }
+        }
+        Ok(())
+    })?;
+    Ok(AgnesStore {
+        columns: columns
+            .into_iter()
+            .map(|(idx, _, data)| (names[idx].clone(), data))
+            .collect(),
+    })
+}
+
+/// Creates a new store from the `len` bytes of CSV data at `data`, and writes it to `out_store`.
+/// The CSV dialect and column types are sniffed from the data; columns with integer values are
+/// loaded as `int64_t` columns, columns with floating-point values as `double` columns, and other
+/// columns are skipped. Empty values are loaded as missing values. Columns are named by the header
+/// row, or by their (zero-based) index if the data has no header row. The new store is owned by
+/// the caller, and must be released with [agnes_store_free](fn.agnes_store_free.html).
+///
+/// Fails with `AgnesStatus::InvalidArgument` if the data cannot be analyzed or parsed, if a
+/// record does not have the sniffed number of fields, or if an unsigned integer value is too
+/// large for an `int64_t`.
+///
+/// # Safety
+/// `data` must point to at least `len` bytes, and `out_store` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn agnes_store_from_csv(
+    data: *const u8,
+    len: usize,
+    out_store: *mut *mut AgnesStore,
+) -> AgnesStatus {
+    run(|| {
+        let data = array(data, len, "CSV data")?;
+        let out_store = non_null_mut(out_store, "output pointer")?;
+        let store = load_csv(data)?;
+        *out_store = Box::into_raw(Box::new(store));
+        Ok(())
+    })
+}
+
+fn json_values<T, F>(data: &FieldData<T>, to_json: F) -> serde_json::Value
+where
+    T: ::std::fmt::Debug,
+    F: Fn(&T) -> serde_json::Value,
+{
+    serde_json::Value::Array(
+        data.iter()
+            .map(|value| match value {
+                Value::Exists(value) => to_json(value),
+                Value::Na => serde_json::Value::Null,
+            })
+            .collect(),
+    )
+}
+
+// Formats `store` as a JSON object mapping column names (in column order) to arrays of values.
+fn store_json(store: &AgnesStore) -> String {
+    let mut json = String::from("{");
+    for (idx, (name, data)) in store.columns.iter().enumerate() {
+        if idx > 0 {
+            json.push(',');
+        }
+        json.push_str(&serde_json::Value::from(name.as_str()).to_string());
+        json.push(':');
+        let values = match *data {
+            ColumnData::F64(ref field) => json_values(field, |&value| value.into()),
+            ColumnData::I64(ref field) => json_values(field, |&value| value.into()),
+        };
+        json.push_str(&values.to_string());
+    }
+    json.push('}');
+    json
+}
+
+/// Formats `store` as a JSON object mapping each column name (in column order) to an array of the
+/// column's values, with missing (and non-finite) values as `null`, and writes the resulting
+/// NUL-terminated string to `out_json`. The string is owned by the caller, and must be released
+/// with [agnes_string_free](fn.agnes_string_free.html).
+///
+/// # Safety
+/// `store` must be a valid store, and `out_json` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn agnes_store_to_json(
+    store: *const AgnesStore,
+    out_json: *mut *mut c_char,
+) -> AgnesStatus {
+    run(|| {
+        let store = non_null(store, "store")?;
+        let out_json = non_null_mut(out_json, "output pointer")?;
+        let json = CString::new(store_json(store))
+            .map_err(|_| Failure::new(AgnesStatus::InvalidArgument, "column name contains NUL"))?;
+        *out_json = json.into_raw();
+        Ok(())
+    })
+}
+
+/// Releases a string returned by [agnes_store_to_json](fn.agnes_store_to_json.html). Does nothing
+/// if `string` is null.
+///
+/// # Safety
+/// `string` must be null or a string returned by this library which has not yet been freed.
+#[no_mangle]
+pub unsafe extern "C" fn agnes_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Allocates a zeroed buffer of `len` bytes, which must be released with
+/// [agnes_dealloc](fn.agnes_dealloc.html). This allows callers which cannot otherwise allocate in
+/// the library's memory (such as JavaScript code calling into a WebAssembly build) to pass data into
+/// the library.
+#[no_mangle]
+pub extern "C" fn agnes_alloc(len: usize) -> *mut u8 {
+    Box::into_raw(vec![0u8; len].into_boxed_slice()) as *mut u8
+}
+
+/// Releases a buffer of `len` bytes allocated with [agnes_alloc](fn.agnes_alloc.html). Does
+/// nothing if `buffer` is null.
+///
+/// # Safety
+/// `buffer` must be null or a buffer returned by [agnes_alloc](fn.agnes_alloc.html) which has not
+/// yet been released, and `len` must be the length it was allocated with.
+#[no_mangle]
+pub unsafe extern "C" fn agnes_dealloc(buffer: *mut u8, len: usize) {
+    if !buffer.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(buffer, len)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    #[test]
+    fn store_round_trip() {
+        unsafe {
+            let store = agnes_store_new();
+            let (price, qty) = (name("price"), name("qty"));
+            let prices = [1.5f64, 2.0, 4.5, 3.0];
+            let valid = [1u8, 1, 0, 1];
+            assert_eq!(
+                agnes_store_add_f64(store, price.as_ptr(), prices.as_ptr(), valid.as_ptr(), 4),
+                AgnesStatus::Ok
+            );
+            let qtys = [3i64, 1, 7, 2];
+            assert_eq!(
+                agnes_store_add_i64(store, qty.as_ptr(), qtys.as_ptr(), ptr::null(), 4),
+                AgnesStatus::Ok
+            );
+            let (mut nrows, mut ncols) = (0, 0);
+            assert_eq!(
+                agnes_store_shape(store, &mut nrows, &mut ncols),
+                AgnesStatus::Ok
+            );
+            assert_eq!((nrows, ncols), (4, 2));
+
+            // column buffers hold a placeholder for missing values
+            let mut values: *const f64 = ptr::null();
+            let mut len = 0;
+            assert_eq!(
+                agnes_store_column_f64(store, price.as_ptr(), &mut values, &mut len),
+                AgnesStatus::Ok
+            );
+            assert_eq!(slice::from_raw_parts(values, len), &[1.5, 2.0, 0.0, 3.0]);
+            let mut out_valid = [9u8; 4];
+            assert_eq!(
+                agnes_store_column_valid(store, price.as_ptr(), out_valid.as_mut_ptr(), 4),
+                AgnesStatus::Ok
+            );
+            assert_eq!(out_valid, valid);
+            let mut ty = AgnesType::F64;
+            assert_eq!(
+                agnes_store_column_type(store, qty.as_ptr(), &mut ty),
+                AgnesStatus::Ok
+            );
+            assert_eq!(ty, AgnesType::I64);
+
+            let mut total = 0.0;
+            assert_eq!(
+                agnes_store_aggregate(store, price.as_ptr(), AgnesAggregate::Sum, &mut total),
+                AgnesStatus::Ok
+            );
+            assert_eq!(total, 6.5);
+            assert_eq!(
+                agnes_store_aggregate(store, qty.as_ptr(), AgnesAggregate::Max, &mut total),
+                AgnesStatus::Ok
+            );
+            assert_eq!(total, 7.0);
+
+            // filtering creates a new, separately-owned store
+            let mut filtered = ptr::null_mut();
+            assert_eq!(
+                agnes_store_filter(store, qty.as_ptr(), AgnesCompare::Ge, 2.0, &mut filtered),
+                AgnesStatus::Ok
+            );
+            agnes_store_free(store);
+            let mut ints: *const i64 = ptr::null();
+            assert_eq!(
+                agnes_store_column_i64(filtered, qty.as_ptr(), &mut ints, &mut len),
+                AgnesStatus::Ok
+            );
+            assert_eq!(slice::from_raw_parts(ints, len), &[3, 7, 2]);
+            assert_eq!(
+                agnes_store_aggregate(filtered, price.as_ptr(), AgnesAggregate::Count, &mut total),
+                AgnesStatus::Ok
+            );
+            assert_eq!(total, 2.0);
+            agnes_store_free(filtered);
+        }
+    }
+
+    #[test]
+    fn store_errors() {
+        unsafe {
+            let store = agnes_store_new();
+            let col = name("x");
+            let values = [1.0f64, 2.0];
+            assert_eq!(
+                agnes_store_add_f64(store, col.as_ptr(), values.as_ptr(), ptr::null(), 2),
+                AgnesStatus::Ok
+            );
+            assert_eq!(
+                agnes_store_add_f64(store, col.as_ptr(), values.as_ptr(), ptr::null(), 2),
+                AgnesStatus::InvalidArgument
+            );
+            let other = name("y");
+            assert_eq!(
+                agnes_store_add_f64(store, other.as_ptr(), values.as_ptr(), ptr::null(), 1),
+                AgnesStatus::LengthMismatch
+            );
+            let mut ints: *const i64 = ptr::null();
+            let mut len = 0;
+            assert_eq!(
+                agnes_store_column_i64(store, col.as_ptr(), &mut ints, &mut len),
+                AgnesStatus::TypeMismatch
+            );
+            assert_eq!(
+                agnes_store_column_i64(store, other.as_ptr(), &mut ints, &mut len),
+                AgnesStatus::NotFound
+            );
+            let message = CStr::from_ptr(agnes_last_error_message());
+            assert_eq!(message.to_str().unwrap(), "no column 'y'");
+            assert_eq!(
+                agnes_store_shape(ptr::null(), &mut len, &mut len),
+                AgnesStatus::NullPointer
+            );
+
+            let empty = agnes_store_new();
+            let values: [f64; 0] = [];
+            assert_eq!(
+                agnes_store_add_f64(empty, col.as_ptr(), values.as_ptr(), ptr::null(), 0),
+                AgnesStatus::Ok
+            );
+            let mut out = 0.0;
+            assert_eq!(
+                agnes_store_aggregate(empty, col.as_ptr(), AgnesAggregate::Min, &mut out),
+                AgnesStatus::Empty
+            );
+            agnes_store_free(empty);
+            agnes_store_free(store);
+        }
+    }
+
+    #[test]
+    fn csv_json() {
+        unsafe {
+            let csv =
+                "state,year,pop,area\nOH,2000,11.4,116.1\nPA,2000,12.3,\nOH,2010,11.5,116.1\n\
+                       NY,2010,19.4,141.3\n";
+            let buffer = agnes_alloc(csv.len());
+            slice::from_raw_parts_mut(buffer, csv.len()).copy_from_slice(csv.as_bytes());
+            let mut store = ptr::null_mut();
+            assert_eq!(
+                agnes_store_from_csv(buffer, csv.len(), &mut store),
+                AgnesStatus::Ok
+            );
+            agnes_dealloc(buffer, csv.len());
+
+            // the text column is skipped
+            let (mut nrows, mut ncols) = (0, 0);
+            assert_eq!(
+                agnes_store_shape(store, &mut nrows, &mut ncols),
+                AgnesStatus::Ok
+            );
+            assert_eq!((nrows, ncols), (4, 3));
+            let mut ty = AgnesType::F64;
+            let year = name("year");
+            assert_eq!(
+                agnes_store_column_type(store, year.as_ptr(), &mut ty),
+                AgnesStatus::Ok
+            );
+            assert_eq!(ty, AgnesType::I64);
+
+            let mut filtered = ptr::null_mut();
+            assert_eq!(
+                agnes_store_filter(
+                    store,
+                    year.as_ptr(),
+                    AgnesCompare::Eq,
+                    2000.0,
+                    &mut filtered
+                ),
+                AgnesStatus::Ok
+            );
+            let mut json = ptr::null_mut();
+            assert_eq!(agnes_store_to_json(filtered, &mut json), AgnesStatus::Ok);
+            assert_eq!(
+                CStr::from_ptr(json).to_str().unwrap(),
+                r#"{"year":[2000,2000],"pop":[11.4,12.3],"area":[116.1,null]}"#
+            );
+            agnes_string_free(json);
+            agnes_store_free(filtered);
+            agnes_store_free(store);
+
+            assert_eq!(
+                agnes_store_from_csv(ptr::null(), 3, &mut store),
+                AgnesStatus::NullPointer
+            );
+
+            // unsigned values which do not fit in an `int64_t` are rejected
+            let csv = "id,count\n1,4\n2,18446744073709551615\n3,7\n4,2\n";
+            assert_eq!(
+                agnes_store_from_csv(csv.as_ptr(), csv.len(), &mut store),
+                AgnesStatus::InvalidArgument
+            );
+            let message = CStr::from_ptr(agnes_last_error_message());
+            assert!(message.to_str().unwrap().contains("overflow"));
+        }
+    }
+}
@@ -0,0 +1,238 @@
+/*!
+Conditional construction of derived fields (as with `CASE WHEN` in SQL).
+
+The [DataView::when](../view/struct.DataView.html#method.when) method starts a chain of
+conditions over the fields of a `DataView`, each followed by the value the derived field takes for
+the records which match it. Each condition is a predicate over the values of a single field (such
+as those in the [filter](../filter/index.html) module), and conditions may refer to different
+fields. Each record takes the value of the first condition it matches, and
+[otherwise](struct.Case.html#method.otherwise) supplies the value for records which match none of
+them, producing the new field.
+
+```
+# #[macro_use] extern crate agnes;
+use agnes::access::DataIndex;
+use agnes::cons::Nil;
+use agnes::field::FieldData;
+use agnes::filter::between;
+use agnes::store::{DataStore, PushBackField};
+use agnes::value::Value;
+
+tablespace![
+    table emps {
+        Salary: u64,
+        Active: bool,
+    }
+];
+
+fn main() {
+    let dv = DataStore::<Nil>::empty()
+        .push_back_field::<emps::Salary, _>(FieldData::<u64>::from_vec(vec![
+            500u64, 1500, 3000, 800,
+        ]))
+        .push_back_field::<emps::Active, _>(FieldData::<bool>::from_vec(vec![
+            true, true, true, false,
+        ]))
+        .into_view();
+
+    let bands = dv
+        .when::<emps::Active, _>(|active| active == Value::Exists(&false))
+        .then("inactive")
+        .when::<emps::Salary, _>(between(0, 1000))
+        .then("low")
+        .when::<emps::Salary, _>(between(1000, 2000))
+        .then("medium")
+        .otherwise("high");
+    assert_eq!(bands.to_vec(), vec!["low", "medium", "high", "inactive"]);
+}
+```
+*/
+use std::fmt::Debug;
+
+use access::DataIndex;
+use field::FieldData;
+use select::SelectFieldByLabel;
+use value::Value;
+use view::{DataView, VFieldTypeOf};
+
+// Evaluates `predicate` over the values of the field labeled `Label` of `view`.
+fn condition<View, Label, P>(view: &View, predicate: P) -> Vec<bool>
+where
+    View: SelectFieldByLabel<Label>,
+    P: FnMut(Value<&VFieldTypeOf<View, Label>>) -> bool,
+{
+    SelectFieldByLabel::<Label>::select_field(view)
+        .iter()
+        .map(predicate)
+        .collect()
+}
+
+/// The first condition of a conditional field construction, awaiting the value for the records
+/// which match it. Created by [DataView::when](../view/struct.DataView.html#method.when).
+#[derive(Debug)]
+pub struct When<'a, View: 'a> {
+    view: &'a View,
+    condition: Vec<bool>,
+}
+
+impl<'a, View> When<'a, View> {
+    /// Sets the value of the derived field for the records which match this condition.
+    pub fn then<T>(self, value: T) -> Case<'a, View, T>
+    where
+        T: Clone,
+    {
+        let values = self
+            .condition
+            .iter()
+            .map(|&matched| {
+                if matched {
+                    Some(Value::Exists(value.clone()))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        Case {
+            view: self.view,
+            values,
+        }
+    }
+}
+
+/// A conditional field construction with values of type `T` assigned for one or more conditions.
+/// Further conditions can be added with [when](#method.when), and the construction is completed
+/// with [otherwise](#method.otherwise) or [otherwise_na](#method.otherwise_na).
+#[derive(Debug)]
+pub struct Case<'a, View: 'a, T> {
+    view: &'a View,
+    // value of each record, if it has matched a condition
+    values: Vec<Option<Value<T>>>,
+}
+
+impl<'a, View, T> Case<'a, View, T> {
+    /// Adds a condition over the values of the field labeled `Label`. Records which already matched
+    /// an earlier condition are unaffected.
+    pub fn when<Label, P>(self, predicate: P) -> CaseWhen<'a, View, T>
+    where
+        View: SelectFieldByLabel<Label>,
+        P: FnMut(Value<&VFieldTypeOf<View, Label>>) -> bool,
+    {
+        let condition = condition::<View, Label, P>(self.view, predicate);
+        CaseWhen {
+            case: self,
+            condition,
+        }
+    }
+
+    /// Completes the construction, producing a new field in which the records which did not match
+    /// any condition take the value `value`.
+    pub fn otherwise(self, value: T) -> FieldData<T>
+    where
+        T: Clone + Debug + Default,
+    {
+        self.finish(Value::Exists(value))
+    }
+
+    /// Completes the construction, producing a new field in which the records which did not match
+    /// any condition are missing (NA).
+    pub fn otherwise_na(self) -> FieldData<T>
+    where
+        T: Clone + Debug + Default,
+    {
+        self.finish(Value::Na)
+    }
+
+    fn finish(self, default: Value<T>) -> FieldData<T>
+    where
+        T: Clone + Debug + Default,
+    {
+        FieldData::from_field_vec(
+            self.values
+                .into_iter()
+                .map(|value| value.unwrap_or_else(|| default.clone()))
+                .collect(),
+        )
+    }
+}
+
+/// A conditional field construction awaiting the value for the records which match its latest
+/// condition. Created by [Case::when](struct.Case.html#method.when).
+#[derive(Debug)]
+pub struct CaseWhen<'a, View: 'a, T> {
+    case: Case<'a, View, T>,
+    condition: Vec<bool>,
+}
+
+impl<'a, View, T> CaseWhen<'a, View, T> {
+    /// Sets the value of the derived field for the records which match this condition (and did
+    /// not match an earlier condition).
+    pub fn then(mut self, value: T) -> Case<'a, View, T>
+    where
+        T: Clone,
+    {
+        for (assigned, &matched) in self.case.values.iter_mut().zip(&self.condition) {
+            if matched && assigned.is_none() {
+                *assigned = Some(Value::Exists(value.clone()));
+            }
+        }
+        self.case
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Starts the conditional construction of a new field with a condition `predicate` over the
+    /// values of the field labeled `Label`. See the [case](../case/index.html) module for details.
+    pub fn when<Label, P>(&self, predicate: P) -> When<'_, Self>
+    where
+        Self: SelectFieldByLabel<Label>,
+        P: FnMut(Value<&VFieldTypeOf<Self, Label>>) -> bool,
+    {
+        When {
+            view: self,
+            condition: condition::<Self, Label, P>(self, predicate),
+        }
+    }
+}
+
+#[cfg(feature = "test-utils")]
+#[cfg(test)]
+mod tests {
+    use access::DataIndex;
+    use filter::is_in;
+    use test_utils::*;
+    use value::Value;
+
+    #[test]
+    fn when() {
+        let dv = sample_merged_emp_table();
+        let groups = dv
+            .when::<emp_table::DeptId, _>(is_in(&[1]))
+            .then(1u8)
+            .when::<extra_emp::DidTraining, _>(|trained| trained == Value::Exists(&true))
+            .then(2)
+            .when::<emp_table::DeptId, _>(is_in(&[1, 4]))
+            .then(3)
+            .otherwise_na();
+        // department 1 is assigned before training is considered
+        assert_eq!(
+            groups.to_value_vec(),
+            vec![
+                Value::Exists(1u8),
+                Value::Na,
+                Value::Exists(1),
+                Value::Exists(1),
+                Value::Exists(2),
+                Value::Exists(3),
+                Value::Exists(2),
+            ]
+        );
+
+        let names = dv
+            .when::<emp_table::EmpId, _>(|id| id == Value::Exists(&0))
+            .then("first".to_string())
+            .otherwise("other".to_string());
+        assert_eq!(names.len(), 7);
+        assert_eq!(names.get(0), Some(Value::Exists(&"first".to_string())));
+        assert_eq!(names.get(6), Some(Value::Exists(&"other".to_string())));
+    }
+}
@@ -0,0 +1,199 @@
+/*!
+Casting of numeric fields between data types with explicit casting rules.
+
+The [Cast](trait.Cast.html) trait (and the
+[DataView::cast](../view/struct.DataView.html#method.cast) method) convert the values of a numeric
+field into a different numeric type, producing a new field. How values which cannot be represented
+exactly in the target type are handled is controlled by a [CastMode](enum.CastMode.html):
+
+* `CastMode::Lossless` -- fail with `AgnesError::Cast` if any value would change (overflow, or loss
+  of a fractional part or precision). NaN values are kept when casting between floating-point
+  types.
+* `CastMode::Lossy` -- truncate fractional parts and replace values outside of the range of the
+  target type (or NaN) with missing (NA) values.
+* `CastMode::Saturating` -- truncate fractional parts and clamp values outside of the range of the
+  target type to its bounds. NaN values become missing (NA) values.
+*/
+use std::fmt::Debug;
+
+use num_traits::{Bounded, NumCast};
+
+use access::DataIndex;
+use error;
+use field::FieldData;
+use select::{FieldSelect, SelectFieldByLabel};
+use value::Value;
+use view::{DataView, VFieldTypeOf};
+
+/// Rules for handling values which cannot be exactly represented in the target type of a cast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastMode {
+    /// Fail if any value cannot be exactly represented in the target type.
+    Lossless,
+    /// Truncate fractional parts, and replace values outside of the range of the target type with
+    /// missing (NA) values.
+    Lossy,
+    /// Truncate fractional parts, and clamp values outside of the range of the target type to its
+    /// bounds.
+    Saturating,
+}
+
+/// Casts a single value `value` into type `Dst` using casting rules `mode`. Returns `None` if the
+/// cast fails under `CastMode::Lossless`, and `Some(Value::Na)` for values which become missing.
+pub fn cast_value<Src, Dst>(value: Src, mode: CastMode) -> Option<Value<Dst>>
+where
+    Src: NumCast + PartialEq + Copy,
+    Dst: NumCast + Bounded + Copy,
+{
+    let cast: Option<Dst> = NumCast::from(value);
+    match mode {
+        // NaN values are preserved by float-to-float casts, despite comparing unequal to themselves
+        CastMode::Lossless => cast
+            .filter(|cast| {
+                <Src as NumCast>::from(*cast)
+                    .is_some_and(|back| back == value || (is_nan(back) && is_nan(value)))
+            })
+            .map(Value::Exists),
+        CastMode::Lossy => Some(cast.into()),
+        CastMode::Saturating => Some(match cast {
+            Some(cast) => Value::Exists(cast),
+            None => match value.to_f64() {
+                Some(value) if value.is_nan() => Value::Na,
+                Some(value) if value < 0.0 => Value::Exists(Dst::min_value()),
+                _ => Value::Exists(Dst::max_value()),
+            },
+        }),
+    }
+}
+
+fn is_nan<T: NumCast>(value: T) -> bool {
+    value.to_f64().is_some_and(f64::is_nan)
+}
+
+/// A trait for casting the values of a numeric field into a different numeric type.
+pub trait Cast {
+    /// Returns a new field with the values of this field cast into type `Dst` using the casting
+    /// rules `mode`. Missing (NA) values remain missing.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cast` if `mode` is `CastMode::Lossless` and a value cannot be
+    /// exactly represented in type `Dst`.
+    fn cast<Dst>(&self, mode: CastMode) -> error::Result<FieldData<Dst>>
+    where
+        Dst: NumCast + Bounded + Copy + Debug + Default;
+}
+
+impl<DI> Cast for DI
+where
+    DI: DataIndex,
+    DI::DType: NumCast + PartialEq + Copy,
+{
+    fn cast<Dst>(&self, mode: CastMode) -> error::Result<FieldData<Dst>>
+    where
+        Dst: NumCast + Bounded + Copy + Debug + Default,
+    {
+        self.iter()
+            .enumerate()
+            .map(|(index, value)| match value {
+                Value::Exists(&value) => {
+                    cast_value::<_, Dst>(value, mode).ok_or(error::AgnesError::Cast { index })
+                }
+                Value::Na => Ok(Value::Na),
+            })
+            .collect()
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Returns a new field with the values of the field labeled `SrcLabel` cast into type
+    /// `DstType` using the casting rules `mode`. See the [cast](../cast/index.html) module for
+    /// details on the casting rules.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cast` if `mode` is `CastMode::Lossless` and a value cannot be
+    /// exactly represented in type `DstType`.
+    pub fn cast<SrcLabel, DstType>(&self, mode: CastMode) -> error::Result<FieldData<DstType>>
+    where
+        Self: SelectFieldByLabel<SrcLabel>,
+        VFieldTypeOf<Self, SrcLabel>: NumCast + PartialEq + Copy,
+        DstType: NumCast + Bounded + Copy + Debug + Default,
+    {
+        self.field::<SrcLabel>().cast(mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use error::AgnesError;
+
+    #[test]
+    fn lossless() {
+        let field = FieldData::from_field_vec(vec![Value::Exists(3.0f64), Value::Na]);
+        assert_eq!(
+            field.cast::<u8>(CastMode::Lossless).unwrap().to_value_vec(),
+            vec![Value::Exists(3u8), Value::Na]
+        );
+
+        let field = FieldData::<f64>::from_vec(vec![3.0, 2.5]);
+        match field.cast::<i64>(CastMode::Lossless) {
+            Err(AgnesError::Cast { index }) => assert_eq!(index, 1),
+            _ => panic!("expected cast error"),
+        }
+        let field = FieldData::<i64>::from_vec(vec![-1]);
+        assert!(field.cast::<u64>(CastMode::Lossless).is_err());
+        // precision loss
+        let field = FieldData::<u64>::from_vec(vec![(1u64 << 53) + 1]);
+        assert!(field.cast::<f64>(CastMode::Lossless).is_err());
+        // NaN values are preserved between float types, but cannot be represented as integers
+        let field = FieldData::<f64>::from_vec(vec![1.5, f64::NAN]);
+        let cast = field.cast::<f32>(CastMode::Lossless).unwrap().to_vec();
+        assert_eq!(cast[0], 1.5);
+        assert!(cast[1].is_nan());
+        assert!(field.cast::<i32>(CastMode::Lossless).is_err());
+    }
+
+    #[test]
+    fn lossy() {
+        let field = FieldData::<f64>::from_vec(vec![2.7, -1.0, 300.0, f64::NAN]);
+        assert_eq!(
+            field.cast::<u8>(CastMode::Lossy).unwrap().to_value_vec(),
+            vec![Value::Exists(2u8), Value::Na, Value::Na, Value::Na]
+        );
+    }
+
+    #[test]
+    fn saturating() {
+        let field = FieldData::<f64>::from_vec(vec![2.7, -1.0, 300.0, f64::NAN]);
+        assert_eq!(
+            field
+                .cast::<u8>(CastMode::Saturating)
+                .unwrap()
+                .to_value_vec(),
+            vec![
+                Value::Exists(2u8),
+                Value::Exists(0),
+                Value::Exists(255),
+                Value::Na
+            ]
+        );
+        let field = FieldData::<i64>::from_vec(vec![-70000, 70000, 5]);
+        assert_eq!(
+            field.cast::<i16>(CastMode::Saturating).unwrap().to_vec(),
+            vec![i16::MIN, i16::MAX, 5]
+        );
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn view_cast() {
+        use test_utils::*;
+
+        let dv = sample_emp_table().into_view();
+        let ids = dv
+            .cast::<emp_table::EmpId, f32>(CastMode::Lossless)
+            .unwrap();
+        assert_eq!(ids.to_vec()[..3], [0.0f32, 2.0, 5.0]);
+    }
+}
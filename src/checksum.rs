@@ -0,0 +1,177 @@
+/*!
+Content hashes of `DataView`s, for detecting whether data has changed without a full comparison.
+
+[DataView::content_hash](../view/struct.DataView.html#method.content_hash) hashes the field names
+and the values of every record in order, so that any change to the values, their order, or the
+set of fields changes the hash.
+[DataView::content_hash_unordered](../view/struct.DataView.html#method.content_hash_unordered)
+combines the hashes of the individual records independently of their order, so that views
+containing the same records in different orders (e.g. after a sort) have the same hash.
+
+Values are hashed with the same per-record hashing
+([KeyIndex](../erased/trait.KeyIndex.html)) used to build hash indexes for
+[unique_indices](../view/struct.DataView.html#method.unique_indices) and joins, with
+floating-point values (which are not `Hash`) hashed by their bit patterns. Hashes are computed
+with the standard library's `DefaultHasher` (created with `DefaultHasher::new`), so they are
+stable between runs of the same program, but may change between versions of Rust. Fields of types
+other than `String`, `&str`, `ArenaStr`, `f64`, `f32`, `u64`, `u32`, `i64`, `i32`, `bool`, and
+`FloatKey` do not contribute to the hash.
+*/
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use access::{DataIndex, NRows};
+use erased::KeyIndex;
+use field::ArenaStr;
+use key::FloatKey;
+use label::StrLabels;
+use partial::{Func, FuncDefault, Implemented, IsImplemented};
+use view::{DataView, FieldMap};
+
+/// Function (implementing [Func](../partial/trait.Func.html)) which feeds the values of every
+/// field of a `DataView` into a separate hasher for each record, for use by
+/// [DataView::content_hash](../view/struct.DataView.html#method.content_hash).
+#[derive(Debug)]
+pub struct HashRecordsFn {
+    hashers: Vec<DefaultHasher>,
+}
+impl HashRecordsFn {
+    fn hash_records<DI>(&mut self, data: &DI)
+    where
+        DI: KeyIndex,
+    {
+        for (idx, hasher) in self.hashers.iter_mut().enumerate() {
+            data.hash_at(idx, hasher);
+        }
+    }
+}
+macro_rules! impl_hash_records_fn {
+    ($($dtype:ty)*) => {$(
+        impl Func<$dtype> for HashRecordsFn {
+            type Output = ();
+            fn call<DI>(&mut self, data: &DI) -> Self::Output
+            where
+                DI: DataIndex<DType = $dtype>,
+            {
+                self.hash_records(data);
+            }
+        }
+    )*}
+}
+impl_hash_records_fn![String ArenaStr u64 u32 i64 i32 bool FloatKey];
+
+impl<'a> Func<&'a str> for HashRecordsFn {
+    type Output = ();
+    fn call<DI>(&mut self, data: &DI) -> Self::Output
+    where
+        DI: DataIndex<DType = &'a str>,
+    {
+        self.hash_records(data);
+    }
+}
+
+// floating-point values are not `Hash`, so are hashed by their bit patterns
+macro_rules! impl_hash_records_fn_float {
+    ($($dtype:ty)*) => {$(
+        impl Func<$dtype> for HashRecordsFn {
+            type Output = ();
+            fn call<DI>(&mut self, data: &DI) -> Self::Output
+            where
+                DI: DataIndex<DType = $dtype>,
+            {
+                for (hasher, value) in self.hashers.iter_mut().zip(data.iter()) {
+                    value.map(|value| value.to_bits()).hash(hasher);
+                }
+            }
+        }
+    )*}
+}
+impl_hash_records_fn_float![f64 f32];
+
+impl FuncDefault for HashRecordsFn {
+    type Output = ();
+    fn call(&mut self) -> Self::Output {}
+}
+macro_rules! impl_hash_records_is_impl {
+    ($($dtype:ty)*) => {$(
+        impl IsImplemented<HashRecordsFn> for $dtype {
+            type IsImpl = Implemented;
+        }
+    )*}
+}
+impl_hash_records_is_impl![String &str ArenaStr f64 f32 u64 u32 i64 i32 bool FloatKey];
+
+impl<Labels, Frames> DataView<Labels, Frames>
+where
+    Self: FieldMap<HashRecordsFn> + NRows,
+    Labels: StrLabels,
+{
+    fn record_hashes(&self) -> (DefaultHasher, Vec<u64>) {
+        let mut func = HashRecordsFn {
+            hashers: (0..self.nrows()).map(|_| DefaultHasher::new()).collect(),
+        };
+        self.field_map(&mut func);
+        let mut hasher = DefaultHasher::new();
+        <Labels as StrLabels>::labels_vec().hash(&mut hasher);
+        (
+            hasher,
+            func.hashers.iter().map(|hasher| hasher.finish()).collect(),
+        )
+    }
+
+    /// Returns a hash of the field names and values of this `DataView`, which depends on the
+    /// order of the records. See the [checksum](../checksum/index.html) module for more details.
+    pub fn content_hash(&self) -> u64 {
+        let (mut hasher, records) = self.record_hashes();
+        records.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns a hash of the field names and values of this `DataView`, which does not depend on
+    /// the order of the records. See the [checksum](../checksum/index.html) module for more
+    /// details.
+    pub fn content_hash_unordered(&self) -> u64 {
+        let (mut hasher, records) = self.record_hashes();
+        records.len().hash(&mut hasher);
+        records
+            .iter()
+            .fold(0u64, |acc, &record| acc.wrapping_add(record))
+            .hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn content_hash() {
+        use test_utils::*;
+
+        let dv = sample_emp_table().into_view();
+        let same = sample_emp_table().into_view();
+        assert_eq!(dv.content_hash(), same.content_hash());
+        assert_eq!(dv.content_hash_unordered(), same.content_hash_unordered());
+
+        let sorted = dv.clone().sort_by_label::<emp_table::EmpName>();
+        assert_ne!(dv.content_hash(), sorted.content_hash());
+        assert_eq!(dv.content_hash_unordered(), sorted.content_hash_unordered());
+
+        let filtered = dv.query("EmpId != 5").unwrap();
+        assert_ne!(dv.content_hash(), filtered.content_hash());
+        assert_ne!(
+            dv.content_hash_unordered(),
+            filtered.content_hash_unordered()
+        );
+
+        let subset = dv.v::<Labels![emp_table::EmpId, emp_table::DeptId]>();
+        assert_ne!(dv.content_hash(), subset.content_hash());
+
+        let changed: emp_table::Store = emp_table![
+            vec![0u64, 2, 5, 6, 8, 9, 10],
+            vec![1u64, 2, 1, 1, 3, 4, 4],
+            ["Sally", "Jamie", "Bob", "Cara", "Louis", "Louise", "Anne"]
+        ];
+        assert_ne!(dv.content_hash(), changed.into_view().content_hash());
+    }
+}
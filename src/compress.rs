@@ -0,0 +1,320 @@
+/*!
+Compressed encodings of individual fields.
+
+Many fields are highly repetitive (status flags, category codes, sorted keys). This module provides
+two compressed encodings of a single field's values, which implement
+[DataIndex](../access/trait.DataIndex.html) and so can be used with any operation which accepts a
+`DataIndex` (statistics, iteration, chunked access, etc.):
+
+* [RleField](struct.RleField.html) -- run-length encoding, which stores each run of repeated values
+  (or missing values) once. Well-suited to sorted or clustered data.
+* [BitPackedField](struct.BitPackedField.html) -- dictionary encoding with bit-packed codes, which
+  stores each distinct value once and each row as a code of only as many bits as needed to
+  distinguish the distinct values. Well-suited to categorical and small-range integer data.
+
+Since `DataIndex` provides access to values by reference, both encodings keep one copy of each
+distinct value (or run value) in memory; the savings come from not storing a full value per row.
+
+Compressed fields can be created from any `DataIndex` using the
+[Compress](trait.Compress.html) trait, and converted back into a `FieldData` with their
+`decompress` methods.
+
+These are not a storage format for data stores: the fields of a
+[DataStore](../store/struct.DataStore.html) are always stored uncompressed as `FieldData`, and an
+`RleField` or `BitPackedField` cannot be added to a store or view, or passed to operations which
+work on them (joins, aggregation, sorting, sinks, etc.). They are intended for holding individual
+fields compactly (for instance, a field extracted from a view for later computation) and computing
+over them through `DataIndex`. To use their data in a store, `decompress` them and add the
+resulting `FieldData` to the store (e.g. with
+[push_back_field](../store/struct.DataStore.html#method.push_back_field)), which takes the
+uncompressed amount of memory.
+*/
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use access::{chunk_end, DataChunk, DataIndex};
+use error;
+use field::FieldData;
+use value::Value;
+
+/// Run-length encoded field data. Each run of consecutive equal values (or consecutive missing
+/// values) is stored as a single value along with the index at which the run ends.
+///
+/// An `RleField` cannot be used as a field of a `DataStore`; see the [module-level
+/// documentation](index.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RleField<T> {
+    // value for each run (NA for runs of missing values)
+    values: FieldData<T>,
+    // exclusive end index of each run, in increasing order
+    run_ends: Vec<usize>,
+}
+
+impl<T> RleField<T>
+where
+    T: Debug + Default + Clone + PartialEq,
+{
+    /// Creates a new run-length encoded field from the values of `data`.
+    pub fn new<DI>(data: &DI) -> RleField<T>
+    where
+        DI: DataIndex<DType = T>,
+    {
+        let mut values = FieldData::default();
+        let mut run_ends: Vec<usize> = vec![];
+        let mut current: Option<Value<&T>> = None;
+        for (idx, value) in data.iter().enumerate() {
+            if current.as_ref() == Some(&value) {
+                *run_ends.last_mut().expect("missing run") = idx + 1;
+            } else {
+                values.push_ref(value);
+                run_ends.push(idx + 1);
+                current = Some(value);
+            }
+        }
+        RleField { values, run_ends }
+    }
+
+    /// Returns the number of runs in this field.
+    pub fn nruns(&self) -> usize {
+        self.run_ends.len()
+    }
+
+    /// Converts this field back into an uncompressed `FieldData`.
+    pub fn decompress(&self) -> FieldData<T> {
+        self.iter().collect()
+    }
+}
+
+impl<T> RleField<T> {
+    fn run_index(&self, idx: usize) -> usize {
+        // index of first run which ends after `idx`
+        match self.run_ends.binary_search(&idx) {
+            Ok(run) => run + 1,
+            Err(run) => run,
+        }
+    }
+}
+
+impl<T> DataIndex for RleField<T>
+where
+    T: Debug,
+{
+    type DType = T;
+
+    fn get_datum(&self, idx: usize) -> error::Result<Value<&T>> {
+        if idx >= self.len() {
+            return Err(error::AgnesError::IndexError {
+                index: idx,
+                len: self.len(),
+            });
+        }
+        self.values.get_datum(self.run_index(idx))
+    }
+    fn len(&self) -> usize {
+        self.run_ends.last().cloned().unwrap_or(0)
+    }
+    fn get_chunk(&self, start: usize, len: usize) -> error::Result<DataChunk<'_, T>> {
+        let end = chunk_end(start, len, self.len())?;
+        let mut values = Vec::with_capacity(end - start);
+        let mut run = self.run_index(start);
+        for idx in start..end {
+            if idx >= self.run_ends[run] {
+                run += 1;
+            }
+            values.push(self.values.get_datum(run)?);
+        }
+        Ok(DataChunk::from_values(start, values))
+    }
+}
+
+/// Dictionary-encoded field data with bit-packed codes. Each distinct value is stored once, and
+/// each row is stored as a code using the minimum number of bits required to distinguish all
+/// distinct values (plus missing values).
+///
+/// A `BitPackedField` cannot be used as a field of a `DataStore`; see the [module-level
+/// documentation](index.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitPackedField<T> {
+    // distinct values; code `i + 1` refers to `dictionary[i]`, code `0` is a missing value
+    dictionary: Vec<T>,
+    bit_width: usize,
+    codes: Vec<u64>,
+    len: usize,
+}
+
+const WORD_BITS: usize = 64;
+
+impl<T> BitPackedField<T>
+where
+    T: Debug + Default + Clone + Eq + Hash,
+{
+    /// Creates a new bit-packed field from the values of `data`.
+    pub fn new<DI>(data: &DI) -> BitPackedField<T>
+    where
+        DI: DataIndex<DType = T>,
+    {
+        let mut dictionary = vec![];
+        let mut lookup: HashMap<&T, u64> = HashMap::new();
+        let row_codes = data
+            .iter()
+            .map(|value| match value {
+                Value::Exists(value) => *lookup.entry(value).or_insert_with(|| {
+                    dictionary.push(value.clone());
+                    dictionary.len() as u64
+                }),
+                Value::Na => 0,
+            })
+            .collect::<Vec<_>>();
+
+        // number of bits needed to represent codes 0..=dictionary.len()
+        let bit_width =
+            ((WORD_BITS as u32 - (dictionary.len() as u64).leading_zeros()) as usize).max(1);
+        let per_word = WORD_BITS / bit_width;
+        let codes = row_codes
+            .chunks(per_word)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u64, |word, (i, &code)| word | code << (i * bit_width))
+            })
+            .collect();
+        BitPackedField {
+            dictionary,
+            bit_width,
+            codes,
+            len: row_codes.len(),
+        }
+    }
+
+    /// Converts this field back into an uncompressed `FieldData`.
+    pub fn decompress(&self) -> FieldData<T> {
+        self.iter().collect()
+    }
+}
+
+impl<T> BitPackedField<T> {
+    /// Returns the number of distinct (non-missing) values in this field.
+    pub fn ndistinct(&self) -> usize {
+        self.dictionary.len()
+    }
+
+    /// Returns the number of bits used to store each value in this field.
+    pub fn bit_width(&self) -> usize {
+        self.bit_width
+    }
+
+    fn code(&self, idx: usize) -> usize {
+        let per_word = WORD_BITS / self.bit_width;
+        let mask = if self.bit_width == WORD_BITS {
+            !0
+        } else {
+            (1u64 << self.bit_width) - 1
+        };
+        ((self.codes[idx / per_word] >> ((idx % per_word) * self.bit_width)) & mask) as usize
+    }
+}
+
+impl<T> DataIndex for BitPackedField<T>
+where
+    T: Debug,
+{
+    type DType = T;
+
+    fn get_datum(&self, idx: usize) -> error::Result<Value<&T>> {
+        if idx >= self.len {
+            return Err(error::AgnesError::IndexError {
+                index: idx,
+                len: self.len,
+            });
+        }
+        Ok(match self.code(idx) {
+            0 => Value::Na,
+            code => Value::Exists(&self.dictionary[code - 1]),
+        })
+    }
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/// A trait for converting a field into one of the compressed representations in this module.
+pub trait Compress: DataIndex + Sized {
+    /// Returns a run-length encoded copy of this field.
+    fn compress_rle(&self) -> RleField<Self::DType>
+    where
+        Self::DType: Debug + Default + Clone + PartialEq,
+    {
+        RleField::new(self)
+    }
+
+    /// Returns a dictionary-encoded, bit-packed copy of this field.
+    fn bit_pack(&self) -> BitPackedField<Self::DType>
+    where
+        Self::DType: Debug + Default + Clone + Eq + Hash,
+    {
+        BitPackedField::new(self)
+    }
+}
+
+impl<DI> Compress for DI where DI: DataIndex {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use stats::NaCount;
+
+    fn sample() -> FieldData<u64> {
+        FieldData::from_field_vec(vec![
+            Value::Exists(3u64),
+            Value::Exists(3),
+            Value::Na,
+            Value::Na,
+            Value::Exists(7),
+            Value::Exists(3),
+            Value::Exists(3),
+            Value::Exists(3),
+        ])
+    }
+
+    #[test]
+    fn rle() {
+        let field = sample();
+        let rle = field.compress_rle();
+        assert_eq!(rle.nruns(), 4);
+        assert_eq!(rle.len(), 8);
+        assert_eq!(rle.to_value_vec(), field.to_value_vec());
+        assert_eq!(rle.decompress(), field);
+        assert_eq!(rle.num_na(), 2);
+        assert!(rle.get_datum(8).is_err());
+        assert_eq!(
+            rle.get_chunk(3, 3)
+                .unwrap()
+                .iter()
+                .map(|v| v.cloned())
+                .collect::<Vec<_>>(),
+            vec![Value::Na, Value::Exists(7), Value::Exists(3)]
+        );
+
+        let empty = FieldData::<u64>::default().compress_rle();
+        assert_eq!((empty.len(), empty.nruns()), (0, 0));
+    }
+
+    #[test]
+    fn bit_pack() {
+        let field = sample();
+        let packed = field.bit_pack();
+        assert_eq!(packed.ndistinct(), 2);
+        assert_eq!(packed.bit_width(), 2);
+        assert_eq!(packed.to_value_vec(), field.to_value_vec());
+        assert_eq!(packed.decompress(), field);
+        assert!(packed.get_datum(8).is_err());
+
+        let field = FieldData::<u64>::from_vec((0..1000u64).map(|i| i % 37).collect::<Vec<_>>());
+        let packed = field.bit_pack();
+        assert_eq!(packed.bit_width(), 6);
+        assert_eq!(packed.to_vec(), field.to_vec());
+    }
+}
@@ -0,0 +1,515 @@
+/*!
+Comparison of two versions of a `DataView`, reporting the records and values that changed.
+
+[DataView::diff](../view/struct.DataView.html#method.diff) matches the records of two `DataView`s
+of the same type (e.g. two exports of the same table, or two views aligned to a common set of
+fields with [align_to](../view/struct.DataView.html#method.align_to)) by the values of a set of key
+fields, and produces a report `DataView` (of type [changes::View](changes/type.View.html)) with one
+record for each difference:
+
+* `"removed"` -- a record of the original view whose key does not exist in the other view.
+* `"added"` -- a record of the other view whose key does not exist in the original view.
+* `"changed"` -- a (non-key) field whose value differs between matched records.
+
+Each report record contains the kind of change, the indices of the affected records in the
+original (`LeftRow`) and other (`RightRow`) views, and for changed values, the name of the field
+and the original and new values (rendered as text). Values are missing (NA) where they do not
+apply (e.g. `LeftRow` for added records).
+
+Values are compared by value (not by their text representation), with all floating-point NaN
+values considered equal to each other and positive and negative zero considered equal. Fields of
+types other than `String`, `&str`, `ArenaStr`, `f64`, `f32`, `u64`, `u32`, `i64`, `i32`, `bool`,
+and `FloatKey` are not compared, and cannot be used as key fields (`diff` fails with
+`AgnesError::UnsupportedType` if they are). The report's labels belong to a
+[BuiltinTable](../label/struct.BuiltinTable.html), so they never collide with the labels of
+user-declared tables.
+*/
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use access::DataIndex;
+use cons::Nil;
+use error::*;
+use field::ArenaStr;
+use key::FloatKey;
+use label::{HasLabels, LabelIds, StrLabels};
+use partial::{Func, FuncDefault, Implemented, IsImplemented, Unimplemented};
+use store::DataStore;
+use value::Value;
+use view::{DataView, FieldMap};
+
+tablespace![
+    @builtin(::typenum::U1) pub table changes {
+        Change: String,
+        LeftRow: u64,
+        RightRow: u64,
+        Field: String,
+        Left: String,
+        Right: String,
+    }
+];
+
+// A single field value as compared by `diff`.
+#[derive(Debug, Clone)]
+enum Cell {
+    Text(String),
+    Unsigned(u64),
+    Signed(i64),
+    Bool(bool),
+    // normalized so that all NaN values are identical and negative zero is positive zero
+    Float(f64),
+}
+
+impl Cell {
+    fn float(value: f64) -> Cell {
+        Cell::Float(if value.is_nan() {
+            f64::NAN
+        } else if value == 0.0 {
+            0.0
+        } else {
+            value
+        })
+    }
+}
+
+impl PartialEq for Cell {
+    fn eq(&self, other: &Cell) -> bool {
+        match (self, other) {
+            (Cell::Text(l), Cell::Text(r)) => l == r,
+            (Cell::Unsigned(l), Cell::Unsigned(r)) => l == r,
+            (Cell::Signed(l), Cell::Signed(r)) => l == r,
+            (Cell::Bool(l), Cell::Bool(r)) => l == r,
+            (Cell::Float(l), Cell::Float(r)) => l.to_bits() == r.to_bits(),
+            _ => false,
+        }
+    }
+}
+impl Eq for Cell {}
+
+impl Hash for Cell {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        match self {
+            Cell::Text(value) => value.hash(state),
+            Cell::Unsigned(value) => value.hash(state),
+            Cell::Signed(value) => value.hash(state),
+            Cell::Bool(value) => value.hash(state),
+            Cell::Float(value) => value.to_bits().hash(state),
+        }
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Cell::Text(value) => write!(f, "{}", value),
+            Cell::Unsigned(value) => write!(f, "{}", value),
+            Cell::Signed(value) => write!(f, "{}", value),
+            Cell::Bool(value) => write!(f, "{}", value),
+            Cell::Float(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Function (implementing [Func](../partial/trait.Func.html)) which collects the values of every
+/// field of a `DataView` for comparison by
+/// [DataView::diff](../view/struct.DataView.html#method.diff).
+#[derive(Debug, Default)]
+pub struct CollectValuesFn {
+    // collected values of each field (in label order); `None` for unsupported fields
+    values: Vec<Option<Vec<Value<Cell>>>>,
+}
+macro_rules! impl_collect_fn {
+    ($($dtype:ty => $cell:expr;)*) => {$(
+        impl Func<$dtype> for CollectValuesFn {
+            type Output = ();
+            fn call<DI>(&mut self, data: &DI) -> Self::Output
+            where
+                DI: DataIndex<DType = $dtype>,
+            {
+                self.values.push(Some(data.iter().map(|value| value.map($cell)).collect()));
+            }
+        }
+        impl IsImplemented<CollectValuesFn> for $dtype {
+            type IsImpl = Implemented;
+        }
+    )*}
+}
+impl_collect_fn![
+    String => |value: &String| Cell::Text(value.clone());
+    ArenaStr => |value: &ArenaStr| Cell::Text(value.to_string());
+    f64 => |value: &f64| Cell::float(*value);
+    f32 => |value: &f32| Cell::float(f64::from(*value));
+    u64 => |value: &u64| Cell::Unsigned(*value);
+    u32 => |value: &u32| Cell::Unsigned(u64::from(*value));
+    i64 => |value: &i64| Cell::Signed(*value);
+    i32 => |value: &i32| Cell::Signed(i64::from(*value));
+    bool => |value: &bool| Cell::Bool(*value);
+    FloatKey => |value: &FloatKey| Cell::float(value.value());
+];
+
+impl<'a> Func<&'a str> for CollectValuesFn {
+    type Output = ();
+    fn call<DI>(&mut self, data: &DI) -> Self::Output
+    where
+        DI: DataIndex<DType = &'a str>,
+    {
+        self.values.push(Some(
+            data.iter()
+                .map(|value| value.map(|value| Cell::Text(value.to_string())))
+                .collect(),
+        ));
+    }
+}
+impl IsImplemented<CollectValuesFn> for &str {
+    type IsImpl = Implemented;
+}
+
+macro_rules! impl_collect_fn_nonimpl {
+    ($($dtype:ty)*) => {$(
+        impl IsImplemented<CollectValuesFn> for $dtype {
+            type IsImpl = Unimplemented;
+        }
+    )*}
+}
+impl_collect_fn_nonimpl![u8 u16 i8 i16 usize isize];
+
+impl FuncDefault for CollectValuesFn {
+    type Output = ();
+    fn call(&mut self) -> Self::Output {
+        self.values.push(None);
+    }
+}
+
+// Accumulated records of a `changes` report.
+#[derive(Default)]
+struct Report {
+    change: Vec<String>,
+    left_row: Vec<Value<u64>>,
+    right_row: Vec<Value<u64>>,
+    field: Vec<Value<String>>,
+    left: Vec<Value<String>>,
+    right: Vec<Value<String>>,
+}
+
+impl Report {
+    fn push_record(&mut self, change: &str, left_row: Option<usize>, right_row: Option<usize>) {
+        self.push(change, left_row, right_row, Value::Na, Value::Na, Value::Na);
+    }
+
+    fn push(
+        &mut self,
+        change: &str,
+        left_row: Option<usize>,
+        right_row: Option<usize>,
+        field: Value<String>,
+        left: Value<String>,
+        right: Value<String>,
+    ) {
+        self.change.push(change.to_string());
+        self.left_row.push(left_row.map(|idx| idx as u64).into());
+        self.right_row.push(right_row.map(|idx| idx as u64).into());
+        self.field.push(field);
+        self.left.push(left);
+        self.right.push(right);
+    }
+
+    fn into_view(self) -> changes::View {
+        DataStore::<Nil>::empty()
+            .push_back_from_iter::<changes::Change, _, _, _>(self.change)
+            .push_back_from_value_iter::<changes::LeftRow, _, _, _>(self.left_row)
+            .push_back_from_value_iter::<changes::RightRow, _, _, _>(self.right_row)
+            .push_back_from_value_iter::<changes::Field, _, _, _>(self.field)
+            .push_back_from_value_iter::<changes::Left, _, _, _>(self.left)
+            .push_back_from_value_iter::<changes::Right, _, _, _>(self.right)
+            .into_view()
+    }
+}
+
+// Collected values of each field of a view (in label order); `None` for unsupported fields.
+type Collected = [Option<Vec<Value<Cell>>>];
+
+fn nrows(values: &Collected) -> usize {
+    values.iter().flatten().map(Vec::len).next().unwrap_or(0)
+}
+
+// Returns the values of the key fields (those fields for which `is_key` is set) of the record at
+// index `idx`.
+fn key_of<'a>(values: &'a Collected, is_key: &[bool], idx: usize) -> Vec<Value<&'a Cell>> {
+    values
+        .iter()
+        .zip(is_key)
+        .filter(|&(_, &is_key)| is_key)
+        .filter_map(|(field, _)| field.as_ref())
+        .map(|field| field[idx].as_ref())
+        .collect()
+}
+
+fn render(value: &Value<Cell>) -> Value<String> {
+    value.as_ref().map(Cell::to_string)
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Compares this `DataView` with `other`, matching records by the values of the fields
+    /// labeled `KeyLabels`, and returns a report of the added records, removed records, and
+    /// changed values. If several records share the same key, they are matched in order of
+    /// appearance. See the [diff](../diff/index.html) module for more details.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::UnsupportedType` if a key field is of a type which `diff` cannot
+    /// compare (see the [diff](../diff/index.html) module).
+    pub fn diff<KeyLabels>(&self, other: &Self) -> Result<changes::View>
+    where
+        Self: FieldMap<CollectValuesFn>,
+        Labels: StrLabels + LabelIds + HasLabels<KeyLabels>,
+        KeyLabels: LabelIds,
+    {
+        let names = <Labels as StrLabels>::labels_vec();
+        // key fields are identified by label, not by name (distinct labels may share a name)
+        let key_ids = KeyLabels::label_ids();
+        let is_key = Labels::label_ids()
+            .iter()
+            .map(|id| key_ids.contains(id))
+            .collect::<Vec<_>>();
+
+        let collect = |view: &Self| {
+            let mut func = CollectValuesFn::default();
+            view.field_map(&mut func);
+            func.values
+        };
+        let (left, right) = (collect(self), collect(other));
+        if let Some((name, _)) = names
+            .iter()
+            .zip(&left)
+            .zip(&is_key)
+            .find(|&((_, values), &is_key)| is_key && values.is_none())
+            .map(|(field, _)| field)
+        {
+            return Err(AgnesError::UnsupportedType(format!(
+                "key field '{}' is of a type diff cannot compare",
+                name
+            )));
+        }
+
+        let mut right_rows: HashMap<Vec<Value<&Cell>>, VecDeque<usize>> = HashMap::new();
+        for idx in 0..nrows(&right) {
+            right_rows
+                .entry(key_of(&right, &is_key, idx))
+                .or_default()
+                .push_back(idx);
+        }
+
+        let mut report = Report::default();
+        let mut matched = vec![false; nrows(&right)];
+        for left_idx in 0..nrows(&left) {
+            let right_idx = match right_rows
+                .get_mut(&key_of(&left, &is_key, left_idx))
+                .and_then(VecDeque::pop_front)
+            {
+                Some(right_idx) => right_idx,
+                None => {
+                    report.push_record("removed", Some(left_idx), None);
+                    continue;
+                }
+            };
+            matched[right_idx] = true;
+            for (field_idx, name) in names.iter().enumerate() {
+                if is_key[field_idx] {
+                    continue;
+                }
+                if let (Some(l), Some(r)) = (&left[field_idx], &right[field_idx]) {
+                    if l[left_idx] != r[right_idx] {
+                        report.push(
+                            "changed",
+                            Some(left_idx),
+                            Some(right_idx),
+                            Value::Exists(name.to_string()),
+                            render(&l[left_idx]),
+                            render(&r[right_idx]),
+                        );
+                    }
+                }
+            }
+        }
+        for (right_idx, _) in matched.iter().enumerate().filter(|&(_, &m)| !m) {
+            report.push_record("added", None, Some(right_idx));
+        }
+        Ok(report.into_view())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn diff() {
+        use super::*;
+        use field::FieldData;
+        use select::FieldSelect;
+        use test_utils::*;
+
+        let before = sample_emp_table().into_view();
+        let after = emp_table_from_field![
+            FieldData::from_vec(vec![0u64, 2, 6, 8, 9, 10, 11]),
+            FieldData::from_vec(vec![1u64, 2, 3, 3, 4, 4, 1]),
+            FieldData::from_vec(
+                ["Sally", "Jamie", "Cara", "Lewis", "Louise", "Ann", "Zed"]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+            )
+        ]
+        .into_view();
+
+        let report = before.diff::<Labels![emp_table::EmpId]>(&after).unwrap();
+        assert_eq!(
+            report.field::<changes::Change>().to_vec(),
+            vec!["removed", "changed", "changed", "added"]
+        );
+        assert_eq!(
+            report.field::<changes::LeftRow>().to_value_vec(),
+            vec![
+                Value::Exists(2),
+                Value::Exists(3),
+                Value::Exists(4),
+                Value::Na
+            ]
+        );
+        assert_eq!(
+            report.field::<changes::RightRow>().to_value_vec(),
+            vec![
+                Value::Na,
+                Value::Exists(2),
+                Value::Exists(3),
+                Value::Exists(6)
+            ]
+        );
+        assert_eq!(
+            report.field::<changes::Field>().to_value_vec(),
+            vec![
+                Value::Na,
+                Value::Exists("DeptId".to_string()),
+                Value::Exists("EmpName".to_string()),
+                Value::Na
+            ]
+        );
+        assert_eq!(
+            report.field::<changes::Left>().to_value_vec()[1..3],
+            [
+                Value::Exists("1".to_string()),
+                Value::Exists("Louis".to_string())
+            ]
+        );
+        assert_eq!(
+            report.field::<changes::Right>().to_value_vec()[1..3],
+            [
+                Value::Exists("3".to_string()),
+                Value::Exists("Lewis".to_string())
+            ]
+        );
+
+        assert_eq!(
+            before
+                .diff::<Labels![emp_table::EmpId]>(&before)
+                .unwrap()
+                .nrows(),
+            0
+        );
+    }
+
+    #[test]
+    fn unsupported_key() {
+        use super::*;
+        use field::FieldData;
+
+        tablespace![
+            table readings {
+                Reading: u64,
+                Sensor: u8,
+            }
+        ];
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<readings::Reading, _>(FieldData::<u64>::from_vec(vec![10u64, 20]))
+            .push_back_field::<readings::Sensor, _>(FieldData::<u8>::from_vec(vec![1u8, 2]))
+            .into_view();
+        match dv.diff::<Labels![readings::Sensor]>(&dv) {
+            Err(AgnesError::UnsupportedType(_)) => {}
+            _ => panic!("expected unsupported type error"),
+        }
+        // unsupported non-key fields are not compared
+        assert_eq!(
+            dv.diff::<Labels![readings::Reading]>(&dv).unwrap().nrows(),
+            0
+        );
+    }
+
+    #[test]
+    fn float_values() {
+        use super::*;
+        use field::FieldData;
+        use select::FieldSelect;
+
+        tablespace![
+            table levels {
+                Sensor: u64,
+                Level: f64,
+            }
+        ];
+
+        let view = |levels: Vec<f64>| {
+            DataStore::<Nil>::empty()
+                .push_back_field::<levels::Sensor, _>(FieldData::<u64>::from_vec(vec![1u64, 2, 3]))
+                .push_back_field::<levels::Level, _>(FieldData::<f64>::from_vec(levels))
+                .into_view()
+        };
+        // values are compared by value: NaN values are equal, as are positive and negative zero
+        let before = view(vec![0.0, f64::NAN, 1.0]);
+        let after = view(vec![-0.0, f64::NAN, 1.5]);
+        let report = before.diff::<Labels![levels::Sensor]>(&after).unwrap();
+        assert_eq!(report.field::<changes::Change>().to_vec(), vec!["changed"]);
+        assert_eq!(
+            report.field::<changes::Right>().to_value_vec(),
+            vec![Value::Exists("1.5".to_string())]
+        );
+    }
+
+    #[test]
+    fn duplicate_names() {
+        use super::*;
+        use field::FieldData;
+        use select::FieldSelect;
+
+        tablespace![
+            table orders {
+                Id: u64,
+            }
+            table customers {
+                Id: u64,
+            }
+        ];
+
+        let view = |order_ids: Vec<u64>| {
+            DataStore::<Nil>::empty()
+                .push_back_field::<orders::Id, _>(FieldData::<u64>::from_vec(order_ids))
+                .push_back_field::<customers::Id, _>(FieldData::<u64>::from_vec(vec![7u64, 8]))
+                .into_view()
+        };
+        // only the key label is a key field, not every field sharing its name
+        let report = view(vec![1, 2])
+            .diff::<Labels![customers::Id]>(&view(vec![1, 3]))
+            .unwrap();
+        assert_eq!(report.field::<changes::Change>().to_vec(), vec!["changed"]);
+        assert_eq!(
+            report.field::<changes::Field>().to_value_vec(),
+            vec![Value::Exists("Id".to_string())]
+        );
+        assert_eq!(
+            report.field::<changes::Right>().to_value_vec(),
+            vec![Value::Exists("3".to_string())]
+        );
+    }
+}
@@ -0,0 +1,1117 @@
+/*!
+Configurable rendering of the values of a [DataView](../view/struct.DataView.html) as text.
+
+By default, a `DataView` is displayed with every value rendered in full (with its `Display`
+implementation), which can make tables with floating-point fields hard to read. A
+[DisplayOptions](struct.DisplayOptions.html) controls how numeric values are rendered: the number
+of digits after the decimal point in floating-point fields, the magnitude beyond which
+floating-point values are rendered in scientific notation, and the separator between groups of
+thousands. Individual fields can instead be rendered with their own
+[NumberFormat](../format/struct.NumberFormat.html).
+
+When displaying a view as a table, [footer rows](enum.Footer.html) can also be appended with the
+sum, mean, or count of the values of each field, for quick sanity checks of intermediate views,
+and values can be [colorized](struct.DisplayOptions.html#method.colorize) for interactive use.
+Columns are sized by the displayed width of their values (so that values containing wide
+characters, such as CJK characters or emoji, remain aligned), and long values can be
+[truncated](struct.DisplayOptions.html#method.max_width). Other tables of text (such as
+[memory reports](../memory/struct.MemoryReport.html) and
+[view statistics](../view_stats/struct.ViewStats.html)) are displayed with the same layout by
+[TextTable](struct.TextTable.html).
+
+The same options are used to display a view as a table (with
+[DataView::display](../view/struct.DataView.html#method.display)) and to write it in CSV format
+(with [write_csv_formatted_to](../view/struct.DataView.html#method.write_csv_formatted_to)).
+
+# Example
+
+```
+# #[macro_use] extern crate agnes;
+use agnes::display::DisplayOptions;
+
+tablespace![
+    pub table measurements {
+        Station: String,
+        Count: u64,
+        Flow: f64,
+        Depth: f64,
+    }
+];
+
+fn main() {
+    let dv = table![
+        measurements::Station = ["north", "south"];
+        measurements::Count = [1200u64, 35];
+        measurements::Flow = [1234.56789, 0.00012];
+        measurements::Depth = [2.5, 12.125];
+    ];
+    let options = DisplayOptions::new()
+        .precision(2)
+        .scientific_threshold(1e3)
+        .thousands(',')
+        .field_format::<measurements::Depth>(">6.1".parse().unwrap());
+    let table = dv.display(&options).to_string();
+    assert!(table.contains("1,200"));
+    assert!(table.contains("1.23e3"));
+    assert!(table.contains("1.20e-4"));
+    assert!(table.contains("  12.1"));
+}
+```
+*/
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+use num_traits::ToPrimitive;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use access::{DataIndex, NRows};
+use cons::Len;
+use field::{ArenaStr, StrFieldData};
+use format::NumberFormat;
+use key::FloatKey;
+use label::{LabelName, StrLabels};
+use partial::{Func, FuncDefault, Implemented, IsImplemented, PartialCall, Unimplemented};
+use value::Value;
+use view::{DataView, FieldMap};
+
+/// Options for rendering the values of a [DataView](../view/struct.DataView.html) as text. See
+/// the [module-level documentation](index.html) for details.
+#[derive(Debug, Clone)]
+pub struct DisplayOptions {
+    max_rows: usize,
+    max_width: Option<usize>,
+    precision: Option<usize>,
+    sci_threshold: Option<f64>,
+    thousands: Option<char>,
+    field_formats: HashMap<&'static str, NumberFormat>,
+    footers: Vec<Footer>,
+    colorize: bool,
+}
+
+/// An aggregate of the values of each field, displayed in a footer row below the values of a
+/// [DataView](../view/struct.DataView.html) (see
+/// [DisplayOptions::footer](struct.DisplayOptions.html#method.footer)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Footer {
+    /// The sum of the (non-missing) values of each numeric field.
+    Sum,
+    /// The mean of the (non-missing) values of each numeric field.
+    Mean,
+    /// The number of non-missing values of each field.
+    Count,
+}
+
+impl Footer {
+    /// Returns the name of this aggregate, which labels its footer row.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Footer::Sum => "sum",
+            Footer::Mean => "mean",
+            Footer::Count => "count",
+        }
+    }
+}
+
+impl Display for Footer {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl Default for DisplayOptions {
+    fn default() -> DisplayOptions {
+        DisplayOptions {
+            max_rows: 1000,
+            max_width: None,
+            precision: None,
+            sci_threshold: None,
+            thousands: None,
+            field_formats: HashMap::new(),
+            footers: vec![],
+            colorize: false,
+        }
+    }
+}
+
+impl DisplayOptions {
+    /// Creates a new set of display options, which renders every value in full and displays at
+    /// most 1000 rows.
+    pub fn new() -> DisplayOptions {
+        DisplayOptions::default()
+    }
+
+    /// Sets the maximum number of rows displayed (by
+    /// [DataView::display](../view/struct.DataView.html#method.display)). All rows are written
+    /// when writing in CSV format.
+    pub fn max_rows(mut self, max_rows: usize) -> DisplayOptions {
+        self.max_rows = max_rows;
+        self
+    }
+
+    /// Sets the maximum displayed width (in terminal columns) of each line of each cell (when
+    /// displayed with [DataView::display](../view/struct.DataView.html#method.display)). Longer
+    /// lines are truncated, ending with `…`. The width is at least 1. Values are not truncated
+    /// when written in CSV format.
+    pub fn max_width(mut self, max_width: usize) -> DisplayOptions {
+        self.max_width = Some(max_width.max(1));
+        self
+    }
+
+    /// Sets the number of digits displayed after the decimal point in floating-point fields.
+    pub fn precision(mut self, precision: usize) -> DisplayOptions {
+        self.precision = Some(precision);
+        self
+    }
+
+    /// Renders floating-point values in scientific notation if their magnitude is at least
+    /// `threshold`, or if they are non-zero with a magnitude less than `1 / threshold`.
+    pub fn scientific_threshold(mut self, threshold: f64) -> DisplayOptions {
+        self.sci_threshold = Some(threshold);
+        self
+    }
+
+    /// Sets the separator inserted between groups of thousands in numeric fields.
+    pub fn thousands(mut self, separator: char) -> DisplayOptions {
+        self.thousands = Some(separator);
+        self
+    }
+
+    /// Renders the numeric field labeled `Label` with the format `format`, instead of the
+    /// precision and thousands separator set for all fields. Scientific notation (if enabled with
+    /// [scientific_threshold](#method.scientific_threshold)) still applies to floating-point
+    /// fields, using the precision of `format`.
+    pub fn field_format<Label>(mut self, format: NumberFormat) -> DisplayOptions
+    where
+        Label: LabelName,
+    {
+        self.field_formats.insert(Label::name(), format);
+        self
+    }
+
+    /// Appends a footer row which displays the aggregate `footer` of each field (when displayed
+    /// with [DataView::display](../view/struct.DataView.html#method.display)). Aggregates are
+    /// computed over all the rows of the view, including rows not displayed due to the maximum
+    /// number of rows. When any footer rows are displayed, the table gains a leading column
+    /// containing the name of each footer's aggregate. Footer rows are not written in CSV format.
+    pub fn footer(mut self, footer: Footer) -> DisplayOptions {
+        self.footers.push(footer);
+        self
+    }
+
+    /// Sets whether values are highlighted with ANSI terminal colors (when displayed with
+    /// [DataView::display](../view/struct.DataView.html#method.display)): missing (NA) values are
+    /// dimmed red, and the minimum and maximum values of each numeric field are bold blue and
+    /// bold green, respectively. Values are not colorized by default, and are never colorized
+    /// when written in CSV format.
+    pub fn colorize(mut self, colorize: bool) -> DisplayOptions {
+        self.colorize = colorize;
+        self
+    }
+
+    // Returns the format used for the field named `label`.
+    fn number_format(&self, label: &str, float: bool) -> NumberFormat {
+        self.field_formats
+            .get(label)
+            .cloned()
+            .unwrap_or_else(|| NumberFormat {
+                thousands: self.thousands,
+                precision: if float { self.precision } else { None },
+                ..NumberFormat::default()
+            })
+    }
+
+    fn format_float<T>(&self, label: &str, value: &T) -> String
+    where
+        T: Display + ToPrimitive,
+    {
+        let format = self.number_format(label, true);
+        match (self.sci_threshold, value.to_f64()) {
+            (Some(threshold), Some(value))
+                if value.is_finite()
+                    && value != 0.0
+                    && (value.abs() >= threshold || value.abs() < threshold.recip()) =>
+            {
+                let rendered = match format.precision {
+                    Some(precision) => format!("{:.*e}", precision, value),
+                    None => format!("{:e}", value),
+                };
+                if format.sign_plus && value > 0.0 {
+                    format.pad(&format!("+{}", rendered))
+                } else {
+                    format.pad(&rendered)
+                }
+            }
+            _ => format.format(value),
+        }
+    }
+
+    fn format_int<T>(&self, label: &str, value: &T) -> String
+    where
+        T: Display + ToPrimitive,
+    {
+        self.number_format(label, false).format(value)
+    }
+}
+
+/// Trait for data types whose values can be rendered as text according to a
+/// [DisplayOptions](struct.DisplayOptions.html).
+pub trait DisplayCell {
+    /// Renders this value, a value of the field named `label`, according to `options`. Text values
+    /// are borrowed rather than copied.
+    fn display_cell(&self, label: &str, options: &DisplayOptions) -> Cow<'_, str>;
+
+    /// Whether this data type is numeric, and has [Footer](enum.Footer.html) sums and means.
+    const NUMERIC: bool = false;
+
+    /// Returns this value as a `f64`, for computing [Footer](enum.Footer.html) sums and means, or
+    /// `None` (the default) if this data type is not numeric.
+    fn numeric_value(&self) -> Option<f64> {
+        None
+    }
+
+    /// Renders the sum of values of this data type, a sum of the field named `label`, according
+    /// to `options`. Only called for numeric data types.
+    fn display_sum(sum: f64, label: &str, options: &DisplayOptions) -> String
+    where
+        Self: Sized,
+    {
+        options.format_float(label, &sum)
+    }
+}
+
+macro_rules! impl_display_cell {
+    ($method:ident: $($dtype:ty)*) => {$(
+        impl DisplayCell for $dtype {
+            const NUMERIC: bool = true;
+            fn display_cell(&self, label: &str, options: &DisplayOptions) -> Cow<'_, str> {
+                options.$method(label, self).into()
+            }
+            fn numeric_value(&self) -> Option<f64> {
+                self.to_f64()
+            }
+            fn display_sum(sum: f64, label: &str, options: &DisplayOptions) -> String {
+                options.$method(label, &sum)
+            }
+        }
+    )*}
+}
+impl_display_cell![format_float: f64 f32];
+impl_display_cell![format_int: u64 u32 i64 i32];
+
+impl DisplayCell for FloatKey {
+    const NUMERIC: bool = true;
+    fn display_cell(&self, label: &str, options: &DisplayOptions) -> Cow<'_, str> {
+        options.format_float(label, &self.value()).into()
+    }
+    fn numeric_value(&self) -> Option<f64> {
+        Some(self.value())
+    }
+}
+impl DisplayCell for String {
+    fn display_cell(&self, _label: &str, _options: &DisplayOptions) -> Cow<'_, str> {
+        self.as_str().into()
+    }
+}
+impl DisplayCell for &str {
+    fn display_cell(&self, _label: &str, _options: &DisplayOptions) -> Cow<'_, str> {
+        (*self).into()
+    }
+}
+impl DisplayCell for ArenaStr {
+    fn display_cell(&self, _label: &str, _options: &DisplayOptions) -> Cow<'_, str> {
+        self.as_str().into()
+    }
+}
+impl DisplayCell for bool {
+    fn display_cell(&self, _label: &str, _options: &DisplayOptions) -> Cow<'_, str> {
+        if *self { "true" } else { "false" }.into()
+    }
+}
+
+/// Marker for data types which are displayed with their `Display` implementation, for data types
+/// which don't implement [DisplayCell](trait.DisplayCell.html). Use it as the `IsImpl` of the data
+/// type's [IsImplemented](../partial/trait.IsImplemented.html) implementation for
+/// [FormatCellsFn](struct.FormatCellsFn.html):
+///
+/// ```
+/// # extern crate agnes;
+/// use std::fmt;
+///
+/// use agnes::display::{Displayed, FormatCellsFn};
+/// use agnes::partial::IsImplemented;
+///
+/// #[derive(Debug, Default)]
+/// struct Point(i64, i64);
+/// impl fmt::Display for Point {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         write!(f, "({}, {})", self.0, self.1)
+///     }
+/// }
+/// impl IsImplemented<FormatCellsFn> for Point {
+///     type IsImpl = Displayed;
+/// }
+/// # fn main() {}
+/// ```
+///
+/// Values of such data types are rendered in full (ignoring the number formatting options), and
+/// have no footer sums or means.
+#[derive(Debug, Clone, Copy)]
+pub struct Displayed;
+
+// How `FormatCellsFn` renders the values of a data type `T`.
+trait RenderCell<T> {
+    const NUMERIC: bool;
+    fn render<'a>(value: &'a T, label: &str, options: &DisplayOptions) -> Cow<'a, str>;
+    fn numeric_value(value: &T) -> Option<f64>;
+    fn display_sum(sum: f64, label: &str, options: &DisplayOptions) -> String;
+}
+// values rendered with their `DisplayCell` implementation
+struct WithDisplayCell;
+impl<T: DisplayCell> RenderCell<T> for WithDisplayCell {
+    const NUMERIC: bool = T::NUMERIC;
+    fn render<'a>(value: &'a T, label: &str, options: &DisplayOptions) -> Cow<'a, str> {
+        value.display_cell(label, options)
+    }
+    fn numeric_value(value: &T) -> Option<f64> {
+        value.numeric_value()
+    }
+    fn display_sum(sum: f64, label: &str, options: &DisplayOptions) -> String {
+        T::display_sum(sum, label, options)
+    }
+}
+// values rendered with their `Display` implementation
+struct WithDisplay;
+impl<T: Display> RenderCell<T> for WithDisplay {
+    const NUMERIC: bool = false;
+    fn render<'a>(value: &'a T, _label: &str, _options: &DisplayOptions) -> Cow<'a, str> {
+        value.to_string().into()
+    }
+    fn numeric_value(_value: &T) -> Option<f64> {
+        None
+    }
+    fn display_sum(sum: f64, label: &str, options: &DisplayOptions) -> String {
+        options.format_float(label, &sum)
+    }
+}
+
+// ANSI escape sequences for highlighting missing values and extreme values.
+const NA_STYLE: &str = "\x1b[2;31m";
+const MIN_STYLE: &str = "\x1b[1;34m";
+const MAX_STYLE: &str = "\x1b[1;32m";
+const RESET_STYLE: &str = "\x1b[0m";
+
+/// Function (implementing [Func](../partial/trait.Func.html)) that renders the values of a field
+/// according to a [DisplayOptions](struct.DisplayOptions.html). Values of data types which
+/// implement [DisplayCell](trait.DisplayCell.html) are rendered with it, and values of data types
+/// marked [Displayed](struct.Displayed.html) with their `Display` implementation. Fields of data
+/// types which cannot be rendered are rendered as empty cells.
+///
+/// The function owns a copy of the options, rather than borrowing them: functions with lifetime
+/// parameters make mapping them over the fields of views with many fields much slower to compile.
+#[derive(Debug)]
+pub struct FormatCellsFn {
+    options: DisplayOptions,
+    labels: Vec<&'static str>,
+    nrows: usize,
+    columns: Vec<StrFieldData>,
+    table: bool,
+    footers: Vec<Vec<String>>,
+    styles: Vec<Vec<&'static str>>,
+}
+impl FormatCellsFn {
+    /// Creates a new function rendering the first `nrows` values of fields labeled (in the
+    /// order the fields are visited) with `labels`.
+    pub(crate) fn new(
+        options: &DisplayOptions,
+        labels: Vec<&'static str>,
+        nrows: usize,
+    ) -> FormatCellsFn {
+        FormatCellsFn {
+            options: options.clone(),
+            labels,
+            nrows,
+            columns: vec![],
+            table: false,
+            footers: vec![],
+            styles: vec![],
+        }
+    }
+
+    /// Also computes the footer rows and colors specified in the options (which only apply when
+    /// displaying a table), over all the values of each field.
+    pub(crate) fn for_table(mut self) -> FormatCellsFn {
+        self.table = true;
+        self
+    }
+
+    /// Returns the rendered values, one column for each field visited.
+    pub(crate) fn into_columns(self) -> Vec<StrFieldData> {
+        self.columns
+    }
+}
+impl FormatCellsFn {
+    // Renders the first `nrows` values of `data` (and, for tables, computes its footers and
+    // styles), using `R` to render each value.
+    fn format_cells<R, DI>(&mut self, data: &DI)
+    where
+        DI: DataIndex,
+        R: RenderCell<DI::DType>,
+    {
+        debug_assert!(data.len() >= self.nrows);
+        let label = self.labels[self.columns.len()];
+        let options = &self.options;
+        // rendered text is copied into a single buffer for the column
+        let mut column = StrFieldData::with_capacity(self.nrows, 0);
+        for i in 0..self.nrows {
+            match data.get_datum(i).unwrap() {
+                Value::Exists(value) => {
+                    column.push(Value::Exists(&R::render(value, label, options)))
+                }
+                Value::Na => column.push(Value::Na),
+            }
+        }
+
+        if self.table {
+            let (mut count, mut sum) = (0usize, 0.0);
+            let (mut min, mut max) = (f64::INFINITY, f64::NEG_INFINITY);
+            for value in data.iter() {
+                if let Value::Exists(value) = value {
+                    count += 1;
+                    let value = R::numeric_value(value).unwrap_or(0.0);
+                    sum += value;
+                    if !value.is_nan() {
+                        min = min.min(value);
+                        max = max.max(value);
+                    }
+                }
+            }
+            if options.colorize {
+                let highlight = R::NUMERIC && min < max;
+                self.styles.push(
+                    (0..self.nrows)
+                        .map(|i| match data.get_datum(i).unwrap() {
+                            Value::Na => NA_STYLE,
+                            Value::Exists(value) => match R::numeric_value(value) {
+                                Some(value) if highlight && value == min => MIN_STYLE,
+                                Some(value) if highlight && value == max => MAX_STYLE,
+                                _ => RESET_STYLE,
+                            },
+                        })
+                        .collect(),
+                );
+            }
+            self.footers.push(
+                options
+                    .footers
+                    .iter()
+                    .map(|footer| match *footer {
+                        Footer::Count => count.to_string(),
+                        _ if !R::NUMERIC => String::new(),
+                        Footer::Sum => R::display_sum(sum, label, options),
+                        Footer::Mean if count == 0 => Value::<String>::Na.to_string(),
+                        Footer::Mean => options.format_float(label, &(sum / count as f64)),
+                    })
+                    .collect(),
+            );
+        }
+        self.columns.push(column);
+    }
+}
+impl<DType> Func<DType> for FormatCellsFn
+where
+    DType: DisplayCell,
+{
+    type Output = ();
+    fn call<DI>(&mut self, data: &DI) -> Self::Output
+    where
+        DI: DataIndex<DType = DType>,
+    {
+        self.format_cells::<WithDisplayCell, _>(data);
+    }
+}
+impl<DType> PartialCall<DType, Displayed> for FormatCellsFn
+where
+    DType: Display,
+{
+    fn partial_call<DI>(&mut self, data: &DI)
+    where
+        DI: DataIndex<DType = DType>,
+    {
+        self.format_cells::<WithDisplay, _>(data);
+    }
+}
+impl FuncDefault for FormatCellsFn {
+    type Output = ();
+    fn call(&mut self) -> Self::Output {
+        self.columns.push((0..self.nrows).map(|_| "").collect());
+        if self.table {
+            self.footers
+                .push(vec![String::new(); self.options.footers.len()]);
+            if self.options.colorize {
+                self.styles.push(vec![RESET_STYLE; self.nrows]);
+            }
+        }
+    }
+}
+macro_rules! impl_format_cells_is_impl {
+    ($($dtype:ty)*) => {$(
+        impl IsImplemented<FormatCellsFn> for $dtype {
+            type IsImpl = Implemented;
+        }
+    )*}
+}
+impl_format_cells_is_impl![String &str ArenaStr f64 f32 u64 u32 i64 i32 bool FloatKey];
+
+/// Function formerly used to display the cells of a [DataView](../view/struct.DataView.html).
+/// Data types marked with an [IsImplemented](../partial/trait.IsImplemented.html) implementation
+/// for `AddCellToRowFn` are still displayed, with their `Display` implementation (as if marked
+/// [Displayed](struct.Displayed.html) for [FormatCellsFn](struct.FormatCellsFn.html)).
+#[deprecated(note = "implement `IsImplemented<FormatCellsFn>` with `IsImpl = Displayed` instead")]
+#[derive(Debug)]
+pub struct AddCellToRowFn {
+    _private: (),
+}
+
+/// Trait mapping the implementation status of a data type for the deprecated
+/// [AddCellToRowFn](struct.AddCellToRowFn.html) to its status for
+/// [FormatCellsFn](struct.FormatCellsFn.html).
+pub trait AddCellIsImpl {
+    /// The implementation status for `FormatCellsFn`:
+    /// [Displayed](struct.Displayed.html) for implemented data types, and
+    /// [Unimplemented](../partial/struct.Unimplemented.html) otherwise.
+    type FormatCellsIsImpl;
+}
+impl AddCellIsImpl for Implemented {
+    type FormatCellsIsImpl = Displayed;
+}
+impl AddCellIsImpl for Unimplemented {
+    type FormatCellsIsImpl = Unimplemented;
+}
+#[allow(deprecated)]
+impl<DType> IsImplemented<FormatCellsFn> for DType
+where
+    DType: IsImplemented<AddCellToRowFn>,
+    <DType as IsImplemented<AddCellToRowFn>>::IsImpl: AddCellIsImpl,
+{
+    type IsImpl =
+        <<DType as IsImplemented<AddCellToRowFn>>::IsImpl as AddCellIsImpl>::FormatCellsIsImpl;
+}
+
+/// A [DataView](../view/struct.DataView.html) along with the
+/// [DisplayOptions](struct.DisplayOptions.html) used to display it. Created by
+/// [DataView::display](../view/struct.DataView.html#method.display).
+#[derive(Debug)]
+pub struct DisplayView<'a, Labels: 'a, Frames: 'a> {
+    view: &'a DataView<Labels, Frames>,
+    options: &'a DisplayOptions,
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Returns an object which displays this `DataView` as a table, with values rendered
+    /// according to `options`.
+    pub fn display<'a>(&'a self, options: &'a DisplayOptions) -> DisplayView<'a, Labels, Frames> {
+        DisplayView {
+            view: self,
+            options,
+        }
+    }
+}
+
+impl<'a, Labels, Frames> Display for DisplayView<'a, Labels, Frames>
+where
+    Frames: Len + NRows,
+    DataView<Labels, Frames>: FieldMap<FormatCellsFn>,
+    Labels: StrLabels,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if Frames::is_empty() {
+            return write!(f, "Empty DataView");
+        }
+        let labels = <Labels as StrLabels>::labels_vec();
+        let nrows = self.view.nrows().min(self.options.max_rows);
+        let mut func = FormatCellsFn::new(self.options, labels.clone(), nrows).for_table();
+        self.view.field_map(&mut func);
+
+        write_table(
+            f,
+            &table_cells(&labels, &func),
+            self.options.colorize,
+            false,
+        )
+    }
+}
+
+// A line of a table cell, along with the style (ANSI escape sequence) it is displayed with.
+struct CellLine<'a> {
+    text: Cow<'a, str>,
+    style: &'static str,
+}
+
+// Collects the lines of the cells of the table of values (and footers) rendered by `func`, by row
+// (starting with the title row) and then by column. Lines are truncated to the maximum width set
+// in the options.
+fn table_cells<'a>(labels: &[&'a str], func: &'a FormatCellsFn) -> Vec<Vec<Vec<CellLine<'a>>>> {
+    let max_width = func.options.max_width;
+    let cell = |text: &'a str, style: &'static str| cell_lines(text, style, max_width);
+    // footer rows are labeled in an additional leading column
+    let footers = &func.options.footers;
+    let leading = |text: &'a str| {
+        if footers.is_empty() {
+            vec![]
+        } else {
+            vec![cell(text, RESET_STYLE)]
+        }
+    };
+
+    let mut rows = vec![];
+    let mut titles = leading("");
+    titles.extend(labels.iter().map(|label| cell(label, RESET_STYLE)));
+    rows.push(titles);
+    for i in 0..func.nrows {
+        let mut row = leading("");
+        row.extend(func.columns.iter().enumerate().map(|(j, column)| {
+            let style = func.styles.get(j).map_or(RESET_STYLE, |styles| styles[i]);
+            match column.get(i).unwrap() {
+                Value::Exists(text) => cell(text, style),
+                Value::Na => cell("NA", style),
+            }
+        }));
+        rows.push(row);
+    }
+    for (j, footer) in footers.iter().enumerate() {
+        let mut row = leading(footer.name());
+        row.extend(
+            func.footers
+                .iter()
+                .map(|column| cell(&column[j], RESET_STYLE)),
+        );
+        rows.push(row);
+    }
+    rows
+}
+
+// Splits the text of a cell into lines, each truncated to `max_width` (if any).
+fn cell_lines<'a>(
+    text: &'a str,
+    style: &'static str,
+    max_width: Option<usize>,
+) -> Vec<CellLine<'a>> {
+    text.lines()
+        .map(|text| CellLine {
+            text: match max_width {
+                Some(max_width) if text.width() > max_width => truncate(text, max_width).into(),
+                _ => text.into(),
+            },
+            style,
+        })
+        .collect()
+}
+
+// Truncates `text` to at most `max_width` terminal columns, ending truncated text with `…`.
+fn truncate(text: &str, max_width: usize) -> String {
+    let mut truncated = String::new();
+    let mut width = 0;
+    for c in text.chars() {
+        width += c.width().unwrap_or(0);
+        if width + 1 > max_width {
+            break;
+        }
+        truncated.push(c);
+    }
+    truncated.push('…');
+    truncated
+}
+
+// Writes the table of cells `rows` (see `table_cells`), with a title separator line and
+// '|'-separated columns, each padded to the displayed width of its widest line. Bordered tables
+// also have borders on the left and right, and separator lines above and below the table. Lines
+// are displayed with their styles if `colorize` is set.
+fn write_table(
+    f: &mut Formatter,
+    rows: &[Vec<Vec<CellLine>>],
+    colorize: bool,
+    borders: bool,
+) -> fmt::Result {
+    let ncols = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut widths = vec![0; ncols];
+    for row in rows {
+        for (j, lines) in row.iter().enumerate() {
+            for line in lines {
+                widths[j] = widths[j].max(line.text.width());
+            }
+        }
+    }
+
+    let border = if borders { "|" } else { "" };
+    let separator = {
+        let columns = widths
+            .iter()
+            .map(|width| "-".repeat(width + 2))
+            .collect::<Vec<_>>()
+            .join("+");
+        if borders {
+            format!("+{}+", columns)
+        } else {
+            columns
+        }
+    };
+    let border_line = |f: &mut Formatter| {
+        if borders {
+            writeln!(f, "{}", separator)
+        } else {
+            Ok(())
+        }
+    };
+    border_line(f)?;
+    for (r, row) in rows.iter().enumerate() {
+        let height = row
+            .iter()
+            .map(|lines| lines.len())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        for l in 0..height {
+            write!(f, "{}", border)?;
+            for (j, &width) in widths.iter().enumerate() {
+                if j > 0 {
+                    write!(f, "|")?;
+                }
+                let line = row.get(j).and_then(|lines| lines.get(l));
+                let text = line.map_or("", |line| line.text.as_ref());
+                match line {
+                    Some(line) if colorize => write!(f, " {}{}{}", line.style, text, RESET_STYLE)?,
+                    _ => write!(f, " {}", text)?,
+                }
+                // as with prettytable, the last column is only padded if the table has borders
+                if j + 1 < ncols || borders {
+                    write!(f, "{:1$}", "", width - text.width())?;
+                }
+                write!(f, " ")?;
+            }
+            writeln!(f, "{}", border)?;
+        }
+        if r == 0 {
+            writeln!(f, "{}", separator)?;
+        }
+    }
+    border_line(f)
+}
+
+/// A table of text, displayed with the same layout as a
+/// [DataView](../view/struct.DataView.html): a row of titles, followed by a separator line and a
+/// line for each row of cells, with '|'-separated columns padded to the displayed width of their
+/// widest value (except for the last column, unless the table has
+/// [borders](#method.borders)). Cells containing line breaks span multiple lines.
+///
+/// # Example
+///
+/// ```
+/// # extern crate agnes;
+/// use agnes::display::TextTable;
+///
+/// # fn main() {
+/// let mut table = TextTable::new(&["Field", "Type"]);
+/// table.add_row(&["Station", "String"]);
+/// table.add_row(&["Count", "u64"]);
+/// assert_eq!(
+///     table.to_string(),
+///     " Field   | Type \n\
+///      ---------+--------\n \
+///      Station | String \n \
+///      Count   | u64 \n"
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TextTable {
+    titles: Vec<String>,
+    rows: Vec<Vec<String>>,
+    borders: bool,
+}
+impl TextTable {
+    /// Creates a new table with titles `titles`, and no rows.
+    pub fn new<I>(titles: I) -> TextTable
+    where
+        I: IntoIterator,
+        I::Item: ToString,
+    {
+        TextTable {
+            titles: titles.into_iter().map(|title| title.to_string()).collect(),
+            rows: vec![],
+            borders: false,
+        }
+    }
+
+    /// Sets whether the table is displayed with borders: '|' borders on the left and right of
+    /// each line, and separator lines above and below the table.
+    pub fn borders(mut self, borders: bool) -> TextTable {
+        self.borders = borders;
+        self
+    }
+
+    /// Appends a row of cells to this table.
+    pub fn add_row<I>(&mut self, row: I)
+    where
+        I: IntoIterator,
+        I::Item: ToString,
+    {
+        self.rows
+            .push(row.into_iter().map(|cell| cell.to_string()).collect());
+    }
+}
+impl Display for TextTable {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let rows = Some(&self.titles)
+            .into_iter()
+            .chain(&self.rows)
+            .map(|row| {
+                row.iter()
+                    .map(|text| cell_lines(text, RESET_STYLE, None))
+                    .collect()
+            })
+            .collect::<Vec<_>>();
+        write_table(f, &rows, false, self.borders)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cons::Nil;
+    use field::FieldData;
+    use store::DataStore;
+
+    tablespace![
+        table readings {
+            B: f64,
+            Name: String,
+        }
+    ];
+
+    // a data type without a `DisplayCell` implementation
+    #[derive(Debug, Clone, Default)]
+    pub struct Point(i64, i64);
+    impl Display for Point {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+            write!(f, "({}, {})", self.0, self.1)
+        }
+    }
+    impl IsImplemented<FormatCellsFn> for Point {
+        type IsImpl = Displayed;
+    }
+    // labels of custom data types are declared separately, since `tablespace` field types must be
+    // nameable from within the generated table modules
+    mod points {
+        use super::{Grade, Point};
+        first_label![Location, ::typenum::U4, Point];
+        next_label![Mark, Location, Grade];
+    }
+    // a data type marked for the deprecated `AddCellToRowFn`
+    #[derive(Debug, Clone, Default)]
+    pub struct Grade(char);
+    impl Display for Grade {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+    #[allow(deprecated)]
+    impl IsImplemented<AddCellToRowFn> for Grade {
+        type IsImpl = Implemented;
+    }
+
+    #[test]
+    fn display_cell() {
+        let options = DisplayOptions::new();
+        assert_eq!(1234.5678f64.display_cell("A", &options), "1234.5678");
+        assert_eq!(1234567u64.display_cell("A", &options), "1234567");
+
+        let options = options
+            .precision(2)
+            .thousands(',')
+            .scientific_threshold(1e6);
+        assert_eq!(1234.5678f64.display_cell("A", &options), "1,234.57");
+        assert_eq!(1234567u64.display_cell("A", &options), "1,234,567");
+        assert_eq!((-25i64).display_cell("A", &options), "-25");
+        assert_eq!(12345678.9f64.display_cell("A", &options), "1.23e7");
+        assert_eq!(0.0000001f32.display_cell("A", &options), "1.00e-7");
+        assert_eq!(0.0f64.display_cell("A", &options), "0.00");
+        assert_eq!(f64::NAN.display_cell("A", &options), "NaN");
+        assert_eq!(FloatKey::new(0.5, 1).display_cell("A", &options), "0.50");
+        assert_eq!("a".to_string().display_cell("A", &options), "a");
+
+        let options = options.field_format::<readings::B>("+8.1".parse().unwrap());
+        assert_eq!(1234.5678f64.display_cell("B", &options), " +1234.6");
+        assert_eq!(12345678.9f64.display_cell("B", &options), "  +1.2e7");
+        assert_eq!(1234.5678f64.display_cell("A", &options), "1,234.57");
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn display_view() {
+        use test_utils::*;
+
+        let dv = sample_merged_emp_table();
+        let options = DisplayOptions::new();
+        assert_eq!(dv.display(&options).to_string(), dv.to_string());
+
+        let options = options.max_rows(2).precision(3);
+        let table = dv.display(&options).to_string();
+        assert_eq!(table.lines().count(), 4);
+        assert!(table.contains("47.300"));
+        assert!(!table.contains("98.300"));
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn display_colorized() {
+        use test_utils::*;
+
+        let dv = sample_merged_emp_table();
+        let table = dv
+            .display(&DisplayOptions::new().colorize(true))
+            .to_string();
+        let plain = dv.to_string();
+        // escape sequences don't affect the column widths
+        assert_eq!(
+            table
+                .replace(NA_STYLE, "")
+                .replace(MIN_STYLE, "")
+                .replace(MAX_STYLE, "")
+                .replace(RESET_STYLE, ""),
+            plain
+        );
+        assert!(table.contains("\x1b[0mEmpId\x1b[0m"));
+        assert!(table.contains("\x1b[1;34m0\x1b[0m"));
+        assert!(table.contains("\x1b[1;32m10\x1b[0m"));
+        assert!(table.contains("\x1b[1;34m-1.2\x1b[0m"));
+        assert!(table.contains("\x1b[1;32m98.3\x1b[0m"));
+        assert!(!table.contains("\x1b[1;34mfalse"));
+
+        let options = DisplayOptions::new().footer(Footer::Mean);
+        let table = dv.display(&options.clone().colorize(true)).to_string();
+        assert_eq!(
+            table
+                .replace(MIN_STYLE, "")
+                .replace(RESET_STYLE, "")
+                .replace(MAX_STYLE, ""),
+            dv.display(&options).to_string()
+        );
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<readings::B, _>(FieldData::from_field_vec(vec![
+                Value::Exists(1.5),
+                Value::Na,
+            ]))
+            .into_view();
+        let table = dv
+            .display(&DisplayOptions::new().colorize(true))
+            .to_string();
+        assert!(table.contains("\x1b[2;31mNA\x1b[0m"));
+        assert!(!dv.to_string().contains('\x1b'));
+    }
+
+    #[test]
+    fn display_wide_chars() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<readings::Name, _>(FieldData::<String>::from_vec(vec![
+                "東京都".to_string(),
+                "a👨‍👩‍👧".to_string(),
+                "two\nlines".to_string(),
+            ]))
+            .push_back_field::<readings::B, _>(FieldData::<f64>::from_vec(vec![1.0, 2.0, 3.0]))
+            .into_view();
+        assert_eq!(
+            dv.to_string(),
+            " Name   | B \n\
+             --------+---\n \
+             東京都 | 1 \n \
+             a👨‍👩‍👧    | 2 \n \
+             two    | 3 \n \
+             lines  |  \n"
+        );
+        assert_eq!(
+            dv.display(&DisplayOptions::new().max_width(4)).to_string(),
+            " Name | B \n\
+             ------+---\n \
+             東…  | 1 \n \
+             a👨‍👩‍👧  | 2 \n \
+             two  | 3 \n \
+             lin… |  \n"
+        );
+    }
+
+    #[test]
+    fn display_fallback() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<points::Location, _>(FieldData::<Point>::from_vec(vec![
+                Point(1, 2),
+                Point(-3, 4),
+            ]))
+            .push_back_field::<points::Mark, _>(FieldData::<Grade>::from_vec(vec![
+                Grade('A'),
+                Grade('C'),
+            ]))
+            .push_back_field::<readings::B, _>(FieldData::<f64>::from_vec(vec![1.0, 2.0]))
+            .into_view();
+        assert_eq!(
+            dv.to_string(),
+            " Location | Mark | B \n\
+             ----------+------+---\n \
+             (1, 2)   | A    | 1 \n \
+             (-3, 4)  | C    | 2 \n"
+        );
+    }
+
+    #[test]
+    fn text_table() {
+        let mut table = TextTable::new(&["Field", "Total"]).borders(true);
+        table.add_row(&["EmpId", "56"]);
+        table.add_row(&["東京都", "two\nlines"]);
+        assert_eq!(
+            table.to_string(),
+            "+--------+-------+\n\
+             | Field  | Total |\n\
+             +--------+-------+\n\
+             | EmpId  | 56    |\n\
+             | 東京都 | two   |\n\
+             |        | lines |\n\
+             +--------+-------+\n"
+        );
+        assert_eq!(
+            TextTable::new(&["Field"]).to_string(),
+            " Field \n\
+             -------\n"
+        );
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn display_footers() {
+        use test_utils::*;
+
+        let dv = sample_merged_emp_table();
+        let options = DisplayOptions::new()
+            .precision(2)
+            .max_rows(0)
+            .footer(Footer::Sum)
+            .footer(Footer::Mean)
+            .footer(Footer::Count);
+        let table = dv.display(&options).to_string();
+        let rows = table
+            .lines()
+            .skip(2)
+            .map(|line| line.split('|').map(str::trim).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                vec!["sum", "40", "16", "", "-13", "", "238.60"],
+                vec!["mean", "5.71", "2.29", "", "-1.86", "", "34.09"],
+                vec!["count", "7", "7", "7", "7", "7", "7"],
+            ]
+        );
+    }
+}
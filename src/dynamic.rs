@@ -0,0 +1,456 @@
+/*!
+Runtime-typed tables, for working with CSV files whose fields are not known at compile time.
+
+The rest of the agnes API identifies fields with type-level labels, which requires the fields (and
+their types) of a data source to be specified at compile time. A
+[DynTable](struct.DynTable.html) instead loads every field of a
+[CsvSource](../source/csv/struct.CsvSource.html), naming each field by the source's header row and
+typing it with the type inferred when the source was analyzed (sniffed). Fields are then selected
+by name at runtime, which allows tables to be filtered and joined on fields chosen while the
+program is running (as done by the `agnes-cli` command-line tool).
+*/
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use csv_sniffer::Type;
+
+use access::DataIndex;
+use erased;
+use error::*;
+use field::{FieldData, FieldIdent};
+use join::Equal;
+use observer::NoObserver;
+use permute::SortOrder;
+use query::{compare_values, matching_records, Query};
+use source::csv::CsvSource;
+use source::decode::{decode, parse_value};
+use stats::NaCount;
+use value::Value;
+
+/// A field of a [DynTable](struct.DynTable.html), holding values of the type inferred for its
+/// column of the source CSV file.
+#[derive(Debug, Clone)]
+pub enum DynColumn {
+    /// Field of unsigned integer values.
+    Unsigned(FieldData<u64>),
+    /// Field of signed integer values.
+    Signed(FieldData<i64>),
+    /// Field of floating-point values.
+    Float(FieldData<f64>),
+    /// Field of boolean values.
+    Boolean(FieldData<bool>),
+    /// Field of text values.
+    Text(FieldData<String>),
+}
+
+// Evaluates `$body` with `$data` bound to the `FieldData` held by the `DynColumn` `$column`.
+macro_rules! with_data {
+    ($column:expr, $data:ident => $body:expr) => {
+        match *$column {
+            DynColumn::Unsigned(ref $data) => $body,
+            DynColumn::Signed(ref $data) => $body,
+            DynColumn::Float(ref $data) => $body,
+            DynColumn::Boolean(ref $data) => $body,
+            DynColumn::Text(ref $data) => $body,
+        }
+    };
+}
+
+impl DynColumn {
+    // Creates an empty field for values of type `ty`.
+    fn new(ty: Type) -> DynColumn {
+        match ty {
+            Type::Unsigned => DynColumn::Unsigned(FieldData::default()),
+            Type::Signed => DynColumn::Signed(FieldData::default()),
+            Type::Float => DynColumn::Float(FieldData::default()),
+            Type::Boolean => DynColumn::Boolean(FieldData::default()),
+            Type::Text => DynColumn::Text(FieldData::default()),
+        }
+    }
+
+    /// Returns the type of the values of this field.
+    pub fn ty(&self) -> Type {
+        match *self {
+            DynColumn::Unsigned(_) => Type::Unsigned,
+            DynColumn::Signed(_) => Type::Signed,
+            DynColumn::Float(_) => Type::Float,
+            DynColumn::Boolean(_) => Type::Boolean,
+            DynColumn::Text(_) => Type::Text,
+        }
+    }
+
+    /// Returns the number of values (existing or missing) in this field.
+    pub fn len(&self) -> usize {
+        with_data!(self, data => data.len())
+    }
+
+    /// Returns whether or not this field contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of existing (non-missing) values in this field.
+    pub fn num_exists(&self) -> usize {
+        with_data!(self, data => data.num_exists())
+    }
+
+    /// Returns the value at index `idx` of this field formatted as text (`NA` for missing
+    /// values), or `None` if `idx` is out of bounds.
+    pub fn format_value(&self, idx: usize) -> Option<String> {
+        with_data!(self, data => data.get(idx).map(|value| value.to_string()))
+    }
+
+    // Parses `text` as a value of the type of this field (with empty text as a missing value if
+    // `empty_as_na` is set), and appends it to this field.
+    fn push_parsed(&mut self, text: &str, empty_as_na: bool) -> Result<()> {
+        match *self {
+            DynColumn::Unsigned(ref mut data) => data.push_val(parse_value(text, empty_as_na)?),
+            DynColumn::Signed(ref mut data) => data.push_val(parse_value(text, empty_as_na)?),
+            DynColumn::Float(ref mut data) => data.push_val(parse_value(text, empty_as_na)?),
+            DynColumn::Boolean(ref mut data) => data.push_val(parse_value(text, empty_as_na)?),
+            DynColumn::Text(ref mut data) => data.push_val(parse_value(text, empty_as_na)?),
+        }
+        Ok(())
+    }
+
+    // Returns the indices of the existing values of this field which are equal to `text` (parsed
+    // as a value of the type of this field).
+    fn matching_indices(&self, text: &str) -> Result<Vec<usize>> {
+        fn matching<T: Debug + PartialEq>(data: &FieldData<T>, value: Value<T>) -> Vec<usize> {
+            match value {
+                Value::Exists(ref value) => (0..data.len())
+                    .filter(|&idx| data.get_datum(idx).unwrap() == Value::Exists(value))
+                    .collect(),
+                Value::Na => vec![],
+            }
+        }
+        Ok(with_data!(self, data => matching(data, parse(text)?)))
+    }
+
+    // Returns a new field containing the values of this field at `indices`, in order.
+    fn subset(&self, indices: &[usize]) -> DynColumn {
+        fn subset<T: Debug + Clone + Default>(
+            data: &FieldData<T>,
+            indices: &[usize],
+        ) -> FieldData<T> {
+            indices
+                .iter()
+                .map(|&idx| data.get_datum(idx).unwrap().cloned())
+                .collect()
+        }
+        match *self {
+            DynColumn::Unsigned(ref data) => DynColumn::Unsigned(subset(data, indices)),
+            DynColumn::Signed(ref data) => DynColumn::Signed(subset(data, indices)),
+            DynColumn::Float(ref data) => DynColumn::Float(subset(data, indices)),
+            DynColumn::Boolean(ref data) => DynColumn::Boolean(subset(data, indices)),
+            DynColumn::Text(ref data) => DynColumn::Text(subset(data, indices)),
+        }
+    }
+}
+
+// Parses `text` (trimmed of whitespace) as a value of type `T`, with empty text as a missing value.
+fn parse<T>(text: &str) -> Result<Value<T>>
+where
+    T: FromStr,
+    ParseError: From<T::Err>,
+{
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        Ok(Value::Na)
+    } else {
+        trimmed
+            .parse()
+            .map(Value::Exists)
+            .map_err(|err| AgnesError::Parse(ParseError::from(err)))
+    }
+}
+
+// Computes the pairs of indices into `left` and `right` with equal (existing) values using the
+// sort-merge join kernel, ordered by left index and then by right index.
+fn merge_indices<T>(left: &FieldData<T>, right: &FieldData<T>) -> Result<(Vec<usize>, Vec<usize>)>
+where
+    T: Debug + Ord,
+{
+    let (left_indices, right_indices) = erased::merge_indices::<Equal, _>(
+        left,
+        &left.sort_order(),
+        right,
+        &right.sort_order(),
+        &mut NoObserver,
+    )?;
+    // the sort orders are stable, so matches with the same left index are already in right order
+    let mut pairs = left_indices
+        .into_iter()
+        .zip(right_indices)
+        .collect::<Vec<_>>();
+    pairs.sort_by_key(|&(left_idx, _)| left_idx);
+    Ok(pairs.into_iter().unzip())
+}
+
+/// A table of data whose fields are named and typed at runtime. See the
+/// [module-level documentation](index.html) for details.
+#[derive(Debug, Clone, Default)]
+pub struct DynTable {
+    names: Vec<String>,
+    columns: Vec<DynColumn>,
+}
+
+impl DynTable {
+    /// Loads every field of the CSV source `src` into a new `DynTable`, reading at most `limit`
+    /// records (or all records if `limit` is `None`). Fields are named by the header row of the
+    /// source (or by their zero-based index if it has no header row), and their values are parsed
+    /// as the types inferred for them when the source was analyzed. Records are read with the
+    /// options `src` was created with (see
+    /// [SniffOptions](../source/csv/struct.SniffOptions.html)): its encoding, header
+    /// normalization, footer, row limit, ragged-row policy, and handling of empty values.
+    ///
+    /// # Errors
+    /// Fails if unable to read the source, if a record does not have the sniffed number of
+    /// fields (unless allowed by the source's ragged-row policy), or if a value cannot be parsed as
+    /// the type inferred for its field.
+    pub fn from_csv(src: &CsvSource, limit: Option<usize>) -> Result<DynTable> {
+        let empty_as_na = src.empty_as_na();
+        let mut columns = src
+            .metadata()
+            .types
+            .iter()
+            .map(|&ty| DynColumn::new(ty))
+            .collect::<Vec<_>>();
+        let names = src.scan_records(limit, |_, record| {
+            for (idx, column) in columns.iter_mut().enumerate() {
+                column.push_parsed(&decode(record.get(idx).unwrap_or(b""))?, empty_as_na)?;
+            }
+            Ok(())
+        })?;
+        Ok(DynTable { names, columns })
+    }
+
+    /// Returns the names of the fields of this table, in order.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Returns the fields of this table, in order.
+    pub fn columns(&self) -> &[DynColumn] {
+        &self.columns
+    }
+
+    /// Returns the number of records in this table.
+    pub fn nrows(&self) -> usize {
+        self.columns.first().map_or(0, |column| column.len())
+    }
+
+    /// Returns the field named `name`.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::FieldNotFound` if no field is named `name`.
+    pub fn column(&self, name: &str) -> Result<&DynColumn> {
+        self.position(name).map(|idx| &self.columns[idx])
+    }
+
+    /// Returns a new table containing the records of this table (in order) whose value of the
+    /// field named `name` equals `value`, parsed as a value of the type of that field. Missing
+    /// values never match.
+    ///
+    /// # Errors
+    /// Fails if no field is named `name`, or if `value` cannot be parsed as the type of that
+    /// field.
+    pub fn filter_eq(&self, name: &str, value: &str) -> Result<DynTable> {
+        let indices = self.column(name)?.matching_indices(value)?;
+        Ok(self.subset(&indices))
+    }
+
+    // Returns a new table containing the records of this table at `indices`, in order.
+    fn subset(&self, indices: &[usize]) -> DynTable {
+        DynTable {
+            names: self.names.clone(),
+            columns: self
+                .columns
+                .iter()
+                .map(|column| column.subset(indices))
+                .collect(),
+        }
+    }
+
+    /// Returns a new table containing the records of this table (in order) matching the query
+    /// expression `expr`, with fields referred to by name. See the
+    /// [query](../query/index.html) module for the expression syntax.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Query` if `expr` is not a valid expression, refers to a field which
+    /// does not exist, or compares a field with a value of a different kind (e.g. a text field with
+    /// a number).
+    pub fn query(&self, expr: &str) -> Result<DynTable> {
+        let query = expr.parse::<Query>()?;
+        let mut results = vec![];
+        for (field, op, value) in query.comparisons() {
+            let column = match self.position(field) {
+                Ok(idx) => &self.columns[idx],
+                Err(_) => return Err(AgnesError::Query(format!("field '{}' not found", field))),
+            };
+            results.push(with_data!(column, data => compare_values(field, data, op, value))?);
+        }
+        let indices = matching_records(&query, results, self.nrows());
+        Ok(self.subset(&indices))
+    }
+
+    /// Computes the inner equi-join of this table with the table `right`, on the field named
+    /// `left_field` of this table and the field named `right_field` of `right`, using the same
+    /// sort-merge join kernel as [DataView::join](../view/struct.DataView.html#method.join). The
+    /// joined table contains one record for each pair of records with equal (existing) values of
+    /// the join fields, ordered by the record of this table, and then by the record of `right`. It
+    /// contains the fields of this table followed by the fields of `right` except `right_field`.
+    ///
+    /// # Errors
+    /// Fails if either join field does not exist, if the join fields have different types, or if
+    /// the join fields hold floating-point values (which cannot be compared for equality).
+    pub fn join(&self, right: &DynTable, left_field: &str, right_field: &str) -> Result<DynTable> {
+        let right_idx = right.position(right_field)?;
+        let (left_indices, right_indices) =
+            match (self.column(left_field)?, &right.columns[right_idx]) {
+                (DynColumn::Unsigned(l), DynColumn::Unsigned(r)) => merge_indices(l, r)?,
+                (DynColumn::Signed(l), DynColumn::Signed(r)) => merge_indices(l, r)?,
+                (DynColumn::Boolean(l), DynColumn::Boolean(r)) => merge_indices(l, r)?,
+                (DynColumn::Text(l), DynColumn::Text(r)) => merge_indices(l, r)?,
+                (l, r) => {
+                    return Err(AgnesError::UnsupportedType(format!(
+                        "cannot join {:?} field '{}' with {:?} field '{}'",
+                        l.ty(),
+                        left_field,
+                        r.ty(),
+                        right_field
+                    )))
+                }
+            };
+
+        let mut joined = DynTable {
+            names: self.names.clone(),
+            columns: self
+                .columns
+                .iter()
+                .map(|column| column.subset(&left_indices))
+                .collect(),
+        };
+        for (idx, (name, column)) in right.names.iter().zip(&right.columns).enumerate() {
+            if idx != right_idx {
+                joined.names.push(name.clone());
+                joined.columns.push(column.subset(&right_indices));
+            }
+        }
+        Ok(joined)
+    }
+
+    // Returns the index of the field named `name`.
+    fn position(&self, name: &str) -> Result<usize> {
+        self.names
+            .iter()
+            .position(|field| field == name)
+            .ok_or_else(|| AgnesError::FieldNotFound(FieldIdent::Name(name.to_string())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+
+    use tempfile::NamedTempFile;
+
+    fn data_file(filename: &str) -> PathBuf {
+        Path::new(file!()) // start as this file
+            .parent()
+            .unwrap() // navigate up to src directory
+            .parent()
+            .unwrap() // navigate up to root directory
+            .join("tests") // navigate into integration tests directory
+            .join("data") // navigate into data directory
+            .join(filename) // navigate to target file
+    }
+
+    fn load(filename: &str) -> DynTable {
+        DynTable::from_csv(&CsvSource::new(data_file(filename)).unwrap(), None).unwrap()
+    }
+
+    fn values(table: &DynTable, name: &str) -> Vec<String> {
+        let column = table.column(name).unwrap();
+        (0..column.len())
+            .map(|idx| column.format_value(idx).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn from_csv() {
+        let table = load("sample1.csv");
+        assert_eq!(table.names(), &["state", "val1", "val2"]);
+        assert_eq!(table.nrows(), 8);
+        let types = table.columns().iter().map(|c| c.ty()).collect::<Vec<_>>();
+        assert_eq!(types, vec![Type::Text, Type::Unsigned, Type::Float]);
+        assert_eq!(values(&table, "val1")[..3], ["4", "54", "23"]);
+        assert!(table.column("val3").is_err());
+
+        let head = DynTable::from_csv(&CsvSource::new(data_file("sample1.csv")).unwrap(), Some(2))
+            .unwrap();
+        assert_eq!(values(&head, "state"), vec!["OH", "PA"]);
+    }
+
+    #[test]
+    fn short_record() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "a,b,c\n1,2,3\n4,5,6\n7,8\n10,11,12\n").unwrap();
+        let src = CsvSource::new(file.path().to_path_buf()).unwrap();
+        assert!(DynTable::from_csv(&src, None).is_err());
+        assert_eq!(DynTable::from_csv(&src, Some(2)).unwrap().nrows(), 2);
+    }
+
+    #[test]
+    fn filter_eq() {
+        let table = load("sample1.csv");
+        let filtered = table.filter_eq("state", "PA").unwrap();
+        assert_eq!(values(&filtered, "val1"), vec!["54"]);
+        assert_eq!(filtered.names(), table.names());
+        assert_eq!(table.filter_eq("val1", "54").unwrap().nrows(), 1);
+        assert_eq!(table.filter_eq("val1", "55").unwrap().nrows(), 0);
+        // values which cannot be parsed as the field's type fail
+        assert!(table.filter_eq("val1", "PA").is_err());
+        assert!(table.filter_eq("val3", "1").is_err());
+    }
+
+    #[test]
+    fn query() {
+        let table = load("sample1.csv");
+        let filtered = table.query("state == \"PA\" || val1 < 10").unwrap();
+        assert_eq!(values(&filtered, "state"), vec!["OH", "PA"]);
+        assert_eq!(filtered.names(), table.names());
+        assert_eq!(table.query("!(val1 >= 10)").unwrap().nrows(), 1);
+        assert!(table.query("val1 == \"PA\"").is_err());
+        assert!(table.query("val3 == 1").is_err());
+        assert!(table.query("val1 ==").is_err());
+    }
+
+    #[test]
+    fn join() {
+        let (left, right) = (load("sample1.csv"), load("states.csv"));
+        let joined = left.join(&right, "state", "state").unwrap();
+        assert_eq!(
+            joined.names(),
+            &["state", "val1", "val2", "region", "pop_rank"]
+        );
+        // records are in left order; records without a match are dropped
+        assert_eq!(values(&joined, "state"), vec!["OH", "PA", "CA", "NY"]);
+        assert_eq!(
+            values(&joined, "region"),
+            vec!["Midwest", "Northeast", "West", "Northeast"]
+        );
+
+        let joined = left.join(&right, "val1", "pop_rank").unwrap();
+        assert_eq!(values(&joined, "val1"), vec!["4"]);
+        assert_eq!(joined.names()[3], "state");
+
+        // join fields must exist and have the same (non-floating-point) type
+        assert!(left.join(&right, "region", "region").is_err());
+        assert!(left.join(&right, "state", "pop_rank").is_err());
+        assert!(left.join(&left, "val2", "val2").is_err());
+    }
+}
@@ -0,0 +1,753 @@
+/*!
+Type-erased execution layer for heavy `DataView` operations.
+
+Most of the agnes API is fully typed: fields are selected from cons-lists using type-level labels,
+and operations are generic over the resulting field types. Compiling the full bodies of
+operations like [join](../view/struct.DataView.html#method.join),
+[unique_indices](../view/struct.DataView.html#method.unique_indices), and
+[aggregate](../view/struct.DataView.html#method.aggregate) for every combination of field list
+and accessor type produces a large amount of (nearly) identical code. Instead, these operations
+convert their typed inputs into trait objects and delegate to the non-generic (or minimally
+generic) kernels in this module, leaving only a thin typed wrapper to be monomorphized.
+*/
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::panic;
+use std::thread;
+
+use access::DataIndex;
+use cons::{Cons, Nil};
+use error::*;
+use join::{OuterJoinKind, PredAction, Predicate};
+use observer::{NoObserver, Observer, Reporter, Stage, REPORT_INTERVAL};
+use value::Value;
+
+/// Type-erased access to the values of a single field for the purposes of hashing and comparing
+/// records. Implemented for every [DataIndex](../access/trait.DataIndex.html) whose values can be
+/// hashed and compared.
+pub trait KeyIndex {
+    /// Updates the hash state `state` with the value at index `idx`.
+    fn hash_at(&self, idx: usize, state: &mut dyn Hasher);
+    /// Returns whether the value at index `idx` is equal to the value at index `other_idx`.
+    fn eq_at(&self, idx: usize, other_idx: usize) -> bool;
+}
+
+impl<DI> KeyIndex for DI
+where
+    DI: DataIndex,
+    for<'a> Value<&'a DI::DType>: Hash + PartialEq,
+{
+    fn hash_at(&self, idx: usize, mut state: &mut dyn Hasher) {
+        self.get_datum(idx).unwrap().hash(&mut state);
+    }
+    fn eq_at(&self, idx: usize, other_idx: usize) -> bool {
+        self.get_datum(idx).unwrap() == self.get_datum(other_idx).unwrap()
+    }
+}
+
+/// Trait for converting a cons-list of fields (as returned by
+/// [field_list](../view/struct.DataView.html#method.field_list)) into a list of
+/// [KeyIndex](trait.KeyIndex.html) trait objects.
+pub trait KeyList {
+    /// Appends a `KeyIndex` trait object for each field in this cons-list to `keys`.
+    fn push_keys<'a>(&'a self, keys: &mut Vec<&'a dyn KeyIndex>);
+
+    /// Returns a `Vec` of `KeyIndex` trait objects, one for each field in this cons-list.
+    fn keys(&self) -> Vec<&dyn KeyIndex> {
+        let mut keys = vec![];
+        self.push_keys(&mut keys);
+        keys
+    }
+}
+
+impl KeyList for Nil {
+    fn push_keys<'a>(&'a self, _keys: &mut Vec<&'a dyn KeyIndex>) {}
+}
+
+impl<Head, Tail> KeyList for Cons<Head, Tail>
+where
+    Head: KeyIndex,
+    Tail: KeyList,
+{
+    fn push_keys<'a>(&'a self, keys: &mut Vec<&'a dyn KeyIndex>) {
+        keys.push(&self.head);
+        self.tail.push_keys(keys);
+    }
+}
+
+/// Type-erased access to the values of a single field for the purposes of ordering records.
+/// Implemented for every [DataIndex](../access/trait.DataIndex.html) whose values are totally
+/// ordered.
+pub trait OrdKeyIndex {
+    /// Compares the value at index `idx` with the value at index `other_idx`. Missing (NA) values
+    /// are considered to be of 'lesser' value than existing values.
+    fn cmp_at(&self, idx: usize, other_idx: usize) -> Ordering;
+}
+
+impl<DI> OrdKeyIndex for DI
+where
+    DI: DataIndex,
+    DI::DType: Ord,
+{
+    fn cmp_at(&self, idx: usize, other_idx: usize) -> Ordering {
+        self.get_datum(idx)
+            .unwrap()
+            .cmp(&self.get_datum(other_idx).unwrap())
+    }
+}
+
+/// Trait for converting a cons-list of fields (as returned by
+/// [field_list](../view/struct.DataView.html#method.field_list)) into a list of
+/// [OrdKeyIndex](trait.OrdKeyIndex.html) trait objects.
+pub trait OrdKeyList {
+    /// Appends an `OrdKeyIndex` trait object for each field in this cons-list to `keys`.
+    fn push_ord_keys<'a>(&'a self, keys: &mut Vec<&'a dyn OrdKeyIndex>);
+
+    /// Returns a `Vec` of `OrdKeyIndex` trait objects, one for each field in this cons-list.
+    fn ord_keys(&self) -> Vec<&dyn OrdKeyIndex> {
+        let mut keys = vec![];
+        self.push_ord_keys(&mut keys);
+        keys
+    }
+}
+
+impl OrdKeyList for Nil {
+    fn push_ord_keys<'a>(&'a self, _keys: &mut Vec<&'a dyn OrdKeyIndex>) {}
+}
+
+impl<Head, Tail> OrdKeyList for Cons<Head, Tail>
+where
+    Head: OrdKeyIndex,
+    Tail: OrdKeyList,
+{
+    fn push_ord_keys<'a>(&'a self, keys: &mut Vec<&'a dyn OrdKeyIndex>) {
+        keys.push(&self.head);
+        self.tail.push_ord_keys(keys);
+    }
+}
+
+/// A single record (row) across a list of key fields.
+struct KeyRecord<'a, 'b> {
+    keys: &'b [&'a dyn KeyIndex],
+    idx: usize,
+}
+
+impl<'a, 'b> Hash for KeyRecord<'a, 'b> {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        for key in self.keys {
+            key.hash_at(self.idx, state);
+        }
+    }
+}
+
+impl<'a, 'b> PartialEq for KeyRecord<'a, 'b> {
+    fn eq(&self, other: &Self) -> bool {
+        self.keys.iter().all(|key| key.eq_at(self.idx, other.idx))
+    }
+}
+
+impl<'a, 'b> Eq for KeyRecord<'a, 'b> {}
+
+/// The result of grouping the records in a list of key fields by their composite values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Groups {
+    /// For each group (in order of first appearance), the index of the first record in that group.
+    pub first_indices: Vec<usize>,
+    /// For each record, the index of the group it belongs to.
+    pub record_groups: Vec<usize>,
+}
+
+impl Groups {
+    /// Returns the number of groups.
+    pub fn len(&self) -> usize {
+        self.first_indices.len()
+    }
+
+    /// Returns `true` if there are no groups.
+    pub fn is_empty(&self) -> bool {
+        self.first_indices.is_empty()
+    }
+}
+
+/// Groups the first `nrows` records of the fields in `keys` by their composite values. All
+/// fields in `keys` must contain at least `nrows` values.
+pub fn group_records(keys: &[&dyn KeyIndex], nrows: usize) -> Groups {
+    // grouping without an observer cannot be cancelled
+    group_records_observed(keys, nrows, &NoObserver).unwrap()
+}
+
+/// Groups the first `nrows` records of the fields in `keys` by their composite values (as
+/// [group_records](fn.group_records.html)), checking every few thousand records whether
+/// `observer` requests cancellation.
+///
+/// # Errors
+/// Fails with `AgnesError::Cancelled` if `observer` requests cancellation.
+pub fn group_records_observed(
+    keys: &[&dyn KeyIndex],
+    nrows: usize,
+    observer: &dyn Observer,
+) -> Result<Groups> {
+    let mut map = HashMap::new();
+    let mut first_indices = vec![];
+    let mut record_groups = Vec::with_capacity(nrows);
+    for idx in 0..nrows {
+        if idx % REPORT_INTERVAL == 0 && observer.is_cancelled() {
+            return Err(AgnesError::Cancelled);
+        }
+        let group = *map.entry(KeyRecord { keys, idx }).or_insert_with(|| {
+            first_indices.push(idx);
+            first_indices.len() - 1
+        });
+        record_groups.push(group);
+    }
+    Ok(Groups {
+        first_indices,
+        record_groups,
+    })
+}
+
+/// Returns the stable sorted permutation order of the first `nrows` records of the fields in
+/// `keys`, ordered lexicographically by the values of each field in turn. All fields in `keys` must
+/// contain at least `nrows` values.
+pub fn sort_records(keys: &[&dyn OrdKeyIndex], nrows: usize) -> Vec<usize> {
+    let mut order = (0..nrows).collect::<Vec<_>>();
+    order.sort_by(|&left, &right| {
+        keys.iter()
+            .map(|key| key.cmp_at(left, right))
+            .find(|&ordering| ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    });
+    order
+}
+
+/// Computes the pairs of indices into `left` and `right` which satisfy the join predicate `Pred`,
+/// using a sort-merge join algorithm. `left_order` and `right_order` are the sorted orders of the
+/// values in `left` and `right`, respectively. The number of `left` records processed is reported
+/// to `observer`.
+///
+/// # Errors
+/// Fails with `AgnesError::Cancelled` if `observer` requests cancellation.
+pub fn merge_indices<Pred, T>(
+    left: &dyn DataIndex<DType = T>,
+    left_order: &[usize],
+    right: &dyn DataIndex<DType = T>,
+    right_order: &[usize],
+    observer: &mut dyn Observer,
+) -> Result<(Vec<usize>, Vec<usize>)>
+where
+    Pred: Predicate,
+    T: PartialEq + Ord,
+{
+    // NOTE: actual_idx = perm[sorted_idx]
+    // NOTE: value = key_data.get(actual_idx).unwrap();
+
+    let lval = |sorted_idx| left.get_datum(left_order[sorted_idx]).unwrap();
+    let rval = |sorted_idx| right.get_datum(right_order[sorted_idx]).unwrap();
+
+    let mut reporter = Reporter::new(observer, Stage::Join, Some(left_order.len()));
+    reporter.check()?;
+    let (mut left_idx, mut right_idx) = (0, 0);
+    let mut left_merge_indices = vec![];
+    let mut right_merge_indices = vec![];
+    while left_idx < left_order.len() && right_idx < right_order.len() {
+        let left_val = lval(left_idx);
+        let right_val = rval(right_idx);
+        let pred_results = Pred::apply(left_val, right_val);
+        match pred_results {
+            PredAction::Add => {
+                // figure out subsets
+                let mut left_subset = vec![left_idx];
+                let mut right_subset = vec![right_idx];
+                let (mut left_idx_end, mut right_idx_end);
+                if Pred::is_equality_pred() {
+                    // for equality predicates, add all records with same value
+                    left_idx_end = left_idx + 1;
+                    while left_idx_end < left_order.len() && left_val == lval(left_idx_end) {
+                        left_subset.push(left_idx_end);
+                        left_idx_end += 1;
+                    }
+                    right_idx_end = right_idx + 1;
+                    while right_idx_end < right_order.len() && right_val == rval(right_idx_end) {
+                        right_subset.push(right_idx_end);
+                        right_idx_end += 1;
+                    }
+                } else {
+                    left_idx_end = left_idx + 1;
+                    right_idx_end = right_idx + 1;
+                }
+                let (left_eq_end, right_eq_end) = (left_idx_end, right_idx_end);
+                if Pred::is_greater_than_pred() {
+                    // for greater-than predicates, we can add the rest of the left values
+                    while left_idx_end < left_order.len() {
+                        left_subset.push(left_idx_end);
+                        left_idx_end += 1;
+                    }
+                }
+                if Pred::is_less_than_pred() {
+                    // for less-than predicates, we can add the rest of the right values
+                    while right_idx_end < right_order.len() {
+                        right_subset.push(right_idx_end);
+                        right_idx_end += 1;
+                    }
+                }
+                // add cross product of subsets to merge indices
+                for lidx in &left_subset {
+                    // NAs shouldn't match a predicate, only add if value exists
+                    if lval(*lidx).exists() {
+                        for ridx in &right_subset {
+                            if rval(*ridx).exists() {
+                                left_merge_indices.push(left_order[*lidx]);
+                                right_merge_indices.push(right_order[*ridx]);
+                            }
+                        }
+                    }
+                }
+                // advance as needed
+                Pred::advance(&mut left_idx, &mut right_idx, left_eq_end, right_eq_end);
+            }
+            PredAction::Advance { left, right } => {
+                if left {
+                    left_idx += 1;
+                }
+                if right {
+                    right_idx += 1;
+                }
+            }
+        }
+        reporter.update(left_idx)?;
+    }
+    reporter.finish();
+    Ok((left_merge_indices, right_merge_indices))
+}
+
+/// Returns the number of threads to use for a parallel operation given a requested number of
+/// threads `nthreads`, where `0` requests one thread per available CPU.
+pub fn resolve_nthreads(nthreads: usize) -> usize {
+    if nthreads > 0 {
+        nthreads
+    } else {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    }
+}
+
+fn partition_of<T>(value: &T, npartitions: usize) -> usize
+where
+    T: Hash,
+{
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    (hasher.finish() % npartitions as u64) as usize
+}
+
+// Hash-partitions the indices of the existing values in `keys` into `npartitions` partitions, using
+// `npartitions` threads. Indices within each partition are in increasing order.
+fn partition_indices<T>(keys: &[Value<&T>], npartitions: usize) -> Vec<Vec<usize>>
+where
+    T: Hash + Sync,
+{
+    let chunk_size = keys.len().div_ceil(npartitions).max(1);
+    thread::scope(|scope| {
+        let handles = keys
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(chunk_idx, chunk)| {
+                scope.spawn(move || {
+                    let mut partitions = vec![vec![]; npartitions];
+                    for (idx, key) in chunk.iter().enumerate() {
+                        if let Value::Exists(key) = *key {
+                            partitions[partition_of(key, npartitions)]
+                                .push(chunk_idx * chunk_size + idx);
+                        }
+                    }
+                    partitions
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut partitions = vec![vec![]; npartitions];
+        for handle in handles {
+            let chunk_partitions = handle.join().unwrap_or_else(|e| panic::resume_unwind(e));
+            for (partition, indices) in partitions.iter_mut().zip(chunk_partitions) {
+                partition.extend(indices);
+            }
+        }
+        partitions
+    })
+}
+
+/// Computes the pairs of indices into `left` and `right` which have equal (existing) key values,
+/// using a partitioned hash join algorithm: the keys on both sides are hash-partitioned across
+/// `nthreads` threads, and each partition is joined independently. The resulting pairs are ordered
+/// by left index, then by right index. The number of `left` records processed is reported to
+/// `observer`.
+///
+/// # Errors
+/// Fails with `AgnesError::Cancelled` if `observer` requests cancellation.
+pub fn hash_join_indices<T>(
+    left: &[Value<&T>],
+    right: &[Value<&T>],
+    nthreads: usize,
+    observer: &mut dyn Observer,
+) -> Result<(Vec<usize>, Vec<usize>)>
+where
+    T: Hash + Eq + Sync,
+{
+    let npartitions = resolve_nthreads(nthreads);
+    let mut reporter = Reporter::new(observer, Stage::Join, Some(left.len()));
+    reporter.check()?;
+
+    let left_partitions = partition_indices(left, npartitions);
+    let right_partitions = partition_indices(right, npartitions);
+    reporter.check()?;
+
+    let pairs = thread::scope(|scope| {
+        let handles = left_partitions
+            .iter()
+            .zip(right_partitions.iter())
+            .map(|(left_partition, right_partition)| {
+                scope.spawn(move || {
+                    let mut table: HashMap<&T, Vec<usize>> = HashMap::new();
+                    for &right_idx in right_partition {
+                        if let Value::Exists(key) = right[right_idx] {
+                            table.entry(key).or_default().push(right_idx);
+                        }
+                    }
+                    let mut pairs = vec![];
+                    for &left_idx in left_partition {
+                        let matches = match left[left_idx] {
+                            Value::Exists(key) => table.get(key),
+                            Value::Na => None,
+                        };
+                        if let Some(right_indices) = matches {
+                            pairs.extend(
+                                right_indices.iter().map(|&right_idx| (left_idx, right_idx)),
+                            );
+                        }
+                    }
+                    (left_partition.len(), pairs)
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut pairs = vec![];
+        for handle in handles {
+            let (nprocessed, partition_pairs) =
+                handle.join().unwrap_or_else(|e| panic::resume_unwind(e));
+            pairs.extend(partition_pairs);
+            reporter.advance(nprocessed)?;
+        }
+        Ok::<_, AgnesError>(pairs)
+    })?;
+    reporter.finish();
+
+    Ok(order_by_left(left.len(), pairs))
+}
+
+// Arranges the `pairs` of matching indices found by a partitioned join in order of left index,
+// without sorting: each left index falls in exactly one partition, whose pairs for that index are
+// already in order of right index, so the pairs are placed in a single pass at offsets computed
+// from the number of matches of each left index.
+fn order_by_left(nleft: usize, pairs: Vec<(usize, usize)>) -> (Vec<usize>, Vec<usize>) {
+    let mut offsets = vec![0usize; nleft + 1];
+    for &(left_idx, _) in &pairs {
+        offsets[left_idx + 1] += 1;
+    }
+    for idx in 0..nleft {
+        offsets[idx + 1] += offsets[idx];
+    }
+    let mut left_indices = vec![0; pairs.len()];
+    let mut right_indices = vec![0; pairs.len()];
+    for (left_idx, right_idx) in pairs {
+        let pos = offsets[left_idx];
+        left_indices[pos] = left_idx;
+        right_indices[pos] = right_idx;
+        offsets[left_idx] += 1;
+    }
+    (left_indices, right_indices)
+}
+
+/// Computes the pairs of indices into `left` and `right` which have equal (existing) key values,
+/// using a broadcast hash join algorithm: a hash table is built from the keys of `right` (which
+/// should be the smaller side), and the keys of `left` are streamed through it in order. The
+/// resulting pairs are ordered by left index, then by right index (the same order as produced by
+/// [hash_join_indices](fn.hash_join_indices.html)). The number of `left` records processed is
+/// reported to `observer`.
+///
+/// # Errors
+/// Fails with `AgnesError::Cancelled` if `observer` requests cancellation.
+pub fn broadcast_join_indices<T>(
+    left: &[Value<&T>],
+    right: &[Value<&T>],
+    observer: &mut dyn Observer,
+) -> Result<(Vec<usize>, Vec<usize>)>
+where
+    T: Hash + Eq,
+{
+    let mut reporter = Reporter::new(observer, Stage::Join, Some(left.len()));
+    reporter.check()?;
+
+    let mut table: HashMap<&T, Vec<usize>> = HashMap::new();
+    for (right_idx, key) in right.iter().enumerate() {
+        if let Value::Exists(key) = *key {
+            table.entry(key).or_default().push(right_idx);
+        }
+    }
+
+    let mut left_indices = vec![];
+    let mut right_indices = vec![];
+    for (left_idx, key) in left.iter().enumerate() {
+        let matches = match *key {
+            Value::Exists(key) => table.get(key),
+            Value::Na => None,
+        };
+        if let Some(matches) = matches {
+            left_indices.extend(matches.iter().map(|_| left_idx));
+            right_indices.extend(matches);
+        }
+        reporter.update(left_idx + 1)?;
+    }
+    reporter.finish();
+    Ok((left_indices, right_indices))
+}
+
+/// Computes the join indices of an outer equi-join between the key values `left` and `right`.
+/// Each left-hand record is emitted (in order) once for each matching right-hand record; records
+/// without a match on the side(s) retained by `kind` are emitted once with a `None` index for the
+/// other side. Unmatched right-hand records are emitted (in order) after all left-hand records.
+/// Missing (NA) key values never match.
+pub fn outer_join_indices<T>(
+    left: &[Value<&T>],
+    right: &[Value<&T>],
+    kind: OuterJoinKind,
+) -> (Vec<Option<usize>>, Vec<Option<usize>>)
+where
+    T: Hash + Eq,
+{
+    let mut table: HashMap<&T, Vec<usize>> = HashMap::new();
+    for (right_idx, key) in right.iter().enumerate() {
+        if let Value::Exists(key) = *key {
+            table.entry(key).or_default().push(right_idx);
+        }
+    }
+
+    let mut right_matched = vec![false; right.len()];
+    let mut left_indices = vec![];
+    let mut right_indices = vec![];
+    for (left_idx, key) in left.iter().enumerate() {
+        let matches = match *key {
+            Value::Exists(key) => table.get(key),
+            Value::Na => None,
+        };
+        match matches {
+            Some(matches) => {
+                for &right_idx in matches {
+                    right_matched[right_idx] = true;
+                    left_indices.push(Some(left_idx));
+                    right_indices.push(Some(right_idx));
+                }
+            }
+            None if kind.keep_left() => {
+                left_indices.push(Some(left_idx));
+                right_indices.push(None);
+            }
+            None => {}
+        }
+    }
+    if kind.keep_right() {
+        for (right_idx, _) in right_matched.iter().enumerate().filter(|(_, &m)| !m) {
+            left_indices.push(None);
+            right_indices.push(Some(right_idx));
+        }
+    }
+    (left_indices, right_indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use field::FieldData;
+    use join::{Equal, GreaterThan};
+    use observer::{CancellationToken, NoObserver};
+
+    #[test]
+    fn group() {
+        let field1 = FieldData::from_field_vec(vec![
+            Value::Exists(1u64),
+            Value::Exists(2),
+            Value::Exists(1),
+            Value::Na,
+            Value::Exists(1),
+            Value::Na,
+        ]);
+        let field2: FieldData<&str> = FieldData::from_vec(vec!["a", "a", "b", "a", "a", "a"]);
+
+        let groups = group_records(&[&field1], field1.len());
+        assert_eq!(groups.first_indices, vec![0, 1, 3]);
+        assert_eq!(groups.record_groups, vec![0, 1, 0, 2, 0, 2]);
+
+        let groups = group_records(&[&field1, &field2], field1.len());
+        assert_eq!(groups.first_indices, vec![0, 1, 2, 3]);
+        assert_eq!(groups.record_groups, vec![0, 1, 2, 3, 0, 3]);
+
+        let groups = group_records(&[&field2], 0);
+        assert!(groups.is_empty());
+
+        let token = CancellationToken::new();
+        assert!(group_records_observed(&[&field1], field1.len(), &token).is_ok());
+        token.cancel();
+        match group_records_observed(&[&field1], field1.len(), &token) {
+            Err(AgnesError::Cancelled) => {}
+            _ => panic!("expected cancellation"),
+        }
+    }
+
+    #[test]
+    fn sort() {
+        let field1 = FieldData::from_field_vec(vec![
+            Value::Exists(1u64),
+            Value::Exists(2),
+            Value::Exists(1),
+            Value::Na,
+            Value::Exists(1),
+        ]);
+        let field2: FieldData<&str> = FieldData::from_vec(vec!["b", "a", "a", "c", "b"]);
+
+        assert_eq!(sort_records(&[&field1], field1.len()), vec![3, 0, 2, 4, 1]);
+        assert_eq!(
+            sort_records(&[&field1, &field2], field1.len()),
+            vec![3, 2, 0, 4, 1]
+        );
+        assert_eq!(sort_records(&[&field2, &field1], 3), vec![2, 1, 0]);
+        assert!(sort_records(&[], 0).is_empty());
+    }
+
+    #[test]
+    fn merge() {
+        let left = FieldData::from_vec(vec![3u64, 1, 2]);
+        let right =
+            FieldData::from_field_vec(vec![Value::Exists(2u64), Value::Na, Value::Exists(3)]);
+
+        let mut reports = vec![];
+        let (left_indices, right_indices) = merge_indices::<Equal, _>(
+            &left,
+            &[1, 2, 0],
+            &right,
+            &[1, 0, 2],
+            &mut |stage, processed, total| reports.push((stage, processed, total)),
+        )
+        .unwrap();
+        assert_eq!(left_indices, vec![2, 0]);
+        assert_eq!(right_indices, vec![0, 2]);
+        assert_eq!(
+            reports,
+            vec![(Stage::Join, 0, Some(3)), (Stage::Join, 3, Some(3))]
+        );
+
+        let (left_indices, right_indices) =
+            merge_indices::<GreaterThan, _>(&left, &[1, 2, 0], &right, &[1, 0, 2], &mut NoObserver)
+                .unwrap();
+        assert_eq!(left_indices, vec![0]);
+        assert_eq!(right_indices, vec![0]);
+
+        let token = CancellationToken::new();
+        token.cancel();
+        match merge_indices::<Equal, _>(&left, &[1, 2, 0], &right, &[1, 0, 2], &mut token.clone()) {
+            Err(AgnesError::Cancelled) => {}
+            _ => panic!("expected cancellation"),
+        }
+    }
+
+    #[test]
+    fn hash_join() {
+        let left = vec![
+            Value::Exists(&3u64),
+            Value::Exists(&1),
+            Value::Na,
+            Value::Exists(&2),
+            Value::Exists(&3),
+        ];
+        let right = vec![
+            Value::Exists(&2u64),
+            Value::Na,
+            Value::Exists(&3),
+            Value::Exists(&3),
+        ];
+
+        for nthreads in 1..4 {
+            let (left_indices, right_indices) =
+                hash_join_indices(&left, &right, nthreads, &mut NoObserver).unwrap();
+            assert_eq!(left_indices, vec![0, 0, 3, 4, 4]);
+            assert_eq!(right_indices, vec![2, 3, 0, 2, 3]);
+        }
+
+        // matches from every partition are in order of left index, then right index
+        let keys = (0..200u64).map(|i| i % 13).collect::<Vec<_>>();
+        let many = keys.iter().map(Value::Exists).collect::<Vec<_>>();
+        for nthreads in 1..5 {
+            assert_eq!(
+                hash_join_indices(&many[..150], &many[50..], nthreads, &mut NoObserver).unwrap(),
+                broadcast_join_indices(&many[..150], &many[50..], &mut NoObserver).unwrap()
+            );
+        }
+
+        let (left_indices, _) = hash_join_indices::<u64>(&[], &right, 0, &mut NoObserver).unwrap();
+        assert!(left_indices.is_empty());
+
+        let token = CancellationToken::new();
+        token.cancel();
+        match hash_join_indices(&left, &right, 2, &mut token.clone()) {
+            Err(AgnesError::Cancelled) => {}
+            _ => panic!("expected cancellation"),
+        }
+
+        let mut reports = vec![];
+        assert_eq!(
+            broadcast_join_indices(&left, &right, &mut |stage, processed, total| {
+                reports.push((stage, processed, total))
+            })
+            .unwrap(),
+            hash_join_indices(&left, &right, 2, &mut NoObserver).unwrap()
+        );
+        assert_eq!(
+            reports,
+            vec![(Stage::Join, 0, Some(5)), (Stage::Join, 5, Some(5))]
+        );
+    }
+
+    #[test]
+    fn outer_join() {
+        let left = vec![
+            Value::Exists(&1u64),
+            Value::Na,
+            Value::Exists(&2),
+            Value::Exists(&4),
+        ];
+        let right = vec![
+            Value::Exists(&2u64),
+            Value::Exists(&3),
+            Value::Exists(&1),
+            Value::Na,
+        ];
+        assert_eq!(
+            outer_join_indices(&left, &right, OuterJoinKind::Left),
+            (
+                vec![Some(0), Some(1), Some(2), Some(3)],
+                vec![Some(2), None, Some(0), None]
+            )
+        );
+        assert_eq!(
+            outer_join_indices(&left, &right, OuterJoinKind::Right),
+            (
+                vec![Some(0), Some(2), None, None],
+                vec![Some(2), Some(0), Some(1), Some(3)]
+            )
+        );
+        let (left_indices, right_indices) = outer_join_indices(&left, &right, OuterJoinKind::Full);
+        assert_eq!(left_indices.len(), 6);
+        assert_eq!(&right_indices[4..], &[Some(1), Some(3)]);
+    }
+}
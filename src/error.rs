@@ -5,10 +5,19 @@ use std::error::Error;
 use std::fmt;
 use std::io;
 
+use chrono;
 use csv;
 use csv_sniffer;
-use hyper;
-use native_tls;
+use glob;
+#[cfg(feature = "object-store")]
+use object_store;
+use regex;
+#[cfg(feature = "net")]
+use reqwest;
+#[cfg(feature = "net")]
+use tokio;
+#[cfg(feature = "net")]
+use url;
 
 use field::FieldIdent;
 
@@ -18,6 +27,7 @@ pub enum AgnesError {
     /// File IO error.
     Io(io::Error),
     /// Network-related error
+    #[cfg(feature = "net")]
     Net(NetError),
     /// CSV reading / parsing error
     Csv(csv::Error),
@@ -25,14 +35,29 @@ pub enum AgnesError {
     CsvSniffer(csv_sniffer::error::SnifferError),
     /// CSV dialect error
     CsvDialect(String),
+    /// Invalid file glob pattern
+    Glob(glob::PatternError),
+    /// Invalid regular expression
+    Regex(regex::Error),
+    /// Multiple-source operation provided with no sources
+    NoSources,
     /// Parsing error (failure parsing as specified type).
     Parse(ParseError),
+    /// Failure parsing the value of a field at a specific record
+    ParseValue {
+        /// index of the record whose value failed to parse
+        index: usize,
+        /// underlying parse error
+        err: ParseError,
+    },
     /// Charset Decoding error.
     Decode(String),
     /// Field missing from DataSource.
     FieldNotFound(FieldIdent),
     /// Dimension mismatch
     DimensionMismatch(String),
+    /// Field of a type which an operation does not support
+    UnsupportedType(String),
     /// Indexing error
     IndexError {
         /// out-of-bounds index
@@ -47,6 +72,34 @@ pub enum AgnesError {
         /// Observed length
         actual: usize,
     },
+    /// Arithmetic overflow (or integer division by zero)
+    Overflow {
+        /// index of the value at which the operation failed
+        index: usize,
+    },
+    /// Value which cannot be represented in the target type of a cast
+    Cast {
+        /// index of the value which failed to cast
+        index: usize,
+    },
+    /// Invalid number format specification
+    Format(String),
+    /// Index built over a different field than the one an operation requires
+    IndexMismatch(String),
+    /// Join key cardinality differs from that specified for a validated join
+    JoinValidation(String),
+    /// Invalid or inapplicable query expression
+    Query(String),
+    /// Invalid validation rule
+    Validation(String),
+    /// Invalid fixed-width file column specification
+    FwfColumns(String),
+    /// Avro schema or data error
+    Avro(String),
+    /// Avro file compressed with an unsupported codec (named by the contained string)
+    UnsupportedCodec(String),
+    /// Operation cancelled by an observer (see [observer](../observer/index.html))
+    Cancelled,
 }
 
 /// Wrapper for DataFrame-based results.
@@ -56,16 +109,24 @@ impl fmt::Display for AgnesError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             AgnesError::Io(ref err) => write!(f, "IO error: {}", err),
+            #[cfg(feature = "net")]
             AgnesError::Net(ref err) => write!(f, "Network error: {}", err),
             AgnesError::Csv(ref err) => write!(f, "CSV error: {}", err),
             AgnesError::CsvSniffer(ref err) => write!(f, "CSV sniffer error: {}", err),
             AgnesError::CsvDialect(ref s) => write!(f, "CSV structure error: {}", s),
+            AgnesError::Glob(ref err) => write!(f, "Glob pattern error: {}", err),
+            AgnesError::Regex(ref err) => write!(f, "Regular expression error: {}", err),
+            AgnesError::NoSources => write!(f, "No data sources provided"),
             AgnesError::Parse(ref err) => write!(f, "Parse error: {}", err),
+            AgnesError::ParseValue { index, ref err } => {
+                write!(f, "Parse error at index {}: {}", index, err)
+            }
             AgnesError::Decode(ref s) => write!(f, "Decode error: {}", s),
             AgnesError::FieldNotFound(ref ident) => {
                 write!(f, "Missing source field: {}", ident.to_string())
             }
             AgnesError::DimensionMismatch(ref s) => write!(f, "Dimension mismatch: {}", s),
+            AgnesError::UnsupportedType(ref s) => write!(f, "Unsupported type: {}", s),
             AgnesError::IndexError { index, len } => write!(
                 f,
                 "Index error: index {} exceeds data length {}",
@@ -76,6 +137,23 @@ impl fmt::Display for AgnesError {
                 "Length mismatch: expected {} does not match actual {}",
                 expected, actual
             ),
+            AgnesError::Overflow { index } => {
+                write!(f, "Arithmetic overflow error at index {}", index)
+            }
+            AgnesError::Cast { index } => write!(
+                f,
+                "Cast error: value at index {} cannot be represented in target type",
+                index
+            ),
+            AgnesError::Format(ref s) => write!(f, "Format specification error: {}", s),
+            AgnesError::IndexMismatch(ref s) => write!(f, "Index mismatch: {}", s),
+            AgnesError::JoinValidation(ref s) => write!(f, "Join validation error: {}", s),
+            AgnesError::Query(ref s) => write!(f, "Query error: {}", s),
+            AgnesError::Validation(ref s) => write!(f, "Validation error: {}", s),
+            AgnesError::FwfColumns(ref s) => write!(f, "Fixed-width column error: {}", s),
+            AgnesError::Avro(ref s) => write!(f, "Avro error: {}", s),
+            AgnesError::UnsupportedCodec(ref s) => write!(f, "Unsupported Avro codec: {}", s),
+            AgnesError::Cancelled => write!(f, "Operation cancelled"),
         }
     }
 }
@@ -84,72 +162,127 @@ impl Error for AgnesError {
     fn description(&self) -> &str {
         match *self {
             AgnesError::Io(ref err) => err.description(),
+            #[cfg(feature = "net")]
             AgnesError::Net(ref err) => err.description(),
             AgnesError::Csv(ref err) => err.description(),
             AgnesError::CsvSniffer(ref err) => err.description(),
             AgnesError::CsvDialect(ref s) => s,
+            AgnesError::Glob(ref err) => err.msg,
+            AgnesError::Regex(_) => "invalid regular expression",
+            AgnesError::NoSources => "no data sources provided",
             AgnesError::Parse(ref err) => err.description(),
+            AgnesError::ParseValue { ref err, .. } => err.description(),
             AgnesError::Decode(ref s) => s,
             AgnesError::FieldNotFound(_) => "missing source field",
             AgnesError::DimensionMismatch(ref s) => s,
+            AgnesError::UnsupportedType(ref s) => s,
             AgnesError::IndexError { .. } => "indexing error",
             AgnesError::LengthMismatch { .. } => "length mismatch",
+            AgnesError::Overflow { .. } => "arithmetic overflow",
+            AgnesError::Cast { .. } => "cast error",
+            AgnesError::Format(ref s) => s,
+            AgnesError::IndexMismatch(ref s) => s,
+            AgnesError::JoinValidation(ref s) => s,
+            AgnesError::Query(ref s) => s,
+            AgnesError::Validation(ref s) => s,
+            AgnesError::FwfColumns(ref s) => s,
+            AgnesError::Avro(ref s) => s,
+            AgnesError::UnsupportedCodec(ref s) => s,
+            AgnesError::Cancelled => "operation cancelled",
         }
     }
 
     fn cause(&self) -> Option<&dyn Error> {
         match *self {
             AgnesError::Io(ref err) => Some(err),
+            #[cfg(feature = "net")]
             AgnesError::Net(ref err) => Some(err),
             AgnesError::Csv(ref err) => Some(err),
             AgnesError::CsvSniffer(ref err) => Some(err),
             AgnesError::CsvDialect(_) => None,
+            AgnesError::Glob(ref err) => Some(err),
+            AgnesError::Regex(ref err) => Some(err),
+            AgnesError::NoSources => None,
             AgnesError::Parse(ref err) => Some(err),
+            AgnesError::ParseValue { ref err, .. } => Some(err),
             AgnesError::Decode(_) => None,
             AgnesError::FieldNotFound(_) => None,
             AgnesError::DimensionMismatch(_) => None,
+            AgnesError::UnsupportedType(_) => None,
             AgnesError::IndexError { .. } => None,
             AgnesError::LengthMismatch { .. } => None,
+            AgnesError::Overflow { .. } => None,
+            AgnesError::Cast { .. } => None,
+            AgnesError::Format(_) => None,
+            AgnesError::IndexMismatch(_) => None,
+            AgnesError::JoinValidation(_) => None,
+            AgnesError::Query(_) => None,
+            AgnesError::Validation(_) => None,
+            AgnesError::FwfColumns(_) => None,
+            AgnesError::Avro(_) => None,
+            AgnesError::UnsupportedCodec(_) => None,
+            AgnesError::Cancelled => None,
         }
     }
 }
 
 /// Error that stems from some sort of network-related exception.
+#[cfg(feature = "net")]
 #[derive(Debug)]
 pub enum NetError {
     /// Invalid URI
-    Uri(hyper::http::uri::InvalidUri),
+    Uri(url::ParseError),
     /// Unsupported Scheme
-    UnsupportedScheme(Option<hyper::http::uri::Scheme>),
-    /// Secure layer error.
-    Tls(native_tls::Error),
-    /// HTTP error.
-    Http(hyper::Error),
+    UnsupportedScheme(String),
+    /// HTTP error (including secure layer errors).
+    Http(reqwest::Error),
+    /// Failure of a background task (e.g. a parsing task panicked or was cancelled).
+    Task(tokio::task::JoinError),
     /// Local file error
     LocalFile,
+    /// Object store error.
+    #[cfg(feature = "object-store")]
+    ObjectStore(object_store::Error),
+    /// Invalid object store URI (missing bucket or key)
+    #[cfg(feature = "object-store")]
+    InvalidObjectUri(String),
+    /// Object store object error
+    #[cfg(feature = "object-store")]
+    ObjectFile,
 }
+#[cfg(feature = "net")]
 impl fmt::Display for NetError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             NetError::Uri(ref err) => write!(f, "Invalid URI error: {}", err),
-            NetError::UnsupportedScheme(ref scheme) => match scheme {
-                Some(scheme) => write!(f, "Unsupported scheme: {}", scheme),
-                None => write!(f, "Missing scheme"),
-            },
-            NetError::Tls(ref err) => write!(f, "TLS error: {}", err),
+            NetError::UnsupportedScheme(ref scheme) => write!(f, "Unsupported scheme: {}", scheme),
             NetError::Http(ref err) => write!(f, "HTTP error: {}", err),
+            NetError::Task(ref err) => write!(f, "Background task error: {}", err),
             NetError::LocalFile => write!(f, "unable to access local file over HTTP"),
+            #[cfg(feature = "object-store")]
+            NetError::ObjectStore(ref err) => write!(f, "Object store error: {}", err),
+            #[cfg(feature = "object-store")]
+            NetError::InvalidObjectUri(ref uri) => write!(f, "Invalid object store URI: {}", uri),
+            #[cfg(feature = "object-store")]
+            NetError::ObjectFile => write!(f, "unable to access object store object over HTTP"),
         }
     }
 }
+#[cfg(feature = "net")]
 impl Error for NetError {
     fn description(&self) -> &str {
         match *self {
             NetError::Uri(ref err) => err.description(),
-            NetError::UnsupportedScheme(_) => "unsupported / missing scheme",
-            NetError::Tls(ref err) => err.description(),
+            NetError::UnsupportedScheme(_) => "unsupported scheme",
             NetError::Http(ref err) => err.description(),
+            NetError::Task(ref err) => err.description(),
             NetError::LocalFile => "unable to read local file over HTTP",
+            #[cfg(feature = "object-store")]
+            NetError::ObjectStore(ref err) => err.description(),
+            #[cfg(feature = "object-store")]
+            NetError::InvalidObjectUri(_) => "invalid object store URI",
+            #[cfg(feature = "object-store")]
+            NetError::ObjectFile => "unable to read object store object over HTTP",
         }
     }
 
@@ -157,9 +290,15 @@ impl Error for NetError {
         match *self {
             NetError::Uri(ref err) => Some(err),
             NetError::UnsupportedScheme(_) => None,
-            NetError::Tls(ref err) => Some(err),
             NetError::Http(ref err) => Some(err),
+            NetError::Task(ref err) => Some(err),
             NetError::LocalFile => None,
+            #[cfg(feature = "object-store")]
+            NetError::ObjectStore(ref err) => Some(err),
+            #[cfg(feature = "object-store")]
+            NetError::InvalidObjectUri(_) => None,
+            #[cfg(feature = "object-store")]
+            NetError::ObjectFile => None,
         }
     }
 }
@@ -175,6 +314,8 @@ pub enum ParseError {
     Float(std::num::ParseFloatError),
     /// String
     Str(std::string::ParseError),
+    /// Date / time
+    DateTime(chrono::ParseError),
 }
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -183,6 +324,7 @@ impl fmt::Display for ParseError {
             ParseError::Bool(ref err) => write!(f, "Boolean parse error: {}", err),
             ParseError::Float(ref err) => write!(f, "Float parse error: {}", err),
             ParseError::Str(ref err) => write!(f, "String parse error: {}", err),
+            ParseError::DateTime(ref err) => write!(f, "Date / time parse error: {}", err),
         }
     }
 }
@@ -193,6 +335,7 @@ impl Error for ParseError {
             ParseError::Bool(ref err) => err.description(),
             ParseError::Float(ref err) => err.description(),
             ParseError::Str(ref err) => err.description(),
+            ParseError::DateTime(ref err) => err.description(),
         }
     }
 
@@ -202,6 +345,7 @@ impl Error for ParseError {
             ParseError::Bool(ref err) => Some(err),
             ParseError::Float(ref err) => Some(err),
             ParseError::Str(ref err) => Some(err),
+            ParseError::DateTime(ref err) => Some(err),
         }
     }
 }
@@ -246,6 +390,16 @@ impl From<std::string::ParseError> for AgnesError {
         AgnesError::Parse(err.into())
     }
 }
+impl From<chrono::ParseError> for ParseError {
+    fn from(err: chrono::ParseError) -> ParseError {
+        ParseError::DateTime(err)
+    }
+}
+impl From<chrono::ParseError> for AgnesError {
+    fn from(err: chrono::ParseError) -> AgnesError {
+        AgnesError::Parse(err.into())
+    }
+}
 impl From<ParseError> for AgnesError {
     fn from(err: ParseError) -> AgnesError {
         AgnesError::Parse(err)
@@ -258,45 +412,77 @@ impl From<io::Error> for AgnesError {
     }
 }
 
+#[cfg(feature = "net")]
 impl From<NetError> for AgnesError {
     fn from(err: NetError) -> AgnesError {
         AgnesError::Net(err)
     }
 }
 
-impl From<native_tls::Error> for NetError {
-    fn from(err: native_tls::Error) -> NetError {
-        NetError::Tls(err)
+#[cfg(feature = "net")]
+impl From<reqwest::Error> for NetError {
+    fn from(err: reqwest::Error) -> NetError {
+        NetError::Http(err)
     }
 }
-impl From<native_tls::Error> for AgnesError {
-    fn from(err: native_tls::Error) -> AgnesError {
+#[cfg(feature = "net")]
+impl From<reqwest::Error> for AgnesError {
+    fn from(err: reqwest::Error) -> AgnesError {
         AgnesError::Net(err.into())
     }
 }
 
-impl From<hyper::Error> for NetError {
-    fn from(err: hyper::Error) -> NetError {
-        NetError::Http(err)
+#[cfg(feature = "net")]
+impl From<url::ParseError> for NetError {
+    fn from(err: url::ParseError) -> NetError {
+        NetError::Uri(err)
     }
 }
-impl From<hyper::Error> for AgnesError {
-    fn from(err: hyper::Error) -> AgnesError {
+#[cfg(feature = "net")]
+impl From<url::ParseError> for AgnesError {
+    fn from(err: url::ParseError) -> AgnesError {
         AgnesError::Net(err.into())
     }
 }
 
-impl From<hyper::http::uri::InvalidUri> for NetError {
-    fn from(err: hyper::http::uri::InvalidUri) -> NetError {
-        NetError::Uri(err)
+#[cfg(feature = "net")]
+impl From<tokio::task::JoinError> for NetError {
+    fn from(err: tokio::task::JoinError) -> NetError {
+        NetError::Task(err)
     }
 }
-impl From<hyper::http::uri::InvalidUri> for AgnesError {
-    fn from(err: hyper::http::uri::InvalidUri) -> AgnesError {
+#[cfg(feature = "net")]
+impl From<tokio::task::JoinError> for AgnesError {
+    fn from(err: tokio::task::JoinError) -> AgnesError {
         AgnesError::Net(err.into())
     }
 }
 
+#[cfg(feature = "object-store")]
+impl From<object_store::Error> for NetError {
+    fn from(err: object_store::Error) -> NetError {
+        NetError::ObjectStore(err)
+    }
+}
+#[cfg(feature = "object-store")]
+impl From<object_store::Error> for AgnesError {
+    fn from(err: object_store::Error) -> AgnesError {
+        AgnesError::Net(err.into())
+    }
+}
+
+impl From<glob::PatternError> for AgnesError {
+    fn from(err: glob::PatternError) -> AgnesError {
+        AgnesError::Glob(err)
+    }
+}
+
+impl From<regex::Error> for AgnesError {
+    fn from(err: regex::Error) -> AgnesError {
+        AgnesError::Regex(err)
+    }
+}
+
 impl From<csv::Error> for AgnesError {
     fn from(err: csv::Error) -> AgnesError {
         AgnesError::Csv(err)
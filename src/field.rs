@@ -2,21 +2,30 @@
 Data structures and implementations for fields.
 
 Provides the [FieldData](struct.FieldData.html) struct for holding the data of a field and handling
-missing values.
+missing values, and the arena-backed [StrFieldData](struct.StrFieldData.html) struct for compactly
+holding the data of text fields (which is stored in a data store as a field of
+[ArenaStr](struct.ArenaStr.html) values).
 */
 
+use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::fmt;
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
 use std::iter::FromIterator;
 use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::Arc;
 
 #[cfg(feature = "serialize")]
 use serde::ser::{Serialize, SerializeSeq, Serializer};
 
-use access::{DataIndex, DataIndexMut};
+use access::{
+    chunk_end, DataChunk, DataIndex, DataIndexMut, DataIterator, NaMask, RawColumn, RawColumnar,
+};
 use bit_vec::BitVec;
 use error;
+use memory::{vec_memory, FieldMemory, HeapSize, MemoryUsage};
 use value::Value;
 
 /// Data vector containing the data for a single field (column) of an agnes data store.
@@ -119,6 +128,31 @@ impl<T> Default for FieldData<T> {
     }
 }
 impl<T> FieldData<T> {
+    /// Create a new, empty `FieldData` struct with space for at least `capacity` values, so that
+    /// the first `capacity` values can be added without reallocation.
+    pub fn with_capacity(capacity: usize) -> FieldData<T> {
+        FieldData {
+            mask: None,
+            data: Vec::with_capacity(capacity),
+        }
+    }
+    /// Reserves space for at least `additional` more values to be added to this `FieldData`.
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+        if let Some(ref mut mask) = self.mask {
+            mask.reserve(additional);
+        }
+    }
+    /// Shrinks the capacity of this `FieldData` (and its missing-value mask) as much as possible,
+    /// releasing any memory allocated for values beyond its length.
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+        if let Some(ref mut mask) = self.mask {
+            if mask.capacity() > mask.len() {
+                *mask = mask.iter().collect();
+            }
+        }
+    }
     /// Create a `FieldData` struct from a vector of non-NA values. Resulting `FieldData` struct
     /// will have no `Value::Na` values.
     pub fn from_vec<U: Into<T>>(mut v: Vec<U>) -> FieldData<T> {
@@ -182,6 +216,32 @@ where
         ret
     }
 }
+impl<T> FieldData<T>
+where
+    T: Clone,
+{
+    /// Appends (clones of) all the values (and missing values) of `other` to the end of this
+    /// `FieldData`, reserving space for them up front.
+    pub fn extend_from(&mut self, other: &FieldData<T>) {
+        let prev_len = self.data.len();
+        self.data.extend_from_slice(&other.data);
+        match other.mask {
+            Some(ref other_mask) => {
+                let mask = self
+                    .mask
+                    .get_or_insert_with(|| BitVec::from_elem(prev_len, true));
+                mask.reserve(other_mask.len());
+                mask.extend(other_mask.iter());
+            }
+            None => {
+                // if mask exists, all the new values exist
+                if let Some(ref mut mask) = self.mask {
+                    mask.grow(other.data.len(), true);
+                }
+            }
+        }
+    }
+}
 impl<T> FromIterator<Value<T>> for FieldData<T>
 where
     T: Debug + Default,
@@ -236,6 +296,43 @@ where
     fn len(&self) -> usize {
         self.len()
     }
+    fn get_chunk(&self, start: usize, len: usize) -> error::Result<DataChunk<'_, T>> {
+        let end = chunk_end(start, len, self.len())?;
+        Ok(DataChunk::from_slice(
+            start,
+            &self.data[start..end],
+            self.mask.as_ref(),
+        ))
+    }
+    fn na_mask(&self) -> NaMask<'_> {
+        match self.mask {
+            Some(ref mask) => NaMask::from_bitvec(mask),
+            None => NaMask::all_exist(self.len()),
+        }
+    }
+}
+impl<T> RawColumnar for FieldData<T>
+where
+    T: Debug,
+{
+    fn raw_column(&self) -> error::Result<RawColumn<'_, T>> {
+        Ok(RawColumn::from_storage(
+            &self.data,
+            self.mask.as_ref().map(NaMask::from_bitvec),
+            None,
+        ))
+    }
+}
+impl<'a, T> IntoIterator for &'a FieldData<T>
+where
+    T: 'a + Debug,
+{
+    type Item = Value<&'a T>;
+    type IntoIter = DataIterator<'a, T>;
+
+    fn into_iter(self) -> DataIterator<'a, T> {
+        self.iter()
+    }
 }
 impl<T> DataIndexMut for FieldData<T>
 where
@@ -254,6 +351,22 @@ where
         })
     }
 }
+impl<T> MemoryUsage for FieldData<T>
+where
+    T: HeapSize,
+{
+    fn memory_usage(&self) -> FieldMemory {
+        FieldMemory {
+            na_mask: mask_memory(&self.mask),
+            ..vec_memory(&self.data)
+        }
+    }
+}
+
+// Returns the number of bytes allocated for a missing-value mask.
+fn mask_memory(mask: &Option<BitVec>) -> usize {
+    mask.as_ref().map_or(0, |mask| mask.capacity().div_ceil(8))
+}
 
 #[cfg(feature = "serialize")]
 impl<T> Serialize for FieldData<T>
@@ -284,6 +397,298 @@ where
         seq.end()
     }
 }
+/// Arena-backed data vector for text fields. Rather than allocating a separate `String` for each
+/// value (as `FieldData<String>` does), the text of every value is stored contiguously in a single
+/// shared buffer, with each value identified by its offset into the buffer. This greatly reduces
+/// the number of allocations and improves cache locality for string-heavy data sets.
+///
+/// Since no `String` exists for each value, values are accessed as `&str` (rather than the
+/// `&String` returned by [DataIndex](../access/trait.DataIndex.html)). A `StrFieldData` is added to
+/// a data store by converting it into a `FieldData<ArenaStr>` (which shares its text buffer, see
+/// [ArenaStr](struct.ArenaStr.html)), and can also be converted to and from a `FieldData<String>`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct StrFieldData {
+    mask: Option<BitVec>,
+    buffer: String,
+    // end offset (into `buffer`) of each value; each value starts at the end of the previous one
+    ends: Vec<usize>,
+}
+impl StrFieldData {
+    /// Create a new, empty `StrFieldData`.
+    pub fn new() -> StrFieldData {
+        StrFieldData::default()
+    }
+    /// Create a new, empty `StrFieldData` with space for at least `capacity` values, containing a
+    /// total of at least `text_capacity` bytes of text, without reallocation.
+    pub fn with_capacity(capacity: usize, text_capacity: usize) -> StrFieldData {
+        StrFieldData {
+            mask: None,
+            buffer: String::with_capacity(text_capacity),
+            ends: Vec::with_capacity(capacity),
+        }
+    }
+    /// Returns the length of this data vector.
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+    /// Returns `true` if this field contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the total number of bytes of text stored in this field.
+    pub fn text_len(&self) -> usize {
+        self.buffer.len()
+    }
+    /// Get the value at the given index. Returns `None` if `index` is out of bounds, or a
+    /// `Value` enum.
+    pub fn get(&self, index: usize) -> Option<Value<&str>> {
+        if index >= self.ends.len() {
+            None
+        } else if self.mask.as_ref().is_none_or(|mask| mask[index]) {
+            let start = if index == 0 { 0 } else { self.ends[index - 1] };
+            Some(Value::Exists(&self.buffer[start..self.ends[index]]))
+        } else {
+            Some(Value::Na)
+        }
+    }
+    /// Add a new value (or an indication of a missing one) to the end of this data vector. The
+    /// text of the value is copied into the shared buffer.
+    pub fn push(&mut self, value: Value<&str>) {
+        match value {
+            Value::Exists(s) => {
+                self.buffer.push_str(s);
+                // if mask exists (which means there are NA values), then add a true to the end
+                if let Some(ref mut mask) = self.mask {
+                    mask.push(true);
+                }
+            }
+            Value::Na => {
+                let prev_len = self.ends.len();
+                // either get or create mask, and add a false to the end
+                self.mask
+                    .get_or_insert_with(|| BitVec::from_elem(prev_len, true))
+                    .push(false);
+            }
+        }
+        self.ends.push(self.buffer.len());
+    }
+    /// Returns an iterator over the values in this field.
+    pub fn iter(&self) -> StrFieldIter<'_> {
+        StrFieldIter {
+            data: self,
+            cur_idx: 0,
+        }
+    }
+    /// Copies the values of this field into a `FieldData<String>`.
+    pub fn to_field_data(&self) -> FieldData<String> {
+        self.iter().map(|value| value.map(str::to_owned)).collect()
+    }
+}
+impl<'a> FromIterator<Value<&'a str>> for StrFieldData {
+    fn from_iter<I: IntoIterator<Item = Value<&'a str>>>(iter: I) -> Self {
+        let mut data = StrFieldData::new();
+        for value in iter {
+            data.push(value);
+        }
+        data
+    }
+}
+impl<'a> FromIterator<&'a str> for StrFieldData {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        iter.into_iter().map(Value::Exists).collect()
+    }
+}
+impl<'a> From<&'a FieldData<String>> for StrFieldData {
+    fn from(other: &'a FieldData<String>) -> StrFieldData {
+        let text_len = other.data.iter().map(String::len).sum();
+        let mut data = StrFieldData::with_capacity(other.len(), text_len);
+        for idx in 0..other.len() {
+            data.push(other.get(idx).unwrap().map(String::as_str));
+        }
+        data
+    }
+}
+impl MemoryUsage for StrFieldData {
+    fn memory_usage(&self) -> FieldMemory {
+        FieldMemory {
+            na_mask: mask_memory(&self.mask),
+            // the text of all values is stored in the shared buffer
+            heap: self.buffer.capacity(),
+            ..vec_memory(&self.ends)
+        }
+    }
+}
+
+impl From<StrFieldData> for FieldData<String> {
+    fn from(other: StrFieldData) -> FieldData<String> {
+        other.to_field_data()
+    }
+}
+impl From<StrFieldData> for FieldData<ArenaStr> {
+    fn from(other: StrFieldData) -> FieldData<ArenaStr> {
+        let StrFieldData { mask, buffer, ends } = other;
+        let arena: Arc<str> = Arc::from(buffer);
+        let mut start = 0;
+        let data = ends
+            .into_iter()
+            .map(|end| {
+                let value = ArenaStr {
+                    arena: Arc::clone(&arena),
+                    start,
+                    end,
+                };
+                start = end;
+                value
+            })
+            .collect();
+        FieldData { mask, data }
+    }
+}
+
+/// A text value of a field stored in a data store from a
+/// [StrFieldData](struct.StrFieldData.html). Each `ArenaStr` refers to its text within the shared
+/// (atomically reference-counted) buffer of the field, so the field's values require no
+/// allocations of their own, and can be shared between threads. An `ArenaStr` dereferences to a
+/// `str`, and is compared, ordered, hashed, displayed, and serialized as its text.
+///
+/// Since [DataIndex](../access/trait.DataIndex.html) hands out references to values of its
+/// `DType`, an arena-backed field cannot present itself as a `DataIndex<DType = String>` (which
+/// would require a `String` for each value); fields of `ArenaStr` are instead supported alongside
+/// `String` fields by the operations which handle text (e.g. display, serialization, queries,
+/// diffs, and checksums).
+#[derive(Clone)]
+pub struct ArenaStr {
+    arena: Arc<str>,
+    start: usize,
+    end: usize,
+}
+impl ArenaStr {
+    /// Returns the text of this value.
+    pub fn as_str(&self) -> &str {
+        &self.arena[self.start..self.end]
+    }
+}
+impl FieldData<ArenaStr> {
+    /// Copies the text of the values of this field into a new shared buffer holding only their
+    /// text, so that the (possibly much larger) buffer this field's values refer to can be
+    /// released.
+    pub fn repack(&self) -> FieldData<ArenaStr> {
+        let text_len = self.data.iter().map(|value| value.len()).sum();
+        let mut data = StrFieldData::with_capacity(self.len(), text_len);
+        for idx in 0..self.len() {
+            data.push(self.get(idx).unwrap().map(ArenaStr::as_str));
+        }
+        data.into()
+    }
+}
+impl Default for ArenaStr {
+    fn default() -> ArenaStr {
+        ArenaStr::from("")
+    }
+}
+impl<'a> From<&'a str> for ArenaStr {
+    fn from(text: &'a str) -> ArenaStr {
+        ArenaStr {
+            arena: Arc::from(text),
+            start: 0,
+            end: text.len(),
+        }
+    }
+}
+impl Deref for ArenaStr {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+impl AsRef<str> for ArenaStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+impl Borrow<str> for ArenaStr {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+impl Debug for ArenaStr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+impl fmt::Display for ArenaStr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+impl PartialEq for ArenaStr {
+    fn eq(&self, other: &ArenaStr) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+impl Eq for ArenaStr {}
+impl PartialEq<str> for ArenaStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+impl<'a> PartialEq<&'a str> for ArenaStr {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str() == *other
+    }
+}
+impl PartialOrd for ArenaStr {
+    fn partial_cmp(&self, other: &ArenaStr) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for ArenaStr {
+    fn cmp(&self, other: &ArenaStr) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+impl Hash for ArenaStr {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.as_str().hash(state)
+    }
+}
+impl HeapSize for ArenaStr {
+    // the shared buffer is attributed to the values in proportion to the length of their text
+    fn heap_size(&self) -> usize {
+        self.end - self.start
+    }
+}
+#[cfg(feature = "serialize")]
+impl Serialize for ArenaStr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Iterator over the values of a [StrFieldData](struct.StrFieldData.html).
+#[derive(Debug)]
+pub struct StrFieldIter<'a> {
+    data: &'a StrFieldData,
+    cur_idx: usize,
+}
+impl<'a> Iterator for StrFieldIter<'a> {
+    type Item = Value<&'a str>;
+
+    fn next(&mut self) -> Option<Value<&'a str>> {
+        let value = self.data.get(self.cur_idx);
+        if value.is_some() {
+            self.cur_idx += 1;
+        }
+        value
+    }
+}
+
 /// Identifier for a field in the source.
 #[derive(Debug, Clone)]
 pub enum FieldIdent {
@@ -395,8 +800,10 @@ impl<T> TFieldIdent<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "serialize")]
     use serde_json;
 
+    #[cfg(feature = "serialize")]
     #[test]
     fn field_serialize() {
         let field: FieldData<f64> = vec![5.0f64, 3.4, -1.3, 5.2, 6.0, -126.9].into();
@@ -405,4 +812,136 @@ mod tests {
             "[5.0,3.4,-1.3,5.2,6.0,-126.9]"
         );
     }
+
+    #[test]
+    fn extend_from() {
+        let mut field = FieldData::<u64>::with_capacity(6);
+        field.extend_from(&FieldData::from_vec(vec![1u64, 2]));
+        assert_eq!(field.mask, None);
+        field.extend_from(&FieldData::from_field_vec(vec![
+            Value::Na,
+            Value::Exists(4),
+        ]));
+        field.reserve(2);
+        field.extend_from(&FieldData::from_vec(vec![5u64, 6]));
+        assert_eq!(field.len(), 6);
+        assert_eq!(
+            field.as_vec(),
+            vec![
+                Value::Exists(&1),
+                Value::Exists(&2),
+                Value::Na,
+                Value::Exists(&4),
+                Value::Exists(&5),
+                Value::Exists(&6)
+            ]
+        );
+        field.extend_from(&field.clone());
+        assert_eq!(field.len(), 12);
+        assert_eq!(field.get(8), Some(Value::Na));
+        assert_eq!(field.get(11), Some(Value::Exists(&6)));
+    }
+
+    #[test]
+    fn str_field_data() {
+        let field = FieldData::from_field_vec(vec![
+            Value::Exists("Sally".to_string()),
+            Value::Na,
+            Value::Exists("".to_string()),
+            Value::Exists("Bob".to_string()),
+        ]);
+        let strs = StrFieldData::from(&field);
+        assert_eq!(strs.len(), 4);
+        assert_eq!(strs.text_len(), 8);
+        assert_eq!(strs.get(0), Some(Value::Exists("Sally")));
+        assert_eq!(strs.get(1), Some(Value::Na));
+        assert_eq!(strs.get(2), Some(Value::Exists("")));
+        assert_eq!(strs.get(3), Some(Value::Exists("Bob")));
+        assert_eq!(strs.get(4), None);
+        assert_eq!(strs.iter().count(), 4);
+        assert_eq!(strs.to_field_data(), field);
+
+        let strs = vec!["a", "bc"].into_iter().collect::<StrFieldData>();
+        assert_eq!(
+            strs.iter().collect::<Vec<_>>(),
+            vec![Value::Exists("a"), Value::Exists("bc")]
+        );
+        assert_eq!(
+            FieldData::<String>::from(strs.clone()),
+            FieldData::from_vec(vec!["a", "bc"])
+        );
+
+        // the values of a `FieldData<ArenaStr>` share the text buffer
+        let arena = FieldData::<ArenaStr>::from(StrFieldData::from(&field));
+        assert_eq!(arena.len(), 4);
+        assert_eq!(
+            arena.get(0).unwrap().map(|s| s.as_str()),
+            Value::Exists("Sally")
+        );
+        assert_eq!(arena.get(1).unwrap(), Value::Na);
+        assert_eq!(arena.get(3).unwrap().map(|s| &s[..]), Value::Exists("Bob"));
+        assert!(Arc::ptr_eq(
+            &arena.get(0).unwrap().unwrap().arena,
+            &arena.get(3).unwrap().unwrap().arena
+        ));
+        let strs = FieldData::<ArenaStr>::from(strs);
+        assert_eq!(strs.to_vec(), vec!["a", "bc"]);
+    }
+
+    #[test]
+    fn arena_str_store_field() {
+        use cons::Nil;
+        use select::FieldSelect;
+        use store::DataStore;
+
+        // labels are declared separately, since `tablespace` field types must be nameable from
+        // within the generated table modules
+        mod cities {
+            use field::ArenaStr;
+            first_label![Name, ::typenum::U0, ArenaStr];
+            next_label![Population, Name, u64];
+        }
+
+        let names = vec!["Paris", "Oslo", "Lima"]
+            .into_iter()
+            .collect::<StrFieldData>();
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<cities::Name, _>(FieldData::<ArenaStr>::from(names))
+            .push_back_field::<cities::Population, _>(FieldData::<u64>::from_vec(vec![
+                2_100_000u64,
+                700_000,
+                10_000_000,
+            ]))
+            .into_view();
+        assert_eq!(
+            dv.field::<cities::Name>().get_datum(1).unwrap(),
+            Value::Exists(&"Oslo".into())
+        );
+        let dv = dv.filter::<cities::Name, _>(|name: Value<&ArenaStr>| {
+            name.map_or(false, |name| name.starts_with('L') || name == "Paris")
+        });
+        assert_eq!(dv.field::<cities::Name>().to_vec(), vec!["Paris", "Lima"]);
+        assert_eq!(
+            dv.field::<cities::Population>().to_vec(),
+            vec![2_100_000, 10_000_000]
+        );
+        assert!(dv.to_string().contains("Lima"));
+
+        // compacting copies the visible text out of the original buffer
+        let compacted = dv.compact();
+        assert_eq!(
+            compacted.field::<cities::Name>().to_vec(),
+            vec!["Paris", "Lima"]
+        );
+        let names = compacted.field::<cities::Name>();
+        let name = names.get_datum(1).unwrap().unwrap();
+        assert_eq!(name.arena.len(), "ParisLima".len());
+    }
+
+    #[test]
+    fn arena_str_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ArenaStr>();
+        assert_send_sync::<FieldData<ArenaStr>>();
+    }
 }
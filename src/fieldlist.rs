@@ -23,6 +23,21 @@ pub enum FieldDesignator {
     Idx(usize),
 }
 impl SelfValued for FieldDesignator {}
+impl From<&str> for FieldDesignator {
+    fn from(name: &str) -> FieldDesignator {
+        FieldDesignator::Expr(name.to_string())
+    }
+}
+impl From<String> for FieldDesignator {
+    fn from(name: String) -> FieldDesignator {
+        FieldDesignator::Expr(name)
+    }
+}
+impl From<usize> for FieldDesignator {
+    fn from(idx: usize) -> FieldDesignator {
+        FieldDesignator::Idx(idx)
+    }
+}
 
 /// Type alias for a cons-list containing fields with their labels, data type, and source
 /// designators.
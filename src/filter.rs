@@ -0,0 +1,128 @@
+/*!
+Predicate constructors for use with [DataView::filter](../view/struct.DataView.html#method.filter).
+
+Each function in this module returns a predicate over the values of a field (a function from
+`Value<&T>` to `bool`). Missing (NA) values never match these predicates.
+
+```
+# #[macro_use] extern crate agnes;
+use agnes::access::DataIndex;
+use agnes::cons::Nil;
+use agnes::field::FieldData;
+use agnes::filter::{between, is_in, is_in_field};
+use agnes::select::FieldSelect;
+use agnes::store::{DataStore, PushBackField};
+
+tablespace![
+    table emps {
+        EmpId: u64,
+        DeptId: u64,
+    }
+    table depts {
+        DeptId: u64,
+    }
+];
+
+fn main() {
+    let emps = DataStore::<Nil>::empty()
+        .push_back_field::<emps::EmpId, _>(FieldData::<u64>::from_vec(vec![0u64, 1, 2, 3, 4]))
+        .push_back_field::<emps::DeptId, _>(FieldData::<u64>::from_vec(vec![1u64, 2, 1, 3, 4]))
+        .into_view();
+
+    let filtered = emps.clone().filter::<emps::EmpId, _>(between(1, 3));
+    assert_eq!(filtered.field::<emps::EmpId>().to_vec(), vec![1u64, 2, 3]);
+
+    let filtered = emps.clone().filter::<emps::DeptId, _>(is_in(&[2, 4]));
+    assert_eq!(filtered.field::<emps::EmpId>().to_vec(), vec![1u64, 4]);
+
+    // keep the employees of departments which appear in another view (a semi-join)
+    let depts = DataStore::<Nil>::empty()
+        .push_back_field::<depts::DeptId, _>(FieldData::<u64>::from_vec(vec![3u64, 1]))
+        .into_view();
+    let filtered = emps.filter::<emps::DeptId, _>(is_in_field(&depts.field::<depts::DeptId>()));
+    assert_eq!(filtered.field::<emps::EmpId>().to_vec(), vec![0u64, 2, 3]);
+}
+```
+*/
+
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use access::DataIndex;
+use value::Value;
+
+/// Returns a predicate which matches existing values between `lo` and `hi` (inclusive).
+pub fn between<T>(lo: T, hi: T) -> impl Fn(Value<&T>) -> bool
+where
+    T: PartialOrd,
+{
+    move |value| match value {
+        Value::Exists(value) => lo <= *value && *value <= hi,
+        Value::Na => false,
+    }
+}
+
+/// Returns a predicate which matches existing values contained in `values`. The values are
+/// collected into a hash set, so each check takes constant time.
+pub fn is_in<'a, T, I>(values: I) -> impl Fn(Value<&T>) -> bool
+where
+    I: IntoIterator<Item = &'a T>,
+    T: 'a + Hash + Eq + Clone,
+{
+    let set = values.into_iter().cloned().collect::<HashSet<_>>();
+    move |value| match value {
+        Value::Exists(value) => set.contains(value),
+        Value::Na => false,
+    }
+}
+
+/// Returns a predicate which matches existing values contained in the field `field` (such as a
+/// field of another `DataView`), for filtering the records of one `DataView` to those with a
+/// matching record in another (a semi-join). The existing values of `field` are collected into a
+/// hash set of references to the values (which are not cloned), so each check takes constant time.
+pub fn is_in_field<'a, DI>(field: &'a DI) -> impl Fn(Value<&DI::DType>) -> bool + 'a
+where
+    DI: DataIndex,
+    DI::DType: Hash + Eq,
+{
+    let set = field
+        .iter()
+        .filter_map(|value| match value {
+            Value::Exists(value) => Some(value),
+            Value::Na => None,
+        })
+        .collect::<HashSet<_>>();
+    move |value| match value {
+        Value::Exists(value) => set.contains(value),
+        Value::Na => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use field::FieldData;
+    use permute::FilterPerm;
+
+    #[test]
+    fn predicates() {
+        let field = FieldData::from_field_vec(vec![
+            Value::Exists(4u64),
+            Value::Na,
+            Value::Exists(1),
+            Value::Exists(7),
+            Value::Exists(5),
+        ]);
+        assert_eq!(field.filter_perm(between(4, 5)), vec![0, 4]);
+        assert_eq!(field.filter_perm(between(5, 4)), Vec::<usize>::new());
+        assert_eq!(field.filter_perm(is_in(&[7, 1, 3])), vec![2, 3]);
+        assert_eq!(field.filter_perm(is_in(&[])), Vec::<usize>::new());
+
+        let other =
+            FieldData::from_field_vec(vec![Value::Exists(5u64), Value::Na, Value::Exists(4)]);
+        assert_eq!(field.filter_perm(is_in_field(&other)), vec![0, 4]);
+
+        let names = FieldData::from_vec(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(names.filter_perm(is_in(&["b".to_string()])), vec![1]);
+    }
+}
@@ -0,0 +1,357 @@
+/*!
+String formatting of numeric fields with control over precision, padding, and digit grouping.
+
+A [NumberFormat](struct.NumberFormat.html) describes how to render numeric values as text. It can
+be parsed from a format specification string similar to those of the standard library's `format!`
+macro:
+
+```text
+[[fill]align][+][width][,][.precision]
+```
+
+* `fill` -- the character used for padding (defaults to a space).
+* `align` -- `<` (left), `^` (center), or `>` (right, the default).
+* `+` -- always display the sign of non-negative values.
+* `width` -- the minimum width of the output.
+* `,` -- separate groups of thousands in the integral part with commas.
+* `.precision` -- the number of digits after the decimal point.
+
+For example, `">12,.2"` renders `1234567.891` as `"1,234,567.89"` right-aligned within 12
+characters. The [FormatValues](trait.FormatValues.html) trait formats the values of a single field,
+and the [DataView::format_field](../view/struct.DataView.html#method.format_field) method adds a
+formatted text field to a `DataView`.
+*/
+use std::fmt::Display;
+use std::str::FromStr;
+
+use num_traits::ToPrimitive;
+
+use access::DataIndex;
+use error;
+use field::FieldData;
+use frame::IntoFrame;
+use join::AddFrame;
+use select::{FieldSelect, SelectFieldByLabel};
+use store::IntoStore;
+use view::{DataView, VFieldTypeOf};
+
+/// Alignment of formatted values within their padded width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    /// Align values to the left.
+    Left,
+    /// Center values.
+    Center,
+    /// Align values to the right.
+    #[default]
+    Right,
+}
+
+/// Specification of how to render numeric values as text. See the [format](index.html) module
+/// for the format specification string syntax.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Character used for padding values narrower than `width`.
+    pub fill: char,
+    /// Alignment of values within `width`.
+    pub align: Align,
+    /// Whether to display a `+` sign for non-negative values.
+    pub sign_plus: bool,
+    /// Minimum width of the formatted values.
+    pub width: usize,
+    /// Separator inserted between groups of thousands, if any.
+    pub thousands: Option<char>,
+    /// Number of digits after the decimal point, if fixed.
+    pub precision: Option<usize>,
+}
+
+impl Default for NumberFormat {
+    fn default() -> NumberFormat {
+        NumberFormat {
+            fill: ' ',
+            align: Align::default(),
+            sign_plus: false,
+            width: 0,
+            thousands: None,
+            precision: None,
+        }
+    }
+}
+
+fn parse_align(c: char) -> Option<Align> {
+    match c {
+        '<' => Some(Align::Left),
+        '^' => Some(Align::Center),
+        '>' => Some(Align::Right),
+        _ => None,
+    }
+}
+
+impl FromStr for NumberFormat {
+    type Err = error::AgnesError;
+
+    fn from_str(spec: &str) -> error::Result<NumberFormat> {
+        let invalid =
+            || error::AgnesError::Format(format!("invalid format specification: '{}'", spec));
+        let mut fmt = NumberFormat::default();
+        let chars = spec.chars().collect::<Vec<_>>();
+        let mut pos = 0;
+
+        if let Some(align) = chars.get(1).and_then(|&c| parse_align(c)) {
+            fmt.fill = chars[0];
+            fmt.align = align;
+            pos = 2;
+        } else if let Some(align) = chars.first().and_then(|&c| parse_align(c)) {
+            fmt.align = align;
+            pos = 1;
+        }
+        if chars.get(pos) == Some(&'+') {
+            fmt.sign_plus = true;
+            pos += 1;
+        }
+        let take_digits = |pos: &mut usize| -> Option<usize> {
+            let start = *pos;
+            while chars.get(*pos).is_some_and(|c| c.is_ascii_digit()) {
+                *pos += 1;
+            }
+            chars[start..*pos].iter().collect::<String>().parse().ok()
+        };
+        if let Some(width) = take_digits(&mut pos) {
+            fmt.width = width;
+        }
+        if chars.get(pos) == Some(&',') {
+            fmt.thousands = Some(',');
+            pos += 1;
+        }
+        if chars.get(pos) == Some(&'.') {
+            pos += 1;
+            fmt.precision = Some(take_digits(&mut pos).ok_or_else(invalid)?);
+        }
+        if pos != chars.len() {
+            return Err(invalid());
+        }
+        Ok(fmt)
+    }
+}
+
+impl NumberFormat {
+    /// Formats `value` according to this specification.
+    pub fn format<T>(&self, value: &T) -> String
+    where
+        T: Display + ToPrimitive,
+    {
+        let plain = value.to_string();
+        let rendered = match self.precision {
+            // integral values are padded with zeros directly rather than formatted through `f64`,
+            // which cannot represent large integers exactly
+            Some(precision) if is_integral(&plain) => {
+                if precision == 0 {
+                    plain
+                } else {
+                    let mut rendered = plain;
+                    rendered.push('.');
+                    rendered.extend((0..precision).map(|_| '0'));
+                    rendered
+                }
+            }
+            Some(precision) => match value.to_f64() {
+                Some(value) => format!("{:.*}", precision, value),
+                None => plain,
+            },
+            None => plain,
+        };
+        let (negative, unsigned) = match rendered.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, rendered.as_str()),
+        };
+        let mut body = match self.thousands {
+            Some(separator) => group_thousands(unsigned, separator),
+            None => unsigned.to_string(),
+        };
+        if negative {
+            body.insert(0, '-');
+        } else if self.sign_plus {
+            body.insert(0, '+');
+        }
+        self.pad(&body)
+    }
+
+    /// Pads the already-rendered `body` to this specification's width, with its fill character
+    /// and alignment.
+    pub(crate) fn pad(&self, body: &str) -> String {
+        let padding = self.width.saturating_sub(body.chars().count());
+        let (left, right) = match self.align {
+            Align::Left => (0, padding),
+            Align::Center => (padding / 2, padding - padding / 2),
+            Align::Right => (padding, 0),
+        };
+        let mut output = String::with_capacity(body.len() + padding);
+        output.extend((0..left).map(|_| self.fill));
+        output.push_str(body);
+        output.extend((0..right).map(|_| self.fill));
+        output
+    }
+}
+
+/// Returns whether `rendered` is the rendering of an integral value (an optional minus sign
+/// followed by only digits).
+fn is_integral(rendered: &str) -> bool {
+    let digits = rendered.strip_prefix('-').unwrap_or(rendered);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Inserts `separator` between groups of thousands in the integral part of the unsigned number
+/// `unsigned`. Non-finite values (e.g. `inf`, `NaN`) are left unchanged.
+fn group_thousands(unsigned: &str, separator: char) -> String {
+    let int_len = unsigned
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(unsigned.len());
+    let (int_part, rest) = unsigned.split_at(int_len);
+    let mut grouped = String::with_capacity(unsigned.len() + int_len / 3);
+    for (i, c) in int_part.chars().enumerate() {
+        if i > 0 && (int_len - i) % 3 == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(c);
+    }
+    grouped.push_str(rest);
+    grouped
+}
+
+/// A trait for formatting the values of a numeric field as text.
+pub trait FormatValues {
+    /// Returns a text field containing the values of this field formatted according to `fmt`.
+    /// Missing (NA) values remain missing.
+    fn format_values(&self, fmt: &NumberFormat) -> FieldData<String>;
+}
+
+impl<DI> FormatValues for DI
+where
+    DI: DataIndex,
+    DI::DType: Display + ToPrimitive,
+{
+    fn format_values(&self, fmt: &NumberFormat) -> FieldData<String> {
+        self.iter()
+            .map(|value| value.map(|value| fmt.format(value)))
+            .collect()
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Creates a new `DataView` with an additional text field labeled `DstLabel`, containing the
+    /// values of the numeric field labeled `SrcLabel` formatted according to the format
+    /// specification string `fmt`. See the [format](../format/index.html) module for the format
+    /// specification syntax.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Format` if `fmt` is not a valid format specification.
+    pub fn format_field<SrcLabel, DstLabel>(
+        &self,
+        fmt: &str,
+    ) -> error::Result<<Self as FormatField<SrcLabel, DstLabel>>::Output>
+    where
+        Self: FormatField<SrcLabel, DstLabel>,
+    {
+        Ok(FormatField::<SrcLabel, DstLabel>::format_field(
+            self,
+            &fmt.parse()?,
+        ))
+    }
+}
+
+/// Trait providing the `format_field` method for adding a formatted text field to a `DataView`.
+/// See the intrinsic method [format_field](../view/struct.DataView.html#method.format_field) for
+/// more details.
+pub trait FormatField<SrcLabel, DstLabel> {
+    /// Type produced by this method.
+    type Output;
+
+    /// Add the formatted field. See the intrinsic method
+    /// [format_field](../view/struct.DataView.html#method.format_field) for more details.
+    fn format_field(&self, fmt: &NumberFormat) -> Self::Output;
+}
+
+/// Type of the frame added by [FormatField](trait.FormatField.html).
+pub type FormattedFrameOf<DstLabel> =
+    <<FieldData<String> as IntoStore<DstLabel>>::Output as IntoFrame>::Output;
+
+impl<Labels, Frames, SrcLabel, DstLabel> FormatField<SrcLabel, DstLabel>
+    for DataView<Labels, Frames>
+where
+    Self: SelectFieldByLabel<SrcLabel> + AddFrame<FormattedFrameOf<DstLabel>>,
+    VFieldTypeOf<Self, SrcLabel>: Display + ToPrimitive,
+    FieldData<String>: IntoStore<DstLabel>,
+    <FieldData<String> as IntoStore<DstLabel>>::Output: IntoFrame,
+{
+    type Output = <Self as AddFrame<FormattedFrameOf<DstLabel>>>::Output;
+
+    fn format_field(&self, fmt: &NumberFormat) -> Self::Output {
+        let formatted = self.field::<SrcLabel>().format_values(fmt);
+        self.add_frame(IntoStore::<DstLabel>::into_store(formatted).into_frame())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use value::Value;
+
+    fn format<T: Display + ToPrimitive>(spec: &str, value: T) -> String {
+        spec.parse::<NumberFormat>().unwrap().format(&value)
+    }
+
+    #[test]
+    fn number_format() {
+        assert_eq!(format("", 1234.5), "1234.5");
+        assert_eq!(format(".2", 1234.567), "1234.57");
+        assert_eq!(format(",", 1234567u64), "1,234,567");
+        assert_eq!(format(",.1", -1234.06), "-1,234.1");
+        assert_eq!(format(",", 123i64), "123");
+        assert_eq!(format("8", 42u32), "      42");
+        assert_eq!(format("<6", 42u32), "42    ");
+        assert_eq!(format("*^7", 42u32), "**42***");
+        assert_eq!(format("0>+6.1", 3.25), "00+3.2");
+        assert_eq!(format(">12,.2", 1234567.891), "1,234,567.89");
+        assert_eq!(format(",", f64::INFINITY), "inf");
+        // large integers are formatted exactly
+        assert_eq!(format(",.2", u64::MAX), "18,446,744,073,709,551,615.00");
+        assert_eq!(format(".0", i64::MIN), "-9223372036854775808");
+        assert_eq!(format(".1", 9007199254740993u64), "9007199254740993.0");
+
+        assert!("8x".parse::<NumberFormat>().is_err());
+        assert!(".".parse::<NumberFormat>().is_err());
+    }
+
+    #[test]
+    fn format_values() {
+        let field = FieldData::from_field_vec(vec![Value::Exists(1500.0f64), Value::Na]);
+        let fmt = ",.2".parse::<NumberFormat>().unwrap();
+        assert_eq!(
+            field.format_values(&fmt).to_value_vec(),
+            vec![Value::Exists("1,500.00".to_string()), Value::Na]
+        );
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn format_field() {
+        use test_utils::*;
+
+        let dv = sample_emp_table().into_view();
+        assert!(dv
+            .format_field::<emp_table::EmpId, dept_table::DeptName>("?")
+            .is_err());
+        let dv = dv
+            .format_field::<emp_table::EmpId, dept_table::DeptName>("0>3")
+            .unwrap();
+        assert_eq!(
+            dv.fieldnames(),
+            vec!["EmpId", "DeptId", "EmpName", "DeptName"]
+        );
+        assert_eq!(
+            dv.field::<dept_table::DeptName>().to_vec()[..3],
+            ["000".to_string(), "002".to_string(), "005".to_string()]
+        );
+    }
+}
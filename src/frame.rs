@@ -10,19 +10,21 @@ use std::marker::PhantomData;
 
 #[cfg(feature = "serialize")]
 use serde::ser::{Serialize, SerializeSeq, Serializer};
+use std::borrow::Cow;
 use std::fmt::Debug;
 use std::rc::Rc;
 use std::sync::Arc;
 
 use typenum::UTerm;
 
-use access::{DataIndex, NRows};
+use access::{gather_chunk, DataChunk, DataIndex, DataIterator, NRows, RawColumn, RawColumnar};
 use cons::Nil;
 use error;
 use field::FieldData;
 use fieldlist::FieldCons;
 use label::*;
-use permute::{self, UpdatePermutation};
+use memory::{FieldMemory, MemoryUsage};
+use permute::{self, FramePermutations, OptimizeAccess, UpdatePermutation};
 use select::{FieldSelect, SelectFieldByLabel};
 use store::{AssocFrameLookup, AssocStorage, DataRef, DataStore, IntoView};
 use value::Value;
@@ -179,8 +181,35 @@ impl<FrameFields, FramedStore> StoreRefCount for DataFrame<FrameFields, FramedSt
 }
 impl<FrameFields, FramedStore> UpdatePermutation for DataFrame<FrameFields, FramedStore> {
     fn update_permutation(mut self, new_permutation: &[usize]) -> Self {
-        let perm = (*self.permutation).clone();
-        self.permutation = Rc::new(perm.update_indices(new_permutation));
+        self.permutation = Rc::new(self.permutation.compose(new_permutation));
+        self
+    }
+}
+impl<FrameFields, FramedStore> FramePermutations for DataFrame<FrameFields, FramedStore> {
+    fn frame_permutations(&self, perms: &mut Vec<Rc<Permutation>>) {
+        perms.push(Rc::clone(&self.permutation));
+    }
+}
+impl<FrameFields, FramedStore> OptimizeAccess for DataFrame<FrameFields, FramedStore>
+where
+    FramedStore: NRows,
+{
+    fn optimize_access(mut self, shared: &mut Vec<Rc<Permutation>>) -> Self {
+        if !self.permutation.is_permuted() {
+            return self;
+        }
+        if self.permutation.is_identity(self.store.nrows()) {
+            // unpermuted fields provide direct (contiguous) access to their data
+            self.permutation = Rc::new(Permutation::default());
+            return self;
+        }
+        match shared
+            .iter()
+            .find(|perm| Rc::ptr_eq(perm, &self.permutation) || **perm == self.permutation)
+        {
+            Some(perm) => self.permutation = Rc::clone(perm),
+            None => shared.push(Rc::clone(&self.permutation)),
+        }
         self
     }
 }
@@ -409,6 +438,23 @@ where
         }
     }
 }
+impl<T, DI> MemoryUsage for Framed<T, DI>
+where
+    DI: MemoryUsage,
+{
+    fn memory_usage(&self) -> FieldMemory {
+        // report the memory of the underlying field(s); the permutation is shared by the frame
+        match self.data {
+            FrameKind::Single(ref field) => field.memory_usage(),
+            FrameKind::Melt(ref fields) => {
+                fields.iter().fold(FieldMemory::default(), |acc, field| {
+                    acc + field.memory_usage()
+                })
+            }
+        }
+    }
+}
+
 impl<T> From<DataRef<T>> for Framed<T, DataRef<T>> {
     fn from(orig: DataRef<T>) -> Framed<T, DataRef<T>> {
         Framed {
@@ -452,6 +498,55 @@ where
         // nfields * nrows
         self.data.nfields() * self.permutation.len().unwrap_or(self.data.nrows())
     }
+    fn get_chunk(&self, start: usize, len: usize) -> error::Result<DataChunk<'_, T>> {
+        assert!(!self.data.is_empty());
+        match self.data {
+            // unpermuted single fields can provide their underlying data directly
+            FrameKind::Single(ref field) if self.permutation.len().is_none() => {
+                field.get_chunk(start, len)
+            }
+            _ => gather_chunk(self, start, len),
+        }
+    }
+}
+impl<T, DI> RawColumnar for Framed<T, DI>
+where
+    T: Debug,
+    DI: RawColumnar<DType = T> + Debug,
+{
+    fn raw_column(&self) -> error::Result<RawColumn<'_, T>> {
+        match self.data {
+            FrameKind::Single(ref field) => {
+                let (values, na_mask, inner) = field.raw_column()?.into_parts();
+                let permutation = match (inner, self.permutation.as_slice()) {
+                    // the frame's permutation indexes into the inner field's permutation
+                    (Some(inner), Some(outer)) => Permutation::from(inner.into_owned())
+                        .compose(outer)
+                        .into_vec()
+                        .map(Cow::Owned),
+                    (Some(inner), None) => Some(inner),
+                    (None, outer) => outer.map(Cow::Borrowed),
+                };
+                Ok(RawColumn::from_storage(values, na_mask, permutation))
+            }
+            FrameKind::Melt(_) => Err(error::AgnesError::DimensionMismatch(
+                "melted fields have no single raw value buffer".into(),
+            )),
+        }
+    }
+}
+
+impl<'a, T, DI> IntoIterator for &'a Framed<T, DI>
+where
+    T: 'a + Debug,
+    DI: DataIndex<DType = T> + Debug,
+{
+    type Item = Value<&'a T>;
+    type IntoIter = DataIterator<'a, T>;
+
+    fn into_iter(self) -> DataIterator<'a, T> {
+        self.iter()
+    }
 }
 
 #[cfg(feature = "serialize")]
@@ -487,10 +582,15 @@ pub trait SelectAndFrame<Label, FramedStore> {
 }
 
 /// Helper trait for selecting and framing fields. Used by
-/// [SelectAndFrame](trait.SelectAndFrame.html). `Label` is the label to select, `FramedStore` is
-/// the struct the data is stored in, and `Match` is whether or not `Label` matches the head
-/// label in this type.
-pub trait SelectAndFrameMatch<Label, FramedStore, Match> {
+/// [SelectAndFrame](trait.SelectAndFrame.html). Implemented by the
+/// [StoreFieldMarkers](struct.StoreFieldMarkers.html) associated with the label `Label` in a
+/// frame's [FieldLookupCons](type.FieldLookupCons.html), where `FramedStore` is the struct the data
+/// is stored in.
+///
+/// The `StoreFieldMarkers` for a label is found with a type-level lookup, so selecting a field does
+/// not recurse through the frame's fields at run-time. This keeps the number of functions the
+/// compiler instantiates for each selected field small, which matters for very wide frames.
+pub trait SelectAndFrameMarked<Label, FramedStore> {
     /// The resultant data type of the field.
     type DType: Debug;
     /// The field accessor type.
@@ -503,39 +603,33 @@ pub trait SelectAndFrameMatch<Label, FramedStore, Match> {
     ) -> Framed<Self::DType, Self::Field>;
 }
 
-impl<TargetLabel, FrameLabel, StoreDetails, Tail, FramedStore>
-    SelectAndFrame<TargetLabel, FramedStore> for FieldLookupCons<FrameLabel, StoreDetails, Tail>
+impl<TargetLabel, FrameFields, FramedStore> SelectAndFrame<TargetLabel, FramedStore> for FrameFields
 where
-    TargetLabel: LabelEq<FrameLabel>,
-    FieldLookupCons<FrameLabel, StoreDetails, Tail>:
-        SelectAndFrameMatch<TargetLabel, FramedStore, <TargetLabel as LabelEq<FrameLabel>>::Eq>,
+    FrameFields: LookupMarkedElemByLabel<TargetLabel>,
+    MarkerOfElemOf<FrameFields, TargetLabel>: SelectAndFrameMarked<TargetLabel, FramedStore>,
 {
-    type DType = <FieldLookupCons<FrameLabel, StoreDetails, Tail> as SelectAndFrameMatch<
+    type DType = <MarkerOfElemOf<FrameFields, TargetLabel> as SelectAndFrameMarked<
         TargetLabel,
         FramedStore,
-        <TargetLabel as LabelEq<FrameLabel>>::Eq,
     >>::DType;
-    type Field = <FieldLookupCons<FrameLabel, StoreDetails, Tail> as SelectAndFrameMatch<
+    type Field = <MarkerOfElemOf<FrameFields, TargetLabel> as SelectAndFrameMarked<
         TargetLabel,
         FramedStore,
-        <TargetLabel as LabelEq<FrameLabel>>::Eq,
     >>::Field;
 
     fn select_and_frame(
         perm: &Rc<Permutation>,
         store: &FramedStore,
     ) -> Framed<Self::DType, Self::Field> {
-        <Self as SelectAndFrameMatch<
+        <MarkerOfElemOf<FrameFields, TargetLabel> as SelectAndFrameMarked<
             TargetLabel,
             FramedStore,
-            <TargetLabel as LabelEq<FrameLabel>>::Eq,
         >>::select_and_frame(perm, store)
     }
 }
 
-impl<TargetLabel, FrameLabel, StoreFieldList, Tail, FramedStore>
-    SelectAndFrameMatch<TargetLabel, FramedStore, True>
-    for FieldLookupCons<FrameLabel, StoreFieldMarkers<Single, StoreFieldList>, Tail>
+impl<TargetLabel, StoreFieldList, FramedStore> SelectAndFrameMarked<TargetLabel, FramedStore>
+    for StoreFieldMarkers<Single, StoreFieldList>
 where
     FramedStore: SelectFieldByLabel<TargetLabel>,
     <FramedStore as SelectFieldByLabel<TargetLabel>>::DType: Debug,
@@ -554,9 +648,8 @@ where
     }
 }
 
-impl<TargetLabel, FrameLabel, StoreFieldList, Tail, FramedStore>
-    SelectAndFrameMatch<TargetLabel, FramedStore, True>
-    for FieldLookupCons<FrameLabel, StoreFieldMarkers<Melt, StoreFieldList>, Tail>
+impl<TargetLabel, StoreFieldList, FramedStore> SelectAndFrameMarked<TargetLabel, FramedStore>
+    for StoreFieldMarkers<Melt, StoreFieldList>
 where
     StoreFieldList: RotateFields<FramedStore>,
     <StoreFieldList as RotateFields<FramedStore>>::DType: Debug,
@@ -577,23 +670,6 @@ where
     }
 }
 
-impl<TargetLabel, FrameLabel, StoreDetails, Tail, FramedStore>
-    SelectAndFrameMatch<TargetLabel, FramedStore, False>
-    for FieldLookupCons<FrameLabel, StoreDetails, Tail>
-where
-    Tail: SelectAndFrame<TargetLabel, FramedStore>,
-{
-    type DType = <Tail as SelectAndFrame<TargetLabel, FramedStore>>::DType;
-    type Field = <Tail as SelectAndFrame<TargetLabel, FramedStore>>::Field;
-
-    fn select_and_frame(
-        perm: &Rc<Permutation>,
-        store: &FramedStore,
-    ) -> Framed<Self::DType, Self::Field> {
-        <Tail as SelectAndFrame<TargetLabel, FramedStore>>::select_and_frame(perm, store)
-    }
-}
-
 /// Trait for generating a collection of objects implementing
 /// [DataIndex](../access/trait.DataIndex.html) with the same underlying type. Used for rotating
 /// through source fields in a [Melt](struct.Melt.html)ed field.
@@ -682,6 +758,7 @@ mod tests {
     use std::path::Path;
 
     use csv_sniffer::metadata::Metadata;
+    #[cfg(feature = "serialize")]
     use serde_json;
 
     use super::*;
@@ -735,6 +812,7 @@ mod tests {
         println!("{:?}", frame.field::<gdp::CountryName>());
     }
 
+    #[cfg(feature = "serialize")]
     #[test]
     fn framed_serialize() {
         let field: FieldData<f64> = vec![5.0f64, 3.4, -1.3, 5.2, 6.0, -126.9].into();
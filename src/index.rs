@@ -0,0 +1,723 @@
+/*!
+Persistent indexes over key fields, for accelerating repeated lookups, joins, and group-bys.
+
+Operations like [join](../view/struct.DataView.html#method.join),
+[filter](../view/struct.DataView.html#method.filter), and
+[aggregate_many](../view/struct.DataView.html#method.aggregate_many) hash or sort their key field
+every time they are called. When the same (unchanged) field is used as a key repeatedly, an index
+can be built over it once and reused instead:
+
+* [HashIndex](struct.HashIndex.html) -- maps each distinct value to the records containing it.
+  Built with [DataView::build_index](../view/struct.DataView.html#method.build_index).
+* [SortedIndex](struct.SortedIndex.html) -- stores the records in sorted order of their values,
+  supporting range queries as well as point lookups. Built with
+  [DataView::build_sorted_index](../view/struct.DataView.html#method.build_sorted_index).
+
+Both implement the [FieldIndex](trait.FieldIndex.html) trait, and can be used with the
+[filter_indexed](../view/struct.DataView.html#method.filter_indexed),
+[join_indexed](../view/struct.DataView.html#method.join_indexed), and
+[aggregate_many_indexed](../view/struct.DataView.html#method.aggregate_many_indexed) methods.
+An index does not track later changes to the `DataView` it was built from. For indexes built from a
+`DataView`, these methods check that they are applied to a view with the same records in the same
+order -- the view the index was built from, or a clone, subview, or extension (with further fields)
+of it -- and that the index was built over the key field they use. Sorting, filtering, or otherwise
+reordering the view (including [optimize_access](../view/struct.DataView.html#method.optimize_access))
+produces a view the index no longer applies to, and the index must be rebuilt. Indexes built
+directly from field data are only checked against the number of records.
+*/
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::{Bound, RangeBounds};
+use std::rc::{Rc, Weak};
+
+use access::{DataIndex, NRows};
+use aggregate::{AggregateMany, AggregateManyOutputOf};
+use cons::Nil;
+use erased::Groups;
+use error::*;
+use join::{Equal, Join, JoinIntoStore, JoinedViewOf};
+use label::{self, LCons};
+use observer::NoObserver;
+use permute::{FramePermutations, Permutation, UpdatePermutation};
+use select::{FieldSelect, SelectFieldByLabel};
+use store::{DataStore, IntoView};
+use value::Value;
+use view::{DataView, VFieldTypeOf};
+
+/// Common interface of the indexes in this module.
+pub trait FieldIndex {
+    /// The data type of the indexed field.
+    type DType;
+
+    /// Returns the number of records in the indexed field.
+    fn nrows(&self) -> usize;
+
+    /// Returns the indices (in increasing order) of the records whose value is equal to `value`.
+    fn lookup(&self, value: &Self::DType) -> &[usize];
+
+    /// Returns the indices (in increasing order) of the records with missing (NA) values.
+    fn na_indices(&self) -> &[usize];
+
+    /// Returns the grouping of the records of the indexed field by value, as used by group-by
+    /// operations. Missing (NA) values form a group of their own.
+    fn groups(&self) -> Groups;
+
+    /// Returns the key field this index was built over, if it was built from a `DataView` (with
+    /// [build_index](../view/struct.DataView.html#method.build_index) or
+    /// [build_sorted_index](../view/struct.DataView.html#method.build_sorted_index)). Indexes
+    /// built directly from field data return `None`.
+    fn key(&self) -> Option<&IndexKey> {
+        None
+    }
+
+    /// Returns the records of the `DataView` this index was built over, if it was built from a
+    /// `DataView`. Indexes built directly from field data return `None`.
+    fn source(&self) -> Option<&IndexSource> {
+        None
+    }
+}
+
+/// Identifies the key field (by label) an index was built over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexKey {
+    label: TypeId,
+    // `Labels![Label]`, for comparison with the key labels of group-by operations
+    labels: TypeId,
+    name: &'static str,
+}
+
+impl IndexKey {
+    fn of<Label>() -> IndexKey
+    where
+        Label: label::Label + 'static,
+    {
+        IndexKey {
+            label: TypeId::of::<Label>(),
+            labels: TypeId::of::<LCons<Label, Nil>>(),
+            name: Label::NAME,
+        }
+    }
+
+    /// Returns the name of the key field.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// Identifies the records (and their order) of the `DataView` an index was built over, by the
+/// permutations of the view's frames.
+#[derive(Debug, Clone, Default)]
+pub struct IndexSource {
+    // held weakly so that the index doesn't keep the permutations' index arrays alive (and so that
+    // a permutation's address isn't reused for another while the index exists)
+    frames: Vec<Weak<Permutation<Vec<usize>>>>,
+}
+
+impl IndexSource {
+    fn of<Frames: FramePermutations>(frames: &Frames) -> IndexSource {
+        let mut perms = vec![];
+        frames.frame_permutations(&mut perms);
+        IndexSource {
+            frames: perms.iter().map(Rc::downgrade).collect(),
+        }
+    }
+
+    // Returns whether a view with frames `frames` shares a frame (and therefore its records and
+    // their order) with the view this index was built over.
+    fn matches<Frames: FramePermutations>(&self, frames: &Frames) -> bool {
+        let mut perms = vec![];
+        frames.frame_permutations(&mut perms);
+        perms.iter().any(|perm| {
+            self.frames
+                .iter()
+                .any(|source| source.as_ptr() == Rc::as_ptr(perm))
+        })
+    }
+}
+
+// Builds a `Groups` from the records in each group, in order of `group_rows`.
+fn groups_from_rows<'a, I>(group_rows: I, nrows: usize) -> Groups
+where
+    I: Iterator<Item = &'a [usize]>,
+{
+    let mut first_indices = vec![];
+    let mut record_groups = vec![0; nrows];
+    for (group, rows) in group_rows.filter(|rows| !rows.is_empty()).enumerate() {
+        first_indices.push(rows[0]);
+        for &row in rows {
+            record_groups[row] = group;
+        }
+    }
+    Groups {
+        first_indices,
+        record_groups,
+    }
+}
+
+/// A hash-based index over a field, mapping each distinct value to the records containing it.
+#[derive(Debug, Clone)]
+pub struct HashIndex<T> {
+    // group of each distinct value
+    key_groups: HashMap<T, usize>,
+    // records of each group, in order of first appearance (including the missing value group)
+    group_rows: Vec<Vec<usize>>,
+    na_group: Option<usize>,
+    nrows: usize,
+    key: Option<IndexKey>,
+    source: Option<IndexSource>,
+}
+
+impl<T> HashIndex<T>
+where
+    T: Hash + Eq + Clone,
+{
+    /// Builds a new hash index over the values of `data`.
+    pub fn new<DI>(data: &DI) -> HashIndex<T>
+    where
+        DI: DataIndex<DType = T>,
+    {
+        let mut key_groups = HashMap::new();
+        let mut group_rows: Vec<Vec<usize>> = vec![];
+        let mut na_group = None;
+        for (idx, value) in data.iter().enumerate() {
+            let group = match value {
+                // keys are only cloned for the first record in each group
+                Value::Exists(value) => match key_groups.get(value) {
+                    Some(&group) => group,
+                    None => {
+                        group_rows.push(vec![]);
+                        key_groups.insert(value.clone(), group_rows.len() - 1);
+                        group_rows.len() - 1
+                    }
+                },
+                Value::Na => *na_group.get_or_insert_with(|| {
+                    group_rows.push(vec![]);
+                    group_rows.len() - 1
+                }),
+            };
+            group_rows[group].push(idx);
+        }
+        HashIndex {
+            key_groups,
+            group_rows,
+            na_group,
+            nrows: data.len(),
+            key: None,
+            source: None,
+        }
+    }
+}
+
+impl<T> HashIndex<T> {
+    /// Returns the number of distinct (non-missing) values in the indexed field.
+    pub fn ndistinct(&self) -> usize {
+        self.key_groups.len()
+    }
+}
+
+impl<T> FieldIndex for HashIndex<T>
+where
+    T: Hash + Eq,
+{
+    type DType = T;
+
+    fn nrows(&self) -> usize {
+        self.nrows
+    }
+    fn lookup(&self, value: &T) -> &[usize] {
+        match self.key_groups.get(value) {
+            Some(&group) => &self.group_rows[group],
+            None => &[],
+        }
+    }
+    fn na_indices(&self) -> &[usize] {
+        match self.na_group {
+            Some(group) => &self.group_rows[group],
+            None => &[],
+        }
+    }
+    /// Groups are in order of first appearance in the indexed field.
+    fn groups(&self) -> Groups {
+        groups_from_rows(
+            self.group_rows.iter().map(|rows| rows.as_slice()),
+            self.nrows,
+        )
+    }
+    fn key(&self) -> Option<&IndexKey> {
+        self.key.as_ref()
+    }
+    fn source(&self) -> Option<&IndexSource> {
+        self.source.as_ref()
+    }
+}
+
+/// A sort-based index over a field, storing the records in (stable) sorted order of their values.
+/// In addition to point lookups, supports [range](#method.range) queries.
+#[derive(Debug, Clone)]
+pub struct SortedIndex<T> {
+    // distinct (non-missing) values, in ascending order
+    keys: Vec<T>,
+    // `order[bounds[i]..bounds[i + 1]]` are the records with value `keys[i]`
+    bounds: Vec<usize>,
+    // records with existing values, in sorted order
+    order: Vec<usize>,
+    na: Vec<usize>,
+    key: Option<IndexKey>,
+    source: Option<IndexSource>,
+}
+
+impl<T> SortedIndex<T>
+where
+    T: Ord + Clone,
+{
+    /// Builds a new sorted index over the values of `data`.
+    pub fn new<DI>(data: &DI) -> SortedIndex<T>
+    where
+        DI: DataIndex<DType = T>,
+    {
+        let mut na = vec![];
+        let mut existing = vec![];
+        for (idx, value) in data.iter().enumerate() {
+            match value {
+                Value::Exists(value) => existing.push((value, idx)),
+                Value::Na => na.push(idx),
+            }
+        }
+        existing.sort_by(|left, right| left.0.cmp(right.0));
+
+        let mut keys: Vec<T> = vec![];
+        let mut bounds = vec![0];
+        for (pos, &(value, _)) in existing.iter().enumerate() {
+            if keys.last() != Some(value) {
+                if pos > 0 {
+                    bounds.push(pos);
+                }
+                keys.push(value.clone());
+            }
+        }
+        bounds.push(existing.len());
+        if keys.is_empty() {
+            bounds.truncate(1);
+        }
+        SortedIndex {
+            keys,
+            bounds,
+            order: existing.into_iter().map(|(_, idx)| idx).collect(),
+            na,
+            key: None,
+            source: None,
+        }
+    }
+}
+
+impl<T> SortedIndex<T>
+where
+    T: Ord,
+{
+    /// Returns the number of distinct (non-missing) values in the indexed field.
+    pub fn ndistinct(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns the distinct (non-missing) values of the indexed field, in ascending order.
+    pub fn keys(&self) -> &[T] {
+        &self.keys
+    }
+
+    /// Returns the indices of the records whose value is within `range`, in ascending order of
+    /// value (and increasing order of index for equal values).
+    pub fn range<R>(&self, range: R) -> &[usize]
+    where
+        R: RangeBounds<T>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(lo) => self.keys.partition_point(|key| key < lo),
+            Bound::Excluded(lo) => self.keys.partition_point(|key| key <= lo),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(hi) => self.keys.partition_point(|key| key <= hi),
+            Bound::Excluded(hi) => self.keys.partition_point(|key| key < hi),
+            Bound::Unbounded => self.keys.len(),
+        };
+        if start >= end {
+            return &[];
+        }
+        &self.order[self.bounds[start]..self.bounds[end]]
+    }
+}
+
+impl<T> FieldIndex for SortedIndex<T>
+where
+    T: Ord,
+{
+    type DType = T;
+
+    fn nrows(&self) -> usize {
+        self.order.len() + self.na.len()
+    }
+    fn lookup(&self, value: &T) -> &[usize] {
+        match self.keys.binary_search(value) {
+            Ok(pos) => &self.order[self.bounds[pos]..self.bounds[pos + 1]],
+            Err(_) => &[],
+        }
+    }
+    fn na_indices(&self) -> &[usize] {
+        &self.na
+    }
+    /// Groups are in ascending order of value, with the missing value group (if any) first.
+    fn groups(&self) -> Groups {
+        let na = Some(self.na.as_slice());
+        let keyed = self.bounds.windows(2).map(|b| &self.order[b[0]..b[1]]);
+        groups_from_rows(na.into_iter().chain(keyed), self.nrows())
+    }
+    fn key(&self) -> Option<&IndexKey> {
+        self.key.as_ref()
+    }
+    fn source(&self) -> Option<&IndexSource> {
+        self.source.as_ref()
+    }
+}
+
+fn check_index<I, Labels, Frames>(index: &I, view: &DataView<Labels, Frames>) -> Result<()>
+where
+    I: FieldIndex,
+    DataView<Labels, Frames>: NRows,
+    Frames: FramePermutations,
+{
+    if index.nrows() != view.nrows() {
+        return Err(AgnesError::LengthMismatch {
+            expected: view.nrows(),
+            actual: index.nrows(),
+        });
+    }
+    match index.source() {
+        Some(source) if !source.matches(&view.frames) => Err(AgnesError::IndexMismatch(
+            "index was built over a different view, or over this view's records in a different \
+             order"
+                .to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn check_key<I, F>(index: &I, matches: F) -> Result<()>
+where
+    I: FieldIndex,
+    F: Fn(&IndexKey) -> bool,
+{
+    match index.key() {
+        Some(key) if !matches(key) => Err(AgnesError::IndexMismatch(format!(
+            "index was built over field '{}', not the key field of this operation",
+            key.name
+        ))),
+        _ => Ok(()),
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Builds a [HashIndex](../index/struct.HashIndex.html) over the field labeled `Label`, which
+    /// can be reused across lookups, joins, and group-bys on that field. See the
+    /// [index](../index/index.html) module for more details.
+    pub fn build_index<Label>(&self) -> HashIndex<VFieldTypeOf<Self, Label>>
+    where
+        Self: SelectFieldByLabel<Label>,
+        Frames: FramePermutations,
+        Label: label::Label + 'static,
+        VFieldTypeOf<Self, Label>: Hash + Eq + Clone,
+    {
+        let mut index = HashIndex::new(&self.field::<Label>());
+        index.key = Some(IndexKey::of::<Label>());
+        index.source = Some(IndexSource::of(&self.frames));
+        index
+    }
+
+    /// Builds a [SortedIndex](../index/struct.SortedIndex.html) over the field labeled `Label`,
+    /// which can be reused across lookups (including range queries), joins, and group-bys on that
+    /// field. See the [index](../index/index.html) module for more details.
+    pub fn build_sorted_index<Label>(&self) -> SortedIndex<VFieldTypeOf<Self, Label>>
+    where
+        Self: SelectFieldByLabel<Label>,
+        Frames: FramePermutations,
+        Label: label::Label + 'static,
+        VFieldTypeOf<Self, Label>: Ord + Clone,
+    {
+        let mut index = SortedIndex::new(&self.field::<Label>());
+        index.key = Some(IndexKey::of::<Label>());
+        index.source = Some(IndexSource::of(&self.frames));
+        index
+    }
+
+    /// Returns a new `DataView` containing only the records whose indexed value (as indexed by
+    /// `index`, built over a field of this `DataView`) is equal to `value`, in their original
+    /// order.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::LengthMismatch` if `index` was not built over a field with the same
+    /// number of records as this `DataView`, and with `AgnesError::IndexMismatch` if `index` was
+    /// built (from a `DataView`) over different records or a different record order than this
+    /// `DataView`'s.
+    pub fn filter_indexed<I>(&self, index: &I, value: &I::DType) -> Result<Self>
+    where
+        Self: NRows,
+        Frames: Clone + UpdatePermutation + FramePermutations,
+        I: FieldIndex,
+    {
+        check_index(index, self)?;
+        Ok(self.subset_records(index.lookup(value)))
+    }
+
+    /// Combine two `DataView` objects using the specified equality join, where `index` (built over
+    /// the right-hand key field of `right`) is used to find matching records rather than hashing
+    /// or sorting the right-hand key field again. The joined records are in the same order as
+    /// those produced by [par_join](#method.par_join). See
+    /// [IndexedJoin](../index/trait.IndexedJoin.html).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::LengthMismatch` if `index` was not built over a field with the same
+    /// number of records as `right`, and with `AgnesError::IndexMismatch` if `index` was built
+    /// (from a `DataView`) over a field other than the right-hand key field, or over different
+    /// records or a different record order than `right`'s.
+    pub fn join_indexed<Join, RLabels, RFrames, I>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        index: &I,
+    ) -> Result<<Self as IndexedJoin<RLabels, RFrames, Join, I>>::Output>
+    where
+        Self: IndexedJoin<RLabels, RFrames, Join, I>,
+    {
+        IndexedJoin::join_indexed(self, right, index)
+    }
+
+    /// Computes several aggregations as in [aggregate_many](#method.aggregate_many), using the
+    /// grouping provided by `index` (built over the single key field in `KeyLabels`) rather than
+    /// grouping the records again. Groups appear in the order given by
+    /// [FieldIndex::groups](../index/trait.FieldIndex.html#tymethod.groups).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::LengthMismatch` if `index` was not built over a field with the same
+    /// number of records as this `DataView`, and with `AgnesError::IndexMismatch` if `index` was
+    /// built (from a `DataView`) over a field other than the key field in `KeyLabels`, or over
+    /// different records or a different record order than this `DataView`'s.
+    pub fn aggregate_many_indexed<KeyLabels, Aggs, I>(
+        &self,
+        index: &I,
+        aggs: Aggs,
+    ) -> Result<AggregateManyOutputOf<Self, KeyLabels, Aggs>>
+    where
+        Self: NRows + AggregateMany<KeyLabels, Aggs>,
+        Frames: FramePermutations,
+        KeyLabels: 'static,
+        I: FieldIndex,
+    {
+        check_index(index, self)?;
+        check_key(index, |key| key.labels == TypeId::of::<KeyLabels>())?;
+        AggregateMany::<KeyLabels, Aggs>::aggregate_many_grouped(
+            self,
+            aggs,
+            index.groups(),
+            &mut NoObserver,
+        )
+    }
+}
+
+/// A trait for joining a [DataView](../view/struct.DataView.html) with the current object using a
+/// prebuilt [FieldIndex](trait.FieldIndex.html) over the right-hand key field. See the intrinsic
+/// method [join_indexed](../view/struct.DataView.html#method.join_indexed) for more details.
+pub trait IndexedJoin<RLabels, RFrames, Join, I> {
+    /// Resultant data structure after join.
+    type Output;
+
+    /// Join this object with a `DataView` using `index`. See the intrinsic method
+    /// [join_indexed](../view/struct.DataView.html#method.join_indexed) for more details.
+    fn join_indexed(&self, right: &DataView<RLabels, RFrames>, index: &I) -> Result<Self::Output>;
+}
+
+impl<LLabels, LFrames, RLabels, RFrames, LLabel, RLabel, I>
+    IndexedJoin<RLabels, RFrames, Join<LLabel, RLabel, Equal>, I> for DataView<LLabels, LFrames>
+where
+    LFrames: JoinIntoStore<LLabels, DataStore<Nil>>,
+    RFrames: JoinIntoStore<RLabels, <LFrames as JoinIntoStore<LLabels, DataStore<Nil>>>::Output>,
+    <RFrames as JoinIntoStore<
+        RLabels,
+        <LFrames as JoinIntoStore<LLabels, DataStore<Nil>>>::Output,
+    >>::Output: IntoView,
+    Self: SelectFieldByLabel<LLabel>,
+    DataView<RLabels, RFrames>: NRows,
+    RFrames: FramePermutations,
+    RLabel: 'static,
+    I: FieldIndex<DType = VFieldTypeOf<Self, LLabel>>,
+{
+    type Output = JoinedViewOf<LLabels, LFrames, RLabels, RFrames>;
+
+    fn join_indexed(&self, right: &DataView<RLabels, RFrames>, index: &I) -> Result<Self::Output> {
+        check_index(index, right)?;
+        check_key(index, |key| key.label == TypeId::of::<RLabel>())?;
+        let mut left_indices = vec![];
+        let mut right_indices = vec![];
+        for (left_idx, key) in self.field::<LLabel>().iter().enumerate() {
+            if let Value::Exists(key) = key {
+                let matches = index.lookup(key);
+                left_indices.extend(matches.iter().map(|_| left_idx));
+                right_indices.extend_from_slice(matches);
+            }
+        }
+        self.join_with_indices(right, (left_indices, right_indices))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use field::FieldData;
+
+    fn sample() -> FieldData<u64> {
+        FieldData::from_field_vec(vec![
+            Value::Exists(4u64),
+            Value::Exists(2),
+            Value::Na,
+            Value::Exists(4),
+            Value::Exists(7),
+            Value::Exists(2),
+        ])
+    }
+
+    #[test]
+    fn hash_index() {
+        let index = HashIndex::new(&sample());
+        assert_eq!(index.nrows(), 6);
+        assert_eq!(index.ndistinct(), 3);
+        assert_eq!(index.lookup(&4), &[0, 3]);
+        assert_eq!(index.lookup(&2), &[1, 5]);
+        assert_eq!(index.lookup(&5), &[] as &[usize]);
+        assert_eq!(index.na_indices(), &[2]);
+        assert_eq!(
+            index.groups(),
+            Groups {
+                first_indices: vec![0, 1, 2, 4],
+                record_groups: vec![0, 1, 2, 0, 3, 1],
+            }
+        );
+    }
+
+    #[test]
+    fn sorted_index() {
+        let index = SortedIndex::new(&sample());
+        assert_eq!(index.nrows(), 6);
+        assert_eq!(index.keys(), &[2, 4, 7]);
+        assert_eq!(index.lookup(&4), &[0, 3]);
+        assert_eq!(index.lookup(&3), &[] as &[usize]);
+        assert_eq!(index.na_indices(), &[2]);
+        assert_eq!(index.range(3..), &[0, 3, 4]);
+        assert_eq!(index.range(..=4), &[1, 5, 0, 3]);
+        assert_eq!(index.range(5..7), &[] as &[usize]);
+        assert_eq!(
+            index.groups(),
+            Groups {
+                first_indices: vec![2, 1, 0, 4],
+                record_groups: vec![2, 1, 0, 2, 3, 1],
+            }
+        );
+
+        let empty = SortedIndex::new(&FieldData::<u64>::default());
+        assert_eq!(empty.range(..), &[] as &[usize]);
+        assert!(empty.groups().is_empty());
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn indexed_view_ops() {
+        use aggregate::agg;
+        use cons::cons;
+        use test_utils::*;
+
+        let dv_emp = sample_emp_table().into_view();
+        let dv_dept = sample_dept_table().into_view();
+
+        let emp_index = dv_emp.build_index::<emp_table::DeptId>();
+        let marketing = dv_emp.filter_indexed(&emp_index, &1).unwrap();
+        assert_eq!(
+            marketing.field::<emp_table::EmpId>().to_vec(),
+            vec![0u64, 5, 6]
+        );
+        assert!(dv_dept.filter_indexed(&emp_index, &1).is_err());
+
+        // indexes apply to clones and subviews of the view they were built over, but not to its
+        // records in a different order (even of the same length)
+        let subview = dv_emp.v::<Labels![emp_table::EmpId, emp_table::DeptId]>();
+        assert_eq!(
+            subview
+                .filter_indexed(&emp_index, &1)
+                .unwrap()
+                .field::<emp_table::EmpId>()
+                .to_vec(),
+            vec![0u64, 5, 6]
+        );
+        assert!(dv_emp.clone().filter_indexed(&emp_index, &1).is_ok());
+        let sorted = dv_emp.clone().sort_by_label::<emp_table::EmpName>();
+        match sorted.filter_indexed(&emp_index, &1) {
+            Err(AgnesError::IndexMismatch(_)) => {}
+            _ => panic!("expected index mismatch error"),
+        }
+        let other = sample_emp_table().into_view();
+        match other.filter_indexed(&emp_index, &1) {
+            Err(AgnesError::IndexMismatch(_)) => {}
+            _ => panic!("expected index mismatch error"),
+        }
+        // indexes built directly from field data are only checked against the number of records
+        let field_index = HashIndex::new(&dv_emp.field::<emp_table::DeptId>());
+        assert!(sorted.filter_indexed(&field_index, &1).is_ok());
+
+        type EmpDept = Join<emp_table::DeptId, dept_table::DeptId, Equal>;
+        let dept_index = dv_dept.build_sorted_index::<dept_table::DeptId>();
+        let joined = dv_emp
+            .join_indexed::<EmpDept, _, _, _>(&dv_dept, &dept_index)
+            .unwrap();
+        let par_joined = dv_emp.par_join::<EmpDept, _, _>(&dv_dept, 1);
+        assert_eq!(
+            joined.field::<emp_table::EmpId>().to_vec(),
+            par_joined.field::<emp_table::EmpId>().to_vec()
+        );
+        assert_eq!(
+            joined.field::<dept_table::DeptName>().to_vec(),
+            par_joined.field::<dept_table::DeptName>().to_vec()
+        );
+        assert!(dv_emp
+            .join_indexed::<EmpDept, _, _, _>(&dv_dept, &emp_index)
+            .is_err());
+
+        let counts = dv_emp
+            .aggregate_many_indexed::<Labels![emp_table::DeptId], _, _>(
+                &emp_index,
+                cons(
+                    agg::<emp_table::EmpId, emp_stats::NumEmps, _, _>(0u64, |n, _| *n += 1),
+                    Nil,
+                ),
+            )
+            .unwrap();
+        assert_eq!(
+            counts.field::<emp_table::DeptId>().to_vec(),
+            vec![1u64, 2, 3, 4]
+        );
+        assert_eq!(
+            counts.field::<emp_stats::NumEmps>().to_vec(),
+            vec![3u64, 1, 1, 2]
+        );
+
+        // indexes over other fields (with the same number of records) are rejected
+        let id_index = dv_emp.build_index::<emp_table::EmpId>();
+        assert_eq!(id_index.key().map(|key| key.name()), Some("EmpId"));
+        match dv_emp.aggregate_many_indexed::<Labels![emp_table::DeptId], _, _>(
+            &id_index,
+            cons(
+                agg::<emp_table::EmpId, emp_stats::NumEmps, _, _>(0u64, |n, _| *n += 1),
+                Nil,
+            ),
+        ) {
+            Err(AgnesError::IndexMismatch(_)) => {}
+            _ => panic!("expected index mismatch error"),
+        }
+    }
+}
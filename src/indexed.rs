@@ -0,0 +1,392 @@
+/*!
+Views with an existing field designated as their logical index.
+
+[DataView::set_index](../view/struct.DataView.html#method.set_index) designates one of a view's
+fields as its index, producing an [IndexedView](struct.IndexedView.html). Indexed views join with
+other indexed views on their index fields without naming the key fields again
+([join_on_index](struct.IndexedView.html#method.join_on_index)), and support "as-of" operations,
+which match each index value with the record having the closest preceding index value in another
+view ([asof_join](struct.IndexedView.html#method.asof_join) and
+[lookup_asof](struct.IndexedView.html#method.lookup_asof)) -- for example, matching each trade with
+the latest quote at the time of the trade. For repeated lookups against the same view,
+[prepare_asof](struct.IndexedView.html#method.prepare_asof) builds a reusable
+[AsofIndex](struct.AsofIndex.html). Views known to be sorted by their index field (for example, by
+[sort_by_label](../view/struct.DataView.html#method.sort_by_label)) are searched without sorting.
+
+Combined with [with_row_index](../view/struct.DataView.html#method.with_row_index), this captures
+the permutation state of a view explicitly: `dv.with_row_index::<Row>().set_index::<Row>()`
+indexes a view by its current record order, which later joins and as-of operations can match
+against regardless of how the view is reordered.
+
+The index field remains an ordinary field of the view, which an `IndexedView` dereferences to;
+[reset_index](struct.IndexedView.html#method.reset_index) drops the index designation.
+
+This is distinct from the prebuilt lookup structures of the [index](../index/index.html) module.
+*/
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use access::DataIndex;
+use cons::Nil;
+use error::*;
+use join::{Equal, Join, JoinIntoStore, JoinedViewOf, SortMergeJoin};
+use select::{FieldSelect, SelectFieldByLabel};
+use store::{DataStore, IntoView};
+use value::Value;
+use view::{DataView, VFieldTypeOf};
+
+/// A view (typically a [DataView](../view/struct.DataView.html)) with its field labeled
+/// `IdxLabel` designated as the view's logical index. See the [module-level
+/// documentation](index.html) for details.
+#[derive(Debug, Clone)]
+pub struct IndexedView<IdxLabel, V> {
+    view: V,
+    _index: PhantomData<IdxLabel>,
+}
+
+impl<IdxLabel, V> IndexedView<IdxLabel, V> {
+    fn new(view: V) -> IndexedView<IdxLabel, V> {
+        IndexedView {
+            view,
+            _index: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying view.
+    pub fn view(&self) -> &V {
+        &self.view
+    }
+
+    /// Drops the index designation, returning the underlying view (which still contains the
+    /// index field).
+    pub fn reset_index(self) -> V {
+        self.view
+    }
+}
+
+impl<IdxLabel, V> Deref for IndexedView<IdxLabel, V> {
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        &self.view
+    }
+}
+
+impl<IdxLabel, V> fmt::Display for IndexedView<IdxLabel, V>
+where
+    V: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.view.fmt(f)
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Designates the existing field labeled `IdxLabel` as the logical index of this view. See the
+    /// [indexed](../indexed/index.html) module.
+    pub fn set_index<IdxLabel>(self) -> IndexedView<IdxLabel, Self>
+    where
+        Self: SelectFieldByLabel<IdxLabel>,
+    {
+        IndexedView::new(self)
+    }
+}
+
+/// Type alias for the view produced by joining `DataView<Labels, Frames>` with
+/// `DataView<RLabels, RFrames>` on their index fields `IdxLabel` and `RIdxLabel`.
+pub type IndexJoinOutputOf<Labels, Frames, RLabels, RFrames, IdxLabel, RIdxLabel> =
+    <DataView<Labels, Frames> as SortMergeJoin<
+        RLabels,
+        RFrames,
+        Join<IdxLabel, RIdxLabel, Equal>,
+    >>::Output;
+
+impl<IdxLabel, Labels, Frames> IndexedView<IdxLabel, DataView<Labels, Frames>> {
+    /// Returns the index field of this view.
+    pub fn index(&self) -> <DataView<Labels, Frames> as SelectFieldByLabel<IdxLabel>>::Output
+    where
+        DataView<Labels, Frames>: SelectFieldByLabel<IdxLabel>,
+    {
+        self.view.field::<IdxLabel>()
+    }
+
+    /// Returns the position of the record of this view with the greatest index value that is not
+    /// greater than `value` (the latest record "as of" `value`), or `None` if every index value is
+    /// greater than `value` or missing. Among records with equal index values, the last one (in
+    /// the view's order) is returned.
+    ///
+    /// Unless the view is known to be sorted by its index field, this sorts the index values on
+    /// each call; use [prepare_asof](#method.prepare_asof) for repeated lookups.
+    pub fn lookup_asof(
+        &self,
+        value: &VFieldTypeOf<DataView<Labels, Frames>, IdxLabel>,
+    ) -> Option<usize>
+    where
+        IdxLabel: 'static,
+        DataView<Labels, Frames>: SelectFieldByLabel<IdxLabel>,
+        VFieldTypeOf<DataView<Labels, Frames>, IdxLabel>: Ord,
+    {
+        self.prepare_asof().lookup(value)
+    }
+
+    /// Prepares an [AsofIndex](struct.AsofIndex.html) over the index field of this view, which
+    /// answers repeated [lookup_asof](#method.lookup_asof) queries without sorting the index values
+    /// again.
+    pub fn prepare_asof(
+        &self,
+    ) -> AsofIndex<<DataView<Labels, Frames> as SelectFieldByLabel<IdxLabel>>::Output>
+    where
+        IdxLabel: 'static,
+        DataView<Labels, Frames>: SelectFieldByLabel<IdxLabel>,
+        VFieldTypeOf<DataView<Labels, Frames>, IdxLabel>: Ord,
+    {
+        AsofIndex::new(self.index(), self.view.invariants.is_sorted::<IdxLabel>())
+    }
+
+    /// Joins this view with `right` on their index fields, matching records with equal index
+    /// values (as with a sort-merge [join](../view/struct.DataView.html#method.join) on
+    /// `Join<IdxLabel, RIdxLabel, Equal>`). The joined view is indexed by this view's index field.
+    pub fn join_on_index<RIdxLabel, RLabels, RFrames>(
+        &self,
+        right: &IndexedView<RIdxLabel, DataView<RLabels, RFrames>>,
+    ) -> IndexedView<
+        IdxLabel,
+        IndexJoinOutputOf<Labels, Frames, RLabels, RFrames, IdxLabel, RIdxLabel>,
+    >
+    where
+        DataView<Labels, Frames>: SortMergeJoin<RLabels, RFrames, Join<IdxLabel, RIdxLabel, Equal>>,
+    {
+        IndexedView::new(SortMergeJoin::<
+            RLabels,
+            RFrames,
+            Join<IdxLabel, RIdxLabel, Equal>,
+        >::join(&self.view, &right.view))
+    }
+
+    /// Performs a backward as-of join of this view with `right`: each record of this view is
+    /// joined with the record of `right` with the greatest index value not greater than its own
+    /// (as found by [lookup_asof](#method.lookup_asof)). Every record of this view is kept, in
+    /// order; records with no such match in `right` (including records with missing index
+    /// values) have missing (NA) values in the fields of `right`. The joined view is indexed by
+    /// this view's index field.
+    pub fn asof_join<RIdxLabel, RLabels, RFrames>(
+        &self,
+        right: &IndexedView<RIdxLabel, DataView<RLabels, RFrames>>,
+    ) -> Result<IndexedView<IdxLabel, JoinedViewOf<Labels, Frames, RLabels, RFrames>>>
+    where
+        RIdxLabel: 'static,
+        DataView<Labels, Frames>: SelectFieldByLabel<IdxLabel>,
+        VFieldTypeOf<DataView<Labels, Frames>, IdxLabel>: Ord,
+        DataView<RLabels, RFrames>:
+            SelectFieldByLabel<RIdxLabel, DType = VFieldTypeOf<DataView<Labels, Frames>, IdxLabel>>,
+        Frames: JoinIntoStore<Labels, DataStore<Nil>>,
+        RFrames: JoinIntoStore<RLabels, <Frames as JoinIntoStore<Labels, DataStore<Nil>>>::Output>,
+        <RFrames as JoinIntoStore<
+            RLabels,
+            <Frames as JoinIntoStore<Labels, DataStore<Nil>>>::Output,
+        >>::Output: IntoView,
+    {
+        let right_index = right.prepare_asof();
+        let index = self.index();
+        let right_indices = index
+            .iter()
+            .map(|value| match value {
+                Value::Exists(value) => right_index.lookup(value),
+                Value::Na => None,
+            })
+            .collect::<Vec<_>>();
+        let left_indices = (0..right_indices.len()).map(Some).collect();
+        self.view
+            .outer_join_with_indices(&right.view, (left_indices, right_indices))
+            .map(IndexedView::new)
+    }
+}
+
+/// A prepared structure for as-of lookups against the index field of an
+/// [IndexedView](struct.IndexedView.html), created by
+/// [prepare_asof](struct.IndexedView.html#method.prepare_asof). It holds the positions of the
+/// existing index values in sorted order of value, so each lookup is a binary search.
+#[derive(Debug, Clone)]
+pub struct AsofIndex<DI> {
+    index: DI,
+    // positions of the existing values of `index`, sorted by value (and by position among equal
+    // values)
+    order: Vec<usize>,
+}
+
+impl<DI> AsofIndex<DI>
+where
+    DI: DataIndex,
+    DI::DType: Ord,
+{
+    // Prepares a lookup structure over `index`. If `sorted`, the index values are known to be in
+    // ascending order (with missing values first), and aren't sorted again.
+    fn new(index: DI, sorted: bool) -> AsofIndex<DI> {
+        let mut order = (0..index.len())
+            .filter(|&idx| index.get_datum(idx).unwrap().exists())
+            .collect::<Vec<_>>();
+        if !sorted {
+            // stable, so equal values stay in order of position
+            order.sort_by(|&left, &right| existing(&index, left).cmp(existing(&index, right)));
+        }
+        AsofIndex { index, order }
+    }
+
+    /// Returns the position (in the indexed view) of the record with the greatest index value
+    /// that is not greater than `value`, or `None` if every index value is greater than `value`
+    /// or missing. Among records with equal index values, the last one is returned. See
+    /// [lookup_asof](struct.IndexedView.html#method.lookup_asof).
+    pub fn lookup(&self, value: &DI::DType) -> Option<usize> {
+        let end = self
+            .order
+            .partition_point(|&idx| existing(&self.index, idx) <= value);
+        end.checked_sub(1).map(|pos| self.order[pos])
+    }
+}
+
+// Returns the value of `index` at `idx`, which is known to exist.
+fn existing<DI: DataIndex>(index: &DI, idx: usize) -> &DI::DType {
+    match index.get_datum(idx).unwrap() {
+        Value::Exists(value) => value,
+        Value::Na => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cons::Nil;
+    use field::FieldData;
+    use store::DataStore;
+
+    tablespace![
+        table trades {
+            Time: u64,
+            Shares: u64,
+        }
+        table quotes {
+            QuoteTime: u64,
+            Price: f64,
+        }
+    ];
+
+    fn trades() -> IndexedView<trades::Time, trades::View> {
+        DataStore::<Nil>::empty()
+            .push_back_field::<trades::Time, _>(FieldData::from_field_vec(vec![
+                Value::Exists(2u64),
+                Value::Exists(5),
+                Value::Na,
+                Value::Exists(9),
+                Value::Exists(1),
+            ]))
+            .push_back_field::<trades::Shares, _>(FieldData::from(vec![10u64, 20, 30, 40, 50]))
+            .into_view()
+            .set_index::<trades::Time>()
+    }
+
+    fn quotes() -> IndexedView<quotes::QuoteTime, quotes::View> {
+        DataStore::<Nil>::empty()
+            .push_back_field::<quotes::QuoteTime, _>(FieldData::from(vec![5u64, 2, 8, 5]))
+            .push_back_field::<quotes::Price, _>(FieldData::from(vec![1.5, 1.0, 2.0, 1.75]))
+            .into_view()
+            .set_index::<quotes::QuoteTime>()
+    }
+
+    #[test]
+    fn lookup_asof() {
+        let quotes = quotes();
+        assert_eq!(quotes.lookup_asof(&1), None);
+        assert_eq!(quotes.lookup_asof(&2), Some(1));
+        assert_eq!(quotes.lookup_asof(&4), Some(1));
+        // the last of the records with equal index values
+        assert_eq!(quotes.lookup_asof(&7), Some(3));
+        assert_eq!(quotes.lookup_asof(&100), Some(2));
+    }
+
+    #[test]
+    fn prepare_asof() {
+        let quotes = quotes();
+        let prepared = quotes.prepare_asof();
+        for value in 0..10 {
+            assert_eq!(prepared.lookup(&value), quotes.lookup_asof(&value));
+        }
+
+        // a view sorted by its index field is searched in its own order
+        let sorted = quotes
+            .reset_index()
+            .sort_by_label::<quotes::QuoteTime>()
+            .set_index::<quotes::QuoteTime>();
+        let prepared = sorted.prepare_asof();
+        assert_eq!(prepared.order, vec![0, 1, 2, 3]);
+        assert_eq!(prepared.lookup(&1), None);
+        assert_eq!(prepared.lookup(&4), Some(0));
+        assert_eq!(prepared.lookup(&7), Some(2));
+        assert_eq!(prepared.lookup(&100), Some(3));
+    }
+
+    #[test]
+    fn asof_join() {
+        let joined = trades().asof_join(&quotes()).unwrap();
+        assert_eq!(
+            joined.field::<trades::Shares>().to_vec(),
+            vec![10, 20, 30, 40, 50]
+        );
+        assert_eq!(
+            joined.field::<quotes::Price>().to_value_vec(),
+            vec![
+                Value::Exists(1.0),
+                Value::Exists(1.75),
+                Value::Na,
+                Value::Exists(2.0),
+                Value::Na
+            ]
+        );
+        assert_eq!(
+            joined.index().to_value_vec(),
+            trades().index().to_value_vec()
+        );
+        let view = joined.reset_index();
+        assert_eq!(
+            view.field::<quotes::QuoteTime>().to_value_vec()[3],
+            Value::Exists(8)
+        );
+    }
+
+    #[test]
+    fn join_on_index() {
+        let joined = trades().join_on_index(&quotes());
+        // joined in sorted order of the index values
+        assert_eq!(joined.index().to_vec(), vec![2, 5, 5]);
+        assert_eq!(joined.field::<trades::Shares>().to_vec(), vec![10, 20, 20]);
+        assert_eq!(
+            joined.field::<quotes::Price>().to_vec(),
+            vec![1.0, 1.5, 1.75]
+        );
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn row_index() {
+        use access::DataIndex;
+        use test_utils::emp_stats::NumEmps as RowIdx;
+        use test_utils::emp_table::*;
+        use test_utils::*;
+
+        // capture the original order of the employees, then find records by it after sorting
+        let dv = sample_emp_table().into_view();
+        let names = dv.field::<EmpName>().to_vec();
+        let sorted = dv
+            .with_row_index::<RowIdx>()
+            .sort_by_label::<EmpName>()
+            .set_index::<RowIdx>();
+        for (row, name) in names.iter().enumerate() {
+            let pos = sorted.lookup_asof(&(row as u64)).unwrap();
+            assert_eq!(
+                sorted.field::<EmpName>().get_datum(pos).unwrap(),
+                Value::Exists(name)
+            );
+        }
+    }
+}
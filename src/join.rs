@@ -7,17 +7,21 @@ combining fields of two `DataView` objects with the same number of rows into a s
 */
 use std::cmp::Ordering;
 use std::fmt::Debug;
+use std::hash::Hash;
 use std::marker::PhantomData;
 use std::ops::Add;
 
 use access::DataIndex;
 use cons::*;
+use erased;
 use error::*;
-use frame::DataFrame;
+use field::FieldData;
+use frame::{DataFrame, IntoFrame};
 use label::{LVCons, Labeled, LookupValuedElemByLabel, Valued};
+use observer::{NoObserver, Observer};
 use permute::SortOrder;
 use select::{FieldSelect, SelectFieldByLabel};
-use store::{DataStore, IntoView, PushBackClonedFromValueIter};
+use store::{DataStore, IntoStore, IntoView, PushBackClonedFromValueIter};
 use value::Value;
 use view::*;
 
@@ -151,6 +155,7 @@ where
         DataView {
             _labels: PhantomData,
             frames,
+            invariants: Invariants::default(),
         }
     }
 }
@@ -196,6 +201,7 @@ where
         DataView {
             _labels: PhantomData,
             frames: out_frames,
+            invariants: Invariants::default(),
         }
     }
 }
@@ -207,6 +213,20 @@ pub struct Join<LLabel, RLabel, Predicate> {
     _marker: PhantomData<(LLabel, RLabel, Predicate)>,
 }
 
+/// Trait for updating a join to refer to the right-hand side label after colliding labels have
+/// been affixed with `A` (see [AffixColliding](../view/trait.AffixColliding.html)).
+/// `LLabels` are the labels of the left-hand side.
+pub trait AffixJoin<LLabels, A> {
+    /// The updated join.
+    type Output;
+}
+impl<LLabels, A, LLabel, RLabel, Pred> AffixJoin<LLabels, A> for Join<LLabel, RLabel, Pred>
+where
+    RLabel: AffixIfColliding<LLabels, A>,
+{
+    type Output = Join<LLabel, <RLabel as AffixIfColliding<LLabels, A>>::Output, Pred>;
+}
+
 /// A trait for describing the course of action in a sort-merge join. This trait differentiates
 /// the actions that are taken during a sort-merge join based on the implementing type.
 pub trait Predicate {
@@ -396,11 +416,27 @@ pub trait SortMergeJoin<RLabels, RFrames, Join> {
     type Output;
 
     /// Join this object with a `DataView`, using the join details specified with `Join`.
-    fn join(&self, right: &DataView<RLabels, RFrames>) -> Self::Output;
+    fn join(&self, right: &DataView<RLabels, RFrames>) -> Self::Output {
+        // joins without an observer cannot be cancelled
+        self.join_observed(right, &mut NoObserver).unwrap()
+    }
+
+    /// Join this object with a `DataView`, using the join details specified with `Join`, and
+    /// reporting the progress of the join to `observer`.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation.
+    fn join_observed<O: Observer>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        observer: &mut O,
+    ) -> Result<Self::Output>;
 }
 impl<LLabels, LFrames, RLabels, RFrames, LLabel, RLabel, Pred>
     SortMergeJoin<RLabels, RFrames, Join<LLabel, RLabel, Pred>> for DataView<LLabels, LFrames>
 where
+    LLabel: 'static,
+    RLabel: 'static,
     LFrames: JoinIntoStore<LLabels, DataStore<Nil>>,
     RFrames: JoinIntoStore<RLabels, <LFrames as JoinIntoStore<LLabels, DataStore<Nil>>>::Output>,
     <RFrames as JoinIntoStore<
@@ -419,115 +455,460 @@ where
         <LFrames as JoinIntoStore<LLabels, DataStore<Nil>>>::Output,
     >>::Output as IntoView>::Output;
 
-    fn join(&self, right: &DataView<RLabels, RFrames>) -> Self::Output {
+    fn join_observed<O: Observer>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        observer: &mut O,
+    ) -> Result<Self::Output> {
         let left = self;
         //TODO: return empty dataview if left or right is empty
 
-        let merge_indices =
-            merge_indices::<Pred, _, _>(&left.field::<LLabel>(), &right.field::<RLabel>());
+        // sides already in order (as recorded, or found by a linear scan) need not be sorted
+        let merge_indices = merge_indices::<Pred, _, _>(
+            &left.field::<LLabel>(),
+            left.is_sorted::<LLabel>(),
+            &right.field::<RLabel>(),
+            right.is_sorted::<RLabel>(),
+            observer,
+        )?;
 
         let store = DataStore::<Nil>::empty();
 
-        let store = left
-            .frames
-            .join_into_store(store, &merge_indices.0)
-            .unwrap();
-        let store = right
-            .frames
-            .join_into_store(store, &merge_indices.1)
+        let store = left.frames.join_into_store(store, &merge_indices.0)?;
+        let store = right.frames.join_into_store(store, &merge_indices.1)?;
+        Ok(store.into_view())
+    }
+}
+
+/// Expected cardinality of the relationship between the key values of the left-hand and right-hand
+/// sides of a join, for use with [ValidateJoin](trait.ValidateJoin.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JoinValidation {
+    /// Key values may be repeated on either side (no validation).
+    #[default]
+    ManyToMany,
+    /// Key values must be unique on both sides.
+    OneToOne,
+    /// Key values must be unique on the left-hand side.
+    OneToMany,
+    /// Key values must be unique on the right-hand side.
+    ManyToOne,
+}
+
+impl JoinValidation {
+    fn unique_left(self) -> bool {
+        self == JoinValidation::OneToOne || self == JoinValidation::OneToMany
+    }
+    fn unique_right(self) -> bool {
+        self == JoinValidation::OneToOne || self == JoinValidation::ManyToOne
+    }
+    fn name(self) -> &'static str {
+        match self {
+            JoinValidation::ManyToMany => "many-to-many",
+            JoinValidation::OneToOne => "one-to-one",
+            JoinValidation::OneToMany => "one-to-many",
+            JoinValidation::ManyToOne => "many-to-one",
+        }
+    }
+}
+
+// Returns the indices of two records with the same (existing) key value in `keys`, if any.
+fn find_duplicate_key<DI>(keys: &DI) -> Option<(usize, usize)>
+where
+    DI: DataIndex + SortOrder,
+    DI::DType: PartialEq,
+{
+    let order = keys.sort_order();
+    order.windows(2).find_map(|pair| {
+        let (first, second) = (
+            keys.get_datum(pair[0]).unwrap(),
+            keys.get_datum(pair[1]).unwrap(),
+        );
+        if first.exists() && first == second {
+            Some((pair[0], pair[1]))
+        } else {
+            None
+        }
+    })
+}
+
+/// A trait for checking that the key values of a join between a
+/// [DataView](../view/struct.DataView.html) and the current object have the expected cardinality,
+/// catching unexpected duplicate keys (which would silently multiply the number of joined records)
+/// before performing the join. Missing (NA) key values are ignored, since they never match.
+pub trait ValidateJoin<RLabels, RFrames, Join> {
+    /// Checks that the key values of `self` and `right` satisfy `validation`.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::JoinValidation` describing the first duplicate key value found on a
+    /// side which `validation` requires to be unique.
+    fn validate_join(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        validation: JoinValidation,
+    ) -> Result<()>;
+}
+impl<LLabels, LFrames, RLabels, RFrames, LLabel, RLabel, Pred>
+    ValidateJoin<RLabels, RFrames, Join<LLabel, RLabel, Pred>> for DataView<LLabels, LFrames>
+where
+    LLabel: 'static,
+    RLabel: 'static,
+    Self: SelectFieldByLabel<LLabel>,
+    <Self as SelectFieldByLabel<LLabel>>::Output: SortOrder,
+    VFieldTypeOf<Self, LLabel>: PartialEq + Debug,
+    DataView<RLabels, RFrames>: SelectFieldByLabel<RLabel>,
+    <DataView<RLabels, RFrames> as SelectFieldByLabel<RLabel>>::Output: SortOrder,
+    VFieldTypeOf<DataView<RLabels, RFrames>, RLabel>: PartialEq + Debug,
+{
+    fn validate_join(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        validation: JoinValidation,
+    ) -> Result<()> {
+        fn check<DI>(keys: &DI, side: &str, validation: JoinValidation) -> Result<()>
+        where
+            DI: DataIndex + SortOrder,
+            DI::DType: PartialEq + Debug,
+        {
+            match find_duplicate_key(keys) {
+                Some((first, second)) => Err(AgnesError::JoinValidation(format!(
+                    "expected {} join, but key value {:?} appears more than once in {}-hand side \
+                     (at indices {} and {})",
+                    validation.name(),
+                    keys.get_datum(first).unwrap().unwrap(),
+                    side,
+                    first,
+                    second
+                ))),
+                None => Ok(()),
+            }
+        }
+        // views known to be unique by their key need not be checked
+        if validation.unique_left() && !self.invariants.is_unique::<Labels![LLabel]>() {
+            check(&self.field::<LLabel>(), "left", validation)?;
+        }
+        if validation.unique_right() && !right.invariants.is_unique::<Labels![RLabel]>() {
+            check(&right.field::<RLabel>(), "right", validation)?;
+        }
+        Ok(())
+    }
+}
+
+/// A trait for joining a [DataView](../view/struct.DataView.html) with the current object using an
+/// equality `Join`, executed as a partitioned hash join across multiple threads. `RLabels` and
+/// `RFrames` are the `Labels` and `Frames` type parameters for the `DataView` to merge.
+///
+/// The keys of both sides are hash-partitioned across threads, and each partition is joined
+/// independently (see [erased::hash_join_indices](../erased/fn.hash_join_indices.html)). Unlike
+/// [SortMergeJoin](trait.SortMergeJoin.html), the joined records are ordered by their position in
+/// the left-hand side (then the right-hand side) rather than by key value.
+///
+/// When the right-hand side is small (such as a lookup table), the
+/// [broadcast_join](#method.broadcast_join) method can be used instead to skip partitioning
+/// entirely: a hash table is built once from the right-hand side, and the left-hand side is
+/// streamed through it.
+pub trait ParallelJoin<RLabels, RFrames, Join> {
+    /// Resultant data structure after join.
+    type Output;
+
+    /// Join this object with a `DataView` using `nthreads` threads (or one per available CPU, if
+    /// `nthreads` is `0`), using the join details specified with `Join`.
+    fn par_join(&self, right: &DataView<RLabels, RFrames>, nthreads: usize) -> Self::Output {
+        // joins without an observer cannot be cancelled
+        self.par_join_observed(right, nthreads, &mut NoObserver)
+            .unwrap()
+    }
+
+    /// Join this object with a `DataView` using `nthreads` threads (or one per available CPU, if
+    /// `nthreads` is `0`), using the join details specified with `Join`, and reporting the
+    /// progress of the join to `observer`.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation.
+    fn par_join_observed<O: Observer>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        nthreads: usize,
+        observer: &mut O,
+    ) -> Result<Self::Output>;
+
+    /// Join this object with a `DataView` using the join details specified with `Join`. If `right`
+    /// has at most `max_broadcast_rows` rows, the join is performed on the current thread by
+    /// building a hash table from `right` and streaming this object's records through it;
+    /// otherwise, this falls back to [par_join](#method.par_join) with one thread per available
+    /// CPU. The joined records are in the same order either way.
+    fn broadcast_join(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        max_broadcast_rows: usize,
+    ) -> Self::Output {
+        // joins without an observer cannot be cancelled
+        self.broadcast_join_observed(right, max_broadcast_rows, &mut NoObserver)
+            .unwrap()
+    }
+
+    /// Join this object with a `DataView` as in [broadcast_join](#method.broadcast_join),
+    /// reporting the progress of the join to `observer`.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation.
+    fn broadcast_join_observed<O: Observer>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        max_broadcast_rows: usize,
+        observer: &mut O,
+    ) -> Result<Self::Output>;
+}
+impl<LLabels, LFrames, RLabels, RFrames, LLabel, RLabel>
+    ParallelJoin<RLabels, RFrames, Join<LLabel, RLabel, Equal>> for DataView<LLabels, LFrames>
+where
+    LFrames: JoinIntoStore<LLabels, DataStore<Nil>>,
+    RFrames: JoinIntoStore<RLabels, <LFrames as JoinIntoStore<LLabels, DataStore<Nil>>>::Output>,
+    <RFrames as JoinIntoStore<
+        RLabels,
+        <LFrames as JoinIntoStore<LLabels, DataStore<Nil>>>::Output,
+    >>::Output: IntoView,
+    Self: SelectFieldByLabel<LLabel>,
+    VFieldTypeOf<Self, LLabel>: Hash + Eq + Sync,
+    DataView<RLabels, RFrames>: SelectFieldByLabel<RLabel, DType = VFieldTypeOf<Self, LLabel>>,
+{
+    type Output = <<RFrames as JoinIntoStore<
+        RLabels,
+        <LFrames as JoinIntoStore<LLabels, DataStore<Nil>>>::Output,
+    >>::Output as IntoView>::Output;
+
+    fn par_join_observed<O: Observer>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        nthreads: usize,
+        observer: &mut O,
+    ) -> Result<Self::Output> {
+        let left_field = self.field::<LLabel>();
+        let right_field = right.field::<RLabel>();
+        let left_keys = left_field.iter().collect::<Vec<_>>();
+        let right_keys = right_field.iter().collect::<Vec<_>>();
+        let join_indices = erased::hash_join_indices(&left_keys, &right_keys, nthreads, observer)?;
+        self.join_with_indices(right, join_indices)
+    }
+
+    fn broadcast_join_observed<O: Observer>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        max_broadcast_rows: usize,
+        observer: &mut O,
+    ) -> Result<Self::Output> {
+        let left_field = self.field::<LLabel>();
+        let right_field = right.field::<RLabel>();
+        let left_keys = left_field.iter().collect::<Vec<_>>();
+        let right_keys = right_field.iter().collect::<Vec<_>>();
+        let join_indices = if right_keys.len() <= max_broadcast_rows {
+            erased::broadcast_join_indices(&left_keys, &right_keys, observer)?
+        } else {
+            erased::hash_join_indices(&left_keys, &right_keys, 0, observer)?
+        };
+        self.join_with_indices(right, join_indices)
+    }
+}
+
+/// The kind of outer join to perform with [OuterJoin](trait.OuterJoin.html), specifying which
+/// side's unmatched records are retained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OuterJoinKind {
+    /// Retain unmatched records from the left-hand side.
+    Left,
+    /// Retain unmatched records from the right-hand side.
+    Right,
+    /// Retain unmatched records from both sides.
+    Full,
+}
+
+impl OuterJoinKind {
+    pub(crate) fn keep_left(self) -> bool {
+        self == OuterJoinKind::Left || self == OuterJoinKind::Full
+    }
+    pub(crate) fn keep_right(self) -> bool {
+        self == OuterJoinKind::Right || self == OuterJoinKind::Full
+    }
+}
+
+/// Type of the indicator frame added by
+/// [outer_join_with_indicator](trait.OuterJoin.html#method.outer_join_with_indicator).
+pub type IndicatorFrameOf<IndLabel> =
+    <<FieldData<String> as IntoStore<IndLabel>>::Output as IntoFrame>::Output;
+
+/// A trait for performing an outer equi-join between a [DataView](../view/struct.DataView.html)
+/// and the current object. Records matched on both sides are joined as with
+/// [ParallelJoin](trait.ParallelJoin.html); unmatched records on the side(s) specified by an
+/// [OuterJoinKind](enum.OuterJoinKind.html) are retained, with missing (NA) values in the fields of
+/// the other side. Joined records are ordered by their position in the left-hand side, followed by
+/// any unmatched right-hand records in their original order.
+pub trait OuterJoin<RLabels, RFrames, Join> {
+    /// Resultant data structure after join.
+    type Output;
+
+    /// Join this object with a `DataView` using the join details specified with `Join`, retaining
+    /// unmatched records as specified by `kind`. Also returns an indicator field with the
+    /// provenance of each joined record: `"both"`, `"left_only"`, or `"right_only"`.
+    fn outer_join_indicated(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        kind: OuterJoinKind,
+    ) -> (Self::Output, FieldData<String>);
+
+    /// Join this object with a `DataView` using the join details specified with `Join`, retaining
+    /// unmatched records as specified by `kind`.
+    fn outer_join(&self, right: &DataView<RLabels, RFrames>, kind: OuterJoinKind) -> Self::Output {
+        self.outer_join_indicated(right, kind).0
+    }
+
+    /// Join this object with a `DataView` as in [outer_join](#method.outer_join), adding an
+    /// indicator field labeled `IndLabel` with the provenance of each joined record: `"both"`,
+    /// `"left_only"`, or `"right_only"`.
+    fn outer_join_with_indicator<IndLabel>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        kind: OuterJoinKind,
+    ) -> <Self::Output as AddFrame<IndicatorFrameOf<IndLabel>>>::Output
+    where
+        Self::Output: AddFrame<IndicatorFrameOf<IndLabel>>,
+        FieldData<String>: IntoStore<IndLabel>,
+        <FieldData<String> as IntoStore<IndLabel>>::Output: IntoFrame,
+    {
+        let (joined, indicator) = self.outer_join_indicated(right, kind);
+        joined.add_frame(IntoStore::<IndLabel>::into_store(indicator).into_frame())
+    }
+}
+impl<LLabels, LFrames, RLabels, RFrames, LLabel, RLabel>
+    OuterJoin<RLabels, RFrames, Join<LLabel, RLabel, Equal>> for DataView<LLabels, LFrames>
+where
+    LFrames: JoinIntoStore<LLabels, DataStore<Nil>>,
+    RFrames: JoinIntoStore<RLabels, <LFrames as JoinIntoStore<LLabels, DataStore<Nil>>>::Output>,
+    <RFrames as JoinIntoStore<
+        RLabels,
+        <LFrames as JoinIntoStore<LLabels, DataStore<Nil>>>::Output,
+    >>::Output: IntoView,
+    Self: SelectFieldByLabel<LLabel>,
+    VFieldTypeOf<Self, LLabel>: Hash + Eq,
+    DataView<RLabels, RFrames>: SelectFieldByLabel<RLabel, DType = VFieldTypeOf<Self, LLabel>>,
+{
+    type Output = JoinedViewOf<LLabels, LFrames, RLabels, RFrames>;
+
+    fn outer_join_indicated(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        kind: OuterJoinKind,
+    ) -> (Self::Output, FieldData<String>) {
+        let left_field = self.field::<LLabel>();
+        let right_field = right.field::<RLabel>();
+        let left_keys = left_field.iter().collect::<Vec<_>>();
+        let right_keys = right_field.iter().collect::<Vec<_>>();
+        let (left_indices, right_indices) =
+            erased::outer_join_indices(&left_keys, &right_keys, kind);
+
+        let indicator = left_indices
+            .iter()
+            .zip(&right_indices)
+            .map(|(left_idx, right_idx)| {
+                match (left_idx, right_idx) {
+                    (Some(_), Some(_)) => "both",
+                    (Some(_), None) => "left_only",
+                    _ => "right_only",
+                }
+                .to_string()
+            })
+            .collect::<Vec<_>>();
+
+        // indices computed above are always in bounds, so this cannot fail
+        let joined = self
+            .outer_join_with_indices(right, (left_indices, right_indices))
             .unwrap();
-        store.into_view()
+        (joined, FieldData::<String>::from_vec(indicator))
     }
 }
 
-fn merge_indices<Pred, T, U>(left_key_data: &T, right_key_data: &U) -> (Vec<usize>, Vec<usize>)
+impl<LLabels, LFrames> DataView<LLabels, LFrames> {
+    pub(crate) fn join_with_indices<RLabels, RFrames>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        (left_indices, right_indices): (Vec<usize>, Vec<usize>),
+    ) -> Result<JoinedViewOf<LLabels, LFrames, RLabels, RFrames>>
+    where
+        LFrames: JoinIntoStore<LLabels, DataStore<Nil>>,
+        RFrames:
+            JoinIntoStore<RLabels, <LFrames as JoinIntoStore<LLabels, DataStore<Nil>>>::Output>,
+        <RFrames as JoinIntoStore<
+            RLabels,
+            <LFrames as JoinIntoStore<LLabels, DataStore<Nil>>>::Output,
+        >>::Output: IntoView,
+    {
+        let store = DataStore::<Nil>::empty();
+        let store = self.frames.join_into_store(store, &left_indices)?;
+        let store = right.frames.join_into_store(store, &right_indices)?;
+        Ok(store.into_view())
+    }
+
+    // Joins this view with `right` as in `join_with_indices`, where `None` indices produce
+    // missing values in the fields of that side.
+    pub(crate) fn outer_join_with_indices<RLabels, RFrames>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        (left_indices, right_indices): (Vec<Option<usize>>, Vec<Option<usize>>),
+    ) -> Result<JoinedViewOf<LLabels, LFrames, RLabels, RFrames>>
+    where
+        LFrames: JoinIntoStore<LLabels, DataStore<Nil>>,
+        RFrames:
+            JoinIntoStore<RLabels, <LFrames as JoinIntoStore<LLabels, DataStore<Nil>>>::Output>,
+        <RFrames as JoinIntoStore<
+            RLabels,
+            <LFrames as JoinIntoStore<LLabels, DataStore<Nil>>>::Output,
+        >>::Output: IntoView,
+    {
+        let store = DataStore::<Nil>::empty();
+        let store = self.frames.outer_join_into_store(store, &left_indices)?;
+        let store = right.frames.outer_join_into_store(store, &right_indices)?;
+        Ok(store.into_view())
+    }
+}
+
+pub(crate) type JoinedViewOf<LLabels, LFrames, RLabels, RFrames> = <<RFrames as JoinIntoStore<
+    RLabels,
+    <LFrames as JoinIntoStore<LLabels, DataStore<Nil>>>::Output,
+>>::Output as IntoView>::Output;
+
+// Computes the merge join indices of the two key fields, skipping the sort of any side which is
+// already known to be sorted.
+fn merge_indices<Pred, T, U>(
+    left_key_data: &T,
+    left_sorted: bool,
+    right_key_data: &U,
+    right_sorted: bool,
+    observer: &mut dyn Observer,
+) -> Result<(Vec<usize>, Vec<usize>)>
 where
     Pred: Predicate,
     T: DataIndex + SortOrder,
     U: DataIndex<DType = <T as DataIndex>::DType> + SortOrder,
     <T as DataIndex>::DType: PartialEq + Ord,
 {
-    let left_order = left_key_data.sort_order();
-    let right_order = right_key_data.sort_order();
+    let left_order = if left_sorted {
+        (0..left_key_data.len()).collect()
+    } else {
+        left_key_data.sort_order()
+    };
+    let right_order = if right_sorted {
+        (0..right_key_data.len()).collect()
+    } else {
+        right_key_data.sort_order()
+    };
 
     debug_assert!(!left_order.is_empty() && !right_order.is_empty());
-    // NOTE: actual_idx = perm[sorted_idx]
-    // NOTE: value = key_data.get(actual_idx).unwrap();
-
-    let lval = |sorted_idx| left_key_data.get_datum(left_order[sorted_idx]).unwrap();
-    let rval = |sorted_idx| right_key_data.get_datum(right_order[sorted_idx]).unwrap();
-
-    // we know left_order and right_order both are non-empty, so there is at least one value
-    let (mut left_idx, mut right_idx) = (0, 0);
-    let mut left_merge_indices = vec![];
-    let mut right_merge_indices = vec![];
-    while left_idx < left_order.len() && right_idx < right_order.len() {
-        let left_val = lval(left_idx);
-        let right_val = rval(right_idx);
-        let pred_results = Pred::apply(left_val, right_val);
-        match pred_results {
-            PredAction::Add => {
-                // figure out subsets
-                let mut left_subset = vec![left_idx];
-                let mut right_subset = vec![right_idx];
-                let (mut left_idx_end, mut right_idx_end);
-                if Pred::is_equality_pred() {
-                    // for equality predicates, add all records with same value
-                    left_idx_end = left_idx + 1;
-                    while left_idx_end < left_order.len() && left_val == lval(left_idx_end) {
-                        left_subset.push(left_idx_end);
-                        left_idx_end += 1;
-                    }
-                    right_idx_end = right_idx + 1;
-                    while right_idx_end < right_order.len() && right_val == rval(right_idx_end) {
-                        right_subset.push(right_idx_end);
-                        right_idx_end += 1;
-                    }
-                } else {
-                    left_idx_end = left_idx + 1;
-                    right_idx_end = right_idx + 1;
-                }
-                let (left_eq_end, right_eq_end) = (left_idx_end, right_idx_end);
-                if Pred::is_greater_than_pred() {
-                    // for greater-than predicates, we can add the rest of the left values
-                    while left_idx_end < left_order.len() {
-                        left_subset.push(left_idx_end);
-                        left_idx_end += 1;
-                    }
-                }
-                if Pred::is_less_than_pred() {
-                    // for less-than predicates, we can add the rest of the right values
-                    while right_idx_end < right_order.len() {
-                        right_subset.push(right_idx_end);
-                        right_idx_end += 1;
-                    }
-                }
-                // add cross product of subsets to merge indices
-                for lidx in &left_subset {
-                    // NAs shouldn't match a predicate, only add if value exists
-                    if lval(*lidx).exists() {
-                        for ridx in &right_subset {
-                            if rval(*ridx).exists() {
-                                left_merge_indices.push(left_order[*lidx]);
-                                right_merge_indices.push(right_order[*ridx]);
-                            }
-                        }
-                    }
-                }
-                // advance as needed
-                Pred::advance(&mut left_idx, &mut right_idx, left_eq_end, right_eq_end);
-            }
-            PredAction::Advance { left, right } => {
-                if left {
-                    left_idx += 1;
-                }
-                if right {
-                    right_idx += 1;
-                }
-            }
-        }
-    }
-    (left_merge_indices, right_merge_indices)
+    erased::merge_indices::<Pred, _>(
+        left_key_data,
+        &left_order,
+        right_key_data,
+        &right_order,
+        observer,
+    )
 }
 
 /// A trait for augmenting type `Store` (a [DataStore](../store/struct.DataStore.html)) with
@@ -539,12 +920,24 @@ pub trait JoinIntoStore<Labels, Store> {
     /// Augments `store` with data from `self` (as specified with `Labels`), using the provided
     /// permutation indices.
     fn join_into_store(&self, store: Store, permutation: &[usize]) -> Result<Self::Output>;
+
+    /// Augments `store` with data from `self` (as specified with `Labels`), using the provided
+    /// indices, where a `None` index produces a missing value (as for the unmatched records of an
+    /// outer join).
+    fn outer_join_into_store(
+        &self,
+        store: Store,
+        indices: &[Option<usize>],
+    ) -> Result<Self::Output>;
 }
 impl<Frames, Store> JoinIntoStore<Nil, Store> for Frames {
     type Output = Store;
     fn join_into_store(&self, store: Store, _permutation: &[usize]) -> Result<Store> {
         Ok(store)
     }
+    fn outer_join_into_store(&self, store: Store, _indices: &[Option<usize>]) -> Result<Store> {
+        Ok(store)
+    }
 }
 impl<Label, FrameIndex, FrameLabel, Tail, Frames, Store>
     JoinIntoStore<FrameLookupCons<Label, FrameIndex, FrameLabel, Tail>, Store> for Frames
@@ -587,6 +980,26 @@ where
         let store = JoinIntoStore::<Tail, _>::join_into_store(self, store, permutation)?;
         Ok(store)
     }
+
+    fn outer_join_into_store(
+        &self,
+        store: Store,
+        indices: &[Option<usize>],
+    ) -> Result<Self::Output> {
+        let field = SelectFieldByLabel::<FrameLabel>::select_field(
+            LookupValuedElemByLabel::<FrameIndex>::elem(self).value_ref(),
+        );
+        let values = indices
+            .iter()
+            .map(|idx| match *idx {
+                Some(idx) => field.get_datum(idx),
+                None => Ok(Value::Na),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let store = store.push_back_cloned_from_value_iter(values);
+        let store = JoinIntoStore::<Tail, _>::outer_join_into_store(self, store, indices)?;
+        Ok(store)
+    }
 }
 
 #[cfg(feature = "test-utils")]
@@ -634,6 +1047,217 @@ mod tests {
         );
     }
 
+    #[test]
+    fn inner_equi_join_observed() {
+        use observer::{CancellationToken, Stage, WithCancellation};
+
+        let dv_emp = sample_emp_table().into_view();
+        let dv_dept = sample_dept_table().into_view();
+
+        let mut reports = vec![];
+        let joined_dv = dv_emp
+            .join_observed::<Join<emp_table::DeptId, dept_table::DeptId, Equal>, _, _, _>(
+                &dv_dept,
+                &mut |stage, processed, total| reports.push((stage, processed, total)),
+            )
+            .unwrap();
+        assert_eq!(joined_dv.nrows(), 7);
+        assert_eq!(
+            reports,
+            vec![(Stage::Join, 0, Some(7)), (Stage::Join, 7, Some(7))]
+        );
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let joined = dv_emp
+            .join_observed::<Join<emp_table::DeptId, dept_table::DeptId, Equal>, _, _, _>(
+                &dv_dept,
+                &mut WithCancellation::new(NoObserver, token),
+            );
+        match joined {
+            Err(AgnesError::Cancelled) => {}
+            _ => panic!("expected cancellation"),
+        }
+    }
+
+    #[test]
+    fn parallel_equi_join() {
+        let dv_emp = sample_emp_table().into_view();
+        let dv_dept = sample_dept_table().into_view();
+
+        for nthreads in 0..4 {
+            let joined_dv = dv_emp
+                .par_join::<Join<emp_table::DeptId, dept_table::DeptId, Equal>, _, _>(
+                    &dv_dept, nthreads,
+                );
+            assert_eq!(joined_dv.nrows(), 7);
+            assert_eq!(joined_dv.nfields(), 5);
+            // records are in the order of the left-hand side
+            assert_eq!(
+                joined_dv.field::<emp_table::EmpId>().to_vec(),
+                vec![0u64, 2, 5, 6, 8, 9, 10]
+            );
+            assert_eq!(
+                joined_dv.field::<dept_table::DeptName>().to_vec(),
+                vec![
+                    "Marketing",
+                    "Sales",
+                    "Marketing",
+                    "Marketing",
+                    "Manufacturing",
+                    "R&D",
+                    "R&D"
+                ]
+            );
+        }
+    }
+
+    // indicator label in a table not used by the test tables
+    mod provenance {
+        first_label![Merge, ::typenum::U5, String];
+    }
+
+    #[test]
+    fn outer_equi_join() {
+        let dv_emp = sample_emp_table().into_view();
+        let dv_dept = dept_table_from_field(
+            FieldData::<u64>::from_vec(vec![1u64, 2, 7]),
+            FieldData::<String>::from_vec(vec!["Marketing", "Sales", "Legal"]),
+        )
+        .into_view();
+        type EmpDept = Join<emp_table::DeptId, dept_table::DeptId, Equal>;
+
+        let left = dv_emp.outer_join::<EmpDept, _, _>(&dv_dept, OuterJoinKind::Left);
+        assert_eq!(left.nrows(), 7);
+        assert_eq!(
+            left.field::<emp_table::EmpId>().to_vec(),
+            vec![0u64, 2, 5, 6, 8, 9, 10]
+        );
+        assert_eq!(
+            left.field::<dept_table::DeptName>().to_value_vec(),
+            vec![
+                Value::Exists("Marketing".to_string()),
+                Value::Exists("Sales".to_string()),
+                Value::Exists("Marketing".to_string()),
+                Value::Exists("Marketing".to_string()),
+                Value::Na,
+                Value::Na,
+                Value::Na,
+            ]
+        );
+
+        let right = dv_emp.outer_join::<EmpDept, _, _>(&dv_dept, OuterJoinKind::Right);
+        assert_eq!(right.nrows(), 5);
+        assert_eq!(
+            right.field::<emp_table::EmpId>().to_value_vec(),
+            vec![
+                Value::Exists(0u64),
+                Value::Exists(2),
+                Value::Exists(5),
+                Value::Exists(6),
+                Value::Na
+            ]
+        );
+        assert_eq!(
+            right.field::<dept_table::DeptId>().to_vec(),
+            vec![1u64, 2, 1, 1, 7]
+        );
+
+        let full = dv_emp.outer_join_with_indicator::<EmpDept, provenance::Merge, _, _>(
+            &dv_dept,
+            OuterJoinKind::Full,
+        );
+        println!("{}", full);
+        assert_eq!(full.nrows(), 8);
+        assert_eq!(full.nfields(), 6);
+        assert_eq!(
+            full.field::<provenance::Merge>().to_vec(),
+            vec![
+                "both",
+                "both",
+                "both",
+                "both",
+                "left_only",
+                "left_only",
+                "left_only",
+                "right_only"
+            ]
+        );
+    }
+
+    #[test]
+    fn broadcast_equi_join() {
+        let dv_emp = sample_emp_table().into_view();
+        let dv_dept = sample_dept_table().into_view();
+
+        let par_joined = dv_emp
+            .par_join::<Join<emp_table::DeptId, dept_table::DeptId, Equal>, _, _>(&dv_dept, 2);
+        // broadcast fast path, and the partitioned fallback
+        for &max_broadcast_rows in &[100, 2] {
+            let joined_dv = dv_emp
+                .broadcast_join::<Join<emp_table::DeptId, dept_table::DeptId, Equal>, _, _>(
+                    &dv_dept,
+                    max_broadcast_rows,
+                );
+            assert_eq!(joined_dv.nrows(), 7);
+            assert_eq!(
+                joined_dv.field::<emp_table::EmpId>().to_vec(),
+                par_joined.field::<emp_table::EmpId>().to_vec()
+            );
+            assert_eq!(
+                joined_dv.field::<dept_table::DeptName>().to_vec(),
+                par_joined.field::<dept_table::DeptName>().to_vec()
+            );
+        }
+    }
+
+    #[test]
+    fn validated_join() {
+        let dv_emp = sample_emp_table().into_view();
+        let dv_dept = sample_dept_table().into_view();
+
+        // each department has many employees
+        let joined_dv = dv_emp
+            .join_validated::<Join<emp_table::DeptId, dept_table::DeptId, Equal>, _, _>(
+                &dv_dept,
+                JoinValidation::ManyToOne,
+            )
+            .unwrap();
+        assert_eq!(joined_dv.nrows(), 7);
+        assert!(dv_dept
+            .join_validated::<Join<dept_table::DeptId, emp_table::DeptId, Equal>, _, _>(
+                &dv_emp,
+                JoinValidation::OneToMany,
+            )
+            .is_ok());
+        match dv_emp.join_validated::<Join<emp_table::DeptId, dept_table::DeptId, Equal>, _, _>(
+            &dv_dept,
+            JoinValidation::OneToOne,
+        ) {
+            Err(AgnesError::JoinValidation(msg)) => {
+                assert_eq!(
+                    msg,
+                    "expected one-to-one join, but key value 1 appears more than once in \
+                     left-hand side (at indices 0 and 2)"
+                );
+            }
+            _ => panic!("expected join validation error"),
+        }
+
+        // missing key values are not considered duplicates
+        let dv_dept = dept_table_from_field(
+            FieldData::from_field_vec(vec![Value::Na, Value::Na, Value::Exists(1)]),
+            FieldData::from_vec(vec!["A", "B", "C"]),
+        )
+        .into_view();
+        assert!(dv_emp
+            .join_validated::<Join<emp_table::DeptId, dept_table::DeptId, Equal>, _, _>(
+                &dv_dept,
+                JoinValidation::ManyToOne,
+            )
+            .is_ok());
+    }
+
     #[test]
     fn inner_equi_join_missing_dept_id() {
         // dept id missing from dept table, should remove the entire marketing department from join
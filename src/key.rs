@@ -0,0 +1,263 @@
+/*!
+Rounded floating-point keys for joining, deduplicating, and grouping on floating-point fields.
+
+Floating-point values are neither hashable nor totally ordered, and values which should be equal
+often differ due to rounding error (e.g. `0.1 + 0.2 != 0.3`). A [FloatKey](struct.FloatKey.html)
+is a floating-point value rounded to a fixed number of decimal places, which is hashable and
+totally ordered, and can therefore be used as a key field for
+[join](../view/struct.DataView.html#method.join),
+[unique_indices](../view/struct.DataView.html#method.unique_indices), and
+[aggregate](../view/struct.DataView.html#method.aggregate) operations.
+
+The [FloatKeys](trait.FloatKeys.html) trait creates a field of `FloatKey`s from a numeric field,
+and the [DataView::with_float_key](../view/struct.DataView.html#method.with_float_key) method adds
+such a field to a `DataView`.
+*/
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use num_traits::AsPrimitive;
+
+use access::DataIndex;
+use field::FieldData;
+use frame::IntoFrame;
+use join::AddFrame;
+use select::{FieldSelect, SelectFieldByLabel};
+use store::IntoStore;
+use view::{DataView, VFieldTypeOf};
+
+/// A floating-point value rounded to a fixed number of decimal places, for use as a key in
+/// hashing- and ordering-based operations. All NaN values are considered equal to each other (and
+/// greater than all other values), and positive and negative zero are considered equal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FloatKey(f64);
+
+impl FloatKey {
+    /// Creates a new `FloatKey` from `value` rounded to `decimals` decimal places (rounding half
+    /// away from zero). Negative values of `decimals` round to the left of the decimal point (e.g.
+    /// `-2` rounds to the nearest hundred). Rounding to more decimal places than `value` can
+    /// represent leaves it unchanged, and rounding to the left of more digits than `value` has
+    /// yields zero.
+    pub fn new<T>(value: T, decimals: i32) -> FloatKey
+    where
+        T: AsPrimitive<f64>,
+    {
+        let value: f64 = value.as_();
+        let scale = 10f64.powi(decimals);
+        let scaled = value * scale;
+        let rounded = if !value.is_finite() {
+            value
+        } else if scale == 0.0 {
+            // `value` is smaller than the rounding unit (which overflows `f64`)
+            0.0
+        } else if !scaled.is_finite() {
+            // the rounding unit is smaller than the precision of `value`
+            value
+        } else {
+            scaled.round() / scale
+        };
+        FloatKey(if rounded.is_nan() {
+            f64::NAN
+        } else if rounded == 0.0 {
+            // normalize negative zero
+            0.0
+        } else {
+            rounded
+        })
+    }
+
+    /// Returns the rounded value of this key.
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+impl PartialEq for FloatKey {
+    fn eq(&self, other: &FloatKey) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for FloatKey {}
+
+impl PartialOrd for FloatKey {
+    fn partial_cmp(&self, other: &FloatKey) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for FloatKey {
+    fn cmp(&self, other: &FloatKey) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Hash for FloatKey {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.0.to_bits().hash(state);
+    }
+}
+
+impl fmt::Display for FloatKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// A trait for converting the values of a numeric field into [FloatKey](struct.FloatKey.html)s.
+pub trait FloatKeys {
+    /// Returns a field containing the values of this field rounded to `decimals` decimal places
+    /// as `FloatKey`s. Missing (NA) values remain missing.
+    fn float_keys(&self, decimals: i32) -> FieldData<FloatKey>;
+}
+
+impl<DI> FloatKeys for DI
+where
+    DI: DataIndex,
+    DI::DType: AsPrimitive<f64>,
+{
+    fn float_keys(&self, decimals: i32) -> FieldData<FloatKey> {
+        self.iter()
+            .map(|value| value.map(|&value| FloatKey::new(value, decimals)))
+            .collect()
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Creates a new `DataView` with an additional field labeled `KeyLabel`, containing the values
+    /// of the numeric field labeled `Label` rounded to `decimals` decimal places as
+    /// [FloatKey](../key/struct.FloatKey.html)s. The new field can then be used as a key field for
+    /// joins, deduplication, and grouping, so that floating-point noise does not produce spurious
+    /// mismatches or groups. See the [key](../key/index.html) module for more details.
+    pub fn with_float_key<Label, KeyLabel>(
+        &self,
+        decimals: i32,
+    ) -> <Self as WithFloatKey<Label, KeyLabel>>::Output
+    where
+        Self: WithFloatKey<Label, KeyLabel>,
+    {
+        WithFloatKey::<Label, KeyLabel>::with_float_key(self, decimals)
+    }
+}
+
+/// Trait providing the `with_float_key` method for adding a rounded floating-point key field to a
+/// `DataView`. See the intrinsic method
+/// [with_float_key](../view/struct.DataView.html#method.with_float_key) for more details.
+pub trait WithFloatKey<Label, KeyLabel> {
+    /// Type produced by this method.
+    type Output;
+
+    /// Add the rounded key field. See the intrinsic method
+    /// [with_float_key](../view/struct.DataView.html#method.with_float_key) for more details.
+    fn with_float_key(&self, decimals: i32) -> Self::Output;
+}
+
+/// Type of the frame added by [WithFloatKey](trait.WithFloatKey.html).
+pub type FloatKeyFrameOf<KeyLabel> =
+    <<FieldData<FloatKey> as IntoStore<KeyLabel>>::Output as IntoFrame>::Output;
+
+impl<Labels, Frames, Label, KeyLabel> WithFloatKey<Label, KeyLabel> for DataView<Labels, Frames>
+where
+    Self: SelectFieldByLabel<Label> + AddFrame<FloatKeyFrameOf<KeyLabel>>,
+    VFieldTypeOf<Self, Label>: AsPrimitive<f64>,
+    FieldData<FloatKey>: IntoStore<KeyLabel>,
+    <FieldData<FloatKey> as IntoStore<KeyLabel>>::Output: IntoFrame,
+{
+    type Output = <Self as AddFrame<FloatKeyFrameOf<KeyLabel>>>::Output;
+
+    fn with_float_key(&self, decimals: i32) -> Self::Output {
+        let keys = self.field::<Label>().float_keys(decimals);
+        self.add_frame(IntoStore::<KeyLabel>::into_store(keys).into_frame())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cons::Nil;
+    use join::{Equal, Join};
+    use store::DataStore;
+    use value::Value;
+
+    tablespace![
+        pub table prices {
+            Price: f64,
+            Qty: u64,
+        }
+        pub table rates {
+            Rate: f64,
+        }
+    ];
+    // key labels are declared separately, since `tablespace` field types must be nameable from
+    // within the generated table modules
+    mod keys {
+        use key::FloatKey;
+        first_label![PriceKey, ::typenum::U2, FloatKey];
+        next_label![RateKey, PriceKey, FloatKey];
+    }
+
+    #[test]
+    fn float_key() {
+        assert_eq!(FloatKey::new(0.1 + 0.2, 6), FloatKey::new(0.3, 6));
+        assert_ne!(0.1 + 0.2, 0.3);
+        assert_eq!(FloatKey::new(1.25f32, 1).value(), 1.3);
+        assert_eq!(FloatKey::new(1234u64, -2).value(), 1200.0);
+        assert_eq!(FloatKey::new(-0.0001, 2), FloatKey::new(0.0, 2));
+        assert_eq!(FloatKey::new(f64::NAN, 2), FloatKey::new(-f64::NAN, 0));
+        assert!(FloatKey::new(f64::NAN, 2) > FloatKey::new(f64::INFINITY, 2));
+        assert!(FloatKey::new(-1.0, 2) < FloatKey::new(0.5, 2));
+        assert_eq!(FloatKey::new(2.5, 0).to_string(), "3");
+        // decimal counts beyond the range of `f64` scales
+        assert_eq!(FloatKey::new(0.1, 400).value(), 0.1);
+        assert_eq!(FloatKey::new(1e300, 20).value(), 1e300);
+        assert_eq!(FloatKey::new(-1.5, i32::MAX).value(), -1.5);
+        assert_eq!(FloatKey::new(1234.5, -400).value(), 0.0);
+        assert_eq!(FloatKey::new(1234.5, i32::MIN).value(), 0.0);
+        assert_eq!(FloatKey::new(f64::INFINITY, 400).value(), f64::INFINITY);
+
+        let field = FieldData::from_field_vec(vec![Value::Exists(0.333), Value::Na]);
+        assert_eq!(
+            field.float_keys(2).to_value_vec(),
+            vec![Value::Exists(FloatKey::new(0.33, 2)), Value::Na]
+        );
+    }
+
+    #[test]
+    fn unique_group_join() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<prices::Price, _>(FieldData::<f64>::from_vec(vec![
+                0.1 + 0.2,
+                0.3,
+                1.5,
+                1.5000001,
+            ]))
+            .push_back_field::<prices::Qty, _>(FieldData::<u64>::from_vec(vec![1u64, 2, 3, 4]))
+            .into_view()
+            .with_float_key::<prices::Price, keys::PriceKey>(4);
+        assert_eq!(dv.fieldnames(), vec!["Price", "Qty", "PriceKey"]);
+        println!("{}", dv);
+        assert_eq!(dv.unique_indices::<Labels![keys::PriceKey]>(), vec![0, 2]);
+
+        let totals = dv.sum_by::<Labels![keys::PriceKey], prices::Qty, prices::Qty>();
+        assert_eq!(
+            totals.field::<keys::PriceKey>().to_vec(),
+            vec![FloatKey::new(0.3, 4), FloatKey::new(1.5, 4)]
+        );
+        assert_eq!(totals.field::<prices::Qty>().to_vec(), vec![3u64, 7]);
+
+        let rates = DataStore::<Nil>::empty()
+            .push_back_field::<rates::Rate, _>(FieldData::<f64>::from_vec(vec![1.49999, 0.7]))
+            .into_view()
+            .with_float_key::<rates::Rate, keys::RateKey>(4);
+        let joined = dv.join::<Join<keys::PriceKey, keys::RateKey, Equal>, _, _>(&rates);
+        assert_eq!(joined.nrows(), 2);
+        assert_eq!(joined.field::<prices::Qty>().to_vec(), vec![3u64, 4]);
+        assert_eq!(
+            joined.field::<rates::Rate>().to_vec(),
+            vec![1.49999, 1.49999]
+        );
+    }
+}
@@ -1,9 +1,11 @@
 /*!
 Traits, structs, and type aliases for handling cons-list element labels and associated logic.
 */
-use std::collections::VecDeque;
+use std::any::TypeId;
+use std::collections::{HashMap, VecDeque};
 use std::marker::PhantomData;
-use std::ops::{Add, BitAnd, BitOr, Not, Sub};
+use std::ops::{Add, BitAnd, BitOr, Not, Range, Sub};
+use std::sync::{Mutex, OnceLock};
 
 use typenum::{
     bit::{B0, B1},
@@ -145,6 +147,190 @@ where
     type Eq = <T::Ident as IdentEq<U::Ident>>::Eq;
 }
 
+/// A prefix and / or suffix which can be applied to a label using
+/// [Affixed](struct.Affixed.html). Affixes are identified by the type-level natural number `Id`,
+/// which must be unique among the affixes in use. The [label_affix](../macro.label_affix.html)
+/// macro can be used to declare new affixes.
+pub trait Affix {
+    /// The `typenum`-based natural number identifying this affix.
+    type Id;
+    /// The prefix added to affixed label names.
+    const PREFIX: &'static str = "";
+    /// The suffix added to affixed label names.
+    const SUFFIX: &'static str = "";
+}
+
+/// Affix adding the suffix `_left` to label names.
+#[derive(Debug, Clone)]
+pub struct Left;
+impl Affix for Left {
+    type Id = ::typenum::consts::U0;
+    const SUFFIX: &'static str = "_left";
+}
+
+/// Affix adding the suffix `_right` to label names.
+#[derive(Debug, Clone)]
+pub struct Right;
+impl Affix for Right {
+    type Id = ::typenum::consts::U1;
+    const SUFFIX: &'static str = "_right";
+}
+
+/// Label wrapper which produces a new label, distinct from `L`, with the name of `L` modified by
+/// the affix `A`. Affixed labels are used to disambiguate fields which share a label (for example,
+/// the non-key fields of a self-join); see
+/// [DataView::affix_colliding](../view/struct.DataView.html#method.affix_colliding) and
+/// [DataView::join_affixed](../view/struct.DataView.html#method.join_affixed).
+#[derive(Debug, Clone)]
+pub struct Affixed<L, A> {
+    _marker: PhantomData<(L, A)>,
+}
+
+/// The table of an [Affixed](struct.Affixed.html) label: the table `Tbl` of the original label
+/// along with the identifier `Id` of the affix.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AffixedTable<Tbl, Id> {
+    _marker: PhantomData<(Tbl, Id)>,
+}
+
+impl<L, A> Identifier for Affixed<L, A>
+where
+    L: Identifier,
+    A: Affix,
+{
+    type Ident = Ident<Self::Table, Self::Natural>;
+    type Table = AffixedTable<TblOf<L>, A::Id>;
+    type Natural = NatOf<L>;
+}
+
+impl<L, A> LabelName for Affixed<L, A>
+where
+    L: LabelName + 'static,
+    A: Affix + 'static,
+{
+    fn name() -> &'static str {
+        // affixed names are generated once per label / affix combination
+        static NAMES: OnceLock<Mutex<HashMap<TypeId, &'static str>>> = OnceLock::new();
+        let names = NAMES.get_or_init(Default::default);
+        let key = TypeId::of::<(L, A)>();
+        if let Some(&name) = names.lock().unwrap_or_else(|e| e.into_inner()).get(&key) {
+            return name;
+        }
+        // `L` may itself be affixed, so its name must be computed without holding the lock
+        let name = format!("{}{}{}", A::PREFIX, L::name(), A::SUFFIX);
+        names
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key)
+            .or_insert_with(|| Box::leak(name.into_boxed_str()))
+    }
+    fn str_type() -> &'static str {
+        L::str_type()
+    }
+}
+
+impl<L, A> Typed for Affixed<L, A>
+where
+    L: Typed,
+{
+    type DType = L::DType;
+}
+
+impl<Tbl, Id, UTbl, UId> IsEqual<AffixedTable<UTbl, UId>> for AffixedTable<Tbl, Id>
+where
+    Tbl: IsEqual<UTbl>,
+    Id: IsEqual<UId>,
+    <Tbl as IsEqual<UTbl>>::Output: BitAnd<<Id as IsEqual<UId>>::Output>,
+    And<<Tbl as IsEqual<UTbl>>::Output, <Id as IsEqual<UId>>::Output>: Bit,
+{
+    type Output = And<<Tbl as IsEqual<UTbl>>::Output, <Id as IsEqual<UId>>::Output>;
+    fn is_equal(self, _rhs: AffixedTable<UTbl, UId>) -> Self::Output {
+        Bit::new()
+    }
+}
+// affixed tables never match the (`typenum`-based) tables of `tablespace` labels
+impl<Tbl, Id> IsEqual<UTerm> for AffixedTable<Tbl, Id> {
+    type Output = False;
+    fn is_equal(self, _rhs: UTerm) -> False {
+        B0
+    }
+}
+impl<Tbl, Id, U, B> IsEqual<UInt<U, B>> for AffixedTable<Tbl, Id> {
+    type Output = False;
+    fn is_equal(self, _rhs: UInt<U, B>) -> False {
+        B0
+    }
+}
+impl<Tbl, Id> IsEqual<AffixedTable<Tbl, Id>> for UTerm {
+    type Output = False;
+    fn is_equal(self, _rhs: AffixedTable<Tbl, Id>) -> False {
+        B0
+    }
+}
+impl<U, B, Tbl, Id> IsEqual<AffixedTable<Tbl, Id>> for UInt<U, B> {
+    type Output = False;
+    fn is_equal(self, _rhs: AffixedTable<Tbl, Id>) -> False {
+        B0
+    }
+}
+
+/// The table of the labels which `agnes` itself defines for the output of some operations (such as
+/// the report of [DataView::validate](../view/struct.DataView.html#method.validate)), identified by
+/// the `typenum`-based natural number `Id`. Built-in tables never match the (`typenum`-based)
+/// tables of `tablespace` labels or the tables of affixed labels, so built-in labels never collide
+/// with user-defined labels.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BuiltinTable<Id> {
+    _marker: PhantomData<Id>,
+}
+
+impl<Id, UId> IsEqual<BuiltinTable<UId>> for BuiltinTable<Id>
+where
+    Id: IsEqual<UId>,
+    <Id as IsEqual<UId>>::Output: Bit,
+{
+    type Output = <Id as IsEqual<UId>>::Output;
+    fn is_equal(self, _rhs: BuiltinTable<UId>) -> Self::Output {
+        Bit::new()
+    }
+}
+impl<Id> IsEqual<UTerm> for BuiltinTable<Id> {
+    type Output = False;
+    fn is_equal(self, _rhs: UTerm) -> False {
+        B0
+    }
+}
+impl<Id, U, B> IsEqual<UInt<U, B>> for BuiltinTable<Id> {
+    type Output = False;
+    fn is_equal(self, _rhs: UInt<U, B>) -> False {
+        B0
+    }
+}
+impl<Id, Tbl, AId> IsEqual<AffixedTable<Tbl, AId>> for BuiltinTable<Id> {
+    type Output = False;
+    fn is_equal(self, _rhs: AffixedTable<Tbl, AId>) -> False {
+        B0
+    }
+}
+impl<Id> IsEqual<BuiltinTable<Id>> for UTerm {
+    type Output = False;
+    fn is_equal(self, _rhs: BuiltinTable<Id>) -> False {
+        B0
+    }
+}
+impl<U, B, Id> IsEqual<BuiltinTable<Id>> for UInt<U, B> {
+    type Output = False;
+    fn is_equal(self, _rhs: BuiltinTable<Id>) -> False {
+        B0
+    }
+}
+impl<Tbl, AId, Id> IsEqual<BuiltinTable<Id>> for AffixedTable<Tbl, AId> {
+    type Output = False;
+    fn is_equal(self, _rhs: BuiltinTable<Id>) -> False {
+        B0
+    }
+}
+
 /// Container for a value of type `V` labeled with `L`.
 #[derive(Debug, Clone)]
 pub struct Labeled<L, V> {
@@ -235,6 +421,7 @@ impl<T> SelfValued for ::field::FieldData<T> {}
 impl<T, DI> SelfValued for ::frame::Framed<T, DI> {}
 impl<T> SelfValued for DataRef<T> {}
 impl<T> SelfValued for PhantomData<T> {}
+impl<T> SelfValued for Range<T> {}
 
 /// Trait for extracting the an associated value of a value-holding container (e.g.
 /// [TypedValue](struct.TypedValue.html), [Labeled](struct.Labeled.html)).
@@ -985,6 +1172,29 @@ where
     }
 }
 
+/// Trait for generating the `TypeId`s of the labels associated with the `Self` cons-list. Unlike
+/// their names, `TypeId`s identify labels uniquely.
+pub trait LabelIds {
+    /// Returns the `TypeId`s of the labels associated with `Self`, in order.
+    fn label_ids() -> Vec<TypeId>;
+}
+impl LabelIds for Nil {
+    fn label_ids() -> Vec<TypeId> {
+        vec![]
+    }
+}
+impl<L, V, T> LabelIds for LVCons<L, V, T>
+where
+    L: 'static,
+    T: LabelIds,
+{
+    fn label_ids() -> Vec<TypeId> {
+        let mut ids = vec![TypeId::of::<L>()];
+        ids.extend(T::label_ids());
+        ids
+    }
+}
+
 impl NRows for Nil {
     fn nrows(&self) -> usize {
         0
@@ -1063,43 +1273,19 @@ where
 /// these modules, you can refer to the labels as you would a normal type; e.g., `employee::EmpId`.
 #[macro_export]
 macro_rules! tablespace {
-    (@fields() -> ($($out:tt)*)) => {
-        declare_fields![Table; $($out)*];
-        /// `FieldCons` cons-list of fields in this table.
-        pub type Fields = Fields![$($out)*];
-    };
-    (@fields(,) -> ($($out:tt)*)) => {
-        declare_fields![Table; $($out)*];
+    // fields are parsed in a single pass (rather than one field per recursion) so that wide
+    // tables don't hit the macro recursion limit
+    (@body($($field_name:ident: $field_ty:ident $(= {$str_name:expr})*),*$(,)*)) => {
+        declare_fields![
+            Table;
+            $($field_name: $field_ty = tablespace![@name $field_name $($str_name)*],)*
+        ];
         /// `FieldCons` cons-list of fields in this table.
-        pub type Fields = Fields![$($out)*];
+        pub type Fields = Fields![$($field_name: $field_ty,)*];
     };
 
-    (@fields
-        (,$field_name:ident: $field_ty:ident = {$str_name:expr} $($rest:tt)*)
-        ->
-        ($($out:tt)*)
-    ) => {
-        tablespace![@fields
-            ($($rest)*)
-            ->
-            ($($out)* $field_name: $field_ty = $str_name,)
-        ];
-    };
-    (@fields
-        (,$field_name:ident: $field_ty:ident $($rest:tt)*)
-        ->
-        ($($out:tt)*)
-    ) => {
-        tablespace![@fields
-            ($($rest)*)
-            ->
-            ($($out)* $field_name: $field_ty = stringify![$field_name],)
-        ];
-    };
-
-    (@body($($body:tt)*)) => {
-        tablespace![@fields(,$($body)*) -> ()];
-    };
+    (@name $field_name:ident) => { stringify![$field_name] };
+    (@name $field_name:ident $str_name:expr) => { $str_name };
 
     (@construct($vis:vis $tbl_name:ident)($nat:ty)($($body:tt)*)) => {
         $vis mod $tbl_name {
@@ -1127,6 +1313,12 @@ macro_rules! tablespace {
         }
     };
 
+    // tables of labels defined by `agnes` itself, which use a `BuiltinTable` so that they never
+    // collide with user-defined labels
+    (@builtin($id:ty) $vis:vis table $tbl_name:ident { $($body:tt)* }) => {
+        tablespace![@construct($vis $tbl_name)($crate::label::BuiltinTable<$id>)($($body)*)];
+    };
+
     // end case
     (@continue($prev_tbl:ty)) => {};
 
@@ -1178,6 +1370,36 @@ macro_rules! nat_label {
     };
 }
 
+/// Declare a new [Affix](label/trait.Affix.html) marker struct `$name`, identified by the
+/// `typenum`-based natural number `$id` (which must be unique among the affixes in use; `U0` and
+/// `U1` are used by the built-in [Left](label/struct.Left.html) and
+/// [Right](label/struct.Right.html) affixes).
+///
+/// # Example
+/// ```ignore
+/// label_affix![pub Prev, typenum::U2, prefix = "prev_"];
+/// label_affix![pub Next, typenum::U3, suffix = "_next"];
+/// ```
+#[macro_export]
+macro_rules! label_affix {
+    ($vis:vis $name:ident, $id:ty, $($kind:ident = $affix:expr),*) => {
+        /// Affix marker struct $name.
+        #[derive(Debug, Clone)]
+        $vis struct $name;
+
+        impl $crate::label::Affix for $name {
+            type Id = $id;
+            $(label_affix![@const $kind = $affix];)*
+        }
+    };
+    (@const prefix = $affix:expr) => {
+        const PREFIX: &'static str = $affix;
+    };
+    (@const suffix = $affix:expr) => {
+        const SUFFIX: &'static str = $affix;
+    };
+}
+
 /// Macro for handling creation of the first label in a table. Used by
 /// [declare_fields](macro.declare_fields.html).
 #[macro_export]
@@ -1218,6 +1440,14 @@ macro_rules! next_label {
 #[macro_export]
 macro_rules! Labels {
     (@labels()) => { $crate::cons::Nil };
+    // eight labels at a time, to keep the macro recursion depth manageable for long label lists
+    (@labels($l1:tt, $l2:tt, $l3:tt, $l4:tt, $l5:tt, $l6:tt, $l7:tt, $l8:tt, $($rest:tt,)*)) =>
+    {
+        $crate::label::LCons<$l1, $crate::label::LCons<$l2, $crate::label::LCons<$l3,
+        $crate::label::LCons<$l4, $crate::label::LCons<$l5, $crate::label::LCons<$l6,
+        $crate::label::LCons<$l7, $crate::label::LCons<$l8, Labels![@labels($($rest,)*)]
+        >>>>>>>>
+    };
     (@labels($label:ident, $($rest:tt,)*)) =>
     {
         $crate::label::LCons<$label, Labels![@labels($($rest,)*)]>
@@ -1243,6 +1473,51 @@ macro_rules! declare_fields
     // end case
     (@step($tbl:ty)($prev_label:ident)()) => {};
 
+    // eight non-initial labels at a time, to keep the macro recursion depth (and the depth of the
+    // type-level naturals backing the labels) manageable for wide tables
+    (@step
+        ($tbl:ty)
+        ($prev_label:ident)
+        (
+            $l1:ident: $d1:ident = $n1:expr,
+            $l2:ident: $d2:ident = $n2:expr,
+            $l3:ident: $d3:ident = $n3:expr,
+            $l4:ident: $d4:ident = $n4:expr,
+            $l5:ident: $d5:ident = $n5:expr,
+            $l6:ident: $d6:ident = $n6:expr,
+            $l7:ident: $d7:ident = $n7:expr,
+            $l8:ident: $d8:ident = $n8:expr,
+            $($rest:tt)*
+        )
+    )
+        =>
+    {
+        declare_fields![@offset($prev_label)($l1: $d1 = $n1, U1)];
+        declare_fields![@offset($prev_label)($l2: $d2 = $n2, U2)];
+        declare_fields![@offset($prev_label)($l3: $d3 = $n3, U3)];
+        declare_fields![@offset($prev_label)($l4: $d4 = $n4, U4)];
+        declare_fields![@offset($prev_label)($l5: $d5 = $n5, U5)];
+        declare_fields![@offset($prev_label)($l6: $d6 = $n6, U6)];
+        declare_fields![@offset($prev_label)($l7: $d7 = $n7, U7)];
+        declare_fields![@offset($prev_label)($l8: $d8 = $n8, U8)];
+        declare_fields![@step
+            ($tbl)
+            ($l8)
+            ($($rest)*)
+        ];
+    };
+
+    // label backed by the natural `$offset` greater than that of `$prev_label`
+    (@offset($prev_label:ident)($label:ident: $dtype:ident = $name:expr, $offset:ident)) => {
+        nat_label![
+            $label,
+            $crate::label::TblOf<$prev_label>,
+            $crate::typenum::Sum<$crate::label::NatOf<$prev_label>, $crate::typenum::$offset>,
+            $dtype,
+            $name
+        ];
+    };
+
     // non-initial label
     (@step
         ($tbl:ty)
@@ -1297,6 +1572,25 @@ macro_rules! declare_fields
 #[macro_export]
 macro_rules! Fields {
     (@fields()) => { $crate::cons::Nil };
+    // eight fields at a time, to keep the macro recursion depth manageable for wide tables
+    (@fields(
+        $l1:ident: $d1:ident, $l2:ident: $d2:ident, $l3:ident: $d3:ident, $l4:ident: $d4:ident,
+        $l5:ident: $d5:ident, $l6:ident: $d6:ident, $l7:ident: $d7:ident, $l8:ident: $d8:ident,
+        $($rest_label:ident: $rest_dtype:ident,)*
+    ))
+        =>
+    {
+        $crate::fieldlist::FieldCons<$l1, $d1,
+        $crate::fieldlist::FieldCons<$l2, $d2,
+        $crate::fieldlist::FieldCons<$l3, $d3,
+        $crate::fieldlist::FieldCons<$l4, $d4,
+        $crate::fieldlist::FieldCons<$l5, $d5,
+        $crate::fieldlist::FieldCons<$l6, $d6,
+        $crate::fieldlist::FieldCons<$l7, $d7,
+        $crate::fieldlist::FieldCons<$l8, $d8,
+        Fields![@fields($($rest_label: $rest_dtype,)*)]
+        >>>>>>>>
+    };
     (@fields(
         $label:ident: $dtype:ident $(= $name:expr)*,
         $($rest_label:ident: $rest_dtype:ident $(= $rest_name:expr)*,)*)
@@ -1646,4 +1940,68 @@ mod tests {
             <LabelSetDiff<LSet2, LSet2> as StrLabels>::labels_vec()[..]
         );
     }
+
+    tablespace![
+        table wide {
+            W0: u64,
+            W1: u64,
+            W2: u64,
+            W3: u64,
+            W4: u64,
+            W5: u64,
+            W6: u64,
+            W7: u64,
+            W8: u64,
+            W9: u64,
+            W10: u64,
+            W11: String = {"eleven"},
+            W12: u64,
+            W13: u64,
+            W14: u64,
+            W15: u64,
+            W16: u64,
+            W17: u64,
+            W18: u64,
+            W19: u64,
+        }
+    ];
+
+    #[test]
+    fn wide_tablespace() {
+        use typenum::Unsigned;
+
+        // labels declared in (and beyond) the eight-label chunks have the expected naturals
+        assert_eq!(<NatOf<wide::W0> as Unsigned>::USIZE, 0);
+        assert_eq!(<NatOf<wide::W8> as Unsigned>::USIZE, 8);
+        assert_eq!(<NatOf<wide::W11> as Unsigned>::USIZE, 11);
+        assert_eq!(<NatOf<wide::W16> as Unsigned>::USIZE, 16);
+        assert_eq!(<NatOf<wide::W19> as Unsigned>::USIZE, 19);
+        assert!(!<wide::W7 as LabelEq<wide::W8>>::Eq::to_bool());
+        assert!(<wide::W16 as LabelEq<wide::W16>>::Eq::to_bool());
+
+        assert_eq!(wide::W3::name(), "W3");
+        assert_eq!(wide::W11::name(), "eleven");
+        assert_eq!(wide::W11::str_type(), "String");
+
+        assert_eq!(length![wide::Fields], 20);
+        let labels = <<wide::Fields as AssocLabels>::Labels as StrLabels>::labels_vec();
+        assert_eq!(labels.len(), 20);
+        assert_eq!(labels[11], "eleven");
+        assert_eq!(labels[19], "W19");
+        assert_eq!(
+            <Labels![
+                wide::W0,
+                wide::W1,
+                wide::W2,
+                wide::W3,
+                wide::W4,
+                wide::W5,
+                wide::W6,
+                wide::W7,
+                wide::W8,
+                wide::W9
+            ] as StrLabels>::labels_vec(),
+            labels[..10].to_vec()
+        );
+    }
 }
@@ -53,33 +53,62 @@ The basic cons-list implementation can be found in the [cons](cons/index.html) m
 functionality for labeling cons-list elements and retrieving elements based on labels can be found
 in the [label](label/index.html) module.
 
+## Wide Tables
+
+Since fields are looked up at compile time, the compile time of code using a table (and the depth
+of the trait resolution the compiler needs to perform) grows with the number of fields in the
+table, and for operations which visit every field of a view (e.g. displaying, serializing, or
+writing a view to a sink) it grows faster than linearly. The label-declaring macros process fields
+in chunks, and these operations select each field directly from its frame rather than searching for
+its label, but each selection still looks the field up in its store. As a guide, displaying and
+serializing a view of 64 fields (the widest view in this crate's tests) adds under a minute to a
+debug build, and a view of 100 fields a few minutes.
+
+Tables with more than about 35 fields also need a larger trait recursion limit than the compiler's
+default: `#![recursion_limit = "256"]` in the crate using `agnes` is enough for 64 fields, and
+`#![recursion_limit = "512"]` for 100 fields.
+
 */
 
 #![warn(missing_docs)]
 #![deny(bare_trait_objects, unconditional_recursion)]
 
 extern crate bit_vec;
+#[cfg(feature = "net")]
+extern crate bytes;
+extern crate chrono;
 extern crate csv;
 extern crate encoding;
-extern crate futures;
-extern crate hyper;
-extern crate hyper_tls;
+#[cfg(feature = "net")]
+extern crate futures_util;
+extern crate glob;
 extern crate indexmap;
-extern crate native_tls;
 extern crate num_traits;
+#[cfg(feature = "object-store")]
+extern crate object_store;
+#[cfg(feature = "net")]
+extern crate reqwest;
 extern crate serde;
-extern crate tokio_core;
-extern crate tokio_io;
-#[macro_use]
-extern crate prettytable;
+#[cfg(feature = "net")]
+extern crate tokio;
+#[cfg(feature = "net")]
+extern crate tokio_util;
+#[cfg(feature = "net")]
+extern crate url;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+#[cfg(feature = "avro")]
+extern crate miniz_oxide;
+extern crate rand;
+extern crate regex;
 extern crate csv_sniffer;
+#[cfg(any(feature = "net", test))]
 extern crate tempfile;
+extern crate unicode_width;
 // re-export typenum (since it's used in exported macros)
 pub extern crate typenum;
 
-#[cfg(test)]
-extern crate rand;
-#[cfg(test)]
+#[cfg(any(feature = "serialize", feature = "avro", feature = "capi", test))]
 extern crate serde_json;
 
 #[macro_use]
@@ -102,15 +131,47 @@ pub mod value;
 pub mod test_utils;
 
 pub mod access;
+pub mod aggregate;
+pub mod align;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod case;
+pub mod cast;
+pub mod checksum;
+pub mod compress;
+pub mod diff;
+pub mod display;
+pub mod dynamic;
+pub mod erased;
 pub mod error;
+pub mod filter;
+pub mod format;
 pub mod frame;
+pub mod index;
+pub mod indexed;
 pub mod join;
+pub mod key;
+pub mod memory;
+pub mod observer;
 #[cfg(feature = "ops")]
 pub mod ops;
+#[cfg(feature = "parallel")]
+pub mod par;
+pub mod parse;
 pub mod permute;
+#[cfg(feature = "plot")]
+pub mod plot;
+pub mod query;
+pub mod random;
+pub mod recode;
+pub mod reduce;
 pub mod select;
+pub mod sink;
+pub mod sized;
 pub mod source;
+pub mod stack;
 pub mod stats;
+pub mod validate;
 pub mod view;
 pub mod view_stats;
 
@@ -0,0 +1,317 @@
+/*!
+Reporting of the memory used by the fields of `DataStore`s and `DataView`s.
+
+[DataStore::memory_usage](../store/struct.DataStore.html#method.memory_usage) and
+[DataView::memory_usage](../view/struct.DataView.html#method.memory_usage) return a
+[MemoryReport](struct.MemoryReport.html) with the number of bytes allocated for each field, broken
+down into the field's values, its missing-value (NA) mask, and any heap data owned by the values
+(such as the text of `String` values). This can be used to find the fields which dominate the
+memory usage of a data set, and decide which to drop (or, for fields only needed for computation
+outside the store, which to hold in one of the compressed encodings of the
+[compress](../compress/index.html) module).
+
+Reported sizes are of allocated capacity (which may exceed the space needed for the values), and
+do not include the (small, fixed) size of the field structures themselves. Since views share the
+fields of the data stores they were created from, the memory reported for a view is that of the
+underlying store fields (which is not freed until every view of the store is dropped), and may be
+shared with other views. The report of a view also includes the memory of the
+[permutations](struct.MemoryReport.html#method.permutations) which select and order the records of
+its frames, which for filtered or sorted views of large stores can exceed that of the fields.
+*/
+
+use std::cmp::Reverse;
+use std::fmt;
+use std::mem;
+use std::rc::Rc;
+
+use cons::Nil;
+use display::TextTable;
+use key::FloatKey;
+use label::{LVCons, LabelName, Valued};
+use permute::{FramePermutations, Permutation};
+use view::{AssocDataIndexCons, AssocDataIndexConsOf, DataView};
+
+/// Trait for values which may own heap-allocated data, which should be counted towards the memory
+/// usage of the fields containing them.
+pub trait HeapSize {
+    /// Returns the number of heap bytes owned by this value (not including the size of the value
+    /// itself).
+    fn heap_size(&self) -> usize;
+}
+
+macro_rules! impl_heap_size_none {
+    ($($dtype:ty)*) => {$(
+        impl HeapSize for $dtype {
+            fn heap_size(&self) -> usize {
+                0
+            }
+        }
+    )*}
+}
+impl_heap_size_none![f64 f32 u64 u32 u16 u8 usize i64 i32 i16 i8 isize bool char FloatKey];
+
+impl HeapSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+impl HeapSize for &str {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+/// The number of bytes allocated for a single field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FieldMemory {
+    /// Bytes allocated for the field's values.
+    pub values: usize,
+    /// Bytes allocated for the field's missing-value (NA) mask (zero if the field has never
+    /// contained a missing value).
+    pub na_mask: usize,
+    /// Heap bytes owned by the field's values (e.g. the text of `String` values).
+    pub heap: usize,
+}
+
+impl FieldMemory {
+    /// Returns the total number of bytes allocated for the field.
+    pub fn total(&self) -> usize {
+        self.values + self.na_mask + self.heap
+    }
+}
+
+impl ::std::ops::Add for FieldMemory {
+    type Output = FieldMemory;
+
+    fn add(self, other: FieldMemory) -> FieldMemory {
+        FieldMemory {
+            values: self.values + other.values,
+            na_mask: self.na_mask + other.na_mask,
+            heap: self.heap + other.heap,
+        }
+    }
+}
+
+/// Returns the number of bytes allocated for the values (and the heap data owned by the values)
+/// of `data`, a vector of values of type `T`.
+pub(crate) fn vec_memory<T: HeapSize>(data: &Vec<T>) -> FieldMemory {
+    FieldMemory {
+        values: data.capacity() * mem::size_of::<T>(),
+        na_mask: 0,
+        heap: data.iter().map(HeapSize::heap_size).sum(),
+    }
+}
+
+/// Trait for fields which can report the number of bytes allocated for their data.
+pub trait MemoryUsage {
+    /// Returns the number of bytes allocated for this field.
+    fn memory_usage(&self) -> FieldMemory;
+}
+
+/// Per-field memory usage of a `DataStore` or `DataView`. The `Display` implementation prints a
+/// table of the memory used by each field (and by the permutations of a view's frames).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryReport {
+    fields: Vec<(String, FieldMemory)>,
+    permutations: usize,
+}
+
+impl MemoryReport {
+    /// Returns the field names and memory usage of each field, in field order.
+    pub fn fields(&self) -> &[(String, FieldMemory)] {
+        &self.fields
+    }
+
+    /// Returns the memory usage of the field named `name`, if it exists.
+    pub fn get(&self, name: &str) -> Option<FieldMemory> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|&(_, memory)| memory)
+    }
+
+    /// Returns the number of bytes allocated for the permutations of a view's frames (the indices
+    /// of the records each frame selects, in order), counting permutations shared by several
+    /// frames once. This is zero for data stores, and for views whose records have not been
+    /// filtered or reordered.
+    pub fn permutations(&self) -> usize {
+        self.permutations
+    }
+
+    /// Returns the total number of bytes allocated for all fields (and permutations).
+    pub fn total(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|(_, memory)| memory.total())
+            .sum::<usize>()
+            + self.permutations
+    }
+
+    /// Returns the fields ordered by total memory usage, largest first.
+    pub fn largest(&self) -> Vec<(String, FieldMemory)> {
+        let mut fields = self.fields.clone();
+        fields.sort_by_key(|(_, memory)| Reverse(memory.total()));
+        fields
+    }
+}
+
+impl fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut table =
+            TextTable::new(&["Field", "Values", "NA Mask", "Heap", "Total"]).borders(true);
+        for (name, memory) in &self.fields {
+            table.add_row(&[
+                name.clone(),
+                memory.values.to_string(),
+                memory.na_mask.to_string(),
+                memory.heap.to_string(),
+                memory.total().to_string(),
+            ]);
+        }
+        if self.permutations > 0 {
+            let permutations = self.permutations.to_string();
+            table.add_row(&["Permutations", &permutations, "", "", &permutations]);
+        }
+        table.add_row(&["Total", "", "", "", &self.total().to_string()]);
+        table.fmt(f)
+    }
+}
+
+/// Trait for collecting the memory usage of each field in a cons-list of labeled fields (such as
+/// the storage of a `DataStore`, or the fields of a `DataView`).
+pub trait FieldsMemoryUsage {
+    /// Appends the name and memory usage of each field in this cons-list to `fields`.
+    fn fields_memory_usage(&self, fields: &mut Vec<(String, FieldMemory)>);
+
+    /// Returns a [MemoryReport](struct.MemoryReport.html) of the fields in this cons-list.
+    fn memory_report(&self) -> MemoryReport {
+        let mut fields = vec![];
+        self.fields_memory_usage(&mut fields);
+        MemoryReport {
+            fields,
+            permutations: 0,
+        }
+    }
+}
+impl FieldsMemoryUsage for Nil {
+    fn fields_memory_usage(&self, _fields: &mut Vec<(String, FieldMemory)>) {}
+}
+impl<Label, Value, Tail> FieldsMemoryUsage for LVCons<Label, Value, Tail>
+where
+    Value: Valued,
+    Value::Value: MemoryUsage,
+    Label: LabelName,
+    Tail: FieldsMemoryUsage,
+{
+    fn fields_memory_usage(&self, fields: &mut Vec<(String, FieldMemory)>) {
+        fields.push((
+            Label::name().to_string(),
+            self.head.value_ref().memory_usage(),
+        ));
+        self.tail.fields_memory_usage(fields);
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames>
+where
+    Frames: AssocDataIndexCons<Labels> + FramePermutations,
+    AssocDataIndexConsOf<Labels, Frames>: FieldsMemoryUsage,
+{
+    /// Returns the number of bytes allocated for each field of this `DataView`, and for the
+    /// permutations of its frames. Since views share the fields of their underlying data stores,
+    /// this is the memory of the store fields, which may be shared with other views (see the
+    /// [memory](../memory/index.html) module).
+    pub fn memory_usage(&self) -> MemoryReport {
+        let mut report = self.frames.assoc_data().memory_report();
+        let mut perms = vec![];
+        self.frames.frame_permutations(&mut perms);
+        // frames may share a single permutation
+        let mut distinct: Vec<Rc<Permutation<Vec<usize>>>> = vec![];
+        for perm in perms {
+            if !distinct.iter().any(|other| Rc::ptr_eq(other, &perm)) {
+                distinct.push(perm);
+            }
+        }
+        report.permutations = distinct.iter().map(|perm| perm.heap_size()).sum();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use field::{FieldData, StrFieldData};
+    use value::Value;
+
+    #[test]
+    fn field_memory() {
+        let field: FieldData<u64> = vec![1u64, 2, 3, 4].into();
+        let memory = field.memory_usage();
+        assert!(memory.values >= 4 * mem::size_of::<u64>());
+        assert_eq!(memory.na_mask, 0);
+        assert_eq!(memory.heap, 0);
+
+        let field = FieldData::from_field_vec(vec![Value::Exists(1u64), Value::Na]);
+        assert!(field.memory_usage().na_mask > 0);
+
+        let names = vec!["Sally", "Jamie", "Bob"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect::<FieldData<String>>();
+        let memory = names.memory_usage();
+        assert!(memory.heap >= 13);
+        assert_eq!(memory.total(), memory.values + memory.heap);
+        let compact = StrFieldData::from(&names).memory_usage();
+        assert!(compact.heap >= 13);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn memory_report() {
+        use test_utils::*;
+
+        let store = sample_emp_table();
+        let report = store.memory_usage();
+        assert_eq!(
+            report
+                .fields()
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["EmpId", "DeptId", "EmpName"]
+        );
+        let names = report.get("EmpName").unwrap();
+        assert!(names.heap > 0);
+        assert!(report.get("Missing").is_none());
+        assert_eq!(
+            report.total(),
+            report
+                .fields()
+                .iter()
+                .map(|(_, memory)| memory.total())
+                .sum::<usize>()
+        );
+        assert_eq!(report.largest()[0].0, "EmpName");
+        println!("{}", report);
+
+        // views report the memory of the underlying store fields they select
+        let dv = store.into_view();
+        assert_eq!(dv.memory_usage(), report);
+        let sorted = dv.sort_by_label::<emp_table::EmpName>();
+        let subset = sorted.v::<Labels![emp_table::EmpName]>().memory_usage();
+        assert_eq!(subset.fields().len(), 1);
+        assert_eq!(subset.get("EmpName"), Some(names));
+
+        // permutations of sorted (or filtered) views are counted, once per permutation
+        assert_eq!(report.permutations(), 0);
+        assert!(subset.permutations() >= 7 * mem::size_of::<usize>());
+        let sorted_report = sorted.memory_usage();
+        assert_eq!(sorted_report.permutations(), subset.permutations());
+        assert_eq!(
+            sorted_report.total(),
+            report.total() + sorted_report.permutations()
+        );
+        assert!(sorted_report.to_string().contains("Permutations"));
+        assert!(!report.to_string().contains("Permutations"));
+    }
+}
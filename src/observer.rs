@@ -0,0 +1,252 @@
+/*!
+Hooks for observing the progress of long-running operations.
+
+Operations which may take a long time on large data sets (such as reading a CSV file, sorting, or
+joining) provide `_observed` variants (e.g.
+[CsvReader::read_observed](../source/csv/struct.CsvReader.html#method.read_observed)) which take an
+[Observer](trait.Observer.html). The observer is periodically notified of the number of rows
+processed, allowing user interfaces and command-line tools to display progress. Any closure of the
+form `FnMut(Stage, usize, Option<usize>)` can be used as an observer.
+
+Observers can also request that an operation be aborted, in which case the operation fails with
+[AgnesError::Cancelled](../error/enum.AgnesError.html#variant.Cancelled). A
+[CancellationToken](struct.CancellationToken.html) can be shared with another thread (such as a
+user interface thread) to cancel a long-running computation, and can be used directly as an
+observer or combined with a progress observer using
+[WithCancellation](struct.WithCancellation.html).
+*/
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use error::*;
+
+/// Number of rows processed between successive progress reports.
+pub(crate) const REPORT_INTERVAL: usize = 1 << 13;
+
+/// The stage of an operation which is reporting progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Reading records from a data source.
+    Read,
+    /// Sorting records.
+    Sort,
+    /// Matching records between the two sides of a join.
+    Join,
+    /// Accumulating values into aggregates.
+    Aggregate,
+}
+
+/// A trait for receiving progress updates from long-running operations.
+pub trait Observer {
+    /// Called periodically during an operation in stage `stage`, with the number of rows
+    /// processed so far and the total number of rows to process (if known in advance). Each
+    /// operation reports once when starting and once when finished, with updates every few
+    /// thousand rows in between.
+    fn progress(&mut self, stage: Stage, processed: usize, total: Option<usize>);
+
+    /// Returns `true` if the operation being observed should be aborted. Checked when an operation
+    /// starts and at each progress update. Defaults to `false`.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+impl<F> Observer for F
+where
+    F: FnMut(Stage, usize, Option<usize>),
+{
+    fn progress(&mut self, stage: Stage, processed: usize, total: Option<usize>) {
+        self(stage, processed, total)
+    }
+}
+
+/// An [Observer](trait.Observer.html) which ignores all progress updates.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoObserver;
+
+impl Observer for NoObserver {
+    fn progress(&mut self, _stage: Stage, _processed: usize, _total: Option<usize>) {}
+}
+
+/// A token for cancelling long-running operations. Clones of a token share the same cancellation
+/// state, so a token can be cloned and sent to another thread to cancel an operation in progress.
+///
+/// As an [Observer](trait.Observer.html), a token ignores progress updates and reports
+/// cancellation once [cancel](#method.cancel) has been called.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a new (not yet cancelled) cancellation token.
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Cancels any operations observed by this token (or any of its clones).
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if this token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+impl Observer for CancellationToken {
+    fn progress(&mut self, _stage: Stage, _processed: usize, _total: Option<usize>) {}
+
+    fn is_cancelled(&self) -> bool {
+        CancellationToken::is_cancelled(self)
+    }
+}
+
+/// An [Observer](trait.Observer.html) which passes progress updates to another observer, and
+/// reports cancellation when either that observer or a
+/// [CancellationToken](struct.CancellationToken.html) requests it.
+#[derive(Debug, Clone)]
+pub struct WithCancellation<O> {
+    observer: O,
+    token: CancellationToken,
+}
+
+impl<O> WithCancellation<O> {
+    /// Creates a new observer passing progress updates to `observer`, which can be cancelled
+    /// using `token`.
+    pub fn new(observer: O, token: CancellationToken) -> WithCancellation<O> {
+        WithCancellation { observer, token }
+    }
+}
+
+impl<O> Observer for WithCancellation<O>
+where
+    O: Observer,
+{
+    fn progress(&mut self, stage: Stage, processed: usize, total: Option<usize>) {
+        self.observer.progress(stage, processed, total)
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled() || self.observer.is_cancelled()
+    }
+}
+
+// Helper for reporting the progress of a single operation stage to an observer, throttled to once
+// every `REPORT_INTERVAL` rows.
+pub(crate) struct Reporter<'a> {
+    observer: &'a mut dyn Observer,
+    stage: Stage,
+    total: Option<usize>,
+    processed: usize,
+}
+
+impl<'a> Reporter<'a> {
+    // Creates a new reporter for stage `stage`, reporting the start of the stage.
+    pub(crate) fn new(
+        observer: &'a mut dyn Observer,
+        stage: Stage,
+        total: Option<usize>,
+    ) -> Reporter<'a> {
+        observer.progress(stage, 0, total);
+        Reporter {
+            observer,
+            stage,
+            total,
+            processed: 0,
+        }
+    }
+
+    // Fails with `AgnesError::Cancelled` if the observer has requested cancellation.
+    pub(crate) fn check(&self) -> Result<()> {
+        if self.observer.is_cancelled() {
+            Err(AgnesError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    // Updates the number of rows processed, reporting (and checking for cancellation) if another
+    // `REPORT_INTERVAL` rows have been processed since the last report.
+    pub(crate) fn update(&mut self, processed: usize) -> Result<()> {
+        let reported = self.processed / REPORT_INTERVAL;
+        self.processed = processed;
+        if processed / REPORT_INTERVAL > reported {
+            self.observer.progress(self.stage, processed, self.total);
+            self.check()?;
+        }
+        Ok(())
+    }
+
+    // Adds `n` to the number of rows processed.
+    pub(crate) fn advance(&mut self, n: usize) -> Result<()> {
+        let processed = self.processed + n;
+        self.update(processed)
+    }
+
+    // Reports the completion of this stage.
+    pub(crate) fn finish(self) {
+        let processed = self.total.unwrap_or(self.processed);
+        self.observer.progress(self.stage, processed, self.total);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reporter() {
+        let mut reports = vec![];
+        {
+            let mut observer = |stage, processed, total| reports.push((stage, processed, total));
+            let mut reporter = Reporter::new(&mut observer, Stage::Read, None);
+            for _ in 0..REPORT_INTERVAL * 2 + 10 {
+                reporter.advance(1).unwrap();
+            }
+            reporter.finish();
+        }
+        assert_eq!(
+            reports,
+            vec![
+                (Stage::Read, 0, None),
+                (Stage::Read, REPORT_INTERVAL, None),
+                (Stage::Read, REPORT_INTERVAL * 2, None),
+                (Stage::Read, REPORT_INTERVAL * 2 + 10, None),
+            ]
+        );
+
+        let mut reports = vec![];
+        {
+            let mut observer = |stage, processed, total| reports.push((stage, processed, total));
+            let mut reporter = Reporter::new(&mut observer, Stage::Join, Some(5));
+            reporter.update(3).unwrap();
+            reporter.finish();
+        }
+        assert_eq!(
+            reports,
+            vec![(Stage::Join, 0, Some(5)), (Stage::Join, 5, Some(5))]
+        );
+    }
+
+    #[test]
+    fn cancellation() {
+        let token = CancellationToken::new();
+        let mut observer = WithCancellation::new(NoObserver, token.clone());
+        {
+            let mut reporter = Reporter::new(&mut observer, Stage::Read, None);
+            assert!(reporter.check().is_ok());
+            reporter.update(REPORT_INTERVAL - 1).unwrap();
+            token.cancel();
+            // cancellation only checked at each report
+            reporter.advance(0).unwrap();
+            match reporter.advance(1) {
+                Err(AgnesError::Cancelled) => {}
+                _ => panic!("expected cancellation"),
+            }
+        }
+        assert!(observer.is_cancelled());
+        assert!(!CancellationToken::new().is_cancelled());
+    }
+}
@@ -0,0 +1,216 @@
+/*!
+Division operations with configurable handling of division by zero.
+
+The standard division operator (`/`) on fields produces infinite or NaN values when dividing
+floating-point values by zero, and panics when dividing integer values by zero. The
+[DivWith](trait.DivWith.html) trait provides division operations which take a
+[ZeroDivision](enum.ZeroDivision.html) option, specifying whether to keep this behavior or to
+produce missing (NA) values instead.
+*/
+use std::fmt::Debug;
+use std::ops::Div;
+
+use access::DataIndex;
+use error;
+use field::FieldData;
+use value::Value;
+
+/// Option for handling division by zero (or NaN results) in [DivWith](trait.DivWith.html)
+/// operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZeroDivision {
+    /// Use the behavior of the data type's division operator: infinite or NaN values for
+    /// floating-point data, and a panic for integer data.
+    #[default]
+    Propagate,
+    /// Produce a missing (NA) value for division by zero, as well as for any division resulting in
+    /// NaN.
+    ToNA,
+}
+
+/// A trait for data types which support division producing `None` for division by zero (or NaN
+/// results).
+pub trait DivNA: Copy + Div<Output = Self> {
+    /// Divide `self` by `rhs`, returning `None` if `rhs` is zero or the result is NaN.
+    fn div_na(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_div_na_int {
+    ($($dtype:ty)*) => {$(
+        impl DivNA for $dtype {
+            fn div_na(self, rhs: $dtype) -> Option<$dtype> {
+                self.checked_div(rhs)
+            }
+        }
+    )*}
+}
+impl_div_na_int![u64 u32 usize i64 i32 isize];
+
+macro_rules! impl_div_na_float {
+    ($($dtype:ty)*) => {$(
+        impl DivNA for $dtype {
+            fn div_na(self, rhs: $dtype) -> Option<$dtype> {
+                let result = self / rhs;
+                if rhs == 0.0 || result.is_nan() {
+                    None
+                } else {
+                    Some(result)
+                }
+            }
+        }
+    )*}
+}
+impl_div_na_float![f64 f32];
+
+fn div_value<T>(lhs: T, rhs: T, zero_division: ZeroDivision) -> Value<T>
+where
+    T: DivNA,
+{
+    match zero_division {
+        ZeroDivision::Propagate => Value::Exists(lhs / rhs),
+        ZeroDivision::ToNA => lhs.div_na(rhs).into(),
+    }
+}
+
+/// A trait for division operations on fields, with division by zero handled according to a
+/// [ZeroDivision](enum.ZeroDivision.html) option. Missing (NA) values in either operand result in
+/// missing values.
+pub trait DivWith: DataIndex + Sized
+where
+    <Self as DataIndex>::DType: DivNA + Debug + Default,
+{
+    /// Divide each value of this field by the scalar `rhs`, handling division by zero according
+    /// to `zero_division`.
+    fn div_scalar_with(
+        &self,
+        rhs: Self::DType,
+        zero_division: ZeroDivision,
+    ) -> FieldData<Self::DType> {
+        self.iter()
+            .map(|value| match value {
+                Value::Exists(&lhs) => div_value(lhs, rhs, zero_division),
+                Value::Na => Value::Na,
+            })
+            .collect()
+    }
+
+    /// Divide each value of this field by the corresponding value of the field `rhs`, handling
+    /// division by zero according to `zero_division`.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::LengthMismatch` if the fields are of different lengths.
+    fn div_field_with<R>(
+        &self,
+        rhs: &R,
+        zero_division: ZeroDivision,
+    ) -> error::Result<FieldData<Self::DType>>
+    where
+        R: DataIndex<DType = Self::DType>,
+    {
+        if self.len() != rhs.len() {
+            return Err(error::AgnesError::LengthMismatch {
+                expected: self.len(),
+                actual: rhs.len(),
+            });
+        }
+        Ok(self
+            .iter()
+            .zip(rhs.iter())
+            .map(|values| match values {
+                (Value::Exists(&lhs), Value::Exists(&rhs)) => div_value(lhs, rhs, zero_division),
+                _ => Value::Na,
+            })
+            .collect())
+    }
+}
+
+impl<DI> DivWith for DI
+where
+    DI: DataIndex,
+    DI::DType: DivNA + Debug + Default,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn div_scalar() {
+        let field = FieldData::from_field_vec(vec![
+            Value::Exists(2.0f64),
+            Value::Exists(0.0),
+            Value::Na,
+            Value::Exists(-3.0),
+        ]);
+        let result = field
+            .div_scalar_with(0.0, ZeroDivision::Propagate)
+            .to_value_vec();
+        assert_eq!(result[0], Value::Exists(f64::INFINITY));
+        assert!(result[1].map_or(false, |v| v.is_nan()));
+        assert_eq!(result[2], Value::Na);
+
+        assert_eq!(
+            field
+                .div_scalar_with(0.0, ZeroDivision::ToNA)
+                .to_value_vec(),
+            vec![Value::Na; 4]
+        );
+        assert_eq!(
+            field
+                .div_scalar_with(2.0, ZeroDivision::ToNA)
+                .to_value_vec(),
+            vec![
+                Value::Exists(1.0),
+                Value::Exists(0.0),
+                Value::Na,
+                Value::Exists(-1.5)
+            ]
+        );
+
+        let field = FieldData::<u64>::from_vec(vec![6u64, 3]);
+        assert_eq!(
+            field.div_scalar_with(0, ZeroDivision::ToNA).to_value_vec(),
+            vec![Value::Na, Value::Na]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn div_scalar_propagate_int() {
+        let field = FieldData::<u64>::from_vec(vec![6u64, 3]);
+        field.div_scalar_with(0, ZeroDivision::Propagate);
+    }
+
+    #[test]
+    fn div_field() {
+        let left = FieldData::<i64>::from_vec(vec![6i64, 3, 0, 8]);
+        let right = FieldData::from_field_vec(vec![
+            Value::Exists(2i64),
+            Value::Exists(0),
+            Value::Exists(5),
+            Value::Na,
+        ]);
+        assert_eq!(
+            left.div_field_with(&right, ZeroDivision::ToNA)
+                .unwrap()
+                .to_value_vec(),
+            vec![Value::Exists(3), Value::Na, Value::Exists(0), Value::Na]
+        );
+
+        let left = FieldData::<f64>::from_vec(vec![1.0f64, 0.0]);
+        let right = FieldData::<f64>::from_vec(vec![0.0f64, 0.0]);
+        assert_eq!(
+            left.div_field_with(&right, ZeroDivision::ToNA)
+                .unwrap()
+                .to_value_vec(),
+            vec![Value::Na, Value::Na]
+        );
+        assert!(left
+            .div_field_with(
+                &FieldData::<f64>::from_vec(vec![1.0f64]),
+                ZeroDivision::ToNA
+            )
+            .is_err());
+    }
+}
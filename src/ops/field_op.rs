@@ -9,7 +9,7 @@ use std::ops::{Add, Div, Mul, Sub};
 use access::{DataIndex, DataIndexMut};
 use error;
 use field::FieldData;
-use frame::Framed;
+use ops::FramedRef;
 use store::DataRef;
 
 /// A trait for an add operation between two fields that returns an error if the fields are of
@@ -227,15 +227,15 @@ impl_field_op![
     LengthCheckedAdd add_checked;
 
     [[FieldData<T>] [FieldData<T>]]
-    [[FieldData<T>] [Framed<T>]]
+    [[FieldData<T>] [FramedRef<T>]]
     [[FieldData<T>] [DataRef<T>]]
 
-    [[Framed<T>] [FieldData<T>]]
-    [[Framed<T>] [Framed<T>]]
-    [[Framed<T>] [DataRef<T>]]
+    [[FramedRef<T>] [FieldData<T>]]
+    [[FramedRef<T>] [FramedRef<T>]]
+    [[FramedRef<T>] [DataRef<T>]]
 
     [[DataRef<T>] [FieldData<T>]]
-    [[DataRef<T>] [Framed<T>]]
+    [[DataRef<T>] [FramedRef<T>]]
     [[DataRef<T>] [DataRef<T>]]
 ];
 
@@ -244,15 +244,15 @@ impl_field_op![
     LengthCheckedSub sub_checked;
 
     [[FieldData<T>] [FieldData<T>]]
-    [[FieldData<T>] [Framed<T>]]
+    [[FieldData<T>] [FramedRef<T>]]
     [[FieldData<T>] [DataRef<T>]]
 
-    [[Framed<T>] [FieldData<T>]]
-    [[Framed<T>] [Framed<T>]]
-    [[Framed<T>] [DataRef<T>]]
+    [[FramedRef<T>] [FieldData<T>]]
+    [[FramedRef<T>] [FramedRef<T>]]
+    [[FramedRef<T>] [DataRef<T>]]
 
     [[DataRef<T>] [FieldData<T>]]
-    [[DataRef<T>] [Framed<T>]]
+    [[DataRef<T>] [FramedRef<T>]]
     [[DataRef<T>] [DataRef<T>]]
 ];
 
@@ -261,15 +261,15 @@ impl_field_op![
     LengthCheckedMul mul_checked;
 
     [[FieldData<T>] [FieldData<T>]]
-    [[FieldData<T>] [Framed<T>]]
+    [[FieldData<T>] [FramedRef<T>]]
     [[FieldData<T>] [DataRef<T>]]
 
-    [[Framed<T>] [FieldData<T>]]
-    [[Framed<T>] [Framed<T>]]
-    [[Framed<T>] [DataRef<T>]]
+    [[FramedRef<T>] [FieldData<T>]]
+    [[FramedRef<T>] [FramedRef<T>]]
+    [[FramedRef<T>] [DataRef<T>]]
 
     [[DataRef<T>] [FieldData<T>]]
-    [[DataRef<T>] [Framed<T>]]
+    [[DataRef<T>] [FramedRef<T>]]
     [[DataRef<T>] [DataRef<T>]]
 ];
 
@@ -278,22 +278,22 @@ impl_field_op![
     LengthCheckedDiv div_checked;
 
     [[FieldData<T>] [FieldData<T>]]
-    [[FieldData<T>] [Framed<T>]]
+    [[FieldData<T>] [FramedRef<T>]]
     [[FieldData<T>] [DataRef<T>]]
 
-    [[Framed<T>] [FieldData<T>]]
-    [[Framed<T>] [Framed<T>]]
-    [[Framed<T>] [DataRef<T>]]
+    [[FramedRef<T>] [FieldData<T>]]
+    [[FramedRef<T>] [FramedRef<T>]]
+    [[FramedRef<T>] [DataRef<T>]]
 
     [[DataRef<T>] [FieldData<T>]]
-    [[DataRef<T>] [Framed<T>]]
+    [[DataRef<T>] [FramedRef<T>]]
     [[DataRef<T>] [DataRef<T>]]
 ];
 
 #[cfg(test)]
 mod tests {
     use field::FieldData;
-    use frame::Framed;
+    use ops::FramedRef;
     use store::DataRef;
 
     macro_rules! test_op {
@@ -330,7 +330,7 @@ mod tests {
                 $left, $right, FieldData, DataRef, $result, left, right, &left $op &right
             ];
             test_field_op![@test_structs
-                $left, $right, FieldData, Framed, $result, left, right, &left $op &right
+                $left, $right, FieldData, FramedRef, $result, left, right, &left $op &right
             ];
 
             test_field_op![@test_structs
@@ -340,17 +340,17 @@ mod tests {
                 $left, $right, DataRef, DataRef, $result, left, right, &left $op &right
             ];
             test_field_op![@test_structs
-                $left, $right, DataRef, Framed, $result, left, right, &left $op &right
+                $left, $right, DataRef, FramedRef, $result, left, right, &left $op &right
             ];
 
             test_field_op![@test_structs
-                $left, $right, Framed, FieldData, $result, left, right, &left $op &right
+                $left, $right, FramedRef, FieldData, $result, left, right, &left $op &right
             ];
             test_field_op![@test_structs
-                $left, $right, Framed, DataRef, $result, left, right, &left $op &right
+                $left, $right, FramedRef, DataRef, $result, left, right, &left $op &right
             ];
             test_field_op![@test_structs
-                $left, $right, Framed, Framed, $result, left, right, &left $op &right
+                $left, $right, FramedRef, FramedRef, $result, left, right, &left $op &right
             ];
 
             // &left <op> right
@@ -361,7 +361,7 @@ mod tests {
                 $left, $right, DataRef, FieldData, $result, left, right, &left $op right
             ];
             test_field_op![@test_structs
-                $left, $right, Framed, FieldData, $result, left, right, &left $op right
+                $left, $right, FramedRef, FieldData, $result, left, right, &left $op right
             ];
 
             // left <op> &right
@@ -372,7 +372,7 @@ mod tests {
                 $left, $right, FieldData, DataRef, $result, left, right, left $op &right
             ];
             test_field_op![@test_structs
-                $left, $right, FieldData, Framed, $result, left, right, left $op &right
+                $left, $right, FieldData, FramedRef, $result, left, right, left $op &right
             ];
 
             // left <op> right
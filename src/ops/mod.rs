@@ -2,10 +2,21 @@
 Mathematical operations for `DataView` objects.
 */
 
+use frame::Framed;
+use store::DataRef;
+
+/// Alias for the framed field type produced when selecting a field from a `DataView`.
+pub type FramedRef<T> = Framed<T, DataRef<T>>;
+
 #[macro_use]
 mod scalar_op;
-pub use self::scalar_op::*;
 
 #[macro_use]
 mod field_op;
 pub use self::field_op::*;
+
+mod overflow;
+pub use self::overflow::*;
+
+mod div;
+pub use self::div::*;
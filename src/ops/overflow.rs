@@ -0,0 +1,341 @@
+/*!
+Overflow-checked arithmetic operations for integer fields.
+
+The standard arithmetic operators (`+`, `-`, `*`, `/`) on integer fields behave like the
+underlying integer operators: they panic on overflow in debug builds and silently wrap in release
+builds. The [OverflowArith](trait.OverflowArith.html) trait instead applies an
+[OverflowPolicy](trait.OverflowPolicy.html) to each operation which fails, selected using one of
+the policy marker types:
+
+* [ErrorOnOverflow](struct.ErrorOnOverflow.html) -- fail the entire operation with
+  `AgnesError::Overflow`.
+* [Saturate](struct.Saturate.html) -- clamp the result to the bounds of the data type.
+* [WrapToNA](struct.WrapToNA.html) -- replace the result with a missing (NA) value.
+* [PromoteToFloat](struct.PromoteToFloat.html) -- produce a `f64` field, computing results which
+  do not fit in the data type in floating-point.
+
+Integer division by zero is treated the same way as an overflow, except under the `Saturate`
+policy (where there is no meaningful saturated value) which produces a missing (NA) value.
+*/
+use std::fmt::Debug;
+
+use num_traits::AsPrimitive;
+
+use access::DataIndex;
+use error;
+use field::FieldData;
+use value::Value;
+
+/// Arithmetic operations supported by overflow-checked field arithmetic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithOp {
+    /// Addition
+    Add,
+    /// Subtraction
+    Sub,
+    /// Multiplication
+    Mul,
+    /// Division
+    Div,
+}
+
+/// A trait for integer data types which support overflow-checked arithmetic.
+pub trait CheckedArith: Sized + Copy + AsPrimitive<f64> {
+    /// Apply `op` to `self` and `rhs`, returning `None` if the operation overflows or divides by
+    /// zero.
+    fn checked_arith(self, op: ArithOp, rhs: Self) -> Option<Self>;
+    /// Apply `op` to `self` and `rhs`, saturating at the bounds of the data type. Returns `None`
+    /// for division by zero.
+    fn saturating_arith(self, op: ArithOp, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_checked_arith {
+    ($($dtype:ty)*) => {$(
+        impl CheckedArith for $dtype {
+            fn checked_arith(self, op: ArithOp, rhs: $dtype) -> Option<$dtype> {
+                match op {
+                    ArithOp::Add => self.checked_add(rhs),
+                    ArithOp::Sub => self.checked_sub(rhs),
+                    ArithOp::Mul => self.checked_mul(rhs),
+                    ArithOp::Div => self.checked_div(rhs),
+                }
+            }
+            fn saturating_arith(self, op: ArithOp, rhs: $dtype) -> Option<$dtype> {
+                match op {
+                    ArithOp::Add => Some(self.saturating_add(rhs)),
+                    ArithOp::Sub => Some(self.saturating_sub(rhs)),
+                    ArithOp::Mul => Some(self.saturating_mul(rhs)),
+                    ArithOp::Div if rhs == 0 => None,
+                    ArithOp::Div => Some(self.saturating_div(rhs)),
+                }
+            }
+        }
+    )*}
+}
+
+impl_checked_arith![u64 u32 usize i64 i32 isize];
+
+/// A policy for handling arithmetic operations which overflow (or divide by zero).
+pub trait OverflowPolicy<T> {
+    /// The data type of the results of operations using this policy.
+    type Output: Debug + Default;
+
+    /// Apply `op` to `lhs` and `rhs` according to this policy. Returns `None` if the operation
+    /// should fail.
+    fn apply(lhs: T, op: ArithOp, rhs: T) -> Option<Value<Self::Output>>;
+}
+
+/// Overflow policy which fails the operation with `AgnesError::Overflow`.
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorOnOverflow;
+/// Overflow policy which clamps results to the bounds of the data type.
+#[derive(Debug, Clone, Copy)]
+pub struct Saturate;
+/// Overflow policy which replaces results with missing (NA) values.
+#[derive(Debug, Clone, Copy)]
+pub struct WrapToNA;
+/// Overflow policy which produces `f64` results, computing results which do not fit in the data
+/// type in floating-point (so that division by zero results in infinite or NaN values).
+#[derive(Debug, Clone, Copy)]
+pub struct PromoteToFloat;
+
+impl<T> OverflowPolicy<T> for ErrorOnOverflow
+where
+    T: CheckedArith + Debug + Default,
+{
+    type Output = T;
+
+    fn apply(lhs: T, op: ArithOp, rhs: T) -> Option<Value<T>> {
+        lhs.checked_arith(op, rhs).map(Value::Exists)
+    }
+}
+
+impl<T> OverflowPolicy<T> for Saturate
+where
+    T: CheckedArith + Debug + Default,
+{
+    type Output = T;
+
+    fn apply(lhs: T, op: ArithOp, rhs: T) -> Option<Value<T>> {
+        Some(lhs.saturating_arith(op, rhs).into())
+    }
+}
+
+impl<T> OverflowPolicy<T> for WrapToNA
+where
+    T: CheckedArith + Debug + Default,
+{
+    type Output = T;
+
+    fn apply(lhs: T, op: ArithOp, rhs: T) -> Option<Value<T>> {
+        Some(lhs.checked_arith(op, rhs).into())
+    }
+}
+
+impl<T> OverflowPolicy<T> for PromoteToFloat
+where
+    T: CheckedArith,
+{
+    type Output = f64;
+
+    fn apply(lhs: T, op: ArithOp, rhs: T) -> Option<Value<f64>> {
+        let result = lhs
+            .checked_arith(op, rhs)
+            .map(AsPrimitive::as_)
+            .unwrap_or_else(|| {
+                let (lhs, rhs): (f64, f64) = (lhs.as_(), rhs.as_());
+                match op {
+                    ArithOp::Add => lhs + rhs,
+                    ArithOp::Sub => lhs - rhs,
+                    ArithOp::Mul => lhs * rhs,
+                    ArithOp::Div => lhs / rhs,
+                }
+            });
+        Some(Value::Exists(result))
+    }
+}
+
+/// A trait for arithmetic operations on integer fields which handle overflow according to a
+/// specified [OverflowPolicy](trait.OverflowPolicy.html). Missing (NA) values in either operand
+/// result in missing values.
+pub trait OverflowArith: DataIndex + Sized
+where
+    <Self as DataIndex>::DType: CheckedArith,
+{
+    /// Apply `op` to each value of this field and the scalar `rhs`, handling overflow according
+    /// to the policy `P`.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Overflow` if an operation fails under the policy `P`.
+    fn scalar_op_with<P>(
+        &self,
+        op: ArithOp,
+        rhs: Self::DType,
+    ) -> error::Result<FieldData<P::Output>>
+    where
+        P: OverflowPolicy<Self::DType>,
+    {
+        self.iter()
+            .enumerate()
+            .map(|(index, value)| match value {
+                Value::Exists(&lhs) => {
+                    P::apply(lhs, op, rhs).ok_or(error::AgnesError::Overflow { index })
+                }
+                Value::Na => Ok(Value::Na),
+            })
+            .collect()
+    }
+
+    /// Apply `op` to each pair of values of this field and the field `rhs`, handling overflow
+    /// according to the policy `P`.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::LengthMismatch` if the fields are of different lengths, or with
+    /// `AgnesError::Overflow` if an operation fails under the policy `P`.
+    fn field_op_with<P, R>(&self, op: ArithOp, rhs: &R) -> error::Result<FieldData<P::Output>>
+    where
+        P: OverflowPolicy<Self::DType>,
+        R: DataIndex<DType = Self::DType>,
+    {
+        if self.len() != rhs.len() {
+            return Err(error::AgnesError::LengthMismatch {
+                expected: self.len(),
+                actual: rhs.len(),
+            });
+        }
+        self.iter()
+            .zip(rhs.iter())
+            .enumerate()
+            .map(|(index, values)| match values {
+                (Value::Exists(&lhs), Value::Exists(&rhs)) => {
+                    P::apply(lhs, op, rhs).ok_or(error::AgnesError::Overflow { index })
+                }
+                _ => Ok(Value::Na),
+            })
+            .collect()
+    }
+}
+
+impl<DI> OverflowArith for DI
+where
+    DI: DataIndex,
+    DI::DType: CheckedArith,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use error::AgnesError;
+
+    #[test]
+    fn scalar_overflow() {
+        let field = FieldData::from_vec(vec![2u64, u64::MAX - 1, 5]);
+
+        match field.scalar_op_with::<ErrorOnOverflow>(ArithOp::Add, 2) {
+            Err(AgnesError::Overflow { index }) => assert_eq!(index, 1),
+            _ => panic!("expected overflow error"),
+        }
+        assert_eq!(
+            field
+                .scalar_op_with::<ErrorOnOverflow>(ArithOp::Add, 1)
+                .unwrap()
+                .to_vec(),
+            vec![3u64, u64::MAX, 6]
+        );
+        assert_eq!(
+            field
+                .scalar_op_with::<Saturate>(ArithOp::Add, 2)
+                .unwrap()
+                .to_vec(),
+            vec![4u64, u64::MAX, 7]
+        );
+        assert_eq!(
+            field
+                .scalar_op_with::<WrapToNA>(ArithOp::Sub, 3)
+                .unwrap()
+                .to_value_vec(),
+            vec![Value::Na, Value::Exists(u64::MAX - 4), Value::Exists(2)]
+        );
+        assert_eq!(
+            field
+                .scalar_op_with::<PromoteToFloat>(ArithOp::Mul, 2)
+                .unwrap()
+                .to_vec(),
+            vec![4.0, (u64::MAX - 1) as f64 * 2.0, 10.0]
+        );
+    }
+
+    #[test]
+    fn scalar_div_zero() {
+        let field = FieldData::from_field_vec(vec![Value::Exists(3i64), Value::Na]);
+        assert!(field
+            .scalar_op_with::<ErrorOnOverflow>(ArithOp::Div, 0)
+            .is_err());
+        assert_eq!(
+            field
+                .scalar_op_with::<Saturate>(ArithOp::Div, 0)
+                .unwrap()
+                .to_value_vec(),
+            vec![Value::Na, Value::Na]
+        );
+        assert_eq!(
+            field
+                .scalar_op_with::<PromoteToFloat>(ArithOp::Div, 0)
+                .unwrap()
+                .to_value_vec(),
+            vec![Value::Exists(f64::INFINITY), Value::Na]
+        );
+
+        let field = FieldData::from_vec(vec![i64::MIN]);
+        assert_eq!(
+            field
+                .scalar_op_with::<Saturate>(ArithOp::Div, -1)
+                .unwrap()
+                .to_vec(),
+            vec![i64::MAX]
+        );
+    }
+
+    #[test]
+    fn field_overflow() {
+        let left = FieldData::from_field_vec(vec![
+            Value::Exists(i32::MIN),
+            Value::Exists(4),
+            Value::Na,
+            Value::Exists(i32::MAX),
+        ]);
+        let right = FieldData::from_vec(vec![-1i32, 3, 2, 2]);
+
+        assert_eq!(
+            left.field_op_with::<WrapToNA, _>(ArithOp::Mul, &right)
+                .unwrap()
+                .to_value_vec(),
+            vec![Value::Na, Value::Exists(12), Value::Na, Value::Na]
+        );
+        assert_eq!(
+            left.field_op_with::<Saturate, _>(ArithOp::Add, &right)
+                .unwrap()
+                .to_value_vec(),
+            vec![
+                Value::Exists(i32::MIN),
+                Value::Exists(7),
+                Value::Na,
+                Value::Exists(i32::MAX)
+            ]
+        );
+        match left.field_op_with::<ErrorOnOverflow, _>(ArithOp::Add, &right) {
+            Err(AgnesError::Overflow { index }) => assert_eq!(index, 0),
+            _ => panic!("expected overflow error"),
+        }
+
+        let short = FieldData::from_vec(vec![1i32]);
+        match left.field_op_with::<Saturate, _>(ArithOp::Add, &short) {
+            Err(AgnesError::LengthMismatch { expected, actual }) => {
+                assert_eq!((expected, actual), (4, 1))
+            }
+            _ => panic!("expected length mismatch error"),
+        }
+    }
+}
@@ -3,7 +3,7 @@ use std::ops::{Add, Div, Mul, Sub};
 
 use access::{DataIndex, DataIndexMut};
 use field::FieldData;
-use frame::Framed;
+use ops::FramedRef;
 use store::DataRef;
 
 macro_rules! impl_op {
@@ -74,10 +74,10 @@ macro_rules! impl_op {
     )*}
 }
 
-impl_op![Add add; [FieldData<T>] [Framed<T>] [DataRef<T>]];
-impl_op![Sub sub; [FieldData<T>] [Framed<T>] [DataRef<T>]];
-impl_op![Mul mul; [FieldData<T>] [Framed<T>] [DataRef<T>]];
-impl_op![Div div; [FieldData<T>] [Framed<T>] [DataRef<T>]];
+impl_op![Add add; [FieldData<T>] [FramedRef<T>] [DataRef<T>]];
+impl_op![Sub sub; [FieldData<T>] [FramedRef<T>] [DataRef<T>]];
+impl_op![Mul mul; [FieldData<T>] [FramedRef<T>] [DataRef<T>]];
+impl_op![Div div; [FieldData<T>] [FramedRef<T>] [DataRef<T>]];
 
 macro_rules! impl_op_nongeneric {
     ($dtype:ty; $trait_name:tt $trait_fn:tt; $([$($ty_tt:tt)*])*) => {$(
@@ -151,10 +151,10 @@ macro_rules! impl_op_nongeneric {
 
 macro_rules! impl_scalar_ops_nongeneric {
     ($dtype:ty) => {
-        impl_op_nongeneric![$dtype; Add add; [FieldData] [Framed] [DataRef]];
-        impl_op_nongeneric![$dtype; Sub sub; [FieldData] [Framed] [DataRef]];
-        impl_op_nongeneric![$dtype; Mul mul; [FieldData] [Framed] [DataRef]];
-        impl_op_nongeneric![$dtype; Div div; [FieldData] [Framed] [DataRef]];
+        impl_op_nongeneric![$dtype; Add add; [FieldData] [FramedRef] [DataRef]];
+        impl_op_nongeneric![$dtype; Sub sub; [FieldData] [FramedRef] [DataRef]];
+        impl_op_nongeneric![$dtype; Mul mul; [FieldData] [FramedRef] [DataRef]];
+        impl_op_nongeneric![$dtype; Div div; [FieldData] [FramedRef] [DataRef]];
     }
 }
 
@@ -170,7 +170,7 @@ impl_scalar_ops_nongeneric_prims![f64 f32 u64 u32 usize i64 i32 isize];
 mod tests {
     use access::DataIndex;
     use field::FieldData;
-    use frame::Framed;
+    use ops::FramedRef;
     use store::DataRef;
 
     macro_rules! test_op {
@@ -185,7 +185,7 @@ mod tests {
             assert_eq![(&data          $op &$term          ).to_vec(), $expected];
             assert_eq![(&data          $op  $term.clone()  ).to_vec(), $expected];
 
-            let data: Framed<_> = data.into();
+            let data: FramedRef<_> = data.into();
             assert_eq![(&data          $op &$term          ).to_vec(), $expected];
             assert_eq![(&data          $op  $term.clone()  ).to_vec(), $expected];
         }};
@@ -202,7 +202,7 @@ mod tests {
             assert_eq![(&$term          $op &data          ).to_vec(), $expected];
             assert_eq![( $term.clone()  $op &data          ).to_vec(), $expected];
 
-            let data: Framed<_> = data.into();
+            let data: FramedRef<_> = data.into();
             assert_eq![(&$term          $op &data          ).to_vec(), $expected];
             assert_eq![( $term.clone()  $op &data          ).to_vec(), $expected];
         }};
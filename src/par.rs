@@ -0,0 +1,279 @@
+/*!
+Parallel iteration over the values of fields using [rayon](https://docs.rs/rayon). Requires the
+`parallel` feature.
+
+References to fields (`FieldData`, `DataRef`, and `Framed` fields selected from views) implement
+rayon's `IntoParallelIterator`, so fields can be iterated over in parallel with rayon's `par_iter`
+method. Any other [DataIndex](../access/trait.DataIndex.html) can be iterated over in parallel with
+the [par_values](trait.ParDataIndex.html#method.par_values) method of the
+[ParDataIndex](trait.ParDataIndex.html) trait.
+Like [DataIterator](../access/struct.DataIterator.html), these iterators yield a `Value<&T>` for
+each value, with `Value::Na` for missing values, so that per-element transforms can be computed in
+parallel while respecting the field's missing-value mask. Results can be collected back into a
+[FieldData](../field/struct.FieldData.html):
+
+```
+# extern crate agnes;
+# extern crate rayon;
+use agnes::field::FieldData;
+use agnes::value::Value;
+use rayon::prelude::*;
+
+# fn main() {
+let field = FieldData::from_field_vec(vec![Value::Exists(1.5f64), Value::Na, Value::Exists(4.0)]);
+let scaled: FieldData<f64> = field.par_iter().map(|value| value.map(|x| x * 2.0)).collect();
+assert_eq!(
+    scaled.as_vec(),
+    vec![Value::Exists(&3.0), Value::Na, Value::Exists(&8.0)]
+);
+# }
+```
+
+The values of a field are gathered (as a [DataChunk](../access/struct.DataChunk.html)) on the
+calling thread; fields which store their values contiguously are not copied, while other fields
+(e.g. fields of sorted or filtered views) are gathered into a `Vec` of references.
+*/
+
+use std::fmt::Debug;
+
+use rayon::iter::plumbing::{bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer};
+use rayon::iter::{
+    FromParallelIterator, IndexedParallelIterator, IntoParallelIterator, ParallelIterator,
+};
+
+use access::{DataChunk, DataChunkIntoIter, DataIndex};
+use field::FieldData;
+use frame::Framed;
+use store::DataRef;
+use value::Value;
+
+/// Trait providing parallel iteration over the values of a field. Implemented for every
+/// [DataIndex](../access/trait.DataIndex.html).
+pub trait ParDataIndex: DataIndex {
+    /// Returns a parallel iterator over the values in this field.
+    fn par_values(&self) -> ParDataIter<'_, Self::DType>
+    where
+        Self::DType: Sync,
+    {
+        // this cannot fail, since the chunk starts at the beginning of the field
+        ParDataIter::new(self.get_chunk(0, self.len()).unwrap())
+    }
+}
+impl<DI> ParDataIndex for DI where DI: DataIndex {}
+
+/// Parallel iterator over the values of a field, yielding a `Value<&T>` for each value.
+#[derive(Debug, Clone)]
+pub struct ParDataIter<'a, T>
+where
+    T: 'a,
+{
+    chunk: DataChunk<'a, T>,
+}
+
+impl<'a, T> ParDataIter<'a, T>
+where
+    T: 'a,
+{
+    /// Create a new parallel iterator over the values of a `DataChunk`.
+    pub fn new(chunk: DataChunk<'a, T>) -> ParDataIter<'a, T> {
+        ParDataIter { chunk }
+    }
+}
+
+impl<'a, T> ParallelIterator for ParDataIter<'a, T>
+where
+    T: 'a + Sync,
+{
+    type Item = Value<&'a T>;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.chunk.len())
+    }
+}
+
+impl<'a, T> IndexedParallelIterator for ParDataIter<'a, T>
+where
+    T: 'a + Sync,
+{
+    fn len(&self) -> usize {
+        self.chunk.len()
+    }
+
+    fn drive<C>(self, consumer: C) -> C::Result
+    where
+        C: Consumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB>(self, callback: CB) -> CB::Output
+    where
+        CB: ProducerCallback<Self::Item>,
+    {
+        callback.callback(ChunkProducer { chunk: self.chunk })
+    }
+}
+
+// Producer which splits a `DataChunk` for processing on multiple threads.
+struct ChunkProducer<'a, T>
+where
+    T: 'a,
+{
+    chunk: DataChunk<'a, T>,
+}
+
+impl<'a, T> Producer for ChunkProducer<'a, T>
+where
+    T: 'a + Sync,
+{
+    type Item = Value<&'a T>;
+    type IntoIter = DataChunkIntoIter<'a, T>;
+
+    fn into_iter(self) -> DataChunkIntoIter<'a, T> {
+        self.chunk.into_iter()
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let (left, right) = self.chunk.split_at(index);
+        (
+            ChunkProducer { chunk: left },
+            ChunkProducer { chunk: right },
+        )
+    }
+}
+
+impl<'a, T> IntoParallelIterator for DataChunk<'a, T>
+where
+    T: 'a + Sync,
+{
+    type Iter = ParDataIter<'a, T>;
+    type Item = Value<&'a T>;
+
+    fn into_par_iter(self) -> ParDataIter<'a, T> {
+        ParDataIter::new(self)
+    }
+}
+
+macro_rules! impl_into_par_iter {
+    ($($field:ty => $($bound:ident)*;)*) => {$(
+        impl<'a, T, $($bound,)*> IntoParallelIterator for &'a $field
+        where
+            T: 'a + Debug + Sync,
+            $($bound: DataIndex<DType = T> + Debug,)*
+        {
+            type Iter = ParDataIter<'a, T>;
+            type Item = Value<&'a T>;
+
+            fn into_par_iter(self) -> ParDataIter<'a, T> {
+                self.par_values()
+            }
+        }
+    )*}
+}
+impl_into_par_iter![
+    FieldData<T> => ;
+    DataRef<T> => ;
+    Framed<T, DI> => DI;
+];
+
+impl<T> FromParallelIterator<Value<T>> for FieldData<T>
+where
+    T: Debug + Default + Send,
+{
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = Value<T>>,
+    {
+        par_iter
+            .into_par_iter()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rayon::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn par_iter() {
+        let field = (0..10_000u64)
+            .map(|i| {
+                if i % 7 == 0 {
+                    Value::Na
+                } else {
+                    Value::Exists(i)
+                }
+            })
+            .collect::<FieldData<u64>>();
+        let expected = field
+            .iter()
+            .map(|value| value.map_or(0, |&x| x))
+            .sum::<u64>();
+        assert_eq!(
+            field
+                .par_iter()
+                .map(|value| value.map_or(0, |&x| x))
+                .sum::<u64>(),
+            expected
+        );
+        assert_eq!(
+            field.par_values().filter(|value| value.is_na()).count(),
+            1429
+        );
+
+        let doubled: FieldData<u64> = field
+            .par_iter()
+            .map(|value| value.map(|&x| x * 2))
+            .collect();
+        assert_eq!(doubled.len(), field.len());
+        assert_eq!(doubled.get(0), Some(Value::Na));
+        assert_eq!(doubled.get(9_999), Some(Value::Exists(&19_998)));
+
+        // chunks which are not contiguous slices
+        let chunk = DataChunk::from_values(0, field.iter().rev().collect());
+        let values = chunk.into_par_iter().collect::<Vec<_>>();
+        assert_eq!(values, field.iter().rev().collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn par_iter_view() {
+        use select::FieldSelect;
+        use test_utils::*;
+
+        let dv = sample_emp_table()
+            .into_view()
+            .sort_by_label::<emp_table::EmpName>();
+        let names = dv.field::<emp_table::EmpName>();
+        assert_eq!(
+            names.par_iter().collect::<Vec<_>>(),
+            names.iter().collect::<Vec<_>>()
+        );
+        let lens: FieldData<usize> = names.par_iter().map(|name| name.map(String::len)).collect();
+        assert_eq!(lens.len(), 7);
+    }
+
+    #[test]
+    fn par_iter_arena_str() {
+        use field::{ArenaStr, StrFieldData};
+
+        let names = FieldData::<ArenaStr>::from(
+            vec!["Paris", "Oslo", "Lima"]
+                .into_iter()
+                .collect::<StrFieldData>(),
+        );
+        let lens: FieldData<usize> = names.par_iter().map(|name| name.map(|n| n.len())).collect();
+        assert_eq!(lens.to_vec(), vec![5, 4, 4]);
+    }
+}
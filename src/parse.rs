@@ -0,0 +1,264 @@
+/*!
+Parsing of text fields into typed fields.
+
+Fields which are loaded as text (e.g. a numeric CSV column with a single stray non-numeric value,
+declared as `String` so that loading succeeds) can be converted into typed fields using the
+[ParseValues](trait.ParseValues.html) trait or the
+[DataView::parse](../view/struct.DataView.html#method.parse) method. Any type implementing
+`FromStr` whose parse error converts into a [ParseError](../error/enum.ParseError.html) (integer,
+floating-point, and boolean types, `String`, and the `chrono` date and time types such as
+`NaiveDate`, `NaiveDateTime`, and `DateTime<FixedOffset>`, in their ISO 8601 / RFC 3339 formats)
+can be parsed.
+
+As when loading CSV files, leading and trailing whitespace is ignored and empty values become
+missing (NA) values. The [ParseMode](enum.ParseMode.html) determines how values which fail to parse
+are handled.
+*/
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use access::DataIndex;
+use error::{self, ParseError};
+use field::FieldData;
+use frame::IntoFrame;
+use join::AddFrame;
+use select::{FieldSelect, SelectFieldByLabel};
+use store::IntoStore;
+use value::Value;
+use view::{DataView, VFieldTypeOf};
+
+/// Option for handling values which fail to parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Fail the entire operation with `AgnesError::ParseValue`, reporting the index of the first
+    /// value which fails to parse.
+    #[default]
+    Strict,
+    /// Replace values which fail to parse with missing (NA) values.
+    ToNA,
+}
+
+/// A trait for parsing the values of a text field into a different type.
+pub trait ParseValues {
+    /// Returns a new field with the values of this field parsed into type `T`, handling values
+    /// which fail to parse according to `mode`. Missing (NA), empty, and whitespace-only values
+    /// become missing values.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::ParseValue` (reporting the index of the offending value) if `mode`
+    /// is `ParseMode::Strict` and a value fails to parse.
+    fn parse_values<T>(&self, mode: ParseMode) -> error::Result<FieldData<T>>
+    where
+        T: FromStr + Debug + Default,
+        ParseError: From<<T as FromStr>::Err>;
+}
+
+impl<DI> ParseValues for DI
+where
+    DI: DataIndex,
+    DI::DType: AsRef<str>,
+{
+    fn parse_values<T>(&self, mode: ParseMode) -> error::Result<FieldData<T>>
+    where
+        T: FromStr + Debug + Default,
+        ParseError: From<<T as FromStr>::Err>,
+    {
+        self.iter()
+            .enumerate()
+            .map(|(index, value)| {
+                let trimmed = match value {
+                    Value::Exists(text) => text.as_ref().trim(),
+                    Value::Na => "",
+                };
+                if trimmed.is_empty() {
+                    return Ok(Value::Na);
+                }
+                match (trimmed.parse::<T>(), mode) {
+                    (Ok(value), _) => Ok(Value::Exists(value)),
+                    (Err(_), ParseMode::ToNA) => Ok(Value::Na),
+                    (Err(err), ParseMode::Strict) => Err(error::AgnesError::ParseValue {
+                        index,
+                        err: err.into(),
+                    }),
+                }
+            })
+            .collect()
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Creates a new `DataView` with an additional field labeled `DstLabel`, containing the values
+    /// of the text field labeled `SrcLabel` parsed into type `DType`. Values which fail to parse
+    /// are handled according to `mode`. See the [parse](../parse/index.html) module for more
+    /// details.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::ParseValue` (reporting the index of the offending record) if `mode`
+    /// is `ParseMode::Strict` and a value fails to parse.
+    pub fn parse<SrcLabel, DType, DstLabel>(
+        &self,
+        mode: ParseMode,
+    ) -> error::Result<<Self as Parse<SrcLabel, DType, DstLabel>>::Output>
+    where
+        Self: Parse<SrcLabel, DType, DstLabel>,
+    {
+        Parse::<SrcLabel, DType, DstLabel>::parse(self, mode)
+    }
+}
+
+/// Trait providing the `parse` method for adding a field parsed from a text field to a
+/// `DataView`. See the intrinsic method [parse](../view/struct.DataView.html#method.parse) for
+/// more details.
+pub trait Parse<SrcLabel, DType, DstLabel> {
+    /// Type produced by this method.
+    type Output;
+
+    /// Add the parsed field. See the intrinsic method
+    /// [parse](../view/struct.DataView.html#method.parse) for more details.
+    fn parse(&self, mode: ParseMode) -> error::Result<Self::Output>;
+}
+
+/// Type of the frame added by [Parse](trait.Parse.html).
+pub type ParsedFrameOf<DType, DstLabel> =
+    <<FieldData<DType> as IntoStore<DstLabel>>::Output as IntoFrame>::Output;
+
+impl<Labels, Frames, SrcLabel, DType, DstLabel> Parse<SrcLabel, DType, DstLabel>
+    for DataView<Labels, Frames>
+where
+    Self: SelectFieldByLabel<SrcLabel> + AddFrame<ParsedFrameOf<DType, DstLabel>>,
+    VFieldTypeOf<Self, SrcLabel>: AsRef<str>,
+    DType: FromStr + Debug + Default,
+    ParseError: From<<DType as FromStr>::Err>,
+    FieldData<DType>: IntoStore<DstLabel>,
+    <FieldData<DType> as IntoStore<DstLabel>>::Output: IntoFrame,
+{
+    type Output = <Self as AddFrame<ParsedFrameOf<DType, DstLabel>>>::Output;
+
+    fn parse(&self, mode: ParseMode) -> error::Result<Self::Output> {
+        let parsed = self.field::<SrcLabel>().parse_values::<DType>(mode)?;
+        Ok(self.add_frame(IntoStore::<DstLabel>::into_store(parsed).into_frame()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cons::Nil;
+    use store::DataStore;
+
+    tablespace![
+        pub table raw {
+            Text: String,
+            Count: u64,
+            Ratio: f64,
+            Flag: bool,
+        }
+    ];
+
+    fn sample_text(values: &[&str]) -> FieldData<String> {
+        FieldData::<String>::from_vec(values.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn parse_values() {
+        let field = sample_text(&["3", " 12 ", "", "x"]);
+        assert_eq!(
+            field
+                .parse_values::<u64>(ParseMode::ToNA)
+                .unwrap()
+                .to_value_vec(),
+            vec![Value::Exists(3), Value::Exists(12), Value::Na, Value::Na]
+        );
+        match field.parse_values::<u64>(ParseMode::Strict) {
+            Err(error::AgnesError::ParseValue {
+                index,
+                err: ParseError::Int(_),
+            }) => assert_eq!(index, 3),
+            other => panic!("expected parse error at index 3, got {:?}", other),
+        }
+
+        let field = sample_text(&["true", "false"]);
+        assert_eq!(
+            field
+                .parse_values::<bool>(ParseMode::Strict)
+                .unwrap()
+                .to_vec(),
+            vec![true, false]
+        );
+    }
+
+    #[test]
+    fn parse_datetimes() {
+        use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
+
+        let field = sample_text(&["2019-03-01", "", "2019-02-30"]);
+        assert_eq!(
+            field
+                .parse_values::<NaiveDate>(ParseMode::ToNA)
+                .unwrap()
+                .to_value_vec(),
+            vec![
+                Value::Exists(NaiveDate::from_ymd_opt(2019, 3, 1).unwrap()),
+                Value::Na,
+                Value::Na
+            ]
+        );
+        match field.parse_values::<NaiveDate>(ParseMode::Strict) {
+            Err(error::AgnesError::ParseValue {
+                index,
+                err: ParseError::DateTime(_),
+            }) => assert_eq!(index, 2),
+            other => panic!("expected parse error at index 2, got {:?}", other),
+        }
+
+        let field = sample_text(&["2019-03-01T12:30:00", " 2019-03-02T08:00:15.5 "]);
+        let expected = NaiveDate::from_ymd_opt(2019, 3, 2)
+            .unwrap()
+            .and_hms_milli_opt(8, 0, 15, 500)
+            .unwrap();
+        assert_eq!(
+            field
+                .parse_values::<NaiveDateTime>(ParseMode::Strict)
+                .unwrap()
+                .get_datum(1)
+                .unwrap(),
+            Value::Exists(&expected)
+        );
+
+        let field = sample_text(&["2019-03-01T12:30:00+02:00"]);
+        let parsed = field
+            .parse_values::<DateTime<FixedOffset>>(ParseMode::Strict)
+            .unwrap();
+        assert_eq!(
+            parsed.get_datum(0).unwrap().map(|dt| dt.naive_utc()),
+            Value::Exists(
+                NaiveDate::from_ymd_opt(2019, 3, 1)
+                    .unwrap()
+                    .and_hms_opt(10, 30, 0)
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_view() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<raw::Text, _>(sample_text(&["1.5", "2", "n/a"]))
+            .into_view();
+        let dv = dv
+            .parse::<raw::Text, f64, raw::Ratio>(ParseMode::ToNA)
+            .unwrap();
+        assert_eq!(dv.fieldnames(), vec!["Text", "Ratio"]);
+        assert_eq!(
+            dv.field::<raw::Ratio>().to_value_vec(),
+            vec![Value::Exists(1.5), Value::Exists(2.0), Value::Na]
+        );
+        assert!(dv
+            .parse::<raw::Text, u64, raw::Count>(ParseMode::Strict)
+            .is_err());
+        assert!(dv
+            .parse::<raw::Text, bool, raw::Flag>(ParseMode::Strict)
+            .is_err());
+    }
+}
@@ -126,6 +126,40 @@ pub trait FuncDefault {
     fn call(&mut self) -> Self::Output;
 }
 
+/// Trait for calling a partially-implemented function on a single field with data type `DType`.
+/// Calls [Func::call](trait.Func.html#tymethod.call) if `IsImpl` is
+/// [Implemented](struct.Implemented.html), or
+/// [FuncDefault::call](trait.FuncDefault.html#tymethod.call) if `IsImpl` is
+/// [Unimplemented](struct.Unimplemented.html), discarding the output.
+pub trait PartialCall<DType, IsImpl> {
+    /// Call this function on the field `data`.
+    fn partial_call<DI>(&mut self, data: &DI)
+    where
+        DI: DataIndex<DType = DType>;
+}
+impl<DType, F> PartialCall<DType, Implemented> for F
+where
+    F: Func<DType>,
+{
+    fn partial_call<DI>(&mut self, data: &DI)
+    where
+        DI: DataIndex<DType = DType>,
+    {
+        self.call(data);
+    }
+}
+impl<DType, F> PartialCall<DType, Unimplemented> for F
+where
+    F: FuncDefault,
+{
+    fn partial_call<DI>(&mut self, _data: &DI)
+    where
+        DI: DataIndex<DType = DType>,
+    {
+        self.call();
+    }
+}
+
 /// Trait that augments a [DataIndexCons](../view/type.DataIndexCons.html) (a cons-list of
 /// field access structs) with partial-function capability information as specified by
 /// [IsImplemented](trait.IsImplemented.html) definitions.
@@ -8,9 +8,12 @@ included) or filtering (where a strict subset of the indices are included) of th
 This module also contains traits and methods for sorting data sets.
 */
 use std::cmp::Ordering;
+use std::rc::Rc;
 
 use access::DataIndex;
 use cons::Nil;
+use memory::HeapSize;
+use observer::{Observer, Reporter, Stage, REPORT_INTERVAL};
 use value::Value;
 
 /// A structure containing information about the permutation status of a field. `I` represents the
@@ -38,21 +41,43 @@ impl From<Vec<usize>> for Permutation<Vec<usize>> {
 impl Permutation<Vec<usize>> {
     /// Consumes this `Permutation` and returns a new `Permutation` with new values from
     /// `new_permutation`.
-    pub fn update_indices(mut self, new_permutation: &[usize]) -> Permutation<Vec<usize>> {
+    pub fn update_indices(self, new_permutation: &[usize]) -> Permutation<Vec<usize>> {
+        self.compose(new_permutation)
+    }
+
+    /// Returns a new `Permutation` which applies `new_permutation` after this permutation, as a
+    /// single index array (so that accessing an element through the new permutation requires only
+    /// one lookup).
+    pub fn compose(&self, new_permutation: &[usize]) -> Permutation<Vec<usize>> {
         // check if we already have a permutation
-        self.perm = match self.perm {
+        let perm = match self.perm {
             Some(ref prev_perm) => {
                 // we already have a permutation, map the filter indices through it
-                Some(
-                    new_permutation
-                        .iter()
-                        .map(|&new_idx| prev_perm[new_idx])
-                        .collect(),
-                )
+                new_permutation
+                    .iter()
+                    .map(|&new_idx| prev_perm[new_idx])
+                    .collect()
             }
-            None => Some(new_permutation.iter().map(|&idx| idx).collect()),
+            None => new_permutation.to_vec(),
         };
-        self
+        Permutation { perm: Some(perm) }
+    }
+
+    // Consumes this `Permutation`, returning the permutation indices (if a permutation exists).
+    pub(crate) fn into_vec(self) -> Option<Vec<usize>> {
+        self.perm
+    }
+
+    /// Returns whether this permutation leaves all `len` elements of the underlying data in their
+    /// original order (either because no permutation exists, or because the permutation is the
+    /// identity), in which case it can be dropped.
+    pub fn is_identity(&self, len: usize) -> bool {
+        match self.perm {
+            Some(ref perm) => {
+                perm.len() == len && perm.iter().enumerate().all(|(i, &idx)| i == idx)
+            }
+            None => true,
+        }
     }
 }
 
@@ -73,6 +98,11 @@ macro_rules! impl_permutation_len {
             }
             /// Returns whether or not a permutation actually exists.
             pub fn is_permuted(&self) -> bool { self.perm.is_some() }
+            /// Returns the permutation indices, if a permutation exists.
+            pub fn as_slice(&self) -> Option<&[usize]>
+            {
+                self.perm.as_ref().map(|perm| &perm[..])
+            }
         }
     )*}
 }
@@ -90,6 +120,39 @@ impl UpdatePermutation for Nil {
     }
 }
 
+/// Trait for simplifying the permutations of a data structure's frames to speed up subsequent
+/// access (see [DataView::optimize_access](../view/struct.DataView.html#method.optimize_access)).
+pub trait OptimizeAccess {
+    /// Consumes this object and returns an equivalent object with identity permutations removed,
+    /// and with frames whose permutations are equal to one in `shared` (or to one another) sharing
+    /// a single permutation. Newly-encountered permutations are added to `shared`.
+    fn optimize_access(self, shared: &mut Vec<Rc<Permutation<Vec<usize>>>>) -> Self;
+}
+impl OptimizeAccess for Nil {
+    fn optimize_access(self, _shared: &mut Vec<Rc<Permutation<Vec<usize>>>>) -> Nil {
+        Nil
+    }
+}
+
+impl HeapSize for Permutation<Vec<usize>> {
+    fn heap_size(&self) -> usize {
+        self.perm
+            .as_ref()
+            .map_or(0, |perm| perm.capacity() * ::std::mem::size_of::<usize>())
+    }
+}
+
+/// Trait for collecting the permutations of the frames of a data structure. Since reordering or
+/// filtering records gives each frame a new permutation, the permutations identify the records
+/// (and record order) that the frames present.
+pub trait FramePermutations {
+    /// Adds the permutation of each frame of this object to `perms`.
+    fn frame_permutations(&self, perms: &mut Vec<Rc<Permutation<Vec<usize>>>>);
+}
+impl FramePermutations for Nil {
+    fn frame_permutations(&self, _perms: &mut Vec<Rc<Permutation<Vec<usize>>>>) {}
+}
+
 /// Trait providing function to compute and return the sorted permutation order. This sort is stable
 /// (preserves original order of equal elements).
 pub trait SortOrder {
@@ -139,6 +202,73 @@ where
     }
 }
 
+// Computes the sorted permutation order of `field` (stably if `stable` is set), reporting progress
+// to `observer` with stage `Stage::Sort`. Runs of `REPORT_INTERVAL` indices are sorted first, then
+// merged pairwise until a single run remains; progress is reported after each sorted run and each
+// merge, so the reported total is the number of values times the number of passes over them.
+pub(crate) fn sort_order_observed<DI>(
+    field: &DI,
+    stable: bool,
+    observer: &mut dyn Observer,
+) -> Vec<usize>
+where
+    DI: DataIndex,
+    <DI as DataIndex>::DType: Ord,
+{
+    let len = field.len();
+    let mut passes = 1;
+    let mut width = REPORT_INTERVAL;
+    while width < len {
+        passes += 1;
+        width *= 2;
+    }
+    let mut reporter = Reporter::new(observer, Stage::Sort, Some(len * passes));
+    // a, b are always in range, so unwraps are safe
+    let compare = |&left: &usize, &right: &usize| {
+        field
+            .get_datum(left)
+            .unwrap()
+            .cmp(&field.get_datum(right).unwrap())
+    };
+    // sorting cannot be cancelled, so the cancellation checks of the reporter are ignored
+    let mut order = (0..len).collect::<Vec<_>>();
+    for run in order.chunks_mut(REPORT_INTERVAL) {
+        if stable {
+            run.sort_by(&compare);
+        } else {
+            run.sort_unstable_by(&compare);
+        }
+        let _ = reporter.advance(run.len());
+    }
+    let mut width = REPORT_INTERVAL;
+    let mut merged = Vec::with_capacity(len);
+    while width < len {
+        merged.clear();
+        for start in (0..len).step_by(2 * width) {
+            let mid = (start + width).min(len);
+            let end = (start + 2 * width).min(len);
+            let (mut left, mut right) = (start, mid);
+            // equal values are taken from the left run first, keeping the merge stable
+            while left < mid && right < end {
+                if compare(&order[left], &order[right]) == Ordering::Greater {
+                    merged.push(order[right]);
+                    right += 1;
+                } else {
+                    merged.push(order[left]);
+                    left += 1;
+                }
+            }
+            merged.extend_from_slice(&order[left..mid]);
+            merged.extend_from_slice(&order[right..end]);
+            let _ = reporter.advance(end - start);
+        }
+        ::std::mem::swap(&mut order, &mut merged);
+        width *= 2;
+    }
+    reporter.finish();
+    order
+}
+
 /// Trait providing function to compute and return the sorted permutation order using a comparator.
 /// This sort is stable (preserves original order of equal elements).
 pub trait SortOrderComparator<F> {
@@ -188,6 +318,84 @@ where
     }
 }
 
+/// Trait providing functions to compute the permutation order of the `n` largest or smallest
+/// values, using a partial selection of the values rather than sorting them all. Missing (NA)
+/// values are never selected.
+pub trait TopOrder {
+    /// Returns the indices of the (up to) `n` largest existing values, in descending order of
+    /// value. Equal values are kept in their original order.
+    fn top_order(&self, n: usize) -> Vec<usize>;
+    /// Returns the indices of the (up to) `n` smallest existing values, in ascending order of
+    /// value. Equal values are kept in their original order.
+    fn bottom_order(&self, n: usize) -> Vec<usize>;
+}
+
+impl<DI> TopOrder for DI
+where
+    DI: DataIndex,
+    <DI as DataIndex>::DType: Ord,
+{
+    fn top_order(&self, n: usize) -> Vec<usize> {
+        select_order(self, n, |left, right| right.cmp(&left))
+    }
+    fn bottom_order(&self, n: usize) -> Vec<usize> {
+        select_order(self, n, |left, right| left.cmp(&right))
+    }
+}
+
+/// Trait providing functions to compute the permutation order of the `n` largest or smallest
+/// values using a comparator, using a partial selection of the values rather than sorting them
+/// all. Missing (NA) values are never selected.
+pub trait TopOrderComparator<F> {
+    /// Returns the indices of the (up to) `n` largest existing values (according to `compare`), in
+    /// descending order of value. Equal values are kept in their original order.
+    fn top_order_by(&self, n: usize, compare: F) -> Vec<usize>;
+    /// Returns the indices of the (up to) `n` smallest existing values (according to `compare`),
+    /// in ascending order of value. Equal values are kept in their original order.
+    fn bottom_order_by(&self, n: usize, compare: F) -> Vec<usize>;
+}
+
+impl<DI, F> TopOrderComparator<F> for DI
+where
+    DI: DataIndex,
+    F: FnMut(Value<&DI::DType>, Value<&DI::DType>) -> Ordering,
+{
+    fn top_order_by(&self, n: usize, mut compare: F) -> Vec<usize> {
+        select_order(self, n, |left, right| compare(right, left))
+    }
+    fn bottom_order_by(&self, n: usize, compare: F) -> Vec<usize> {
+        select_order(self, n, compare)
+    }
+}
+
+// Selects the indices of the `n` existing values of `data` which come first according to
+// `compare` (breaking ties by index), and returns them in that order.
+fn select_order<DI, F>(data: &DI, n: usize, mut compare: F) -> Vec<usize>
+where
+    DI: DataIndex + ?Sized,
+    F: FnMut(Value<&DI::DType>, Value<&DI::DType>) -> Ordering,
+{
+    if n == 0 {
+        return vec![];
+    }
+    let mut order = (0..data.len())
+        .filter(|&idx| data.get_datum(idx).unwrap().exists())
+        .collect::<Vec<_>>();
+    let mut compare_indices = |left: &usize, right: &usize| {
+        compare(
+            data.get_datum(*left).unwrap(),
+            data.get_datum(*right).unwrap(),
+        )
+        .then(left.cmp(right))
+    };
+    if n < order.len() {
+        order.select_nth_unstable_by(n - 1, &mut compare_indices);
+        order.truncate(n);
+    }
+    order.sort_unstable_by(compare_indices);
+    order
+}
+
 /// Helper sorting method for floating-point (f32) values
 pub fn sort_f32(left: &f32, right: &f32) -> Ordering {
     left.partial_cmp(&right).unwrap_or_else(|| {
@@ -319,4 +527,43 @@ mod tests {
         let sorted_order = field_data.sort_order_by(sort_f64_values);
         assert_eq!(sorted_order, vec![2, 1, 0, 4, 3]);
     }
+
+    #[test]
+    fn top_order() {
+        let field_data = FieldData::from_field_vec(vec![
+            Value::Exists(2u64),
+            Value::Exists(5),
+            Value::Na,
+            Value::Exists(1),
+            Value::Exists(5),
+            Value::Exists(3),
+        ]);
+        assert_eq!(field_data.top_order(3), vec![1, 4, 5]);
+        assert_eq!(field_data.bottom_order(2), vec![3, 0]);
+        assert_eq!(field_data.top_order(10), vec![1, 4, 5, 0, 3]);
+        assert_eq!(field_data.bottom_order(0), Vec::<usize>::new());
+
+        let field_data: FieldData<f64> =
+            FieldData::from_vec(vec![2.0, 5.4, ::std::f64::NAN, 1.1, 8.2]);
+        assert_eq!(field_data.top_order_by(2, sort_f64_values), vec![4, 1]);
+        assert_eq!(field_data.bottom_order_by(2, sort_f64_values), vec![2, 3]);
+    }
+
+    #[test]
+    fn compose() {
+        let perm = Permutation::default();
+        assert!(perm.is_identity(4));
+        let perm = perm.compose(&[3, 1, 2, 0]);
+        assert_eq!(perm.map_index(0), 3);
+        assert!(!perm.is_identity(4));
+        // composing a permutation with its inverse gives the identity
+        let restored = perm.compose(&[3, 1, 2, 0]);
+        assert!(restored.is_identity(4));
+        assert!(!restored.is_identity(5));
+        // filtered permutations are never the identity
+        let filtered = restored.compose(&[0, 2]);
+        assert!(!filtered.is_identity(4));
+        assert_eq!(filtered.len(), Some(2));
+        assert_eq!(filtered.update_indices(&[1]).map_index(0), 2);
+    }
 }
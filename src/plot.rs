@@ -0,0 +1,318 @@
+/*!
+Adapters for producing basic exploratory charts from fields. Requires the `plot` feature.
+
+The [Series](trait.Series.html) trait and the [xy_series](fn.xy_series.html) function (or the
+[DataView::xy_series](../view/struct.DataView.html#method.xy_series) method) convert one or two
+fields into vectors of coordinate pairs, the form accepted by the series types of plotting
+libraries such as [plotters](https://crates.io/crates/plotters) (e.g. `LineSeries::new(points,
+&RED)`). For quick looks at data directly in the terminal, the
+[ToHistogram](trait.ToHistogram.html) and [Sparkline](trait.Sparkline.html) traits provide
+simple text-based charts.
+*/
+use std::fmt;
+
+use num_traits::AsPrimitive;
+
+use access::DataIndex;
+use select::{FieldSelect, SelectFieldByLabel};
+use value::Value;
+use view::{DataView, VFieldTypeOf};
+
+/// Width (in characters) of the longest bar in a displayed [Histogram](struct.Histogram.html).
+const HISTOGRAM_WIDTH: usize = 40;
+/// Characters used for rendering sparklines, from lowest to highest.
+const SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// A trait for converting a field into a series of `(index, value)` coordinate pairs.
+pub trait Series {
+    /// The data type of the series values.
+    type DType;
+    /// Returns the `(index, value)` pairs for each existing value in this field. Missing (NA)
+    /// values are skipped.
+    fn series(&self) -> Vec<(usize, Self::DType)>;
+}
+
+impl<DI> Series for DI
+where
+    DI: DataIndex,
+    DI::DType: Clone,
+{
+    type DType = DI::DType;
+
+    fn series(&self) -> Vec<(usize, DI::DType)> {
+        self.iter()
+            .enumerate()
+            .filter_map(|(idx, value)| match value {
+                Value::Exists(value) => Some((idx, value.clone())),
+                Value::Na => None,
+            })
+            .collect()
+    }
+}
+
+/// Returns the `(x, y)` coordinate pairs formed by the values of the fields `x` and `y` at each
+/// index. Records where either value is missing (NA) are skipped.
+pub fn xy_series<X, Y>(x: &X, y: &Y) -> Vec<(X::DType, Y::DType)>
+where
+    X: DataIndex,
+    Y: DataIndex,
+    X::DType: Clone,
+    Y::DType: Clone,
+{
+    x.iter()
+        .zip(y.iter())
+        .filter_map(|(x, y)| match (x, y) {
+            (Value::Exists(x), Value::Exists(y)) => Some((x.clone(), y.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Returns the `(x, y)` coordinate pairs formed by the values of the fields labeled `XLabel`
+    /// and `YLabel` in each record of this `DataView`. Records where either value is missing (NA)
+    /// are skipped. See [xy_series](../plot/fn.xy_series.html).
+    pub fn xy_series<XLabel, YLabel>(
+        &self,
+    ) -> Vec<(VFieldTypeOf<Self, XLabel>, VFieldTypeOf<Self, YLabel>)>
+    where
+        Self: SelectFieldByLabel<XLabel> + SelectFieldByLabel<YLabel>,
+        VFieldTypeOf<Self, XLabel>: Clone,
+        VFieldTypeOf<Self, YLabel>: Clone,
+    {
+        xy_series(&self.field::<XLabel>(), &self.field::<YLabel>())
+    }
+}
+
+/// A single bin of a [Histogram](struct.Histogram.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bin {
+    /// Lower bound of the bin (inclusive).
+    pub lower: f64,
+    /// Upper bound of the bin (exclusive, except for the last bin of a histogram).
+    pub upper: f64,
+    /// Number of values falling within this bin.
+    pub count: usize,
+}
+
+/// A histogram of the values in a numeric field, with equal-width bins spanning the range of
+/// values. Implements `Display` for rendering as a simple text-based bar chart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    bins: Vec<Bin>,
+}
+
+impl Histogram {
+    /// Returns the bins of this histogram.
+    pub fn bins(&self) -> &[Bin] {
+        &self.bins
+    }
+}
+
+impl fmt::Display for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let max_count = self.bins.iter().map(|bin| bin.count).max().unwrap_or(0);
+        let labels = self
+            .bins
+            .iter()
+            .enumerate()
+            .map(|(i, bin)| {
+                // the last bin includes its upper bound
+                let close = if i + 1 == self.bins.len() { ']' } else { ')' };
+                format!("[{}, {}{}", bin.lower, bin.upper, close)
+            })
+            .collect::<Vec<_>>();
+        let label_width = labels.iter().map(|label| label.len()).max().unwrap_or(0);
+        for (bin, label) in self.bins.iter().zip(&labels) {
+            let width = (bin.count * HISTOGRAM_WIDTH)
+                .checked_div(max_count)
+                .unwrap_or(0);
+            writeln!(
+                f,
+                "{:>lwidth$} {} {}",
+                label,
+                "█".repeat(width),
+                bin.count,
+                lwidth = label_width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A trait for computing a [Histogram](struct.Histogram.html) of the values in a numeric field.
+pub trait ToHistogram {
+    /// Returns a histogram of the existing values in this field, using `n_bins` equal-width bins.
+    /// Missing (NA) and NaN values are skipped.
+    ///
+    /// # Panics
+    /// Panics if `n_bins` is 0.
+    fn histogram(&self, n_bins: usize) -> Histogram;
+}
+
+impl<DI> ToHistogram for DI
+where
+    DI: DataIndex,
+    DI::DType: AsPrimitive<f64>,
+{
+    fn histogram(&self, n_bins: usize) -> Histogram {
+        assert!(n_bins > 0, "histogram must have at least one bin");
+        let values = finite_values(self);
+        let (min, max) = value_range(&values).unwrap_or((0.0, 0.0));
+        let width = (max - min) / n_bins as f64;
+        let mut bins = (0..n_bins)
+            .map(|i| Bin {
+                lower: min + width * i as f64,
+                upper: if i == n_bins - 1 {
+                    max
+                } else {
+                    min + width * (i + 1) as f64
+                },
+                count: 0,
+            })
+            .collect::<Vec<_>>();
+        for value in values {
+            let bin = if width > 0.0 {
+                (((value - min) / width) as usize).min(n_bins - 1)
+            } else {
+                0
+            };
+            bins[bin].count += 1;
+        }
+        Histogram { bins }
+    }
+}
+
+/// A trait for rendering the values of a numeric field as a sparkline (a small inline chart made
+/// of block characters).
+pub trait Sparkline {
+    /// Returns a sparkline with one character per value of this field, scaled between the minimum
+    /// and maximum existing values. Missing (NA) and NaN values are rendered as spaces.
+    fn sparkline(&self) -> String;
+}
+
+impl<DI> Sparkline for DI
+where
+    DI: DataIndex,
+    DI::DType: AsPrimitive<f64>,
+{
+    fn sparkline(&self) -> String {
+        let (min, max) = value_range(&finite_values(self)).unwrap_or((0.0, 0.0));
+        let top = (SPARK_CHARS.len() - 1) as f64;
+        self.iter()
+            .map(|value| match value {
+                Value::Exists(value) => {
+                    let value: f64 = value.as_();
+                    if !value.is_finite() {
+                        ' '
+                    } else if max > min {
+                        SPARK_CHARS[((value - min) / (max - min) * top).round() as usize]
+                    } else {
+                        SPARK_CHARS[0]
+                    }
+                }
+                Value::Na => ' ',
+            })
+            .collect()
+    }
+}
+
+// Returns the existing, finite values of a field as `f64`s.
+fn finite_values<DI>(data: &DI) -> Vec<f64>
+where
+    DI: DataIndex,
+    DI::DType: AsPrimitive<f64>,
+{
+    data.iter()
+        .filter_map(|value| match value {
+            Value::Exists(value) => Some(value.as_()),
+            Value::Na => None,
+        })
+        .filter(|value: &f64| value.is_finite())
+        .collect()
+}
+
+// Returns the minimum and maximum of a list of values, or `None` if empty.
+fn value_range(values: &[f64]) -> Option<(f64, f64)> {
+    values.iter().fold(None, |range, &value| match range {
+        Some((min, max)) => Some((value.min(min), value.max(max))),
+        None => Some((value, value)),
+    })
+}
+
+#[cfg(feature = "test-utils")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use field::FieldData;
+    use test_utils::*;
+
+    #[test]
+    fn series() {
+        let field =
+            FieldData::from_field_vec(vec![Value::Exists(3u64), Value::Na, Value::Exists(5)]);
+        assert_eq!(field.series(), vec![(0, 3), (2, 5)]);
+
+        let other = FieldData::from_field_vec(vec![
+            Value::Exists(0.5f64),
+            Value::Exists(1.5),
+            Value::Exists(2.5),
+        ]);
+        assert_eq!(xy_series(&field, &other), vec![(3, 0.5), (5, 2.5)]);
+
+        let dv = sample_emp_table().into_view();
+        let pairs = dv.xy_series::<emp_table::EmpId, emp_table::DeptId>();
+        assert_eq!(pairs[..3], [(0, 1), (2, 2), (5, 1)]);
+    }
+
+    #[test]
+    fn histogram() {
+        let field = FieldData::from_field_vec(vec![
+            Value::Exists(0.0f64),
+            Value::Exists(1.0),
+            Value::Na,
+            Value::Exists(2.5),
+            Value::Exists(4.0),
+            Value::Exists(f64::NAN),
+        ]);
+        let hist = field.histogram(2);
+        assert_eq!(
+            hist.bins(),
+            &[
+                Bin {
+                    lower: 0.0,
+                    upper: 2.0,
+                    count: 2
+                },
+                Bin {
+                    lower: 2.0,
+                    upper: 4.0,
+                    count: 2
+                }
+            ]
+        );
+        assert_eq!(
+            hist.to_string(),
+            format!("[0, 2) {} 2\n[2, 4] {} 2\n", "█".repeat(40), "█".repeat(40))
+        );
+
+        let field: FieldData<u64> = FieldData::from_vec(vec![3u64, 3]);
+        assert_eq!(field.histogram(3).bins()[0].count, 2);
+        let field: FieldData<u64> = FieldData::from_vec(Vec::<u64>::new());
+        assert_eq!(field.histogram(1).bins()[0].count, 0);
+    }
+
+    #[test]
+    fn sparkline() {
+        let field = FieldData::from_field_vec(vec![
+            Value::Exists(0u64),
+            Value::Exists(7),
+            Value::Na,
+            Value::Exists(14),
+        ]);
+        assert_eq!(field.sparkline(), "▁▅ █");
+        let field: FieldData<u64> = FieldData::from_vec(vec![2u64, 2]);
+        assert_eq!(field.sparkline(), "▁▁");
+    }
+}
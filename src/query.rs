@@ -0,0 +1,714 @@
+/*!
+String-based boolean filter expressions.
+
+Filtering with [DataView::filter](../view/struct.DataView.html#method.filter) requires a field
+label and a Rust closure for each filter, which is inconvenient for interactive or command-line
+use. The [DataView::query](../view/struct.DataView.html#method.query) method instead filters
+records using an expression parsed at runtime, with fields referred to by name:
+
+```text
+salary > 1000 && (dept == 'Sales' || !active)
+```
+
+Expressions consist of:
+
+* comparisons between a field and a literal value, using `==`, `!=`, `<`, `<=`, `>`, or `>=`
+  (the field may be on either side);
+* bare `bool` field names, which match records where the field is `true`;
+* the logical operators `&&`, `||`, and `!`, and parentheses for grouping.
+
+Field names which are not valid identifiers may be quoted with backticks (e.g. `` `Emp Name` ``).
+Literals are numbers (e.g. `42`, `-1.5e3`), strings quoted with single or double quotes, or `true`
+and `false`. Numeric fields (of any integer or floating-point type) are compared as `f64`s.
+Comparisons involving missing (NA) values are neither true nor false, but missing: `!` leaves them
+missing, `&&` and `||` treat them as unknown (so `false && x` is false and `true || x` is true,
+while `true && x` and `false || x` are missing if `x` is), and only records for which the entire
+expression is true match. For example, `!(salary > 1000)` matches neither records with a salary
+over 1000 nor records with a missing salary.
+*/
+use std::cmp::Ordering;
+use std::fmt;
+
+use access::{DataIndex, NRows};
+use error::*;
+use field::ArenaStr;
+use key::FloatKey;
+use label::StrLabels;
+use partial::{Func, FuncDefault, Implemented, IsImplemented};
+use permute::UpdatePermutation;
+use value::Value;
+use view::{DataView, FieldMap};
+
+/// A literal (or field) value within a query expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryValue {
+    /// A numeric value.
+    Number(f64),
+    /// A text value.
+    Text(String),
+    /// A boolean value.
+    Bool(bool),
+}
+
+impl QueryValue {
+    fn kind(&self) -> &'static str {
+        match *self {
+            QueryValue::Number(_) => "number",
+            QueryValue::Text(_) => "text",
+            QueryValue::Bool(_) => "boolean",
+        }
+    }
+}
+
+impl fmt::Display for QueryValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            QueryValue::Number(n) => write!(f, "{}", n),
+            QueryValue::Text(ref s) => write!(f, "'{}'", s),
+            QueryValue::Bool(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+/// Trait for comparing field values with [QueryValue](enum.QueryValue.html) literals, implemented
+/// for the data types which can be used in query expressions. Values are compared by reference,
+/// without conversion into `QueryValue`s.
+pub trait QueryCompare {
+    /// The kind of literal this type is compared with (`"number"`, `"text"`, or `"boolean"`).
+    const KIND: &'static str;
+
+    /// Compares this value with `value`, returning `None` if they are not comparable (if `value`
+    /// is of a different kind, or either is a floating-point NaN).
+    fn query_cmp(&self, value: &QueryValue) -> Option<Ordering>;
+}
+
+macro_rules! impl_query_compare_number {
+    ($($dtype:ty)*) => {$(
+        impl QueryCompare for $dtype {
+            const KIND: &'static str = "number";
+            fn query_cmp(&self, value: &QueryValue) -> Option<Ordering> {
+                match *value {
+                    QueryValue::Number(ref n) => (*self as f64).partial_cmp(n),
+                    _ => None,
+                }
+            }
+        }
+    )*}
+}
+impl_query_compare_number![f64 f32 u64 u32 i64 i32];
+
+macro_rules! impl_query_compare_text {
+    ($($dtype:ty)*) => {$(
+        impl QueryCompare for $dtype {
+            const KIND: &'static str = "text";
+            fn query_cmp(&self, value: &QueryValue) -> Option<Ordering> {
+                match *value {
+                    QueryValue::Text(ref text) => Some(AsRef::<str>::as_ref(self).cmp(text)),
+                    _ => None,
+                }
+            }
+        }
+    )*}
+}
+impl_query_compare_text![String &str ArenaStr];
+
+impl QueryCompare for bool {
+    const KIND: &'static str = "boolean";
+    fn query_cmp(&self, value: &QueryValue) -> Option<Ordering> {
+        match *value {
+            QueryValue::Bool(ref b) => Some(self.cmp(b)),
+            _ => None,
+        }
+    }
+}
+impl QueryCompare for FloatKey {
+    const KIND: &'static str = "number";
+    fn query_cmp(&self, value: &QueryValue) -> Option<Ordering> {
+        match *value {
+            QueryValue::Number(ref n) => self.value().partial_cmp(n),
+            _ => None,
+        }
+    }
+}
+
+/// Comparison operator within a query expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    /// `==`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+}
+
+impl CmpOp {
+    // operator with operands swapped (e.g. `1 < x` is `x > 1`)
+    fn flip(self) -> CmpOp {
+        match self {
+            CmpOp::Lt => CmpOp::Gt,
+            CmpOp::Le => CmpOp::Ge,
+            CmpOp::Gt => CmpOp::Lt,
+            CmpOp::Ge => CmpOp::Le,
+            op => op,
+        }
+    }
+
+    fn matches(self, ord: Ordering) -> bool {
+        match self {
+            CmpOp::Eq => ord == Ordering::Equal,
+            CmpOp::Ne => ord != Ordering::Equal,
+            CmpOp::Lt => ord == Ordering::Less,
+            CmpOp::Le => ord != Ordering::Greater,
+            CmpOp::Gt => ord == Ordering::Greater,
+            CmpOp::Ge => ord != Ordering::Less,
+        }
+    }
+}
+
+/// A parsed query expression. Create with `str::parse::<Query>()`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    /// Comparison of the field named `field` with `value`.
+    Compare {
+        /// Field name.
+        field: String,
+        /// Comparison operator.
+        op: CmpOp,
+        /// Value to compare with.
+        value: QueryValue,
+    },
+    /// Both sub-expressions are true.
+    And(Box<Query>, Box<Query>),
+    /// Either sub-expression is true.
+    Or(Box<Query>, Box<Query>),
+    /// Sub-expression is false.
+    Not(Box<Query>),
+}
+
+impl Query {
+    /// Returns the names of the fields referred to by this query, in order of first appearance.
+    pub fn fields(&self) -> Vec<&str> {
+        let mut fields = vec![];
+        self.push_fields(&mut fields);
+        fields
+    }
+
+    fn push_fields<'a>(&'a self, fields: &mut Vec<&'a str>) {
+        match *self {
+            Query::Compare { ref field, .. } => {
+                if !fields.contains(&field.as_str()) {
+                    fields.push(field);
+                }
+            }
+            Query::And(ref l, ref r) | Query::Or(ref l, ref r) => {
+                l.push_fields(fields);
+                r.push_fields(fields);
+            }
+            Query::Not(ref q) => q.push_fields(fields),
+        }
+    }
+
+    // Returns the comparisons of this query, in order of appearance.
+    pub(crate) fn comparisons(&self) -> Vec<(&str, CmpOp, &QueryValue)> {
+        let mut comparisons = vec![];
+        self.push_comparisons(&mut comparisons);
+        comparisons
+    }
+
+    fn push_comparisons<'a>(&'a self, comparisons: &mut Vec<(&'a str, CmpOp, &'a QueryValue)>) {
+        match *self {
+            Query::Compare {
+                ref field,
+                op,
+                ref value,
+            } => comparisons.push((field, op, value)),
+            Query::And(ref l, ref r) | Query::Or(ref l, ref r) => {
+                l.push_comparisons(comparisons);
+                r.push_comparisons(comparisons);
+            }
+            Query::Not(ref q) => q.push_comparisons(comparisons),
+        }
+    }
+
+    // Combines the per-record results of each of this query's comparisons (in order of
+    // appearance, as returned by `comparisons`) into the per-record results of the query.
+    fn combine<I>(&self, results: &mut I) -> Vec<Value<bool>>
+    where
+        I: Iterator<Item = Vec<Value<bool>>>,
+    {
+        match *self {
+            Query::Compare { .. } => results.next().expect("missing comparison results"),
+            Query::And(ref l, ref r) => zip_with(l.combine(results), r.combine(results), and),
+            Query::Or(ref l, ref r) => zip_with(l.combine(results), r.combine(results), or),
+            Query::Not(ref q) => q
+                .combine(results)
+                .into_iter()
+                .map(|value| value.map(|b| !b))
+                .collect(),
+        }
+    }
+}
+
+fn zip_with<F>(left: Vec<Value<bool>>, right: Vec<Value<bool>>, f: F) -> Vec<Value<bool>>
+where
+    F: Fn(Value<bool>, Value<bool>) -> Value<bool>,
+{
+    left.into_iter().zip(right).map(|(l, r)| f(l, r)).collect()
+}
+
+// Three-valued (missing-value-aware) logical AND.
+fn and(left: Value<bool>, right: Value<bool>) -> Value<bool> {
+    match (left, right) {
+        (Value::Exists(false), _) | (_, Value::Exists(false)) => Value::Exists(false),
+        (Value::Exists(true), Value::Exists(true)) => Value::Exists(true),
+        _ => Value::Na,
+    }
+}
+
+// Three-valued (missing-value-aware) logical OR.
+fn or(left: Value<bool>, right: Value<bool>) -> Value<bool> {
+    match (left, right) {
+        (Value::Exists(true), _) | (_, Value::Exists(true)) => Value::Exists(true),
+        (Value::Exists(false), Value::Exists(false)) => Value::Exists(false),
+        _ => Value::Na,
+    }
+}
+
+/// Compares each value of `data` with `value` using `op`, as in a query comparison. Comparisons
+/// of missing (NA) values are missing.
+///
+/// # Errors
+/// Fails with `AgnesError::Query` if the values of `data` cannot be compared with values of the
+/// kind of `value` (e.g. a text field with a number). `field` is the name of the field, for the
+/// error message.
+pub(crate) fn compare_values<DI>(
+    field: &str,
+    data: &DI,
+    op: CmpOp,
+    value: &QueryValue,
+) -> Result<Vec<Value<bool>>>
+where
+    DI: DataIndex,
+    DI::DType: QueryCompare,
+{
+    if DI::DType::KIND != value.kind() {
+        return query_error(format!(
+            "cannot compare {} field '{}' with {} value {}",
+            DI::DType::KIND,
+            field,
+            value.kind(),
+            value
+        ));
+    }
+    Ok(data
+        .iter()
+        .map(|datum| datum.map(|datum| datum.query_cmp(value).is_some_and(|ord| op.matches(ord))))
+        .collect())
+}
+
+/// Returns the indices of the records matching `query`, given the per-record results of each of
+/// its comparisons (in order of appearance) over `nrows` records.
+pub(crate) fn matching_records(
+    query: &Query,
+    results: Vec<Vec<Value<bool>>>,
+    nrows: usize,
+) -> Vec<usize> {
+    let matches = query.combine(&mut results.into_iter());
+    debug_assert_eq!(matches.len(), nrows);
+    matches
+        .into_iter()
+        .enumerate()
+        .filter_map(|(idx, matched)| match matched {
+            Value::Exists(true) => Some(idx),
+            _ => None,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Literal(QueryValue),
+    Cmp(CmpOp),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+fn query_error<T>(msg: String) -> Result<T> {
+    Err(AgnesError::Query(msg))
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>> {
+    let chars = expr.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut pos = 0;
+    while pos < chars.len() {
+        let c = chars[pos];
+        let next = chars.get(pos + 1).cloned();
+        let (token, len) = match (c, next) {
+            _ if c.is_whitespace() => {
+                pos += 1;
+                continue;
+            }
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('=', Some('=')) => (Token::Cmp(CmpOp::Eq), 2),
+            ('!', Some('=')) => (Token::Cmp(CmpOp::Ne), 2),
+            ('<', Some('=')) => (Token::Cmp(CmpOp::Le), 2),
+            ('>', Some('=')) => (Token::Cmp(CmpOp::Ge), 2),
+            ('<', _) => (Token::Cmp(CmpOp::Lt), 1),
+            ('>', _) => (Token::Cmp(CmpOp::Gt), 1),
+            ('!', _) => (Token::Not, 1),
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            ('\'', _) | ('"', _) | ('`', _) => {
+                let end = chars[pos + 1..]
+                    .iter()
+                    .position(|&d| d == c)
+                    .map(|end| pos + 1 + end);
+                let end = match end {
+                    Some(end) => end,
+                    None => return query_error(format!("unterminated quote at position {}", pos)),
+                };
+                let text = chars[pos + 1..end].iter().collect::<String>();
+                let token = if c == '`' {
+                    Token::Ident(text)
+                } else {
+                    Token::Literal(QueryValue::Text(text))
+                };
+                (token, end + 1 - pos)
+            }
+            _ if c.is_ascii_digit() || c == '.' || (c == '-' && next.is_some()) => {
+                let mut end = pos + 1;
+                while end < chars.len()
+                    && (chars[end].is_ascii_alphanumeric()
+                        || chars[end] == '.'
+                        || (matches!(chars[end], '+' | '-') && matches!(chars[end - 1], 'e' | 'E')))
+                {
+                    end += 1;
+                }
+                let text = chars[pos..end].iter().collect::<String>();
+                match text.parse::<f64>() {
+                    Ok(n) => (Token::Literal(QueryValue::Number(n)), end - pos),
+                    Err(_) => return query_error(format!("invalid number '{}'", text)),
+                }
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let mut end = pos + 1;
+                while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
+                    end += 1;
+                }
+                let text = chars[pos..end].iter().collect::<String>();
+                let token = match text.as_str() {
+                    "true" => Token::Literal(QueryValue::Bool(true)),
+                    "false" => Token::Literal(QueryValue::Bool(false)),
+                    _ => Token::Ident(text),
+                };
+                (token, end - pos)
+            }
+            _ => return query_error(format!("unexpected character '{}' at position {}", c, pos)),
+        };
+        tokens.push(token);
+        pos += len;
+    }
+    Ok(tokens)
+}
+
+// Recursive-descent parser over a token list:
+//   or      := and ('||' and)*
+//   and     := unary ('&&' unary)*
+//   unary   := '!' unary | '(' or ')' | compare
+//   compare := ident [op literal] | literal op ident
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn or(&mut self) -> Result<Query> {
+        let mut query = self.and()?;
+        while self.eat(&Token::Or) {
+            query = Query::Or(Box::new(query), Box::new(self.and()?));
+        }
+        Ok(query)
+    }
+    fn and(&mut self) -> Result<Query> {
+        let mut query = self.unary()?;
+        while self.eat(&Token::And) {
+            query = Query::And(Box::new(query), Box::new(self.unary()?));
+        }
+        Ok(query)
+    }
+    fn unary(&mut self) -> Result<Query> {
+        if self.eat(&Token::Not) {
+            return Ok(Query::Not(Box::new(self.unary()?)));
+        }
+        if self.eat(&Token::LParen) {
+            let query = self.or()?;
+            return if self.eat(&Token::RParen) {
+                Ok(query)
+            } else {
+                query_error("expected ')'".to_string())
+            };
+        }
+        self.compare()
+    }
+    fn compare(&mut self) -> Result<Query> {
+        match (self.next(), self.peek().cloned()) {
+            (Some(Token::Ident(field)), Some(Token::Cmp(op))) => {
+                self.pos += 1;
+                match self.next() {
+                    Some(Token::Literal(value)) => Ok(Query::Compare { field, op, value }),
+                    _ => query_error(format!("expected value to compare with '{}'", field)),
+                }
+            }
+            // bare boolean field
+            (Some(Token::Ident(field)), _) => Ok(Query::Compare {
+                field,
+                op: CmpOp::Eq,
+                value: QueryValue::Bool(true),
+            }),
+            (Some(Token::Literal(value)), Some(Token::Cmp(op))) => {
+                self.pos += 1;
+                match self.next() {
+                    Some(Token::Ident(field)) => Ok(Query::Compare {
+                        field,
+                        op: op.flip(),
+                        value,
+                    }),
+                    _ => query_error(format!("expected field to compare with {}", value)),
+                }
+            }
+            (Some(token), _) => query_error(format!("unexpected token {:?}", token)),
+            (None, _) => query_error("unexpected end of expression".to_string()),
+        }
+    }
+}
+
+impl ::std::str::FromStr for Query {
+    type Err = AgnesError;
+
+    fn from_str(expr: &str) -> Result<Query> {
+        let mut parser = Parser {
+            tokens: tokenize(expr)?,
+            pos: 0,
+        };
+        let query = parser.or()?;
+        match parser.peek() {
+            None => Ok(query),
+            Some(token) => query_error(format!("unexpected token {:?}", token)),
+        }
+    }
+}
+
+/// Function (implementing [Func](../partial/trait.Func.html)) that evaluates the comparisons of a
+/// query over the fields they refer to.
+pub struct QueryCompareFn {
+    // name of each field, in label order
+    names: Vec<&'static str>,
+    // comparisons of the query, in order of appearance
+    comparisons: Vec<(String, CmpOp, QueryValue)>,
+    // per-record results of each comparison; `None` until its field is reached, and for fields
+    // that cannot be used in queries
+    results: Vec<Option<Vec<Value<bool>>>>,
+    // position (in label order) of the next field
+    next_field: usize,
+    // first comparison error encountered
+    error: Option<AgnesError>,
+}
+impl<DType> Func<DType> for QueryCompareFn
+where
+    DType: QueryCompare,
+{
+    type Output = ();
+    fn call<DI>(&mut self, data: &DI) -> Self::Output
+    where
+        DI: DataIndex<DType = DType>,
+    {
+        let name = self.names[self.next_field];
+        self.next_field += 1;
+        for (idx, &(ref field, op, ref value)) in self.comparisons.iter().enumerate() {
+            if field != name || self.error.is_some() {
+                continue;
+            }
+            match compare_values(field, data, op, value) {
+                Ok(results) => self.results[idx] = Some(results),
+                Err(err) => self.error = Some(err),
+            }
+        }
+    }
+}
+impl FuncDefault for QueryCompareFn {
+    type Output = ();
+    fn call(&mut self) -> Self::Output {
+        self.next_field += 1;
+    }
+}
+macro_rules! impl_query_is_impl {
+    ($($dtype:ty)*) => {$(
+        impl IsImplemented<QueryCompareFn> for $dtype {
+            type IsImpl = Implemented;
+        }
+    )*}
+}
+impl_query_is_impl![String &str ArenaStr f64 f32 u64 u32 i64 i32 bool FloatKey];
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Returns a new `DataView` containing only the records matching the query expression `expr`
+    /// (in their original order). Fields are referred to by name. See the
+    /// [query](../query/index.html) module for the expression syntax.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Query` if `expr` is not a valid expression, refers to a field which
+    /// does not exist (or whose type cannot be used in queries), or compares a field with a value
+    /// of a different kind (e.g. a text field with a number).
+    pub fn query(&self, expr: &str) -> Result<Self>
+    where
+        Self: FieldMap<QueryCompareFn> + NRows,
+        Labels: StrLabels,
+        Frames: Clone + UpdatePermutation,
+    {
+        let query = expr.parse::<Query>()?;
+        let names = <Labels as StrLabels>::labels_vec();
+        for field in query.fields() {
+            if !names.contains(&field) {
+                return query_error(format!("field '{}' not found", field));
+            }
+        }
+        let comparisons = query
+            .comparisons()
+            .into_iter()
+            .map(|(field, op, value)| (field.to_string(), op, value.clone()))
+            .collect::<Vec<_>>();
+        let mut func = QueryCompareFn {
+            names,
+            results: vec![None; comparisons.len()],
+            comparisons,
+            next_field: 0,
+            error: None,
+        };
+        self.field_map(&mut func);
+        if let Some(err) = func.error {
+            return Err(err);
+        }
+        let mut results = vec![];
+        for ((field, _, _), result) in func.comparisons.iter().zip(func.results) {
+            match result {
+                Some(result) => results.push(result),
+                None => return query_error(format!("field '{}' cannot be used in queries", field)),
+            }
+        }
+        let indices = matching_records(&query, results, self.nrows());
+        Ok(self.subset_records(&indices))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let query = "salary > 1000 && (dept == 'Sales' || !active)"
+            .parse::<Query>()
+            .unwrap();
+        assert_eq!(query.fields(), vec!["salary", "dept", "active"]);
+        assert_eq!(
+            "5 <= `Emp Id`".parse::<Query>().unwrap(),
+            Query::Compare {
+                field: "Emp Id".to_string(),
+                op: CmpOp::Ge,
+                value: QueryValue::Number(5.0),
+            }
+        );
+        assert_eq!(
+            "x > -1.5e3".parse::<Query>().unwrap(),
+            Query::Compare {
+                field: "x".to_string(),
+                op: CmpOp::Gt,
+                value: QueryValue::Number(-1500.0),
+            }
+        );
+        // `&&` binds tighter than `||`
+        match "a || b && c".parse::<Query>().unwrap() {
+            Query::Or(_, r) => assert_eq!(r.fields(), vec!["b", "c"]),
+            q => panic!("unexpected query {:?}", q),
+        }
+
+        for invalid in &["", "x >", "x == 'a", "(x", "x == 1 y", "x # 1", "1 == 2"] {
+            assert!(invalid.parse::<Query>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn query_view() {
+        use select::FieldSelect;
+        use test_utils::*;
+
+        let dv = sample_emp_table().into_view();
+        let filtered = dv.query("DeptId == 1 && EmpId >= 5").unwrap();
+        assert_eq!(filtered.field::<emp_table::EmpId>().to_vec(), vec![5u64, 6]);
+        let filtered = dv.query("!(DeptId < 4) || EmpName == \"Jamie\"").unwrap();
+        assert_eq!(
+            filtered.field::<emp_table::EmpName>().to_vec(),
+            vec!["Jamie", "Louise", "Ann"]
+        );
+        assert_eq!(dv.query("EmpId > 100").unwrap().nrows(), 0);
+
+        assert!(dv.query("Salary > 1000").is_err());
+        assert!(dv.query("EmpName > 1000").is_err());
+        assert!(dv.query("DeptId").is_err());
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn query_na() {
+        use select::FieldSelect;
+        use test_utils::*;
+
+        let dv = sample_dept_vacation_table().into_view();
+        let ids = |expr: &str| {
+            dv.query(expr)
+                .unwrap()
+                .field::<emp_table::DeptId>()
+                .to_vec()
+        };
+        assert_eq!(ids("VacationHrs > 4"), vec![1u64, 2]);
+        // comparisons of missing values are neither true nor false, even when negated
+        assert_eq!(ids("!(VacationHrs > 4)"), vec![2u64, 1]);
+        assert_eq!(ids("VacationHrs > 4 || DeptId == 3"), vec![1u64, 2, 3]);
+        assert_eq!(
+            ids("!(VacationHrs > 4 && DeptId == 3)"),
+            vec![1u64, 2, 1, 2, 1]
+        );
+        assert_eq!(ids("!(VacationHrs > 4 || DeptId == 3)"), vec![2u64, 1]);
+    }
+}
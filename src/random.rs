@@ -0,0 +1,252 @@
+/*!
+Reproducible random sampling, shuffling, and train / test splitting of `DataView`s.
+
+Every randomized operation takes the random number generator to use as an explicit argument (any
+`rand::RngCore` implementation), so that results depend only on the state of that generator. For
+reproducible results, use the [DefaultRng](type.DefaultRng.html) created with
+[seeded_rng](fn.seeded_rng.html): given the same seed, the operations in this module return the
+same results across runs, platforms (including 32-bit and 64-bit targets), and versions of agnes.
+
+The algorithms are fixed, and documented so they can be reimplemented elsewhere:
+* The default generator is ChaCha20 (`rand`'s `ChaChaRng`), seeded from a `u64` with
+  `SeedableRng::seed_from_u64` (which expands the seed with a PCG32 sequence).
+* A uniform integer in `0..bound` is drawn from a single `u64` produced by the generator
+  (`next_u64`), rejecting and redrawing values at or above the largest multiple of `bound`, and
+  taking the remainder modulo `bound`.
+* Shuffling is a Fisher-Yates shuffle, which for each position `i` from the last down to the
+  second swaps the element at `i` with the element at a uniform position in `0..=i`.
+* Sampling `n` of `len` rows shuffles the row indices `0..len`, and keeps the last `n` indices of
+  the shuffled order, sorted. Train / test splitting does the same, keeping the last indices as the
+  test set and the remaining indices as the training set.
+
+# Example
+
+```
+# #[cfg(feature = "test-utils")]
+# fn main() {
+use agnes::random::seeded_rng;
+use agnes::test_utils::*;
+
+let emps = sample_emp_table().into_view();
+let (train, test) = emps.train_test_split(0.3, &mut seeded_rng(42));
+assert_eq!((train.nrows(), test.nrows()), (5, 2));
+
+// the same seed produces the same split
+let (_, test_again) = emps.train_test_split(0.3, &mut seeded_rng(42));
+assert_eq!(test_again.to_string(), test.to_string());
+# }
+# #[cfg(not(feature = "test-utils"))]
+# fn main() {}
+```
+*/
+
+use rand::prng::ChaChaRng;
+use rand::{RngCore, SeedableRng};
+
+use access::NRows;
+use permute::UpdatePermutation;
+use view::DataView;
+
+/// The default random number generator for the operations in this module (ChaCha20), which
+/// produces the same sequence on every platform for a given seed.
+pub type DefaultRng = ChaChaRng;
+
+/// Creates a [DefaultRng](type.DefaultRng.html) seeded with `seed`.
+pub fn seeded_rng(seed: u64) -> DefaultRng {
+    DefaultRng::seed_from_u64(seed)
+}
+
+/// Returns a uniformly-distributed integer in `0..bound`, drawn from `rng` (see the
+/// [module-level documentation](index.html) for the algorithm).
+///
+/// # Panics
+/// Panics if `bound` is 0.
+pub fn uniform_below<R: RngCore + ?Sized>(rng: &mut R, bound: u64) -> u64 {
+    assert!(bound > 0, "bound must be positive");
+    // the largest multiple of `bound` representable in a u64 (plus one) is `u64::MAX - threshold`
+    let threshold = (u64::MAX - bound + 1) % bound;
+    loop {
+        let value = rng.next_u64();
+        if value <= u64::MAX - threshold {
+            return value % bound;
+        }
+    }
+}
+
+/// Shuffles `values` in place with a Fisher-Yates shuffle driven by `rng`.
+pub fn shuffle<R: RngCore + ?Sized, T>(values: &mut [T], rng: &mut R) {
+    for i in (1..values.len()).rev() {
+        let j = uniform_below(rng, i as u64 + 1) as usize;
+        values.swap(i, j);
+    }
+}
+
+/// Returns a random permutation of the indices `0..len`.
+pub fn permutation<R: RngCore + ?Sized>(len: usize, rng: &mut R) -> Vec<usize> {
+    let mut indices = (0..len).collect::<Vec<_>>();
+    shuffle(&mut indices, rng);
+    indices
+}
+
+/// Returns `n` distinct indices sampled uniformly from `0..len`, in ascending order (or all of
+/// `0..len` if `n` is at least `len`).
+pub fn sample_indices<R: RngCore + ?Sized>(len: usize, n: usize, rng: &mut R) -> Vec<usize> {
+    let mut indices = permutation(len, rng);
+    let mut sample = indices.split_off(len - n.min(len));
+    sample.sort_unstable();
+    sample
+}
+
+// Returns the number of test rows when splitting `len` rows with the given test fraction.
+fn test_len(len: usize, test_fraction: f64) -> usize {
+    assert!(
+        (0.0..=1.0).contains(&test_fraction),
+        "test fraction must be between 0 and 1"
+    );
+    ((len as f64 * test_fraction).round() as usize).min(len)
+}
+
+/// Randomly partitions the indices `0..len` into a training set and a test set, where the test set
+/// contains `test_fraction` of the indices (rounded to the nearest integer). Both sets are in
+/// ascending order.
+///
+/// # Panics
+/// Panics if `test_fraction` is not between 0 and 1 (inclusive).
+pub fn split_indices<R: RngCore + ?Sized>(
+    len: usize,
+    test_fraction: f64,
+    rng: &mut R,
+) -> (Vec<usize>, Vec<usize>) {
+    let test_len = test_len(len, test_fraction);
+    let mut train = permutation(len, rng);
+    let mut test = train.split_off(len - test_len);
+    train.sort_unstable();
+    test.sort_unstable();
+    (train, test)
+}
+
+impl<Labels, Frames> DataView<Labels, Frames>
+where
+    Self: NRows,
+    Frames: Clone + UpdatePermutation,
+{
+    /// Returns a new `DataView` with the records of this view in a random order, determined by
+    /// `seed`. This is equivalent to [shuffle_with](#method.shuffle_with) with the generator
+    /// created by [seeded_rng](../random/fn.seeded_rng.html), so the order for a given seed is the
+    /// same across runs and platforms.
+    pub fn shuffle(&self, seed: u64) -> Self {
+        self.shuffle_with(&mut seeded_rng(seed))
+    }
+
+    /// Returns a new `DataView` with the records of this view in a random order, determined by
+    /// `rng` (see the [random](../random/index.html) module).
+    pub fn shuffle_with<R: RngCore + ?Sized>(&self, rng: &mut R) -> Self {
+        self.subset_records(&permutation(self.nrows(), rng))
+    }
+
+    /// Returns a new `DataView` with `n` records sampled (without replacement) from this view by
+    /// `rng`, in their original order (see the [random](../random/index.html) module). If `n` is
+    /// at least the number of records in this view, all records are returned.
+    pub fn sample<R: RngCore + ?Sized>(&self, n: usize, rng: &mut R) -> Self {
+        self.subset_records(&sample_indices(self.nrows(), n, rng))
+    }
+
+    /// Randomly splits the records of this view into a training view and a test view (returned in
+    /// that order), where the test view contains `test_fraction` of the records (rounded to the
+    /// nearest integer). Records of both views remain in their original order. The split is
+    /// determined by `rng` (see the [random](../random/index.html) module).
+    ///
+    /// # Panics
+    /// Panics if `test_fraction` is not between 0 and 1 (inclusive).
+    pub fn train_test_split<R: RngCore + ?Sized>(
+        &self,
+        test_fraction: f64,
+        rng: &mut R,
+    ) -> (Self, Self) {
+        let (train, test) = split_indices(self.nrows(), test_fraction, rng);
+        (self.subset_records(&train), self.subset_records(&test))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic() {
+        let first = (0..5)
+            .map(|_| uniform_below(&mut seeded_rng(7), 1000))
+            .collect::<Vec<_>>();
+        assert!(first.iter().all(|&value| value == first[0]));
+
+        let mut rng = seeded_rng(7);
+        assert!((0..1000).all(|_| uniform_below(&mut rng, 3) < 3));
+        assert_eq!(uniform_below(&mut rng, 1), 0);
+
+        let perm = permutation(20, &mut seeded_rng(3));
+        // results for a given seed are fixed across platforms and versions
+        assert_eq!(
+            perm,
+            vec![10, 14, 13, 6, 16, 7, 4, 1, 0, 2, 11, 19, 18, 3, 8, 12, 9, 5, 17, 15]
+        );
+        assert_eq!(perm, permutation(20, &mut seeded_rng(3)));
+        assert_ne!(perm, permutation(20, &mut seeded_rng(4)));
+        let mut sorted = perm.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+
+        let sample = sample_indices(20, 5, &mut seeded_rng(3));
+        assert_eq!(sample.len(), 5);
+        assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(sample_indices(3, 5, &mut seeded_rng(3)), vec![0, 1, 2]);
+
+        let (train, test) = split_indices(10, 0.25, &mut seeded_rng(3));
+        assert_eq!((train.len(), test.len()), (7, 3));
+        let mut all = train.iter().chain(test.iter()).cloned().collect::<Vec<_>>();
+        all.sort_unstable();
+        assert_eq!(all, (0..10).collect::<Vec<_>>());
+        assert!(split_indices(4, 0.0, &mut seeded_rng(3)).1.is_empty());
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn view_sampling() {
+        use access::DataIndex;
+        use select::FieldSelect;
+        use test_utils::*;
+
+        let dv = sample_emp_table().into_view();
+        let ids = |dv: &emp_table::View| dv.field::<emp_table::EmpId>().to_vec();
+
+        let shuffled = dv.shuffle(1);
+        assert_eq!(ids(&shuffled), ids(&dv.shuffle(1)));
+        assert_eq!(ids(&shuffled), ids(&dv.shuffle_with(&mut seeded_rng(1))));
+        assert_ne!(ids(&shuffled), ids(&dv.shuffle(2)));
+        let mut sorted = ids(&shuffled);
+        sorted.sort_unstable();
+        assert_eq!(sorted, ids(&dv));
+
+        // shuffling permutes every field of the view (including fields of merged frames) together
+        let merged = dv
+            .merge(&sample_emp_table_extra().into_view())
+            .unwrap()
+            .shuffle(1);
+        assert_eq!(merged.field::<emp_table::EmpId>().to_vec(), ids(&shuffled));
+        let offsets = sample_emp_table_extra().into_view().shuffle(1);
+        assert_eq!(
+            merged.field::<extra_emp::SalaryOffset>().to_vec(),
+            offsets.field::<extra_emp::SalaryOffset>().to_vec()
+        );
+
+        let sample = dv.sample(3, &mut seeded_rng(1));
+        assert_eq!(sample.nrows(), 3);
+        assert_eq!(ids(&sample), ids(&dv.sample(3, &mut seeded_rng(1))));
+
+        let (train, test) = dv.train_test_split(0.3, &mut seeded_rng(1));
+        assert_eq!((train.nrows(), test.nrows()), (5, 2));
+        let mut all = ids(&train);
+        all.extend(ids(&test));
+        all.sort_unstable();
+        assert_eq!(all, ids(&dv));
+    }
+}
@@ -0,0 +1,178 @@
+/*!
+Replacement of specific values of a field, such as recoding categories or fixing known typos.
+
+The [Recode](trait.Recode.html) trait (and the
+[DataView::replace_values](../view/struct.DataView.html#method.replace_values) and
+[DataView::recode](../view/struct.DataView.html#method.recode) methods) map the values of a field
+through a dictionary of replacements, producing a new field. Values which do not appear in the
+dictionary are kept as they are, and missing (NA) values remain missing. The original field (and
+the store it belongs to) is not modified.
+
+```
+# #[macro_use] extern crate agnes;
+use std::collections::HashMap;
+
+use agnes::access::DataIndex;
+use agnes::cons::Nil;
+use agnes::field::FieldData;
+use agnes::store::{DataStore, PushBackField};
+
+tablespace![
+    table people {
+        Sex: String,
+    }
+];
+
+fn main() {
+    let dv = DataStore::<Nil>::empty()
+        .push_back_field::<people::Sex, _>(FieldData::<String>::from_vec(vec![
+            "M".to_string(), "F".to_string(), "X".to_string(), "F".to_string(),
+        ]))
+        .into_view();
+
+    let mut codes = HashMap::new();
+    codes.insert("M", "Male");
+    codes.insert("F", "Female");
+    let sexes = dv.recode::<people::Sex, _, _>(&codes);
+    assert_eq!(sexes.to_vec(), vec!["Male", "Female", "X", "Female"]);
+}
+```
+*/
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use access::DataIndex;
+use field::FieldData;
+use select::{FieldSelect, SelectFieldByLabel};
+use view::{DataView, VFieldTypeOf};
+
+/// A trait for replacing specific values of a field.
+pub trait Recode: DataIndex {
+    /// Returns a new field with the values of this field which are keys of `replacements` replaced
+    /// by the corresponding values. Other values are kept, and missing (NA) values remain
+    /// missing.
+    fn replace_values(
+        &self,
+        replacements: &HashMap<Self::DType, Self::DType>,
+    ) -> FieldData<Self::DType>
+    where
+        Self::DType: Hash + Eq + Clone + Debug + Default;
+
+    /// Returns a new text field with the text values of this field which are keys of `codes`
+    /// replaced by the corresponding values (e.g. to map the codes `"M"` and `"F"` to `"Male"`
+    /// and `"Female"`). Other values are kept, and missing (NA) values remain missing.
+    fn recode<K, V>(&self, codes: &HashMap<K, V>) -> FieldData<String>
+    where
+        Self::DType: AsRef<str>,
+        K: Borrow<str> + Hash + Eq,
+        V: AsRef<str>;
+}
+
+impl<DI> Recode for DI
+where
+    DI: DataIndex,
+{
+    fn replace_values(&self, replacements: &HashMap<DI::DType, DI::DType>) -> FieldData<DI::DType>
+    where
+        DI::DType: Hash + Eq + Clone + Debug + Default,
+    {
+        self.iter()
+            .map(|value| value.map(|value| replacements.get(value).unwrap_or(value)))
+            .collect()
+    }
+
+    fn recode<K, V>(&self, codes: &HashMap<K, V>) -> FieldData<String>
+    where
+        DI::DType: AsRef<str>,
+        K: Borrow<str> + Hash + Eq,
+        V: AsRef<str>,
+    {
+        self.iter()
+            .map(|value| {
+                value.map(|value| {
+                    let value = value.as_ref();
+                    codes.get(value).map_or(value, AsRef::as_ref).to_string()
+                })
+            })
+            .collect()
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Returns a new field with the values of the field labeled `Label` which are keys of
+    /// `replacements` replaced by the corresponding values. See
+    /// [Recode::replace_values](../recode/trait.Recode.html#method.replace_values).
+    pub fn replace_values<Label>(
+        &self,
+        replacements: &HashMap<VFieldTypeOf<Self, Label>, VFieldTypeOf<Self, Label>>,
+    ) -> FieldData<VFieldTypeOf<Self, Label>>
+    where
+        Self: SelectFieldByLabel<Label>,
+        VFieldTypeOf<Self, Label>: Hash + Eq + Clone + Debug + Default,
+    {
+        self.field::<Label>().replace_values(replacements)
+    }
+
+    /// Returns a new text field with the text values of the field labeled `Label` which are keys
+    /// of `codes` replaced by the corresponding values. See
+    /// [Recode::recode](../recode/trait.Recode.html#method.recode).
+    pub fn recode<Label, K, V>(&self, codes: &HashMap<K, V>) -> FieldData<String>
+    where
+        Self: SelectFieldByLabel<Label>,
+        VFieldTypeOf<Self, Label>: AsRef<str>,
+        K: Borrow<str> + Hash + Eq,
+        V: AsRef<str>,
+    {
+        self.field::<Label>().recode(codes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use value::Value;
+
+    #[test]
+    fn replace_values() {
+        let field = FieldData::from_field_vec(vec![
+            Value::Exists(1u64),
+            Value::Na,
+            Value::Exists(2),
+            Value::Exists(3),
+        ]);
+        let mut replacements = HashMap::new();
+        replacements.insert(1u64, 10);
+        replacements.insert(3, 1);
+        assert_eq!(
+            field.replace_values(&replacements).to_value_vec(),
+            vec![
+                Value::Exists(10u64),
+                Value::Na,
+                Value::Exists(2),
+                Value::Exists(1)
+            ]
+        );
+        assert_eq!(field.replace_values(&HashMap::new()), field);
+    }
+
+    #[test]
+    fn recode() {
+        let field = FieldData::from_field_vec(vec![
+            Value::Exists("colour".to_string()),
+            Value::Exists("color".to_string()),
+            Value::Na,
+        ]);
+        let mut typos = HashMap::new();
+        typos.insert("colour".to_string(), "color".to_string());
+        assert_eq!(
+            field.recode(&typos).to_value_vec(),
+            vec![
+                Value::Exists("color".to_string()),
+                Value::Exists("color".to_string()),
+                Value::Na
+            ]
+        );
+    }
+}
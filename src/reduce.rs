@@ -0,0 +1,376 @@
+/*!
+Folding and reduction of the values of a single field.
+
+The [Fold](trait.Fold.html) trait is implemented for every
+[DataIndex](../access/trait.DataIndex.html), so it can be used on fields selected from a `DataView`
+(e.g. `dv.field::<Label>().fold(0, |acc, value| ...)`) as well as on stand-alone fields. The
+[AnyAll](trait.AnyAll.html) trait similarly provides `any` and `all` reductions over boolean fields
+and predicates, which are useful as guard conditions, and the [FirstLast](trait.FirstLast.html)
+trait provides access to the first and last (existing) values of a field. The
+[NaPolicy](enum.NaPolicy.html) determines how missing values are handled by reductions.
+*/
+use access::DataIndex;
+use value::Value;
+
+/// Option for handling missing (NA) values in reductions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NaPolicy {
+    /// Ignore missing values.
+    #[default]
+    Skip,
+    /// Produce a missing result if a missing value is encountered. [AnyAll](trait.AnyAll.html)
+    /// reductions instead follow three-valued (Kleene) logic, treating a missing value as unknown:
+    /// the result is only missing if it could depend on the missing values.
+    Propagate,
+}
+
+/// A trait for folding and reducing the values of a field into a single result.
+pub trait Fold: DataIndex {
+    /// Folds every value (possibly NA) of this field into an accumulator, starting with `init` and
+    /// applying `f` to the accumulator and each value in order.
+    fn fold<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, Value<&Self::DType>) -> B;
+
+    /// Folds the existing (non-missing) values of this field into an accumulator, starting with
+    /// `init` and applying `f` to the accumulator and each value in order.
+    fn fold_existing<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, &Self::DType) -> B;
+
+    /// Reduces the values of this field into a single value by repeatedly applying `f`, using the
+    /// first value as the initial accumulator. Missing values are handled according to `na`.
+    ///
+    /// Returns `Value::Na` if there are no existing values to reduce, or if `na` is
+    /// `NaPolicy::Propagate` and a missing value exists (in which case the reduction stops at the
+    /// first missing value).
+    fn reduce<F>(&self, na: NaPolicy, f: F) -> Value<Self::DType>
+    where
+        Self::DType: Clone,
+        F: FnMut(Self::DType, &Self::DType) -> Self::DType;
+}
+
+impl<DI> Fold for DI
+where
+    DI: DataIndex,
+{
+    fn fold<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, Value<&DI::DType>) -> B,
+    {
+        self.iter().fold(init, f)
+    }
+
+    fn fold_existing<B, F>(&self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, &DI::DType) -> B,
+    {
+        self.iter().fold(init, |acc, value| match value {
+            Value::Exists(value) => f(acc, value),
+            Value::Na => acc,
+        })
+    }
+
+    fn reduce<F>(&self, na: NaPolicy, mut f: F) -> Value<DI::DType>
+    where
+        DI::DType: Clone,
+        F: FnMut(DI::DType, &DI::DType) -> DI::DType,
+    {
+        let mut acc: Option<DI::DType> = None;
+        for value in self.iter() {
+            match (value, na) {
+                (Value::Exists(value), _) => {
+                    acc = Some(match acc {
+                        Some(acc) => f(acc, value),
+                        None => value.clone(),
+                    });
+                }
+                (Value::Na, NaPolicy::Skip) => {}
+                (Value::Na, NaPolicy::Propagate) => return Value::Na,
+            }
+        }
+        acc.into()
+    }
+}
+
+/// A trait for testing whether any or all of the values of a field are `true` (for boolean fields)
+/// or satisfy a predicate.
+///
+/// Missing values are handled according to a [NaPolicy](enum.NaPolicy.html): with
+/// `NaPolicy::Skip` they are ignored (so `all` of a field with no existing values is `true`), and
+/// with `NaPolicy::Propagate` a missing value makes the result missing (NA) unless the existing
+/// values determine it (`any` is `true` if an existing value is `true`, and `all` is `false` if an
+/// existing value is `false`, whether or not other values are missing).
+pub trait AnyAll: DataIndex {
+    /// Returns whether any of the values of this boolean field are `true`.
+    fn any(&self, na: NaPolicy) -> Value<bool>
+    where
+        Self: DataIndex<DType = bool>,
+    {
+        self.any_where(na, |&value| value)
+    }
+
+    /// Returns whether all of the values of this boolean field are `true`.
+    fn all(&self, na: NaPolicy) -> Value<bool>
+    where
+        Self: DataIndex<DType = bool>,
+    {
+        self.all_where(na, |&value| value)
+    }
+
+    /// Returns whether any of the values of this field satisfy the predicate `pred`. With
+    /// `NaPolicy::Propagate`, the result is `true` if any existing value satisfies `pred`, and
+    /// otherwise missing (NA) if any value is missing.
+    fn any_where<F>(&self, na: NaPolicy, pred: F) -> Value<bool>
+    where
+        F: FnMut(&Self::DType) -> bool;
+
+    /// Returns whether all of the values of this field satisfy the predicate `pred`. With
+    /// `NaPolicy::Propagate`, the result is `false` if any existing value does not satisfy `pred`,
+    /// and otherwise missing (NA) if any value is missing.
+    fn all_where<F>(&self, na: NaPolicy, pred: F) -> Value<bool>
+    where
+        F: FnMut(&Self::DType) -> bool;
+}
+
+impl<DI> AnyAll for DI
+where
+    DI: DataIndex,
+{
+    fn any_where<F>(&self, na: NaPolicy, mut pred: F) -> Value<bool>
+    where
+        F: FnMut(&DI::DType) -> bool,
+    {
+        let mut missing = false;
+        for value in self.iter() {
+            match value {
+                Value::Exists(value) if pred(value) => return Value::Exists(true),
+                Value::Exists(_) => {}
+                Value::Na => missing = true,
+            }
+        }
+        if missing && na == NaPolicy::Propagate {
+            Value::Na
+        } else {
+            Value::Exists(false)
+        }
+    }
+
+    fn all_where<F>(&self, na: NaPolicy, mut pred: F) -> Value<bool>
+    where
+        F: FnMut(&DI::DType) -> bool,
+    {
+        // all values satisfy `pred` exactly when no value fails to satisfy it
+        match self.any_where(na, |value| !pred(value)) {
+            Value::Exists(any_failed) => Value::Exists(!any_failed),
+            Value::Na => Value::Na,
+        }
+    }
+}
+
+/// A trait for accessing the first and last values of a field. See
+/// [DataView::first_by](../view/struct.DataView.html#method.first_by) and related methods for the
+/// first and last values within each group of records.
+pub trait FirstLast: DataIndex {
+    /// Returns the first value (possibly NA) of this field, or `None` if this field is empty.
+    fn first(&self) -> Option<Value<&Self::DType>>;
+    /// Returns the last value (possibly NA) of this field, or `None` if this field is empty.
+    fn last(&self) -> Option<Value<&Self::DType>>;
+    /// Returns the first existing (non-missing) value of this field, or `None` if no values exist.
+    fn first_valid(&self) -> Option<&Self::DType>;
+    /// Returns the last existing (non-missing) value of this field, or `None` if no values exist.
+    fn last_valid(&self) -> Option<&Self::DType>;
+}
+
+impl<DI> FirstLast for DI
+where
+    DI: DataIndex,
+{
+    fn first(&self) -> Option<Value<&DI::DType>> {
+        self.get_datum(0).ok()
+    }
+    fn last(&self) -> Option<Value<&DI::DType>> {
+        self.len()
+            .checked_sub(1)
+            .and_then(|idx| self.get_datum(idx).ok())
+    }
+    fn first_valid(&self) -> Option<&DI::DType> {
+        self.iter().find_map(|value| match value {
+            Value::Exists(value) => Some(value),
+            Value::Na => None,
+        })
+    }
+    fn last_valid(&self) -> Option<&DI::DType> {
+        (0..self.len())
+            .rev()
+            .find_map(|idx| match self.get_datum(idx) {
+                Ok(Value::Exists(value)) => Some(value),
+                _ => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use field::FieldData;
+
+    fn sample() -> FieldData<u64> {
+        FieldData::from_field_vec(vec![
+            Value::Exists(4u64),
+            Value::Na,
+            Value::Exists(7),
+            Value::Exists(1),
+        ])
+    }
+
+    #[test]
+    fn fold() {
+        let field = sample();
+        assert_eq!(
+            field.fold(0usize, |count, value| count + value.exists() as usize),
+            3
+        );
+        assert_eq!(field.fold_existing(0u64, |acc, &value| acc + value), 12);
+        assert_eq!(
+            field.fold_existing(String::new(), |acc, value| acc + &value.to_string()),
+            "471"
+        );
+    }
+
+    #[test]
+    fn reduce() {
+        let field = sample();
+        assert_eq!(
+            field.reduce(NaPolicy::Skip, |acc, &value| acc.max(value)),
+            Value::Exists(7)
+        );
+        assert_eq!(
+            field.reduce(NaPolicy::Propagate, |acc, &value| acc.max(value)),
+            Value::Na
+        );
+        let field = FieldData::<u64>::from_vec(vec![2u64, 3, 4]);
+        assert_eq!(
+            field.reduce(NaPolicy::Propagate, |acc, &value| acc * value),
+            Value::Exists(24)
+        );
+        let empty = FieldData::<u64>::default();
+        assert_eq!(
+            empty.reduce(NaPolicy::Skip, |acc, &value| acc + value),
+            Value::Na
+        );
+    }
+
+    #[test]
+    fn any_all() {
+        let field =
+            FieldData::from_field_vec(vec![Value::Exists(false), Value::Na, Value::Exists(true)]);
+        assert_eq!(field.any(NaPolicy::Skip), Value::Exists(true));
+        // an existing `true` decides `any` regardless of the missing value
+        assert_eq!(field.any(NaPolicy::Propagate), Value::Exists(true));
+        assert_eq!(field.all(NaPolicy::Skip), Value::Exists(false));
+        // an existing `false` decides `all` regardless of the missing value
+        assert_eq!(field.all(NaPolicy::Propagate), Value::Exists(false));
+
+        let field = FieldData::from_field_vec(vec![Value::Exists(true), Value::Na]);
+        assert_eq!(field.all(NaPolicy::Skip), Value::Exists(true));
+        assert_eq!(field.all(NaPolicy::Propagate), Value::Na);
+        assert_eq!(field.any(NaPolicy::Propagate), Value::Exists(true));
+        let field = FieldData::from_field_vec(vec![Value::Na, Value::Exists(false)]);
+        assert_eq!(field.any(NaPolicy::Skip), Value::Exists(false));
+        assert_eq!(field.any(NaPolicy::Propagate), Value::Na);
+        let missing = FieldData::<bool>::from_field_vec(vec![Value::Na]);
+        assert_eq!(missing.any(NaPolicy::Skip), Value::Exists(false));
+        assert_eq!(missing.all(NaPolicy::Skip), Value::Exists(true));
+        assert_eq!(missing.all(NaPolicy::Propagate), Value::Na);
+        assert_eq!(
+            FieldData::<bool>::default().all(NaPolicy::Propagate),
+            Value::Exists(true)
+        );
+        assert_eq!(
+            FieldData::<bool>::default().any(NaPolicy::Skip),
+            Value::Exists(false)
+        );
+
+        let field = sample();
+        assert_eq!(
+            field.any_where(NaPolicy::Skip, |&value| value > 5),
+            Value::Exists(true)
+        );
+        assert_eq!(
+            field.any_where(NaPolicy::Skip, |&value| value > 10),
+            Value::Exists(false)
+        );
+        assert_eq!(
+            field.any_where(NaPolicy::Propagate, |&value| value > 10),
+            Value::Na
+        );
+        assert_eq!(
+            field.all_where(NaPolicy::Skip, |&value| value < 10),
+            Value::Exists(true)
+        );
+        assert_eq!(
+            field.all_where(NaPolicy::Propagate, |&value| value < 10),
+            Value::Na
+        );
+        assert_eq!(
+            field.all_where(NaPolicy::Propagate, |&value| value < 5),
+            Value::Exists(false)
+        );
+    }
+
+    #[test]
+    fn first_last() {
+        let field = FieldData::from_field_vec(vec![
+            Value::Na,
+            Value::Exists(4u64),
+            Value::Exists(7),
+            Value::Na,
+        ]);
+        assert_eq!(field.first(), Some(Value::Na));
+        assert_eq!(field.last(), Some(Value::Na));
+        assert_eq!(field.first_valid(), Some(&4));
+        assert_eq!(field.last_valid(), Some(&7));
+
+        let field = sample();
+        assert_eq!(field.first(), Some(Value::Exists(&4)));
+        assert_eq!(field.last(), Some(Value::Exists(&1)));
+
+        let empty = FieldData::<u64>::default();
+        assert_eq!(empty.first(), None);
+        assert_eq!(empty.last(), None);
+        assert_eq!(empty.first_valid(), None);
+        let missing = FieldData::<u64>::from_field_vec(vec![Value::Na]);
+        assert_eq!(missing.last_valid(), None);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn fold_view_field() {
+        use select::FieldSelect;
+        use test_utils::*;
+
+        let dv = sample_emp_table().into_view();
+        assert_eq!(
+            dv.field::<emp_table::EmpName>()
+                .fold_existing(0, |len, name| len + name.len()),
+            31
+        );
+        assert_eq!(
+            dv.field::<emp_table::DeptId>()
+                .reduce(NaPolicy::Skip, |acc, &value| acc.min(value)),
+            Value::Exists(1)
+        );
+        assert_eq!(
+            dv.field::<emp_table::EmpId>()
+                .all_where(NaPolicy::Propagate, |&id| id <= 10),
+            Value::Exists(true)
+        );
+        assert_eq!(
+            dv.field::<emp_table::EmpName>()
+                .any_where(NaPolicy::Skip, |name| name.is_empty()),
+            Value::Exists(false)
+        );
+    }
+}
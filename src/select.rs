@@ -1,7 +1,10 @@
 /*!
-Traits for selecting a field from a data structure.
+Traits for selecting a field (or several fields) from a data structure.
 */
 use access::DataIndex;
+use cons::{Cons, Nil};
+use label::LCons;
+use value::Value;
 
 /// Trait for accessing the data of a single field as a struct which implements
 /// [DataIndex](../access/trait.DataIndex.html).
@@ -17,6 +20,38 @@ pub trait FieldSelect {
     {
         SelectFieldByLabel::select_field(self)
     }
+
+    /// Returns a [ZipFields](struct.ZipFields.html) struct containing the data for the fields
+    /// specified by the label list `Labels`, which can be iterated over to produce a tuple of
+    /// values (one `Value<&T>` for each field) for each record. Up to eight fields are supported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate agnes;
+    /// # use agnes::select::FieldSelect;
+    /// # use agnes::value::Value;
+    /// # #[cfg(feature = "test-utils")]
+    /// # fn main() {
+    /// # use agnes::test_utils::*;
+    /// let dv = sample_emp_table().into_view();
+    /// for (id, name) in &dv.zip_fields::<Labels![emp_table::EmpId, emp_table::EmpName]>() {
+    ///     if id == Value::Exists(&0) {
+    ///         assert_eq!(name, Value::Exists(&"Sally".to_string()));
+    ///     }
+    /// }
+    /// # }
+    /// # #[cfg(not(feature = "test-utils"))]
+    /// # fn main() {}
+    /// ```
+    fn zip_fields<Labels>(&self) -> ZipFields<<Self as SelectFieldsByLabels<Labels>>::Output>
+    where
+        Self: SelectFieldsByLabels<Labels>,
+    {
+        ZipFields {
+            fields: SelectFieldsByLabels::<Labels>::select_fields(self),
+        }
+    }
 }
 
 /// Trait implemented by data structures to provide access to data for a single field.
@@ -31,6 +66,187 @@ pub trait SelectFieldByLabel<Label> {
     fn select_field(&self) -> Self::Output;
 }
 
+/// Trait for accessing the data of several fields (specified by the label list `Labels`) at once,
+/// as a [Cons](../cons/struct.Cons.html)-list of structs which implement
+/// [DataIndex](../access/trait.DataIndex.html). Implemented for all data structures which
+/// implement [SelectFieldByLabel](trait.SelectFieldByLabel.html) for each label in `Labels`.
+pub trait SelectFieldsByLabels<Labels> {
+    /// The return type for the `select_fields` method.
+    type Output;
+
+    /// Returns a cons-list of objects that provide access to the data in the fields specified by
+    /// `Labels`.
+    fn select_fields(&self) -> Self::Output;
+}
+impl<T> SelectFieldsByLabels<Nil> for T {
+    type Output = Nil;
+
+    fn select_fields(&self) -> Nil {
+        Nil
+    }
+}
+impl<T, Label, LabelTail> SelectFieldsByLabels<LCons<Label, LabelTail>> for T
+where
+    T: SelectFieldByLabel<Label> + SelectFieldsByLabels<LabelTail>,
+{
+    type Output = Cons<
+        <T as SelectFieldByLabel<Label>>::Output,
+        <T as SelectFieldsByLabels<LabelTail>>::Output,
+    >;
+
+    fn select_fields(&self) -> Self::Output {
+        Cons {
+            head: SelectFieldByLabel::<Label>::select_field(self),
+            tail: SelectFieldsByLabels::<LabelTail>::select_fields(self),
+        }
+    }
+}
+
+/// Trait for retrieving the values of a record from a [Cons](../cons/struct.Cons.html)-list of
+/// fields as a tuple.
+pub trait RecordTuple<'a> {
+    /// Tuple of values (one `Value<&T>` for each field) for a single record.
+    type Record;
+
+    /// Returns the number of records in these fields (the length of the shortest field).
+    fn nrecords(&self) -> usize;
+    /// Returns the tuple of values for the record at index `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    fn record(&'a self, idx: usize) -> Self::Record;
+}
+
+macro_rules! fields_cons {
+    () => { Nil };
+    ($field:ident $($rest:ident)*) => { Cons<$field, fields_cons![$($rest)*]> };
+}
+
+macro_rules! impl_record_tuple {
+    ($($field:ident: $($path:ident).+;)+) => {
+        impl<'a, $($field,)+> RecordTuple<'a> for fields_cons![$($field)+]
+        where
+            $($field: 'a + DataIndex,)+
+        {
+            type Record = ($(Value<&'a $field::DType>,)+);
+
+            fn nrecords(&self) -> usize {
+                let mut nrecords = usize::MAX;
+                $(nrecords = nrecords.min(self.$($path).+.len());)+
+                nrecords
+            }
+            fn record(&'a self, idx: usize) -> Self::Record {
+                ($(self.$($path).+.get_datum(idx).unwrap(),)+)
+            }
+        }
+    }
+}
+impl_record_tuple![A: head;];
+impl_record_tuple![A: head; B: tail.head;];
+impl_record_tuple![A: head; B: tail.head; C: tail.tail.head;];
+impl_record_tuple![
+    A: head; B: tail.head; C: tail.tail.head; D: tail.tail.tail.head;
+];
+impl_record_tuple![
+    A: head; B: tail.head; C: tail.tail.head; D: tail.tail.tail.head;
+    E: tail.tail.tail.tail.head;
+];
+impl_record_tuple![
+    A: head; B: tail.head; C: tail.tail.head; D: tail.tail.tail.head;
+    E: tail.tail.tail.tail.head; F: tail.tail.tail.tail.tail.head;
+];
+impl_record_tuple![
+    A: head; B: tail.head; C: tail.tail.head; D: tail.tail.tail.head;
+    E: tail.tail.tail.tail.head; F: tail.tail.tail.tail.tail.head;
+    G: tail.tail.tail.tail.tail.tail.head;
+];
+impl_record_tuple![
+    A: head; B: tail.head; C: tail.tail.head; D: tail.tail.tail.head;
+    E: tail.tail.tail.tail.head; F: tail.tail.tail.tail.tail.head;
+    G: tail.tail.tail.tail.tail.tail.head; H: tail.tail.tail.tail.tail.tail.tail.head;
+];
+
+/// Several fields selected together (with [zip_fields](trait.FieldSelect.html#method.zip_fields))
+/// for iterating over their values record-by-record.
+#[derive(Debug, Clone)]
+pub struct ZipFields<Fields> {
+    fields: Fields,
+}
+impl<Fields> ZipFields<Fields> {
+    /// Returns the cons-list of selected fields.
+    pub fn fields(&self) -> &Fields {
+        &self.fields
+    }
+    /// Returns an iterator over the records of the selected fields, yielding a tuple of values for
+    /// each record.
+    pub fn iter<'a>(&'a self) -> ZipFieldsIter<'a, Fields>
+    where
+        Fields: RecordTuple<'a>,
+    {
+        ZipFieldsIter {
+            fields: &self.fields,
+            cur_idx: 0,
+            end_idx: self.fields.nrecords(),
+        }
+    }
+}
+impl<'a, Fields> IntoIterator for &'a ZipFields<Fields>
+where
+    Fields: RecordTuple<'a>,
+{
+    type Item = Fields::Record;
+    type IntoIter = ZipFieldsIter<'a, Fields>;
+
+    fn into_iter(self) -> ZipFieldsIter<'a, Fields> {
+        self.iter()
+    }
+}
+
+/// Iterator over the records of a [ZipFields](struct.ZipFields.html), yielding a tuple of values
+/// for each record.
+#[derive(Debug)]
+pub struct ZipFieldsIter<'a, Fields: 'a> {
+    fields: &'a Fields,
+    cur_idx: usize,
+    end_idx: usize,
+}
+impl<'a, Fields> Iterator for ZipFieldsIter<'a, Fields>
+where
+    Fields: RecordTuple<'a>,
+{
+    type Item = Fields::Record;
+
+    fn next(&mut self) -> Option<Fields::Record> {
+        if self.cur_idx < self.end_idx {
+            let out = Some(self.fields.record(self.cur_idx));
+            self.cur_idx += 1;
+            out
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end_idx - self.cur_idx;
+        (remaining, Some(remaining))
+    }
+}
+impl<'a, Fields> DoubleEndedIterator for ZipFieldsIter<'a, Fields>
+where
+    Fields: RecordTuple<'a>,
+{
+    fn next_back(&mut self) -> Option<Fields::Record> {
+        if self.cur_idx < self.end_idx {
+            self.end_idx -= 1;
+            Some(self.fields.record(self.end_idx))
+        } else {
+            None
+        }
+    }
+}
+impl<'a, Fields> ExactSizeIterator for ZipFieldsIter<'a, Fields> where Fields: RecordTuple<'a> {}
+
 #[cfg(test)]
 mod tests {
     use super::FieldSelect;
@@ -55,4 +271,37 @@ mod tests {
             .collect::<Vec<_>>();
         assert_eq!(result, vec![1, 1, 1, 1, 1, 1, 1]);
     }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn zip_fields() {
+        use test_utils::emp_table::*;
+
+        let dv = sample_merged_emp_table();
+        let zipped = dv.zip_fields::<Labels![EmpId, EmpName]>();
+        let mut iter = zipped.iter();
+        assert_eq!(iter.len(), 7);
+        assert_eq!(
+            iter.next(),
+            Some((Value::Exists(&0u64), Value::Exists(&"Sally".to_string())))
+        );
+        assert_eq!(
+            iter.next_back(),
+            Some((Value::Exists(&10u64), Value::Exists(&"Ann".to_string())))
+        );
+        assert_eq!(iter.len(), 5);
+
+        let mut total = 0;
+        for (id, dept, _name) in &dv.zip_fields::<Labels![EmpId, DeptId, EmpName]>() {
+            total += id.map_or(0, |&id| id) * dept.map_or(0, |&dept| dept);
+        }
+        assert_eq!(
+            total,
+            dv.field::<EmpId>()
+                .iter()
+                .zip(dv.field::<DeptId>().iter())
+                .map(|(id, dept)| id.map_or(0, |&id| id) * dept.map_or(0, |&dept| dept))
+                .sum::<u64>()
+        );
+    }
 }
@@ -0,0 +1,342 @@
+//! Avro sink implementation, for writing a `DataView` out as an Avro object container file.
+//! Requires the `avro` feature.
+
+use std::collections::hash_map::RandomState;
+use std::fs::File;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde_json;
+
+use access::{DataIndex, NRows};
+use cons::Len;
+use error::*;
+use field::ArenaStr;
+use label::StrLabels;
+use partial::{Func, FuncDefault, Implemented, IsImplemented};
+use source::avro::{AvroValue, MAGIC};
+use value::Value;
+use view::{DataView, FieldMap};
+
+/// Trait for data types which can be written as Avro values.
+pub trait ToAvro {
+    /// Name of the Avro primitive type values of this data type are written as.
+    fn avro_type() -> &'static str;
+    /// Converts this value into an Avro value. Fails if the value cannot be represented by the
+    /// Avro type.
+    fn to_avro(&self) -> Result<AvroValue>;
+}
+
+macro_rules! impl_to_avro {
+    ($($dtype:ty => $avro_ty:expr, $variant:ident, $convert:expr;)*) => {$(
+        impl ToAvro for $dtype {
+            fn avro_type() -> &'static str {
+                $avro_ty
+            }
+            #[allow(clippy::redundant_closure_call)]
+            fn to_avro(&self) -> Result<AvroValue> {
+                ($convert)(self).map(AvroValue::$variant)
+            }
+        }
+    )*}
+}
+impl_to_avro![
+    bool => "boolean", Boolean, |&v: &bool| Ok(v);
+    i32 => "int", Int, |&v: &i32| Ok(v);
+    i64 => "long", Long, |&v: &i64| Ok(v);
+    u32 => "long", Long, |&v: &u32| Ok(i64::from(v));
+    u64 => "long", Long, |&v: &u64| if v > i64::MAX as u64 {
+        Err(AgnesError::Avro(format!("value {} out of range for Avro long", v)))
+    } else {
+        Ok(v as i64)
+    };
+    f32 => "float", Float, |&v: &f32| Ok(v);
+    f64 => "double", Double, |&v: &f64| Ok(v);
+    String => "string", String, |v: &String| Ok(v.clone());
+    &str => "string", String, |v: &&str| Ok(v.to_string());
+    ArenaStr => "string", String, |v: &ArenaStr| Ok(v.to_string());
+];
+
+/// Function (implementing [Func](../../partial/trait.Func.html)) that converts the values of a
+/// field into Avro values. Fields of unsupported data types are written as `null` fields.
+#[derive(Debug)]
+pub struct AvroColumnsFn {
+    nrows: usize,
+    columns: Vec<(&'static str, Vec<AvroValue>)>,
+    error: Option<AgnesError>,
+}
+impl<DType> Func<DType> for AvroColumnsFn
+where
+    DType: ToAvro,
+{
+    type Output = ();
+    fn call<DI>(&mut self, data: &DI) -> Self::Output
+    where
+        DI: DataIndex<DType = DType>,
+    {
+        let mut column = Vec::with_capacity(self.nrows);
+        for i in 0..self.nrows {
+            column.push(match data.get_datum(i).unwrap() {
+                Value::Exists(value) => match value.to_avro() {
+                    Ok(value) => value,
+                    Err(err) => {
+                        self.error.get_or_insert(err);
+                        AvroValue::Null
+                    }
+                },
+                Value::Na => AvroValue::Null,
+            });
+        }
+        self.columns.push((DType::avro_type(), column));
+    }
+}
+impl FuncDefault for AvroColumnsFn {
+    type Output = ();
+    fn call(&mut self) -> Self::Output {
+        self.columns
+            .push(("null", vec![AvroValue::Null; self.nrows]));
+    }
+}
+macro_rules! impl_avro_columns_is_impl {
+    ($($dtype:ty)*) => {$(
+        impl IsImplemented<AvroColumnsFn> for $dtype {
+            type IsImpl = Implemented;
+        }
+    )*}
+}
+impl_avro_columns_is_impl![String &str ArenaStr f64 f32 u64 u32 i64 i32 bool];
+
+fn write_long(buf: &mut Vec<u8>, value: i64) {
+    let mut value = ((value << 1) ^ (value >> 63)) as u64;
+    while value >= 0x80 {
+        buf.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn write_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    write_long(buf, bytes.len() as i64);
+    buf.extend_from_slice(bytes);
+}
+
+fn write_value(buf: &mut Vec<u8>, value: &AvroValue) {
+    match *value {
+        AvroValue::Null => {}
+        AvroValue::Boolean(value) => buf.push(value as u8),
+        AvroValue::Int(value) => write_long(buf, i64::from(value)),
+        AvroValue::Long(value) => write_long(buf, value),
+        AvroValue::Float(value) => buf.extend_from_slice(&value.to_le_bytes()),
+        AvroValue::Double(value) => buf.extend_from_slice(&value.to_le_bytes()),
+        AvroValue::Bytes(ref value) => write_bytes(buf, value),
+        AvroValue::String(ref value) => write_bytes(buf, value.as_bytes()),
+        AvroValue::Record(ref values) => {
+            for value in values {
+                write_value(buf, value);
+            }
+        }
+        AvroValue::Array(ref values) => {
+            if !values.is_empty() {
+                write_long(buf, values.len() as i64);
+                for value in values {
+                    write_value(buf, value);
+                }
+            }
+            write_long(buf, 0);
+        }
+        AvroValue::Map(ref values) => {
+            if !values.is_empty() {
+                write_long(buf, values.len() as i64);
+                for (key, value) in values {
+                    write_bytes(buf, key.as_bytes());
+                    write_value(buf, value);
+                }
+            }
+            write_long(buf, 0);
+        }
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames>
+where
+    Frames: Len + NRows,
+    Self: FieldMap<AvroColumnsFn>,
+    Labels: StrLabels,
+{
+    /// Writes this `DataView` as an (uncompressed) Avro object container file to `writer`. The
+    /// records are written with a record schema named `record_name`, with one field for each
+    /// field of this `DataView` (named by its label). Fields are written as unions of `null` and
+    /// the Avro type of the field's data type, with missing (NA) values written as `null`.
+    ///
+    /// # Errors
+    /// Fails if a value cannot be represented in Avro (e.g. a `u64` value too large for an Avro
+    /// `long`), or if unable to write to `writer`.
+    pub fn write_avro_to<W: Write>(&self, record_name: &str, writer: W) -> Result<()> {
+        let nrows = if Frames::is_empty() { 0 } else { self.nrows() };
+        let mut func = AvroColumnsFn {
+            nrows,
+            columns: vec![],
+            error: None,
+        };
+        self.field_map(&mut func);
+        if let Some(err) = func.error {
+            return Err(err);
+        }
+
+        let fields = <Labels as StrLabels>::labels()
+            .iter()
+            .zip(&func.columns)
+            .map(|(&name, &(ty, _))| {
+                let ty = if ty == "null" {
+                    serde_json::Value::from("null")
+                } else {
+                    serde_json::Value::from(vec!["null", ty])
+                };
+                let mut field = serde_json::Map::new();
+                field.insert("name".into(), name.into());
+                field.insert("type".into(), ty);
+                serde_json::Value::Object(field)
+            })
+            .collect::<Vec<_>>();
+        let mut schema = serde_json::Map::new();
+        schema.insert("type".into(), "record".into());
+        schema.insert("name".into(), record_name.into());
+        schema.insert("fields".into(), fields.into());
+        let schema = serde_json::Value::Object(schema).to_string();
+
+        let random = RandomState::new();
+        let mut sync = vec![];
+        for _ in 0..2 {
+            let mut hasher = random.build_hasher();
+            hasher.write_usize(sync.len());
+            sync.extend_from_slice(&hasher.finish().to_le_bytes());
+        }
+
+        let mut header = MAGIC.to_vec();
+        write_long(&mut header, 2);
+        write_bytes(&mut header, b"avro.schema");
+        write_bytes(&mut header, schema.as_bytes());
+        write_bytes(&mut header, b"avro.codec");
+        write_bytes(&mut header, b"null");
+        write_long(&mut header, 0);
+        header.extend_from_slice(&sync);
+
+        let mut writer = BufWriter::new(writer);
+        writer.write_all(&header)?;
+        if nrows > 0 {
+            let mut block = vec![];
+            for i in 0..nrows {
+                for &(ty, ref column) in &func.columns {
+                    match column[i] {
+                        _ if ty == "null" => {}
+                        AvroValue::Null => write_long(&mut block, 0),
+                        ref value => {
+                            write_long(&mut block, 1);
+                            write_value(&mut block, value);
+                        }
+                    }
+                }
+            }
+            let mut block_header = vec![];
+            write_long(&mut block_header, nrows as i64);
+            write_long(&mut block_header, block.len() as i64);
+            writer.write_all(&block_header)?;
+            writer.write_all(&block)?;
+            writer.write_all(&sync)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes this `DataView` as an Avro object container file to a newly-created file at `path`
+    /// (truncating the file if it already exists). See [write_avro_to](#method.write_avro_to)
+    /// for details.
+    ///
+    /// # Errors
+    /// Fails if unable to create or write to the file at `path`, or for any of the reasons
+    /// [write_avro_to](#method.write_avro_to) might fail.
+    pub fn write_avro<P: AsRef<Path>>(&self, record_name: &str, path: P) -> Result<()> {
+        self.write_avro_to(record_name, File::create(path)?)
+    }
+}
+
+#[cfg(feature = "test-utils")]
+#[cfg(test)]
+mod tests {
+    use access::DataIndex;
+    use cons::Nil;
+    use field::FieldData;
+    use select::FieldSelect;
+    use source::avro::{AvroReader, AvroSource, AvroType};
+    use store::DataStore;
+    use test_utils::*;
+    use value::Value;
+
+    #[test]
+    fn avro_round_trip() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<emp_table::EmpId, _>(FieldData::<u64>::from_vec(vec![0u64, 2, 5]))
+            .push_back_field::<emp_table::EmpName, _>(FieldData::<String>::from_field_vec(vec![
+                Value::Exists("Sally".to_string()),
+                Value::Na,
+                Value::Exists("Bob".to_string()),
+            ]))
+            .push_back_field::<emp_stats::MeanVacationHrs, _>(FieldData::<f64>::from_vec(vec![
+                -1.5f64, 0.0, 2.25,
+            ]))
+            .into_view();
+        let mut output = vec![];
+        dv.write_avro_to("emp", &mut output).unwrap();
+
+        let source = AvroSource::new(output.clone()).unwrap();
+        assert_eq!(
+            source.fields()[1],
+            (
+                "EmpName".to_string(),
+                vec![AvroType::Null, AvroType::String]
+            )
+        );
+        let mut reader = AvroReader::new(
+            &source,
+            schema![
+                fieldname emp_stats::MeanVacationHrs = "MeanVacationHrs";
+                fieldname emp_table::EmpName = "EmpName";
+                fieldindex emp_table::EmpId = 0usize;
+            ],
+        )
+        .unwrap();
+        let loaded = reader.read().unwrap().into_view();
+        assert_eq!(loaded.nrows(), 3);
+        assert_eq!(
+            loaded.field::<emp_table::EmpId>().to_vec(),
+            vec![0u64, 2, 5]
+        );
+        assert_eq!(
+            loaded.field::<emp_table::EmpName>().to_value_vec(),
+            vec![
+                Value::Exists("Sally".to_string()),
+                Value::Na,
+                Value::Exists("Bob".to_string())
+            ]
+        );
+        assert_eq!(
+            loaded.field::<emp_stats::MeanVacationHrs>().to_vec(),
+            vec![-1.5, 0.0, 2.25]
+        );
+
+        // schema validation
+        assert!(
+            AvroReader::new(&source, schema![fieldname emp_table::EmpId = "EmpName";]).is_err()
+        );
+        assert!(
+            AvroReader::new(&source, schema![fieldname emp_table::EmpId = "Missing";]).is_err()
+        );
+        assert!(AvroSource::new(b"not avro".to_vec()).is_err());
+
+        let too_large = DataStore::<Nil>::empty()
+            .push_back_field::<emp_table::EmpId, _>(FieldData::<u64>::from_vec(vec![u64::MAX]))
+            .into_view();
+        assert!(too_large.write_avro_to("emp", vec![]).is_err());
+    }
+}
@@ -0,0 +1,228 @@
+//! CSV-based sink implementation, for writing a `DataView` out as a CSV file.
+
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+use csv::Writer;
+
+use access::{DataIndex, NRows};
+use cons::Len;
+use display::{DisplayOptions, FormatCellsFn};
+use error::*;
+use field::ArenaStr;
+use key::FloatKey;
+use label::StrLabels;
+use partial::{Func, FuncDefault, Implemented, IsImplemented};
+use sink::ignore_broken_pipe;
+use value::Value;
+use view::{DataView, FieldMap};
+
+/// Function (implementing [Func](../../partial/trait.Func.html)) that formats the values of a field
+/// as CSV cells. Missing (NA) values are formatted as empty cells.
+#[derive(Debug)]
+pub struct CsvCellsFn {
+    nrows: usize,
+    columns: Vec<Vec<String>>,
+}
+impl<DType> Func<DType> for CsvCellsFn
+where
+    DType: Display,
+{
+    type Output = ();
+    fn call<DI>(&mut self, data: &DI) -> Self::Output
+    where
+        DI: DataIndex<DType = DType>,
+    {
+        debug_assert!(data.len() >= self.nrows);
+        self.columns.push(
+            (0..self.nrows)
+                .map(|i| match data.get_datum(i).unwrap() {
+                    Value::Exists(value) => value.to_string(),
+                    Value::Na => String::new(),
+                })
+                .collect(),
+        );
+    }
+}
+impl FuncDefault for CsvCellsFn {
+    type Output = ();
+    fn call(&mut self) -> Self::Output {
+        self.columns.push(vec![String::new(); self.nrows]);
+    }
+}
+macro_rules! impl_csvcells_is_impl {
+    ($($dtype:ty)*) => {$(
+        impl IsImplemented<CsvCellsFn> for $dtype {
+            type IsImpl = Implemented;
+        }
+    )*}
+}
+impl_csvcells_is_impl![String &str ArenaStr f64 f32 u64 u32 i64 i32 bool FloatKey];
+
+impl<Labels, Frames> DataView<Labels, Frames>
+where
+    Frames: Len + NRows,
+    Self: FieldMap<CsvCellsFn>,
+    Labels: StrLabels,
+{
+    /// Writes this `DataView` in CSV format to `writer`, with a header row containing the field
+    /// labels. Missing (NA) values are written as empty cells.
+    ///
+    /// # Errors
+    /// Fails if unable to write to `writer`.
+    pub fn write_csv_to<W: Write>(&self, writer: W) -> Result<()> {
+        let nrows = if Frames::is_empty() { 0 } else { self.nrows() };
+        let mut func = CsvCellsFn {
+            nrows,
+            columns: vec![],
+        };
+        self.field_map(&mut func);
+
+        let mut writer = Writer::from_writer(writer);
+        writer.write_record(<Labels as StrLabels>::labels())?;
+        for i in 0..nrows {
+            writer.write_record(func.columns.iter().map(|column| &column[i]))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes this `DataView` in CSV format to a newly-created file at `path` (truncating the
+    /// file if it already exists). See [write_csv_to](#method.write_csv_to) for details.
+    ///
+    /// # Errors
+    /// Fails if unable to create or write to the file at `path`.
+    pub fn write_csv<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.write_csv_to(File::create(path)?)
+    }
+
+    /// Writes this `DataView` in CSV format to standard output. See
+    /// [write_csv_to](#method.write_csv_to) for details. If standard output is closed before
+    /// writing completes (e.g. when piped into `head`), the remaining records are discarded and
+    /// this method succeeds.
+    ///
+    /// # Errors
+    /// Fails if unable to write to standard output.
+    pub fn write_csv_to_stdout(&self) -> Result<()> {
+        let stdout = io::stdout();
+        let result = self.write_csv_to(stdout.lock());
+        ignore_broken_pipe(result)
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames>
+where
+    Frames: Len + NRows,
+    Self: FieldMap<FormatCellsFn>,
+    Labels: StrLabels,
+{
+    /// Writes this `DataView` in CSV format to `writer` (see [write_csv_to](#method.write_csv_to)),
+    /// with numeric values rendered according to `options` (see
+    /// [DisplayOptions](../display/struct.DisplayOptions.html)). All rows are written, regardless
+    /// of the maximum number of rows set in `options`.
+    ///
+    /// # Errors
+    /// Fails if unable to write to `writer`.
+    pub fn write_csv_formatted_to<W: Write>(
+        &self,
+        writer: W,
+        options: &DisplayOptions,
+    ) -> Result<()> {
+        let nrows = if Frames::is_empty() { 0 } else { self.nrows() };
+        let labels = <Labels as StrLabels>::labels_vec();
+        let mut func = FormatCellsFn::new(options, labels.clone(), nrows);
+        self.field_map(&mut func);
+        let columns = func.into_columns();
+
+        let mut writer = Writer::from_writer(writer);
+        writer.write_record(labels)?;
+        for i in 0..nrows {
+            writer.write_record(columns.iter().map(|column| match column.get(i).unwrap() {
+                Value::Exists(value) => value,
+                Value::Na => "",
+            }))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "test-utils")]
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+
+    use cons::Nil;
+    use error::AgnesError;
+    use field::FieldData;
+    use store::DataStore;
+    use test_utils::*;
+    use value::Value;
+
+    #[test]
+    fn write_csv() {
+        let dv = sample_emp_table().into_view();
+        let mut output = vec![];
+        dv.write_csv_to(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let mut lines = output.lines();
+        assert_eq!(lines.next(), Some("EmpId,DeptId,EmpName"));
+        assert_eq!(lines.next(), Some("0,1,Sally"));
+        assert_eq!(lines.last(), Some("10,4,Ann"));
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<emp_table::EmpName, _>(FieldData::from_field_vec(vec![
+                Value::Exists("Smith, Jr.".to_string()),
+                Value::Na,
+            ]))
+            .into_view();
+        let mut output = vec![];
+        dv.write_csv_to(&mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "EmpName\n\"Smith, Jr.\"\n\"\"\n"
+        );
+    }
+    // Writer which behaves like a pipe whose reading end has been closed.
+    struct ClosedPipe;
+    impl Write for ClosedPipe {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+    }
+
+    #[test]
+    fn broken_pipe() {
+        use sink::ignore_broken_pipe;
+
+        let dv = sample_emp_table().into_view();
+        let result = dv.write_csv_to(ClosedPipe);
+        assert!(result.is_err());
+        assert!(ignore_broken_pipe(result).is_ok());
+        assert!(ignore_broken_pipe(Err(AgnesError::Cancelled)).is_err());
+    }
+
+    #[test]
+    fn write_csv_formatted() {
+        use display::DisplayOptions;
+
+        let dv = sample_emp_table_extra().into_view();
+        let options = DisplayOptions::new()
+            .precision(1)
+            .max_rows(1)
+            .field_format::<extra_emp::SalaryOffset>("+".parse().unwrap());
+        let mut output = vec![];
+        dv.write_csv_formatted_to(&mut output, &options).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let mut lines = output.lines();
+        assert_eq!(lines.next(), Some("SalaryOffset,DidTraining,VacationHrs"));
+        assert_eq!(lines.next(), Some("-5,false,47.3"));
+        assert_eq!(lines.next(), Some("+4,false,54.1"));
+        assert_eq!(lines.last(), Some("-1,true,22.5"));
+    }
+}
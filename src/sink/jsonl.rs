@@ -0,0 +1,95 @@
+//! JSON Lines sink implementation, for writing a `DataView` out with one JSON object per record.
+//! Requires the `serialize` feature.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use serde_json;
+
+use access::NRows;
+use cons::Len;
+use error::*;
+use sink::ignore_broken_pipe;
+use view::{DataView, SerializeViewRecord, ViewRecord};
+
+impl<Labels, Frames> DataView<Labels, Frames>
+where
+    Frames: Len + NRows,
+    Labels: Len + SerializeViewRecord<Frames>,
+{
+    /// Writes this `DataView` in [JSON Lines](http://jsonlines.org) format to `writer`: each record
+    /// is written as a JSON object (mapping field labels to values) on a separate line. Missing
+    /// (NA) values are written as `null`.
+    ///
+    /// # Errors
+    /// Fails if unable to serialize a value or write to `writer`.
+    pub fn write_jsonl_to<W: Write>(&self, writer: W) -> Result<()> {
+        let nrows = if Frames::is_empty() { 0 } else { self.nrows() };
+        let mut writer = BufWriter::new(writer);
+        for idx in 0..nrows {
+            serde_json::to_writer(&mut writer, &ViewRecord { view: self, idx })
+                .map_err(io::Error::from)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes this `DataView` in JSON Lines format to a newly-created file at `path` (truncating
+    /// the file if it already exists). See [write_jsonl_to](#method.write_jsonl_to) for details.
+    ///
+    /// # Errors
+    /// Fails if unable to create or write to the file at `path`.
+    pub fn write_jsonl<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.write_jsonl_to(File::create(path)?)
+    }
+
+    /// Writes this `DataView` in JSON Lines format to standard output. See
+    /// [write_jsonl_to](#method.write_jsonl_to) for details. If standard output is closed before
+    /// writing completes (e.g. when piped into `head`), the remaining records are discarded and
+    /// this method succeeds.
+    ///
+    /// # Errors
+    /// Fails if unable to serialize a value or write to standard output.
+    pub fn write_jsonl_to_stdout(&self) -> Result<()> {
+        let stdout = io::stdout();
+        let result = self.write_jsonl_to(stdout.lock());
+        ignore_broken_pipe(result)
+    }
+}
+
+#[cfg(feature = "test-utils")]
+#[cfg(test)]
+mod tests {
+    use cons::Nil;
+    use field::FieldData;
+    use store::DataStore;
+    use test_utils::*;
+    use value::Value;
+
+    #[test]
+    fn write_jsonl() {
+        let dv = sample_emp_table().into_view();
+        let mut output = vec![];
+        dv.write_jsonl_to(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0], r#"{"EmpId":0,"DeptId":1,"EmpName":"Sally"}"#);
+        assert_eq!(lines[6], r#"{"EmpId":10,"DeptId":4,"EmpName":"Ann"}"#);
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<emp_table::EmpName, _>(FieldData::from_field_vec(vec![
+                Value::Exists("Sally".to_string()),
+                Value::Na,
+            ]))
+            .into_view();
+        let mut output = vec![];
+        dv.write_jsonl_to(&mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"EmpName\":\"Sally\"}\n{\"EmpName\":null}\n"
+        );
+    }
+}
@@ -0,0 +1,39 @@
+//! Data sinks.
+//!
+//! The CSV and JSON Lines sinks can write to any `impl Write` (e.g. `write_csv_to`), to a
+//! newly-created file (e.g. `write_csv`), or to standard output (e.g. `write_csv_to_stdout`), so
+//! that agnes-based tools can be composed in shell pipelines.
+
+use std::io;
+
+use csv::ErrorKind as CsvErrorKind;
+
+use error::*;
+
+#[cfg(feature = "avro")]
+pub mod avro;
+pub mod csv;
+#[cfg(feature = "serialize")]
+pub mod jsonl;
+pub mod partition;
+
+// Returns `true` if `err` resulted from writing to a closed pipe.
+fn is_broken_pipe(err: &AgnesError) -> bool {
+    match *err {
+        AgnesError::Io(ref err) => err.kind() == io::ErrorKind::BrokenPipe,
+        AgnesError::Csv(ref err) => match *err.kind() {
+            CsvErrorKind::Io(ref err) => err.kind() == io::ErrorKind::BrokenPipe,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// Treats a write to a closed pipe (e.g. when standard output is piped into `head`) as successful
+// completion.
+pub(crate) fn ignore_broken_pipe(result: Result<()>) -> Result<()> {
+    match result {
+        Err(ref err) if is_broken_pipe(err) => Ok(()),
+        result => result,
+    }
+}
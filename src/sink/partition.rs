@@ -0,0 +1,163 @@
+//! Partitioned dataset sink implementation, for splitting a `DataView` by the values of a key field
+//! and writing each partition to a separate file in a hive-style directory layout.
+
+use std::fmt::{Display, Write as FmtWrite};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use access::{DataIndex, NRows};
+use cons::Len;
+use erased::{self, KeyIndex};
+use error::*;
+use label::{LabelName, StrLabels};
+use permute::UpdatePermutation;
+use select::{FieldSelect, SelectFieldByLabel};
+use sink::csv::CsvCellsFn;
+use value::Value;
+use view::{DataView, FieldMap};
+
+/// Name of the file written into each partition directory.
+pub const PARTITION_FILENAME: &str = "data.csv";
+/// Partition directory value used for records with a missing (NA) key value.
+pub const DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+impl<Labels, Frames> DataView<Labels, Frames>
+where
+    Frames: Len + NRows + UpdatePermutation + Clone,
+    Self: FieldMap<CsvCellsFn>,
+    Labels: StrLabels,
+{
+    /// Splits this `DataView` by the unique values of the field labeled `Label`, and writes the
+    /// records of each partition in CSV format to `dir/<label>=<value>/data.csv` (e.g.
+    /// `out/Year=2019/data.csv`). Records with a missing (NA) key value are written to the
+    /// `<label>=__HIVE_DEFAULT_PARTITION__` partition. Characters in partition directory names
+    /// which are not safe for use in paths are percent-encoded.
+    ///
+    /// All fields (including the key field) are written to each partition file, so that the
+    /// partitioned dataset can be loaded back with
+    /// [CsvSource::from_glob](../source/csv/struct.CsvSource.html#method.from_glob).
+    ///
+    /// Returns the paths of the written files, in order of first appearance of each key value.
+    ///
+    /// # Errors
+    /// Fails if unable to create the partition directories or write the partition files.
+    pub fn write_partitioned_csv<Label, P>(&self, dir: P) -> Result<Vec<PathBuf>>
+    where
+        Label: LabelName,
+        Self: SelectFieldByLabel<Label>,
+        <Self as SelectFieldByLabel<Label>>::Output: KeyIndex,
+        <<Self as SelectFieldByLabel<Label>>::Output as DataIndex>::DType: Display,
+        P: AsRef<Path>,
+    {
+        let key = self.field::<Label>();
+        let nrows = if Frames::is_empty() { 0 } else { self.nrows() };
+        let groups = erased::group_records(&[&key], nrows);
+        let mut partitions = vec![vec![]; groups.len()];
+        for (idx, &group) in groups.record_groups.iter().enumerate() {
+            partitions[group].push(idx);
+        }
+
+        let mut paths = vec![];
+        for (&first_idx, indices) in groups.first_indices.iter().zip(&partitions) {
+            let value = match key.get_datum(first_idx)? {
+                Value::Exists(value) => escape_path_component(&value.to_string()),
+                Value::Na => DEFAULT_PARTITION.to_string(),
+            };
+            let partition_dir = dir.as_ref().join(format!(
+                "{}={}",
+                escape_path_component(Label::name()),
+                value
+            ));
+            fs::create_dir_all(&partition_dir)?;
+            let path = partition_dir.join(PARTITION_FILENAME);
+            self.subset_records(indices).write_csv(&path)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+// Percent-encodes characters that are unsafe in a path component (or that would be ambiguous in a
+// `<label>=<value>` partition directory name).
+fn escape_path_component(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '/' | '\\' | '%' | '=' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' => {
+                write!(escaped, "%{:02X}", c as u32).unwrap();
+            }
+            c if c.is_control() => {
+                write!(escaped, "%{:02X}", c as u32).unwrap();
+            }
+            c => escaped.push(c),
+        }
+    }
+    if escaped == "." || escaped == ".." {
+        escaped = escaped.replace('.', "%2E");
+    }
+    escaped
+}
+
+#[cfg(feature = "test-utils")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cons::Nil;
+    use field::FieldData;
+    use store::DataStore;
+    use tempfile;
+    use test_utils::*;
+
+    #[test]
+    fn write_partitioned() {
+        let dir = tempfile::tempdir().unwrap();
+        let dv = sample_emp_table().into_view();
+        let paths = dv
+            .write_partitioned_csv::<emp_table::DeptId, _>(dir.path())
+            .unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                dir.path().join("DeptId=1/data.csv"),
+                dir.path().join("DeptId=2/data.csv"),
+                dir.path().join("DeptId=3/data.csv"),
+                dir.path().join("DeptId=4/data.csv"),
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(&paths[0]).unwrap(),
+            "EmpId,DeptId,EmpName\n0,1,Sally\n5,1,Bob\n6,1,Cara\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&paths[3]).unwrap(),
+            "EmpId,DeptId,EmpName\n9,4,Louise\n10,4,Ann\n"
+        );
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<emp_table::EmpName, _>(FieldData::from_field_vec(vec![
+                Value::Exists("a/b".to_string()),
+                Value::Na,
+            ]))
+            .into_view();
+        let paths = dv
+            .write_partitioned_csv::<emp_table::EmpName, _>(dir.path())
+            .unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                dir.path().join("EmpName=a%2Fb/data.csv"),
+                dir.path()
+                    .join("EmpName=__HIVE_DEFAULT_PARTITION__/data.csv"),
+            ]
+        );
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(escape_path_component("2019"), "2019");
+        assert_eq!(escape_path_component("a=b/c%"), "a%3Db%2Fc%25");
+        assert_eq!(escape_path_component(".."), "%2E%2E");
+        assert_eq!(escape_path_component(""), "");
+    }
+}
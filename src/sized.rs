@@ -0,0 +1,187 @@
+/*!
+Views with type-level row counts, for merging without runtime length checks.
+
+[DataView::merge](../view/struct.DataView.html#method.merge) fails at runtime if the two views have
+different numbers of rows. For static pipelines over sources of known, fixed size, a
+[SizedView](struct.SizedView.html) tracks the number of rows of a view in its type (as a `typenum`
+unsigned integer). The row count is checked once, when the `SizedView` is created with
+[DataView::sized](../view/struct.DataView.html#method.sized); afterwards, operations which preserve
+the number of rows (subviews, relabeling, and merging) preserve the row count type, so merging two
+`SizedView`s cannot fail, and merging views of different sizes is a compile-time error.
+
+# Example
+
+```
+# #[macro_use] extern crate agnes;
+# extern crate typenum;
+# #[cfg(feature = "test-utils")]
+# fn main() {
+use agnes::test_utils::*;
+use typenum::U7;
+
+let emps = sample_emp_table().into_view().sized::<U7>().unwrap();
+let extra = sample_emp_table_extra().into_view().sized::<U7>().unwrap();
+let merged = emps.merge(&extra.v::<Labels![extra_emp::SalaryOffset]>());
+assert_eq!(merged.nrows(), 7);
+# }
+# #[cfg(not(feature = "test-utils"))]
+# fn main() {}
+```
+
+Merging views with different row counts does not compile:
+
+```compile_fail
+# #[macro_use] extern crate agnes;
+# extern crate typenum;
+# #[cfg(feature = "test-utils")]
+# fn main() {
+use agnes::test_utils::*;
+use typenum::{U4, U7};
+
+let emps = sample_emp_table().into_view().sized::<U7>().unwrap();
+let depts = sample_dept_table().into_view().sized::<U4>().unwrap();
+let merged = emps.merge(&depts.v::<Labels![dept_table::DeptName]>());
+# }
+# #[cfg(not(feature = "test-utils"))]
+# fn main() { compile_error!("requires test-utils"); }
+```
+*/
+
+use std::marker::PhantomData;
+
+use typenum::Unsigned;
+
+use access::NRows;
+use error::*;
+use join::Merge;
+use label::{IsLabelSet, True};
+use view::{DataView, Relabel, Subview};
+
+/// Type alias for the `DataView` resulting from merging `View` with a `DataView<RLabels, RFrames>`.
+pub type MergedViewOf<View, RLabels, RFrames> = DataView<
+    <View as Merge<RLabels, RFrames>>::OutLabels,
+    <View as Merge<RLabels, RFrames>>::OutFrames,
+>;
+
+/// A [DataView](../view/struct.DataView.html) whose number of rows `N` (a `typenum` unsigned
+/// integer) is tracked in its type. See the [module-level documentation](index.html) for details.
+#[derive(Debug, Clone)]
+pub struct SizedView<View, N> {
+    view: View,
+    _nrows: PhantomData<N>,
+}
+
+impl<View, N> SizedView<View, N>
+where
+    N: Unsigned,
+{
+    /// Returns the number of rows in this view (`N`).
+    pub fn nrows(&self) -> usize {
+        N::USIZE
+    }
+    /// Returns a reference to the underlying `DataView`.
+    pub fn view(&self) -> &View {
+        &self.view
+    }
+    /// Returns the underlying `DataView`, discarding the type-level row count.
+    pub fn into_view(self) -> View {
+        self.view
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames>
+where
+    Frames: NRows,
+{
+    /// Converts this `DataView` into a [SizedView](../sized/struct.SizedView.html) with `N` rows,
+    /// checking that this view has exactly `N` rows.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::LengthMismatch` if this view does not have `N` rows.
+    pub fn sized<N: Unsigned>(self) -> Result<SizedView<Self, N>> {
+        if self.nrows() != N::USIZE {
+            return Err(AgnesError::LengthMismatch {
+                expected: N::USIZE,
+                actual: self.nrows(),
+            });
+        }
+        Ok(SizedView {
+            view: self,
+            _nrows: PhantomData,
+        })
+    }
+}
+
+impl<Labels, Frames, N> SizedView<DataView<Labels, Frames>, N> {
+    /// Merge this view with another view with the same number of rows `N`, creating a new
+    /// `SizedView` with all the fields from both views. Unlike
+    /// [DataView::merge](../view/struct.DataView.html#method.merge), this cannot fail.
+    pub fn merge<RLabels, RFrames>(
+        &self,
+        right: &SizedView<DataView<RLabels, RFrames>, N>,
+    ) -> SizedView<MergedViewOf<DataView<Labels, Frames>, RLabels, RFrames>, N>
+    where
+        DataView<Labels, Frames>: Merge<RLabels, RFrames>,
+        <DataView<Labels, Frames> as Merge<RLabels, RFrames>>::OutLabels: IsLabelSet<IsSet = True>,
+    {
+        SizedView {
+            view: Merge::merge(&self.view, &right.view),
+            _nrows: PhantomData,
+        }
+    }
+
+    /// Generate a new subview of this view (see
+    /// [DataView::v](../view/struct.DataView.html#method.v)) with the same number of rows `N`.
+    pub fn v<LabelList>(
+        &self,
+    ) -> SizedView<<DataView<Labels, Frames> as Subview<LabelList>>::Output, N>
+    where
+        DataView<Labels, Frames>: Subview<LabelList>,
+    {
+        SizedView {
+            view: Subview::<LabelList>::subview(&self.view),
+            _nrows: PhantomData,
+        }
+    }
+
+    /// Construct a new `SizedView` with the label `CurrLabel` relabeled with the label `NewLabel`
+    /// (see [DataView::relabel](../view/struct.DataView.html#method.relabel)).
+    pub fn relabel<CurrLabel, NewLabel>(
+        self,
+    ) -> SizedView<DataView<<Labels as Relabel<CurrLabel, NewLabel>>::Output, Frames>, N>
+    where
+        Labels: Relabel<CurrLabel, NewLabel>,
+    {
+        SizedView {
+            view: self.view.relabel::<CurrLabel, NewLabel>(),
+            _nrows: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn sized_merge() {
+        use typenum::{U3, U4, U7};
+
+        use access::DataIndex;
+        use select::FieldSelect;
+        use test_utils::*;
+
+        let emps = sample_emp_table().into_view();
+        assert!(emps.clone().sized::<U3>().is_err());
+        let emps = emps.sized::<U7>().unwrap();
+        let extra = sample_emp_table_extra().into_view().sized::<U7>().unwrap();
+        let merged = emps.merge(&extra.v::<Labels![extra_emp::SalaryOffset]>());
+        assert_eq!(merged.nrows(), 7);
+        assert_eq!(merged.view().nrows(), 7);
+        assert_eq!(
+            merged.view().field::<emp_table::EmpId>().to_vec(),
+            vec![0u64, 2, 5, 6, 8, 9, 10]
+        );
+        let depts = sample_dept_table().into_view().sized::<U4>().unwrap();
+        assert_eq!(depts.into_view().nrows(), 4);
+    }
+}
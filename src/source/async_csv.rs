@@ -0,0 +1,147 @@
+//! Asynchronous CSV reader objects and implementation. Requires the `net` feature.
+//!
+//! An [AsyncCsvReader](struct.AsyncCsvReader.html) streams its file contents (either from a local
+//! file or over HTTP / HTTPS) while a background blocking task concurrently tokenizes the CSV
+//! records as they arrive. Since loading returns a future, loading from several sources can be
+//! overlapped by driving multiple futures at once.
+
+use std::future::Future;
+use std::io::{self, Read};
+use std::pin::Pin;
+
+use bytes::{Buf, Bytes};
+use futures_util::future;
+use futures_util::{FutureExt, SinkExt, StreamExt, TryStreamExt};
+use tokio;
+use tokio::sync::mpsc;
+use tokio_util::sync::PollSender;
+
+use error::*;
+use observer::{NoObserver, Reporter, Stage};
+use source::csv::{BuildDStore, HeaderNormalization, IntoCsvSrcSchema, RecordOptions};
+use source::decode::{TextEncoding, TextReader};
+use source::file::{byte_stream, FileLocator};
+use source::stream::parse_records;
+use store::{AssocStorage, DataStore};
+
+// Number of file chunks which can be buffered between the streaming and parsing tasks.
+const CHANNEL_CAPACITY: usize = 16;
+
+/// Future returned when loading a `DataStore` from an asynchronous source.
+pub type LoadFuture<Fields> = Pin<Box<dyn Future<Output = Result<DataStore<Fields>>> + Send>>;
+
+/// A source that can asynchronously produce a [DataStore](../../store/struct.DataStore.html). See
+/// [DataStore::from_async_source](../../store/struct.DataStore.html#method.from_async_source).
+pub trait AsyncSource {
+    /// `Fields` type parameter of the resultant `DataStore`.
+    type OutputFields: AssocStorage;
+
+    /// Begin loading this source, returning a future that resolves to the loaded `DataStore`.
+    fn load(self) -> LoadFuture<Self::OutputFields>;
+}
+
+/// Object for asynchronously reading CSV sources.
+#[derive(Debug, Clone)]
+pub struct AsyncCsvReader<Schema> {
+    src: FileLocator,
+    schema: Schema,
+}
+
+impl<Schema> AsyncCsvReader<Schema> {
+    /// Create a new asynchronous CSV reader for the file at location `loc`, to be read with schema
+    /// `schema`. No data is read until this reader is loaded (with
+    /// [load](trait.AsyncSource.html#tymethod.load) or
+    /// [DataStore::from_async_source](../../store/struct.DataStore.html#method.from_async_source)).
+    pub fn new<L: Into<FileLocator>>(loc: L, schema: Schema) -> AsyncCsvReader<Schema> {
+        AsyncCsvReader {
+            src: loc.into(),
+            schema,
+        }
+    }
+}
+
+impl<Schema> AsyncSource for AsyncCsvReader<Schema>
+where
+    Schema: IntoCsvSrcSchema + Send + 'static,
+    Schema::CsvSrcSchema: BuildDStore + Send + 'static,
+    <Schema::CsvSrcSchema as BuildDStore>::OutputFields: 'static,
+{
+    type OutputFields = <Schema::CsvSrcSchema as BuildDStore>::OutputFields;
+
+    /// Begin loading this CSV source. The returned future must be polled from within a Tokio
+    /// runtime.
+    ///
+    /// # Errors
+    /// The future fails if unable to read the file contents, if CSV analysis fails, if the fields
+    /// specified in the schema do not exist in the CSV file, or if unable to parse the field
+    /// values.
+    fn load(self) -> LoadFuture<Self::OutputFields> {
+        let AsyncCsvReader { src, schema } = self;
+
+        // defer spawning the streaming and parsing tasks until first polled
+        Box::pin(
+            future::lazy(move |_| {
+                let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+
+                // stream the file contents into the channel; a `None` error signifies that the
+                // parsing task has stopped reading
+                let stream = byte_stream(&src)
+                    .map_err(Some)
+                    .forward(PollSender::new(tx).sink_map_err(|_| None));
+                let parse = tokio::task::spawn_blocking(move || {
+                    let mut observer = NoObserver;
+                    let mut reporter = Reporter::new(&mut observer, Stage::Read, None);
+                    parse_records(
+                        TextReader::new(ChannelReader::new(rx), TextEncoding::Auto),
+                        schema,
+                        &HeaderNormalization::new(),
+                        RecordOptions::default(),
+                        &mut reporter,
+                    )
+                });
+                future::join(stream, parse)
+            })
+            .flatten()
+            .map(|(stream_result, parse_result)| {
+                match (stream_result, parse_result) {
+                    (Err(Some(err)), _) => Err(err),
+                    (_, Err(err)) => Err(err.into()),
+                    (_, Ok(Err(err))) => Err(err),
+                    (_, Ok(Ok((mut csv_src_schema, records, projection)))) => {
+                        csv_src_schema.build_from_projected_records(&records, &projection)
+                    }
+                }
+            }),
+        )
+    }
+}
+
+// Blocking reader of file chunks sent by the streaming task.
+struct ChannelReader {
+    rx: mpsc::Receiver<Bytes>,
+    chunk: Bytes,
+}
+
+impl ChannelReader {
+    fn new(rx: mpsc::Receiver<Bytes>) -> ChannelReader {
+        ChannelReader {
+            rx,
+            chunk: Bytes::new(),
+        }
+    }
+}
+
+impl Read for ChannelReader {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        while self.chunk.is_empty() {
+            match self.rx.blocking_recv() {
+                Some(chunk) => self.chunk = chunk,
+                None => return Ok(0),
+            }
+        }
+        let n = out.len().min(self.chunk.len());
+        out[..n].copy_from_slice(&self.chunk[..n]);
+        self.chunk.advance(n);
+        Ok(n)
+    }
+}
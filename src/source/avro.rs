@@ -0,0 +1,1091 @@
+//! Avro object container file source and reader objects and implementation. Requires the `avro`
+//! feature.
+//!
+//! The records of an [Avro](https://avro.apache.org/docs/current/spec.html) file are mapped to
+//! labels with the [schema](../../macro.schema.html) macro, using the names (`fieldname`) or
+//! positions (`fieldindex`) of fields in the file's record schema. The Avro type of each mapped
+//! field is validated against the data type of its label when creating an
+//! [AvroReader](struct.AvroReader.html):
+//!
+//! * `String` fields accept Avro `string` and `enum` fields.
+//! * `bool` fields accept Avro `boolean` fields.
+//! * `i32` fields accept Avro `int` fields, and `i64`, `u32`, and `u64` fields accept Avro `int`
+//!   and `long` fields (values out of range for unsigned fields fail to load).
+//! * `f32` fields accept Avro `float` fields, and `f64` fields accept Avro `float` and `double`
+//!   fields.
+//!
+//! A field may also be a union of `null` and one of the above types, in which case `null` values
+//! are loaded as missing (NA) values. Fields of other types (records, arrays, maps) cannot be
+//! mapped to a label, but may appear in the file's schema. Files may be uncompressed (`null`
+//! codec) or compressed with the `deflate` or `snappy` codecs; files using other codecs fail to load
+//! with `AgnesError::UnsupportedCodec`.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt::Debug;
+use std::io::Read;
+use std::path::PathBuf;
+
+use miniz_oxide::inflate::decompress_to_vec;
+use serde_json;
+
+use cons::*;
+use error::*;
+use field::FieldIdent;
+use fieldlist::{FieldDesignator, FieldPayloadCons, FieldSchema, SchemaCons};
+use frame::SimpleFrameFields;
+use label::{TypedValue, Valued};
+use source::file::{FileLocator, LocalFileReader};
+use store::{AssocFrameLookup, AssocStorage, DataStore, IntoView, PushFrontFromValueIter};
+use value::Value;
+
+pub(crate) const MAGIC: &[u8] = b"Obj\x01";
+
+/// Non-union Avro type of a record field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AvroType {
+    /// `null`
+    Null,
+    /// `boolean`
+    Boolean,
+    /// `int` (32-bit signed integer)
+    Int,
+    /// `long` (64-bit signed integer)
+    Long,
+    /// `float` (32-bit floating-point)
+    Float,
+    /// `double` (64-bit floating-point)
+    Double,
+    /// `bytes`
+    Bytes,
+    /// `string`
+    String,
+    /// `enum`, with its list of symbols
+    Enum(Vec<String>),
+    /// `fixed`, with its size in bytes
+    Fixed(usize),
+    /// `record`, with the names and types of its fields
+    Record(Vec<(String, AvroFieldType)>),
+    /// `array`, with the type of its items
+    Array(AvroFieldType),
+    /// `map`, with the type of its values
+    Map(AvroFieldType),
+}
+
+/// Type of an Avro record field: a list of union branches (with a single branch for non-union
+/// types).
+pub type AvroFieldType = Vec<AvroType>;
+
+/// A single decoded Avro value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AvroValue {
+    /// `null`
+    Null,
+    /// `boolean`
+    Boolean(bool),
+    /// `int`
+    Int(i32),
+    /// `long`
+    Long(i64),
+    /// `float`
+    Float(f32),
+    /// `double`
+    Double(f64),
+    /// `bytes` or `fixed`
+    Bytes(Vec<u8>),
+    /// `string` or `enum` symbol
+    String(String),
+    /// `record`, with the values of its fields
+    Record(Vec<AvroValue>),
+    /// `array`
+    Array(Vec<AvroValue>),
+    /// `map`
+    Map(Vec<(String, AvroValue)>),
+}
+
+fn avro_err<S: Into<String>>(msg: S) -> AgnesError {
+    AgnesError::Avro(msg.into())
+}
+
+// Named (record, enum, and fixed) types defined so far while parsing a schema, by name and full
+// name.
+type NamedTypes = HashMap<String, AvroType>;
+
+fn parse_type(json: &serde_json::Value, named: &mut NamedTypes) -> Result<AvroType> {
+    match *json {
+        serde_json::Value::String(ref name) => Ok(match name.as_str() {
+            "null" => AvroType::Null,
+            "boolean" => AvroType::Boolean,
+            "int" => AvroType::Int,
+            "long" => AvroType::Long,
+            "float" => AvroType::Float,
+            "double" => AvroType::Double,
+            "bytes" => AvroType::Bytes,
+            "string" => AvroType::String,
+            name => named
+                .get(name)
+                .cloned()
+                .ok_or_else(|| avro_err(format!("unsupported Avro type '{}'", name)))?,
+        }),
+        serde_json::Value::Object(ref obj) => {
+            let ty = match obj.get("type") {
+                Some(serde_json::Value::String(name)) if name == "enum" => obj
+                    .get("symbols")
+                    .and_then(|symbols| symbols.as_array())
+                    .and_then(|symbols| {
+                        symbols
+                            .iter()
+                            .map(|symbol| symbol.as_str().map(|s| s.to_string()))
+                            .collect::<Option<Vec<_>>>()
+                    })
+                    .map(AvroType::Enum)
+                    .ok_or_else(|| avro_err("invalid Avro enum symbols"))?,
+                Some(serde_json::Value::String(name)) if name == "fixed" => obj
+                    .get("size")
+                    .and_then(|size| size.as_u64())
+                    .map(|size| AvroType::Fixed(size as usize))
+                    .ok_or_else(|| avro_err("invalid Avro fixed size"))?,
+                Some(serde_json::Value::String(name)) if name == "record" => {
+                    AvroType::Record(parse_fields(json, named)?)
+                }
+                Some(serde_json::Value::String(name)) if name == "array" => {
+                    AvroType::Array(parse_field_type(
+                        obj.get("items")
+                            .ok_or_else(|| avro_err("Avro array missing 'items'"))?,
+                        named,
+                    )?)
+                }
+                Some(serde_json::Value::String(name)) if name == "map" => {
+                    AvroType::Map(parse_field_type(
+                        obj.get("values")
+                            .ok_or_else(|| avro_err("Avro map missing 'values'"))?,
+                        named,
+                    )?)
+                }
+                // other (e.g. logical) types annotating a type
+                Some(ty) => return parse_type(ty, named),
+                None => return Err(avro_err("Avro type missing 'type' attribute")),
+            };
+            if let Some(name) = obj.get("name").and_then(|name| name.as_str()) {
+                if let Some(namespace) = obj.get("namespace").and_then(|ns| ns.as_str()) {
+                    named.insert(format!("{}.{}", namespace, name), ty.clone());
+                }
+                named.insert(name.to_string(), ty.clone());
+            }
+            Ok(ty)
+        }
+        _ => Err(avro_err(format!("unsupported Avro type '{}'", json))),
+    }
+}
+
+fn parse_field_type(json: &serde_json::Value, named: &mut NamedTypes) -> Result<AvroFieldType> {
+    match *json {
+        serde_json::Value::Array(ref branches) => branches
+            .iter()
+            .map(|branch| parse_type(branch, named))
+            .collect(),
+        _ => Ok(vec![parse_type(json, named)?]),
+    }
+}
+
+// Parses the names and types of the fields of the record schema `json`.
+fn parse_fields(
+    json: &serde_json::Value,
+    named: &mut NamedTypes,
+) -> Result<Vec<(String, AvroFieldType)>> {
+    json.get("fields")
+        .and_then(|fields| fields.as_array())
+        .ok_or_else(|| avro_err("Avro record schema missing 'fields'"))?
+        .iter()
+        .map(|field| {
+            let name = field
+                .get("name")
+                .and_then(|name| name.as_str())
+                .ok_or_else(|| avro_err("Avro record field missing 'name'"))?;
+            let ty = field
+                .get("type")
+                .ok_or_else(|| avro_err(format!("Avro record field '{}' missing 'type'", name)))?;
+            Ok((name.to_string(), parse_field_type(ty, named)?))
+        })
+        .collect()
+}
+
+// Parses the (writer) schema of an Avro file into the names and types of its record fields.
+fn parse_schema(schema: &str) -> Result<Vec<(String, AvroFieldType)>> {
+    let json: serde_json::Value = serde_json::from_str(schema)
+        .map_err(|e| avro_err(format!("invalid Avro schema: {}", e)))?;
+    if json.get("type").and_then(|ty| ty.as_str()) != Some("record") {
+        return Err(avro_err("Avro schema is not a record schema"));
+    }
+    parse_fields(&json, &mut NamedTypes::new())
+}
+
+// Reader for Avro binary-encoded data.
+struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.data.len() - self.pos < n {
+            return Err(avro_err("unexpected end of Avro data"));
+        }
+        let bytes = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn long(&mut self) -> Result<i64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok((value >> 1) as i64 ^ -((value & 1) as i64));
+            }
+        }
+        Err(avro_err("invalid Avro variable-length integer"))
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        let len = self.long()?;
+        if len < 0 {
+            return Err(avro_err("negative Avro length"));
+        }
+        Ok(len as usize)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        String::from_utf8(self.bytes()?.to_vec())
+            .map_err(|_| avro_err("invalid UTF-8 in Avro string"))
+    }
+
+    fn value(&mut self, ty: &AvroType) -> Result<AvroValue> {
+        Ok(match *ty {
+            AvroType::Null => AvroValue::Null,
+            AvroType::Boolean => AvroValue::Boolean(self.take(1)?[0] != 0),
+            AvroType::Int => {
+                let value = self.long()?;
+                AvroValue::Int(
+                    i32::try_from(value)
+                        .map_err(|_| avro_err(format!("Avro int value {} out of range", value)))?,
+                )
+            }
+            AvroType::Long => AvroValue::Long(self.long()?),
+            AvroType::Float => {
+                let mut bytes = [0u8; 4];
+                bytes.copy_from_slice(self.take(4)?);
+                AvroValue::Float(f32::from_le_bytes(bytes))
+            }
+            AvroType::Double => {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(self.take(8)?);
+                AvroValue::Double(f64::from_le_bytes(bytes))
+            }
+            AvroType::Bytes => AvroValue::Bytes(self.bytes()?.to_vec()),
+            AvroType::String => AvroValue::String(self.string()?),
+            AvroType::Enum(ref symbols) => {
+                let idx = self.long()?;
+                AvroValue::String(
+                    symbols
+                        .get(idx as usize)
+                        .ok_or_else(|| avro_err("invalid Avro enum index"))?
+                        .clone(),
+                )
+            }
+            AvroType::Fixed(size) => AvroValue::Bytes(self.take(size)?.to_vec()),
+            AvroType::Record(ref fields) => AvroValue::Record(
+                fields
+                    .iter()
+                    .map(|(_, ty)| self.field_value(ty))
+                    .collect::<Result<_>>()?,
+            ),
+            AvroType::Array(ref items) => {
+                let mut values = vec![];
+                while let Some(count) = self.block_count()? {
+                    for _ in 0..count {
+                        values.push(self.field_value(items)?);
+                    }
+                }
+                AvroValue::Array(values)
+            }
+            AvroType::Map(ref items) => {
+                let mut values = vec![];
+                while let Some(count) = self.block_count()? {
+                    for _ in 0..count {
+                        values.push((self.string()?, self.field_value(items)?));
+                    }
+                }
+                AvroValue::Map(values)
+            }
+        })
+    }
+
+    // Reads the item count of the next block of an array or map, or `None` at the end of the
+    // array or map.
+    fn block_count(&mut self) -> Result<Option<u64>> {
+        let count = self.long()?;
+        if count < 0 {
+            // block size (in bytes) follows negative counts
+            self.long()?;
+        }
+        Ok(if count == 0 {
+            None
+        } else {
+            Some(count.unsigned_abs())
+        })
+    }
+
+    fn field_value(&mut self, ty: &AvroFieldType) -> Result<AvroValue> {
+        if ty.len() == 1 {
+            return self.value(&ty[0]);
+        }
+        let branch = self.long()?;
+        let branch = ty
+            .get(branch as usize)
+            .ok_or_else(|| avro_err("invalid Avro union branch"))?;
+        self.value(branch)
+    }
+}
+
+// Compression codec of the data blocks of an Avro file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Codec {
+    Null,
+    Deflate,
+    Snappy,
+}
+
+impl Codec {
+    // Decompresses the contents of a single data block.
+    fn decompress(self, block: &[u8]) -> Result<Cow<'_, [u8]>> {
+        match self {
+            Codec::Null => Ok(Cow::Borrowed(block)),
+            Codec::Deflate => decompress_to_vec(block)
+                .map(Cow::Owned)
+                .map_err(|e| avro_err(format!("invalid deflate-compressed Avro block: {}", e))),
+            Codec::Snappy => {
+                // compressed data is followed by the big-endian CRC-32 of the uncompressed data
+                if block.len() < 4 {
+                    return Err(avro_err("invalid snappy-compressed Avro block"));
+                }
+                let (compressed, checksum) = block.split_at(block.len() - 4);
+                let data = snappy_decompress(compressed)?;
+                if crc32(&data).to_be_bytes() != checksum {
+                    return Err(avro_err("snappy-compressed Avro block failed checksum"));
+                }
+                Ok(Cow::Owned(data))
+            }
+        }
+    }
+}
+
+// Decompresses a raw (unframed) snappy-compressed buffer.
+fn snappy_decompress(data: &[u8]) -> Result<Vec<u8>> {
+    let invalid = || avro_err("invalid snappy-compressed Avro block");
+    let mut decoder = Decoder { data, pos: 0 };
+    // uncompressed length, as an (unsigned) varint
+    let mut len = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = decoder.take(1).map_err(|_| invalid())?[0];
+        len |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+    let len = usize::try_from(len).map_err(|_| invalid())?;
+    let mut out = Vec::with_capacity(len.min(data.len().saturating_mul(8)));
+    while !decoder.is_empty() {
+        let tag = decoder.take(1)?[0];
+        let (offset, copy_len) = match tag & 0x03 {
+            0 => {
+                let mut literal_len = usize::from(tag >> 2);
+                if literal_len >= 60 {
+                    let nbytes = literal_len - 59;
+                    literal_len = decoder
+                        .take(nbytes)
+                        .map_err(|_| invalid())?
+                        .iter()
+                        .rev()
+                        .fold(0usize, |acc, &byte| (acc << 8) | usize::from(byte));
+                }
+                let literal = decoder.take(literal_len + 1).map_err(|_| invalid())?;
+                out.extend_from_slice(literal);
+                continue;
+            }
+            1 => {
+                let byte = decoder.take(1).map_err(|_| invalid())?[0];
+                (
+                    (usize::from(tag >> 5) << 8) | usize::from(byte),
+                    4 + usize::from((tag >> 2) & 0x07),
+                )
+            }
+            2 => {
+                let bytes = decoder.take(2).map_err(|_| invalid())?;
+                (
+                    usize::from(u16::from_le_bytes([bytes[0], bytes[1]])),
+                    1 + usize::from(tag >> 2),
+                )
+            }
+            _ => {
+                let bytes = decoder.take(4).map_err(|_| invalid())?;
+                let offset = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                (
+                    usize::try_from(offset).map_err(|_| invalid())?,
+                    1 + usize::from(tag >> 2),
+                )
+            }
+        };
+        if offset == 0 || offset > out.len() {
+            return Err(invalid());
+        }
+        // copies may overlap their own output, so are made one byte at a time
+        let start = out.len() - offset;
+        for idx in start..start + copy_len {
+            let byte = out[idx];
+            out.push(byte);
+        }
+    }
+    if out.len() != len {
+        return Err(invalid());
+    }
+    Ok(out)
+}
+
+// CRC-32 (IEEE) checksum of `data`.
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ u32::from(byte), |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+/// Avro data source. Contains the (in-memory) contents of an Avro object container file, and the
+/// names and types of its record fields. Can be turned into an `AvroReader` object.
+#[derive(Debug, Clone)]
+pub struct AvroSource {
+    // File source object for the Avro file
+    src: FileLocator,
+    // Names and types of the fields of the file's record schema
+    fields: Vec<(String, AvroFieldType)>,
+    // Compression codec of the data blocks
+    codec: Codec,
+    // Contents of the file
+    data: Vec<u8>,
+    // Position of the first data block within `data`
+    blocks_start: usize,
+    // Sync marker separating data blocks
+    sync: [u8; 16],
+}
+
+impl AvroSource {
+    /// Create a new `AvroSource` object with provided file location. This constructor reads the
+    /// file and parses its header (including the record schema).
+    ///
+    /// # Error
+    /// Fails if unable to read the file, if the file is not an Avro object container file, if
+    /// the file is compressed with an unsupported codec (with `AgnesError::UnsupportedCodec`), or
+    /// if its schema is not a valid record schema.
+    pub fn new<L: Into<FileLocator>>(loc: L) -> Result<AvroSource> {
+        let loc = loc.into();
+        let mut data = vec![];
+        LocalFileReader::new(&loc)?.read_to_end(&mut data)?;
+
+        let mut decoder = Decoder {
+            data: &data,
+            pos: 0,
+        };
+        if decoder.take(MAGIC.len()).ok() != Some(MAGIC) {
+            return Err(avro_err("not an Avro object container file"));
+        }
+        let mut metadata = HashMap::new();
+        loop {
+            let count = decoder.long()?;
+            if count == 0 {
+                break;
+            }
+            if count < 0 {
+                // block size (in bytes) follows negative counts
+                decoder.long()?;
+            }
+            for _ in 0..count.abs() {
+                let key = decoder.string()?;
+                let value = decoder.bytes()?.to_vec();
+                metadata.insert(key, value);
+            }
+        }
+        let codec = match metadata.get("avro.codec").map(|codec| &codec[..]) {
+            None | Some(b"null") => Codec::Null,
+            Some(b"deflate") => Codec::Deflate,
+            Some(b"snappy") => Codec::Snappy,
+            Some(codec) => {
+                return Err(AgnesError::UnsupportedCodec(
+                    String::from_utf8_lossy(codec).into_owned(),
+                ));
+            }
+        };
+        let schema = metadata
+            .get("avro.schema")
+            .ok_or_else(|| avro_err("Avro file missing schema"))?;
+        let fields = parse_schema(&String::from_utf8_lossy(schema))?;
+        let mut sync = [0u8; 16];
+        sync.copy_from_slice(decoder.take(16)?);
+        let blocks_start = decoder.pos;
+
+        Ok(AvroSource {
+            src: loc,
+            fields,
+            codec,
+            data,
+            blocks_start,
+            sync,
+        })
+    }
+    /// Return the names and types of the record fields of this Avro source.
+    pub fn fields(&self) -> &[(String, AvroFieldType)] {
+        &self.fields
+    }
+    /// Return the location of this Avro source.
+    pub fn locator(&self) -> &FileLocator {
+        &self.src
+    }
+
+    // Decodes every record of this source.
+    fn records(&self) -> Result<Vec<Vec<AvroValue>>> {
+        let mut decoder = Decoder {
+            data: &self.data,
+            pos: self.blocks_start,
+        };
+        let mut records = vec![];
+        while !decoder.is_empty() {
+            let count = decoder.len()?;
+            let block = self.codec.decompress(decoder.bytes()?)?;
+            let mut block = Decoder {
+                data: &block,
+                pos: 0,
+            };
+            for _ in 0..count {
+                records.push(
+                    self.fields
+                        .iter()
+                        .map(|(_, ty)| block.field_value(ty))
+                        .collect::<Result<Vec<_>>>()?,
+                );
+            }
+            if decoder.take(16)? != self.sync {
+                return Err(avro_err("invalid Avro sync marker"));
+            }
+        }
+        Ok(records)
+    }
+}
+
+/// A trait for data types which can be loaded from Avro values.
+pub trait FromAvro: Sized {
+    /// Returns whether or not values of Avro type `ty` can be loaded as this data type.
+    fn accepts(ty: &AvroType) -> bool;
+    /// Converts a (non-null) Avro value into this data type. Fails if the value is of the wrong
+    /// type or out of range.
+    fn from_avro(value: AvroValue) -> Result<Self>;
+}
+
+macro_rules! impl_from_avro {
+    ($dtype:ty: $($avro_ty:ident => $convert:expr),*) => {
+        impl FromAvro for $dtype {
+            fn accepts(ty: &AvroType) -> bool {
+                match *ty {
+                    $(AvroType::$avro_ty { .. } => true,)*
+                    _ => false,
+                }
+            }
+            #[allow(clippy::redundant_closure_call)]
+            fn from_avro(value: AvroValue) -> Result<$dtype> {
+                match value {
+                    $(AvroValue::$avro_ty(value) => ($convert)(value),)*
+                    value => Err(avro_err(format!(
+                        "unable to load Avro value {:?} as {}",
+                        value,
+                        stringify![$dtype]
+                    ))),
+                }
+            }
+        }
+    };
+}
+impl_from_avro![bool: Boolean => Ok];
+impl_from_avro![i32: Int => Ok];
+impl_from_avro![i64: Int => |v| Ok(i64::from(v)), Long => Ok];
+impl_from_avro![f32: Float => Ok];
+impl_from_avro![f64: Float => |v| Ok(f64::from(v)), Double => Ok];
+
+macro_rules! impl_from_avro_unsigned {
+    ($($dtype:ty)*) => {$(
+        impl FromAvro for $dtype {
+            fn accepts(ty: &AvroType) -> bool {
+                match *ty {
+                    AvroType::Int | AvroType::Long => true,
+                    _ => false,
+                }
+            }
+            fn from_avro(value: AvroValue) -> Result<$dtype> {
+                let value = match value {
+                    AvroValue::Int(value) => i64::from(value),
+                    AvroValue::Long(value) => value,
+                    value => {
+                        return Err(avro_err(format!(
+                            "unable to load Avro value {:?} as {}",
+                            value,
+                            stringify![$dtype]
+                        )));
+                    }
+                };
+                if value < 0 || value as u64 > <$dtype>::MAX as u64 {
+                    return Err(avro_err(format!(
+                        "Avro value {} out of range for {}",
+                        value,
+                        stringify![$dtype]
+                    )));
+                }
+                Ok(value as $dtype)
+            }
+        }
+    )*}
+}
+impl_from_avro_unsigned![u32 u64];
+
+impl FromAvro for String {
+    fn accepts(ty: &AvroType) -> bool {
+        matches!(*ty, AvroType::String | AvroType::Enum(_))
+    }
+    fn from_avro(value: AvroValue) -> Result<String> {
+        match value {
+            AvroValue::String(value) => Ok(value),
+            value => Err(avro_err(format!(
+                "unable to load Avro value {:?} as String",
+                value
+            ))),
+        }
+    }
+}
+
+/// Type alias for [Cons](../../cons/struct.Cons.html)-list specifying label, data type, and source
+/// field index information of an Avro data source.
+pub type AvroSrcSchemaCons<Label, DType, Tail> = FieldPayloadCons<Label, DType, usize, Tail>;
+
+/// A trait for converting an object into an [AvroSrcSchemaCons](type.AvroSrcSchemaCons.html),
+/// validating the types of the Avro fields.
+pub trait IntoAvroSrcSchema {
+    /// Resultant `AvroSrcSchemaCons` object.
+    type AvroSrcSchema;
+
+    /// Convert this into an `AvroSrcSchemaCons` cons-list, given the names and types of the
+    /// record fields of the Avro source.
+    fn into_avro_src_schema(
+        self,
+        fields: &[(String, AvroFieldType)],
+    ) -> Result<Self::AvroSrcSchema>;
+}
+impl IntoAvroSrcSchema for Nil {
+    type AvroSrcSchema = Nil;
+
+    fn into_avro_src_schema(self, _fields: &[(String, AvroFieldType)]) -> Result<Nil> {
+        Ok(Nil)
+    }
+}
+impl<Label, DType, Tail> IntoAvroSrcSchema for SchemaCons<Label, DType, Tail>
+where
+    Tail: IntoAvroSrcSchema,
+    DType: FromAvro,
+{
+    type AvroSrcSchema = AvroSrcSchemaCons<Label, DType, Tail::AvroSrcSchema>;
+
+    fn into_avro_src_schema(
+        self,
+        fields: &[(String, AvroFieldType)],
+    ) -> Result<AvroSrcSchemaCons<Label, DType, Tail::AvroSrcSchema>> {
+        let idx = match *self.head.value_ref() {
+            FieldDesignator::Expr(ref s) => fields
+                .iter()
+                .position(|(name, _)| name == s)
+                .ok_or_else(|| AgnesError::FieldNotFound(FieldIdent::Name(s.to_string())))?,
+            FieldDesignator::Idx(idx) => {
+                if idx >= fields.len() {
+                    return Err(AgnesError::IndexError {
+                        index: idx,
+                        len: fields.len(),
+                    });
+                }
+                idx
+            }
+        };
+        let (ref name, ref ty) = fields[idx];
+        let valid = ty
+            .iter()
+            .all(|branch| *branch == AvroType::Null || DType::accepts(branch))
+            && ty.iter().any(|branch| *branch != AvroType::Null);
+        if !valid {
+            return Err(avro_err(format!(
+                "Avro field '{}' of type {:?} cannot be loaded as the data type of its label",
+                name, ty
+            )));
+        }
+        Ok(Cons {
+            head: TypedValue::from(idx).into(),
+            tail: self.tail.into_avro_src_schema(fields)?,
+        })
+    }
+}
+
+/// A trait for building a [DataStore](../../store/struct.DataStore.html) from an
+/// [AvroSrcSchemaCons](type.AvroSrcSchemaCons.html).
+pub trait BuildAvroStore {
+    /// `Fields` type parameter of the resultant `DataStore`.
+    type OutputFields: AssocStorage;
+
+    /// Builds a `DataStore` from the source schema (`self`) and a set of decoded Avro records.
+    fn build_from_avro_records(
+        &self,
+        records: &[Vec<AvroValue>],
+    ) -> Result<DataStore<Self::OutputFields>>;
+}
+impl BuildAvroStore for Nil {
+    type OutputFields = Nil;
+    fn build_from_avro_records(&self, _records: &[Vec<AvroValue>]) -> Result<DataStore<Nil>> {
+        Ok(DataStore::<Nil>::empty())
+    }
+}
+impl<Label, DType, Tail> BuildAvroStore for AvroSrcSchemaCons<Label, DType, Tail>
+where
+    Tail: BuildAvroStore,
+    DataStore<<Tail as BuildAvroStore>::OutputFields>: PushFrontFromValueIter<Label, DType>,
+    Tail::OutputFields: PushBack<FieldSchema<Label, DType>>,
+    <Tail::OutputFields as PushBack<FieldSchema<Label, DType>>>::Output: AssocStorage,
+    Label: Debug,
+    DType: FromAvro + Debug + Default + Clone,
+{
+    type OutputFields =
+        <DataStore<<Tail as BuildAvroStore>::OutputFields> as PushFrontFromValueIter<
+            Label,
+            DType,
+        >>::OutputFields;
+
+    fn build_from_avro_records(
+        &self,
+        records: &[Vec<AvroValue>],
+    ) -> Result<DataStore<Self::OutputFields>> {
+        let ds = self.tail.build_from_avro_records(records)?;
+
+        let idx = *self.head.value_ref();
+        let values: Vec<Value<DType>> = records
+            .iter()
+            .map(|record| match record[idx] {
+                AvroValue::Null => Ok(Value::Na),
+                ref value => DType::from_avro(value.clone()).map(Value::Exists),
+            })
+            .collect::<Result<_>>()?;
+        let ds = ds.push_front_from_value_iter::<Label, DType, _, _>(values);
+
+        Ok(ds)
+    }
+}
+
+/// Object for reading Avro sources.
+#[derive(Debug)]
+pub struct AvroReader<AvroSrcSchema> {
+    src: AvroSource,
+    avro_src_schema: AvroSrcSchema,
+}
+
+impl<AvroSrcSchema> AvroReader<AvroSrcSchema>
+where
+    AvroSrcSchema: Debug,
+{
+    /// Create a new Avro reader from an Avro source specification. This will verify that the
+    /// fields specified in `schema` exist in the source, and that their Avro types can be loaded
+    /// as the data types of their labels.
+    pub fn new<Schema>(
+        src: &AvroSource,
+        schema: Schema,
+    ) -> Result<AvroReader<Schema::AvroSrcSchema>>
+    where
+        Schema: IntoAvroSrcSchema<AvroSrcSchema = AvroSrcSchema>,
+    {
+        let avro_src_schema = schema.into_avro_src_schema(&src.fields)?;
+        Ok(AvroReader {
+            src: src.clone(),
+            avro_src_schema,
+        })
+    }
+
+    /// Read an `AvroSource` into a `DataStore` object.
+    ///
+    /// # Error
+    /// Fails if the Avro data is invalid, or if a value is out of range for its label's data type.
+    pub fn read(&mut self) -> Result<DataStore<AvroSrcSchema::OutputFields>>
+    where
+        AvroSrcSchema: BuildAvroStore,
+    {
+        self.avro_src_schema
+            .build_from_avro_records(&self.src.records()?)
+    }
+}
+
+/// Utility function for loading an Avro file from a [FileLocator](../file/enum.FileLocator.html).
+///
+/// Fails if unable to find or read file at location specified, or if the file does not match
+/// `schema`.
+pub fn load_avro<L: Into<FileLocator>, Schema>(
+    loc: L,
+    schema: Schema,
+) -> Result<<DataStore<<Schema::AvroSrcSchema as BuildAvroStore>::OutputFields> as IntoView>::Output>
+where
+    Schema: IntoAvroSrcSchema,
+    Schema::AvroSrcSchema: BuildAvroStore + Debug,
+    <Schema::AvroSrcSchema as BuildAvroStore>::OutputFields: AssocFrameLookup + SimpleFrameFields,
+{
+    let source = AvroSource::new(loc)?;
+    let mut avro_reader = AvroReader::new(&source, schema)?;
+    Ok(avro_reader.read()?.into_view())
+}
+
+/// Utility function for loading an Avro file from a local file path.
+///
+/// Fails if unable to find or read file at the location specified, or if the file does not match
+/// `schema`.
+pub fn load_avro_from_path<P, Schema>(
+    path: P,
+    schema: Schema,
+) -> Result<<DataStore<<Schema::AvroSrcSchema as BuildAvroStore>::OutputFields> as IntoView>::Output>
+where
+    P: Into<PathBuf>,
+    Schema: IntoAvroSrcSchema,
+    Schema::AvroSrcSchema: BuildAvroStore + Debug,
+    <Schema::AvroSrcSchema as BuildAvroStore>::OutputFields: AssocFrameLookup + SimpleFrameFields,
+{
+    load_avro(path.into(), schema)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use access::DataIndex;
+    use select::FieldSelect;
+
+    tablespace![
+        table avro_test {
+            Id: i64,
+            Kind: String,
+            Score: f64,
+        }
+    ];
+
+    // zig-zag variable-length encoding of an Avro `long`
+    fn long(value: i64) -> Vec<u8> {
+        let mut value = ((value << 1) ^ (value >> 63)) as u64;
+        let mut bytes = vec![];
+        while value >= 0x80 {
+            bytes.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        bytes.push(value as u8);
+        bytes
+    }
+
+    #[test]
+    fn read_avro() {
+        let schema = r#"{"type": "record", "name": "t", "fields": [
+            {"name": "id", "type": "long"},
+            {"name": "kind", "type": {"type": "enum", "name": "k", "symbols": ["a", "b"]}},
+            {"name": "score", "type": ["double", "null"]}
+        ]}"#;
+        let sync = [0xab; 16];
+        let mut data = MAGIC.to_vec();
+        data.extend(long(1));
+        data.extend(long(11));
+        data.extend_from_slice(b"avro.schema");
+        data.extend(long(schema.len() as i64));
+        data.extend_from_slice(schema.as_bytes());
+        data.extend(long(0));
+        data.extend_from_slice(&sync);
+
+        let mut block = vec![];
+        block.extend(long(1));
+        block.extend(long(1));
+        block.extend(long(0));
+        block.extend_from_slice(&0.5f64.to_le_bytes());
+        block.extend(long(-200));
+        block.extend(long(0));
+        block.extend(long(1));
+        data.extend(long(2));
+        data.extend(long(block.len() as i64));
+        data.extend(block);
+        data.extend_from_slice(&sync);
+
+        let source = AvroSource::new(data.clone()).unwrap();
+        assert_eq!(source.fields().len(), 3);
+        assert_eq!(
+            source.fields()[1].1,
+            vec![AvroType::Enum(vec!["a".into(), "b".into()])]
+        );
+        let dv = AvroReader::new(
+            &source,
+            schema![
+                fieldname avro_test::Id = "id";
+                fieldname avro_test::Kind = "kind";
+                fieldname avro_test::Score = "score";
+            ],
+        )
+        .unwrap()
+        .read()
+        .unwrap()
+        .into_view();
+        assert_eq!(dv.field::<avro_test::Id>().to_vec(), vec![1, -200]);
+        assert_eq!(dv.field::<avro_test::Kind>().to_vec(), vec!["b", "a"]);
+        assert_eq!(
+            dv.field::<avro_test::Score>().to_value_vec(),
+            vec![Value::Exists(0.5), Value::Na]
+        );
+
+        // corrupt sync marker
+        let len = data.len();
+        data[len - 1] = 0;
+        let source = AvroSource::new(data).unwrap();
+        assert!(
+            AvroReader::new(&source, schema![fieldname avro_test::Id = "id";])
+                .unwrap()
+                .read()
+                .is_err()
+        );
+    }
+
+    // Avro object container file with a single data block of `count` records (`block`,
+    // compressed with `codec`).
+    fn container(schema: &str, codec: &str, count: i64, block: &[u8]) -> Vec<u8> {
+        let sync = [0xcd; 16];
+        let mut data = MAGIC.to_vec();
+        data.extend(long(2));
+        for (key, value) in [("avro.schema", schema), ("avro.codec", codec)] {
+            data.extend(long(key.len() as i64));
+            data.extend_from_slice(key.as_bytes());
+            data.extend(long(value.len() as i64));
+            data.extend_from_slice(value.as_bytes());
+        }
+        data.extend(long(0));
+        data.extend_from_slice(&sync);
+        data.extend(long(count));
+        data.extend(long(block.len() as i64));
+        data.extend_from_slice(block);
+        data.extend_from_slice(&sync);
+        data
+    }
+
+    fn read_ids(data: Vec<u8>) -> Result<Vec<i64>> {
+        let source = AvroSource::new(data)?;
+        let dv = AvroReader::new(&source, schema![fieldname avro_test::Id = "id";])?
+            .read()?
+            .into_view();
+        Ok(dv.field::<avro_test::Id>().to_vec())
+    }
+
+    const ID_SCHEMA: &str =
+        r#"{"type": "record", "name": "t", "fields": [{"name": "id", "type": "long"}]}"#;
+
+    #[test]
+    fn codecs() {
+        let block = [long(7), long(-3), long(7)].concat();
+        assert_eq!(
+            read_ids(container(ID_SCHEMA, "null", 3, &block)).unwrap(),
+            vec![7, -3, 7]
+        );
+
+        let deflated = ::miniz_oxide::deflate::compress_to_vec(&block, 6);
+        assert_eq!(
+            read_ids(container(ID_SCHEMA, "deflate", 3, &deflated)).unwrap(),
+            vec![7, -3, 7]
+        );
+
+        // a 2-byte literal followed by a (2-byte offset) copy of the first value
+        let mut snappy = vec![3, 1 << 2, block[0], block[1], 0x02, 2, 0];
+        snappy.extend_from_slice(&crc32(&block).to_be_bytes());
+        assert_eq!(
+            read_ids(container(ID_SCHEMA, "snappy", 3, &snappy)).unwrap(),
+            vec![7, -3, 7]
+        );
+        let len = snappy.len();
+        snappy[len - 1] ^= 0xff;
+        assert!(read_ids(container(ID_SCHEMA, "snappy", 3, &snappy)).is_err());
+
+        match AvroSource::new(container(ID_SCHEMA, "zstandard", 3, &block)) {
+            Err(AgnesError::UnsupportedCodec(ref codec)) => assert_eq!(codec, "zstandard"),
+            _ => panic!("expected unsupported codec error"),
+        }
+    }
+
+    #[test]
+    fn nested_fields() {
+        let schema = r#"{"type": "record", "name": "t", "fields": [
+            {"name": "loc", "type": {"type": "record", "name": "point", "fields": [
+                {"name": "x", "type": "double"}, {"name": "y", "type": "double"}
+            ]}},
+            {"name": "tags", "type": {"type": "array", "items": "string"}},
+            {"name": "attrs", "type": ["null", {"type": "map", "values": "point"}]},
+            {"name": "id", "type": "long"}
+        ]}"#;
+        let mut block = vec![];
+        block.extend_from_slice(&1.0f64.to_le_bytes());
+        block.extend_from_slice(&2.0f64.to_le_bytes());
+        // array of two strings, as a single block with a negative count (and its size)
+        block.extend(
+            [
+                long(-2),
+                long(4),
+                long(1),
+                b"a".to_vec(),
+                long(1),
+                b"b".to_vec(),
+            ]
+            .concat(),
+        );
+        block.extend(long(0));
+        // map with one entry
+        block.extend([long(1), long(1), long(1), b"k".to_vec()].concat());
+        block.extend_from_slice(&3.0f64.to_le_bytes());
+        block.extend_from_slice(&4.0f64.to_le_bytes());
+        block.extend([long(0), long(42)].concat());
+
+        // unmapped nested fields are decoded (and skipped)
+        let data = container(schema, "null", 1, &block);
+        assert_eq!(read_ids(data.clone()).unwrap(), vec![42]);
+        // nested fields cannot be mapped
+        let source = AvroSource::new(data).unwrap();
+        assert!(AvroReader::new(&source, schema![fieldname avro_test::Id = "loc";]).is_err());
+    }
+
+    #[test]
+    fn int_range() {
+        let schema =
+            r#"{"type": "record", "name": "t", "fields": [{"name": "id", "type": "int"}]}"#;
+        assert_eq!(
+            read_ids(container(schema, "null", 1, &long(-5))).unwrap(),
+            vec![-5]
+        );
+        assert!(read_ids(container(schema, "null", 1, &long(1 << 40))).is_err());
+    }
+}
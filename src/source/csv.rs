@@ -1,23 +1,524 @@
 //! CSV-based source and reader objects and implentation.
 
-use std::collections::HashMap;
-use std::fmt::Debug;
+use std::any::{type_name, TypeId};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Debug};
+use std::io::{self, BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::iter;
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use csv_sniffer::metadata::Metadata;
+use csv::{ByteRecord, Reader, ReaderBuilder};
+use csv_sniffer::metadata::{Dialect, Metadata};
 use csv_sniffer::Sniffer;
+pub use csv_sniffer::{SampleSize, Type};
+use glob;
 
 use cons::*;
 use error::*;
 use field::FieldIdent;
 use fieldlist::{FieldDesignator, FieldPayloadCons, FieldSchema, SchemaCons};
 use frame::SimpleFrameFields;
-use label::{TypedValue, Valued};
-use source::decode::decode;
-use source::file::{FileLocator, LocalFileReader, Uri};
-use store::{AssocFrameLookup, AssocStorage, DataStore, IntoView, PushFrontFromValueIter};
+use label::{ElemOf, LookupElemByLabel, TypeOf, Typed, TypedValue, Valued};
+use observer::{NoObserver, Observer, Reporter, Stage};
+use random::{seeded_rng, uniform_below};
+pub use source::decode::TextEncoding;
+use source::decode::{decode, detect_encoding, parse_value, TextReader, UTF8_BOM};
+use source::file::{FileLocator, LocalFileReader};
+use store::{
+    AssocFrameLookup, AssocStorage, DataStore, ExtendStorage, IntoView, PushBackFromIter,
+    PushFrontFromValueIter,
+};
 use value::Value;
+use view::DataView;
+
+/// Options controlling how a CSV file is analyzed (sniffed) when creating a
+/// [CsvSource](struct.CsvSource.html), and how empty values are interpreted when loading it.
+///
+/// The field types inferred by the sniffer (available through
+/// [CsvSource::metadata](struct.CsvSource.html#method.metadata)) are only based on a sample of the
+/// file, so they can be adjusted with preferences (e.g. `prefer_signed`, for fields guessed as
+/// unsigned integers which later contain negative values) or overridden for individual fields.
+///
+/// Files in legacy (non-UTF-8) encodings, such as Windows-1252 or Shift-JIS exports, can be loaded
+/// by selecting their [encoding](#method.encoding).
+#[derive(Debug, Clone)]
+pub struct SniffOptions {
+    sample_size: SampleSize,
+    prefer_signed: bool,
+    prefer_float: bool,
+    empty_as_na: bool,
+    type_overrides: Vec<(FieldDesignator, Type)>,
+    encoding: TextEncoding,
+    header_normalization: HeaderNormalization,
+    record_options: RecordOptions,
+}
+
+impl Default for SniffOptions {
+    fn default() -> SniffOptions {
+        SniffOptions {
+            sample_size: SampleSize::Bytes(4096),
+            prefer_signed: false,
+            prefer_float: false,
+            empty_as_na: true,
+            type_overrides: vec![],
+            encoding: TextEncoding::Auto,
+            header_normalization: HeaderNormalization::new(),
+            record_options: RecordOptions::default(),
+        }
+    }
+}
+
+impl SniffOptions {
+    /// Creates a new set of sniffing options with the default values: a sample size of 4096
+    /// bytes, no type preferences or overrides, empty values treated as missing (NA), and
+    /// automatically-detected text encoding.
+    pub fn new() -> SniffOptions {
+        SniffOptions::default()
+    }
+
+    /// Sets the size of the sample of the file to analyze. With a sample size in bytes, only the
+    /// sample (extended to the end of its last line) is read to analyze the file, including when
+    /// the file must be transcoded or its encoding detected.
+    pub fn sample_size(mut self, sample_size: SampleSize) -> SniffOptions {
+        self.sample_size = sample_size;
+        self
+    }
+
+    /// Sets whether or not fields inferred as unsigned integers should be reported as signed
+    /// integers.
+    pub fn prefer_signed(mut self, prefer_signed: bool) -> SniffOptions {
+        self.prefer_signed = prefer_signed;
+        self
+    }
+
+    /// Sets whether or not fields inferred as (signed or unsigned) integers should be reported as
+    /// floating-point values.
+    pub fn prefer_float(mut self, prefer_float: bool) -> SniffOptions {
+        self.prefer_float = prefer_float;
+        self
+    }
+
+    /// Sets whether or not empty (or whitespace-only) values are loaded as missing (NA) values.
+    /// If `false`, empty values are parsed as values of the field's data type where possible
+    /// (e.g. empty strings for `String` fields), and are missing otherwise.
+    pub fn empty_as_na(mut self, empty_as_na: bool) -> SniffOptions {
+        self.empty_as_na = empty_as_na;
+        self
+    }
+
+    /// Sets the character encoding of the file (see [TextEncoding](enum.TextEncoding.html)). With
+    /// a fixed encoding, the file is transcoded to UTF-8 as it is read (and sniffed). With the
+    /// default automatic detection, files which are not valid UTF-8 are read as Windows-1252 (or
+    /// ISO-8859-1, if not valid Windows-1252); the detected encoding is reported by
+    /// [CsvSource::encoding](struct.CsvSource.html#method.encoding).
+    pub fn encoding(mut self, encoding: TextEncoding) -> SniffOptions {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Sets the normalization applied to header names, and to the field names they are matched
+    /// against (in schemas and type overrides). By default, names must match exactly.
+    pub fn normalize_headers(mut self, normalization: HeaderNormalization) -> SniffOptions {
+        self.header_normalization = normalization;
+        self
+    }
+
+    /// Sets the number of footer records (e.g. totals) at the end of the file to skip when
+    /// reading. Footer records are not checked against the [ragged-row
+    /// policy](#method.ragged_rows).
+    pub fn skip_footer(mut self, skip_footer: usize) -> SniffOptions {
+        self.record_options.skip_footer = skip_footer;
+        self
+    }
+
+    /// Sets the maximum number of records to read (after skipping any footer records). Reading
+    /// stops once `max_rows` records have been read.
+    pub fn max_rows(mut self, max_rows: usize) -> SniffOptions {
+        self.record_options.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Sets how records with a different number of fields than the file (as sniffed) are handled
+    /// when reading (by default, they fail with `AgnesError::CsvDialect`). The records in the
+    /// sniffed sample must not be ragged.
+    pub fn ragged_rows(mut self, ragged_rows: RaggedRows) -> SniffOptions {
+        self.record_options.ragged_rows = ragged_rows;
+        self
+    }
+
+    /// Overrides the inferred type of the field designated by `field` (a field name or index) with
+    /// `ty`. Overrides take precedence over the type preferences. Loading the field into a schema
+    /// field whose data type cannot represent values of type `ty` (e.g. a `Type::Float` field as
+    /// `u64`, or a `Type::Text` field as anything but `String`) fails with
+    /// `AgnesError::UnsupportedType`.
+    pub fn override_type<F>(mut self, field: F, ty: Type) -> SniffOptions
+    where
+        F: Into<FieldDesignator>,
+    {
+        self.type_overrides.push((field.into(), ty));
+        self
+    }
+
+    // Applies the type preferences and overrides to the sniffed metadata of the file at `loc`
+    // (with encoding `encoding`), returning the column index and type of each override.
+    fn apply(
+        &self,
+        loc: &FileLocator,
+        encoding: TextEncoding,
+        metadata: &mut Metadata,
+    ) -> Result<Vec<(usize, Type)>> {
+        for ty in &mut metadata.types {
+            *ty = match *ty {
+                Type::Unsigned | Type::Signed if self.prefer_float => Type::Float,
+                Type::Unsigned if self.prefer_signed => Type::Signed,
+                ty => ty,
+            };
+        }
+        if self.type_overrides.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut csv_reader = open_csv_reader(
+            &metadata.dialect,
+            TextReader::new(LocalFileReader::new(loc)?, encoding),
+        )?;
+        let headers = read_headers(&mut csv_reader, metadata, &self.header_normalization)?;
+        let mut overrides = vec![];
+        for (field, ty) in &self.type_overrides {
+            let idx = match *field {
+                FieldDesignator::Expr(ref name) => *headers
+                    .get(&self.header_normalization.normalize(name))
+                    .ok_or_else(|| AgnesError::FieldNotFound(FieldIdent::Name(name.clone())))?,
+                FieldDesignator::Idx(idx) => idx,
+            };
+            let len = metadata.types.len();
+            *metadata
+                .types
+                .get_mut(idx)
+                .ok_or(AgnesError::IndexError { index: idx, len })? = *ty;
+            overrides.push((idx, *ty));
+        }
+        Ok(overrides)
+    }
+}
+
+/// Normalization applied to CSV header names before matching them to field names (which are
+/// normalized the same way). Files produced by spreadsheet applications frequently have headers
+/// with stray whitespace or inconsistent capitalization; normalization allows them to match a
+/// schema without renaming the fields.
+///
+/// By default (with [new](#method.new)), names are left unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeaderNormalization {
+    trim: bool,
+    case_fold: bool,
+    space_replacement: Option<char>,
+}
+
+impl HeaderNormalization {
+    /// Creates a new normalization which leaves names unchanged.
+    pub fn new() -> HeaderNormalization {
+        HeaderNormalization::default()
+    }
+
+    /// Sets whether or not leading and trailing whitespace is removed from names.
+    pub fn trim(mut self, trim: bool) -> HeaderNormalization {
+        self.trim = trim;
+        self
+    }
+
+    /// Sets whether or not names are case-folded (converted to lowercase).
+    pub fn case_fold(mut self, case_fold: bool) -> HeaderNormalization {
+        self.case_fold = case_fold;
+        self
+    }
+
+    /// Replaces each run of whitespace within names with `replacement` (e.g. `'_'`).
+    pub fn replace_spaces(mut self, replacement: char) -> HeaderNormalization {
+        self.space_replacement = Some(replacement);
+        self
+    }
+
+    /// Returns the normalized form of `name`. Names are trimmed, then case-folded, and then have
+    /// their whitespace replaced, as enabled.
+    pub fn normalize(&self, name: &str) -> String {
+        let name = if self.trim { name.trim() } else { name };
+        let name = if self.case_fold {
+            name.to_lowercase()
+        } else {
+            name.to_string()
+        };
+        match self.space_replacement {
+            Some(replacement) => {
+                let mut normalized = String::with_capacity(name.len());
+                let mut in_space = false;
+                for c in name.chars() {
+                    if c.is_whitespace() {
+                        if !in_space {
+                            normalized.push(replacement);
+                        }
+                        in_space = true;
+                    } else {
+                        normalized.push(c);
+                        in_space = false;
+                    }
+                }
+                normalized
+            }
+            None => name,
+        }
+    }
+}
+
+/// Policy for handling CSV records with a different number of fields than the file (ragged rows),
+/// e.g. rows whose empty trailing fields were left off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RaggedRows {
+    /// Fail with `AgnesError::CsvDialect` (the default).
+    #[default]
+    Error,
+    /// Load the fields missing from short records as missing (NA) values, and drop the extra
+    /// trailing fields of long records.
+    PadNa,
+    /// Drop the extra trailing fields of long records. Short records still fail.
+    Truncate,
+}
+
+/// Returned by the callback of [CsvReader::read_batched](struct.CsvReader.html#method.read_batched)
+/// to control whether loading continues after a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchControl {
+    /// Continue reading the next batch.
+    Continue,
+    /// Stop reading, returning the records loaded so far (including the current batch).
+    Stop,
+}
+
+/// Type alias for the temporary view of a batch of records passed to the callback of
+/// [CsvReader::read_batched](struct.CsvReader.html#method.read_batched).
+pub type BatchView<CsvSrcSchema> =
+    <DataStore<<CsvSrcSchema as BuildDStore>::OutputFields> as IntoView>::Output;
+
+/// Options controlling which records are read from CSV data, and how ragged records are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct RecordOptions {
+    pub(crate) skip_footer: usize,
+    pub(crate) max_rows: Option<usize>,
+    pub(crate) ragged_rows: RaggedRows,
+}
+
+/// Selection of the CSV columns which are kept when reading records (projection pushdown), so
+/// that the values of columns which are not part of a source schema are never copied or held in
+/// memory. Projected records contain only the selected columns, in column order.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Projection {
+    // sorted, unique indices of the kept columns (all columns are kept if `None`)
+    columns: Option<Vec<usize>>,
+}
+
+impl Projection {
+    /// Creates a projection which keeps all columns.
+    pub fn all() -> Projection {
+        Projection { columns: None }
+    }
+
+    /// Creates a projection which keeps only the columns of the source schema `schema`.
+    pub fn of<Schema: CsvSrcIndices + ?Sized>(schema: &Schema) -> Projection {
+        let mut columns = schema.src_indices();
+        columns.sort_unstable();
+        columns.dedup();
+        Projection {
+            columns: Some(columns),
+        }
+    }
+
+    /// Returns the position of the CSV column `column` in projected records, or `None` if the
+    /// column is not kept.
+    pub fn position(&self, column: usize) -> Option<usize> {
+        match self.columns {
+            Some(ref columns) => columns.binary_search(&column).ok(),
+            None => Some(column),
+        }
+    }
+
+    /// Returns the projection of `record`, containing only the kept columns. Kept columns
+    /// missing from (short) records are left off the end of the projected record.
+    pub fn project(&self, record: &ByteRecord) -> ByteRecord {
+        match self.columns {
+            Some(ref columns) => columns.iter().map_while(|&col| record.get(col)).collect(),
+            None => record.clone(),
+        }
+    }
+}
+
+/// Reader-side configuration for building a `DataStore` from CSV records (see
+/// [BuildDStore](trait.BuildDStore.html)): the [Projection](struct.Projection.html) the records
+/// were read with, whether empty values are loaded as missing values, and the column types
+/// overridden when the source was analyzed (see [SniffOptions](struct.SniffOptions.html)).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordConfig {
+    projection: Projection,
+    empty_as_na: bool,
+    type_overrides: Vec<(usize, Type)>,
+}
+
+impl Default for RecordConfig {
+    fn default() -> RecordConfig {
+        RecordConfig::new(Projection::all())
+    }
+}
+
+impl RecordConfig {
+    /// Creates a new configuration for records projected with `projection`, with empty values
+    /// loaded as missing (NA) values and no overridden column types.
+    pub fn new(projection: Projection) -> RecordConfig {
+        RecordConfig {
+            projection,
+            empty_as_na: true,
+            type_overrides: vec![],
+        }
+    }
+
+    /// Sets whether or not empty values are loaded as missing (NA) values (see
+    /// [SniffOptions::empty_as_na](struct.SniffOptions.html#method.empty_as_na)).
+    pub fn empty_as_na(mut self, empty_as_na: bool) -> RecordConfig {
+        self.empty_as_na = empty_as_na;
+        self
+    }
+
+    /// Sets the overridden type of the CSV column `column` (see
+    /// [SniffOptions::override_type](struct.SniffOptions.html#method.override_type)).
+    pub fn override_type(mut self, column: usize, ty: Type) -> RecordConfig {
+        self.type_overrides.retain(|&(col, _)| col != column);
+        self.type_overrides.push((column, ty));
+        self
+    }
+
+    /// Returns the projection the records were read with.
+    pub fn projection(&self) -> &Projection {
+        &self.projection
+    }
+
+    // Fails if the CSV column `column` has an overridden type which cannot be represented by
+    // `DType`. Types other than the standard integer, floating-point, boolean, and `String` types
+    // are not checked.
+    fn check_type<DType: 'static>(&self, column: usize) -> Result<()> {
+        let ty = match self.type_overrides.iter().find(|&&(col, _)| col == column) {
+            Some(&(_, ty)) => ty,
+            None => return Ok(()),
+        };
+        let id = TypeId::of::<DType>();
+        let unsigned = [
+            TypeId::of::<u8>(),
+            TypeId::of::<u16>(),
+            TypeId::of::<u32>(),
+            TypeId::of::<u64>(),
+            TypeId::of::<usize>(),
+        ];
+        let signed = [
+            TypeId::of::<i8>(),
+            TypeId::of::<i16>(),
+            TypeId::of::<i32>(),
+            TypeId::of::<i64>(),
+            TypeId::of::<isize>(),
+        ];
+        let float = [TypeId::of::<f32>(), TypeId::of::<f64>()];
+        let (boolean, text) = (TypeId::of::<bool>(), TypeId::of::<String>());
+        let represents = match ty {
+            Type::Unsigned => unsigned.contains(&id) || signed.contains(&id) || float.contains(&id),
+            Type::Signed => signed.contains(&id) || float.contains(&id),
+            Type::Float => float.contains(&id),
+            Type::Boolean => id == boolean,
+            Type::Text => id == text,
+        };
+        let known = unsigned.contains(&id)
+            || signed.contains(&id)
+            || float.contains(&id)
+            || id == boolean
+            || id == text;
+        if represents || !known {
+            Ok(())
+        } else {
+            Err(AgnesError::UnsupportedType(format!(
+                "column {} is overridden as {:?}, which cannot be loaded as {}",
+                column,
+                ty,
+                type_name::<DType>()
+            )))
+        }
+    }
+}
+
+/// Iterator over the (projected) records of a CSV reader, which holds back the configured number
+/// of footer records, stops after the maximum number of records, and applies the ragged-row
+/// policy.
+pub(crate) struct Records<'r, R: 'r> {
+    csv_reader: &'r mut Reader<R>,
+    options: RecordOptions,
+    num_fields: usize,
+    projection: Projection,
+    // buffer for reading each (unprojected) record
+    buffer: ByteRecord,
+    // (unprojected) lengths and projections of the records read ahead of the current record, to
+    // detect the footer records
+    pending: VecDeque<(usize, ByteRecord)>,
+    count: usize,
+}
+
+impl<'r, R: Read> Records<'r, R> {
+    /// Creates an iterator over the records of `csv_reader` (which has `num_fields` fields),
+    /// according to `options`, projected with `projection`.
+    pub(crate) fn new(
+        csv_reader: &'r mut Reader<R>,
+        num_fields: usize,
+        options: RecordOptions,
+        projection: Projection,
+    ) -> Records<'r, R> {
+        Records {
+            csv_reader,
+            options,
+            num_fields,
+            projection,
+            buffer: ByteRecord::new(),
+            pending: VecDeque::with_capacity(options.skip_footer + 1),
+            count: 0,
+        }
+    }
+}
+
+impl<'r, R: Read> Iterator for Records<'r, R> {
+    type Item = Result<ByteRecord>;
+
+    fn next(&mut self) -> Option<Result<ByteRecord>> {
+        if self.options.max_rows.is_some_and(|max| self.count >= max) {
+            return None;
+        }
+        while self.pending.len() <= self.options.skip_footer {
+            match self.csv_reader.read_byte_record(&mut self.buffer) {
+                Ok(true) => self
+                    .pending
+                    .push_back((self.buffer.len(), self.projection.project(&self.buffer))),
+                Ok(false) => return None,
+                Err(err) => return Some(Err(err.into())),
+            }
+        }
+        let (len, mut record) = self.pending.pop_front()?;
+        self.count += 1;
+        let ragged_rows = self.options.ragged_rows;
+        if len == self.num_fields
+            || (len > self.num_fields && ragged_rows != RaggedRows::Error)
+            || (len < self.num_fields && ragged_rows == RaggedRows::PadNa)
+        {
+            record.truncate(self.num_fields);
+            Some(Ok(record))
+        } else {
+            Some(Err(AgnesError::CsvDialect(format!(
+                "record {} has {} fields, expected {}",
+                self.count, len, self.num_fields
+            ))))
+        }
+    }
+}
 
 /// CSV Data source. Contains location of data file, and computes CSV metadata. Can be turned into
 /// `CsvReader` object.
@@ -27,6 +528,16 @@ pub struct CsvSource {
     src: FileLocator,
     // CSV file metadata (from `csv-sniffer` crate)
     metadata: Metadata,
+    // Whether or not empty values are loaded as missing values
+    empty_as_na: bool,
+    // Column indices and types of the type overrides
+    type_overrides: Vec<(usize, Type)>,
+    // Character encoding of the CSV file
+    encoding: TextEncoding,
+    // Normalization of header names
+    header_normalization: HeaderNormalization,
+    // Footer, row limit, and ragged-row options for reading records
+    record_options: RecordOptions,
 }
 
 impl CsvSource {
@@ -36,17 +547,222 @@ impl CsvSource {
     /// # Error
     /// Fails if unable to open the file at the provided location, or if CSV analysis fails.
     pub fn new<L: Into<FileLocator>>(loc: L) -> Result<CsvSource> {
+        CsvSource::with_options(loc, &SniffOptions::default())
+    }
+    /// Create a new `CsvSource` object with provided file location, analyzing (sniffing) the file
+    /// according to `options`.
+    ///
+    /// # Error
+    /// Fails if unable to open the file at the provided location, if CSV analysis fails, or if a
+    /// field with an overridden type does not exist.
+    pub fn with_options<L: Into<FileLocator>>(loc: L, options: &SniffOptions) -> Result<CsvSource> {
         let loc = loc.into();
-        //TODO: make sample size configurable?
-        let mut file_reader = LocalFileReader::new(&loc)?;
-        let metadata = Sniffer::new().sniff_reader(&mut file_reader)?;
+        let mut sniffer = Sniffer::new();
+        sniffer.sample_size(options.sample_size);
+        let mut encoding = options.encoding;
+        let sample_size = options.sample_size;
+        let mut metadata = match encoding {
+            // a byte order mark would otherwise be sniffed as part of the first field
+            TextEncoding::Auto if starts_with_bom(&loc)? => {
+                sniff_transcoded(&mut sniffer, &loc, encoding, sample_size)?
+            }
+            TextEncoding::Auto => match sniffer.sniff_reader(LocalFileReader::new(&loc)?) {
+                Ok(metadata) => metadata,
+                Err(err) => {
+                    // the sniffer requires UTF-8 input; fall back to a legacy encoding if the
+                    // sample is not valid UTF-8
+                    let sample = read_sample(LocalFileReader::new(&loc)?, sample_size)?;
+                    encoding = detect_encoding(&sample);
+                    match encoding {
+                        TextEncoding::Auto => return Err(err.into()),
+                        TextEncoding::Fixed(_) => {
+                            sniff_transcoded(&mut sniffer, &loc, encoding, sample_size)?
+                        }
+                    }
+                }
+            },
+            TextEncoding::Fixed(_) => sniff_transcoded(&mut sniffer, &loc, encoding, sample_size)?,
+        };
+        let type_overrides = options.apply(&loc, encoding, &mut metadata)?;
 
-        Ok(CsvSource { src: loc, metadata })
+        Ok(CsvSource {
+            src: loc,
+            metadata,
+            empty_as_na: options.empty_as_na,
+            type_overrides,
+            encoding,
+            header_normalization: options.header_normalization.clone(),
+            record_options: options.record_options,
+        })
+    }
+    /// Create a new `CsvSource` object from the contents of `reader` (read from its start), which
+    /// are held in memory. This allows CSV data which is embedded, downloaded into memory, or
+    /// generated on the fly to be loaded without writing a temporary file.
+    ///
+    /// # Error
+    /// Fails if unable to read from `reader`, or if CSV analysis fails.
+    pub fn from_reader<R: Read + Seek>(mut reader: R) -> Result<CsvSource> {
+        let mut data = vec![];
+        reader.seek(SeekFrom::Start(0))?;
+        reader.read_to_end(&mut data)?;
+        CsvSource::new(data)
+    }
+    /// Create a new `CsvSource` object from CSV data contained in `data`, which is copied into
+    /// memory.
+    ///
+    /// # Error
+    /// Fails if CSV analysis fails.
+    pub fn from_bytes(data: &[u8]) -> Result<CsvSource> {
+        CsvSource::new(data.to_vec())
+    }
+    /// Create a new `MultiFileSource` object containing every local file matching the glob pattern
+    /// `pattern` (e.g. `"data/part-*.csv"`), in path order. Each file is analyzed (sniffed)
+    /// separately.
+    ///
+    /// # Error
+    /// Fails if `pattern` is invalid, if no files match `pattern`, or if unable to open or analyze
+    /// any matching file.
+    pub fn from_glob(pattern: &str) -> Result<MultiFileSource> {
+        let mut paths = glob::glob(pattern)?
+            .map(|path| path.map_err(io::Error::from))
+            .collect::<::std::result::Result<Vec<_>, _>>()?;
+        if paths.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no files match pattern '{}'", pattern),
+            )
+            .into());
+        }
+        paths.sort();
+        MultiFileSource::new(paths)
     }
     /// Return the compute `Metadata` for this CSV source.
     pub fn metadata(&self) -> &Metadata {
         &self.metadata
     }
+    /// Return the location of this CSV source.
+    pub fn locator(&self) -> &FileLocator {
+        &self.src
+    }
+    /// Return the character encoding of this CSV source.
+    pub fn encoding(&self) -> TextEncoding {
+        self.encoding
+    }
+
+    // Returns the configuration for building a `DataStore` from the records of this source,
+    // projected with `projection`.
+    fn record_config(&self, projection: Projection) -> RecordConfig {
+        self.type_overrides.iter().fold(
+            RecordConfig::new(projection).empty_as_na(self.empty_as_na),
+            |config, &(column, ty)| config.override_type(column, ty),
+        )
+    }
+
+    // Iterates over the records of a CSV reader for this source, projected with `projection`.
+    fn records<'r, R: Read>(
+        &self,
+        csv_reader: &'r mut Reader<R>,
+        projection: &Projection,
+    ) -> Records<'r, R> {
+        Records::new(
+            csv_reader,
+            self.metadata.num_fields,
+            self.record_options,
+            projection.clone(),
+        )
+    }
+
+    // Reads every field of this source without a schema: returns the (normalized) header names
+    // (or the field indices, if the source has no header row), calling `f` with each record read
+    // (at most `limit` of them) and its index. Records are read with the source's encoding and
+    // record options, so short records padded under `RaggedRows::PadNa` have fewer fields than the
+    // source.
+    pub(crate) fn scan_records<F>(&self, limit: Option<usize>, mut f: F) -> Result<Vec<String>>
+    where
+        F: FnMut(usize, &ByteRecord) -> Result<()>,
+    {
+        let mut csv_reader = self.open()?;
+        let names = if self.metadata.dialect.header.has_header_row {
+            let headers = csv_reader.headers()?;
+            if headers.len() != self.metadata.num_fields {
+                return Err(AgnesError::CsvDialect(
+                    "header row does not match sniffed number of fields in CSV file".into(),
+                ));
+            }
+            headers
+                .iter()
+                .map(|name| self.header_normalization.normalize(name))
+                .collect()
+        } else {
+            (0..self.metadata.num_fields)
+                .map(|idx| idx.to_string())
+                .collect()
+        };
+        let records = self
+            .records(&mut csv_reader, &Projection::all())
+            .take(limit.unwrap_or(usize::MAX));
+        for (idx, record) in records.enumerate() {
+            f(idx, &record?)?;
+        }
+        Ok(names)
+    }
+
+    // Returns whether or not empty values of this source are loaded as missing values.
+    pub(crate) fn empty_as_na(&self) -> bool {
+        self.empty_as_na
+    }
+
+    // Opens a CSV reader for this source, transcoding it to UTF-8 if it has a fixed encoding.
+    fn open(&self) -> Result<Reader<TextReader<LocalFileReader>>> {
+        open_csv_reader(
+            &self.metadata.dialect,
+            TextReader::new(LocalFileReader::new(&self.src)?, self.encoding),
+        )
+    }
+}
+
+/// Multiple CSV data sources sharing a common schema. Can be turned into a
+/// [MultiCsvReader](struct.MultiCsvReader.html) object to load and append all sources into a
+/// single `DataStore`.
+#[derive(Debug, Clone)]
+pub struct MultiFileSource {
+    sources: Vec<CsvSource>,
+}
+
+impl MultiFileSource {
+    /// Create a new `MultiFileSource` object with provided file locations, analyzing (sniffing)
+    /// each file separately.
+    ///
+    /// # Error
+    /// Fails if unable to open or analyze any of the files.
+    pub fn new<I, L>(locs: I) -> Result<MultiFileSource>
+    where
+        I: IntoIterator<Item = L>,
+        L: Into<FileLocator>,
+    {
+        MultiFileSource::with_options(locs, &SniffOptions::default())
+    }
+    /// Create a new `MultiFileSource` object with provided file locations, analyzing (sniffing)
+    /// each file separately according to `options`.
+    ///
+    /// # Error
+    /// Fails if unable to open or analyze any of the files.
+    pub fn with_options<I, L>(locs: I, options: &SniffOptions) -> Result<MultiFileSource>
+    where
+        I: IntoIterator<Item = L>,
+        L: Into<FileLocator>,
+    {
+        Ok(MultiFileSource {
+            sources: locs
+                .into_iter()
+                .map(|loc| CsvSource::with_options(loc, options))
+                .collect::<Result<Vec<_>>>()?,
+        })
+    }
+    /// Return the individual CSV sources in this multiple-file source.
+    pub fn sources(&self) -> &[CsvSource] {
+        &self.sources
+    }
 }
 
 /// Type alias for [Cons](../../cons/struct.Cons.html)-list specifying label, data type, and source
@@ -58,12 +774,14 @@ pub trait IntoCsvSrcSchema {
     /// Resultant `CsvSrcSchemaCons` object.
     type CsvSrcSchema;
 
-    /// Convert this into a `CsvSrcSchemaCons` cons-list. `headers` is a map of column header names
-    /// to column indices. `num_fields` is the number of columns in the CSV file (for checking for
-    /// indexing errors).
+    /// Convert this into a `CsvSrcSchemaCons` cons-list. `headers` is a map of (normalized)
+    /// column header names to column indices, and field names are normalized with
+    /// `normalization` before being looked up. `num_fields` is the number of columns in the CSV
+    /// file (for checking for indexing errors).
     fn into_csv_src_schema(
         self,
         headers: &HashMap<String, usize>,
+        normalization: &HeaderNormalization,
         num_fields: usize,
     ) -> Result<Self::CsvSrcSchema>;
 }
@@ -73,6 +791,7 @@ impl IntoCsvSrcSchema for Nil {
     fn into_csv_src_schema(
         self,
         _headers: &HashMap<String, usize>,
+        _normalization: &HeaderNormalization,
         _num_fields: usize,
     ) -> Result<Nil> {
         Ok(Nil)
@@ -88,11 +807,12 @@ where
     fn into_csv_src_schema(
         self,
         headers: &HashMap<String, usize>,
+        normalization: &HeaderNormalization,
         num_fields: usize,
     ) -> Result<CsvSrcSchemaCons<Label, DType, Tail::CsvSrcSchema>> {
         let idx = match *self.head.value_ref() {
             FieldDesignator::Expr(ref s) => *headers
-                .get(s)
+                .get(&normalization.normalize(s))
                 .ok_or(AgnesError::FieldNotFound(FieldIdent::Name(s.to_string())))?,
             FieldDesignator::Idx(idx) => {
                 if idx >= num_fields {
@@ -106,23 +826,70 @@ where
         };
         Ok(Cons {
             head: TypedValue::from(idx).into(),
-            tail: self.tail.into_csv_src_schema(headers, num_fields)?,
+            tail: self
+                .tail
+                .into_csv_src_schema(headers, normalization, num_fields)?,
         })
     }
 }
 
 /// A trait for building a [DataStore](../../store/struct.DataStore.html) from a
 /// [CsvSrcSchemaCons](type.CsvSrcSchemaCons.html).
-pub trait BuildDStore {
+pub trait BuildDStore: CsvSrcIndices {
     /// `Fields` type parameter of the resultant `DataStore`.
-    type OutputFields: AssocStorage;
+    type OutputFields: ExtendStorage;
+
+    /// Builds a `DataStore` from the source schema (`self`) and a CSV source `src`. Only the
+    /// columns of the source schema are read (see [Projection](struct.Projection.html)).
+    fn build(&mut self, src: &CsvSource) -> Result<DataStore<Self::OutputFields>> {
+        let config = src.record_config(Projection::of(self));
+        self.build_with_config(
+            &read_records(
+                src,
+                &config,
+                &[],
+                &mut Reporter::new(&mut NoObserver, Stage::Read, None),
+            )?,
+            &config,
+        )
+    }
+
+    /// Builds a `DataStore` from the source schema (`self`) and a set of previously-read CSV
+    /// records.
+    fn build_from_records(
+        &mut self,
+        records: &[ByteRecord],
+    ) -> Result<DataStore<Self::OutputFields>> {
+        self.build_with_config(records, &RecordConfig::default())
+    }
+
+    /// Builds a `DataStore` from the source schema (`self`) and a set of previously-read CSV
+    /// records, which were projected with `projection` (see
+    /// [build_from_records](#method.build_from_records)).
+    fn build_from_projected_records(
+        &mut self,
+        records: &[ByteRecord],
+        projection: &Projection,
+    ) -> Result<DataStore<Self::OutputFields>> {
+        self.build_with_config(records, &RecordConfig::new(projection.clone()))
+    }
 
-    /// Builds a `DataStore` from the source schema (`self`) and a CSV source `src`.
-    fn build(&mut self, src: &CsvSource) -> Result<DataStore<Self::OutputFields>>;
+    /// Builds a `DataStore` from the source schema (`self`) and a set of previously-read CSV
+    /// records, which were read with the reader-side configuration `config` (see
+    /// [RecordConfig](struct.RecordConfig.html)).
+    fn build_with_config(
+        &mut self,
+        records: &[ByteRecord],
+        config: &RecordConfig,
+    ) -> Result<DataStore<Self::OutputFields>>;
 }
 impl BuildDStore for Nil {
     type OutputFields = Nil;
-    fn build(&mut self, _src: &CsvSource) -> Result<DataStore<Nil>> {
+    fn build_with_config(
+        &mut self,
+        _records: &[ByteRecord],
+        _config: &RecordConfig,
+    ) -> Result<DataStore<Nil>> {
         Ok(DataStore::<Nil>::empty())
     }
 }
@@ -130,10 +897,12 @@ impl<Label, DType, Tail> BuildDStore for CsvSrcSchemaCons<Label, DType, Tail>
 where
     Tail: BuildDStore,
     DataStore<<Tail as BuildDStore>::OutputFields>: PushFrontFromValueIter<Label, DType>,
+    <DataStore<<Tail as BuildDStore>::OutputFields> as PushFrontFromValueIter<Label, DType>>::OutputFields:
+        ExtendStorage,
     Tail::OutputFields: PushBack<FieldSchema<Label, DType>>,
     <Tail::OutputFields as PushBack<FieldSchema<Label, DType>>>::Output: AssocStorage,
     Label: Debug,
-    DType: FromStr + Debug + Default + Clone,
+    DType: FromStr + Debug + Default + Clone + 'static,
     ParseError: From<<DType as FromStr>::Err>,
 {
     type OutputFields = <DataStore<<Tail as BuildDStore>::OutputFields> as PushFrontFromValueIter<
@@ -141,32 +910,27 @@ where
         DType,
     >>::OutputFields;
 
-    fn build(&mut self, src: &CsvSource) -> Result<DataStore<Self::OutputFields>> {
-        let file_reader = LocalFileReader::new(&src.src)?;
-        let mut csv_reader = src.metadata.dialect.open_reader(file_reader)?;
-        let ds = self.tail.build(src)?;
-
-        let values: Vec<Value<DType>> = csv_reader
-            .byte_records()
-            .map(|row| {
-                let record = row?;
-                let value = decode(record.get(*self.head.value_ref().value_ref()).ok_or_else(
-                    || AgnesError::FieldNotFound(FieldIdent::Name(stringify![Field].to_string())),
-                )?)?;
-                Ok(value)
-            })
-            .map(|sresult| {
-                sresult.and_then(|s| {
-                    let trimmed = s.trim();
-                    if trimmed.is_empty() {
-                        Ok(Value::Na)
-                    } else {
-                        trimmed
-                            .parse::<DType>()
-                            .map(|value| Value::Exists(value))
-                            .map_err(|e| AgnesError::Parse(e.into()))
-                    }
-                })
+    fn build_with_config(
+        &mut self,
+        records: &[ByteRecord],
+        config: &RecordConfig,
+    ) -> Result<DataStore<Self::OutputFields>> {
+        let ds = self.tail.build_with_config(records, config)?;
+
+        let column = *self.head.value_ref().value_ref();
+        config.check_type::<DType>(column)?;
+        let position = config
+            .projection
+            .position(column)
+            .ok_or(AgnesError::FieldNotFound(FieldIdent::Index(column)))?;
+        let values: Vec<Value<DType>> = records
+            .iter()
+            .map(|record| {
+                // fields missing from (ragged) records are missing values
+                match record.get(position) {
+                    Some(bytes) => parse_value(&decode(bytes)?, config.empty_as_na),
+                    None => Ok(Value::Na),
+                }
             })
             .collect::<Result<_>>()?;
         let ds = ds.push_front_from_value_iter::<Label, DType, _, _>(values);
@@ -175,11 +939,335 @@ where
     }
 }
 
+/// A trait for retrieving the CSV column indices from a
+/// [CsvSrcSchemaCons](type.CsvSrcSchemaCons.html).
+pub trait CsvSrcIndices {
+    /// Append the CSV column index of each field in this source schema to `indices`.
+    fn push_src_indices(&self, indices: &mut Vec<usize>);
+
+    /// Returns the CSV column index of each field in this source schema.
+    fn src_indices(&self) -> Vec<usize> {
+        let mut indices = vec![];
+        self.push_src_indices(&mut indices);
+        indices
+    }
+}
+impl CsvSrcIndices for Nil {
+    fn push_src_indices(&self, _indices: &mut Vec<usize>) {}
+}
+impl<Label, DType, Tail> CsvSrcIndices for CsvSrcSchemaCons<Label, DType, Tail>
+where
+    Tail: CsvSrcIndices,
+{
+    fn push_src_indices(&self, indices: &mut Vec<usize>) {
+        indices.push(*self.head.value_ref().value_ref());
+        self.tail.push_src_indices(indices);
+    }
+}
+
+/// A predicate on the (parsed) values of a CSV column, used to filter records while they are
+/// read (see [CsvReader::filter](struct.CsvReader.html#method.filter)).
+pub(crate) struct RecordFilter {
+    column: usize,
+    // tests the value of the column (or `None` if missing from the record), with the flag
+    // specifying whether empty values are missing
+    predicate: Box<ValuePredicate>,
+}
+
+// Predicate on an (unparsed) CSV value.
+type ValuePredicate = dyn Fn(Option<&[u8]>, bool) -> Result<bool> + Send + Sync;
+
+impl RecordFilter {
+    // Creates a filter testing the values of the field labeled `Label` of the source schema
+    // `schema` with `predicate` (see `CsvReader::filter`).
+    pub(crate) fn new<CsvSrcSchema, Label, P>(schema: &CsvSrcSchema, predicate: P) -> RecordFilter
+    where
+        CsvSrcSchema: LookupElemByLabel<Label>,
+        ElemOf<CsvSrcSchema, Label>: Typed + Valued<Value = usize>,
+        TypeOf<ElemOf<CsvSrcSchema, Label>>: FromStr,
+        ParseError: From<<TypeOf<ElemOf<CsvSrcSchema, Label>> as FromStr>::Err>,
+        P: Fn(Value<&TypeOf<ElemOf<CsvSrcSchema, Label>>>) -> bool + Send + Sync + 'static,
+    {
+        RecordFilter {
+            column: *LookupElemByLabel::<Label>::elem(schema).value_ref(),
+            predicate: Box::new(move |bytes, empty_as_na| {
+                let value = match bytes {
+                    Some(bytes) => parse_value(&decode(bytes)?, empty_as_na)?,
+                    None => Value::Na,
+                };
+                Ok(predicate(value.as_ref()))
+            }),
+        }
+    }
+
+    // Returns whether `record` (read with `config`) matches all of `filters`.
+    pub(crate) fn matches_all(
+        filters: &[RecordFilter],
+        record: &ByteRecord,
+        config: &RecordConfig,
+    ) -> Result<bool> {
+        for filter in filters {
+            let value = config
+                .projection
+                .position(filter.column)
+                .and_then(|pos| record.get(pos));
+            if !(filter.predicate)(value, config.empty_as_na)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl Debug for RecordFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RecordFilter")
+            .field("column", &self.column)
+            .finish()
+    }
+}
+
+/// Reads all records (after the header row, if one exists) from the CSV source `src`, projected
+/// with the projection of `config`, reporting each record read to `reporter`. Only records
+/// matching all of `filters` are kept.
+pub(crate) fn read_records(
+    src: &CsvSource,
+    config: &RecordConfig,
+    filters: &[RecordFilter],
+    reporter: &mut Reporter,
+) -> Result<Vec<ByteRecord>> {
+    let mut csv_reader = src.open()?;
+    collect_records(
+        src.records(&mut csv_reader, &config.projection),
+        config,
+        filters,
+        reporter,
+    )
+}
+
+/// Collects the (projected) records `records` (read with `config`), reporting each record read to
+/// `reporter`. Only records matching all of `filters` are kept.
+pub(crate) fn collect_records<I>(
+    records: I,
+    config: &RecordConfig,
+    filters: &[RecordFilter],
+    reporter: &mut Reporter,
+) -> Result<Vec<ByteRecord>>
+where
+    I: Iterator<Item = Result<ByteRecord>>,
+{
+    let mut kept = vec![];
+    for record in records {
+        let record = record?;
+        if RecordFilter::matches_all(filters, &record, config)? {
+            kept.push(record);
+        }
+        reporter.advance(1)?;
+    }
+    Ok(kept)
+}
+
+/// Reads a uniform random sample of (at most) `n` records (after the header row, if one exists)
+/// from the CSV source `src` (see `sample_from`), reporting each record scanned to `reporter`.
+pub(crate) fn sample_records(
+    src: &CsvSource,
+    config: &RecordConfig,
+    filters: &[RecordFilter],
+    n: usize,
+    seed: u64,
+    reporter: &mut Reporter,
+) -> Result<Vec<ByteRecord>> {
+    let mut csv_reader = src.open()?;
+    sample_from(
+        src.records(&mut csv_reader, &config.projection),
+        config,
+        filters,
+        n,
+        seed,
+        reporter,
+    )
+}
+
+/// Samples (at most) `n` of the (projected) records `records` (read with `config`) using
+/// reservoir sampling, reporting each record scanned to `reporter`. Only the sampled records are
+/// held in memory. The records are sampled from those matching all of `filters` (using a
+/// `random::DefaultRng` seeded with `seed`), and are returned in their original order.
+pub(crate) fn sample_from<I>(
+    records: I,
+    config: &RecordConfig,
+    filters: &[RecordFilter],
+    n: usize,
+    seed: u64,
+    reporter: &mut Reporter,
+) -> Result<Vec<ByteRecord>>
+where
+    I: Iterator<Item = Result<ByteRecord>>,
+{
+    let mut rng = seeded_rng(seed);
+    let mut reservoir: Vec<(usize, ByteRecord)> = Vec::with_capacity(n);
+    let mut idx = 0;
+    for record in records {
+        let record = record?;
+        reporter.advance(1)?;
+        if !RecordFilter::matches_all(filters, &record, config)? {
+            continue;
+        }
+        if reservoir.len() < n {
+            reservoir.push((idx, record));
+        } else {
+            let replace = uniform_below(&mut rng, idx as u64 + 1) as usize;
+            if replace < n {
+                reservoir[replace] = (idx, record);
+            }
+        }
+        idx += 1;
+    }
+    reservoir.sort_by_key(|&(idx, _)| idx);
+    Ok(reservoir.into_iter().map(|(_, record)| record).collect())
+}
+
+/// Builds a `DataStore` from the source schema `csv_src_schema` and the (projected) records
+/// `records` (read with `config`), calling `callback` after every `batch_size` records matching
+/// all of `filters` are read (see
+/// [CsvReader::read_batched](struct.CsvReader.html#method.read_batched)).
+pub(crate) fn build_batched<I, CsvSrcSchema, F>(
+    records: I,
+    csv_src_schema: &mut CsvSrcSchema,
+    config: &RecordConfig,
+    filters: &[RecordFilter],
+    batch_size: usize,
+    mut callback: F,
+) -> Result<DataStore<CsvSrcSchema::OutputFields>>
+where
+    I: Iterator<Item = Result<ByteRecord>>,
+    CsvSrcSchema: BuildDStore,
+    DataStore<CsvSrcSchema::OutputFields>: IntoView,
+    F: FnMut(&BatchView<CsvSrcSchema>, usize) -> Result<BatchControl>,
+{
+    assert!(batch_size > 0, "batch size must be positive");
+    // each batch is parsed once, appended to the store of all batches, and its records dropped
+    let mut store: Option<DataStore<CsvSrcSchema::OutputFields>> = None;
+    let mut batch_records = Vec::with_capacity(batch_size);
+    let mut batch_start = 0;
+    let mut stopped = false;
+    let mut records = records.peekable();
+    while !stopped && records.peek().is_some() {
+        batch_records.clear();
+        for record in &mut records {
+            let record = record?;
+            if RecordFilter::matches_all(filters, &record, config)? {
+                batch_records.push(record);
+                if batch_records.len() == batch_size {
+                    break;
+                }
+            }
+        }
+        if batch_records.is_empty() {
+            break;
+        }
+        let batch = csv_src_schema.build_with_config(&batch_records, config)?;
+        match store {
+            Some(ref mut store) => store.extend_from(&batch),
+            None => store = Some(batch.share()),
+        }
+        let control = callback(&batch.into_view(), batch_start)?;
+        batch_start += batch_records.len();
+        stopped = control == BatchControl::Stop;
+    }
+    match store {
+        Some(store) => Ok(store),
+        None => csv_src_schema.build_with_config(&[], config),
+    }
+}
+
+// Returns whether or not the file at `loc` starts with a UTF-8 byte order mark.
+fn starts_with_bom(loc: &FileLocator) -> Result<bool> {
+    let mut prefix = vec![];
+    LocalFileReader::new(loc)?
+        .take(UTF8_BOM.len() as u64)
+        .read_to_end(&mut prefix)?;
+    Ok(prefix == UTF8_BOM)
+}
+
+// Sniffs a sample of the file at `loc` (see `read_sample`) after transcoding it from `encoding` to
+// UTF-8 and stripping any byte order mark (since the sniffer requires seekable UTF-8 input).
+fn sniff_transcoded(
+    sniffer: &mut Sniffer,
+    loc: &FileLocator,
+    encoding: TextEncoding,
+    sample_size: SampleSize,
+) -> Result<Metadata> {
+    let sample = read_sample(
+        TextReader::new(LocalFileReader::new(loc)?, encoding),
+        sample_size,
+    )?;
+    Ok(sniffer.sniff_reader(Cursor::new(sample))?)
+}
+
+// Reads the sample of `reader` to sniff: for a sample size in bytes, the first `sample_size` bytes
+// of `reader`, extended to the end of the last (partial) line so that the sample ends on a
+// character boundary; the sniffer only examines complete lines. Other sample sizes are not bounded
+// by a number of bytes, so the entire input is read.
+fn read_sample<R: Read>(reader: R, sample_size: SampleSize) -> Result<Vec<u8>> {
+    let mut reader = BufReader::new(reader);
+    let mut sample = vec![];
+    match sample_size {
+        SampleSize::Bytes(size) => {
+            (&mut reader).take(size as u64).read_to_end(&mut sample)?;
+            if sample.last().map(|&b| b != b'\n').unwrap_or(false) {
+                reader.read_until(b'\n', &mut sample)?;
+            }
+        }
+        _ => {
+            reader.read_to_end(&mut sample)?;
+        }
+    }
+    Ok(sample)
+}
+
+// Opens a CSV reader with the dialect `dialect` over `reader`, skipping the dialect's preamble
+// rows. Unlike `Dialect::open_reader`, this does not require `reader` to be seekable. The reader
+// accepts ragged records, which are handled by `Records`.
+fn open_csv_reader<R: Read>(dialect: &Dialect, mut reader: R) -> Result<Reader<R>> {
+    let mut byte = [0u8];
+    for _ in 0..dialect.header.num_preamble_rows {
+        while reader.read(&mut byte)? > 0 && byte[0] != b'\n' {}
+    }
+    Ok(ReaderBuilder::from(dialect.clone())
+        .flexible(true)
+        .from_reader(reader))
+}
+
+/// Reads the header row (if the CSV dialect specifies one) from `csv_reader`, returning a map of
+/// column header names (normalized with `normalization`) to column indices.
+pub(crate) fn read_headers<R: Read>(
+    csv_reader: &mut Reader<R>,
+    metadata: &Metadata,
+    normalization: &HeaderNormalization,
+) -> Result<HashMap<String, usize>> {
+    if metadata.dialect.header.has_header_row {
+        let headers = csv_reader.headers()?;
+        if headers.len() != metadata.num_fields {
+            return Err(AgnesError::CsvDialect(
+                "header row does not match sniffed number of fields in CSV file".into(),
+            ));
+        }
+        Ok(headers
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (normalization.normalize(s), i))
+            .collect::<HashMap<_, _>>())
+    } else {
+        Ok(HashMap::new())
+    }
+}
+
 /// Object for reading CSV sources.
 #[derive(Debug)]
 pub struct CsvReader<CsvSchema> {
     src: CsvSource,
     csv_src_schema: CsvSchema,
+    filters: Vec<RecordFilter>,
 }
 
 impl<CsvSrcSchema> CsvReader<CsvSrcSchema>
@@ -192,41 +1280,300 @@ where
     where
         Schema: IntoCsvSrcSchema<CsvSrcSchema = CsvSrcSchema>,
     {
-        let file_reader = LocalFileReader::new(&src.src)?;
-        let mut csv_reader = src.metadata.dialect.open_reader(file_reader)?;
+        let mut csv_reader = src.open()?;
 
         debug_assert_eq!(src.metadata.num_fields, src.metadata.types.len());
 
-        let headers = if src.metadata.dialect.header.has_header_row {
-            let headers = csv_reader.headers()?;
-            if headers.len() != src.metadata.num_fields {
-                return Err(AgnesError::CsvDialect(
-                    "header row does not match sniffed number of fields in CSV file".into(),
-                ));
-            }
-            headers
-                .iter()
-                .enumerate()
-                .map(|(i, s)| (s.to_string(), i))
-                .collect::<HashMap<_, _>>()
-        } else {
-            HashMap::new()
-        };
-        let csv_src_schema = schema.into_csv_src_schema(&headers, src.metadata.num_fields)?;
+        let headers = read_headers(&mut csv_reader, &src.metadata, &src.header_normalization)?;
+        let csv_src_schema = schema.into_csv_src_schema(
+            &headers,
+            &src.header_normalization,
+            src.metadata.num_fields,
+        )?;
 
         Ok(CsvReader {
             //TODO: remove source from here
             src: src.clone(),
             csv_src_schema,
+            filters: vec![],
         })
     }
 
+    /// Adds a filter to this reader, so that only records whose values of the field labeled
+    /// `Label` match `predicate` (a function mapping from `Value<&T>` to `bool`, where `T` is the
+    /// type of the field) are loaded. Filters are applied as each record is read, so records
+    /// which do not match are never held in memory. Predicates which own their data, such as
+    /// [between](../../filter/fn.between.html), can be used here as well as with
+    /// [DataView::filter](../../view/struct.DataView.html#method.filter).
+    ///
+    /// Records must match all filters added to a reader. Filters apply to
+    /// [read](#method.read) and [read_sample](#method.read_sample) (which samples from the
+    /// matching records).
+    pub fn filter<Label, P>(mut self, predicate: P) -> Self
+    where
+        CsvSrcSchema: LookupElemByLabel<Label>,
+        ElemOf<CsvSrcSchema, Label>: Typed + Valued<Value = usize>,
+        TypeOf<ElemOf<CsvSrcSchema, Label>>: FromStr,
+        ParseError: From<<TypeOf<ElemOf<CsvSrcSchema, Label>> as FromStr>::Err>,
+        P: Fn(Value<&TypeOf<ElemOf<CsvSrcSchema, Label>>>) -> bool + Send + Sync + 'static,
+    {
+        let filter = RecordFilter::new::<_, Label, _>(&self.csv_src_schema, predicate);
+        self.filters.push(filter);
+        self
+    }
+
     /// Read a `CsvSource` into a `DataStore` object.
     pub fn read(&mut self) -> Result<DataStore<CsvSrcSchema::OutputFields>>
     where
         CsvSrcSchema: BuildDStore,
     {
-        self.csv_src_schema.build(&self.src)
+        self.read_observed(&mut NoObserver)
+    }
+
+    /// Read a `CsvSource` into a `DataStore` object, reporting the number of records read to
+    /// `observer` (with stage [Stage::Read](../../observer/enum.Stage.html)).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation while reading, or
+    /// for any of the reasons [read](#method.read) might fail.
+    pub fn read_observed<O>(
+        &mut self,
+        observer: &mut O,
+    ) -> Result<DataStore<CsvSrcSchema::OutputFields>>
+    where
+        CsvSrcSchema: BuildDStore,
+        O: Observer,
+    {
+        let mut reporter = Reporter::new(observer, Stage::Read, None);
+        reporter.check()?;
+        let config = self.src.record_config(Projection::of(&self.csv_src_schema));
+        let records = read_records(&self.src, &config, &self.filters, &mut reporter)?;
+        reporter.finish();
+        self.csv_src_schema.build_with_config(&records, &config)
+    }
+
+    /// Read a `CsvSource` into a `DataStore` object (see [read](#method.read)), calling `callback`
+    /// after every `batch_size` records are read (and once more for the final, partial batch).
+    /// The callback is passed a temporary view of the batch's records, along with the index of
+    /// the batch's first record among all records loaded. This can be used to validate records,
+    /// display progress, or stop loading early (by returning `BatchControl::Stop`) while a large
+    /// source is being read. Only records matching this reader's [filters](#method.filter) are
+    /// counted towards batches. Each batch is parsed once and appended to the returned store, and
+    /// only the records of the current batch are held in memory.
+    ///
+    /// # Errors
+    /// Fails with any error returned by `callback` (aborting the load), or for any of the reasons
+    /// [read](#method.read) might fail.
+    ///
+    /// # Panics
+    /// Panics if `batch_size` is zero.
+    pub fn read_batched<F>(
+        &mut self,
+        batch_size: usize,
+        callback: F,
+    ) -> Result<DataStore<CsvSrcSchema::OutputFields>>
+    where
+        CsvSrcSchema: BuildDStore,
+        DataStore<CsvSrcSchema::OutputFields>: IntoView,
+        F: FnMut(&BatchView<CsvSrcSchema>, usize) -> Result<BatchControl>,
+    {
+        let config = self.src.record_config(Projection::of(&self.csv_src_schema));
+        let mut csv_reader = self.src.open()?;
+        build_batched(
+            self.src.records(&mut csv_reader, &config.projection),
+            &mut self.csv_src_schema,
+            &config,
+            &self.filters,
+            batch_size,
+            callback,
+        )
+    }
+
+    /// Read a uniform random sample of (at most) `n` records of a `CsvSource` into a `DataStore`
+    /// object. The entire source is scanned, but only the sampled records are kept in memory, so
+    /// this can be used to quickly explore sources too large to load entirely. Sampled records
+    /// remain in source order, and the same `seed` always produces the same sample (records are
+    /// sampled with a [DefaultRng](../../random/type.DefaultRng.html) created by
+    /// [seeded_rng](../../random/fn.seeded_rng.html) from `seed`).
+    pub fn read_sample(
+        &mut self,
+        n: usize,
+        seed: u64,
+    ) -> Result<DataStore<CsvSrcSchema::OutputFields>>
+    where
+        CsvSrcSchema: BuildDStore,
+    {
+        self.read_sample_observed(n, seed, &mut NoObserver)
+    }
+
+    /// Read a uniform random sample of (at most) `n` records of a `CsvSource` into a `DataStore`
+    /// object (see [read_sample](#method.read_sample)), reporting the number of records scanned
+    /// to `observer` (with stage [Stage::Read](../../observer/enum.Stage.html)).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation while reading, or
+    /// for any of the reasons [read](#method.read) might fail.
+    pub fn read_sample_observed<O>(
+        &mut self,
+        n: usize,
+        seed: u64,
+        observer: &mut O,
+    ) -> Result<DataStore<CsvSrcSchema::OutputFields>>
+    where
+        CsvSrcSchema: BuildDStore,
+        O: Observer,
+    {
+        let mut reporter = Reporter::new(observer, Stage::Read, None);
+        reporter.check()?;
+        let config = self.src.record_config(Projection::of(&self.csv_src_schema));
+        let records = sample_records(&self.src, &config, &self.filters, n, seed, &mut reporter)?;
+        reporter.finish();
+        self.csv_src_schema.build_with_config(&records, &config)
+    }
+}
+
+/// Type alias for the `Fields` type parameter of a `DataStore` with `Fields` fields, after adding a
+/// provenance field (labeled `Label`).
+pub type ProvenanceFields<Fields, Label> =
+    <DataStore<Fields> as PushBackFromIter<Label, String>>::OutputFields;
+
+/// Object for reading multiple CSV sources with a common schema into a single `DataStore`, appending
+/// the records of each source in order.
+#[derive(Debug)]
+pub struct MultiCsvReader<CsvSrcSchema> {
+    readers: Vec<CsvReader<CsvSrcSchema>>,
+}
+
+impl<CsvSrcSchema> MultiCsvReader<CsvSrcSchema>
+where
+    CsvSrcSchema: Debug,
+{
+    /// Create a new multiple-file CSV reader from a multiple-file source specification. This will
+    /// process the header row (if exists) of each file, and verify that the fields specified in
+    /// `schema` exist in every file. The order of columns may differ between files.
+    pub fn new<Schema>(
+        src: &MultiFileSource,
+        schema: Schema,
+    ) -> Result<MultiCsvReader<Schema::CsvSrcSchema>>
+    where
+        Schema: IntoCsvSrcSchema<CsvSrcSchema = CsvSrcSchema> + Clone,
+    {
+        Ok(MultiCsvReader {
+            readers: src
+                .sources
+                .iter()
+                .map(|src| CsvReader::new(src, schema.clone()))
+                .collect::<Result<Vec<_>>>()?,
+        })
+    }
+
+    /// Read and append all sources into a single `DataStore` object.
+    pub fn read(&mut self) -> Result<DataStore<CsvSrcSchema::OutputFields>>
+    where
+        CsvSrcSchema: BuildDStore + CsvSrcIndices,
+    {
+        self.read_observed(&mut NoObserver)
+    }
+
+    /// Read and append all sources into a single `DataStore` object, reporting the total number
+    /// of records read to `observer` (with stage [Stage::Read](../../observer/enum.Stage.html)).
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation while reading.
+    pub fn read_observed<O>(
+        &mut self,
+        observer: &mut O,
+    ) -> Result<DataStore<CsvSrcSchema::OutputFields>>
+    where
+        CsvSrcSchema: BuildDStore + CsvSrcIndices,
+        O: Observer,
+    {
+        let (records, _, projection) = self.read_records(observer)?;
+        match self.readers.first_mut() {
+            Some(reader) => reader
+                .csv_src_schema
+                .build_with_config(&records, &reader.src.record_config(projection)),
+            None => Err(AgnesError::NoSources),
+        }
+    }
+
+    /// Read and append all sources into a single `DataStore` object, adding a provenance field
+    /// (labeled `Label`) containing the location of the source file of each record.
+    pub fn read_with_provenance<Label>(
+        &mut self,
+    ) -> Result<DataStore<ProvenanceFields<CsvSrcSchema::OutputFields, Label>>>
+    where
+        CsvSrcSchema: BuildDStore + CsvSrcIndices,
+        DataStore<CsvSrcSchema::OutputFields>: PushBackFromIter<Label, String>,
+    {
+        let (records, lens, projection) = self.read_records(&mut NoObserver)?;
+        let ds = match self.readers.first_mut() {
+            Some(reader) => reader
+                .csv_src_schema
+                .build_with_config(&records, &reader.src.record_config(projection))?,
+            None => return Err(AgnesError::NoSources),
+        };
+        let provenance = self
+            .readers
+            .iter()
+            .zip(lens)
+            .flat_map(|(reader, len)| iter::repeat_n(reader.src.src.to_string(), len))
+            .collect::<Vec<_>>();
+        Ok(ds.push_back_from_iter::<Label, String, _, _>(provenance))
+    }
+
+    // Read the (projected) records from every source, rearranging the columns of each source to
+    // match the layout of the first source. Also returns the number of records read from each
+    // source, and the projection of the first source (which the records are laid out by).
+    fn read_records(
+        &self,
+        observer: &mut dyn Observer,
+    ) -> Result<(Vec<ByteRecord>, Vec<usize>, Projection)>
+    where
+        CsvSrcSchema: CsvSrcIndices,
+    {
+        let (target_indices, target_projection) = match self.readers.first() {
+            Some(reader) => (
+                reader.csv_src_schema.src_indices(),
+                Projection::of(&reader.csv_src_schema),
+            ),
+            None => return Err(AgnesError::NoSources),
+        };
+        let mut reporter = Reporter::new(observer, Stage::Read, None);
+        reporter.check()?;
+        let mut records = vec![];
+        let mut lens = vec![];
+        for reader in &self.readers {
+            let indices = reader.csv_src_schema.src_indices();
+            let config = reader
+                .src
+                .record_config(Projection::of(&reader.csv_src_schema));
+            let projection = config.projection();
+            let src_records = read_records(&reader.src, &config, &reader.filters, &mut reporter)?;
+            lens.push(src_records.len());
+            if indices == target_indices {
+                records.extend(src_records);
+            } else {
+                let positions = target_indices
+                    .iter()
+                    .zip(&indices)
+                    .filter_map(|(&target_idx, &idx)| {
+                        Some((
+                            target_projection.position(target_idx)?,
+                            projection.position(idx)?,
+                        ))
+                    })
+                    .collect::<Vec<_>>();
+                let num_columns = positions.iter().map(|&(pos, _)| pos + 1).max().unwrap_or(0);
+                records.extend(src_records.iter().map(|record| {
+                    let mut columns = vec![&b""[..]; num_columns];
+                    for &(target_pos, pos) in &positions {
+                        columns[target_pos] = record.get(pos).unwrap_or(b"");
+                    }
+                    ByteRecord::from(columns)
+                }));
+            }
+        }
+        reporter.finish();
+        Ok((records, lens, target_projection))
     }
 }
 
@@ -247,9 +1594,12 @@ where
     Ok(csv_reader.read()?.into_view())
 }
 
-/// Utility function for loading a CSV file from a URI string.
+/// Utility function for loading a CSV file from a URI string. Requires the `net` feature. With the
+/// `object-store` feature, S3 (`s3://bucket/key`) and GCS (`gs://bucket/key`) URIs are also
+/// supported.
 ///
 /// Fails if unable to parse `uri`, or if unable to find or read file at the location specified.
+#[cfg(feature = "net")]
 pub fn load_csv_from_uri<Schema>(
     uri: &str,
     schema: Schema,
@@ -259,7 +1609,7 @@ where
     Schema::CsvSrcSchema: BuildDStore + Debug,
     <Schema::CsvSrcSchema as BuildDStore>::OutputFields: AssocFrameLookup + SimpleFrameFields,
 {
-    load_csv(Uri::from_uri(uri.parse::<hyper::Uri>()?)?, schema)
+    load_csv(FileLocator::from_uri(uri)?, schema)
 }
 
 /// Utility function for loading a CSV file from a local file path.
@@ -277,3 +1627,46 @@ where
 {
     load_csv(path.into(), schema)
 }
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Loads a `DataView` from the CSV text `s`, with fields specified by `schema`. This is a
+    /// convenience for small data sets (e.g. in tests and examples) which would otherwise require
+    /// a separate data file.
+    ///
+    /// # Example
+    /// ```
+    /// # #[macro_use] extern crate agnes;
+    /// # use agnes::view::DataView;
+    /// tablespace![
+    ///     table emp {
+    ///         EmpId: u64,
+    ///         Name: String,
+    ///     }
+    /// ];
+    ///
+    /// # fn main() {
+    /// let dv: emp::View = DataView::from_csv_str(
+    ///     schema![
+    ///         fieldname emp::EmpId = "id";
+    ///         fieldname emp::Name = "name";
+    ///     ],
+    ///     "id,name\n0,Sally\n2,Jamie\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(dv.nrows(), 2);
+    /// # }
+    /// ```
+    ///
+    /// # Error
+    /// Fails if CSV analysis fails, if a field in `schema` does not exist, or if a value cannot be
+    /// parsed as its field's data type.
+    pub fn from_csv_str<Schema>(schema: Schema, s: &str) -> Result<Self>
+    where
+        Schema: IntoCsvSrcSchema,
+        Schema::CsvSrcSchema: BuildDStore + Debug,
+        DataStore<<Schema::CsvSrcSchema as BuildDStore>::OutputFields>: IntoView<Output = Self>,
+    {
+        let source = CsvSource::from_bytes(s.as_bytes())?;
+        Ok(CsvReader::new(&source, schema)?.read()?.into_view())
+    }
+}
@@ -1,21 +1,214 @@
 use std;
+use std::fmt;
+use std::io::{self, Read};
+use std::str::FromStr;
 
 use encoding::all::{ISO_8859_1, WINDOWS_1252};
-use encoding::{DecoderTrap, Encoding};
+use encoding::label::encoding_from_whatwg_label;
+use encoding::{DecoderTrap, Encoding, EncodingRef, RawDecoder};
 
 use error::*;
+use value::Value;
+
+/// The character encoding of the text in a data source.
+#[derive(Clone, Copy, Default)]
+pub enum TextEncoding {
+    /// Detects the encoding automatically (the default): text is decoded as UTF-8 if valid, and
+    /// otherwise as Windows-1252, falling back to ISO-8859-1 for bytes Windows-1252 leaves
+    /// undefined.
+    #[default]
+    Auto,
+    /// Decodes the entire source with a specific encoding (from the `encoding` crate), failing on
+    /// invalid input.
+    Fixed(EncodingRef),
+}
+
+impl TextEncoding {
+    /// Returns the encoding with the [WHATWG label](https://encoding.spec.whatwg.org/#names-and-labels)
+    /// `label` (e.g. `"utf-8"`, `"windows-1252"`, or `"shift_jis"`, ignoring case), or the
+    /// automatically-detected encoding for the label `"auto"`.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Decode` if `label` is not a known encoding label.
+    pub fn from_label(label: &str) -> Result<TextEncoding> {
+        if label.trim().eq_ignore_ascii_case("auto") {
+            return Ok(TextEncoding::Auto);
+        }
+        encoding_from_whatwg_label(label)
+            .map(TextEncoding::Fixed)
+            .ok_or_else(|| AgnesError::Decode(format!("unknown encoding label '{}'", label)))
+    }
+
+    /// Returns the name of this encoding (`"auto"` for automatic detection).
+    pub fn name(&self) -> &'static str {
+        match *self {
+            TextEncoding::Auto => "auto",
+            TextEncoding::Fixed(encoding) => encoding.name(),
+        }
+    }
+}
+
+impl fmt::Debug for TextEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TextEncoding({})", self.name())
+    }
+}
 
 #[inline]
 pub(crate) fn decode(bytes: &[u8]) -> Result<String> {
     std::str::from_utf8(bytes)
         .map(|s| s.to_string())
-        .or_else(|_| {
-            // fallback to ISO-8859-1 encoding
-            ISO_8859_1.decode(bytes, DecoderTrap::Strict)
-        })
         .or_else(|_| {
             // fallback to WINDOWS-1252 encoding
             WINDOWS_1252.decode(bytes, DecoderTrap::Strict)
         })
-        .map_err(|_| AgnesError::Decode("unabled to decode input".to_string()))
+        .or_else(|_| {
+            // fallback to ISO-8859-1 encoding (which accepts any input)
+            ISO_8859_1.decode(bytes, DecoderTrap::Strict)
+        })
+        .map_err(|_| AgnesError::Decode("unable to decode input".to_string()))
+}
+
+/// Decodes `bytes` with the encoding `encoding`, as `decode` does for `TextEncoding::Auto`.
+pub(crate) fn decode_as(bytes: &[u8], encoding: TextEncoding) -> Result<String> {
+    match encoding {
+        TextEncoding::Auto => decode(bytes),
+        TextEncoding::Fixed(encoding) => {
+            encoding.decode(bytes, DecoderTrap::Strict).map_err(|err| {
+                AgnesError::Decode(format!(
+                    "unable to decode input as {}: {}",
+                    encoding.name(),
+                    err
+                ))
+            })
+        }
+    }
+}
+
+/// Detects the encoding of `bytes`: `TextEncoding::Auto` if `bytes` is valid UTF-8, and otherwise
+/// Windows-1252, or ISO-8859-1 (which accepts any input) if `bytes` is not valid Windows-1252.
+pub(crate) fn detect_encoding(bytes: &[u8]) -> TextEncoding {
+    if std::str::from_utf8(bytes).is_ok() {
+        TextEncoding::Auto
+    } else if WINDOWS_1252.decode(bytes, DecoderTrap::Strict).is_ok() {
+        TextEncoding::Fixed(WINDOWS_1252)
+    } else {
+        TextEncoding::Fixed(ISO_8859_1)
+    }
+}
+
+// Size of the chunks read from the underlying reader of a `TextReader`.
+const TEXT_CHUNK_SIZE: usize = 1 << 13;
+
+/// The UTF-8 byte order mark, which is stripped from the start of text sources.
+pub(crate) const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Reader which transcodes the text read from an underlying reader into UTF-8, according to a
+/// [TextEncoding](enum.TextEncoding.html), stripping any leading UTF-8 byte order mark. Input with
+/// an automatically-detected encoding is otherwise passed through unchanged (and decoded
+/// value-by-value when parsed).
+pub(crate) struct TextReader<R> {
+    inner: R,
+    decoder: Option<(&'static str, Box<dyn RawDecoder>)>,
+    // transcoded (or, when passing input through, peeked) bytes not yet read
+    buffer: Vec<u8>,
+    pos: usize,
+    started: bool,
+    finished: bool,
+}
+
+impl<R: Read> TextReader<R> {
+    pub(crate) fn new(inner: R, encoding: TextEncoding) -> TextReader<R> {
+        TextReader {
+            inner,
+            decoder: match encoding {
+                TextEncoding::Auto => None,
+                TextEncoding::Fixed(encoding) => Some((encoding.name(), encoding.raw_decoder())),
+            },
+            buffer: vec![],
+            pos: 0,
+            started: false,
+            finished: false,
+        }
+    }
+
+    // Appends the next (transcoded) chunk of the underlying reader to the buffer, marking the
+    // reader as finished at the end of the input.
+    fn fill(&mut self) -> io::Result<()> {
+        let mut chunk = [0u8; TEXT_CHUNK_SIZE];
+        let n = self.inner.read(&mut chunk)?;
+        self.finished = n == 0;
+        let (name, decoder) = match self.decoder {
+            Some((name, ref mut decoder)) => (name, decoder),
+            None => {
+                self.buffer.extend_from_slice(&chunk[..n]);
+                return Ok(());
+            }
+        };
+        let mut decoded = String::new();
+        let err = if n == 0 {
+            decoder.raw_finish(&mut decoded)
+        } else {
+            decoder.raw_feed(&chunk[..n], &mut decoded).1
+        };
+        if let Some(err) = err {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unable to decode input as {}: {}", name, err.cause),
+            ));
+        }
+        self.buffer.extend_from_slice(decoded.as_bytes());
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for TextReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.started {
+            self.started = true;
+            while self.buffer.len() < UTF8_BOM.len() && !self.finished {
+                self.fill()?;
+            }
+            if self.buffer.starts_with(UTF8_BOM) {
+                self.pos = UTF8_BOM.len();
+            }
+        }
+        while self.pos == self.buffer.len() {
+            if self.finished {
+                return Ok(0);
+            }
+            if self.decoder.is_none() {
+                return self.inner.read(buf);
+            }
+            self.buffer.clear();
+            self.pos = 0;
+            self.fill()?;
+        }
+        let len = (self.buffer.len() - self.pos).min(buf.len());
+        buf[..len].copy_from_slice(&self.buffer[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+/// Parses a (decoded) source value `s` as a value of type `T`, ignoring surrounding whitespace.
+/// Empty values are missing (NA) if `empty_as_na` is set, and otherwise are parsed where possible
+/// (and missing if not).
+pub(crate) fn parse_value<T>(s: &str, empty_as_na: bool) -> Result<Value<T>>
+where
+    T: FromStr,
+    ParseError: From<T::Err>,
+{
+    let trimmed = s.trim();
+    if trimmed.is_empty() {
+        Ok(match (empty_as_na, trimmed.parse::<T>()) {
+            (false, Ok(value)) => Value::Exists(value),
+            _ => Value::Na,
+        })
+    } else {
+        trimmed
+            .parse::<T>()
+            .map(Value::Exists)
+            .map_err(|e| AgnesError::Parse(e.into()))
+    }
 }
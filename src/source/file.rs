@@ -1,51 +1,175 @@
-//! Types and implementations for reading files, both locally and over HTTP.
+//! Types and implementations for reading files, both locally and over HTTP (with the `net`
+//! feature).
 
+use std::fmt;
 use std::fs::File;
-use std::io::{self, Read, Seek, SeekFrom, Write};
-use std::mem;
+#[cfg(feature = "net")]
+use std::io::Write;
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
+#[cfg(feature = "net")]
+use std::pin::Pin;
+#[cfg(feature = "net")]
+use std::str::FromStr;
+use std::sync::Arc;
 
-use futures::stream::StreamFuture;
-use futures::Stream;
-use hyper;
-use hyper::client::Client;
+#[cfg(feature = "net")]
+use bytes::{Buf, Bytes};
+#[cfg(feature = "net")]
+use futures_util::future;
+#[cfg(feature = "object-store")]
+use futures_util::FutureExt;
+#[cfg(feature = "object-store")]
+use futures_util::StreamExt;
+#[cfg(feature = "net")]
+use futures_util::{Stream, TryFutureExt, TryStreamExt};
+#[cfg(feature = "object-store")]
+use object_store::aws::AmazonS3Builder;
+#[cfg(feature = "object-store")]
+use object_store::gcp::GoogleCloudStorageBuilder;
+#[cfg(feature = "object-store")]
+use object_store::{self, ObjectStore};
+#[cfg(feature = "net")]
+use reqwest;
+#[cfg(feature = "net")]
 use tempfile;
-
-use hyper_tls::HttpsConnector;
-use tokio_core::reactor::Core;
+#[cfg(feature = "net")]
+use tokio;
+#[cfg(feature = "net")]
+use tokio::runtime::Runtime;
+#[cfg(feature = "net")]
+use tokio_util::io::ReaderStream;
+#[cfg(feature = "net")]
+use url::Url;
 
 use error::*;
 
-/// A wrapper around hyper's Uri struct, enforcing a HTTP or HTTPs scheme.
+/// A wrapper around a URL, enforcing a HTTP or HTTPs scheme.
+#[cfg(feature = "net")]
 #[derive(Debug, Clone)]
 pub struct Uri {
-    uri: hyper::Uri,
-    scheme: UriScheme,
+    uri: Url,
+}
+
+#[cfg(feature = "net")]
+impl Uri {
+    /// Wrap a `Url` with a local `Uri` struct. Fails if scheme is not HTTP / HTTPS.
+    pub fn from_uri(orig: Url) -> Result<Uri> {
+        match orig.scheme() {
+            "http" | "https" => Ok(Uri { uri: orig }),
+            scheme => Err(NetError::UnsupportedScheme(scheme.to_string()).into()),
+        }
+    }
 }
 
+#[cfg(feature = "net")]
+impl FromStr for Uri {
+    type Err = AgnesError;
+
+    fn from_str(s: &str) -> Result<Uri> {
+        Uri::from_uri(Url::parse(s)?)
+    }
+}
+
+/// Object store service hosting an object.
+#[cfg(feature = "object-store")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectService {
+    /// Amazon S3 (`s3://` scheme)
+    S3,
+    /// Google Cloud Storage (`gs://` scheme)
+    Gcs,
+}
+
+/// Location of an object in an S3 (`s3://bucket/key`) or GCS (`gs://bucket/key`) object store.
+/// Requires the `object-store` feature.
+///
+/// Credentials and other configuration for accessing the object store are taken from the
+/// environment (e.g. `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`, and `AWS_REGION` for S3, or
+/// `GOOGLE_SERVICE_ACCOUNT` for GCS).
+#[cfg(feature = "object-store")]
 #[derive(Debug, Clone)]
-enum UriScheme {
-    Http,
-    Https,
+pub struct ObjectUri {
+    service: ObjectService,
+    bucket: String,
+    key: String,
 }
 
-impl Uri {
-    /// Wrap a `hyper::Uri` with a local `Uri` struct. Fails if scheme is not specified or not
-    /// HTTP / HTTPS.
-    pub fn from_uri(orig: hyper::Uri) -> Result<Uri> {
-        if orig.scheme_part() == Some(&hyper::http::uri::Scheme::HTTP) {
-            Ok(Uri {
-                uri: orig,
-                scheme: UriScheme::Http,
-            })
-        } else if orig.scheme_part() == Some(&hyper::http::uri::Scheme::HTTPS) {
-            Ok(Uri {
-                uri: orig,
-                scheme: UriScheme::Https,
-            })
-        } else {
-            Err(NetError::UnsupportedScheme(orig.scheme_part().cloned()).into())
+#[cfg(feature = "object-store")]
+impl ObjectUri {
+    /// Create an `ObjectUri` from a `Url`. Fails if the scheme is not `s3` or `gs`, or if either
+    /// the bucket or object key is missing.
+    pub fn from_url(orig: Url) -> Result<ObjectUri> {
+        let service = match orig.scheme() {
+            "s3" => ObjectService::S3,
+            "gs" => ObjectService::Gcs,
+            scheme => return Err(NetError::UnsupportedScheme(scheme.to_string()).into()),
+        };
+        let bucket = match orig.host_str() {
+            Some(bucket) if !bucket.is_empty() => bucket.to_string(),
+            _ => return Err(NetError::InvalidObjectUri(orig.to_string()).into()),
+        };
+        let key = orig.path().trim_matches('/').to_string();
+        if key.is_empty() {
+            return Err(NetError::InvalidObjectUri(orig.to_string()).into());
         }
+        Ok(ObjectUri {
+            service,
+            bucket,
+            key,
+        })
+    }
+
+    /// Returns the object store service hosting this object.
+    pub fn service(&self) -> ObjectService {
+        self.service
+    }
+    /// Returns the bucket containing this object.
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+    /// Returns the key of this object within its bucket.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    fn store(&self) -> Result<Arc<dyn ObjectStore>> {
+        Ok(match self.service {
+            ObjectService::S3 => Arc::new(
+                AmazonS3Builder::from_env()
+                    .with_bucket_name(self.bucket.clone())
+                    .build()?,
+            ),
+            ObjectService::Gcs => Arc::new(
+                GoogleCloudStorageBuilder::from_env()
+                    .with_bucket_name(self.bucket.clone())
+                    .build()?,
+            ),
+        })
+    }
+
+    fn path(&self) -> object_store::path::Path {
+        object_store::path::Path::from(self.key.as_str())
+    }
+}
+
+#[cfg(feature = "object-store")]
+impl fmt::Display for ObjectUri {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let scheme = match self.service {
+            ObjectService::S3 => "s3",
+            ObjectService::Gcs => "gs",
+        };
+        write!(f, "{}://{}/{}", scheme, self.bucket, self.key)
+    }
+}
+
+#[cfg(feature = "object-store")]
+impl FromStr for ObjectUri {
+    type Err = AgnesError;
+
+    fn from_str(s: &str) -> Result<ObjectUri> {
+        ObjectUri::from_url(Url::parse(s)?)
     }
 }
 
@@ -53,9 +177,43 @@ impl Uri {
 #[derive(Debug, Clone)]
 pub enum FileLocator {
     /// A web-based location (URI)
+    #[cfg(feature = "net")]
     Web(Uri),
+    /// An object in an S3 or GCS object store
+    #[cfg(feature = "object-store")]
+    Object(ObjectUri),
     /// A local file
     File(PathBuf),
+    /// File contents held in memory
+    Memory(Arc<[u8]>),
+}
+
+impl FileLocator {
+    /// Create a `FileLocator` from a URI string. Accepts HTTP / HTTPS URIs, as well as S3
+    /// (`s3://bucket/key`) and GCS (`gs://bucket/key`) URIs if the `object-store` feature is
+    /// enabled. Requires the `net` feature.
+    #[cfg(feature = "net")]
+    pub fn from_uri(uri: &str) -> Result<FileLocator> {
+        let url = Url::parse(uri)?;
+        match url.scheme() {
+            #[cfg(feature = "object-store")]
+            "s3" | "gs" => Ok(FileLocator::Object(ObjectUri::from_url(url)?)),
+            _ => Ok(FileLocator::Web(Uri::from_uri(url)?)),
+        }
+    }
+}
+
+impl fmt::Display for FileLocator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            #[cfg(feature = "net")]
+            FileLocator::Web(Uri { ref uri }) => write!(f, "{}", uri),
+            #[cfg(feature = "object-store")]
+            FileLocator::Object(ref obj) => write!(f, "{}", obj),
+            FileLocator::File(ref path) => write!(f, "{}", path.display()),
+            FileLocator::Memory(ref data) => write!(f, "<memory ({} bytes)>", data.len()),
+        }
+    }
 }
 
 impl<'a> From<&'a Path> for FileLocator {
@@ -73,16 +231,35 @@ impl From<PathBuf> for FileLocator {
         FileLocator::File(orig)
     }
 }
+impl From<Vec<u8>> for FileLocator {
+    fn from(orig: Vec<u8>) -> FileLocator {
+        FileLocator::Memory(orig.into())
+    }
+}
+#[cfg(feature = "net")]
 impl From<Uri> for FileLocator {
     fn from(orig: Uri) -> FileLocator {
         FileLocator::Web(orig)
     }
 }
 
-/// File reader for reading from files locally.
+#[cfg(feature = "object-store")]
+impl From<ObjectUri> for FileLocator {
+    fn from(orig: ObjectUri) -> FileLocator {
+        FileLocator::Object(orig)
+    }
+}
+
+/// File reader for reading from files locally (or from file contents held in memory).
 #[derive(Debug)]
 pub struct LocalFileReader {
-    file: File,
+    file: LocalFile,
+}
+
+#[derive(Debug)]
+enum LocalFile {
+    File(File),
+    Memory(Cursor<Arc<[u8]>>),
 }
 impl LocalFileReader {
     /// Create new reader from a file locator, creating a temporary local file if the file specified
@@ -95,8 +272,14 @@ impl LocalFileReader {
         match *loc {
             FileLocator::File(ref path) => {
                 let file = File::open(path)?;
-                Ok(LocalFileReader { file })
+                Ok(LocalFileReader {
+                    file: LocalFile::File(file),
+                })
             }
+            FileLocator::Memory(ref data) => Ok(LocalFileReader {
+                file: LocalFile::Memory(Cursor::new(data.clone())),
+            }),
+            #[cfg(feature = "net")]
             FileLocator::Web(_) => {
                 // download file up to nbytes and save it to temp directory
                 const BUF_SIZE: usize = 1 << 13; // 8 * 1024
@@ -119,28 +302,58 @@ impl LocalFileReader {
                     }
                 }
                 temp_file.seek(SeekFrom::Start(0))?;
-                Ok(LocalFileReader { file: temp_file })
+                Ok(LocalFileReader {
+                    file: LocalFile::File(temp_file),
+                })
+            }
+            #[cfg(feature = "object-store")]
+            FileLocator::Object(ref obj) => {
+                // download the object and save it to temp directory
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()?;
+                let store = obj.store()?;
+                let mut stream = runtime.block_on(store.get(&obj.path()))?.into_stream();
+                let mut temp_file: File = tempfile::tempfile()?;
+                while let Some(chunk) = runtime.block_on(stream.next()) {
+                    temp_file.write_all(&chunk?)?;
+                }
+                temp_file.seek(SeekFrom::Start(0))?;
+                Ok(LocalFileReader {
+                    file: LocalFile::File(temp_file),
+                })
             }
         }
     }
 }
 impl Read for LocalFileReader {
     fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
-        self.file.read(out)
+        match self.file {
+            LocalFile::File(ref mut file) => file.read(out),
+            LocalFile::Memory(ref mut data) => data.read(out),
+        }
     }
 }
 impl Seek for LocalFileReader {
     fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
-        self.file.seek(pos)
+        match self.file {
+            LocalFile::File(ref mut file) => file.seek(pos),
+            LocalFile::Memory(ref mut data) => data.seek(pos),
+        }
     }
 }
 
-/// File reader for files served over HTTP.
+/// File reader for files served over HTTP. Blocks on an internal single-threaded runtime while
+/// waiting for the response body, so it must not be used from within an asynchronous context; use
+/// [byte_stream](fn.byte_stream.html) instead.
+#[cfg(feature = "net")]
 #[derive(Debug)]
 pub struct HttpFileReader {
-    core: Core,
-    response_state: State,
+    runtime: Runtime,
+    response: reqwest::Response,
+    buffer: Bytes,
 }
+#[cfg(feature = "net")]
 impl HttpFileReader {
     /// Create a new reader from a file locator.
     ///
@@ -149,136 +362,109 @@ impl HttpFileReader {
     /// the remote file.
     pub fn new(loc: &FileLocator) -> Result<HttpFileReader> {
         match *loc {
-            FileLocator::File(_) => Err(NetError::LocalFile.into()),
-            FileLocator::Web(Uri {
-                ref uri,
-                scheme: UriScheme::Http,
-            }) => {
-                // establish event loop
-                let mut core = Core::new()?;
-                // configure a HTTP client to retrieve the file
-                let client = Client::new();
-                // set up a future to retrieve the file.
-                let resp = client.get(uri.clone());
-                Ok(HttpFileReader {
-                    core,
-                    response_state: State::Awaiting(resp),
-                })
-            }
-            FileLocator::Web(Uri {
-                ref uri,
-                scheme: UriScheme::Https,
-            }) => {
-                // establish event loop
-                let mut core = Core::new()?;
-                // configure a HTTPS client to retrieve the file
-                let client = Client::builder().build::<_, hyper::Body>(HttpsConnector::new(4)?);
-                // set up a future to retrieve the file.
-                let resp = client.get(uri.clone());
+            FileLocator::File(_) | FileLocator::Memory(_) => Err(NetError::LocalFile.into()),
+            #[cfg(feature = "object-store")]
+            FileLocator::Object(_) => Err(NetError::ObjectFile.into()),
+            FileLocator::Web(Uri { ref uri }) => {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()?;
+                let response = runtime
+                    .block_on(reqwest::Client::new().get(uri.clone()).send())?
+                    .error_for_status()?;
                 Ok(HttpFileReader {
-                    core,
-                    response_state: State::Awaiting(resp),
+                    runtime,
+                    response,
+                    buffer: Bytes::new(),
                 })
             }
         }
     }
 }
+#[cfg(feature = "net")]
 impl Read for HttpFileReader {
     fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
-        let (response_state, core) = (&mut self.response_state, &mut self.core);
-
-        // Check the existing response state, temporarily storing the 'Empty' state so
-        // we can move stuff out of the current state
-        let (body, mut buf) = match mem::replace(response_state, State::Empty) {
-            State::Awaiting(resp) => {
-                // run the response future and block until we get it
-                let resp = core
-                    .run(resp)
-                    .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
-                (resp.into_body().into_future(), vec![])
+        while self.buffer.is_empty() {
+            // block until we get the next chunk of the body
+            match self
+                .runtime
+                .block_on(self.response.chunk())
+                .map_err(io::Error::other)?
+            {
+                Some(chunk) => self.buffer = chunk,
+                None => return Ok(0),
             }
-            State::Body { body, buffer } => (body, buffer),
-            State::Empty => panic!("double empty!"),
-        };
-
-        // start by putting everything from the buffer into the output
-        let buflen = buf.len();
-        let outlen = out.len();
-        if buflen > 0 && buflen > outlen {
-            // we have a buffer, but it's most the than available space in the output.
-            // copy everything we can into the output, then remove that stuff from the
-            // buffer
-            out[..].copy_from_slice(&buf[0..outlen]);
-            let tmp = buf.split_off(outlen);
-            mem::replace(&mut buf, tmp);
-            // Buffer is full, so we can go ahead and update the state and then return
-            mem::replace(response_state, State::Body { body, buffer: buf });
-            return Ok(outlen);
-        }
-
-        if buflen > 0 {
-            // we have a buffer, and it's less than the output length (or we would've
-            // already returned), so copy the whole buffer into the output.
-            out[0..buflen].copy_from_slice(&buf[..]);
-            buf.clear();
-            // mem::replace(response_state, State::Body { body, buffer: buf });
         }
+        let n = out.len().min(self.buffer.len());
+        out[..n].copy_from_slice(&self.buffer[..n]);
+        self.buffer.advance(n);
+        Ok(n)
+    }
+}
 
-        // let's get the next chunk of the body
-        let (chunk, body) = core
-            .run(body)
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.0))?;
-
-        let total_len = match chunk {
-            Some(ref chunk) => {
-                let chunklen = chunk.len();
-                if buflen + chunklen <= outlen {
-                    // Chunk is smaller than output buffer, just copy everything over
-                    // into output
-                    out[buflen..buflen + chunklen].copy_from_slice(&chunk[..]);
-                    buflen + chunklen
-                } else {
-                    // Chunk is larger than output buffer, copy what we can into the
-                    // output, then put everythig else in a buffer
-                    out[buflen..].copy_from_slice(&chunk[0..outlen - buflen]);
-                    buf.extend_from_slice(&chunk[outlen - buflen..]);
-                    outlen
-                }
-            }
-            None => buflen,
-        };
+/// Stream of chunks of file contents, as returned by [byte_stream](fn.byte_stream.html).
+#[cfg(feature = "net")]
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>;
 
-        if total_len > 0 {
-            mem::replace(
-                response_state,
-                State::Body {
-                    body: body.into_future(),
-                    buffer: buf,
-                },
-            );
-        } else {
-            mem::replace(response_state, State::Empty);
+/// Asynchronously read the contents of the file specified by a file locator as a stream of chunks.
+/// Remote files are streamed as the response body arrives, without first being saved to a
+/// temporary file. Must be polled from within a Tokio runtime.
+#[cfg(feature = "net")]
+pub fn byte_stream(loc: &FileLocator) -> ByteStream {
+    match *loc {
+        FileLocator::File(ref path) => Box::pin(
+            tokio::fs::File::open(path.clone())
+                .map_ok(ReaderStream::new)
+                .try_flatten_stream()
+                .map_err(AgnesError::from),
+        ),
+        FileLocator::Memory(ref data) => Box::pin(futures_util::stream::once(future::ok(
+            Bytes::copy_from_slice(data),
+        ))),
+        FileLocator::Web(Uri { ref uri }) => Box::pin(
+            reqwest::Client::new()
+                .get(uri.clone())
+                .send()
+                .and_then(|response| future::ready(response.error_for_status()))
+                .map_ok(|response| response.bytes_stream())
+                .try_flatten_stream()
+                .map_err(AgnesError::from),
+        ),
+        #[cfg(feature = "object-store")]
+        FileLocator::Object(ref obj) => {
+            let (store, path) = match obj.store() {
+                Ok(store) => (store, obj.path()),
+                Err(err) => return Box::pin(futures_util::stream::once(future::err(err))),
+            };
+            // `ObjectStore::get` returns a future borrowing the store and path, which can't be
+            // returned alongside the values it borrows without async blocks (unavailable in the
+            // 2015 edition). Instead, the owned store and path move to a thread of the blocking
+            // pool, which waits there for the response headers with `Handle::block_on` while the
+            // runtime keeps driving its I/O. The body stream of the response doesn't borrow the
+            // store, and is polled from this stream as it arrives.
+            Box::pin(
+                tokio::task::spawn_blocking(move || {
+                    tokio::runtime::Handle::current().block_on(store.get(&path))
+                })
+                .map(|joined| {
+                    joined
+                        .map_err(AgnesError::from)
+                        .and_then(|result| result.map_err(AgnesError::from))
+                })
+                .map_ok(|result| result.into_stream().map_err(AgnesError::from))
+                .try_flatten_stream(),
+            )
         }
-        Ok(total_len)
     }
 }
 
-#[derive(Debug)]
-enum State {
-    Awaiting(hyper::client::ResponseFuture),
-    Body {
-        body: StreamFuture<hyper::Body>,
-        buffer: Vec<u8>,
-    },
-    Empty,
-}
-
 /// Abstract general file reader, implementing `Read`.
 #[derive(Debug)]
 pub enum FileReader {
     /// Implements `Read` for local files
     Local(LocalFileReader),
     /// Implements `Read` for http-served files (boxed since HttpFileReader is large)
+    #[cfg(feature = "net")]
     Http(Box<HttpFileReader>),
 }
 
@@ -286,7 +472,12 @@ impl FileReader {
     /// Create new reader from a file locator.
     pub fn new(loc: &FileLocator) -> Result<FileReader> {
         match *loc {
-            FileLocator::File(_) => Ok(FileReader::Local(LocalFileReader::new(loc)?)),
+            FileLocator::File(_) | FileLocator::Memory(_) => {
+                Ok(FileReader::Local(LocalFileReader::new(loc)?))
+            }
+            #[cfg(feature = "object-store")]
+            FileLocator::Object(_) => Ok(FileReader::Local(LocalFileReader::new(loc)?)),
+            #[cfg(feature = "net")]
             FileLocator::Web(_) => Ok(FileReader::Http(Box::new(HttpFileReader::new(loc)?))),
         }
     }
@@ -295,7 +486,38 @@ impl Read for FileReader {
     fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
         match *self {
             FileReader::Local(ref mut reader) => reader.read(out),
+            #[cfg(feature = "net")]
             FileReader::Http(ref mut reader) => reader.read(out),
         }
     }
 }
+
+#[cfg(all(test, feature = "object-store"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn object_uri() {
+        let obj = "s3://bucket/path/to/data.csv".parse::<ObjectUri>().unwrap();
+        assert_eq!(obj.service(), ObjectService::S3);
+        assert_eq!(obj.bucket(), "bucket");
+        assert_eq!(obj.key(), "path/to/data.csv");
+
+        let obj = "gs://bucket/data.csv".parse::<ObjectUri>().unwrap();
+        assert_eq!(obj.service(), ObjectService::Gcs);
+        assert_eq!(obj.bucket(), "bucket");
+        assert_eq!(obj.key(), "data.csv");
+
+        assert!("s3://bucket/".parse::<ObjectUri>().is_err());
+        assert!("https://bucket/data.csv".parse::<ObjectUri>().is_err());
+
+        match FileLocator::from_uri("gs://bucket/data.csv").unwrap() {
+            FileLocator::Object(_) => {}
+            loc => panic!("expected object locator, found {:?}", loc),
+        }
+        match FileLocator::from_uri("https://example.com/data.csv").unwrap() {
+            FileLocator::Web(_) => {}
+            loc => panic!("expected web locator, found {:?}", loc),
+        }
+    }
+}
@@ -0,0 +1,310 @@
+//! Fixed-width text file (FWF) source and reader objects and implementation.
+//!
+//! In a fixed-width file, each line is a record, and each field occupies the same range of byte
+//! columns on every line. Fields are specified with the [fwf_schema](../../macro.fwf_schema.html)
+//! macro, which connects field labels with byte ranges. Values are decoded, trimmed, and parsed the
+//! same way as values of CSV files: surrounding whitespace is ignored, and empty values (including
+//! ranges beyond the end of a short line) are missing (NA) unless disabled with
+//! [FwfSource::empty_as_na](struct.FwfSource.html#method.empty_as_na).
+//!
+//! Byte ranges refer to the encoded file: each value is sliced out of its line before being decoded
+//! with the source's [encoding](struct.FwfSource.html#method.encoding), so files in multi-byte
+//! legacy encodings (such as Shift-JIS) keep their column layout. The encoding must represent line
+//! breaks with single `'\n'` bytes (as all ASCII-compatible encodings do).
+
+use std::fmt::Debug;
+use std::io::Read;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use cons::*;
+use error::*;
+use fieldlist::{FieldPayloadCons, FieldSchema};
+use frame::SimpleFrameFields;
+use label::{TypedValue, Valued};
+use source::decode::{decode_as, parse_value, TextEncoding, UTF8_BOM};
+use source::file::{FileLocator, LocalFileReader};
+use store::{AssocFrameLookup, AssocStorage, DataStore, IntoView, PushFrontFromValueIter};
+use value::Value;
+
+/// Type alias for a cons-list containing fields with their labels, data type, and the range of
+/// byte columns they occupy in a fixed-width file.
+pub type FwfSchemaCons<Label, DType, Tail> = FieldPayloadCons<Label, DType, Range<usize>, Tail>;
+
+impl<Label, DType, Tail> FwfSchemaCons<Label, DType, Tail> {
+    /// Create a new `FwfSchemaCons` cons-list from a range of byte columns.
+    pub fn new(columns: Range<usize>, tail: Tail) -> FwfSchemaCons<Label, DType, Tail> {
+        FwfSchemaCons {
+            head: TypedValue::from(columns).into(),
+            tail,
+        }
+    }
+}
+
+/// Macro for creating a source specification structure for fixed-width files, correlating labels
+/// (defined using the [tablespace](macro.tablespace.html) macro) to the range of byte columns
+/// (starting from 0, end-exclusive) each field occupies on a line. This source specification
+/// structure is implemented as a [FwfSchemaCons](source/fwf/type.FwfSchemaCons.html) cons-list.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate agnes;
+///
+/// tablespace![
+///     table station {
+///         StationId: u64,
+///         Name: String,
+///         Elevation: f64,
+///     }
+/// ];
+///
+/// fn main() {
+///     let station_schema = fwf_schema![
+///         columns station::StationId = 0..6;
+///         columns station::Name = 7..37;
+///         columns station::Elevation = 38..46;
+///     ];
+///     // ...
+/// }
+/// ```
+#[macro_export]
+macro_rules! fwf_schema {
+    () => {{
+        $crate::cons::Nil
+    }};
+    (columns $field_label:ty = $columns:expr; $($rest:tt)*) => {{
+        use $crate::source::fwf::FwfSchemaCons;
+        FwfSchemaCons::<
+            $field_label,
+            <$field_label as $crate::label::Typed>::DType,
+            _,
+        >::new(
+            $columns,
+            fwf_schema![$($rest)*]
+        )
+    }};
+}
+
+/// A trait for validating the byte column ranges of a [FwfSchemaCons](type.FwfSchemaCons.html).
+pub trait FwfColumns {
+    /// Fails with `AgnesError::FwfColumns` if any of the byte column ranges of this schema are
+    /// empty.
+    fn check_columns(&self) -> Result<()>;
+}
+impl FwfColumns for Nil {
+    fn check_columns(&self) -> Result<()> {
+        Ok(())
+    }
+}
+impl<Label, DType, Tail> FwfColumns for FwfSchemaCons<Label, DType, Tail>
+where
+    Tail: FwfColumns,
+{
+    fn check_columns(&self) -> Result<()> {
+        let columns = self.head.value_ref();
+        if columns.start >= columns.end {
+            return Err(AgnesError::FwfColumns(format!(
+                "empty column range {}..{}",
+                columns.start, columns.end
+            )));
+        }
+        self.tail.check_columns()
+    }
+}
+
+/// A trait for building a [DataStore](../../store/struct.DataStore.html) from a
+/// [FwfSchemaCons](type.FwfSchemaCons.html).
+pub trait BuildFwfStore {
+    /// `Fields` type parameter of the resultant `DataStore`.
+    type OutputFields: AssocStorage;
+
+    /// Builds a `DataStore` from the source schema (`self`) and the lines (records) of the
+    /// fixed-width file `src`.
+    fn build_from_lines(
+        &self,
+        lines: &[&[u8]],
+        src: &FwfSource,
+    ) -> Result<DataStore<Self::OutputFields>>;
+}
+impl BuildFwfStore for Nil {
+    type OutputFields = Nil;
+    fn build_from_lines(&self, _lines: &[&[u8]], _src: &FwfSource) -> Result<DataStore<Nil>> {
+        Ok(DataStore::<Nil>::empty())
+    }
+}
+impl<Label, DType, Tail> BuildFwfStore for FwfSchemaCons<Label, DType, Tail>
+where
+    Tail: BuildFwfStore,
+    DataStore<<Tail as BuildFwfStore>::OutputFields>: PushFrontFromValueIter<Label, DType>,
+    Tail::OutputFields: PushBack<FieldSchema<Label, DType>>,
+    <Tail::OutputFields as PushBack<FieldSchema<Label, DType>>>::Output: AssocStorage,
+    Label: Debug,
+    DType: FromStr + Debug + Default + Clone,
+    ParseError: From<<DType as FromStr>::Err>,
+{
+    type OutputFields =
+        <DataStore<<Tail as BuildFwfStore>::OutputFields> as PushFrontFromValueIter<
+            Label,
+            DType,
+        >>::OutputFields;
+
+    fn build_from_lines(
+        &self,
+        lines: &[&[u8]],
+        src: &FwfSource,
+    ) -> Result<DataStore<Self::OutputFields>> {
+        let ds = self.tail.build_from_lines(lines, src)?;
+
+        let columns = self.head.value_ref();
+        let values: Vec<Value<DType>> = lines
+            .iter()
+            .map(|line| {
+                let start = columns.start.min(line.len());
+                let end = columns.end.min(line.len());
+                parse_value(
+                    &decode_as(&line[start..end], src.encoding)?,
+                    src.empty_as_na,
+                )
+            })
+            .collect::<Result<_>>()?;
+        let ds = ds.push_front_from_value_iter::<Label, DType, _, _>(values);
+
+        Ok(ds)
+    }
+}
+
+/// Fixed-width file data source. Contains the location of the data file, the number of lines
+/// preceding the records (e.g. header lines) to skip, and the options controlling how values are
+/// decoded and parsed. Can be turned into a `FwfReader` object.
+#[derive(Debug, Clone)]
+pub struct FwfSource {
+    // File source object for the fixed-width file
+    src: FileLocator,
+    // Number of lines to skip before the first record
+    skip_lines: usize,
+    // Whether or not empty values are missing (NA) values
+    empty_as_na: bool,
+    // Character encoding of the fixed-width file
+    encoding: TextEncoding,
+}
+
+impl FwfSource {
+    /// Create a new `FwfSource` object with provided file location, with empty values treated as
+    /// missing (NA) and automatically-detected text encoding.
+    pub fn new<L: Into<FileLocator>>(loc: L) -> FwfSource {
+        FwfSource {
+            src: loc.into(),
+            skip_lines: 0,
+            empty_as_na: true,
+            encoding: TextEncoding::Auto,
+        }
+    }
+    /// Sets the number of lines (e.g. header or separator lines) to skip before the first record.
+    pub fn skip_lines(mut self, skip_lines: usize) -> FwfSource {
+        self.skip_lines = skip_lines;
+        self
+    }
+    /// Sets whether or not empty (or whitespace-only) values are loaded as missing (NA) values.
+    /// If `false`, empty values are parsed as values of the field's data type where possible
+    /// (e.g. empty strings for `String` fields), and are missing otherwise.
+    pub fn empty_as_na(mut self, empty_as_na: bool) -> FwfSource {
+        self.empty_as_na = empty_as_na;
+        self
+    }
+    /// Sets the character encoding of the file (see
+    /// [TextEncoding](../csv/enum.TextEncoding.html)). With the default automatic detection, values
+    /// which are not valid UTF-8 are decoded as Windows-1252 (or ISO-8859-1, if not valid
+    /// Windows-1252).
+    pub fn encoding(mut self, encoding: TextEncoding) -> FwfSource {
+        self.encoding = encoding;
+        self
+    }
+    /// Return the location of this fixed-width source.
+    pub fn locator(&self) -> &FileLocator {
+        &self.src
+    }
+}
+
+/// Object for reading fixed-width file sources.
+#[derive(Debug)]
+pub struct FwfReader<FwfSchema> {
+    src: FwfSource,
+    fwf_schema: FwfSchema,
+}
+
+impl<FwfSchema> FwfReader<FwfSchema>
+where
+    FwfSchema: FwfColumns + Debug,
+{
+    /// Create a new fixed-width file reader from a source specification and a schema (created with
+    /// the [fwf_schema](../../macro.fwf_schema.html) macro).
+    ///
+    /// # Error
+    /// Fails if any of the column ranges of `schema` are empty.
+    pub fn new(src: &FwfSource, schema: FwfSchema) -> Result<FwfReader<FwfSchema>> {
+        schema.check_columns()?;
+        Ok(FwfReader {
+            src: src.clone(),
+            fwf_schema: schema,
+        })
+    }
+
+    /// Read a `FwfSource` into a `DataStore` object. Blank lines are ignored, line endings may be
+    /// either `"\n"` or `"\r\n"`, and a leading UTF-8 byte order mark is stripped.
+    ///
+    /// # Error
+    /// Fails if unable to read the file, or if any value cannot be decoded with the source's
+    /// encoding or parsed as its field's data type.
+    pub fn read(&mut self) -> Result<DataStore<FwfSchema::OutputFields>>
+    where
+        FwfSchema: BuildFwfStore,
+    {
+        let mut contents = vec![];
+        LocalFileReader::new(&self.src.src)?.read_to_end(&mut contents)?;
+        let contents = contents.strip_prefix(UTF8_BOM).unwrap_or(&contents);
+        let lines = contents
+            .split(|&b| b == b'\n')
+            .skip(self.src.skip_lines)
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .filter(|line| !line.iter().all(u8::is_ascii_whitespace))
+            .collect::<Vec<_>>();
+        self.fwf_schema.build_from_lines(&lines, &self.src)
+    }
+}
+
+/// Utility function for loading a fixed-width file from a
+/// [FileLocator](../file/enum.FileLocator.html), skipping the first `skip_lines` lines.
+///
+/// Fails if unable to find or read file at location specified, or if the schema is invalid.
+pub fn load_fwf<L: Into<FileLocator>, Schema>(
+    loc: L,
+    skip_lines: usize,
+    schema: Schema,
+) -> Result<<DataStore<<Schema as BuildFwfStore>::OutputFields> as IntoView>::Output>
+where
+    Schema: BuildFwfStore + FwfColumns + Debug,
+    <Schema as BuildFwfStore>::OutputFields: AssocFrameLookup + SimpleFrameFields,
+{
+    let source = FwfSource::new(loc).skip_lines(skip_lines);
+    let mut fwf_reader = FwfReader::new(&source, schema)?;
+    Ok(fwf_reader.read()?.into_view())
+}
+
+/// Utility function for loading a fixed-width file from a local file path, skipping the first
+/// `skip_lines` lines.
+///
+/// Fails if unable to find or read file at the location specified, or if the schema is invalid.
+pub fn load_fwf_from_path<P, Schema>(
+    path: P,
+    skip_lines: usize,
+    schema: Schema,
+) -> Result<<DataStore<<Schema as BuildFwfStore>::OutputFields> as IntoView>::Output>
+where
+    P: Into<PathBuf>,
+    Schema: BuildFwfStore + FwfColumns + Debug,
+    <Schema as BuildFwfStore>::OutputFields: AssocFrameLookup + SimpleFrameFields,
+{
+    load_fwf(path.into(), skip_lines, schema)
+}
@@ -1,6 +1,12 @@
 //! Data sources.
 
+#[cfg(feature = "net")]
+pub mod async_csv;
+#[cfg(feature = "avro")]
+pub mod avro;
 pub mod csv;
 pub mod file;
+pub mod fwf;
+pub mod stream;
 
 pub(crate) mod decode;
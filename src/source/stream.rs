@@ -0,0 +1,486 @@
+//! Reader objects and implementation for CSV data from non-seekable streams, such as standard
+//! input or a pipe.
+//!
+//! Unlike a [CsvSource](../csv/struct.CsvSource.html), which is analyzed once and then reopened
+//! for reading, a stream can only be read once. A [CsvStreamReader](struct.CsvStreamReader.html)
+//! buffers the first few kilobytes of the stream to sniff the CSV dialect, and then continues
+//! reading the records from the buffered sample followed by the remainder of the stream. This
+//! allows agnes-based tools to be used in shell pipelines (e.g. `cat data.csv | mytool`).
+//!
+//! Records are read from the stream as they are loaded, so a stream can be filtered, loaded in
+//! batches, or sampled (through [CsvStreamReader::open](struct.CsvStreamReader.html#method.open))
+//! in the same way as a [CsvReader](../csv/struct.CsvReader.html) reads a file.
+
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::io::{self, Chain, Cursor, Read, Stdin};
+use std::str::FromStr;
+
+use csv::{ByteRecord, Reader, ReaderBuilder};
+use csv_sniffer::Sniffer;
+
+use error::*;
+use frame::SimpleFrameFields;
+use label::{ElemOf, LookupElemByLabel, TypeOf, Typed, Valued};
+use observer::{NoObserver, Observer, Reporter, Stage};
+use source::csv::{
+    build_batched, collect_records, read_headers, sample_from, BatchControl, BatchView,
+    BuildDStore, CsvSrcIndices, HeaderNormalization, IntoCsvSrcSchema, Projection, RaggedRows,
+    RecordConfig, RecordFilter, RecordOptions, Records,
+};
+use source::decode::{TextEncoding, TextReader};
+use store::{AssocFrameLookup, DataStore, IntoView};
+use value::Value;
+
+// Number of bytes to collect before sniffing the CSV dialect (the `csv-sniffer` default sample
+// size).
+const SNIFF_SAMPLE_SIZE: usize = 1 << 12;
+
+// CSV reader over a stream, continuing from its buffered sample.
+type StreamCsvReader<R> = Reader<Chain<Cursor<Vec<u8>>, R>>;
+
+/// Sniffs the CSV dialect and reads the header row of `reader`, returning the source schema
+/// resulting from `schema`, a CSV reader positioned at the first record, and the number of fields
+/// of the stream (as sniffed). Only the sample used for sniffing is buffered.
+pub(crate) fn open_stream<R, Schema>(
+    mut reader: R,
+    schema: Schema,
+    normalization: &HeaderNormalization,
+) -> Result<(Schema::CsvSrcSchema, StreamCsvReader<R>, usize)>
+where
+    R: Read,
+    Schema: IntoCsvSrcSchema,
+{
+    // collect the sample to sniff, extending it to the end of the last (partial) line
+    let mut sample = vec![];
+    (&mut reader)
+        .take(SNIFF_SAMPLE_SIZE as u64)
+        .read_to_end(&mut sample)?;
+    let mut byte = [0u8];
+    while sample.last().map(|&b| b != b'\n').unwrap_or(false) && reader.read(&mut byte)? > 0 {
+        sample.push(byte[0]);
+    }
+    let metadata = Sniffer::new().sniff_reader(Cursor::new(&sample))?;
+    debug_assert_eq!(metadata.num_fields, metadata.types.len());
+
+    let preamble_len = preamble_len(&sample, metadata.dialect.header.num_preamble_rows)?;
+    let mut sample = Cursor::new(sample);
+    sample.set_position(preamble_len as u64);
+
+    let mut csv_reader = ReaderBuilder::from(metadata.dialect.clone())
+        .flexible(true)
+        .from_reader(Read::chain(sample, reader));
+    let headers: HashMap<String, usize> = read_headers(&mut csv_reader, &metadata, normalization)?;
+    let csv_src_schema =
+        schema.into_csv_src_schema(&headers, normalization, metadata.num_fields)?;
+    Ok((csv_src_schema, csv_reader, metadata.num_fields))
+}
+
+/// Sniffs the CSV dialect, reads the header row, and reads all records from `reader` (projected
+/// to the columns of `schema`), reporting each record read to `reporter`.
+pub(crate) fn parse_records<R, Schema>(
+    reader: R,
+    schema: Schema,
+    normalization: &HeaderNormalization,
+    record_options: RecordOptions,
+    reporter: &mut Reporter,
+) -> Result<(Schema::CsvSrcSchema, Vec<ByteRecord>, Projection)>
+where
+    R: Read,
+    Schema: IntoCsvSrcSchema,
+    Schema::CsvSrcSchema: CsvSrcIndices,
+{
+    let (csv_src_schema, mut csv_reader, num_fields) = open_stream(reader, schema, normalization)?;
+    let config = RecordConfig::new(Projection::of(&csv_src_schema));
+    let records = collect_records(
+        Records::new(
+            &mut csv_reader,
+            num_fields,
+            record_options,
+            config.projection().clone(),
+        ),
+        &config,
+        &[],
+        reporter,
+    )?;
+    Ok((csv_src_schema, records, config.projection().clone()))
+}
+
+// Computes the number of bytes taken up by the first `num_preamble_rows` lines of `sample`.
+pub(crate) fn preamble_len(sample: &[u8], num_preamble_rows: usize) -> Result<usize> {
+    let mut len = 0;
+    for _ in 0..num_preamble_rows {
+        match sample[len..].iter().position(|&b| b == b'\n') {
+            Some(pos) => len += pos + 1,
+            None => {
+                return Err(AgnesError::CsvDialect(
+                    "preamble exceeds CSV sample size".into(),
+                ));
+            }
+        }
+    }
+    Ok(len)
+}
+
+/// Object for reading CSV data from a (possibly non-seekable) stream.
+#[derive(Debug)]
+pub struct CsvStreamReader<R> {
+    reader: R,
+    empty_as_na: bool,
+    encoding: TextEncoding,
+    header_normalization: HeaderNormalization,
+    record_options: RecordOptions,
+}
+
+impl<R> CsvStreamReader<R>
+where
+    R: Read,
+{
+    /// Create a new CSV reader for the stream `reader`. No data is read until
+    /// [read](#method.read) is called.
+    pub fn new(reader: R) -> CsvStreamReader<R> {
+        CsvStreamReader {
+            reader,
+            empty_as_na: true,
+            encoding: TextEncoding::Auto,
+            header_normalization: HeaderNormalization::new(),
+            record_options: RecordOptions::default(),
+        }
+    }
+
+    /// Sets whether empty values are treated as missing (NA) (the default), or are parsed as the
+    /// field's data type (e.g. as empty `String`s).
+    pub fn empty_as_na(mut self, empty_as_na: bool) -> CsvStreamReader<R> {
+        self.empty_as_na = empty_as_na;
+        self
+    }
+
+    /// Sets the character encoding of the stream (see
+    /// [TextEncoding](../csv/enum.TextEncoding.html)). With a fixed encoding, the stream is
+    /// transcoded to UTF-8 as it is read.
+    pub fn encoding(mut self, encoding: TextEncoding) -> CsvStreamReader<R> {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Sets the normalization applied to header names, and to the schema field names they are
+    /// matched against (see [HeaderNormalization](../csv/struct.HeaderNormalization.html)).
+    pub fn normalize_headers(mut self, normalization: HeaderNormalization) -> CsvStreamReader<R> {
+        self.header_normalization = normalization;
+        self
+    }
+
+    /// Sets the number of footer records at the end of the stream to skip (see
+    /// [SniffOptions::skip_footer](../csv/struct.SniffOptions.html#method.skip_footer)).
+    pub fn skip_footer(mut self, skip_footer: usize) -> CsvStreamReader<R> {
+        self.record_options.skip_footer = skip_footer;
+        self
+    }
+
+    /// Sets the maximum number of records to read. Reading stops (leaving the rest of the stream
+    /// unread) once `max_rows` records have been read.
+    pub fn max_rows(mut self, max_rows: usize) -> CsvStreamReader<R> {
+        self.record_options.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Sets how records with a different number of fields than the stream (as sniffed) are
+    /// handled (see [RaggedRows](../csv/enum.RaggedRows.html)).
+    pub fn ragged_rows(mut self, ragged_rows: RaggedRows) -> CsvStreamReader<R> {
+        self.record_options.ragged_rows = ragged_rows;
+        self
+    }
+
+    /// Sniffs the CSV dialect and reads the header row of this stream, verifying that the fields
+    /// specified by `schema` exist, and returns a [CsvStream](struct.CsvStream.html) for reading
+    /// its records. Only the sample of the stream used for sniffing is read.
+    ///
+    /// # Errors
+    /// Fails if unable to read from the stream, if CSV analysis fails, or if the fields specified in
+    /// the schema do not exist in the CSV data.
+    pub fn open<Schema>(self, schema: Schema) -> Result<CsvStream<R, Schema::CsvSrcSchema>>
+    where
+        Schema: IntoCsvSrcSchema,
+    {
+        let (csv_src_schema, csv_reader, num_fields) = open_stream(
+            TextReader::new(self.reader, self.encoding),
+            schema,
+            &self.header_normalization,
+        )?;
+        Ok(CsvStream {
+            csv_reader,
+            num_fields,
+            record_options: self.record_options,
+            empty_as_na: self.empty_as_na,
+            csv_src_schema,
+            filters: vec![],
+        })
+    }
+
+    /// Read the CSV data in this stream into a `DataStore` object, with fields specified by
+    /// `schema`. The stream is consumed.
+    ///
+    /// # Errors
+    /// Fails if unable to read from the stream, if CSV analysis fails, if the fields specified in
+    /// the schema do not exist in the CSV data, or if unable to parse the field values.
+    pub fn read<Schema>(
+        self,
+        schema: Schema,
+    ) -> Result<DataStore<<Schema::CsvSrcSchema as BuildDStore>::OutputFields>>
+    where
+        Schema: IntoCsvSrcSchema,
+        Schema::CsvSrcSchema: BuildDStore,
+    {
+        self.open(schema)?.read()
+    }
+
+    /// Read the CSV data in this stream into a `DataStore` object (see [read](#method.read)),
+    /// reporting the number of records read to `observer` (with stage
+    /// [Stage::Read](../../observer/enum.Stage.html)).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation while reading, or
+    /// for any of the reasons [read](#method.read) might fail.
+    pub fn read_observed<Schema, O>(
+        self,
+        schema: Schema,
+        observer: &mut O,
+    ) -> Result<DataStore<<Schema::CsvSrcSchema as BuildDStore>::OutputFields>>
+    where
+        Schema: IntoCsvSrcSchema,
+        Schema::CsvSrcSchema: BuildDStore,
+        O: Observer,
+    {
+        self.open(schema)?.read_observed(observer)
+    }
+}
+
+/// An opened CSV stream (see [CsvStreamReader::open](struct.CsvStreamReader.html#method.open)),
+/// whose records are read directly from the stream as they are loaded, in the same way as a
+/// [CsvReader](../csv/struct.CsvReader.html) reads them from a file. Each reading method consumes
+/// the stream.
+pub struct CsvStream<R, CsvSrcSchema> {
+    csv_reader: StreamCsvReader<TextReader<R>>,
+    num_fields: usize,
+    record_options: RecordOptions,
+    empty_as_na: bool,
+    csv_src_schema: CsvSrcSchema,
+    filters: Vec<RecordFilter>,
+}
+
+impl<R, CsvSrcSchema> Debug for CsvStream<R, CsvSrcSchema>
+where
+    CsvSrcSchema: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CsvStream")
+            .field("num_fields", &self.num_fields)
+            .field("record_options", &self.record_options)
+            .field("empty_as_na", &self.empty_as_na)
+            .field("csv_src_schema", &self.csv_src_schema)
+            .field("filters", &self.filters)
+            .finish()
+    }
+}
+
+impl<R, CsvSrcSchema> CsvStream<R, CsvSrcSchema>
+where
+    R: Read,
+    CsvSrcSchema: CsvSrcIndices,
+{
+    /// Adds a filter to this stream, so that only records whose values of the field labeled
+    /// `Label` match `predicate` are loaded (see
+    /// [CsvReader::filter](../csv/struct.CsvReader.html#method.filter)). Filters apply to every
+    /// reading method.
+    pub fn filter<Label, P>(mut self, predicate: P) -> Self
+    where
+        CsvSrcSchema: LookupElemByLabel<Label>,
+        ElemOf<CsvSrcSchema, Label>: Typed + Valued<Value = usize>,
+        TypeOf<ElemOf<CsvSrcSchema, Label>>: FromStr,
+        ParseError: From<<TypeOf<ElemOf<CsvSrcSchema, Label>> as FromStr>::Err>,
+        P: Fn(Value<&TypeOf<ElemOf<CsvSrcSchema, Label>>>) -> bool + Send + Sync + 'static,
+    {
+        let filter = RecordFilter::new::<_, Label, _>(&self.csv_src_schema, predicate);
+        self.filters.push(filter);
+        self
+    }
+
+    /// Read the records of this stream into a `DataStore` object.
+    pub fn read(self) -> Result<DataStore<CsvSrcSchema::OutputFields>>
+    where
+        CsvSrcSchema: BuildDStore,
+    {
+        self.read_observed(&mut NoObserver)
+    }
+
+    /// Read the records of this stream into a `DataStore` object, reporting the number of records
+    /// read to `observer` (with stage [Stage::Read](../../observer/enum.Stage.html)).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation while reading, or
+    /// if unable to read from the stream or parse the field values.
+    pub fn read_observed<O>(
+        mut self,
+        observer: &mut O,
+    ) -> Result<DataStore<CsvSrcSchema::OutputFields>>
+    where
+        CsvSrcSchema: BuildDStore,
+        O: Observer,
+    {
+        let mut reporter = Reporter::new(observer, Stage::Read, None);
+        reporter.check()?;
+        let config = self.config();
+        let records = collect_records(
+            Records::new(
+                &mut self.csv_reader,
+                self.num_fields,
+                self.record_options,
+                config.projection().clone(),
+            ),
+            &config,
+            &self.filters,
+            &mut reporter,
+        )?;
+        reporter.finish();
+        self.csv_src_schema.build_with_config(&records, &config)
+    }
+
+    /// Read the records of this stream into a `DataStore` object, calling `callback` after every
+    /// `batch_size` records are read (see
+    /// [CsvReader::read_batched](../csv/struct.CsvReader.html#method.read_batched)). Returning
+    /// `BatchControl::Stop` from `callback` stops reading, leaving the rest of the stream unread.
+    ///
+    /// # Errors
+    /// Fails with any error returned by `callback` (aborting the load), or if unable to read from
+    /// the stream or parse the field values.
+    ///
+    /// # Panics
+    /// Panics if `batch_size` is zero.
+    pub fn read_batched<F>(
+        mut self,
+        batch_size: usize,
+        callback: F,
+    ) -> Result<DataStore<CsvSrcSchema::OutputFields>>
+    where
+        CsvSrcSchema: BuildDStore,
+        DataStore<CsvSrcSchema::OutputFields>: IntoView,
+        F: FnMut(&BatchView<CsvSrcSchema>, usize) -> Result<BatchControl>,
+    {
+        let config = self.config();
+        build_batched(
+            Records::new(
+                &mut self.csv_reader,
+                self.num_fields,
+                self.record_options,
+                config.projection().clone(),
+            ),
+            &mut self.csv_src_schema,
+            &config,
+            &self.filters,
+            batch_size,
+            callback,
+        )
+    }
+
+    /// Read a uniform random sample of (at most) `n` records of this stream into a `DataStore`
+    /// object (see [CsvReader::read_sample](../csv/struct.CsvReader.html#method.read_sample)).
+    /// The entire stream is read, but only the sampled records are kept in memory.
+    pub fn read_sample(self, n: usize, seed: u64) -> Result<DataStore<CsvSrcSchema::OutputFields>>
+    where
+        CsvSrcSchema: BuildDStore,
+    {
+        self.read_sample_observed(n, seed, &mut NoObserver)
+    }
+
+    /// Read a uniform random sample of (at most) `n` records of this stream into a `DataStore`
+    /// object (see [read_sample](#method.read_sample)), reporting the number of records scanned
+    /// to `observer` (with stage [Stage::Read](../../observer/enum.Stage.html)).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation while reading, or
+    /// if unable to read from the stream or parse the field values.
+    pub fn read_sample_observed<O>(
+        mut self,
+        n: usize,
+        seed: u64,
+        observer: &mut O,
+    ) -> Result<DataStore<CsvSrcSchema::OutputFields>>
+    where
+        CsvSrcSchema: BuildDStore,
+        O: Observer,
+    {
+        let mut reporter = Reporter::new(observer, Stage::Read, None);
+        reporter.check()?;
+        let config = self.config();
+        let records = sample_from(
+            Records::new(
+                &mut self.csv_reader,
+                self.num_fields,
+                self.record_options,
+                config.projection().clone(),
+            ),
+            &config,
+            &self.filters,
+            n,
+            seed,
+            &mut reporter,
+        )?;
+        reporter.finish();
+        self.csv_src_schema.build_with_config(&records, &config)
+    }
+
+    // Returns the configuration for building a `DataStore` from the records of this stream.
+    fn config(&self) -> RecordConfig {
+        RecordConfig::new(Projection::of(&self.csv_src_schema)).empty_as_na(self.empty_as_na)
+    }
+}
+
+impl CsvStreamReader<Stdin> {
+    /// Create a new CSV reader for this process's standard input.
+    pub fn stdin() -> CsvStreamReader<Stdin> {
+        CsvStreamReader::new(io::stdin())
+    }
+}
+
+/// Utility function for loading CSV data from a (possibly non-seekable) stream.
+///
+/// Fails if unable to read from the stream, or if the schema does not match the CSV data.
+pub fn load_csv_from_reader<R, Schema>(
+    reader: R,
+    schema: Schema,
+) -> Result<<DataStore<<Schema::CsvSrcSchema as BuildDStore>::OutputFields> as IntoView>::Output>
+where
+    R: Read,
+    Schema: IntoCsvSrcSchema,
+    Schema::CsvSrcSchema: BuildDStore + Debug,
+    <Schema::CsvSrcSchema as BuildDStore>::OutputFields: AssocFrameLookup + SimpleFrameFields,
+{
+    Ok(CsvStreamReader::new(reader).read(schema)?.into_view())
+}
+
+/// Utility function for loading CSV data from standard input.
+///
+/// Fails if unable to read from standard input, or if the schema does not match the CSV data.
+pub fn load_csv_from_stdin<Schema>(
+    schema: Schema,
+) -> Result<<DataStore<<Schema::CsvSrcSchema as BuildDStore>::OutputFields> as IntoView>::Output>
+where
+    Schema: IntoCsvSrcSchema,
+    Schema::CsvSrcSchema: BuildDStore + Debug,
+    <Schema::CsvSrcSchema as BuildDStore>::OutputFields: AssocFrameLookup + SimpleFrameFields,
+{
+    load_csv_from_reader(io::stdin(), schema)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preamble() {
+        let sample = b"preamble 1\npreamble 2\nA,B\n1,2\n";
+        assert_eq!(preamble_len(sample, 0).unwrap(), 0);
+        assert_eq!(preamble_len(sample, 2).unwrap(), 22);
+        assert!(preamble_len(sample, 5).is_err());
+    }
+}
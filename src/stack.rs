@@ -0,0 +1,333 @@
+/*!
+Stacking of several `DataView`s into a single long-format `DataView`.
+
+[DataView::melt](../view/struct.DataView.html#method.melt) converts the fields of a single view
+into long format. When data is split across several views (for example, one per yearly export
+file) that share a common key field but contain different measure fields, a
+[Stack](struct.Stack.html) combines the measure fields of all the views into a single long-format
+view, with a field identifying the source view of each record.
+*/
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use access::{DataIndex, NRows};
+use cons::{Cons, Nil};
+use fieldlist::FieldCons;
+use label::{LCons, StrLabels, Typed};
+use select::{FieldSelect, SelectFieldByLabel};
+use store::{DataStore, IntoView};
+use value::Value;
+use view::{DataView, VFieldOf};
+
+/// Trait for selecting the cons-list of fields with labels `MeasureLabels` (in the order of
+/// `MeasureLabels`) from a `DataView`.
+pub trait SelectMeasures<MeasureLabels> {
+    /// Resultant cons-list of fields.
+    type Output;
+
+    /// Returns the cons-list of fields with labels `MeasureLabels`.
+    fn select_measures(&self) -> Self::Output;
+}
+
+impl<Labels, Frames> SelectMeasures<Nil> for DataView<Labels, Frames> {
+    type Output = Nil;
+
+    fn select_measures(&self) -> Nil {
+        Nil
+    }
+}
+
+impl<Labels, Frames, Label, Tail> SelectMeasures<LCons<Label, Tail>> for DataView<Labels, Frames>
+where
+    Self: SelectFieldByLabel<Label> + SelectMeasures<Tail>,
+{
+    type Output = Cons<VFieldOf<Self, Label>, <Self as SelectMeasures<Tail>>::Output>;
+
+    fn select_measures(&self) -> Self::Output {
+        Cons {
+            head: self.field::<Label>(),
+            tail: SelectMeasures::<Tail>::select_measures(self),
+        }
+    }
+}
+
+/// Trait for converting a cons-list of fields (as returned by
+/// [select_measures](trait.SelectMeasures.html#tymethod.select_measures)) which all contain values
+/// of type `DType` into a list of [DataIndex](../access/trait.DataIndex.html) trait objects.
+pub trait MeasureList<DType> {
+    /// Appends a `DataIndex` trait object for each field in this cons-list to `fields`.
+    fn push_measures<'a>(&'a self, fields: &mut Vec<&'a dyn DataIndex<DType = DType>>);
+}
+
+impl<DType> MeasureList<DType> for Nil {
+    fn push_measures<'a>(&'a self, _fields: &mut Vec<&'a dyn DataIndex<DType = DType>>) {}
+}
+
+impl<DType, Head, Tail> MeasureList<DType> for Cons<Head, Tail>
+where
+    Head: DataIndex<DType = DType>,
+    Tail: MeasureList<DType>,
+{
+    fn push_measures<'a>(&'a self, fields: &mut Vec<&'a dyn DataIndex<DType = DType>>) {
+        fields.push(&self.head);
+        self.tail.push_measures(fields);
+    }
+}
+
+/// [FieldCons](../fieldlist/type.FieldCons.html) cons-list of the fields of a stacked
+/// `DataStore`: the key field labeled `KeyLabel`, the source identifier field labeled
+/// `SourceLabel`, the measure name field labeled `NameLabel`, and the measure value field labeled
+/// `ValueLabel`.
+pub type StackFields<KeyLabel, SourceLabel, NameLabel, ValueLabel> = FieldCons<
+    KeyLabel,
+    <KeyLabel as Typed>::DType,
+    FieldCons<
+        SourceLabel,
+        String,
+        FieldCons<NameLabel, String, FieldCons<ValueLabel, <ValueLabel as Typed>::DType, Nil>>,
+    >,
+>;
+
+/// Builder for stacking the measure fields of several `DataView`s (which share a common key field
+/// labeled `KeyLabel`) into a single long-format `DataView`.
+///
+/// Each record of the stacked view contains the key value (in a field labeled `KeyLabel`), an
+/// identifier for the view the record came from (labeled `SourceLabel`), the name of the measure
+/// field (labeled `NameLabel`), and the measure value (labeled `ValueLabel`). All measure fields
+/// must have the same data type as `ValueLabel`.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate agnes;
+/// use agnes::cons::Nil;
+/// use agnes::stack::Stack;
+/// use agnes::store::DataStore;
+///
+/// tablespace![
+///     table sales {
+///         StoreId: u64,
+///         Sales2018: f64,
+///         Sales2019: f64,
+///         Returns2019: f64,
+///         Year: String,
+///         Measure: String,
+///         Amount: f64,
+///     }
+/// ];
+///
+/// fn main() {
+///     let dv2018 = DataStore::<Nil>::empty()
+///         .push_back_cloned_from_iter::<sales::StoreId, _, _, _>(&[1u64, 2])
+///         .push_back_cloned_from_iter::<sales::Sales2018, _, _, _>(&[100.0, 80.0])
+///         .into_view();
+///     let dv2019 = DataStore::<Nil>::empty()
+///         .push_back_cloned_from_iter::<sales::StoreId, _, _, _>(&[1u64])
+///         .push_back_cloned_from_iter::<sales::Sales2019, _, _, _>(&[110.0])
+///         .push_back_cloned_from_iter::<sales::Returns2019, _, _, _>(&[5.0])
+///         .into_view();
+///
+///     let stacked = Stack::<sales::StoreId, sales::Year, sales::Measure, sales::Amount>::new()
+///         .add::<Labels![sales::Sales2018], _, _>("2018", &dv2018)
+///         .add::<Labels![sales::Sales2019, sales::Returns2019], _, _>("2019", &dv2019)
+///         .into_view();
+///
+///     // two records from 2018, and two (one for each measure) from 2019
+///     assert_eq!(stacked.nrows(), 4);
+///     assert_eq!(stacked.fieldnames(), vec!["StoreId", "Year", "Measure", "Amount"]);
+///     println!("{}", stacked);
+/// }
+/// ```
+/// This code should output:
+/// ```text
+///  StoreId | Year | Measure     | Amount
+/// ---------+------+-------------+--------
+///  1       | 2018 | Sales2018   | 100
+///  2       | 2018 | Sales2018   | 80
+///  1       | 2019 | Sales2019   | 110
+///  1       | 2019 | Returns2019 | 5
+/// ```
+#[derive(Debug, Clone)]
+pub struct Stack<KeyLabel, SourceLabel, NameLabel, ValueLabel>
+where
+    KeyLabel: Typed,
+    ValueLabel: Typed,
+{
+    keys: Vec<Value<KeyLabel::DType>>,
+    sources: Vec<Value<String>>,
+    names: Vec<Value<String>>,
+    values: Vec<Value<ValueLabel::DType>>,
+    _marker: PhantomData<(SourceLabel, NameLabel)>,
+}
+
+impl<KeyLabel, SourceLabel, NameLabel, ValueLabel> Default
+    for Stack<KeyLabel, SourceLabel, NameLabel, ValueLabel>
+where
+    KeyLabel: Typed,
+    ValueLabel: Typed,
+{
+    fn default() -> Self {
+        Stack {
+            keys: vec![],
+            sources: vec![],
+            names: vec![],
+            values: vec![],
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<KeyLabel, SourceLabel, NameLabel, ValueLabel>
+    Stack<KeyLabel, SourceLabel, NameLabel, ValueLabel>
+where
+    KeyLabel: Typed,
+    ValueLabel: Typed,
+{
+    /// Creates a new, empty `Stack`.
+    pub fn new() -> Self {
+        Stack::default()
+    }
+
+    /// Adds the records of `view` to this stack, identified by the source identifier `source`.
+    /// Each record of `view` produces one stacked record for each of the measure fields labeled
+    /// `MeasureLabels`, in the order of `MeasureLabels`.
+    pub fn add<MeasureLabels, Labels, Frames>(
+        mut self,
+        source: &str,
+        view: &DataView<Labels, Frames>,
+    ) -> Self
+    where
+        MeasureLabels: StrLabels,
+        DataView<Labels, Frames>: NRows
+            + SelectFieldByLabel<KeyLabel, DType = KeyLabel::DType>
+            + SelectMeasures<MeasureLabels>,
+        <DataView<Labels, Frames> as SelectMeasures<MeasureLabels>>::Output:
+            MeasureList<ValueLabel::DType>,
+        KeyLabel::DType: Clone,
+        ValueLabel::DType: Clone,
+    {
+        let keys = view.field::<KeyLabel>();
+        let fields = SelectMeasures::<MeasureLabels>::select_measures(view);
+        let mut measures = vec![];
+        fields.push_measures(&mut measures);
+        let names = MeasureLabels::labels_vec();
+        for idx in 0..view.nrows() {
+            let key = keys.get_datum(idx).unwrap().cloned();
+            for (&name, measure) in names.iter().zip(&measures) {
+                self.keys.push(key.clone());
+                self.sources.push(Value::Exists(source.to_string()));
+                self.names.push(Value::Exists(name.to_string()));
+                self.values.push(measure.get_datum(idx).unwrap().cloned());
+            }
+        }
+        self
+    }
+
+    /// Returns the number of stacked records.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if there are no stacked records.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Converts this stack into a `DataStore` containing the stacked records.
+    pub fn into_store(self) -> DataStore<StackFields<KeyLabel, SourceLabel, NameLabel, ValueLabel>>
+    where
+        KeyLabel: Debug,
+        SourceLabel: Debug,
+        NameLabel: Debug,
+        ValueLabel: Debug,
+        KeyLabel::DType: Debug + Default + Clone,
+        ValueLabel::DType: Debug + Default + Clone,
+    {
+        DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<KeyLabel, _, _, _>(self.keys)
+            .push_back_from_value_iter::<SourceLabel, _, _, _>(self.sources)
+            .push_back_from_value_iter::<NameLabel, _, _, _>(self.names)
+            .push_back_from_value_iter::<ValueLabel, _, _, _>(self.values)
+    }
+
+    /// Converts this stack into a `DataView` containing the stacked records.
+    pub fn into_view(
+        self,
+    ) -> <DataStore<StackFields<KeyLabel, SourceLabel, NameLabel, ValueLabel>> as IntoView>::Output
+    where
+        KeyLabel: Debug,
+        SourceLabel: Debug,
+        NameLabel: Debug,
+        ValueLabel: Debug,
+        KeyLabel::DType: Debug + Default + Clone,
+        ValueLabel::DType: Debug + Default + Clone,
+        DataStore<StackFields<KeyLabel, SourceLabel, NameLabel, ValueLabel>>: IntoView,
+    {
+        self.into_store().into_view()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stack;
+
+    use access::DataIndex;
+    use cons::Nil;
+    use select::FieldSelect;
+    use store::DataStore;
+    use value::Value;
+
+    tablespace![
+        pub table yearly {
+            StoreId: u64,
+            Sales2018: f64,
+            Sales2019: f64,
+            Returns2019: f64,
+            Year: String,
+            Measure: String,
+            Amount: f64,
+        }
+    ];
+    use self::yearly::*;
+
+    #[test]
+    fn stack() {
+        let dv2018 = DataStore::<Nil>::empty()
+            .push_back_cloned_from_iter::<StoreId, _, _, _>(&[0u64, 1])
+            .push_back_from_value_iter::<Sales2018, _, _, _>(vec![Value::Exists(3.0), Value::Na])
+            .into_view();
+        let dv2019 = DataStore::<Nil>::empty()
+            .push_back_cloned_from_iter::<Returns2019, _, _, _>(&[1.0])
+            .push_back_cloned_from_iter::<StoreId, _, _, _>(&[2u64])
+            .push_back_cloned_from_iter::<Sales2019, _, _, _>(&[7.0])
+            .into_view();
+
+        let stack = Stack::<StoreId, Year, Measure, Amount>::new()
+            .add::<Labels![Sales2018], _, _>("2018", &dv2018)
+            .add::<Labels![Sales2019, Returns2019], _, _>("2019", &dv2019);
+        assert_eq!(stack.len(), 4);
+        let dv = stack.into_view();
+        assert_eq!(dv.field::<StoreId>().to_vec(), vec![0u64, 1, 2, 2]);
+        assert_eq!(
+            dv.field::<Year>().to_vec(),
+            vec!["2018", "2018", "2019", "2019"]
+        );
+        assert_eq!(
+            dv.field::<Measure>().to_vec(),
+            vec!["Sales2018", "Sales2018", "Sales2019", "Returns2019"]
+        );
+        assert_eq!(
+            dv.field::<Amount>().to_value_vec(),
+            vec![
+                Value::Exists(3.0),
+                Value::Na,
+                Value::Exists(7.0),
+                Value::Exists(1.0)
+            ]
+        );
+
+        let empty = Stack::<StoreId, Year, Measure, Amount>::new();
+        assert!(empty.is_empty());
+        assert_eq!(empty.into_view().nrows(), 0);
+    }
+}
@@ -0,0 +1,395 @@
+/*!
+Exponentially weighted moving statistics of numeric fields.
+
+[Ewm::ewm_mean](trait.Ewm.html#tymethod.ewm_mean) and [Ewm::ewm_var](trait.Ewm.html#tymethod.ewm_var)
+compute, for each value of a field, the mean or variance of the values up to and including that
+value, weighting each value by `(1 - alpha)^k`, where `k` is the number of records since that
+value, and `alpha` is specified directly or by a span (see [Decay](enum.Decay.html)). The
+[DataView::ewm_mean_by](../../view/struct.DataView.html#method.ewm_mean_by) and
+[DataView::ewm_var_by](../../view/struct.DataView.html#method.ewm_var_by) variants compute these
+statistics separately within each group of records (such as each series of a long-format table of
+several time series), adding the result to the view as a new field.
+
+The statistics match those of pandas' `ewm` with its default settings (`adjust=True`,
+`ignore_na=False`): missing (NA) and NaN values are skipped, but still count towards the age of
+earlier values. Each statistic is missing until the first existing value (the second, for the
+variance); at records with a missing value, the statistic of the preceding values is repeated. The
+variance is the weighted variance with a bias correction for the effective number of values.
+
+# Example
+
+```
+use agnes::access::DataIndex;
+use agnes::field::FieldData;
+use agnes::stats::ewm::{Decay, Ewm};
+
+let prices: FieldData<f64> = vec![1.0, 2.0, 3.0].into();
+let mean = prices.ewm_mean(Decay::Alpha(0.5)).to_vec();
+assert_eq!(mean[0], 1.0);
+assert!((mean[2] - 17.0 / 7.0).abs() < 1e-12);
+// a span of 3 corresponds to an alpha of 2 / (3 + 1)
+assert_eq!(prices.ewm_mean(Decay::Span(3.0)), prices.ewm_mean(Decay::Alpha(0.5)));
+```
+*/
+
+use num_traits::AsPrimitive;
+
+use access::{DataIndex, NRows};
+use aggregate::TransformFrameOf;
+use erased::{self, KeyList};
+use field::FieldData;
+use frame::IntoFrame;
+use join::AddFrame;
+use select::{FieldSelect, SelectFieldByLabel};
+use store::IntoStore;
+use value::Value;
+use view::{DataView, FieldList, VFieldTypeOf};
+
+/// The decay of the weights of an exponentially weighted moving statistic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decay {
+    /// The smoothing factor `alpha` (between 0, exclusive, and 1, inclusive): each value's
+    /// weight is `1 - alpha` times the weight of the next value.
+    Alpha(f64),
+    /// The span `s` (at least 1), corresponding to `alpha = 2 / (s + 1)`.
+    Span(f64),
+}
+
+impl Decay {
+    /// Returns the smoothing factor `alpha` this decay corresponds to.
+    ///
+    /// # Panics
+    /// Panics if the smoothing factor is not greater than 0 and at most 1, or the span is less
+    /// than 1.
+    pub fn alpha(&self) -> f64 {
+        match *self {
+            Decay::Alpha(alpha) => {
+                assert!(alpha > 0.0 && alpha <= 1.0, "alpha must be in (0, 1]");
+                alpha
+            }
+            Decay::Span(span) => {
+                assert!(span >= 1.0, "span must be at least 1");
+                2.0 / (span + 1.0)
+            }
+        }
+    }
+}
+
+// Running state of the exponentially weighted mean and variance of a sequence of values.
+#[derive(Debug, Clone)]
+struct EwmState {
+    retain: f64,
+    sum_weights: f64,
+    sum_sq_weights: f64,
+    mean: f64,
+    // weighted variance, without bias correction
+    variance: f64,
+    nobs: usize,
+}
+
+impl EwmState {
+    fn new(alpha: f64) -> EwmState {
+        EwmState {
+            retain: 1.0 - alpha,
+            sum_weights: 0.0,
+            sum_sq_weights: 0.0,
+            mean: 0.0,
+            variance: 0.0,
+            nobs: 0,
+        }
+    }
+
+    fn update(&mut self, value: Option<f64>) {
+        self.sum_weights *= self.retain;
+        self.sum_sq_weights *= self.retain * self.retain;
+        let value = match value {
+            Some(value) if !value.is_nan() => value,
+            _ => return,
+        };
+        let old_weight = self.sum_weights;
+        let old_mean = self.mean;
+        self.mean = (old_weight * old_mean + value) / (old_weight + 1.0);
+        self.variance = (old_weight * (self.variance + (old_mean - self.mean).powi(2))
+            + (value - self.mean).powi(2))
+            / (old_weight + 1.0);
+        self.sum_weights += 1.0;
+        self.sum_sq_weights += 1.0;
+        self.nobs += 1;
+    }
+
+    fn mean(&self) -> Value<f64> {
+        if self.nobs == 0 {
+            Value::Na
+        } else {
+            Value::Exists(self.mean)
+        }
+    }
+
+    fn variance(&self) -> Value<f64> {
+        let numerator = self.sum_weights * self.sum_weights;
+        let denominator = numerator - self.sum_sq_weights;
+        if self.nobs < 2 || denominator <= 0.0 {
+            Value::Na
+        } else {
+            Value::Exists(numerator / denominator * self.variance)
+        }
+    }
+
+    fn statistic(&self, statistic: Statistic) -> Value<f64> {
+        match statistic {
+            Statistic::Mean => self.mean(),
+            Statistic::Variance => self.variance(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Statistic {
+    Mean,
+    Variance,
+}
+
+fn ewm_field<DI>(data: &DI, decay: Decay, statistic: Statistic) -> FieldData<f64>
+where
+    DI: DataIndex,
+    DI::DType: AsPrimitive<f64>,
+{
+    let mut state = EwmState::new(decay.alpha());
+    FieldData::from_field_vec(
+        data.iter()
+            .map(|value| {
+                state.update(value.map(|value| value.as_()).into());
+                state.statistic(statistic)
+            })
+            .collect(),
+    )
+}
+
+/// A trait for computing exponentially weighted moving statistics of the values in a numeric
+/// field. See the [module-level documentation](index.html) for details.
+pub trait Ewm {
+    /// Returns the exponentially weighted moving mean of this field's values, with weights
+    /// decaying by `decay`.
+    ///
+    /// # Panics
+    /// Panics if `decay` is invalid (see [Decay::alpha](enum.Decay.html#method.alpha)).
+    fn ewm_mean(&self, decay: Decay) -> FieldData<f64>;
+
+    /// Returns the exponentially weighted moving (bias-corrected) variance of this field's
+    /// values, with weights decaying by `decay`.
+    ///
+    /// # Panics
+    /// Panics if `decay` is invalid (see [Decay::alpha](enum.Decay.html#method.alpha)).
+    fn ewm_var(&self, decay: Decay) -> FieldData<f64>;
+}
+
+impl<DI> Ewm for DI
+where
+    DI: DataIndex,
+    DI::DType: AsPrimitive<f64>,
+{
+    fn ewm_mean(&self, decay: Decay) -> FieldData<f64> {
+        ewm_field(self, decay, Statistic::Mean)
+    }
+    fn ewm_var(&self, decay: Decay) -> FieldData<f64> {
+        ewm_field(self, decay, Statistic::Variance)
+    }
+}
+
+/// Type alias for the output of adding a per-group exponentially weighted moving statistic as a
+/// new field labeled `OutLabel` to `View`.
+pub type EwmByOutputOf<View, OutLabel> =
+    <View as AddFrame<TransformFrameOf<OutLabel, f64>>>::Output;
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    fn ewm_by<KeyLabels, ValueLabel, OutLabel>(
+        &self,
+        decay: Decay,
+        statistic: Statistic,
+    ) -> EwmByOutputOf<Self, OutLabel>
+    where
+        Self: NRows + SelectFieldByLabel<ValueLabel> + AddFrame<TransformFrameOf<OutLabel, f64>>,
+        VFieldTypeOf<Self, ValueLabel>: AsPrimitive<f64>,
+        Labels: FieldList<KeyLabels, Frames>,
+        <Labels as FieldList<KeyLabels, Frames>>::Output: KeyList,
+        FieldData<f64>: IntoStore<OutLabel>,
+        <FieldData<f64> as IntoStore<OutLabel>>::Output: IntoFrame,
+    {
+        let groups = erased::group_records(&self.field_list::<KeyLabels>().keys(), self.nrows());
+        let mut states = vec![EwmState::new(decay.alpha()); groups.len()];
+        let values = self.field::<ValueLabel>();
+        let result = FieldData::from_field_vec(
+            groups
+                .record_groups
+                .iter()
+                .zip(values.iter())
+                .map(|(&group, value)| {
+                    states[group].update(value.map(|value| value.as_()).into());
+                    states[group].statistic(statistic)
+                })
+                .collect(),
+        );
+        self.add_frame(IntoStore::<OutLabel>::into_store(result).into_frame())
+    }
+
+    /// Computes the exponentially weighted moving mean (see
+    /// [Ewm::ewm_mean](../stats/ewm/trait.Ewm.html#tymethod.ewm_mean)) of the field labeled
+    /// `ValueLabel` separately within each group of records with the same values in the fields
+    /// labeled `KeyLabels` (in record order), and adds it to this view as a new field labeled
+    /// `OutLabel`. The resulting `DataView` has the same records (in the same order) as this
+    /// `DataView`.
+    ///
+    /// # Panics
+    /// Panics if `decay` is invalid (see
+    /// [Decay::alpha](../stats/ewm/enum.Decay.html#method.alpha)).
+    pub fn ewm_mean_by<KeyLabels, ValueLabel, OutLabel>(
+        &self,
+        decay: Decay,
+    ) -> EwmByOutputOf<Self, OutLabel>
+    where
+        Self: NRows + SelectFieldByLabel<ValueLabel> + AddFrame<TransformFrameOf<OutLabel, f64>>,
+        VFieldTypeOf<Self, ValueLabel>: AsPrimitive<f64>,
+        Labels: FieldList<KeyLabels, Frames>,
+        <Labels as FieldList<KeyLabels, Frames>>::Output: KeyList,
+        FieldData<f64>: IntoStore<OutLabel>,
+        <FieldData<f64> as IntoStore<OutLabel>>::Output: IntoFrame,
+    {
+        self.ewm_by::<KeyLabels, ValueLabel, OutLabel>(decay, Statistic::Mean)
+    }
+
+    /// Computes the exponentially weighted moving variance (see
+    /// [Ewm::ewm_var](../stats/ewm/trait.Ewm.html#tymethod.ewm_var)) of the field labeled
+    /// `ValueLabel` separately within each group of records with the same values in the fields
+    /// labeled `KeyLabels`, and adds it to this view as a new field labeled `OutLabel` (see
+    /// [ewm_mean_by](#method.ewm_mean_by)).
+    ///
+    /// # Panics
+    /// Panics if `decay` is invalid (see
+    /// [Decay::alpha](../stats/ewm/enum.Decay.html#method.alpha)).
+    pub fn ewm_var_by<KeyLabels, ValueLabel, OutLabel>(
+        &self,
+        decay: Decay,
+    ) -> EwmByOutputOf<Self, OutLabel>
+    where
+        Self: NRows + SelectFieldByLabel<ValueLabel> + AddFrame<TransformFrameOf<OutLabel, f64>>,
+        VFieldTypeOf<Self, ValueLabel>: AsPrimitive<f64>,
+        Labels: FieldList<KeyLabels, Frames>,
+        <Labels as FieldList<KeyLabels, Frames>>::Output: KeyList,
+        FieldData<f64>: IntoStore<OutLabel>,
+        <FieldData<f64> as IntoStore<OutLabel>>::Output: IntoFrame,
+    {
+        self.ewm_by::<KeyLabels, ValueLabel, OutLabel>(decay, Statistic::Variance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_values_close(actual: FieldData<f64>, expected: Vec<Option<f64>>) {
+        let actual = actual
+            .iter()
+            .map(|value| value.cloned().into())
+            .collect::<Vec<Option<f64>>>();
+        assert_eq!(actual.len(), expected.len());
+        for (actual, expected) in actual.iter().zip(&expected) {
+            match (actual, expected) {
+                (Some(actual), Some(expected)) => assert!(
+                    (actual - expected).abs() < 1e-9,
+                    "{} is not close to {}",
+                    actual,
+                    expected
+                ),
+                (actual, expected) => assert_eq!(actual, expected),
+            }
+        }
+    }
+
+    #[test]
+    fn ewm() {
+        let field: FieldData<u64> = vec![1u64, 2, 3].into();
+        assert_values_close(
+            field.ewm_mean(Decay::Alpha(0.5)),
+            vec![Some(1.0), Some(5.0 / 3.0), Some(17.0 / 7.0)],
+        );
+        assert_values_close(
+            field.ewm_var(Decay::Alpha(0.5)),
+            vec![None, Some(0.5), Some(13.0 / 14.0)],
+        );
+        assert_eq!(
+            field.ewm_mean(Decay::Span(3.0)),
+            field.ewm_mean(Decay::Alpha(0.5))
+        );
+        // with alpha = 1, only the latest value has weight
+        assert_values_close(
+            field.ewm_mean(Decay::Alpha(1.0)),
+            vec![Some(1.0), Some(2.0), Some(3.0)],
+        );
+
+        // missing values are skipped, but count towards the age of earlier values
+        let field = FieldData::from_field_vec(vec![
+            Value::Na,
+            Value::Exists(1.0),
+            Value::Na,
+            Value::Exists(3.0),
+            Value::Exists(f64::NAN),
+        ]);
+        assert_values_close(
+            field.ewm_mean(Decay::Alpha(0.5)),
+            vec![None, Some(1.0), Some(1.0), Some(2.6), Some(2.6)],
+        );
+        assert_values_close(
+            field.ewm_var(Decay::Alpha(0.5)),
+            vec![None, None, None, Some(2.0), Some(2.0)],
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_alpha() {
+        let field: FieldData<u64> = vec![1u64, 2, 3].into();
+        field.ewm_mean(Decay::Alpha(0.0));
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn ewm_by() {
+        use cons::Nil;
+        use store::DataStore;
+
+        tablespace![
+            table prices {
+                Ticker: u64,
+                Price: f64,
+                Smoothed: f64,
+                Volatility: f64,
+            }
+        ];
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<prices::Ticker, _>(FieldData::from(vec![1u64, 2, 1, 2, 1]))
+            .push_back_field::<prices::Price, _>(FieldData::from(vec![1.0, 10.0, 2.0, 20.0, 3.0]))
+            .into_view();
+        let dv = dv
+            .ewm_mean_by::<Labels![prices::Ticker], prices::Price, prices::Smoothed>(Decay::Alpha(
+                0.5,
+            ))
+            .ewm_var_by::<Labels![prices::Ticker], prices::Price, prices::Volatility>(
+                Decay::Alpha(0.5),
+            );
+        assert_values_close(
+            dv.field::<prices::Smoothed>().iter().collect(),
+            vec![
+                Some(1.0),
+                Some(10.0),
+                Some(5.0 / 3.0),
+                Some(50.0 / 3.0),
+                Some(17.0 / 7.0),
+            ],
+        );
+        assert_values_close(
+            dv.field::<prices::Volatility>().iter().collect(),
+            vec![None, None, Some(0.5), Some(50.0), Some(13.0 / 14.0)],
+        );
+    }
+}
@@ -0,0 +1,592 @@
+/*!
+Classical hypothesis tests: two-sample t-tests, the Mann-Whitney U test, and the chi-square test of
+independence.
+
+The two-sample tests compare the existing (non-missing) values of two numeric fields, either
+directly ([t_test](fn.t_test.html), [mann_whitney_u](fn.mann_whitney_u.html)), or for two groups of
+records of a `DataView` identified by the value of a key field
+([DataView::t_test](../../view/struct.DataView.html#method.t_test),
+[DataView::mann_whitney_u](../../view/struct.DataView.html#method.mann_whitney_u)). The chi-square
+test is computed on a [Crosstab](struct.Crosstab.html) of the counts of the value combinations of
+two fields, created with [DataView::crosstab](../../view/struct.DataView.html#method.crosstab).
+Each test returns a [TestResult](struct.TestResult.html) with the test statistic and (two-sided)
+p-value.
+
+# Example
+
+```
+# #[cfg(feature = "test-utils")]
+# fn main() {
+use agnes::test_utils::*;
+
+let emps = sample_emp_table_full().into_view();
+let result = emps
+    .t_test::<full_emp_table::DidTraining, full_emp_table::VacationHrs>(&true, &false, false)
+    .unwrap();
+assert!(result.p_value > 0.05);
+
+let crosstab = emps.crosstab::<full_emp_table::DeptId, full_emp_table::DidTraining>();
+assert_eq!(crosstab.count(&1, &true), Some(2));
+let result = crosstab.chi_square().unwrap();
+assert_eq!(result.df, Some(3.0));
+# }
+# #[cfg(not(feature = "test-utils"))]
+# fn main() {}
+```
+*/
+
+use std::f64::consts::PI;
+
+use num_traits::AsPrimitive;
+
+use access::{DataIndex, NRows};
+use erased::KeyIndex;
+use select::{FieldSelect, SelectFieldByLabel};
+use stats::resample::existing_values;
+use value::Value;
+use view::{DataView, VFieldTypeOf};
+
+/// The result of a hypothesis test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestResult {
+    /// The test statistic (the t statistic, the U statistic of the first sample, or the chi-square
+    /// statistic).
+    pub statistic: f64,
+    /// The degrees of freedom of the statistic's distribution under the null hypothesis, if
+    /// applicable (`None` for the Mann-Whitney U test).
+    pub df: Option<f64>,
+    /// The two-sided p-value.
+    pub p_value: f64,
+}
+
+// Natural logarithm of the gamma function (Lanczos approximation), for `x > 0`.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // reflection formula
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .skip(1)
+        .fold(COEFFICIENTS[0], |sum, (i, &c)| sum + c / (x + i as f64));
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+const MAX_ITERATIONS: usize = 300;
+const EPSILON: f64 = 1e-14;
+const TINY: f64 = 1e-300;
+
+// Modified Lentz evaluation of the continued fraction for the regularized incomplete beta
+// function.
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    let clamp = |value: f64| if value.abs() < TINY { TINY } else { value };
+    let mut c = 1.0;
+    let mut d = 1.0 / clamp(1.0 - (a + b) * x / (a + 1.0));
+    let mut fraction = d;
+    for m in 1..MAX_ITERATIONS {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / clamp(1.0 + even * d);
+        c = clamp(1.0 + even / c);
+        fraction *= d * c;
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / clamp(1.0 + odd * d);
+        c = clamp(1.0 + odd / c);
+        let delta = d * c;
+        fraction *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    fraction
+}
+
+// Regularized incomplete beta function I_x(a, b).
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+// Regularized upper incomplete gamma function Q(a, x).
+fn upper_incomplete_gamma(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let front = (a * x.ln() - x - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        // series for the lower function P(a, x)
+        let (mut term, mut sum) = (1.0 / a, 1.0 / a);
+        for n in 1..MAX_ITERATIONS {
+            term *= x / (a + n as f64);
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        1.0 - sum * front
+    } else {
+        // modified Lentz evaluation of the continued fraction for Q(a, x)
+        let clamp = |value: f64| if value.abs() < TINY { TINY } else { value };
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / TINY;
+        let mut d = 1.0 / b;
+        let mut fraction = d;
+        for n in 1..MAX_ITERATIONS {
+            let an = -(n as f64) * (n as f64 - a);
+            b += 2.0;
+            d = 1.0 / clamp(an * d + b);
+            c = clamp(b + an / c);
+            let delta = d * c;
+            fraction *= delta;
+            if (delta - 1.0).abs() < EPSILON {
+                break;
+            }
+        }
+        front * fraction
+    }
+}
+
+// Two-sided p-value of the t statistic `t` with `df` degrees of freedom.
+fn t_p_value(t: f64, df: f64) -> f64 {
+    incomplete_beta(df / 2.0, 0.5, df / (df + t * t))
+}
+
+// Upper-tail p-value of the chi-square statistic `x` with `df` degrees of freedom.
+fn chi_square_p_value(x: f64, df: f64) -> f64 {
+    upper_incomplete_gamma(df / 2.0, x / 2.0)
+}
+
+// Two-sided p-value of the standard normal statistic `z`.
+fn normal_p_value(z: f64) -> f64 {
+    upper_incomplete_gamma(0.5, z * z / 2.0)
+}
+
+fn mean_variance(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / (n - 1.0);
+    (mean, variance)
+}
+
+fn t_test_values(first: &[f64], second: &[f64], equal_variance: bool) -> Option<TestResult> {
+    if first.len() < 2 || second.len() < 2 {
+        return None;
+    }
+    let (n1, n2) = (first.len() as f64, second.len() as f64);
+    let (mean1, var1) = mean_variance(first);
+    let (mean2, var2) = mean_variance(second);
+    let (std_err, df) = if equal_variance {
+        let pooled = ((n1 - 1.0) * var1 + (n2 - 1.0) * var2) / (n1 + n2 - 2.0);
+        ((pooled * (1.0 / n1 + 1.0 / n2)).sqrt(), n1 + n2 - 2.0)
+    } else {
+        let (se1, se2) = (var1 / n1, var2 / n2);
+        let df = (se1 + se2) * (se1 + se2) / (se1 * se1 / (n1 - 1.0) + se2 * se2 / (n2 - 1.0));
+        ((se1 + se2).sqrt(), df)
+    };
+    if std_err.is_nan() || std_err <= 0.0 {
+        return None;
+    }
+    let statistic = (mean1 - mean2) / std_err;
+    Some(TestResult {
+        statistic,
+        df: Some(df),
+        p_value: t_p_value(statistic, df),
+    })
+}
+
+/// Performs a two-sample t-test for a difference in the means of the existing values of `first`
+/// and `second`. With `equal_variance`, this is Student's t-test (assuming both samples have the
+/// same variance); otherwise, this is Welch's t-test. The statistic is positive if the mean of
+/// `first` is greater. Returns `None` if either sample has fewer than two values, or both samples
+/// are constant.
+pub fn t_test<A, B>(first: &A, second: &B, equal_variance: bool) -> Option<TestResult>
+where
+    A: DataIndex,
+    A::DType: AsPrimitive<f64>,
+    B: DataIndex,
+    B::DType: AsPrimitive<f64>,
+{
+    t_test_values(
+        &existing_values(first),
+        &existing_values(second),
+        equal_variance,
+    )
+}
+
+fn mann_whitney_values(first: &[f64], second: &[f64]) -> Option<TestResult> {
+    if first.is_empty() || second.is_empty() {
+        return None;
+    }
+    let mut pooled = first
+        .iter()
+        .map(|&value| (value, true))
+        .chain(second.iter().map(|&value| (value, false)))
+        .collect::<Vec<_>>();
+    if pooled.iter().any(|(value, _)| value.is_nan()) {
+        return None;
+    }
+    pooled.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    // sum of the (tie-averaged) ranks of the first sample, and the tie correction term
+    let (mut rank_sum, mut ties) = (0.0, 0.0);
+    let mut start = 0;
+    while start < pooled.len() {
+        let end = start
+            + pooled[start..]
+                .iter()
+                .take_while(|(value, _)| *value == pooled[start].0)
+                .count();
+        let rank = (start + end + 1) as f64 / 2.0;
+        let in_first = pooled[start..end]
+            .iter()
+            .filter(|(_, first)| *first)
+            .count();
+        rank_sum += rank * in_first as f64;
+        let tied = (end - start) as f64;
+        ties += tied * tied * tied - tied;
+        start = end;
+    }
+
+    let (n1, n2) = (first.len() as f64, second.len() as f64);
+    let n = n1 + n2;
+    let statistic = rank_sum - n1 * (n1 + 1.0) / 2.0;
+    let mean = n1 * n2 / 2.0;
+    let std_dev = (n1 * n2 / 12.0 * ((n + 1.0) - ties / (n * (n - 1.0)))).sqrt();
+    if std_dev.is_nan() || std_dev <= 0.0 {
+        return None;
+    }
+    // normal approximation, with continuity correction
+    let z = ((statistic - mean).abs() - 0.5).max(0.0) / std_dev;
+    Some(TestResult {
+        statistic,
+        df: None,
+        p_value: normal_p_value(z).min(1.0),
+    })
+}
+
+/// Performs a Mann-Whitney U test (Wilcoxon rank-sum test) for a difference in the distributions
+/// of the existing values of `first` and `second`. The statistic is the U statistic of `first`
+/// (the number of pairs of values, one from each sample, where the value from `first` is greater,
+/// counting ties as one half). The p-value uses the normal approximation to the distribution of U,
+/// corrected for ties and with a continuity correction. Returns `None` if either sample has no
+/// values, if any value is NaN, or if every value is equal.
+pub fn mann_whitney_u<A, B>(first: &A, second: &B) -> Option<TestResult>
+where
+    A: DataIndex,
+    A::DType: AsPrimitive<f64>,
+    B: DataIndex,
+    B::DType: AsPrimitive<f64>,
+{
+    mann_whitney_values(&existing_values(first), &existing_values(second))
+}
+
+/// Counts of the combinations of values of two fields (a contingency table), created with
+/// [DataView::crosstab](../../view/struct.DataView.html#method.crosstab).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Crosstab<R, C> {
+    rows: Vec<R>,
+    columns: Vec<C>,
+    counts: Vec<Vec<u64>>,
+}
+
+impl<R, C> Crosstab<R, C>
+where
+    R: PartialEq,
+    C: PartialEq,
+{
+    /// Returns the distinct values of the row field, in order of first appearance.
+    pub fn rows(&self) -> &[R] {
+        &self.rows
+    }
+
+    /// Returns the distinct values of the column field, in order of first appearance.
+    pub fn columns(&self) -> &[C] {
+        &self.columns
+    }
+
+    /// Returns the counts, indexed by row and then column.
+    pub fn counts(&self) -> &[Vec<u64>] {
+        &self.counts
+    }
+
+    /// Returns the number of records with row value `row` and column value `column`, or `None` if
+    /// either value does not occur.
+    pub fn count(&self, row: &R, column: &C) -> Option<u64> {
+        let row = self.rows.iter().position(|value| value == row)?;
+        let column = self.columns.iter().position(|value| value == column)?;
+        Some(self.counts[row][column])
+    }
+
+    /// Performs a chi-square test of independence of the row and column fields (without continuity
+    /// correction). Returns `None` if the table has fewer than two rows or two columns.
+    pub fn chi_square(&self) -> Option<TestResult> {
+        chi_square(&self.counts)
+    }
+}
+
+/// Performs a chi-square test of independence on a contingency table of `counts`, indexed by row
+/// and then column (without continuity correction). Returns `None` if the table has fewer than two
+/// rows or two columns, if the rows are of different lengths, or if any row or column has no
+/// counts.
+pub fn chi_square(counts: &[Vec<u64>]) -> Option<TestResult> {
+    let ncols = counts.first().map_or(0, |row| row.len());
+    if counts.len() < 2 || ncols < 2 || counts.iter().any(|row| row.len() != ncols) {
+        return None;
+    }
+    let row_totals = counts
+        .iter()
+        .map(|row| row.iter().sum::<u64>() as f64)
+        .collect::<Vec<_>>();
+    let col_totals = (0..ncols)
+        .map(|col| counts.iter().map(|row| row[col]).sum::<u64>() as f64)
+        .collect::<Vec<_>>();
+    if row_totals.contains(&0.0) || col_totals.contains(&0.0) {
+        return None;
+    }
+    let total = row_totals.iter().sum::<f64>();
+    let mut statistic = 0.0;
+    for (row, row_total) in counts.iter().zip(&row_totals) {
+        for (&count, col_total) in row.iter().zip(&col_totals) {
+            let expected = row_total * col_total / total;
+            statistic += (count as f64 - expected) * (count as f64 - expected) / expected;
+        }
+    }
+    let df = ((counts.len() - 1) * (ncols - 1)) as f64;
+    Some(TestResult {
+        statistic,
+        df: Some(df),
+        p_value: chi_square_p_value(statistic, df),
+    })
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Performs a two-sample t-test (see [t_test](../stats/hypothesis/fn.t_test.html)) for a
+    /// difference in the mean of the field labeled `ValueLabel` between the records whose key
+    /// field (labeled `KeyLabel`) is `group_a` and the records whose key field is `group_b`.
+    /// Records with missing keys or values are ignored.
+    pub fn t_test<KeyLabel, ValueLabel>(
+        &self,
+        group_a: &VFieldTypeOf<Self, KeyLabel>,
+        group_b: &VFieldTypeOf<Self, KeyLabel>,
+        equal_variance: bool,
+    ) -> Option<TestResult>
+    where
+        Self: NRows + SelectFieldByLabel<KeyLabel> + SelectFieldByLabel<ValueLabel>,
+        <Self as SelectFieldByLabel<KeyLabel>>::Output: KeyIndex,
+        VFieldTypeOf<Self, KeyLabel>: PartialEq,
+        VFieldTypeOf<Self, ValueLabel>: AsPrimitive<f64>,
+    {
+        let (first, second) = self.group_values::<KeyLabel, ValueLabel>(group_a, group_b);
+        t_test_values(&first, &second, equal_variance)
+    }
+
+    /// Performs a Mann-Whitney U test (see
+    /// [mann_whitney_u](../stats/hypothesis/fn.mann_whitney_u.html)) comparing the values of the
+    /// field labeled `ValueLabel` of the records whose key field (labeled `KeyLabel`) is `group_a`
+    /// with those of the records whose key field is `group_b`. Records with missing keys or values
+    /// are ignored.
+    pub fn mann_whitney_u<KeyLabel, ValueLabel>(
+        &self,
+        group_a: &VFieldTypeOf<Self, KeyLabel>,
+        group_b: &VFieldTypeOf<Self, KeyLabel>,
+    ) -> Option<TestResult>
+    where
+        Self: NRows + SelectFieldByLabel<KeyLabel> + SelectFieldByLabel<ValueLabel>,
+        <Self as SelectFieldByLabel<KeyLabel>>::Output: KeyIndex,
+        VFieldTypeOf<Self, KeyLabel>: PartialEq,
+        VFieldTypeOf<Self, ValueLabel>: AsPrimitive<f64>,
+    {
+        let (first, second) = self.group_values::<KeyLabel, ValueLabel>(group_a, group_b);
+        mann_whitney_values(&first, &second)
+    }
+
+    /// Returns a [Crosstab](../stats/hypothesis/struct.Crosstab.html) of the number of records with
+    /// each combination of values of the fields labeled `RowLabel` and `ColLabel`. Records with a
+    /// missing value in either field are ignored.
+    pub fn crosstab<RowLabel, ColLabel>(
+        &self,
+    ) -> Crosstab<VFieldTypeOf<Self, RowLabel>, VFieldTypeOf<Self, ColLabel>>
+    where
+        Self: SelectFieldByLabel<RowLabel> + SelectFieldByLabel<ColLabel>,
+        VFieldTypeOf<Self, RowLabel>: PartialEq + Clone,
+        VFieldTypeOf<Self, ColLabel>: PartialEq + Clone,
+    {
+        let row_field = self.field::<RowLabel>();
+        let col_field = self.field::<ColLabel>();
+        let mut crosstab = Crosstab {
+            rows: vec![],
+            columns: vec![],
+            counts: vec![],
+        };
+        for (row, col) in row_field.iter().zip(col_field.iter()) {
+            if let (Value::Exists(row), Value::Exists(col)) = (row, col) {
+                let row = match crosstab.rows.iter().position(|value| value == row) {
+                    Some(idx) => idx,
+                    None => {
+                        crosstab.rows.push(row.clone());
+                        crosstab.counts.push(vec![0; crosstab.columns.len()]);
+                        crosstab.rows.len() - 1
+                    }
+                };
+                let col = match crosstab.columns.iter().position(|value| value == col) {
+                    Some(idx) => idx,
+                    None => {
+                        crosstab.columns.push(col.clone());
+                        for counts in &mut crosstab.counts {
+                            counts.push(0);
+                        }
+                        crosstab.columns.len() - 1
+                    }
+                };
+                crosstab.counts[row][col] += 1;
+            }
+        }
+        crosstab
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use field::FieldData;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() < tolerance,
+            "{} is not close to {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn distributions() {
+        assert_close(ln_gamma(5.0), 24f64.ln(), 1e-12);
+        assert_close(ln_gamma(0.5), PI.sqrt().ln(), 1e-12);
+        assert_close(t_p_value(2.0, 10.0), 0.073_388, 1e-6);
+        assert_close(t_p_value(0.0, 5.0), 1.0, 1e-12);
+        assert_close(chi_square_p_value(3.841_459, 1.0), 0.05, 1e-6);
+        assert_close(chi_square_p_value(5.991_465, 2.0), 0.05, 1e-6);
+        assert_close(chi_square_p_value(30.0, 10.0), 0.000_856_6, 1e-7);
+        assert_close(normal_p_value(1.959_964), 0.05, 1e-6);
+    }
+
+    #[test]
+    fn t_tests() {
+        let first: FieldData<u64> = vec![1u64, 2, 3, 4, 5].into();
+        let second = FieldData::from_field_vec(vec![
+            Value::Exists(2.0),
+            Value::Exists(4.0),
+            Value::Na,
+            Value::Exists(6.0),
+            Value::Exists(8.0),
+            Value::Exists(10.0),
+        ]);
+        let welch = t_test(&first, &second, false).unwrap();
+        assert_close(welch.statistic, -3.0 / 2.5f64.sqrt(), 1e-12);
+        assert_close(welch.df.unwrap(), 6.25 / 1.0625, 1e-12);
+        assert_close(welch.p_value, 0.107_5, 1e-4);
+        let student = t_test(&first, &second, true).unwrap();
+        assert_close(student.statistic, -3.0 / 2.5f64.sqrt(), 1e-12);
+        assert_eq!(student.df, Some(8.0));
+        assert_close(student.p_value, 0.094_3, 1e-4);
+
+        let constant: FieldData<u64> = vec![1u64, 1, 1].into();
+        assert!(t_test(&constant, &constant, false).is_none());
+        let single: FieldData<u64> = vec![1u64].into();
+        assert!(t_test(&single, &first, false).is_none());
+    }
+
+    #[test]
+    fn mann_whitney() {
+        let first: FieldData<u64> = vec![1u64, 2, 3, 4, 5].into();
+        let second: FieldData<u64> = vec![6u64, 7, 8, 9, 10].into();
+        let result = mann_whitney_u(&first, &second).unwrap();
+        assert_eq!(result.statistic, 0.0);
+        assert_close(result.p_value, 0.012_19, 1e-5);
+        assert_eq!(
+            mann_whitney_u(&second, &first).unwrap().p_value,
+            result.p_value
+        );
+
+        // ties are assigned average ranks
+        let tied: FieldData<u64> = vec![1u64, 2, 2, 3].into();
+        let other: FieldData<u64> = vec![2u64, 4].into();
+        let result = mann_whitney_u(&tied, &other).unwrap();
+        assert_eq!(result.statistic, 2.0);
+        assert!(result.p_value > 0.1 && result.p_value <= 1.0);
+
+        let same: FieldData<u64> = vec![3u64, 3].into();
+        assert!(mann_whitney_u(&same, &same).is_none());
+    }
+
+    #[test]
+    fn chi_square_test() {
+        let result = chi_square(&[vec![10, 20], vec![20, 10]]).unwrap();
+        assert_close(result.statistic, 20.0 / 3.0, 1e-12);
+        assert_eq!(result.df, Some(1.0));
+        assert_close(result.p_value, 0.009_823, 1e-6);
+
+        assert!(chi_square(&[vec![10, 20]]).is_none());
+        assert!(chi_square(&[vec![10, 0], vec![20, 0]]).is_none());
+        assert!(chi_square(&[vec![10, 20], vec![20]]).is_none());
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn view_tests() {
+        use test_utils::*;
+
+        let dv = sample_emp_table_full().into_view();
+        let result = dv
+            .t_test::<full_emp_table::DidTraining, full_emp_table::VacationHrs>(
+                &true, &false, false,
+            )
+            .unwrap();
+        assert!(result.p_value > 0.05 && result.p_value <= 1.0);
+        let result = dv
+            .mann_whitney_u::<full_emp_table::DidTraining, full_emp_table::SalaryOffset>(
+                &true, &false,
+            )
+            .unwrap();
+        // trained offsets [12, -33, 10, -1] vs. untrained offsets [-5, 4, 0]
+        assert_eq!(result.statistic, 7.0);
+
+        let crosstab = dv.crosstab::<full_emp_table::DeptId, full_emp_table::DidTraining>();
+        assert_eq!(crosstab.rows(), &[1, 2, 3, 4]);
+        assert_eq!(crosstab.columns(), &[false, true]);
+        assert_eq!(
+            crosstab.counts(),
+            &[vec![1, 2], vec![1, 0], vec![0, 1], vec![1, 1]]
+        );
+        assert_eq!(crosstab.count(&4, &true), Some(1));
+        assert_eq!(crosstab.count(&5, &true), None);
+        let result = crosstab.chi_square().unwrap();
+        assert_eq!(result.df, Some(3.0));
+    }
+}
@@ -0,0 +1,974 @@
+/*!
+Useful statistics-calculating traits for fields with numeric data.
+
+Classical hypothesis tests (t-tests, the Mann-Whitney U test, and the chi-square test) are provided
+by the [hypothesis](hypothesis/index.html) submodule, and resampling-based inference (bootstrap
+confidence intervals and permutation tests) by the [resample](resample/index.html) submodule.
+Exponentially weighted moving means and variances, for smoothing time series, are provided by the
+[ewm](ewm/index.html) submodule.
+*/
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{Add, Mul};
+
+use num_traits::{AsPrimitive, Float, Zero};
+
+use access::DataIndex;
+use field::FieldData;
+use value::Value;
+
+pub mod ewm;
+pub mod hypothesis;
+pub mod resample;
+
+/// A trait for counting NA and existing values in a field.
+pub trait NaCount {
+    /// Returns the number of NA (missing) values in this field.
+    fn num_na(&self) -> usize;
+    /// Returns the number of existing (non-missing) values in this field.
+    fn num_exists(&self) -> usize;
+}
+
+impl<DI> NaCount for DI
+where
+    DI: DataIndex,
+{
+    fn num_na(&self) -> usize {
+        self.na_mask().count_zeros()
+    }
+    fn num_exists(&self) -> usize {
+        self.na_mask().count_ones()
+    }
+}
+
+/// A trait for computing the sum of values in a field.
+pub trait Sum {
+    /// The data type of the sum result.
+    type Output;
+    /// Returns the sum of values in this field. Treats missing values as `0`.
+    fn sum(&self) -> Self::Output;
+}
+
+impl<DI> Sum for DI
+where
+    DI: DataIndex,
+    DI::DType: for<'a> Add<&'a DI::DType, Output = DI::DType> + Zero,
+{
+    type Output = <DI as DataIndex>::DType;
+
+    fn sum(&self) -> Self::Output {
+        self.iter().fold(
+            <<Self as DataIndex>::DType as Zero>::zero(),
+            |sum, value| match value {
+                Value::Exists(value) => sum + value,
+                Value::Na => sum,
+            },
+        )
+    }
+}
+
+/// A trait for calculating the arithmetic mean of a field.
+pub trait Mean {
+    /// Compute the arithmetic mean of a field. Ignores missing values in the computation. If all
+    /// values missing, returns `0.0`.
+    fn mean(&self) -> f64;
+}
+
+impl<DI> Mean for DI
+where
+    DI: DataIndex + NaCount + Sum,
+    <DI as Sum>::Output: AsPrimitive<f64>,
+{
+    fn mean(&self) -> f64 {
+        let nexists = match self.num_exists() {
+            0 => {
+                return 0.0;
+            }
+            val => val as f64,
+        };
+        self.sum().as_() / nexists
+    }
+}
+
+/// A trait for calculating the sum of squares of values in this field.
+pub trait SumSq {
+    /// The data type of the sum result.
+    type Output;
+
+    /// Calculate the sum of squares of values in this field. Treats missing values as `0`.
+    fn sum_sq(&self) -> Self::Output;
+}
+
+impl<DI> SumSq for DI
+where
+    DI: DataIndex,
+    DI::DType: for<'a> Add<&'a DI::DType, Output = DI::DType> + Zero,
+    for<'a, 'b> &'a DI::DType: Mul<&'b DI::DType, Output = DI::DType>,
+{
+    type Output = DI::DType;
+
+    fn sum_sq(&self) -> DI::DType {
+        self.iter().fold(
+            <<Self as DataIndex>::DType as Zero>::zero(),
+            |sum, value| match value {
+                Value::Exists(value) => sum + value.clone() * value,
+                Value::Na => sum,
+            },
+        )
+    }
+}
+
+/// A trait for computing the variance and standard deviation of values in a field.
+pub trait Variance {
+    /// Computes sample variance of this field. Ignores missing values in this computation. If all
+    /// values are missing, returns `0.0`.
+    fn var(&self) -> f64;
+    /// Computes population variance of this field. Ignores missing values in this computation. If
+    /// all values are missing, returns `0.0`.
+    fn varp(&self) -> f64;
+    /// Computes sample standard deviation of this field. Ignores missing values in this
+    /// computation. If all values are missing, returns `0.0`.
+    fn stdev(&self) -> f64 {
+        self.var().sqrt()
+    }
+    /// Computes population standard deviation of this field. Ignores missing values in this
+    /// computation. If all values are missing, returns `0.0`.
+    fn stdevp(&self) -> f64 {
+        self.varp().sqrt()
+    }
+}
+
+impl<DI> Variance for DI
+where
+    DI: DataIndex + SumSq + NaCount + Mean,
+    <DI as SumSq>::Output: AsPrimitive<f64>,
+{
+    fn var(&self) -> f64 {
+        let nexists = match self.num_exists() {
+            0 => {
+                return 0.0;
+            }
+            val => val as f64,
+        };
+        let sum_sq = self.sum_sq();
+        let mean: f64 = self.mean().as_();
+        sum_sq.as_() / (nexists - 1.0) - nexists / (nexists - 1.0) * mean * mean
+    }
+    fn varp(&self) -> f64 {
+        let nexists = match self.num_exists() {
+            0 => {
+                return 0.0;
+            }
+            val => val as f64,
+        };
+        let sum_sq = self.sum_sq();
+        let mean: f64 = self.mean().as_();
+        sum_sq.as_() / nexists - mean * mean
+    }
+}
+
+/// A trait for computing the upper and lower extrema values for a field.
+pub trait Extrema {
+    /// The data type of the upper and lower values.
+    type Output;
+
+    /// The minimum value in this field. Returns `None` if no values exist in this field.
+    fn min(&self) -> Option<&Self::Output>;
+    /// The maximum value in this field. Returns `None` if no values exist in this field.
+    fn max(&self) -> Option<&Self::Output>;
+}
+
+impl<DI> Extrema for DI
+where
+    DI: DataIndex,
+    DI::DType: PartialOrd,
+{
+    type Output = DI::DType;
+
+    fn min(&self) -> Option<&DI::DType> {
+        if self.num_exists() == 0 {
+            return None;
+        }
+        let mut ret = None;
+        for val in self.iter() {
+            match (ret, val) {
+                (None, Value::Exists(val)) => {
+                    ret = Some(val);
+                }
+                (Some(cur_min), Value::Exists(val)) => {
+                    if val < cur_min {
+                        ret = Some(val);
+                    }
+                }
+                _ => {}
+            }
+        }
+        ret
+    }
+    fn max(&self) -> Option<&DI::DType> {
+        if self.num_exists() == 0 {
+            return None;
+        }
+        let mut ret = None;
+        for val in self.iter() {
+            match (ret, val) {
+                (None, Value::Exists(val)) => {
+                    ret = Some(val);
+                }
+                (Some(cur_max), Value::Exists(val)) => {
+                    if val > cur_max {
+                        ret = Some(val);
+                    }
+                }
+                _ => {}
+            }
+        }
+        ret
+    }
+}
+
+/// A trait for computing the indices of the upper and lower extrema values for a field. The
+/// returned indices can be used with [DataView::take](../view/struct.DataView.html#method.take) to
+/// retrieve the corresponding records.
+pub trait ArgExtrema {
+    /// The index of the minimum value in this field (the first such index, if the minimum occurs
+    /// more than once). Missing values and values not comparable to themselves (such as NaN) are
+    /// ignored. Returns `None` if no other values exist in this field.
+    fn argmin(&self) -> Option<usize>;
+    /// The index of the maximum value in this field (the first such index, if the maximum occurs
+    /// more than once). Missing values and values not comparable to themselves (such as NaN) are
+    /// ignored. Returns `None` if no other values exist in this field.
+    fn argmax(&self) -> Option<usize>;
+}
+
+impl<DI> ArgExtrema for DI
+where
+    DI: DataIndex,
+    DI::DType: PartialOrd,
+{
+    fn argmin(&self) -> Option<usize> {
+        arg_extremum(self, |val, cur_min| val < cur_min)
+    }
+    fn argmax(&self) -> Option<usize> {
+        arg_extremum(self, |val, cur_max| val > cur_max)
+    }
+}
+
+fn arg_extremum<DI, F>(data: &DI, replaces: F) -> Option<usize>
+where
+    DI: DataIndex,
+    DI::DType: PartialOrd,
+    F: Fn(&DI::DType, &DI::DType) -> bool,
+{
+    let mut ret: Option<(usize, &DI::DType)> = None;
+    for (idx, val) in data.iter().enumerate() {
+        if let Value::Exists(val) = val {
+            // a NaN would never be replaced, since it compares false with every value
+            if val.partial_cmp(val).is_none() {
+                continue;
+            }
+            if ret.is_none_or(|(_, cur)| replaces(val, cur)) {
+                ret = Some((idx, val));
+            }
+        }
+    }
+    ret.map(|(idx, _)| idx)
+}
+
+/// A trait for computing the most frequent value in a field.
+pub trait Mode {
+    /// The data type of the most frequent value.
+    type Output;
+
+    /// The most frequent existing value in this field. If several values are equally frequent,
+    /// returns the one which appears first. Returns `None` if no values exist in this field.
+    fn mode(&self) -> Option<&Self::Output>;
+}
+
+impl<DI> Mode for DI
+where
+    DI: DataIndex,
+    DI::DType: Hash + Eq,
+{
+    type Output = DI::DType;
+
+    fn mode(&self) -> Option<&DI::DType> {
+        // map from value to (count, index of first appearance)
+        let mut counts = HashMap::new();
+        for (idx, val) in self.iter().enumerate() {
+            if let Value::Exists(val) = val {
+                counts.entry(val).or_insert((0usize, idx)).0 += 1;
+            }
+        }
+        counts
+            .into_iter()
+            .max_by(
+                |&(_, (left_count, left_idx)), &(_, (right_count, right_idx))| {
+                    // prefer lower first-appearance index among equal counts
+                    left_count.cmp(&right_count).then(right_idx.cmp(&left_idx))
+                },
+            )
+            .map(|(val, _)| val)
+    }
+}
+
+/// A trait for computing quantiles of the values in a numeric field.
+pub trait Quantile {
+    /// Returns the `q`-th quantile (for `q` between `0.0` and `1.0`) of the existing values in
+    /// this field, linearly interpolating between the two nearest values if the quantile falls
+    /// between them. Missing (NA) and NaN values are ignored. Returns `None` if no other values
+    /// exist in this field.
+    ///
+    /// # Panics
+    /// Panics if `q` is not between `0.0` and `1.0`.
+    fn quantile(&self, q: f64) -> Option<f64>;
+
+    /// Returns the median of the existing values in this field. See
+    /// [quantile](#tymethod.quantile).
+    fn median(&self) -> Option<f64> {
+        self.quantile(0.5)
+    }
+}
+
+impl<DI> Quantile for DI
+where
+    DI: DataIndex,
+    DI::DType: AsPrimitive<f64>,
+{
+    fn quantile(&self, q: f64) -> Option<f64> {
+        quantile_of(
+            self.iter()
+                .filter_map(|value| value.map(|value| value.as_()).into())
+                .collect(),
+            q,
+        )
+    }
+}
+
+/// A trait for computing weighted quantiles of the values in a numeric field.
+pub trait WeightedQuantile {
+    /// Returns the weighted `q`-th quantile (for `q` between `0.0` and `1.0`) of the existing
+    /// values in this field, where each value is weighted by the value at the same index in
+    /// `weights`. The weighted quantile is the smallest value for which the total weight of values
+    /// less than or equal to it is at least `q` times the total weight. Values which are missing
+    /// (NA) or NaN, or which have a missing or non-positive weight, are ignored. Returns `None` if
+    /// no other values exist in this field.
+    ///
+    /// # Panics
+    /// Panics if `q` is not between `0.0` and `1.0`.
+    fn weighted_quantile<W>(&self, weights: &W, q: f64) -> Option<f64>
+    where
+        W: DataIndex,
+        W::DType: AsPrimitive<f64>;
+}
+
+impl<DI> WeightedQuantile for DI
+where
+    DI: DataIndex,
+    DI::DType: AsPrimitive<f64>,
+{
+    fn weighted_quantile<W>(&self, weights: &W, q: f64) -> Option<f64>
+    where
+        W: DataIndex,
+        W::DType: AsPrimitive<f64>,
+    {
+        weighted_quantile_of(
+            self.iter()
+                .zip(weights.iter())
+                .filter_map(|(value, weight)| match (value, weight) {
+                    (Value::Exists(value), Value::Exists(weight)) => {
+                        Some((value.as_(), weight.as_()))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            q,
+        )
+    }
+}
+
+/// Option for handling NaN values in [NanStats](trait.NanStats.html) computations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanPolicy {
+    /// Ignore NaN values, treating them the same as missing (NA) values.
+    #[default]
+    Skip,
+    /// Produce a NaN result if any NaN values exist in the field.
+    Propagate,
+}
+
+/// A trait for computing statistics over a numeric field with explicit handling of NaN values
+/// (according to a [NanPolicy](enum.NanPolicy.html)). Missing (NA) values are always ignored.
+///
+/// Infinite values are not affected by the `NanPolicy`; use
+/// [sanitize_floats](trait.SanitizeFloats.html#tymethod.sanitize_floats) to convert them to
+/// missing values before computing statistics.
+pub trait NanStats {
+    /// Returns the sum of values in this field. Returns `0.0` if no values exist.
+    fn sum_nan(&self, nan: NanPolicy) -> f64;
+    /// Returns the arithmetic mean of values in this field. Returns `0.0` if no values exist.
+    fn mean_nan(&self, nan: NanPolicy) -> f64;
+    /// Returns the sample variance of values in this field. Returns `0.0` if fewer than two values
+    /// exist.
+    fn var_nan(&self, nan: NanPolicy) -> f64;
+    /// Returns the population variance of values in this field. Returns `0.0` if no values exist.
+    fn varp_nan(&self, nan: NanPolicy) -> f64;
+    /// Returns the sample standard deviation of values in this field. Returns `0.0` if fewer than
+    /// two values exist.
+    fn stdev_nan(&self, nan: NanPolicy) -> f64 {
+        self.var_nan(nan).sqrt()
+    }
+    /// Returns the population standard deviation of values in this field. Returns `0.0` if no
+    /// values exist.
+    fn stdevp_nan(&self, nan: NanPolicy) -> f64 {
+        self.varp_nan(nan).sqrt()
+    }
+    /// Returns the minimum value in this field. Returns `None` if no values exist.
+    fn min_nan(&self, nan: NanPolicy) -> Option<f64>;
+    /// Returns the maximum value in this field. Returns `None` if no values exist.
+    fn max_nan(&self, nan: NanPolicy) -> Option<f64>;
+}
+
+impl<DI> NanStats for DI
+where
+    DI: DataIndex,
+    DI::DType: AsPrimitive<f64>,
+{
+    fn sum_nan(&self, nan: NanPolicy) -> f64 {
+        nan_values(self, nan).map_or(f64::NAN, |values| values.iter().sum())
+    }
+    fn mean_nan(&self, nan: NanPolicy) -> f64 {
+        nan_values(self, nan).map_or(f64::NAN, |values| mean_of(&values))
+    }
+    fn var_nan(&self, nan: NanPolicy) -> f64 {
+        nan_values(self, nan).map_or(f64::NAN, |values| {
+            if values.len() < 2 {
+                0.0
+            } else {
+                sum_sq_dev(&values) / (values.len() - 1) as f64
+            }
+        })
+    }
+    fn varp_nan(&self, nan: NanPolicy) -> f64 {
+        nan_values(self, nan).map_or(f64::NAN, |values| {
+            if values.is_empty() {
+                0.0
+            } else {
+                sum_sq_dev(&values) / values.len() as f64
+            }
+        })
+    }
+    fn min_nan(&self, nan: NanPolicy) -> Option<f64> {
+        match nan_values(self, nan) {
+            Some(values) => values.into_iter().fold(None, |min, value| match min {
+                Some(min) if min <= value => Some(min),
+                _ => Some(value),
+            }),
+            None => Some(f64::NAN),
+        }
+    }
+    fn max_nan(&self, nan: NanPolicy) -> Option<f64> {
+        match nan_values(self, nan) {
+            Some(values) => values.into_iter().fold(None, |max, value| match max {
+                Some(max) if max >= value => Some(max),
+                _ => Some(value),
+            }),
+            None => Some(f64::NAN),
+        }
+    }
+}
+
+// Returns the existing, non-NaN values of a field as `f64`s, or `None` if the field contains a NaN
+// value and `nan` is `NanPolicy::Propagate`.
+fn nan_values<DI>(data: &DI, nan: NanPolicy) -> Option<Vec<f64>>
+where
+    DI: DataIndex,
+    DI::DType: AsPrimitive<f64>,
+{
+    let mut values = Vec::with_capacity(data.len());
+    for value in data.iter() {
+        if let Value::Exists(value) = value {
+            let value: f64 = value.as_();
+            if !value.is_nan() {
+                values.push(value);
+            } else if nan == NanPolicy::Propagate {
+                return None;
+            }
+        }
+    }
+    Some(values)
+}
+
+// Computes the arithmetic mean of `values`, or `0.0` if empty.
+fn mean_of(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+// Computes the sum of squared deviations from the mean of `values`.
+fn sum_sq_dev(values: &[f64]) -> f64 {
+    let mean = mean_of(values);
+    values
+        .iter()
+        .map(|value| (value - mean) * (value - mean))
+        .sum()
+}
+
+/// A trait for converting the non-finite (NaN or infinite) values in a floating-point field into
+/// missing (NA) values.
+pub trait SanitizeFloats {
+    /// The data type of the field values.
+    type DType;
+
+    /// Returns a copy of this field with all NaN and infinite values replaced by missing (NA)
+    /// values.
+    fn sanitize_floats(&self) -> FieldData<Self::DType>;
+}
+
+impl<DI> SanitizeFloats for DI
+where
+    DI: DataIndex,
+    DI::DType: Float + Debug + Default,
+{
+    type DType = DI::DType;
+
+    fn sanitize_floats(&self) -> FieldData<DI::DType> {
+        self.iter()
+            .map(|value| match value {
+                Value::Exists(value) if value.is_finite() => Value::Exists(*value),
+                _ => Value::Na,
+            })
+            .collect()
+    }
+}
+
+// Computes the `q`-th quantile of `values` (with linear interpolation), ignoring NaN values.
+pub(crate) fn quantile_of(mut values: Vec<f64>, q: f64) -> Option<f64> {
+    assert!((0.0..=1.0).contains(&q), "quantile must be between 0 and 1");
+    values.retain(|value| !value.is_nan());
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|left, right| left.partial_cmp(right).unwrap());
+    let rank = q * (values.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    Some(values[lower] + (values[upper] - values[lower]) * (rank - lower as f64))
+}
+
+// Computes the weighted `q`-th quantile of a list of `(value, weight)` pairs, ignoring NaN values
+// and non-positive (or NaN) weights.
+pub(crate) fn weighted_quantile_of(mut values: Vec<(f64, f64)>, q: f64) -> Option<f64> {
+    assert!((0.0..=1.0).contains(&q), "quantile must be between 0 and 1");
+    values.retain(|&(value, weight)| !value.is_nan() && weight > 0.0);
+    values.sort_by(|left, right| left.0.partial_cmp(&right.0).unwrap());
+    let target = q * values.iter().map(|&(_, weight)| weight).sum::<f64>();
+    let mut cumulative = 0.0;
+    for &(value, weight) in &values {
+        cumulative += weight;
+        if cumulative >= target {
+            return Some(value);
+        }
+    }
+    // only reachable due to rounding error in the cumulative weights
+    values.last().map(|&(value, _)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cons::Nil;
+    use field::FieldData;
+    use select::FieldSelect;
+    use store::DataStore;
+    use value::Value;
+
+    tablespace![
+        pub table foo {
+            Foo: f64
+        }
+    ];
+
+    #[test]
+    fn na_count() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(0.0),
+                Value::Exists(-5.0),
+                Value::Na,
+                Value::Na,
+                Value::Exists(-3.0),
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().num_na(), 2);
+        assert_eq!(dv.field::<foo::Foo>().num_exists(), 3);
+    }
+
+    #[test]
+    fn sum() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(0u64),
+                Value::Exists(5),
+                Value::Na,
+                Value::Na,
+                Value::Exists(3),
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().sum(), 8);
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(0),
+                Value::Exists(-5),
+                Value::Na,
+                Value::Na,
+                Value::Exists(-3),
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().sum(), -8);
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(true),
+                Value::Exists(true),
+                Value::Exists(false),
+                Value::Na,
+                Value::Exists(true),
+            ])
+            .into_view();
+        assert_eq!(
+            dv.field::<foo::Foo>()
+                .iter()
+                .map(|b| if b.exists() && *b.unwrap() { 1 } else { 0 })
+                .collect::<FieldData<_>>()
+                .sum(),
+            3
+        );
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(0.0),
+                Value::Exists(-5.0),
+                Value::Na,
+                Value::Na,
+                Value::Exists(-3.0),
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().sum(), -8.0);
+    }
+
+    #[test]
+    fn stdev() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(-5.0),
+                Value::Exists(-4.0),
+                Value::Na,
+                Value::Exists(12.0),
+                Value::Exists(3.0),
+                Value::Na,
+                Value::Exists(6.0),
+                Value::Exists(0.0),
+                Value::Exists(-3.1),
+            ])
+            .into_view();
+        assert!((dv.field::<foo::Foo>().var() - 38.049048).abs() < 1e-6);
+        assert!((dv.field::<foo::Foo>().stdev() - 6.168391).abs() < 1e-6);
+        assert!((dv.field::<foo::Foo>().varp() - 32.613469).abs() < 1e-6);
+        assert!((dv.field::<foo::Foo>().stdevp() - 5.710820).abs() < 1e-6);
+        assert!((dv.field::<foo::Foo>().mean() - 1.271429).abs() < 1e-6);
+        assert_eq!(dv.field::<foo::Foo>().sum(), 8.9);
+    }
+
+    #[test]
+    fn min() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(0u32),
+                Value::Exists(9),
+                Value::Na,
+                Value::Na,
+                Value::Exists(3),
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().min(), Some(&0));
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(0i32),
+                Value::Exists(-9),
+                Value::Na,
+                Value::Na,
+                Value::Exists(-3),
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().min(), Some(&-9));
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(true),
+                Value::Exists(true),
+                Value::Exists(false),
+                Value::Na,
+                Value::Exists(true),
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().min(), Some(&false));
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(0.0),
+                Value::Exists(-9.0),
+                Value::Na,
+                Value::Na,
+                Value::Exists(-3.0),
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().min(), Some(&-9.0));
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, f64, _, _>(vec![
+                Value::Na,
+                Value::Na,
+                Value::Na,
+                Value::Na,
+                Value::Na,
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().min(), None);
+    }
+
+    #[test]
+    fn max() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(0u32),
+                Value::Exists(9),
+                Value::Na,
+                Value::Na,
+                Value::Exists(3),
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().max(), Some(&9));
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(0i64),
+                Value::Exists(-9),
+                Value::Na,
+                Value::Na,
+                Value::Exists(-3),
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().max(), Some(&0));
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(true),
+                Value::Exists(true),
+                Value::Exists(false),
+                Value::Na,
+                Value::Exists(true),
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().max(), Some(&true));
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(0.0),
+                Value::Exists(-9.0),
+                Value::Na,
+                Value::Na,
+                Value::Exists(-3.0),
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().max(), Some(&0.0));
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, f64, _, _>(vec![
+                Value::Na,
+                Value::Na,
+                Value::Na,
+                Value::Na,
+                Value::Na,
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().max(), None);
+    }
+
+    #[test]
+    fn arg_extrema() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Na,
+                Value::Exists(3i64),
+                Value::Exists(-9),
+                Value::Exists(9),
+                Value::Exists(-9),
+                Value::Na,
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().argmin(), Some(2));
+        assert_eq!(dv.field::<foo::Foo>().argmax(), Some(3));
+
+        let taken = dv
+            .take(&[dv.field::<foo::Foo>().argmax().unwrap()])
+            .unwrap();
+        assert_eq!(taken.field::<foo::Foo>().to_vec(), vec![9]);
+        assert!(dv.take(&[6]).is_err());
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, f64, _, _>(vec![Value::Na, Value::Na])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().argmin(), None);
+        assert_eq!(dv.field::<foo::Foo>().argmax(), None);
+
+        // NaN values are skipped
+        let field = FieldData::<f64>::from_vec(vec![f64::NAN, 2.0, -1.0, f64::NAN, 5.0]);
+        assert_eq!(field.argmin(), Some(2));
+        assert_eq!(field.argmax(), Some(4));
+        let field = FieldData::<f64>::from_vec(vec![f64::NAN]);
+        assert_eq!(field.argmin(), None);
+        assert_eq!(field.argmax(), None);
+    }
+
+    #[test]
+    fn mode() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(2u64),
+                Value::Na,
+                Value::Exists(3),
+                Value::Na,
+                Value::Exists(3),
+                Value::Exists(2),
+                Value::Na,
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().mode(), Some(&2));
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists("b"),
+                Value::Exists("a"),
+                Value::Exists("a"),
+            ])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().mode(), Some(&"a"));
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, u64, _, _>(vec![Value::Na, Value::Na])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().mode(), None);
+    }
+
+    #[test]
+    fn quantile() {
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, _, _, _>(vec![
+                Value::Exists(4.0),
+                Value::Na,
+                Value::Exists(1.0),
+                Value::Exists(f64::NAN),
+                Value::Exists(3.0),
+                Value::Exists(2.0),
+            ])
+            .into_view();
+        let field = dv.field::<foo::Foo>();
+        assert_eq!(field.quantile(0.0), Some(1.0));
+        assert_eq!(field.quantile(1.0), Some(4.0));
+        assert_eq!(field.median(), Some(2.5));
+        assert_eq!(field.quantile(0.25), Some(1.75));
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<foo::Foo, u64, _, _>(vec![Value::Na])
+            .into_view();
+        assert_eq!(dv.field::<foo::Foo>().median(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn quantile_out_of_range() {
+        FieldData::<f64>::from_vec(vec![1.0]).quantile(1.5);
+    }
+
+    #[test]
+    fn weighted_quantile() {
+        let values = FieldData::from_field_vec(vec![
+            Value::Exists(1u64),
+            Value::Exists(2),
+            Value::Exists(3),
+            Value::Na,
+            Value::Exists(4),
+        ]);
+        let weights = FieldData::from_field_vec(vec![
+            Value::Exists(1.0f64),
+            Value::Exists(1.0),
+            Value::Exists(6.0),
+            Value::Exists(100.0),
+            Value::Exists(0.0),
+        ]);
+        assert_eq!(values.weighted_quantile(&weights, 0.0), Some(1.0));
+        assert_eq!(values.weighted_quantile(&weights, 0.25), Some(2.0));
+        assert_eq!(values.weighted_quantile(&weights, 0.5), Some(3.0));
+        assert_eq!(values.weighted_quantile(&weights, 1.0), Some(3.0));
+
+        let weights = FieldData::<f64>::from_vec(vec![1.0, 1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(values.weighted_quantile(&weights, 0.5), Some(2.0));
+        let none = FieldData::<f64>::from_vec(vec![0.0; 5]);
+        assert_eq!(values.weighted_quantile(&none, 0.5), None);
+    }
+
+    #[test]
+    fn nan_stats() {
+        let field = FieldData::from_field_vec(vec![
+            Value::Exists(2.0),
+            Value::Exists(f64::NAN),
+            Value::Na,
+            Value::Exists(-4.0),
+            Value::Exists(8.0),
+        ]);
+        assert_eq!(field.sum_nan(NanPolicy::Skip), 6.0);
+        assert_eq!(field.mean_nan(NanPolicy::Skip), 2.0);
+        assert_eq!(field.varp_nan(NanPolicy::Skip), 24.0);
+        assert_eq!(field.var_nan(NanPolicy::Skip), 36.0);
+        assert_eq!(field.stdev_nan(NanPolicy::Skip), 6.0);
+        assert_eq!(field.min_nan(NanPolicy::Skip), Some(-4.0));
+        assert_eq!(field.max_nan(NanPolicy::Skip), Some(8.0));
+
+        assert!(field.sum_nan(NanPolicy::Propagate).is_nan());
+        assert!(field.mean_nan(NanPolicy::Propagate).is_nan());
+        assert!(field.var_nan(NanPolicy::Propagate).is_nan());
+        assert!(field.min_nan(NanPolicy::Propagate).unwrap().is_nan());
+        assert!(field.max_nan(NanPolicy::Propagate).unwrap().is_nan());
+
+        let field = FieldData::from_field_vec(vec![Value::Exists(f64::NAN), Value::Na]);
+        assert_eq!(field.mean_nan(NanPolicy::Skip), 0.0);
+        assert_eq!(field.var_nan(NanPolicy::Skip), 0.0);
+        assert_eq!(field.min_nan(NanPolicy::Skip), None);
+
+        // integer fields never contain NaN values
+        let field = FieldData::<u64>::from_vec(vec![3u64, 5]);
+        assert_eq!(field.mean_nan(NanPolicy::Propagate), 4.0);
+
+        let field = FieldData::from_field_vec(vec![
+            Value::Exists(1.5f32),
+            Value::Exists(f32::NAN),
+            Value::Exists(f32::NEG_INFINITY),
+            Value::Na,
+        ]);
+        assert_eq!(
+            field.sanitize_floats().to_value_vec(),
+            vec![Value::Exists(1.5), Value::Na, Value::Na, Value::Na]
+        );
+        let field = FieldData::<f64>::from_vec(vec![f64::INFINITY, 0.0]);
+        assert_eq!(
+            field.sanitize_floats().to_value_vec(),
+            vec![Value::Na, Value::Exists(0.0)]
+        );
+    }
+}
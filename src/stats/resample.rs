@@ -0,0 +1,342 @@
+/*!
+Resampling-based inference: bootstrap confidence intervals and permutation tests.
+
+[Bootstrap::bootstrap](trait.Bootstrap.html#tymethod.bootstrap) computes a percentile bootstrap
+confidence interval for a statistic of the values of a field, and
+[DataView::permutation_test](../../view/struct.DataView.html#method.permutation_test) tests for a
+difference in a statistic between two groups of records (identified by the value of a key field).
+Statistics are arbitrary functions of a slice of the (existing) values of a field, as `f64`s.
+
+Both methods draw from an explicit random number generator; with a generator created by
+[seeded_rng](../../random/fn.seeded_rng.html), results are reproducible across runs and
+platforms (see the [random](../../random/index.html) module).
+
+# Example
+
+```
+# #[cfg(feature = "test-utils")]
+# fn main() {
+use agnes::random::seeded_rng;
+use agnes::select::FieldSelect;
+use agnes::stats::resample::Bootstrap;
+use agnes::test_utils::*;
+
+let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+
+let emps = sample_emp_table_full().into_view();
+let ci = emps
+    .field::<full_emp_table::VacationHrs>()
+    .bootstrap(mean, 1000, 0.95, &mut seeded_rng(0))
+    .unwrap();
+assert!(ci.lower <= ci.estimate && ci.estimate <= ci.upper);
+
+// difference in mean vacation hours of employees with and without training
+let test = emps
+    .permutation_test::<full_emp_table::DidTraining, full_emp_table::VacationHrs, _, _>(
+        &true, &false, mean, 1000, &mut seeded_rng(0),
+    )
+    .unwrap();
+assert!(test.p_value > 0.0 && test.p_value <= 1.0);
+# }
+# #[cfg(not(feature = "test-utils"))]
+# fn main() {}
+```
+*/
+
+use num_traits::AsPrimitive;
+use rand::RngCore;
+
+use access::{DataIndex, NRows};
+use erased::{self, KeyIndex};
+use random::{shuffle, uniform_below};
+use select::{FieldSelect, SelectFieldByLabel};
+use stats::quantile_of;
+use value::Value;
+use view::{DataView, VFieldTypeOf};
+
+/// A bootstrap confidence interval for a statistic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfidenceInterval {
+    /// The statistic computed over the original values.
+    pub estimate: f64,
+    /// The lower bound of the interval.
+    pub lower: f64,
+    /// The upper bound of the interval.
+    pub upper: f64,
+}
+
+/// The result of a permutation test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PermutationTest {
+    /// The observed difference in the statistic between the two groups (the statistic of the
+    /// first group minus that of the second).
+    pub observed: f64,
+    /// The (two-sided) p-value: the estimated probability of a difference at least as large in
+    /// magnitude as the observed difference, if the group labels were assigned at random.
+    pub p_value: f64,
+}
+
+// Returns the existing values of `data`, as `f64`s.
+pub(crate) fn existing_values<DI>(data: &DI) -> Vec<f64>
+where
+    DI: DataIndex,
+    DI::DType: AsPrimitive<f64>,
+{
+    data.iter()
+        .filter_map(|value| value.map(|value| value.as_()).into())
+        .collect()
+}
+
+/// A trait for computing bootstrap confidence intervals of statistics of the values in a numeric
+/// field.
+pub trait Bootstrap {
+    /// Returns the percentile bootstrap confidence interval, at confidence level `confidence`, of
+    /// `statistic` computed over the existing values of this field. Each of the `resamples`
+    /// bootstrap resamples draws (with replacement, using `rng`) as many values as exist in this
+    /// field; the interval bounds are the `(1 - confidence) / 2` and `(1 + confidence) / 2`
+    /// quantiles of the statistic over the resamples. Missing (NA) values are ignored. Returns
+    /// `None` if no values exist in this field (or the statistic is NaN for every resample).
+    ///
+    /// # Panics
+    /// Panics if `resamples` is 0, or `confidence` is not strictly between `0.0` and `1.0`.
+    fn bootstrap<F, R>(
+        &self,
+        statistic: F,
+        resamples: usize,
+        confidence: f64,
+        rng: &mut R,
+    ) -> Option<ConfidenceInterval>
+    where
+        F: Fn(&[f64]) -> f64,
+        R: RngCore + ?Sized;
+}
+
+impl<DI> Bootstrap for DI
+where
+    DI: DataIndex,
+    DI::DType: AsPrimitive<f64>,
+{
+    fn bootstrap<F, R>(
+        &self,
+        statistic: F,
+        resamples: usize,
+        confidence: f64,
+        rng: &mut R,
+    ) -> Option<ConfidenceInterval>
+    where
+        F: Fn(&[f64]) -> f64,
+        R: RngCore + ?Sized,
+    {
+        assert!(resamples > 0, "number of resamples must be positive");
+        assert!(
+            confidence > 0.0 && confidence < 1.0,
+            "confidence must be between 0 and 1"
+        );
+        let values = existing_values(self);
+        if values.is_empty() {
+            return None;
+        }
+        let mut resample = vec![0.0; values.len()];
+        let statistics = (0..resamples)
+            .map(|_| {
+                for value in resample.iter_mut() {
+                    *value = values[uniform_below(rng, values.len() as u64) as usize];
+                }
+                statistic(&resample)
+            })
+            .collect::<Vec<_>>();
+        let alpha = (1.0 - confidence) / 2.0;
+        Some(ConfidenceInterval {
+            estimate: statistic(&values),
+            lower: quantile_of(statistics.clone(), alpha)?,
+            upper: quantile_of(statistics, 1.0 - alpha)?,
+        })
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Returns the existing values (as `f64`s) of the field labeled `ValueLabel` of the records
+    /// whose key field (labeled `KeyLabel`) is `group_a`, and of those whose key field is
+    /// `group_b`. Records with missing keys or values are ignored.
+    pub(crate) fn group_values<KeyLabel, ValueLabel>(
+        &self,
+        group_a: &VFieldTypeOf<Self, KeyLabel>,
+        group_b: &VFieldTypeOf<Self, KeyLabel>,
+    ) -> (Vec<f64>, Vec<f64>)
+    where
+        Self: NRows + SelectFieldByLabel<KeyLabel> + SelectFieldByLabel<ValueLabel>,
+        <Self as SelectFieldByLabel<KeyLabel>>::Output: KeyIndex,
+        VFieldTypeOf<Self, KeyLabel>: PartialEq,
+        VFieldTypeOf<Self, ValueLabel>: AsPrimitive<f64>,
+    {
+        let keys = self.field::<KeyLabel>();
+        let groups = erased::group_records(&[&keys], self.nrows());
+        // find the groups of records with each of the two keys (if any records have them)
+        let group_of = |key| {
+            groups
+                .first_indices
+                .iter()
+                .position(|&idx| keys.get_datum(idx).unwrap() == Value::Exists(key))
+        };
+        let (group_a, group_b) = (group_of(group_a), group_of(group_b));
+        let values = self.field::<ValueLabel>();
+        let (mut first, mut second) = (vec![], vec![]);
+        for (&group, value) in groups.record_groups.iter().zip(values.iter()) {
+            if let Value::Exists(value) = value {
+                if Some(group) == group_a {
+                    first.push(value.as_());
+                } else if Some(group) == group_b {
+                    second.push(value.as_());
+                }
+            }
+        }
+        (first, second)
+    }
+
+    /// Performs a permutation test for a difference in `statistic` of the values of the field
+    /// labeled `ValueLabel` between the records whose key field (labeled `KeyLabel`) is `group_a`
+    /// and the records whose key field is `group_b`. Records with missing key or values, and
+    /// records in neither group, are ignored.
+    ///
+    /// The observed difference is `statistic` of the first group's values minus `statistic` of the
+    /// second's. For each of the `permutations` permutations, the pooled values of both groups
+    /// are shuffled (using `rng`) and split into groups of the original sizes; the p-value is
+    /// `(k + 1) / (permutations + 1)`, where `k` is the number of permutations whose difference is
+    /// at least as large in magnitude as the observed difference. Returns `None` if either group
+    /// has no values.
+    pub fn permutation_test<KeyLabel, ValueLabel, F, R>(
+        &self,
+        group_a: &VFieldTypeOf<Self, KeyLabel>,
+        group_b: &VFieldTypeOf<Self, KeyLabel>,
+        statistic: F,
+        permutations: usize,
+        rng: &mut R,
+    ) -> Option<PermutationTest>
+    where
+        Self: NRows + SelectFieldByLabel<KeyLabel> + SelectFieldByLabel<ValueLabel>,
+        <Self as SelectFieldByLabel<KeyLabel>>::Output: KeyIndex,
+        VFieldTypeOf<Self, KeyLabel>: PartialEq,
+        VFieldTypeOf<Self, ValueLabel>: AsPrimitive<f64>,
+        F: Fn(&[f64]) -> f64,
+        R: RngCore + ?Sized,
+    {
+        let (mut pooled, second) = self.group_values::<KeyLabel, ValueLabel>(group_a, group_b);
+        if pooled.is_empty() || second.is_empty() {
+            return None;
+        }
+        let observed = statistic(&pooled) - statistic(&second);
+        // allow for rounding differences when the statistic is computed over reordered values
+        let threshold = observed.abs() * (1.0 - 1e-9);
+        let split = pooled.len();
+        pooled.extend(second);
+        let mut extreme = 0;
+        for _ in 0..permutations {
+            shuffle(&mut pooled, rng);
+            let difference = statistic(&pooled[..split]) - statistic(&pooled[split..]);
+            if difference.abs() >= threshold {
+                extreme += 1;
+            }
+        }
+        Some(PermutationTest {
+            observed,
+            p_value: (extreme + 1) as f64 / (permutations + 1) as f64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use field::FieldData;
+    use random::seeded_rng;
+
+    fn mean(values: &[f64]) -> f64 {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+
+    #[test]
+    fn bootstrap() {
+        let field = FieldData::from_field_vec(vec![
+            Value::Exists(2u64),
+            Value::Exists(4),
+            Value::Na,
+            Value::Exists(9),
+            Value::Exists(5),
+        ]);
+        let ci = field.bootstrap(mean, 500, 0.9, &mut seeded_rng(1)).unwrap();
+        assert_eq!(ci.estimate, 5.0);
+        assert!(ci.lower >= 2.0 && ci.lower <= ci.estimate);
+        assert!(ci.upper <= 9.0 && ci.upper >= ci.estimate);
+        assert_eq!(
+            field.bootstrap(mean, 500, 0.9, &mut seeded_rng(1)),
+            Some(ci)
+        );
+
+        // a constant field has a degenerate interval
+        let constant: FieldData<f64> = vec![3.0; 4].into();
+        let ci = constant
+            .bootstrap(mean, 100, 0.95, &mut seeded_rng(1))
+            .unwrap();
+        assert_eq!((ci.lower, ci.estimate, ci.upper), (3.0, 3.0, 3.0));
+
+        let empty = FieldData::<f64>::from_field_vec(vec![Value::Na]);
+        assert!(empty
+            .bootstrap(mean, 100, 0.95, &mut seeded_rng(1))
+            .is_none());
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn permutation_test() {
+        use cons::Nil;
+        use store::DataStore;
+        use test_utils::*;
+
+        let dv = sample_emp_table_full().into_view();
+        let test = dv
+            .permutation_test::<full_emp_table::DidTraining, full_emp_table::VacationHrs, _, _>(
+                &true,
+                &false,
+                mean,
+                999,
+                &mut seeded_rng(2),
+            )
+            .unwrap();
+        let as_f64 = |values: &[f32]| values.iter().map(|&v| v as f64).collect::<Vec<_>>();
+        let trained = mean(&as_f64(&[98.3, 12.2, -1.2, 22.5]));
+        let untrained = mean(&as_f64(&[47.3, 54.1, 5.4]));
+        assert!((test.observed - (trained - untrained)).abs() < 1e-9);
+        assert!(test.p_value > 0.0 && test.p_value <= 1.0);
+
+        // no group
+        assert!(dv
+            .permutation_test::<full_emp_table::EmpId, full_emp_table::VacationHrs, _, _>(
+                &0,
+                &100,
+                mean,
+                10,
+                &mut seeded_rng(2),
+            )
+            .is_none());
+
+        // clearly-separated groups have a small p-value
+        let separated: emp_table::Store = DataStore::<Nil>::empty()
+            .push_back_field(FieldData::from(vec![
+                0u64, 1, 2, 3, 4, 100, 101, 102, 103, 104,
+            ]))
+            .push_back_field(FieldData::from(vec![1u64, 1, 1, 1, 1, 2, 2, 2, 2, 2]))
+            .push_back_field(FieldData::from(vec![String::new(); 10]));
+        let test = separated
+            .into_view()
+            .permutation_test::<emp_table::DeptId, emp_table::EmpId, _, _>(
+                &1,
+                &2,
+                mean,
+                999,
+                &mut seeded_rng(2),
+            )
+            .unwrap();
+        assert_eq!(test.observed, -100.0);
+        assert!(test.p_value < 0.05);
+    }
+}
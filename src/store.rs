@@ -11,14 +11,17 @@ use std::rc::Rc;
 use serde::ser::{Serialize, Serializer};
 use typenum::uint::UTerm;
 
-use access::{DataIndex, NRows};
+use access::{DataChunk, DataIndex, DataIterator, NRows, NaMask, RawColumn, RawColumnar};
 use cons::*;
 use error;
 use field::FieldData;
 use fieldlist::{FieldCons, FieldPayloadCons, FieldSchema};
 use frame::{DataFrame, SimpleFrameFields};
 use label::*;
+use memory::{FieldMemory, FieldsMemoryUsage, HeapSize, MemoryReport, MemoryUsage};
 use select::{FieldSelect, SelectFieldByLabel};
+#[cfg(feature = "net")]
+use source::async_csv::{AsyncSource, LoadFuture};
 use value::Value;
 use view::{DataView, FrameLookupCons, ViewFrameCons};
 
@@ -46,6 +49,15 @@ impl<T> Deref for DataRef<T> {
     }
 }
 
+impl<T> MemoryUsage for DataRef<T>
+where
+    T: HeapSize,
+{
+    fn memory_usage(&self) -> FieldMemory {
+        self.0.memory_usage()
+    }
+}
+
 impl<T> From<FieldData<T>> for DataRef<T> {
     fn from(orig: FieldData<T>) -> DataRef<T> {
         DataRef(Rc::new(orig))
@@ -65,6 +77,31 @@ where
     fn len(&self) -> usize {
         <FieldData<T> as DataIndex>::len(&self.0)
     }
+    fn get_chunk(&self, start: usize, len: usize) -> error::Result<DataChunk<'_, T>> {
+        <FieldData<T> as DataIndex>::get_chunk(&self.0, start, len)
+    }
+    fn na_mask(&self) -> NaMask<'_> {
+        <FieldData<T> as DataIndex>::na_mask(&self.0)
+    }
+}
+impl<T> RawColumnar for DataRef<T>
+where
+    T: Debug,
+{
+    fn raw_column(&self) -> error::Result<RawColumn<'_, T>> {
+        self.0.raw_column()
+    }
+}
+impl<'a, T> IntoIterator for &'a DataRef<T>
+where
+    T: 'a + Debug,
+{
+    type Item = Value<&'a T>;
+    type IntoIter = DataIterator<'a, T>;
+
+    fn into_iter(self) -> DataIterator<'a, T> {
+        self.iter()
+    }
 }
 
 #[cfg(feature = "serialize")]
@@ -119,6 +156,90 @@ where
     }
 }
 
+#[cfg(feature = "net")]
+impl<Fields> DataStore<Fields>
+where
+    Fields: AssocStorage,
+{
+    /// Asynchronously load a data store from the source `src` (e.g. an
+    /// [AsyncCsvReader](../source/async_csv/struct.AsyncCsvReader.html)). Requires the `net`
+    /// feature, and the returned future must be polled from within a Tokio runtime.
+    ///
+    /// Since loading begins only when the returned future is polled, several sources can be loaded
+    /// concurrently (overlapping network and parse time) by joining their futures.
+    pub fn from_async_source<Src>(src: Src) -> LoadFuture<Fields>
+    where
+        Src: AsyncSource<OutputFields = Fields>,
+    {
+        src.load()
+    }
+}
+
+impl<Fields> DataStore<Fields>
+where
+    Fields: AssocStorage,
+    Fields::Storage: FieldsMemoryUsage,
+{
+    /// Returns the number of bytes allocated for each field of this `DataStore` (see the
+    /// [memory](../memory/index.html) module).
+    pub fn memory_usage(&self) -> MemoryReport {
+        self.data.memory_report()
+    }
+}
+
+/// Trait for appending the values of each field of a [DataStore](struct.DataStore.html)'s
+/// associated storage to the corresponding fields of another storage with the same fields.
+pub trait ExtendStorage: AssocStorage {
+    /// Appends (clones of) the values of each field of `other` to the corresponding field of
+    /// `storage`. Field data shared with other stores or views is copied before it is modified.
+    fn extend_storage(storage: &mut Self::Storage, other: &Self::Storage);
+
+    /// Returns a storage cons-list which shares the field data of `storage`.
+    fn share_storage(storage: &Self::Storage) -> Self::Storage;
+}
+impl ExtendStorage for Nil {
+    fn extend_storage(_storage: &mut Nil, _other: &Nil) {}
+    fn share_storage(_storage: &Nil) -> Nil {
+        Nil
+    }
+}
+impl<Label, DType, Tail> ExtendStorage for FieldCons<Label, DType, Tail>
+where
+    Tail: ExtendStorage,
+    Label: Debug,
+    DType: Debug + Clone,
+{
+    fn extend_storage(storage: &mut Self::Storage, other: &Self::Storage) {
+        Rc::make_mut(&mut storage.head.value_mut().0).extend_from(other.head.value_ref());
+        Tail::extend_storage(&mut storage.tail, &other.tail);
+    }
+    fn share_storage(storage: &Self::Storage) -> Self::Storage {
+        Cons {
+            head: TypedValue::from(storage.head.value_ref().clone()).into(),
+            tail: Tail::share_storage(&storage.tail),
+        }
+    }
+}
+
+impl<Fields> DataStore<Fields>
+where
+    Fields: ExtendStorage,
+{
+    /// Appends (clones of) the values of each field of `other` to the corresponding field of this
+    /// `DataStore`. Field data shared with views of this store is copied (leaving the views
+    /// unchanged) before it is extended.
+    pub fn extend_from(&mut self, other: &DataStore<Fields>) {
+        Fields::extend_storage(&mut self.data, &other.data);
+    }
+
+    /// Returns a `DataStore` which shares the field data of this store.
+    pub(crate) fn share(&self) -> DataStore<Fields> {
+        DataStore {
+            data: Fields::share_storage(&self.data),
+        }
+    }
+}
+
 impl<Fields> NRows for DataStore<Fields>
 where
     Fields: AssocStorage,
@@ -714,6 +835,7 @@ mod tests {
     use csv_sniffer::metadata::Metadata;
 
     use super::{DataStore, NRows};
+    use access::DataIndex;
     use cons::*;
     use select::FieldSelect;
     use source::csv::{CsvReader, CsvSource, IntoCsvSrcSchema};
@@ -786,4 +908,24 @@ mod tests {
         assert_eq!(ds.nrows(), EXPECTED_GDP_NROWS);
         assert_eq!(ds.field::<gdp::CountryName>().len(), EXPECTED_GDP_NROWS);
     }
+
+    #[test]
+    fn extend_from() {
+        type TestTablespace = U0;
+        first_label![Test, TestTablespace, u64];
+
+        let mut ds = DataStore::<Nil>::empty()
+            .push_back_from_value_iter::<Test, _, _, _>(vec![Value::Exists(4u64), Value::Na]);
+        let shared = ds.share();
+        ds.extend_from(
+            &DataStore::<Nil>::empty()
+                .push_back_from_value_iter::<Test, _, _, _>(vec![Value::Na, Value::Exists(3)]),
+        );
+        assert_eq!(
+            ds.field::<Test>().to_value_vec(),
+            vec![Value::Exists(4), Value::Na, Value::Na, Value::Exists(3)]
+        );
+        // shared field data is left unchanged
+        assert_eq!(shared.nrows(), 2);
+    }
 }
@@ -4,8 +4,12 @@ Functions for generating sample data tables for tests.
 use cons::Nil;
 use field::FieldData;
 use store::DataStore;
+use value::Value;
 use view::ViewMerge;
 
+use self::emp_table::DeptId;
+use self::extra_emp::VacationHrs;
+
 tablespace![
     pub table emp_table {
         EmpId: u64,
@@ -29,6 +33,10 @@ tablespace![
         DeptId: u64,
         DeptName: String,
     }
+    pub table emp_stats {
+        NumEmps: u64,
+        MeanVacationHrs: f64,
+    }
 ];
 
 macro_rules! emp_table_from_field {
@@ -135,3 +143,22 @@ pub fn dept_table_from_field(
         .push_back_field(deptids)
         .push_back_field(names)
 }
+
+/// Type alias for the `DataStore` generated by
+/// [sample_dept_vacation_table](fn.sample_dept_vacation_table.html).
+pub type DeptVacationStore = DataStore<Fields![DeptId: u64, VacationHrs: f32]>;
+
+/// Generates a table of department IDs (`DeptId`) and vacation hours (`VacationHrs`) with
+/// repeated departments and missing vacation hours (for use in aggregation tests).
+pub fn sample_dept_vacation_table() -> DeptVacationStore {
+    DataStore::<Nil>::empty()
+        .push_back_cloned_from_iter(&[1u64, 2, 1, 3, 2, 1])
+        .push_back_field(FieldData::from_field_vec(vec![
+            Value::Exists(12.0f32),
+            Value::Exists(5.0),
+            Value::Na,
+            Value::Na,
+            Value::Exists(4.0),
+            Value::Exists(3.0),
+        ]))
+}
@@ -0,0 +1,317 @@
+/*!
+Rule-based validation of the values within a `DataView`.
+
+A set of [Rules](struct.Rules.html) is declared by adding per-field rules with builder methods:
+
+* [not_null](struct.Rules.html#method.not_null) -- values must not be missing (NA).
+* [unique](struct.Rules.html#method.unique) -- values must not be repeated.
+* [in_range](struct.Rules.html#method.in_range) -- values must lie within a range.
+* [matches](struct.Rules.html#method.matches) -- text values must match a regular expression.
+* [references](struct.Rules.html#method.references) -- values must exist in another field
+  (typically a key field of another `DataView`).
+
+With the exception of `not_null`, rules only apply to existing values; missing values never
+violate them.
+
+Running the rules against a `DataView` (with
+[DataView::validate](../view/struct.DataView.html#method.validate)) produces a report `DataView`
+(of type [violations::View](violations/type.View.html)) with one record for each violation,
+containing the index of the violating record, the name of the field, and the name of the violated
+rule. The report's labels belong to a [BuiltinTable](../label/struct.BuiltinTable.html), so they
+never collide with the labels of user-declared tables.
+*/
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::RangeBounds;
+
+use regex::Regex;
+
+use access::DataIndex;
+use cons::Nil;
+use error;
+use field::FieldData;
+use label::LabelName;
+use select::SelectFieldByLabel;
+use store::DataStore;
+use value::Value;
+use view::{DataView, VFieldTypeOf};
+
+tablespace![
+    @builtin(::typenum::U0) pub table violations {
+        Row: u64,
+        Field: String,
+        Rule: String,
+    }
+];
+
+// Function returning the indices of the records of a view which violate a rule.
+type Check<View> = Box<dyn Fn(&View) -> Vec<usize>>;
+
+struct Rule<View> {
+    field: &'static str,
+    name: String,
+    check: Check<View>,
+}
+
+/// A set of validation rules to run against a `DataView` of type `View`. See the
+/// [validate](index.html) module for more details.
+pub struct Rules<View> {
+    rules: Vec<Rule<View>>,
+}
+
+impl<View> Default for Rules<View> {
+    fn default() -> Rules<View> {
+        Rules { rules: vec![] }
+    }
+}
+
+impl<View> Rules<View> {
+    /// Creates a new, empty set of rules.
+    pub fn new() -> Rules<View> {
+        Rules::default()
+    }
+
+    /// Returns the number of rules in this set.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Returns whether or not this set of rules is empty.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    fn with_rule<Label, F>(mut self, name: String, check: F) -> Rules<View>
+    where
+        Label: LabelName,
+        F: Fn(&View) -> Vec<usize> + 'static,
+    {
+        self.rules.push(Rule {
+            field: Label::name(),
+            name,
+            check: Box::new(check),
+        });
+        self
+    }
+
+    /// Adds a rule that values of the field labeled `Label` must not be missing (NA).
+    pub fn not_null<Label>(self) -> Rules<View>
+    where
+        View: SelectFieldByLabel<Label> + 'static,
+        Label: LabelName + 'static,
+    {
+        self.with_rule::<Label, _>("not_null".into(), |view: &View| {
+            violating::<View, Label, _>(view, |value| value.exists())
+        })
+    }
+
+    /// Adds a rule that existing values of the field labeled `Label` must not be repeated. Every
+    /// record containing a repeated value violates this rule.
+    pub fn unique<Label>(self) -> Rules<View>
+    where
+        View: SelectFieldByLabel<Label> + 'static,
+        Label: LabelName + 'static,
+        VFieldTypeOf<View, Label>: Hash + Eq,
+    {
+        self.with_rule::<Label, _>("unique".into(), |view: &View| {
+            let field = SelectFieldByLabel::<Label>::select_field(view);
+            let mut counts = HashMap::new();
+            for value in field.iter() {
+                if let Value::Exists(value) = value {
+                    *counts.entry(value).or_insert(0usize) += 1;
+                }
+            }
+            field
+                .iter()
+                .enumerate()
+                .filter(|&(_, value)| match value {
+                    Value::Exists(value) => counts[value] > 1,
+                    Value::Na => false,
+                })
+                .map(|(idx, _)| idx)
+                .collect()
+        })
+    }
+
+    /// Adds a rule that existing values of the field labeled `Label` must lie within `range`.
+    pub fn in_range<Label, R>(self, range: R) -> Rules<View>
+    where
+        View: SelectFieldByLabel<Label> + 'static,
+        Label: LabelName + 'static,
+        VFieldTypeOf<View, Label>: PartialOrd,
+        R: RangeBounds<VFieldTypeOf<View, Label>> + 'static,
+    {
+        self.with_rule::<Label, _>("in_range".into(), move |view: &View| {
+            violating::<View, Label, _>(view, |value| match value {
+                Value::Exists(value) => range.contains(value),
+                Value::Na => true,
+            })
+        })
+    }
+
+    /// Adds a rule that existing values of the text field labeled `Label` must match the regular
+    /// expression `pattern`. The pattern may match anywhere within a value; use `^` and `$` to
+    /// match entire values.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Regex` if `pattern` is not a valid regular expression.
+    pub fn matches<Label>(self, pattern: &str) -> error::Result<Rules<View>>
+    where
+        View: SelectFieldByLabel<Label> + 'static,
+        Label: LabelName + 'static,
+        VFieldTypeOf<View, Label>: AsRef<str>,
+    {
+        let regex = Regex::new(pattern)?;
+        Ok(
+            self.with_rule::<Label, _>(format!("matches({})", pattern), move |view: &View| {
+                violating::<View, Label, _>(view, |value| match value {
+                    Value::Exists(value) => regex.is_match(value.as_ref()),
+                    Value::Na => true,
+                })
+            }),
+        )
+    }
+
+    /// Adds a rule that existing values of the field labeled `Label` must exist in `other` (e.g.
+    /// a key field of another `DataView`). The values of `other` are copied when the rule is
+    /// added.
+    pub fn references<Label, DI>(self, other: &DI) -> Rules<View>
+    where
+        View: SelectFieldByLabel<Label> + 'static,
+        Label: LabelName + 'static,
+        VFieldTypeOf<View, Label>: Hash + Eq + Clone + 'static,
+        DI: DataIndex<DType = VFieldTypeOf<View, Label>>,
+    {
+        let known = other
+            .iter()
+            .filter_map(|value| match value {
+                Value::Exists(value) => Some(value.clone()),
+                Value::Na => None,
+            })
+            .collect::<HashSet<_>>();
+        self.with_rule::<Label, _>("references".into(), move |view: &View| {
+            violating::<View, Label, _>(view, |value| match value {
+                Value::Exists(value) => known.contains(value),
+                Value::Na => true,
+            })
+        })
+    }
+
+    /// Runs these rules against `view`, returning a report with one record for each violation.
+    /// Violations are reported in the order the rules were added, and in record order for each
+    /// rule.
+    pub fn validate(&self, view: &View) -> violations::View {
+        let (mut rows, mut fields, mut names) = (vec![], vec![], vec![]);
+        for rule in &self.rules {
+            for idx in (rule.check)(view) {
+                rows.push(idx as u64);
+                fields.push(rule.field.to_string());
+                names.push(rule.name.clone());
+            }
+        }
+        DataStore::<Nil>::empty()
+            .push_back_field::<violations::Row, _>(FieldData::from_vec(rows))
+            .push_back_field::<violations::Field, _>(FieldData::from_vec(fields))
+            .push_back_field::<violations::Rule, _>(FieldData::from_vec(names))
+            .into_view()
+    }
+}
+
+// Returns the indices of the records of `view` whose values in the field labeled `Label` do not
+// satisfy `valid`.
+fn violating<View, Label, F>(view: &View, valid: F) -> Vec<usize>
+where
+    View: SelectFieldByLabel<Label>,
+    F: Fn(Value<&VFieldTypeOf<View, Label>>) -> bool,
+{
+    SelectFieldByLabel::<Label>::select_field(view)
+        .iter()
+        .enumerate()
+        .filter(|&(_, value)| !valid(value))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Runs the validation rules `rules` against this `DataView`, returning a report with one
+    /// record for each violation. See the [validate](../validate/index.html) module for more
+    /// details.
+    pub fn validate(&self, rules: &Rules<Self>) -> violations::View {
+        rules.validate(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn validate() {
+        use super::*;
+        use select::FieldSelect;
+        use test_utils::*;
+
+        let dv = sample_emp_table().into_view();
+        let depts = dept_table_from_field(
+            FieldData::from_vec(vec![1u64, 2, 3]),
+            FieldData::from_vec(vec!["Marketing".to_string(), "Sales".into(), "R&D".into()]),
+        )
+        .into_view();
+
+        let rules = Rules::new()
+            .not_null::<emp_table::EmpName>()
+            .unique::<emp_table::DeptId>()
+            .in_range::<emp_table::EmpId, _>(0..9)
+            .matches::<emp_table::EmpName>("^[A-Z][a-z]+$")
+            .unwrap()
+            .matches::<emp_table::EmpName>("ou")
+            .unwrap()
+            .references::<emp_table::DeptId, _>(&depts.field::<dept_table::DeptId>());
+        assert_eq!(rules.len(), 6);
+        match Rules::<emp_table::View>::new().matches::<emp_table::EmpName>("(") {
+            Err(error::AgnesError::Regex(_)) => {}
+            other => panic!(
+                "expected regex error, got {:?}",
+                other.map(|rules| rules.len())
+            ),
+        }
+
+        let report = dv.validate(&rules);
+        assert_eq!(
+            report.field::<violations::Row>().to_vec(),
+            vec![0u64, 2, 3, 5, 6, 5, 6, 0, 1, 2, 3, 6, 5, 6]
+        );
+        assert_eq!(
+            report.field::<violations::Rule>().to_vec()[..7],
+            ["unique", "unique", "unique", "unique", "unique", "in_range", "in_range"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()[..]
+        );
+        assert_eq!(
+            report.field::<violations::Field>().to_vec()[12],
+            "DeptId".to_string()
+        );
+
+        assert_eq!(
+            dv.validate(&Rules::new().not_null::<emp_table::EmpId>())
+                .nrows(),
+            0
+        );
+
+        // the report's labels are distinct from user-defined labels (`emp_table::EmpId` occupies
+        // the first position of the first table of its tablespace), so it can be joined back to
+        // the validated records
+        use join::{Equal, Join};
+        let rows = dv.with_row_index::<emp_stats::NumEmps>();
+        let joined = report.join::<Join<violations::Row, emp_stats::NumEmps, Equal>, _, _>(&rows);
+        assert_eq!(joined.nrows(), 14);
+        assert_eq!(
+            joined.field::<violations::Row>().to_vec(),
+            joined.field::<emp_stats::NumEmps>().to_vec()
+        );
+        assert_eq!(
+            joined.field::<emp_table::EmpId>().to_vec(),
+            vec![0u64, 0, 2, 5, 5, 6, 6, 9, 9, 9, 10, 10, 10, 10]
+        );
+    }
+}
@@ -109,6 +109,13 @@ impl<'a, T: Clone> Value<&'a T> {
         }
     }
 }
+impl<'a> Value<&'a String> {
+    /// Converts a `Value` holding a reference to a `String` into a `Value` holding a `&str`,
+    /// without copying the string.
+    pub fn as_str(self) -> Value<&'a str> {
+        self.map(String::as_str)
+    }
+}
 
 /// Small utility macro to construct a [Value](field/enum.Value.html) enum with a reference to
 /// an existing value. Typically only used for tests.
@@ -230,6 +237,7 @@ where
     }
 }
 
+#[cfg(feature = "serialize")]
 #[cfg(test)]
 mod tests {
     use super::*;
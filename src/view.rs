@@ -13,34 +13,44 @@ object with all of the records of the two source `DataView`s.
 parameters.
 
 */
+use std::any::{Any, TypeId};
+use std::cmp::Ordering;
+use std::collections::HashSet;
 #[cfg(test)]
 use std::collections::VecDeque;
-use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Debug, Display, Formatter};
 use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
+use std::ops::Add;
+use std::rc::Rc;
 
-use prettytable as pt;
+use num_traits::{AsPrimitive, Zero};
+use regex::Regex;
 #[cfg(feature = "serialize")]
-use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde::ser::{self, Serialize, SerializeMap, SerializeSeq, Serializer};
 
 use access::*;
 use cons::*;
+use display::{DisplayOptions, FormatCellsFn};
+use erased::{self, KeyList};
 use error;
-use field::FieldData;
+use field::{ArenaStr, FieldData};
 use fieldlist::FieldPayloadCons;
 #[cfg(test)]
 use frame::StoreRefCount;
 use frame::{Framed, IntoFrame, IntoMeltFrame, IntoStrFrame};
 use join::*;
 use label::*;
-use partial::{DeriveCapabilities, Func, FuncDefault, Implemented, IsImplemented, PartialMap};
+use observer::{NoObserver, Observer, Reporter, Stage};
+use partial::{IsImplemented, PartialCall};
 use permute::{
-    FilterPerm, SortOrder, SortOrderComparator, SortOrderUnstable, SortOrderUnstableComparator,
-    UpdatePermutation,
+    sort_order_observed, FilterPerm, FramePermutations, OptimizeAccess, Permutation, SortOrder,
+    SortOrderComparator, SortOrderUnstable, SortOrderUnstableComparator, TopOrder,
+    TopOrderComparator, UpdatePermutation,
 };
 use select::{FieldSelect, SelectFieldByLabel};
-use store::{IntoStore, IntoView};
+use stats;
+use store::{AssocStorage, DataStore, IntoStore, IntoView, PushFrontField};
 use value::Value;
 
 /// Cons-list of `DataFrame`s held by a `DataView. `FrameIndex` is simply an index used by
@@ -66,6 +76,68 @@ pub(crate) type FrameLookupCons<Label, FrameIndex, FrameLabel, Tail> =
 pub struct DataView<Labels, Frames> {
     pub(crate) _labels: PhantomData<Labels>,
     pub(crate) frames: Frames,
+    pub(crate) invariants: Invariants,
+}
+
+/// Invariants known to hold for the fields of a [DataView](struct.DataView.html), which allow
+/// operations to skip redundant work (such as re-sorting already-sorted records). Invariants are
+/// recorded by the operations which establish them (sorting and
+/// [unique_values](struct.DataView.html#method.unique_values)), and carried through operations
+/// which preserve them (such as filtering and subviews). Views of newly-loaded data have no
+/// recorded invariants; for data loaded in sorted order, sorting (and sort-merge joins) find the
+/// records already in order in linear time and leave them as they are, and sorting records the
+/// invariant. Labels are identified by `TypeId`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Invariants {
+    // label by which the records are sorted in ascending order (missing values first), if any
+    sorted_by: Option<TypeId>,
+    // label lists whose composite values are unique among the records
+    unique_by: Vec<TypeId>,
+}
+
+impl Invariants {
+    /// Returns whether the records are known to be sorted by `Label`.
+    pub(crate) fn is_sorted<Label: 'static>(&self) -> bool {
+        self.sorted_by == Some(TypeId::of::<Label>())
+    }
+    /// Returns whether the composite values of the fields in `LabelList` are known to be unique.
+    pub(crate) fn is_unique<LabelList: 'static>(&self) -> bool {
+        self.unique_by.contains(&TypeId::of::<LabelList>())
+    }
+    fn set_sorted<Label: 'static>(&mut self) {
+        self.sorted_by = Some(TypeId::of::<Label>());
+    }
+    /// Forgets the sortedness of the records, for operations which reorder them.
+    pub(crate) fn clear_sorted(&mut self) {
+        self.sorted_by = None;
+    }
+    fn add_unique<LabelList: 'static>(&mut self) {
+        if !self.is_unique::<LabelList>() {
+            self.unique_by.push(TypeId::of::<LabelList>());
+        }
+    }
+    /// Returns the invariants which still hold for the records at `indices` (in the order
+    /// provided): sortedness holds if the indices are in ascending order, and uniqueness if no
+    /// index is repeated.
+    pub(crate) fn subset(&self, indices: &[usize]) -> Invariants {
+        let ascending = indices.windows(2).all(|pair| pair[0] <= pair[1]);
+        let distinct = if ascending {
+            indices.windows(2).all(|pair| pair[0] < pair[1])
+        } else {
+            let mut seen = vec![false; indices.iter().max().map_or(0, |&max| max + 1)];
+            indices
+                .iter()
+                .all(|&idx| !::std::mem::replace(&mut seen[idx], true))
+        };
+        Invariants {
+            sorted_by: if ascending { self.sorted_by } else { None },
+            unique_by: if distinct {
+                self.unique_by.clone()
+            } else {
+                vec![]
+            },
+        }
+    }
 }
 
 /// Marker struct with the details of where to find a field's data. The `FrameIndex` specifies
@@ -81,12 +153,12 @@ pub trait FrameDetails {
     /// The associated frame index.
     type FrameIndex: Identifier;
     /// The associated `Label` within the frame.
-    type FrameLabel: Label;
+    type FrameLabel: Identifier;
 }
 impl<FrameIndex, FrameLabel> FrameDetails for FrameDetailMarkers<FrameIndex, FrameLabel>
 where
     FrameIndex: Identifier,
-    FrameLabel: Label,
+    FrameLabel: Identifier,
 {
     type FrameIndex = FrameIndex;
     type FrameLabel = FrameLabel;
@@ -98,6 +170,7 @@ impl<Labels, Frames> DataView<Labels, Frames> {
         DataView {
             _labels: PhantomData,
             frames,
+            invariants: Invariants::default(),
         }
     }
 }
@@ -178,6 +251,84 @@ where
         DataView {
             _labels: PhantomData,
             frames: self.frames.subset_clone(),
+            invariants: self.invariants.clone(),
+        }
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames>
+where
+    Frames: Clone,
+{
+    /// Generate a new subview of this `DataView` containing all fields except those in
+    /// `LabelList` (the complement of [v](struct.DataView.html#method.v)). The remaining fields
+    /// retain their original order. `LabelList` is a
+    /// [LabelCons](../label/type.LabelCons.html) list of labels, which can be generated using the
+    /// [Labels](../macro.Labels.html) macro.
+    pub fn drop<LabelList>(&self) -> <Self as DropFields<LabelList>>::Output
+    where
+        Self: DropFields<LabelList>,
+    {
+        DropFields::<LabelList>::drop_fields(self)
+    }
+
+    /// Generate a new `DataView` with the same fields as this `DataView`, displayed in the order
+    /// specified by `LabelList`. `LabelList` must contain every field in this `DataView` exactly
+    /// once. Unlike [v](struct.DataView.html#method.v), the frames of this `DataView` are left
+    /// untouched; only the order of the field lookup list changes.
+    pub fn reorder_fields<LabelList>(&self) -> <Self as ReorderFields<LabelList>>::Output
+    where
+        Self: ReorderFields<LabelList>,
+    {
+        ReorderFields::<LabelList>::reorder_fields(self)
+    }
+}
+
+/// Trait for generating a subview of a [DataView](struct.DataView.html) which excludes a set of
+/// fields. `LabelList` is the fields to remove from the generated `DataView`.
+pub trait DropFields<LabelList> {
+    /// Resulting subview `DataView` type.
+    type Output;
+
+    /// Generate a new subview of this `DataView`, resulting in a newly created `DataView` object
+    /// containing all the fields except those matching the labels in `LabelList`.
+    fn drop_fields(&self) -> Self::Output;
+}
+
+impl<Labels, Frames, LabelList> DropFields<LabelList> for DataView<Labels, Frames>
+where
+    Labels: HasLabels<LabelList> + SetDiff<LabelList>,
+    Self: Subview<<Labels as SetDiff<LabelList>>::Set>,
+{
+    type Output = <Self as Subview<<Labels as SetDiff<LabelList>>::Set>>::Output;
+
+    fn drop_fields(&self) -> Self::Output {
+        Subview::<<Labels as SetDiff<LabelList>>::Set>::subview(self)
+    }
+}
+
+/// Trait for reordering the fields of a [DataView](struct.DataView.html). `LabelList` is the new
+/// ordering of the fields, and must contain exactly the labels of the `DataView`.
+pub trait ReorderFields<LabelList> {
+    /// Resulting reordered `DataView` type.
+    type Output;
+
+    /// Generate a new `DataView` with the fields of this `DataView` in the order of `LabelList`.
+    fn reorder_fields(&self) -> Self::Output;
+}
+
+impl<Labels, Frames, LabelList> ReorderFields<LabelList> for DataView<Labels, Frames>
+where
+    Labels: Reorder<LabelList>,
+    Frames: Clone,
+{
+    type Output = DataView<<Labels as Reorder<LabelList>>::Output, Frames>;
+
+    fn reorder_fields(&self) -> Self::Output {
+        DataView {
+            _labels: PhantomData,
+            frames: self.frames.clone(),
+            invariants: self.invariants.clone(),
         }
     }
 }
@@ -380,6 +531,43 @@ where
     }
 }
 
+/// Trait for selecting a field (implementing [DataIndex](../access/trait.DataIndex.html)) directly
+/// from its frame details: the index `FrameIndex` of the frame within this type, and the label
+/// `FrameLabel` of the field within that frame.
+///
+/// Unlike [SelectFieldFromLabels](trait.SelectFieldFromLabels.html), this does not search a label
+/// lookup list for the field's label. Traversals over every field in a view (e.g.
+/// [FieldMap](trait.FieldMap.html) and serialization) already know the frame details of each field
+/// they visit, so using this trait keeps their compile-time cost from growing with the square of
+/// the number of fields in the view.
+pub trait SelectFieldByFrameDetails<FrameIndex, FrameLabel> {
+    /// Data type of field accessor
+    type DType;
+    /// Selected field accessor.
+    type Output: DataIndex<DType = Self::DType>;
+
+    /// Returns an accessor (implementing [DataIndex](../access/trait.DataIndex.html)) for the
+    /// selected field.
+    fn select_field(&self) -> Self::Output;
+}
+impl<Frames, FrameIndex, FrameLabel> SelectFieldByFrameDetails<FrameIndex, FrameLabel> for Frames
+where
+    Frames: LookupValuedElemByLabel<FrameIndex>,
+    FrameByFrameIndexOf<Frames, FrameIndex>: SelectFieldByLabel<FrameLabel>,
+    FieldFromFrameDetailsOf<Frames, FrameIndex, FrameLabel>: SelfValued + Clone,
+    FieldTypeFromFrameDetailsOf<Frames, FrameIndex, FrameLabel>: fmt::Debug,
+{
+    type DType = FieldTypeFromFrameDetailsOf<Frames, FrameIndex, FrameLabel>;
+    type Output = FieldFromFrameDetailsOf<Frames, FrameIndex, FrameLabel>;
+
+    fn select_field(&self) -> Self::Output {
+        SelectFieldByLabel::<FrameLabel>::select_field(
+            LookupValuedElemByLabel::<FrameIndex>::elem(self).value_ref(),
+        )
+        .clone()
+    }
+}
+
 impl<Labels, Frames, Label> SelectFieldByLabel<Label> for DataView<Labels, Frames>
 where
     Frames: SelectFieldFromLabels<Labels, Label>,
@@ -414,33 +602,21 @@ impl<Frames> AssocDataIndexCons<Nil> for Frames {
 impl<Label, FrameIndex, FrameLabel, LookupTail, Frames>
     AssocDataIndexCons<FrameLookupCons<Label, FrameIndex, FrameLabel, LookupTail>> for Frames
 where
-    Self: SelectFieldFromLabels<FrameLookupCons<Label, FrameIndex, FrameLabel, LookupTail>, Label>,
+    Self: SelectFieldByFrameDetails<FrameIndex, FrameLabel>,
     Self: AssocDataIndexCons<LookupTail>,
-    <Self as SelectFieldFromLabels<
-        FrameLookupCons<Label, FrameIndex, FrameLabel, LookupTail>,
-        Label,
-    >>::Output: Typed,
+    <Self as SelectFieldByFrameDetails<FrameIndex, FrameLabel>>::Output: Typed,
 {
     type Output = DataIndexCons<
         Label,
-        TypeOf<
-            <Frames as SelectFieldFromLabels<
-                FrameLookupCons<Label, FrameIndex, FrameLabel, LookupTail>,
-                Label,
-            >>::Output,
-        >,
-        <Frames as SelectFieldFromLabels<
-            FrameLookupCons<Label, FrameIndex, FrameLabel, LookupTail>,
-            Label,
-        >>::Output,
+        TypeOf<<Frames as SelectFieldByFrameDetails<FrameIndex, FrameLabel>>::Output>,
+        <Frames as SelectFieldByFrameDetails<FrameIndex, FrameLabel>>::Output,
         <Frames as AssocDataIndexCons<LookupTail>>::Output,
     >;
     fn assoc_data(&self) -> Self::Output {
         DataIndexCons {
-            head: TypedValue::from(SelectFieldFromLabels::<
-                FrameLookupCons<Label, FrameIndex, FrameLabel, LookupTail>,
-                Label,
-            >::select_field(self))
+            head: TypedValue::from(
+                SelectFieldByFrameDetails::<FrameIndex, FrameLabel>::select_field(self),
+            )
             .into(),
             tail: AssocDataIndexCons::<LookupTail>::assoc_data(self),
         }
@@ -461,80 +637,57 @@ pub trait FieldMap<F> {
 
 impl<F, Labels, Frames> FieldMap<F> for DataView<Labels, Frames>
 where
-    Frames: AssocDataIndexCons<Labels>,
-    AssocDataIndexConsOf<Labels, Frames>: DeriveCapabilities<F>,
+    Labels: FieldMapFrames<Frames, F>,
 {
     fn field_map(&self, f: &mut F) {
-        self.frames.assoc_data().derive().map(f);
+        Labels::field_map_frames(&self.frames, f);
     }
 }
 
-const MAX_DISP_ROWS: usize = 1000;
-
-impl<Labels, Frames> Display for DataView<Labels, Frames>
+/// Trait for applying a function (implementing [Func](../partial/trait.Func.html)) to each field
+/// in `Frames` associated with the labels in a label lookup list. Used by the
+/// [FieldMap](trait.FieldMap.html) implementation of [DataView](struct.DataView.html).
+///
+/// Each field is selected directly by its frame details, and the function is called on it before
+/// moving on to the next label, without first collecting the fields into a
+/// [DataIndexCons](type.DataIndexCons.html) cons-list. This keeps the compile-time cost of mapping
+/// over views with many fields manageable.
+pub trait FieldMapFrames<Frames, F> {
+    /// Apply function `f` to every field in `frames` associated with this label lookup list.
+    fn field_map_frames(frames: &Frames, f: &mut F);
+}
+impl<Frames, F> FieldMapFrames<Frames, F> for Nil {
+    fn field_map_frames(_frames: &Frames, _f: &mut F) {}
+}
+impl<Frames, F, Label, FrameIndex, FrameLabel, Tail> FieldMapFrames<Frames, F>
+    for FrameLookupCons<Label, FrameIndex, FrameLabel, Tail>
 where
-    Frames: Len + NRows,
-    Self: FieldMap<AddCellToRowFn>,
-    Labels: StrLabels,
+    Frames: SelectFieldByFrameDetails<FrameIndex, FrameLabel>,
+    <Frames as SelectFieldByFrameDetails<FrameIndex, FrameLabel>>::DType: IsImplemented<F>,
+    F: PartialCall<
+        <Frames as SelectFieldByFrameDetails<FrameIndex, FrameLabel>>::DType,
+        <<Frames as SelectFieldByFrameDetails<FrameIndex, FrameLabel>>::DType as IsImplemented<
+            F,
+        >>::IsImpl,
+    >,
+    Tail: FieldMapFrames<Frames, F>,
 {
-    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-        if Frames::is_empty() {
-            return write!(f, "Empty DataView");
-        }
-        let mut table = pt::Table::new();
-
-        let nrows = self.nrows();
-        let mut func = AddCellToRowFn {
-            rows: vec![pt::row::Row::empty(); nrows.min(MAX_DISP_ROWS)],
-        };
-        self.field_map(&mut func);
-        for row in func.rows.drain(..) {
-            table.add_row(row);
-        }
-
-        table.set_titles(<Labels as StrLabels>::labels().into());
-        table.set_format(*pt::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
-
-        Display::fmt(&table, f)
+    fn field_map_frames(frames: &Frames, f: &mut F) {
+        f.partial_call(&SelectFieldByFrameDetails::<FrameIndex, FrameLabel>::select_field(frames));
+        Tail::field_map_frames(frames, f);
     }
 }
 
-/// Function (implementing [Func](../partial/trait.Func.html)) that adds cells to
-/// `prettytable::row::Row`.
-pub struct AddCellToRowFn {
-    rows: Vec<pt::row::Row>,
-}
-impl<DType> Func<DType> for AddCellToRowFn
+impl<Labels, Frames> Display for DataView<Labels, Frames>
 where
-    for<'a> Value<&'a DType>: ToString,
+    Frames: Len + NRows,
+    Self: FieldMap<FormatCellsFn>,
+    Labels: StrLabels,
 {
-    type Output = ();
-    fn call<DI>(&mut self, data: &DI) -> Self::Output
-    where
-        DI: DataIndex<DType = DType>,
-    {
-        debug_assert!(data.len() >= self.rows.len());
-        for i in 0..self.rows.len() {
-            self.rows[i].add_cell(cell!(data.get_datum(i).unwrap()));
-        }
-    }
-}
-impl FuncDefault for AddCellToRowFn {
-    type Output = ();
-    fn call(&mut self) -> Self::Output {
-        for i in 0..self.rows.len() {
-            self.rows[i].add_cell(cell!());
-        }
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        Display::fmt(&self.display(&DisplayOptions::default()), f)
     }
 }
-macro_rules! impl_addcell_is_impl {
-    ($($dtype:ty)*) => {$(
-        impl IsImplemented<AddCellToRowFn> for $dtype {
-            type IsImpl = Implemented;
-        }
-    )*}
-}
-impl_addcell_is_impl![String &str f64 f32 u64 u32 i64 i32 bool];
 
 impl<Labels, Frames> DataView<Labels, Frames> {
     /// Construct a new `DataView` with the label `CurrLabel` relabeled with the label `NewLabel`.
@@ -547,6 +700,7 @@ impl<Labels, Frames> DataView<Labels, Frames> {
         DataView {
             _labels: PhantomData,
             frames: self.frames,
+            invariants: Invariants::default(),
         }
     }
 }
@@ -600,6 +754,162 @@ where
     >;
 }
 
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Construct a new `DataView` with each label which is also found in `other` replaced by the
+    /// [Affixed](../label/struct.Affixed.html) label `Affixed<Label, A>`, so that this `DataView`
+    /// can be merged or joined with `other` without label collisions. Labels which aren't found in
+    /// `other` are left unchanged.
+    pub fn affix_colliding<A, OLabels, OFrames>(
+        &self,
+        _other: &DataView<OLabels, OFrames>,
+    ) -> DataView<<Labels as AffixColliding<OLabels, A>>::Output, Frames>
+    where
+        Labels: AffixColliding<OLabels, A>,
+        Frames: Clone,
+    {
+        DataView {
+            _labels: PhantomData,
+            frames: self.frames.clone(),
+            invariants: Invariants::default(),
+        }
+    }
+
+    /// Construct a new `DataView` with every label replaced by the
+    /// [Affixed](../label/struct.Affixed.html) label `Affixed<Label, A>`. This relabels the whole
+    /// view in one call, mapping each of its labels into a table distinct from the original
+    /// labels' tables, so that the view can be merged or joined with another view (or with
+    /// itself) drawn from the same tables.
+    pub fn affix_all<A>(self) -> DataView<<Labels as AffixAll<A>>::Output, Frames>
+    where
+        Labels: AffixAll<A>,
+    {
+        DataView {
+            _labels: PhantomData,
+            frames: self.frames,
+            invariants: Invariants::default(),
+        }
+    }
+
+    /// Construct a shallow copy of this `DataView` with every label replaced by the
+    /// [Affixed](../label/struct.Affixed.html) label `Affixed<Label, A>` (see
+    /// [affix_all](#method.affix_all)). The copy references the same underlying data as this
+    /// `DataView`, and can be joined with it without label collisions, for self-joins such as
+    /// joining employees to their managers.
+    pub fn alias<A>(&self) -> DataView<<Labels as AffixAll<A>>::Output, Frames>
+    where
+        Labels: AffixAll<A>,
+        Frames: Clone,
+    {
+        DataView {
+            _labels: PhantomData,
+            frames: self.frames.clone(),
+            invariants: Invariants::default(),
+        }
+    }
+
+    /// Combine two `DataView` objects using specified join as in [join](#method.join), with the
+    /// labels of `right` which collide with labels of this `DataView` replaced by
+    /// [Affixed](../label/struct.Affixed.html) labels using affix `A` (see
+    /// [affix_colliding](#method.affix_colliding)). `Join` refers to the original (unaffixed)
+    /// labels. If both sides of the join share the key label, the right-hand key field is affixed
+    /// as well.
+    pub fn join_affixed<Join, A, RLabels, RFrames>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+    ) -> AffixedJoinOutput<Self, Join, A, Labels, RLabels, RFrames>
+    where
+        RLabels: AffixColliding<Labels, A>,
+        RFrames: Clone,
+        Join: AffixJoin<Labels, A>,
+        Self: SortMergeJoin<AffixedOf<RLabels, Labels, A>, RFrames, AffixedJoinOf<Join, Labels, A>>,
+    {
+        SortMergeJoin::join(self, &right.affix_colliding::<A, _, _>(self))
+    }
+}
+
+type AffixedOf<Labels, OtherLabels, A> = <Labels as AffixColliding<OtherLabels, A>>::Output;
+type AffixedJoinOf<Join, LLabels, A> = <Join as AffixJoin<LLabels, A>>::Output;
+type AffixedJoinOutput<View, Join, A, LLabels, RLabels, RFrames> = <View as SortMergeJoin<
+    AffixedOf<RLabels, LLabels, A>,
+    RFrames,
+    AffixedJoinOf<Join, LLabels, A>,
+>>::Output;
+type IndicatedJoinOf<View, RLabels, RFrames, Join, IndLabel> = <<View as OuterJoin<
+    RLabels,
+    RFrames,
+    Join,
+>>::Output as AddFrame<
+    IndicatorFrameOf<IndLabel>,
+>>::Output;
+
+/// Trait for replacing the labels in `Self` which are also found in `OtherLabels` with
+/// [Affixed](../label/struct.Affixed.html) labels using affix `A`.
+pub trait AffixColliding<OtherLabels, A> {
+    /// The output type after affixing colliding labels.
+    type Output;
+}
+impl<OtherLabels, A> AffixColliding<OtherLabels, A> for Nil {
+    type Output = Nil;
+}
+impl<OtherLabels, A, Label, FrameIndex, FrameLabel, Tail> AffixColliding<OtherLabels, A>
+    for FrameLookupCons<Label, FrameIndex, FrameLabel, Tail>
+where
+    Label: AffixIfColliding<OtherLabels, A>,
+    Tail: AffixColliding<OtherLabels, A>,
+{
+    type Output = FrameLookupCons<
+        <Label as AffixIfColliding<OtherLabels, A>>::Output,
+        FrameIndex,
+        FrameLabel,
+        <Tail as AffixColliding<OtherLabels, A>>::Output,
+    >;
+}
+
+/// Trait for replacing the label `Self` with the [Affixed](../label/struct.Affixed.html) label
+/// `Affixed<Self, A>` if `Self` is found in `OtherLabels`.
+pub trait AffixIfColliding<OtherLabels, A> {
+    /// The resulting label.
+    type Output;
+}
+impl<Label, OtherLabels, A> AffixIfColliding<OtherLabels, A> for Label
+where
+    OtherLabels: Member<Label>,
+    Label: AffixIfMatch<A, <OtherLabels as Member<Label>>::IsMember>,
+{
+    type Output = <Label as AffixIfMatch<A, <OtherLabels as Member<Label>>::IsMember>>::Output;
+}
+
+/// Helper trait for affixing labels. Used by [AffixIfColliding](trait.AffixIfColliding.html).
+/// `Match` is whether or not the label `Self` should be affixed with `A`.
+pub trait AffixIfMatch<A, Match> {
+    /// The resulting label.
+    type Output;
+}
+impl<Label, A> AffixIfMatch<A, True> for Label {
+    type Output = Affixed<Label, A>;
+}
+impl<Label, A> AffixIfMatch<A, False> for Label {
+    type Output = Label;
+}
+
+/// Trait for replacing every label in `Self` with the [Affixed](../label/struct.Affixed.html)
+/// label `Affixed<Label, A>`.
+pub trait AffixAll<A> {
+    /// The output type after affixing all labels.
+    type Output;
+}
+impl<A> AffixAll<A> for Nil {
+    type Output = Nil;
+}
+impl<A, Label, FrameIndex, FrameLabel, Tail> AffixAll<A>
+    for FrameLookupCons<Label, FrameIndex, FrameLabel, Tail>
+where
+    Tail: AffixAll<A>,
+{
+    type Output =
+        FrameLookupCons<Affixed<Label, A>, FrameIndex, FrameLabel, <Tail as AffixAll<A>>::Output>;
+}
+
 /// Trait for merging the data from two [DataView](struct.DataView.html)s into one new `DataView`.
 /// The two `DataView`s should have the same number of rows, and the resultant `DataView` is one
 /// with all the fields of both of the two original `DataView`s.
@@ -676,6 +986,144 @@ impl<Labels, Frames> DataView<Labels, Frames> {
         //     }
         // }
     }
+
+    /// Combine two `DataView` objects using specified join, reporting the progress of the join to
+    /// `observer` (with stage [Stage::Join](../observer/enum.Stage.html)). See
+    /// [join](#method.join).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation.
+    pub fn join_observed<Join, RLabels, RFrames, O>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        observer: &mut O,
+    ) -> error::Result<<Self as SortMergeJoin<RLabels, RFrames, Join>>::Output>
+    where
+        Self: SortMergeJoin<RLabels, RFrames, Join>,
+        O: Observer,
+    {
+        SortMergeJoin::join_observed(self, right, observer)
+    }
+
+    /// Combine two `DataView` objects using specified join as in [join](#method.join), after
+    /// checking that the key values of both `DataView`s have the cardinality specified by
+    /// `validation` (see [ValidateJoin](../join/trait.ValidateJoin.html)).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::JoinValidation` if a key value is repeated on a side which
+    /// `validation` requires to be unique.
+    pub fn join_validated<Join, RLabels, RFrames>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        validation: JoinValidation,
+    ) -> error::Result<<Self as SortMergeJoin<RLabels, RFrames, Join>>::Output>
+    where
+        Self: SortMergeJoin<RLabels, RFrames, Join> + ValidateJoin<RLabels, RFrames, Join>,
+    {
+        ValidateJoin::validate_join(self, right, validation)?;
+        Ok(SortMergeJoin::join(self, right))
+    }
+
+    /// Combine two `DataView` objects using the specified equality join, executed as a
+    /// partitioned hash join across `nthreads` threads (or one thread per available CPU, if
+    /// `nthreads` is `0`). The resulting `DataView` contains the same records as [join](#method.join)
+    /// would produce, but ordered by position in this `DataView` rather than by key value. See
+    /// [ParallelJoin](../join/trait.ParallelJoin.html) for more details.
+    pub fn par_join<Join, RLabels, RFrames>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        nthreads: usize,
+    ) -> <Self as ParallelJoin<RLabels, RFrames, Join>>::Output
+    where
+        Self: ParallelJoin<RLabels, RFrames, Join>,
+    {
+        ParallelJoin::par_join(self, right, nthreads)
+    }
+
+    /// Combine two `DataView` objects using the specified equality join across `nthreads` threads,
+    /// reporting the progress of the join to `observer` (with stage
+    /// [Stage::Join](../observer/enum.Stage.html)). See [par_join](#method.par_join).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation.
+    pub fn par_join_observed<Join, RLabels, RFrames, O>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        nthreads: usize,
+        observer: &mut O,
+    ) -> error::Result<<Self as ParallelJoin<RLabels, RFrames, Join>>::Output>
+    where
+        Self: ParallelJoin<RLabels, RFrames, Join>,
+        O: Observer,
+    {
+        ParallelJoin::par_join_observed(self, right, nthreads, observer)
+    }
+
+    /// Combine two `DataView` objects using the specified equality join, with a fast path for
+    /// small right-hand sides (such as lookup tables): if `right` has at most `max_broadcast_rows`
+    /// rows, a hash table is built once from `right` and the records of this `DataView` are
+    /// streamed through it. Larger right-hand sides fall back to [par_join](#method.par_join). See
+    /// [ParallelJoin](../join/trait.ParallelJoin.html) for more details.
+    pub fn broadcast_join<Join, RLabels, RFrames>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        max_broadcast_rows: usize,
+    ) -> <Self as ParallelJoin<RLabels, RFrames, Join>>::Output
+    where
+        Self: ParallelJoin<RLabels, RFrames, Join>,
+    {
+        ParallelJoin::broadcast_join(self, right, max_broadcast_rows)
+    }
+
+    /// Combine two `DataView` objects as in [broadcast_join](#method.broadcast_join), reporting
+    /// the progress of the join to `observer` (with stage
+    /// [Stage::Join](../observer/enum.Stage.html)).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation.
+    pub fn broadcast_join_observed<Join, RLabels, RFrames, O>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        max_broadcast_rows: usize,
+        observer: &mut O,
+    ) -> error::Result<<Self as ParallelJoin<RLabels, RFrames, Join>>::Output>
+    where
+        Self: ParallelJoin<RLabels, RFrames, Join>,
+        O: Observer,
+    {
+        ParallelJoin::broadcast_join_observed(self, right, max_broadcast_rows, observer)
+    }
+
+    /// Combine two `DataView` objects using the specified equality join, retaining the unmatched
+    /// records of the side(s) specified by `kind` with missing (NA) values in the fields of the
+    /// other side. See [OuterJoin](../join/trait.OuterJoin.html) for more details.
+    pub fn outer_join<Join, RLabels, RFrames>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        kind: OuterJoinKind,
+    ) -> <Self as OuterJoin<RLabels, RFrames, Join>>::Output
+    where
+        Self: OuterJoin<RLabels, RFrames, Join>,
+    {
+        OuterJoin::outer_join(self, right, kind)
+    }
+
+    /// Combine two `DataView` objects as in [outer_join](#method.outer_join), adding a `String`
+    /// indicator field labeled `IndLabel` which records whether each joined record matched on
+    /// both sides (`"both"`) or came from only one side (`"left_only"` or `"right_only"`).
+    pub fn outer_join_with_indicator<Join, IndLabel, RLabels, RFrames>(
+        &self,
+        right: &DataView<RLabels, RFrames>,
+        kind: OuterJoinKind,
+    ) -> IndicatedJoinOf<Self, RLabels, RFrames, Join, IndLabel>
+    where
+        Self: OuterJoin<RLabels, RFrames, Join>,
+        <Self as OuterJoin<RLabels, RFrames, Join>>::Output: AddFrame<IndicatorFrameOf<IndLabel>>,
+        FieldData<String>: IntoStore<IndLabel>,
+        <FieldData<String> as IntoStore<IndLabel>>::Output: IntoFrame,
+    {
+        OuterJoin::outer_join_with_indicator::<IndLabel>(self, right, kind)
+    }
 }
 
 impl<FrameIndex, Frame, Tail> UpdatePermutation for ViewFrameCons<FrameIndex, Frame, Tail>
@@ -691,6 +1139,59 @@ where
     }
 }
 
+impl<FrameIndex, Frame, Tail> FramePermutations for ViewFrameCons<FrameIndex, Frame, Tail>
+where
+    Frame: Valued<Value = Frame>,
+    ValueOf<Frame>: FramePermutations,
+    Tail: FramePermutations,
+{
+    fn frame_permutations(&self, perms: &mut Vec<Rc<Permutation<Vec<usize>>>>) {
+        self.head.value_ref().frame_permutations(perms);
+        self.tail.frame_permutations(perms);
+    }
+}
+
+impl<FrameIndex, Frame, Tail> OptimizeAccess for ViewFrameCons<FrameIndex, Frame, Tail>
+where
+    Frame: Valued<Value = Frame>,
+    ValueOf<Frame>: OptimizeAccess,
+    Tail: OptimizeAccess,
+{
+    fn optimize_access(mut self, shared: &mut Vec<Rc<Permutation<Vec<usize>>>>) -> Self {
+        self.head = Labeled::from(self.head.value().optimize_access(shared));
+        self.tail = self.tail.optimize_access(shared);
+        self
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames>
+where
+    Frames: OptimizeAccess,
+{
+    /// Simplifies the record permutations of this `DataView` to speed up subsequent access to its
+    /// values. Permutations which leave every record of a frame in its original order (for
+    /// example, after sorting already-sorted data, or filtering with a predicate which matches
+    /// every record) are removed, allowing fields to provide direct, contiguous access to their
+    /// values (see [DataIndex::get_chunk](../access/trait.DataIndex.html#method.get_chunk)).
+    /// Frames with equal permutations (such as the frames of a merged view after sorting) are
+    /// made to share a single permutation, reducing memory usage and improving cache locality
+    /// when scanning fields of several frames together.
+    ///
+    /// Successive sorts and filters are always composed into a single permutation for each frame,
+    /// so each value access requires at most one permutation lookup; to remove this lookup
+    /// entirely, copy the data into a new store with [to_store](#method.to_store) or
+    /// [compact](#method.compact).
+    pub fn optimize_access(mut self) -> Self {
+        self.frames = self.frames.optimize_access(&mut vec![]);
+        self
+    }
+}
+
+// Returns whether the sort order `order` leaves every record in place.
+fn is_identity(order: &[usize]) -> bool {
+    order.iter().enumerate().all(|(idx, &record)| idx == record)
+}
+
 impl<Labels, Frames> DataView<Labels, Frames>
 where
     Frames: UpdatePermutation,
@@ -702,30 +1203,90 @@ where
     /// values).
     pub fn sort_by_label<Label>(mut self) -> Self
     where
+        Label: 'static,
         Self: SelectFieldByLabel<Label>,
         <Self as SelectFieldByLabel<Label>>::Output: SortOrder,
     {
+        // already sorted by this field; sorting would not change the order
+        if self.invariants.is_sorted::<Label>() {
+            return self;
+        }
         // find sort order for this field
         let sorted = self.field::<Label>().sort_order();
-        // apply sort order to each frame
-        self.frames = self.frames.update_permutation(&sorted);
+        // apply sort order to each frame, unless the records are already in order
+        if !is_identity(&sorted) {
+            self.frames = self.frames.update_permutation(&sorted);
+        }
+        self.invariants.set_sorted::<Label>();
         self
     }
 
-    /// Sorts this `DataView` by the provided label. This sort is unstable -- it does not
-    /// necessarily preserve the original order of equal elements, but may be faster. Consumes the
-    /// `DataView` and returns a `DataView sorted by values from field identifier by `Label` in
-    /// ascending order, with missing (NA) values at the beginning of the order (considered to be of
+    /// Sorts this `DataView` by the provided label, reporting the progress of the sort to
+    /// `observer` (with stage [Stage::Sort](../observer/enum.Stage.html)) after each block of
+    /// records is sorted and after each merge of sorted blocks. See
+    /// [sort_by_label](#method.sort_by_label).
+    pub fn sort_by_label_observed<Label, O>(mut self, observer: &mut O) -> Self
+    where
+        Label: 'static,
+        Self: SelectFieldByLabel<Label>,
+        VFieldTypeOf<Self, Label>: Ord,
+        O: Observer,
+    {
+        // already in order (checked in linear time); sorting would not change the order
+        if self.is_sorted::<Label>() {
+            self.invariants.set_sorted::<Label>();
+            return self;
+        }
+        let sorted = sort_order_observed(&self.field::<Label>(), true, observer);
+        self.frames = self.frames.update_permutation(&sorted);
+        self.invariants.set_sorted::<Label>();
+        self
+    }
+
+    /// Sorts this `DataView` by the provided label. This sort is unstable -- it does not
+    /// necessarily preserve the original order of equal elements, but may be faster. Consumes the
+    /// `DataView` and returns a `DataView sorted by values from field identifier by `Label` in
+    /// ascending order, with missing (NA) values at the beginning of the order (considered to be of
     /// 'lesser' value than existing values).
     pub fn sort_unstable_by_label<Label>(mut self) -> Self
     where
+        Label: 'static,
         Self: SelectFieldByLabel<Label>,
         <Self as SelectFieldByLabel<Label>>::Output: SortOrderUnstable,
     {
+        // already sorted by this field; sorting would not change the order
+        if self.invariants.is_sorted::<Label>() {
+            return self;
+        }
         // find sort order for this field
         let sorted = self.field::<Label>().sort_order_unstable();
-        // apply sort order to each frame
+        // apply sort order to each frame, unless the records are already in order
+        if !is_identity(&sorted) {
+            self.frames = self.frames.update_permutation(&sorted);
+        }
+        self.invariants.set_sorted::<Label>();
+        self
+    }
+
+    /// Sorts this `DataView` by the provided label (using an unstable sort), reporting the progress
+    /// of the sort to `observer` (with stage [Stage::Sort](../observer/enum.Stage.html)) after each
+    /// block of records is sorted and after each merge of sorted blocks. See
+    /// [sort_unstable_by_label](#method.sort_unstable_by_label).
+    pub fn sort_unstable_by_label_observed<Label, O>(mut self, observer: &mut O) -> Self
+    where
+        Label: 'static,
+        Self: SelectFieldByLabel<Label>,
+        VFieldTypeOf<Self, Label>: Ord,
+        O: Observer,
+    {
+        // already in order (checked in linear time); sorting would not change the order
+        if self.is_sorted::<Label>() {
+            self.invariants.set_sorted::<Label>();
+            return self;
+        }
+        let sorted = sort_order_observed(&self.field::<Label>(), false, observer);
         self.frames = self.frames.update_permutation(&sorted);
+        self.invariants.set_sorted::<Label>();
         self
     }
 
@@ -743,6 +1304,7 @@ where
         let sorted = self.field::<Label>().sort_order_by(compare);
         // apply sort order to each frame
         self.frames = self.frames.update_permutation(&sorted);
+        self.invariants.clear_sorted();
         self
     }
 
@@ -760,13 +1322,108 @@ where
         let sorted = self.field::<Label>().sort_order_unstable_by(compare);
         // apply sort order to each frame
         self.frames = self.frames.update_permutation(&sorted);
+        self.invariants.clear_sorted();
+        self
+    }
+
+    /// Returns a `DataView` containing the (up to) `n` records with the largest values of the field
+    /// labeled `Label`, in descending order of that field. Equal values are kept in their original
+    /// order, and records with missing (NA) values are never selected. Unlike sorting the whole
+    /// `DataView` with [sort_by_label](#method.sort_by_label), this only partially orders the
+    /// records, which is much faster when `n` is small compared to the number of records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate agnes;
+    /// use agnes::access::DataIndex;
+    /// use agnes::cons::Nil;
+    /// use agnes::field::FieldData;
+    /// use agnes::select::FieldSelect;
+    /// use agnes::store::{DataStore, PushBackField};
+    ///
+    /// tablespace![
+    ///     table scores {
+    ///         Name: String,
+    ///         Score: u64,
+    ///     }
+    /// ];
+    ///
+    /// fn main() {
+    ///     let dv = DataStore::<Nil>::empty()
+    ///         .push_back_field::<scores::Name, _>(FieldData::<String>::from_vec(vec![
+    ///             "A".to_string(), "B".to_string(), "C".to_string(), "D".to_string(),
+    ///         ]))
+    ///         .push_back_field::<scores::Score, _>(FieldData::<u64>::from_vec(vec![7u64, 9, 3, 8]))
+    ///         .into_view();
+    ///
+    ///     let top = dv.clone().top_n::<scores::Score>(2);
+    ///     assert_eq!(top.field::<scores::Name>().to_vec(), vec!["B", "D"]);
+    ///     let bottom = dv.bottom_n::<scores::Score>(2);
+    ///     assert_eq!(bottom.field::<scores::Name>().to_vec(), vec!["C", "A"]);
+    /// }
+    /// ```
+    pub fn top_n<Label>(mut self, n: usize) -> Self
+    where
+        Self: SelectFieldByLabel<Label>,
+        <Self as SelectFieldByLabel<Label>>::Output: TopOrder,
+    {
+        let order = self.field::<Label>().top_order(n);
+        self.frames = self.frames.update_permutation(&order);
+        self.invariants.clear_sorted();
+        self
+    }
+
+    /// Returns a `DataView` containing the (up to) `n` records with the smallest values of the
+    /// field labeled `Label`, in ascending order of that field. Equal values are kept in their
+    /// original order, and records with missing (NA) values are never selected. See
+    /// [top_n](#method.top_n).
+    pub fn bottom_n<Label>(mut self, n: usize) -> Self
+    where
+        Label: 'static,
+        Self: SelectFieldByLabel<Label>,
+        <Self as SelectFieldByLabel<Label>>::Output: TopOrder,
+    {
+        let order = self.field::<Label>().bottom_order(n);
+        self.frames = self.frames.update_permutation(&order);
+        // the selected records are in ascending order without missing values
+        self.invariants.set_sorted::<Label>();
+        self
+    }
+
+    /// Returns a `DataView` containing the (up to) `n` records with the largest values of the field
+    /// labeled `Label` according to the comparator `compare`, in descending order. Records with
+    /// missing (NA) values are never selected. See [top_n](#method.top_n).
+    pub fn top_n_comparator<Label, F>(mut self, n: usize, compare: F) -> Self
+    where
+        Self: SelectFieldByLabel<Label>,
+        <Self as SelectFieldByLabel<Label>>::Output: TopOrderComparator<F>,
+    {
+        let order = self.field::<Label>().top_order_by(n, compare);
+        self.frames = self.frames.update_permutation(&order);
+        self.invariants.clear_sorted();
+        self
+    }
+
+    /// Returns a `DataView` containing the (up to) `n` records with the smallest values of the
+    /// field labeled `Label` according to the comparator `compare`, in ascending order. Records
+    /// with missing (NA) values are never selected. See [top_n](#method.top_n).
+    pub fn bottom_n_comparator<Label, F>(mut self, n: usize, compare: F) -> Self
+    where
+        Self: SelectFieldByLabel<Label>,
+        <Self as SelectFieldByLabel<Label>>::Output: TopOrderComparator<F>,
+    {
+        let order = self.field::<Label>().bottom_order_by(n, compare);
+        self.frames = self.frames.update_permutation(&order);
+        self.invariants.clear_sorted();
         self
     }
 
     /// Filters this `DataView` by `predicate` (a function mapping from `Value<&T>` to `bool` where
     /// `T` is the type of the field with label `Label`). Consumes this `DataView` and returns a new
     /// `DataView` such that only those rows where values within the field with label `Label`
-    /// matching `predicate` remain.
+    /// matching `predicate` remain. The [filter](../filter/index.html) module provides common
+    /// predicates, such as ranges of values and set membership.
     pub fn filter<Label, P>(mut self, predicate: P) -> Self
     where
         Self: SelectFieldByLabel<Label>,
@@ -776,6 +1433,130 @@ where
         self.frames = self.frames.update_permutation(&perm);
         self
     }
+
+    /// Returns a new `DataView` containing only the records at indices `indices` (in the order
+    /// provided), such as those returned by [argmin](../stats/trait.ArgExtrema.html#tymethod.argmin)
+    /// or [lookup](#method.lookup).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::IndexError` if any of the `indices` are out of bounds.
+    pub fn take(&self, indices: &[usize]) -> error::Result<Self>
+    where
+        Self: NRows,
+        Frames: Clone,
+    {
+        let nrows = self.nrows();
+        if let Some(&index) = indices.iter().find(|&&index| index >= nrows) {
+            return Err(error::AgnesError::IndexError { index, len: nrows });
+        }
+        Ok(self.subset_records(indices))
+    }
+
+    /// Returns a new `DataView` containing only the records at indices `indices` (in the order
+    /// provided), keeping the invariants which still hold for them.
+    pub(crate) fn subset_records(&self, indices: &[usize]) -> Self
+    where
+        Frames: Clone,
+    {
+        DataView {
+            _labels: PhantomData,
+            frames: self.frames.clone().update_permutation(indices),
+            invariants: self.invariants.subset(indices),
+        }
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Returns whether the records of this `DataView` are sorted in ascending order by the field
+    /// labeled `Label`, with missing (NA) values first (the order produced by
+    /// [sort_by_label](#method.sort_by_label)). If this `DataView` is already known to be sorted by
+    /// `Label`, returns without examining the field.
+    pub fn is_sorted<Label>(&self) -> bool
+    where
+        Label: 'static,
+        Self: SelectFieldByLabel<Label>,
+        VFieldTypeOf<Self, Label>: PartialOrd,
+    {
+        if self.invariants.is_sorted::<Label>() {
+            return true;
+        }
+        let field = self.field::<Label>();
+        let mut prev: Value<&VFieldTypeOf<Self, Label>> = Value::Na;
+        for datum in field.iter() {
+            match (prev, datum) {
+                (Value::Exists(_), Value::Na) => return false,
+                (Value::Exists(prev), Value::Exists(datum))
+                    if prev
+                        .partial_cmp(datum)
+                        .is_none_or(|ord| ord == Ordering::Greater) =>
+                {
+                    return false
+                }
+                _ => {}
+            }
+            prev = datum;
+        }
+        true
+    }
+
+    /// Returns whether the values of the field labeled `Label` are unique among the records of
+    /// this `DataView`. As with [unique_indices](#method.unique_indices), missing (NA) values are
+    /// considered equal to each other. If this `DataView` is already known to be unique by `Label`
+    /// (e.g. it was produced by [unique_values](#method.unique_values)), returns without examining
+    /// the field.
+    pub fn is_unique<Label>(&self) -> bool
+    where
+        Label: 'static,
+        Self: SelectFieldByLabel<Label>,
+        VFieldTypeOf<Self, Label>: Hash + Eq,
+    {
+        if self.invariants.is_unique::<Labels![Label]>() {
+            return true;
+        }
+        let field = self.field::<Label>();
+        let mut seen = HashSet::with_capacity(field.len());
+        field.iter().all(|datum| seen.insert(datum))
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Returns the indices (in increasing order) of the records whose value in the field labeled
+    /// `Label` is equal to `value`. If this `DataView` is known to be sorted by `Label` (i.e. it
+    /// was produced by [sort_by_label](#method.sort_by_label) or
+    /// [sort_unstable_by_label](#method.sort_unstable_by_label), possibly followed by
+    /// [filter](#method.filter)), the matching records are found with a binary search rather than a
+    /// scan of the entire field.
+    pub fn lookup<Label>(&self, value: &VFieldTypeOf<Self, Label>) -> Vec<usize>
+    where
+        Label: 'static,
+        Self: SelectFieldByLabel<Label>,
+        VFieldTypeOf<Self, Label>: PartialOrd,
+    {
+        let field = self.field::<Label>();
+        if !self.invariants.is_sorted::<Label>() {
+            return field
+                .iter()
+                .enumerate()
+                .filter(|&(_, datum)| datum == Value::Exists(value))
+                .map(|(idx, _)| idx)
+                .collect();
+        }
+        // missing values are sorted before all existing values
+        let partition_point = |pred: &dyn Fn(&VFieldTypeOf<Self, Label>) -> bool| {
+            let (mut lo, mut hi) = (0, field.len());
+            while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                match field.get_datum(mid).unwrap() {
+                    Value::Exists(datum) if !pred(datum) => hi = mid,
+                    _ => lo = mid + 1,
+                }
+            }
+            lo
+        };
+        let start = partition_point(&|datum| datum < value);
+        let end = partition_point(&|datum| datum <= value);
+        (start..end).collect()
+    }
 }
 
 /// Trait for finding a cons-list of fields (implementing
@@ -825,20 +1606,17 @@ pub trait FieldListPred<LabelList, Frames, IsMember> {
 impl<LabelList, Frames, Label, FrameIndex, FrameLabel, Tail> FieldListPred<LabelList, Frames, True>
     for FrameLookupCons<Label, FrameIndex, FrameLabel, Tail>
 where
-    Frames: SelectFieldFromLabels<Self, Label>,
+    Frames: SelectFieldByFrameDetails<FrameIndex, FrameLabel>,
     Tail: FieldList<LabelList, Frames>,
 {
     type Output = Cons<
-        <Frames as SelectFieldFromLabels<
-            FrameLookupCons<Label, FrameIndex, FrameLabel, Tail>,
-            Label,
-        >>::Output,
+        <Frames as SelectFieldByFrameDetails<FrameIndex, FrameLabel>>::Output,
         <Tail as FieldList<LabelList, Frames>>::Output,
     >;
 
     fn field_list_pred(frames: &Frames) -> Self::Output {
         Cons {
-            head: SelectFieldFromLabels::<Self, Label>::select_field(frames),
+            head: SelectFieldByFrameDetails::<FrameIndex, FrameLabel>::select_field(frames),
             tail: Tail::field_list(frames),
         }
     }
@@ -857,6 +1635,7 @@ where
 }
 
 /// A struct representing a single record across the fields in the field list `Fields`.
+#[deprecated(note = "records are now compared through `erased::KeyList`")]
 #[derive(Debug, Clone)]
 pub struct Record<'a, Fields> {
     // a field cons-list (returned from FieldList trait method)
@@ -864,8 +1643,11 @@ pub struct Record<'a, Fields> {
     idx: usize,
 }
 
+#[allow(deprecated)]
 impl<'a, Fields> Record<'a, Fields> {
-    fn new(field_list: &'a Fields, idx: usize) -> Record<'a, Fields> {
+    /// Creates a record of the values at index `idx` of the fields in `field_list` (as returned
+    /// by [field_list](struct.DataView.html#method.field_list)).
+    pub fn new(field_list: &'a Fields, idx: usize) -> Record<'a, Fields> {
         Record {
             fields: field_list,
             idx,
@@ -874,6 +1656,7 @@ impl<'a, Fields> Record<'a, Fields> {
 }
 
 /// Trait for computing the hash of a single index (record) within a list of data fields.
+#[deprecated(note = "use `erased::KeyList`, which `Unique` and `Aggregate` now require instead")]
 pub trait HashIndex {
     /// Compute the hash of the values within this list of data fields with the index `idx`,
     /// updating the hash state.
@@ -882,6 +1665,7 @@ pub trait HashIndex {
         H: Hasher;
 }
 
+#[allow(deprecated)]
 impl<T, DI> HashIndex for Framed<T, DI>
 where
     for<'a> Value<&'a T>: Hash,
@@ -895,6 +1679,7 @@ where
     }
 }
 
+#[allow(deprecated)]
 impl HashIndex for Nil {
     fn hash_index<H>(&self, _idx: usize, _state: &mut H)
     where
@@ -903,6 +1688,7 @@ impl HashIndex for Nil {
     }
 }
 
+#[allow(deprecated)]
 impl<Head, Tail> HashIndex for Cons<Head, Tail>
 where
     Head: HashIndex,
@@ -917,6 +1703,7 @@ where
     }
 }
 
+#[allow(deprecated)]
 impl<'a, Fields> Hash for Record<'a, Fields>
 where
     Fields: HashIndex,
@@ -930,11 +1717,13 @@ where
 }
 
 /// Trait for computing equality of a single index (record) within a list of data fields.
+#[deprecated(note = "use `erased::KeyList`, which `Unique` and `Aggregate` now require instead")]
 pub trait PartialEqIndex {
     /// Returns equality of the values within this list of data fields with the index `idx`.
     fn eq_index(&self, other: &Self, idx: usize) -> bool;
 }
 
+#[allow(deprecated)]
 impl<T, DI> PartialEqIndex for Framed<T, DI>
 where
     for<'a> Value<&'a T>: PartialEq,
@@ -947,12 +1736,14 @@ where
     }
 }
 
+#[allow(deprecated)]
 impl PartialEqIndex for Nil {
     fn eq_index(&self, _other: &Nil, _idx: usize) -> bool {
         true
     }
 }
 
+#[allow(deprecated)]
 impl<Head, Tail> PartialEqIndex for Cons<Head, Tail>
 where
     Head: PartialEqIndex,
@@ -963,6 +1754,7 @@ where
     }
 }
 
+#[allow(deprecated)]
 impl<'a, Fields> PartialEq for Record<'a, Fields>
 where
     Fields: PartialEqIndex,
@@ -972,14 +1764,17 @@ where
     }
 }
 
+#[allow(deprecated)]
 impl<'a, Fields> Eq for Record<'a, Fields> where Self: PartialEq {}
 
+#[allow(deprecated)]
 impl<'a> Display for Record<'a, Nil> {
     fn fmt(&self, _f: &mut Formatter) -> Result<(), fmt::Error> {
         Ok(())
     }
 }
 
+#[allow(deprecated)]
 impl<'a, Head, Tail> Display for Record<'a, Cons<Head, Tail>>
 where
     Head: DataIndex,
@@ -1052,11 +1847,12 @@ pub trait Unique<LabelList> {
 
 impl<Labels, Frames, LabelList> Unique<LabelList> for DataView<Labels, Frames>
 where
+    LabelList: 'static,
     Labels: FieldList<LabelList, Frames>
         + HasLabels<LabelList>
         + LabelSubset<LabelList>
         + FrameIndexList,
-    <Labels as FieldList<LabelList, Frames>>::Output: HashIndex + PartialEqIndex,
+    <Labels as FieldList<LabelList, Frames>>::Output: KeyList,
     <Labels as LabelSubset<LabelList>>::Output: Reorder<LabelList>,
     Frames: NRows + SubsetClone<<Labels as FrameIndexList>::LabelList>,
     <Frames as SubsetClone<<Labels as FrameIndexList>::LabelList>>::Output: UpdatePermutation,
@@ -1067,25 +1863,23 @@ where
     >;
 
     fn unique_indices(&self) -> Vec<usize> {
-        let fl = self.field_list::<LabelList>();
-        let mut indices = vec![];
-        let mut set = HashSet::new();
-        for i in 0..self.nrows() {
-            let record = Record::new(&fl, i);
-            if !set.contains(&record) {
-                set.insert(record);
-                indices.push(i);
-            }
+        if self.invariants.is_unique::<LabelList>() {
+            return (0..self.nrows()).collect();
         }
-        indices
+        let fl = self.field_list::<LabelList>();
+        erased::group_records(&fl.keys(), self.nrows()).first_indices
     }
 
     fn unique_values(&self) -> Self::Output {
         let indices = self.unique_indices::<LabelList>();
         let new_frames = self.frames.subset_clone().update_permutation(&indices);
+        // exemplar records are in their original order, so sortedness is preserved
+        let mut invariants = self.invariants.clone();
+        invariants.add_unique::<LabelList>();
         DataView {
             _labels: PhantomData,
             frames: new_frames,
+            invariants,
         }
     }
 }
@@ -1128,8 +1922,8 @@ impl<Frames> SerializeViewField<Frames> for Nil {
 impl<Frames, Label, FrameIndex, FrameLabel, Tail> SerializeViewField<Frames>
     for FrameLookupCons<Label, FrameIndex, FrameLabel, Tail>
 where
-    Frames: SelectFieldFromLabels<Self, Label>,
-    <Frames as SelectFieldFromLabels<Self, Label>>::Output: Serialize,
+    Frames: SelectFieldByFrameDetails<FrameIndex, FrameLabel>,
+    <Frames as SelectFieldByFrameDetails<FrameIndex, FrameLabel>>::Output: Serialize,
     Label: LabelName,
     Tail: SerializeViewField<Frames>,
 {
@@ -1139,12 +1933,125 @@ where
     {
         map.serialize_entry(
             Label::name(),
-            &SelectFieldFromLabels::<Self, Label>::select_field(frames),
+            &SelectFieldByFrameDetails::<FrameIndex, FrameLabel>::select_field(frames),
         )?;
         Tail::serialize_view_field(frames, map)
     }
 }
 
+/// Trait for serializing the value of a single record (row) of a single field in a view. Used for
+/// serializing a [DataView](struct.DataView.html) in row-oriented form.
+#[cfg(feature = "serialize")]
+pub trait SerializeViewRecord<Frames> {
+    /// Serialize the value of record `idx` of this single field using data from `frames`, and
+    /// adding to map `SerializeMap`.
+    fn serialize_view_record<M>(frames: &Frames, idx: usize, map: M) -> Result<M::Ok, M::Error>
+    where
+        M: SerializeMap;
+}
+
+#[cfg(feature = "serialize")]
+impl<Frames> SerializeViewRecord<Frames> for Nil {
+    fn serialize_view_record<M>(_frames: &Frames, _idx: usize, map: M) -> Result<M::Ok, M::Error>
+    where
+        M: SerializeMap,
+    {
+        map.end()
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<Frames, Label, FrameIndex, FrameLabel, Tail> SerializeViewRecord<Frames>
+    for FrameLookupCons<Label, FrameIndex, FrameLabel, Tail>
+where
+    Frames: SelectFieldByFrameDetails<FrameIndex, FrameLabel>,
+    <Frames as SelectFieldByFrameDetails<FrameIndex, FrameLabel>>::DType: Serialize,
+    Label: LabelName,
+    Tail: SerializeViewRecord<Frames>,
+{
+    fn serialize_view_record<M>(frames: &Frames, idx: usize, mut map: M) -> Result<M::Ok, M::Error>
+    where
+        M: SerializeMap,
+    {
+        map.serialize_entry(
+            Label::name(),
+            &SelectFieldByFrameDetails::<FrameIndex, FrameLabel>::select_field(frames)
+                .get_datum(idx)
+                .map_err(ser::Error::custom)?,
+        )?;
+        Tail::serialize_view_record(frames, idx, map)
+    }
+}
+
+/// A single record (row) of a [DataView](struct.DataView.html), which serializes as a map from
+/// field labels to values.
+#[cfg(feature = "serialize")]
+#[derive(Debug)]
+pub(crate) struct ViewRecord<'a, Labels: 'a, Frames: 'a> {
+    pub(crate) view: &'a DataView<Labels, Frames>,
+    pub(crate) idx: usize,
+}
+
+#[cfg(feature = "serialize")]
+impl<'a, Labels, Frames> Serialize for ViewRecord<'a, Labels, Frames>
+where
+    Labels: Len + SerializeViewRecord<Frames>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let map = serializer.serialize_map(Some(self.view.nfields()))?;
+        Labels::serialize_view_record(&self.view.frames, self.idx, map)
+    }
+}
+
+/// Wrapper around a [DataView](struct.DataView.html) which serializes the view in row-oriented
+/// form: as a sequence of records, each of which is a map from field labels to values. Returned by
+/// [serialize_rows](struct.DataView.html#method.serialize_rows).
+#[cfg(feature = "serialize")]
+#[derive(Debug)]
+pub struct SerializeRows<'a, Labels: 'a, Frames: 'a> {
+    view: &'a DataView<Labels, Frames>,
+}
+
+#[cfg(feature = "serialize")]
+impl<'a, Labels, Frames> Serialize for SerializeRows<'a, Labels, Frames>
+where
+    Frames: Len + NRows,
+    Labels: Len + SerializeViewRecord<Frames>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let nrows = if Frames::is_empty() {
+            0
+        } else {
+            self.view.nrows()
+        };
+        let mut seq = serializer.serialize_seq(Some(nrows))?;
+        for idx in 0..nrows {
+            seq.serialize_element(&ViewRecord {
+                view: self.view,
+                idx,
+            })?;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Returns a wrapper around this `DataView` that serializes in row-oriented form (as a sequence
+    /// of records, each a map from field labels to values), instead of the column-oriented form
+    /// (a map from field labels to sequences of values) used by this `DataView`'s `Serialize`
+    /// implementation.
+    pub fn serialize_rows(&self) -> SerializeRows<'_, Labels, Frames> {
+        SerializeRows { view: self }
+    }
+}
+
 impl<Labels, Frames> DataView<Labels, Frames> {
     /// Creates a new a `DataView` that accesses source data in a different way, viewing the data
     /// as a series of identifier / value pairs instead of a having values in multiple
@@ -1275,6 +2182,9 @@ impl<Labels, Frames> DataView<Labels, Frames> {
     ///  2     | Year2013   | 1020
     ///  2     | Year2014   | 1100
     /// ```
+    ///
+    /// To combine the fields of several views into a single long-format view, see
+    /// [Stack](../stack/struct.Stack.html).
     pub fn melt<MeltLabels, NameLabel, ValueLabel, HoldLabels>(
         &self,
     ) -> <Self as Melt<MeltLabels, NameLabel, ValueLabel, HoldLabels>>::Output
@@ -1376,79 +2286,500 @@ where
 }
 
 impl<Labels, Frames> DataView<Labels, Frames> {
-    /// Creates a new `DataView` that aggregates values in the `ValueLabel` field, grouping by
-    /// records in the `KeyLabels` set of fields, and storing the result in a new field with
-    /// label `AggLabel`. The resulting `DataView` will contain the `KeyLabels` fields and the
-    /// newly constructed `AggLabel` field.
-    ///
-    /// For each unique set of key values in `KeyLabels`, this method will find all the records
-    /// in the `DataView` which match, initialize an accumulator value with the argument `init`,
-    /// and call `AggFunc` for each of the values in the `ValueLabel` field. `AggFunc` takes a
-    /// mutable `AggType` value which it updates with the
-    /// [Value](../field/enum.Value.html)s of type `DType` from the `ValueLabel` field.
+    /// Creates a new `DataView` with an additional `u64` field labeled `IdxLabel` containing the
+    /// position (starting at 0) of each record in the current ordering of this `DataView`.
     ///
-    /// # Example
-    /// Let's start with the data table which contains three fields: an employee ID `EmpId`, an
-    /// annual salary `Salary`, and a text field denoting which year this salary took place:
-    /// `SalaryYear`. This table (which is the final result of the example for the
-    /// [melt](struct.DataView.html#method.melt) documentation) can be represented with the
-    /// tablespace:
-    /// ```
-    /// # #[macro_use] extern crate agnes;
-    /// tablespace![
-    ///     table salary {
-    ///         EmpId: u64,
-    ///         SalaryYear: String,
-    ///         Salary: f64,
-    ///     }
-    /// ];
-    /// ```
-    /// and data:
-    /// ```text
-    ///  EmpId | SalaryYear | Salary
-    /// -------+------------+--------
-    ///  0     | Year2010   | 1500
-    ///  0     | Year2011   | 1600
-    ///  0     | Year2012   | 1700
-    ///  0     | Year2013   | 1850
-    ///  0     | Year2014   | 2000
-    ///  1     | Year2010   | 900
-    ///  1     | Year2011   | 920
-    ///  1     | Year2012   | 940
-    ///  1     | Year2013   | 940
-    ///  1     | Year2014   | 970
-    ///  2     | Year2010   | 600
-    ///  2     | Year2011   | 800
-    ///  2     | Year2012   | 900
-    ///  2     | Year2013   | 1020
-    ///  2     | Year2014   | 1100
-    /// ```
-    /// For this example, let's compute the total yearly salary being payed out to all employees.
-    /// Thus, we want to aggregate over each value in `SalaryYear`, and compute the sum of `Salary`.
-    /// Therefore, our `KeyLabels` (our groups) would be `Labels![SalaryYear]` (since we can have
-    /// more than one labels as our key, we need to use the label list-making macro
-    /// [Labels](../macro.Labels.html)). Our `ValueLabel` (the value being summed) is `Salary`, and
-    /// `AggLabel` will be a new label we need to add to our tablespace, which we'll call
-    /// `TotalYearlySalary`.
+    /// This materializes the permutation state of this `DataView` (from any sorting or filtering)
+    /// as an explicit field, which can later be used to restore the captured ordering (using
+    /// [sort_by_label](struct.DataView.html#method.sort_by_label)), or as a key field for
+    /// [join](struct.DataView.html#method.join) operations. Designating the new field as the
+    /// view's logical index (with [set_index](struct.DataView.html#method.set_index)) allows
+    /// [index joins and as-of lookups](../indexed/index.html) against the captured ordering.
+    pub fn with_row_index<IdxLabel>(&self) -> <Self as WithRowIndex<IdxLabel>>::Output
+    where
+        Self: WithRowIndex<IdxLabel>,
+    {
+        WithRowIndex::<IdxLabel>::with_row_index(self)
+    }
+}
+
+/// Trait providing the `with_row_index` method for adding a row index field to a `DataView`. See
+/// the intrinsic method [with_row_index](struct.DataView.html#method.with_row_index) for more
+/// details.
+pub trait WithRowIndex<IdxLabel> {
+    /// Type produced by this method.
+    type Output;
+
+    /// Add the row index field. See the intrinsic method
+    /// [with_row_index](struct.DataView.html#method.with_row_index) for more details.
+    fn with_row_index(&self) -> Self::Output;
+}
+
+impl<Labels, Frames, IdxLabel> WithRowIndex<IdxLabel> for DataView<Labels, Frames>
+where
+    Frames: NRows,
+    Self: AddFrame<AsFrame<<FieldData<u64> as IntoStore<IdxLabel>>::Output>>,
+    FieldData<u64>: IntoStore<IdxLabel>,
+    <FieldData<u64> as IntoStore<IdxLabel>>::Output: IntoFrame,
+{
+    type Output = WithFrame<Self, AsFrame<<FieldData<u64> as IntoStore<IdxLabel>>::Output>>;
+
+    fn with_row_index(&self) -> Self::Output {
+        let index = FieldData::from_vec((0..self.nrows() as u64).collect::<Vec<_>>());
+        self.add_frame(IntoStore::<IdxLabel>::into_store(index).into_frame())
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Creates a new `DataView` holding a copy of only the records and fields visible in this
+    /// `DataView`, in a newly-allocated store with no excess capacity.
     ///
-    /// ```
-    /// # #[macro_use] extern crate agnes;
-    /// tablespace![
-    ///     table salary {
-    ///         EmpId: u64,
-    ///         SalaryYear: String,
-    ///         Salary: f64,
-    ///         TotalYearlySalary: f64,
-    ///     }
-    /// ];
-    /// #
-    /// # use salary::*;
-    /// #
-    /// fn main() {
-    /// #     let salary_table = table![
-    /// #         EmpId = [0u64, 0, 0, 0, 0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2];
-    /// #         SalaryYear = [
-    /// #             "Year2010", "Year2011", "Year2012", "Year2013", "Year2014",
+    /// Views created by filtering, sorting, or taking subviews share the stores of the original
+    /// data, which keep every record and field of the original data alive (and in memory) for as
+    /// long as any view of them exists. Compacting a view after discarding most of its data, and
+    /// dropping the original view, allows the memory of the discarded data to be released (see
+    /// [memory_usage](#method.memory_usage)). The compacted view has the same fields, records, and
+    /// record order as this `DataView`.
+    pub fn compact(&self) -> <Self as Compact>::Output
+    where
+        Self: Compact,
+    {
+        Compact::compact(self)
+    }
+
+    /// Copies the records and fields visible in this `DataView` into a single new contiguous
+    /// `DataStore`, in the current record order. Multiple frames (e.g. from merges or joins) are
+    /// collapsed into one store, and any sorting or filtering is applied to the copied data, so
+    /// that accessing the store's fields requires no permutation lookups. This can be useful before
+    /// serializing a view, or before repeatedly accessing the values of a heavily-permuted view.
+    pub fn to_store(&self) -> DataStore<ViewStoreFields<Labels, Frames>>
+    where
+        Frames: AssocDataIndexCons<Labels>,
+        AssocDataIndexConsOf<Labels, Frames>: CompactFields,
+    {
+        self.frames.assoc_data().compact_fields()
+    }
+
+    /// Copies the visible data of this `DataView` into a new `DataStore` as in
+    /// [to_store](#method.to_store), consuming this `DataView` (and releasing its references to
+    /// the original data).
+    pub fn into_store(self) -> DataStore<ViewStoreFields<Labels, Frames>>
+    where
+        Frames: AssocDataIndexCons<Labels>,
+        AssocDataIndexConsOf<Labels, Frames>: CompactFields,
+    {
+        self.to_store()
+    }
+}
+
+/// Type alias for the `Fields` type parameter of the `DataStore` created from a
+/// `DataView<Labels, Frames>` by [to_store](struct.DataView.html#method.to_store).
+pub type ViewStoreFields<Labels, Frames> =
+    <AssocDataIndexConsOf<Labels, Frames> as CompactFields>::OutputFields;
+
+/// Trait providing the `compact` method for copying the visible data of a `DataView` into a new
+/// store. See the intrinsic method [compact](struct.DataView.html#method.compact) for more
+/// details.
+pub trait Compact {
+    /// Type produced by this compact method.
+    type Output;
+
+    /// Perform the compaction. See the intrinsic method
+    /// [compact](struct.DataView.html#method.compact) for more details.
+    fn compact(&self) -> Self::Output;
+}
+
+impl<Labels, Frames, OutLabels, OutFrames> Compact for DataView<Labels, Frames>
+where
+    Frames: AssocDataIndexCons<Labels>,
+    AssocDataIndexConsOf<Labels, Frames>: CompactFields,
+    DataStore<ViewStoreFields<Labels, Frames>>: IntoView<Output = DataView<OutLabels, OutFrames>>,
+{
+    type Output = DataView<OutLabels, OutFrames>;
+
+    fn compact(&self) -> Self::Output {
+        let mut compacted = self.to_store().into_view();
+        // the compacted view holds the same records in the same order
+        compacted.invariants = self.invariants.clone();
+        compacted
+    }
+}
+
+/// Trait for copying the values of a [DataIndexCons](type.DataIndexCons.html) cons-list of fields
+/// into a new `DataStore` (used by [to_store](struct.DataView.html#method.to_store) and
+/// [compact](struct.DataView.html#method.compact)).
+pub trait CompactFields {
+    /// [FieldCons](../fieldlist/type.FieldCons.html) cons-list of the new store's fields.
+    type OutputFields: AssocStorage;
+
+    /// Builds a `DataStore` holding a copy of the values of each field in this cons-list.
+    fn compact_fields(&self) -> DataStore<Self::OutputFields>;
+}
+
+impl CompactFields for Nil {
+    type OutputFields = Nil;
+
+    fn compact_fields(&self) -> DataStore<Nil> {
+        DataStore::<Nil>::empty()
+    }
+}
+
+impl<Label, DType, DI, Tail> CompactFields for DataIndexCons<Label, DType, DI, Tail>
+where
+    Tail: CompactFields,
+    DI: DataIndex<DType = DType> + SelfValued,
+    DType: Debug + Default + Clone + 'static,
+    DataStore<Tail::OutputFields>: PushFrontField<Label, DType>,
+{
+    type OutputFields =
+        <DataStore<Tail::OutputFields> as PushFrontField<Label, DType>>::OutputFields;
+
+    fn compact_fields(&self) -> DataStore<Self::OutputFields> {
+        let mut data: FieldData<DType> = self.head.value_ref().iter().collect();
+        // copied `ArenaStr` values still refer to (and keep alive) the original text buffer
+        if let Some(text) = (&mut data as &mut dyn Any).downcast_mut::<FieldData<ArenaStr>>() {
+            *text = text.repack();
+        }
+        data.shrink_to_fit();
+        self.tail.compact_fields().push_front_field(data)
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Splits the text values of the field labeled `Label` on `delimiter`, and duplicates each
+    /// record once for each resulting token. The tokens are stored in a new field labeled
+    /// `TokenLabel` (added after the existing fields, which are all retained). Records with a
+    /// missing (NA) value produce a single record with a missing token, and records with an empty
+    /// value produce a single record with an empty token.
+    ///
+    /// The duplicated records share the data of this `DataView`; only the new token field is
+    /// newly allocated.
+    pub fn explode<Label, TokenLabel>(
+        &self,
+        delimiter: &str,
+    ) -> <Self as Explode<Label, TokenLabel>>::Output
+    where
+        Self: Explode<Label, TokenLabel>,
+    {
+        Explode::<Label, TokenLabel>::explode(self, delimiter)
+    }
+}
+
+/// Trait providing the `explode` method for splitting a delimited text field into multiple
+/// records. See the intrinsic method [explode](struct.DataView.html#method.explode) for more
+/// details.
+pub trait Explode<Label, TokenLabel> {
+    /// Type produced by this explode method.
+    type Output;
+
+    /// Perform the 'explode' operation. See the intrinsic method
+    /// [explode](struct.DataView.html#method.explode) for more details.
+    fn explode(&self, delimiter: &str) -> Self::Output;
+}
+
+impl<Labels, Frames, Label, TokenLabel> Explode<Label, TokenLabel> for DataView<Labels, Frames>
+where
+    Self: SelectFieldByLabel<Label>
+        + AddFrame<AsFrame<<FieldData<String> as IntoStore<TokenLabel>>::Output>>,
+    VFieldTypeOf<Self, Label>: AsRef<str>,
+    Frames: Clone + UpdatePermutation,
+    FieldData<String>: IntoStore<TokenLabel>,
+    <FieldData<String> as IntoStore<TokenLabel>>::Output: IntoFrame,
+{
+    type Output = WithFrame<Self, AsFrame<<FieldData<String> as IntoStore<TokenLabel>>::Output>>;
+
+    fn explode(&self, delimiter: &str) -> Self::Output {
+        let field = self.field::<Label>();
+        // index permutation repeating each record once per token
+        let mut permutation = Vec::with_capacity(field.len());
+        let mut tokens = Vec::with_capacity(field.len());
+        for (idx, value) in field.iter().enumerate() {
+            match value {
+                Value::Exists(text) => {
+                    for token in text.as_ref().split(delimiter) {
+                        permutation.push(idx);
+                        tokens.push(Value::Exists(token.to_string()));
+                    }
+                }
+                Value::Na => {
+                    permutation.push(idx);
+                    tokens.push(Value::Na);
+                }
+            }
+        }
+        let token_frame =
+            IntoStore::<TokenLabel>::into_store(FieldData::from_field_vec(tokens)).into_frame();
+
+        DataView {
+            _labels: PhantomData,
+            frames: self.frames.clone().update_permutation(&permutation),
+            invariants: Invariants::default(),
+        }
+        .add_frame(token_frame)
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Filters this `DataView` to the records whose text values in the field labeled `Label`
+    /// match the regular expression `pattern` (which is compiled once). The pattern may match
+    /// anywhere within a value; use `^` and `$` to match entire values. Records with missing (NA)
+    /// values are removed. Consumes this `DataView` as in [filter](#method.filter).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Regex` if `pattern` is not a valid regular expression.
+    pub fn filter_regex<Label>(mut self, pattern: &str) -> error::Result<Self>
+    where
+        Self: SelectFieldByLabel<Label>,
+        Frames: UpdatePermutation,
+        VFieldTypeOf<Self, Label>: AsRef<str>,
+    {
+        let regex = Regex::new(pattern)?;
+        let perm = self.field::<Label>().filter_perm(|value| match value {
+            Value::Exists(text) => regex.is_match(text.as_ref()),
+            Value::Na => false,
+        });
+        self.frames = self.frames.update_permutation(&perm);
+        Ok(self)
+    }
+
+    /// Extracts the capture groups of the regular expression `pattern` from the text values of the
+    /// field labeled `Label` into new text fields labeled `CaptureLabels` (one label for each of
+    /// the first capture groups of `pattern`, in order), added after the existing fields. A
+    /// captured value is missing (NA) if the value does not match `pattern`, if the capture group
+    /// does not participate in the match, or if the original value is missing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[macro_use] extern crate agnes;
+    /// use agnes::access::DataIndex;
+    /// use agnes::cons::Nil;
+    /// use agnes::field::FieldData;
+    /// use agnes::select::FieldSelect;
+    /// use agnes::store::{DataStore, PushBackField};
+    /// use agnes::value::Value;
+    ///
+    /// tablespace![
+    ///     table log {
+    ///         Line: String,
+    ///         Level: String,
+    ///         Message: String,
+    ///     }
+    /// ];
+    ///
+    /// fn main() {
+    ///     let dv = DataStore::<Nil>::empty()
+    ///         .push_back_field::<log::Line, _>(FieldData::<String>::from_vec(vec![
+    ///             "[WARN] disk almost full".to_string(),
+    ///             "starting up".to_string(),
+    ///             "[ERROR] disk full".to_string(),
+    ///         ]))
+    ///         .into_view();
+    ///
+    ///     let errors = dv.clone().filter_regex::<log::Line>(r"^\[ERROR\]").unwrap();
+    ///     assert_eq!(errors.nrows(), 1);
+    ///
+    ///     let parsed = dv
+    ///         .extract_regex::<log::Line, Labels![log::Level, log::Message]>(r"^\[(\w+)\] (.*)$")
+    ///         .unwrap();
+    ///     assert_eq!(
+    ///         parsed.field::<log::Level>().to_value_vec(),
+    ///         vec![Value::Exists("WARN".to_string()), Value::Na, Value::Exists("ERROR".to_string())]
+    ///     );
+    ///     assert_eq!(
+    ///         parsed.field::<log::Message>().to_value_vec()[2],
+    ///         Value::Exists("disk full".to_string())
+    ///     );
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Regex` if `pattern` is not a valid regular expression, or with
+    /// `AgnesError::DimensionMismatch` if `pattern` has fewer capture groups than there are
+    /// `CaptureLabels`.
+    pub fn extract_regex<Label, CaptureLabels>(
+        &self,
+        pattern: &str,
+    ) -> error::Result<<Self as ExtractRegex<Label, CaptureLabels>>::Output>
+    where
+        Self: ExtractRegex<Label, CaptureLabels>,
+    {
+        ExtractRegex::<Label, CaptureLabels>::extract_regex(self, pattern)
+    }
+}
+
+/// Trait implemented by label lists of the fields which hold the capture groups extracted by
+/// [extract_regex](struct.DataView.html#method.extract_regex).
+pub trait CaptureList {
+    /// [FieldCons](../fieldlist/type.FieldCons.html) cons-list of the capture group fields.
+    type OutputFields: AssocStorage;
+
+    /// Builds a `DataStore` of the capture group fields, taking the values of each field (in label
+    /// order) from `captures`.
+    fn capture_store(
+        captures: &mut ::std::vec::IntoIter<Vec<Value<String>>>,
+    ) -> DataStore<Self::OutputFields>;
+}
+
+impl CaptureList for Nil {
+    type OutputFields = Nil;
+
+    fn capture_store(
+        _captures: &mut ::std::vec::IntoIter<Vec<Value<String>>>,
+    ) -> DataStore<Self::OutputFields> {
+        DataStore::<Nil>::empty()
+    }
+}
+
+impl<Label, Tail> CaptureList for LCons<Label, Tail>
+where
+    Tail: CaptureList,
+    DataStore<Tail::OutputFields>: PushFrontField<Label, String>,
+{
+    type OutputFields =
+        <DataStore<Tail::OutputFields> as PushFrontField<Label, String>>::OutputFields;
+
+    fn capture_store(
+        captures: &mut ::std::vec::IntoIter<Vec<Value<String>>>,
+    ) -> DataStore<Self::OutputFields> {
+        // there is one vector of captured values for each label
+        let values = captures.next().unwrap();
+        Tail::capture_store(captures).push_front_field(FieldData::from_field_vec(values))
+    }
+}
+
+/// Trait providing the `extract_regex` method for extracting the capture groups of a regular
+/// expression into new fields. See the intrinsic method
+/// [extract_regex](struct.DataView.html#method.extract_regex) for more details.
+pub trait ExtractRegex<Label, CaptureLabels> {
+    /// Type produced by this extract method.
+    type Output;
+
+    /// Extract the capture groups of `pattern`. See the intrinsic method
+    /// [extract_regex](struct.DataView.html#method.extract_regex) for more details.
+    fn extract_regex(&self, pattern: &str) -> error::Result<Self::Output>;
+}
+
+impl<Labels, Frames, Label, CaptureLabels> ExtractRegex<Label, CaptureLabels>
+    for DataView<Labels, Frames>
+where
+    Self: SelectFieldByLabel<Label>
+        + AddFrame<AsFrame<DataStore<<CaptureLabels as CaptureList>::OutputFields>>>,
+    VFieldTypeOf<Self, Label>: AsRef<str>,
+    CaptureLabels: CaptureList + Len,
+    DataStore<<CaptureLabels as CaptureList>::OutputFields>: IntoFrame,
+{
+    type Output = WithFrame<Self, AsFrame<DataStore<<CaptureLabels as CaptureList>::OutputFields>>>;
+
+    fn extract_regex(&self, pattern: &str) -> error::Result<Self::Output> {
+        let regex = Regex::new(pattern)?;
+        let ncaptures = CaptureLabels::len();
+        // the first capture group is the whole match
+        if regex.captures_len() - 1 < ncaptures {
+            return Err(error::AgnesError::DimensionMismatch(format!(
+                "pattern '{}' has {} capture groups, but {} capture fields were provided",
+                pattern,
+                regex.captures_len() - 1,
+                ncaptures
+            )));
+        }
+        let field = self.field::<Label>();
+        let mut captured = vec![Vec::with_capacity(field.len()); ncaptures];
+        for value in field.iter() {
+            let captures = match value {
+                Value::Exists(text) => regex.captures(text.as_ref()),
+                Value::Na => None,
+            };
+            for (i, values) in captured.iter_mut().enumerate() {
+                values.push(
+                    captures
+                        .as_ref()
+                        .and_then(|captures| captures.get(i + 1))
+                        .map_or(Value::Na, |capture| {
+                            Value::Exists(capture.as_str().to_string())
+                        }),
+                );
+            }
+        }
+        let capture_store = CaptureLabels::capture_store(&mut captured.into_iter());
+        Ok(self.add_frame(capture_store.into_frame()))
+    }
+}
+
+impl<Labels, Frames> DataView<Labels, Frames> {
+    /// Creates a new `DataView` that aggregates values in the `ValueLabel` field, grouping by
+    /// records in the `KeyLabels` set of fields, and storing the result in a new field with
+    /// label `AggLabel`. The resulting `DataView` will contain the `KeyLabels` fields and the
+    /// newly constructed `AggLabel` field.
+    ///
+    /// For each unique set of key values in `KeyLabels`, this method will find all the records
+    /// in the `DataView` which match, initialize an accumulator value with the argument `init`,
+    /// and call `AggFunc` for each of the values in the `ValueLabel` field. `AggFunc` takes a
+    /// mutable `AggType` value which it updates with the
+    /// [Value](../field/enum.Value.html)s of type `DType` from the `ValueLabel` field.
+    ///
+    /// # Example
+    /// Let's start with the data table which contains three fields: an employee ID `EmpId`, an
+    /// annual salary `Salary`, and a text field denoting which year this salary took place:
+    /// `SalaryYear`. This table (which is the final result of the example for the
+    /// [melt](struct.DataView.html#method.melt) documentation) can be represented with the
+    /// tablespace:
+    /// ```
+    /// # #[macro_use] extern crate agnes;
+    /// tablespace![
+    ///     table salary {
+    ///         EmpId: u64,
+    ///         SalaryYear: String,
+    ///         Salary: f64,
+    ///     }
+    /// ];
+    /// ```
+    /// and data:
+    /// ```text
+    ///  EmpId | SalaryYear | Salary
+    /// -------+------------+--------
+    ///  0     | Year2010   | 1500
+    ///  0     | Year2011   | 1600
+    ///  0     | Year2012   | 1700
+    ///  0     | Year2013   | 1850
+    ///  0     | Year2014   | 2000
+    ///  1     | Year2010   | 900
+    ///  1     | Year2011   | 920
+    ///  1     | Year2012   | 940
+    ///  1     | Year2013   | 940
+    ///  1     | Year2014   | 970
+    ///  2     | Year2010   | 600
+    ///  2     | Year2011   | 800
+    ///  2     | Year2012   | 900
+    ///  2     | Year2013   | 1020
+    ///  2     | Year2014   | 1100
+    /// ```
+    /// For this example, let's compute the total yearly salary being payed out to all employees.
+    /// Thus, we want to aggregate over each value in `SalaryYear`, and compute the sum of `Salary`.
+    /// Therefore, our `KeyLabels` (our groups) would be `Labels![SalaryYear]` (since we can have
+    /// more than one labels as our key, we need to use the label list-making macro
+    /// [Labels](../macro.Labels.html)). Our `ValueLabel` (the value being summed) is `Salary`, and
+    /// `AggLabel` will be a new label we need to add to our tablespace, which we'll call
+    /// `TotalYearlySalary`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate agnes;
+    /// tablespace![
+    ///     table salary {
+    ///         EmpId: u64,
+    ///         SalaryYear: String,
+    ///         Salary: f64,
+    ///         TotalYearlySalary: f64,
+    ///     }
+    /// ];
+    /// #
+    /// # use salary::*;
+    /// #
+    /// fn main() {
+    /// #     let salary_table = table![
+    /// #         EmpId = [0u64, 0, 0, 0, 0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2];
+    /// #         SalaryYear = [
+    /// #             "Year2010", "Year2011", "Year2012", "Year2013", "Year2014",
     /// #             "Year2010", "Year2011", "Year2012", "Year2013", "Year2014",
     /// #             "Year2010", "Year2011", "Year2012", "Year2013", "Year2014"
     /// #         ];
@@ -1495,6 +2826,16 @@ impl<Labels, Frames> DataView<Labels, Frames> {
     ///  Year2013   | 3810
     ///  Year2014   | 4070
     /// ```
+    ///
+    /// Common aggregations are also available as one-line shorthands: this example is equivalent
+    /// to `salary_table.sum_by::<Labels![SalaryYear], Salary, TotalYearlySalary>()`. See
+    /// [sum_by](#method.sum_by), [mean_by](#method.mean_by), [count_by](#method.count_by),
+    /// [min_by](#method.min_by), and [max_by](#method.max_by). To compute several aggregations over
+    /// the same grouping in a single pass, see [aggregate_many](#method.aggregate_many).
+    ///
+    /// Groups appear in the output in order of first appearance in this `DataView`. To sort the
+    /// groups by key or by aggregated value instead, see
+    /// [aggregate_ordered](#method.aggregate_ordered).
     pub fn aggregate<KeyLabels, ValueLabel, AggLabel, DType, AggType, AggFunc>(
         &self,
         init: AggType,
@@ -1508,6 +2849,409 @@ impl<Labels, Frames> DataView<Labels, Frames> {
             self, init, f,
         )
     }
+
+    /// Aggregate values over a specified grouping of records, reporting the number of records
+    /// accumulated to `observer` (with stage [Stage::Aggregate](../observer/enum.Stage.html)). See
+    /// [aggregate](#method.aggregate).
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation.
+    pub fn aggregate_observed<KeyLabels, ValueLabel, AggLabel, DType, AggType, AggFunc, O>(
+        &self,
+        init: AggType,
+        f: AggFunc,
+        observer: &mut O,
+    ) -> error::Result<<Self as Aggregate<KeyLabels, ValueLabel, AggLabel, DType, AggType>>::Output>
+    where
+        Self: Aggregate<KeyLabels, ValueLabel, AggLabel, DType, AggType>,
+        AggFunc: Fn(&mut AggType, Value<&DType>),
+        O: Observer,
+    {
+        Aggregate::<KeyLabels, ValueLabel, AggLabel, DType, AggType>::aggregate_observed::<AggFunc, O>(
+            self, init, f, observer,
+        )
+    }
+
+    /// Computes the sum of the values of the field labeled `ValueLabel` for each group of records
+    /// with the same values in the fields labeled `KeyLabels`, storing the sums in a new field
+    /// labeled `AggLabel`. Missing (NA) values are treated as `0`, so a group without any existing
+    /// values has a sum of `0`. See [aggregate](#method.aggregate).
+    pub fn sum_by<KeyLabels, ValueLabel, AggLabel>(
+        &self,
+    ) -> AggregateOutputOf<
+        Self,
+        KeyLabels,
+        ValueLabel,
+        AggLabel,
+        VFieldTypeOf<Self, ValueLabel>,
+        VFieldTypeOf<Self, ValueLabel>,
+    >
+    where
+        Self: SelectFieldByLabel<ValueLabel>
+            + AggregateWith<
+                KeyLabels,
+                ValueLabel,
+                AggLabel,
+                VFieldTypeOf<Self, ValueLabel>,
+                VFieldTypeOf<Self, ValueLabel>,
+                VFieldTypeOf<Self, ValueLabel>,
+            >,
+        VFieldTypeOf<Self, ValueLabel>: for<'a> Add<&'a VFieldTypeOf<Self, ValueLabel>, Output = VFieldTypeOf<Self, ValueLabel>>
+            + Zero
+            + Clone,
+    {
+        self.aggregate_with(
+            Zero::zero(),
+            |sum, value| {
+                if let Value::Exists(value) = value {
+                    *sum = sum.clone() + value;
+                }
+            },
+            |sums| sums.into(),
+            &mut NoObserver,
+        )
+        .unwrap()
+    }
+
+    /// Computes the arithmetic mean of the values of the field labeled `ValueLabel` for each group
+    /// of records with the same values in the fields labeled `KeyLabels`, storing the means in a
+    /// new field labeled `AggLabel`. Missing (NA) values are ignored, and the mean of a group
+    /// without any existing values is missing (NA). See [aggregate](#method.aggregate).
+    pub fn mean_by<KeyLabels, ValueLabel, AggLabel>(
+        &self,
+    ) -> AggregateOutputOf<Self, KeyLabels, ValueLabel, AggLabel, (f64, u64), f64>
+    where
+        Self: SelectFieldByLabel<ValueLabel>
+            + AggregateWith<
+                KeyLabels,
+                ValueLabel,
+                AggLabel,
+                VFieldTypeOf<Self, ValueLabel>,
+                (f64, u64),
+                f64,
+            >,
+        VFieldTypeOf<Self, ValueLabel>: AsPrimitive<f64>,
+    {
+        self.aggregate_with(
+            (0.0, 0),
+            |&mut (ref mut sum, ref mut count), value| {
+                if let Value::Exists(value) = value {
+                    *sum += value.as_();
+                    *count += 1;
+                }
+            },
+            |sums| {
+                FieldData::from_field_vec(
+                    sums.into_iter()
+                        .map(|(sum, count)| match count {
+                            0 => Value::Na,
+                            count => Value::Exists(sum / count as f64),
+                        })
+                        .collect(),
+                )
+            },
+            &mut NoObserver,
+        )
+        .unwrap()
+    }
+
+    /// Counts the number of existing (non-NA) values of the field labeled `ValueLabel` for each
+    /// group of records with the same values in the fields labeled `KeyLabels`, storing the counts
+    /// in a new field labeled `AggLabel`. See [aggregate](#method.aggregate).
+    pub fn count_by<KeyLabels, ValueLabel, AggLabel>(
+        &self,
+    ) -> AggregateOutputOf<Self, KeyLabels, ValueLabel, AggLabel, u64, u64>
+    where
+        Self: SelectFieldByLabel<ValueLabel>
+            + AggregateWith<KeyLabels, ValueLabel, AggLabel, VFieldTypeOf<Self, ValueLabel>, u64, u64>,
+    {
+        self.aggregate_with(
+            0,
+            |count, value| {
+                if value.exists() {
+                    *count += 1;
+                }
+            },
+            |counts| counts.into(),
+            &mut NoObserver,
+        )
+        .unwrap()
+    }
+
+    /// Computes the minimum of the values of the field labeled `ValueLabel` for each group of
+    /// records with the same values in the fields labeled `KeyLabels`, storing the minimums in a
+    /// new field labeled `AggLabel`. Missing (NA) values are ignored, and the minimum of a group
+    /// without any existing values is missing (NA). See [aggregate](#method.aggregate).
+    pub fn min_by<KeyLabels, ValueLabel, AggLabel>(
+        &self,
+    ) -> MinMaxOutputOf<Self, KeyLabels, ValueLabel, AggLabel>
+    where
+        Self: SelectFieldByLabel<ValueLabel>
+            + AggregateWith<
+                KeyLabels,
+                ValueLabel,
+                AggLabel,
+                VFieldTypeOf<Self, ValueLabel>,
+                Value<VFieldTypeOf<Self, ValueLabel>>,
+                VFieldTypeOf<Self, ValueLabel>,
+            >,
+        VFieldTypeOf<Self, ValueLabel>: PartialOrd + Debug + Default + Clone,
+    {
+        self.aggregate_with(
+            Value::Na,
+            |min, value| {
+                if let Value::Exists(value) = value {
+                    if min.as_ref().map_or(true, |min| value < min) {
+                        *min = Value::Exists(value.clone());
+                    }
+                }
+            },
+            FieldData::from_field_vec,
+            &mut NoObserver,
+        )
+        .unwrap()
+    }
+
+    /// Computes the maximum of the values of the field labeled `ValueLabel` for each group of
+    /// records with the same values in the fields labeled `KeyLabels`, storing the maximums in a
+    /// new field labeled `AggLabel`. Missing (NA) values are ignored, and the maximum of a group
+    /// without any existing values is missing (NA). See [aggregate](#method.aggregate).
+    pub fn max_by<KeyLabels, ValueLabel, AggLabel>(
+        &self,
+    ) -> MinMaxOutputOf<Self, KeyLabels, ValueLabel, AggLabel>
+    where
+        Self: SelectFieldByLabel<ValueLabel>
+            + AggregateWith<
+                KeyLabels,
+                ValueLabel,
+                AggLabel,
+                VFieldTypeOf<Self, ValueLabel>,
+                Value<VFieldTypeOf<Self, ValueLabel>>,
+                VFieldTypeOf<Self, ValueLabel>,
+            >,
+        VFieldTypeOf<Self, ValueLabel>: PartialOrd + Debug + Default + Clone,
+    {
+        self.aggregate_with(
+            Value::Na,
+            |max, value| {
+                if let Value::Exists(value) = value {
+                    if max.as_ref().map_or(true, |max| value > max) {
+                        *max = Value::Exists(value.clone());
+                    }
+                }
+            },
+            FieldData::from_field_vec,
+            &mut NoObserver,
+        )
+        .unwrap()
+    }
+
+    /// Finds the first value (which may be missing) of the field labeled `ValueLabel` for each
+    /// group of records with the same values in the fields labeled `KeyLabels`, storing the values
+    /// in a new field labeled `AggLabel`. See [aggregate](#method.aggregate) and
+    /// [first_valid_by](#method.first_valid_by). To select the entire first record of each group,
+    /// see [first_record_by](#method.first_record_by).
+    pub fn first_by<KeyLabels, ValueLabel, AggLabel>(
+        &self,
+    ) -> FirstLastOutputOf<Self, KeyLabels, ValueLabel, AggLabel>
+    where
+        Self: SelectFieldByLabel<ValueLabel>
+            + AggregateWith<
+                KeyLabels,
+                ValueLabel,
+                AggLabel,
+                VFieldTypeOf<Self, ValueLabel>,
+                Option<Value<VFieldTypeOf<Self, ValueLabel>>>,
+                VFieldTypeOf<Self, ValueLabel>,
+            >,
+        VFieldTypeOf<Self, ValueLabel>: Debug + Default + Clone,
+    {
+        self.aggregate_with(
+            None,
+            |first, value| {
+                if first.is_none() {
+                    *first = Some(value.cloned());
+                }
+            },
+            |groups| {
+                FieldData::from_field_vec(
+                    groups
+                        .into_iter()
+                        .map(|value| value.unwrap_or(Value::Na))
+                        .collect(),
+                )
+            },
+            &mut NoObserver,
+        )
+        .unwrap()
+    }
+
+    /// Finds the last value (which may be missing) of the field labeled `ValueLabel` for each
+    /// group of records with the same values in the fields labeled `KeyLabels`, storing the values
+    /// in a new field labeled `AggLabel`. See [aggregate](#method.aggregate) and
+    /// [last_valid_by](#method.last_valid_by). To select the entire last record of each group, see
+    /// [last_record_by](#method.last_record_by).
+    pub fn last_by<KeyLabels, ValueLabel, AggLabel>(
+        &self,
+    ) -> FirstLastOutputOf<Self, KeyLabels, ValueLabel, AggLabel>
+    where
+        Self: SelectFieldByLabel<ValueLabel>
+            + AggregateWith<
+                KeyLabels,
+                ValueLabel,
+                AggLabel,
+                VFieldTypeOf<Self, ValueLabel>,
+                Option<Value<VFieldTypeOf<Self, ValueLabel>>>,
+                VFieldTypeOf<Self, ValueLabel>,
+            >,
+        VFieldTypeOf<Self, ValueLabel>: Debug + Default + Clone,
+    {
+        self.aggregate_with(
+            None,
+            |last, value| *last = Some(value.cloned()),
+            |groups| {
+                FieldData::from_field_vec(
+                    groups
+                        .into_iter()
+                        .map(|value| value.unwrap_or(Value::Na))
+                        .collect(),
+                )
+            },
+            &mut NoObserver,
+        )
+        .unwrap()
+    }
+
+    /// Finds the first existing value of the field labeled `ValueLabel` for each group of records
+    /// with the same values in the fields labeled `KeyLabels`, storing the values in a new field
+    /// labeled `AggLabel`. Missing (NA) values are skipped, and the value of a group without any
+    /// existing values is missing (NA). See [aggregate](#method.aggregate).
+    pub fn first_valid_by<KeyLabels, ValueLabel, AggLabel>(
+        &self,
+    ) -> MinMaxOutputOf<Self, KeyLabels, ValueLabel, AggLabel>
+    where
+        Self: SelectFieldByLabel<ValueLabel>
+            + AggregateWith<
+                KeyLabels,
+                ValueLabel,
+                AggLabel,
+                VFieldTypeOf<Self, ValueLabel>,
+                Value<VFieldTypeOf<Self, ValueLabel>>,
+                VFieldTypeOf<Self, ValueLabel>,
+            >,
+        VFieldTypeOf<Self, ValueLabel>: Debug + Default + Clone,
+    {
+        self.aggregate_with(
+            Value::Na,
+            |first, value| {
+                if first.is_na() {
+                    *first = value.cloned();
+                }
+            },
+            FieldData::from_field_vec,
+            &mut NoObserver,
+        )
+        .unwrap()
+    }
+
+    /// Finds the last existing value of the field labeled `ValueLabel` for each group of records
+    /// with the same values in the fields labeled `KeyLabels`, storing the values in a new field
+    /// labeled `AggLabel`. Missing (NA) values are skipped, and the value of a group without any
+    /// existing values is missing (NA). See [aggregate](#method.aggregate).
+    pub fn last_valid_by<KeyLabels, ValueLabel, AggLabel>(
+        &self,
+    ) -> MinMaxOutputOf<Self, KeyLabels, ValueLabel, AggLabel>
+    where
+        Self: SelectFieldByLabel<ValueLabel>
+            + AggregateWith<
+                KeyLabels,
+                ValueLabel,
+                AggLabel,
+                VFieldTypeOf<Self, ValueLabel>,
+                Value<VFieldTypeOf<Self, ValueLabel>>,
+                VFieldTypeOf<Self, ValueLabel>,
+            >,
+        VFieldTypeOf<Self, ValueLabel>: Debug + Default + Clone,
+    {
+        self.aggregate_with(
+            Value::Na,
+            |last, value| {
+                if value.exists() {
+                    *last = value.cloned();
+                }
+            },
+            FieldData::from_field_vec,
+            &mut NoObserver,
+        )
+        .unwrap()
+    }
+
+    /// Computes the `q`-th quantile (for `q` between `0.0` and `1.0`) of the values of the field
+    /// labeled `ValueLabel` for each group of records with the same values in the fields labeled
+    /// `KeyLabels`, storing the quantiles in a new field labeled `AggLabel`. Quantiles are
+    /// computed as in [Quantile::quantile](../stats/trait.Quantile.html#tymethod.quantile), and the
+    /// quantile of a group without any existing values is missing (NA). See
+    /// [aggregate](#method.aggregate).
+    ///
+    /// # Panics
+    /// Panics if `q` is not between `0.0` and `1.0`.
+    pub fn quantile_by<KeyLabels, ValueLabel, AggLabel>(
+        &self,
+        q: f64,
+    ) -> AggregateOutputOf<Self, KeyLabels, ValueLabel, AggLabel, Vec<f64>, f64>
+    where
+        Self: SelectFieldByLabel<ValueLabel>
+            + AggregateWith<
+                KeyLabels,
+                ValueLabel,
+                AggLabel,
+                VFieldTypeOf<Self, ValueLabel>,
+                Vec<f64>,
+                f64,
+            >,
+        VFieldTypeOf<Self, ValueLabel>: AsPrimitive<f64>,
+    {
+        assert!((0.0..=1.0).contains(&q), "quantile must be between 0 and 1");
+        self.aggregate_with(
+            vec![],
+            |values, value| {
+                if let Value::Exists(value) = value {
+                    values.push(value.as_());
+                }
+            },
+            |groups| {
+                FieldData::from_field_vec(
+                    groups
+                        .into_iter()
+                        .map(|values| stats::quantile_of(values, q).into())
+                        .collect(),
+                )
+            },
+            &mut NoObserver,
+        )
+        .unwrap()
+    }
+
+    /// Computes the median of the values of the field labeled `ValueLabel` for each group of
+    /// records with the same values in the fields labeled `KeyLabels`, storing the medians in a
+    /// new field labeled `AggLabel`. See [quantile_by](#method.quantile_by).
+    pub fn median_by<KeyLabels, ValueLabel, AggLabel>(
+        &self,
+    ) -> AggregateOutputOf<Self, KeyLabels, ValueLabel, AggLabel, Vec<f64>, f64>
+    where
+        Self: SelectFieldByLabel<ValueLabel>
+            + AggregateWith<
+                KeyLabels,
+                ValueLabel,
+                AggLabel,
+                VFieldTypeOf<Self, ValueLabel>,
+                Vec<f64>,
+                f64,
+            >,
+        VFieldTypeOf<Self, ValueLabel>: AsPrimitive<f64>,
+    {
+        self.quantile_by::<KeyLabels, ValueLabel, AggLabel>(0.5)
+    }
 }
 
 /// Trait providing the `aggregate` method for aggregating values over a specified grouping of
@@ -1521,18 +3265,121 @@ pub trait Aggregate<KeyLabels, ValueLabel, AggLabel, DType, AggType> {
     /// [aggregate](struct.DataView.html#method.aggregate) for more details.
     fn aggregate<AggFunc>(&self, init: AggType, f: AggFunc) -> Self::Output
     where
-        AggFunc: Fn(&mut AggType, Value<&DType>);
+        AggFunc: Fn(&mut AggType, Value<&DType>),
+    {
+        // aggregations without an observer cannot be cancelled
+        self.aggregate_observed(init, f, &mut NoObserver).unwrap()
+    }
+
+    /// Perform the 'aggregate' operation, reporting progress to `observer`. See the intrinsic
+    /// method [aggregate_observed](struct.DataView.html#method.aggregate_observed) for more details.
+    fn aggregate_observed<AggFunc, O>(
+        &self,
+        init: AggType,
+        f: AggFunc,
+        observer: &mut O,
+    ) -> error::Result<Self::Output>
+    where
+        AggFunc: Fn(&mut AggType, Value<&DType>),
+        O: Observer;
 }
 
 impl<Labels, Frames, KeyLabels, ValueLabel, AggLabel, DType, AggType>
     Aggregate<KeyLabels, ValueLabel, AggLabel, DType, AggType> for DataView<Labels, Frames>
+where
+    Self: AggregateWith<KeyLabels, ValueLabel, AggLabel, DType, AggType, AggType>,
+{
+    type Output =
+        <Self as AggregateWith<KeyLabels, ValueLabel, AggLabel, DType, AggType, AggType>>::Output;
+
+    fn aggregate_observed<AggFunc, O>(
+        &self,
+        init: AggType,
+        f: AggFunc,
+        observer: &mut O,
+    ) -> error::Result<Self::Output>
+    where
+        AggFunc: Fn(&mut AggType, Value<&DType>),
+        O: Observer,
+    {
+        self.aggregate_with(init, f, |aggregates| aggregates.into(), observer)
+    }
+}
+
+/// Type alias for the output of aggregating (with
+/// [AggregateWith](trait.AggregateWith.html)) the values of the field labeled `ValueLabel` in
+/// `View` grouped by the fields labeled `KeyLabels`, into a new field labeled `AggLabel`.
+pub type AggregateOutputOf<View, KeyLabels, ValueLabel, AggLabel, AccType, AggType> =
+    <View as AggregateWith<
+        KeyLabels,
+        ValueLabel,
+        AggLabel,
+        VFieldTypeOf<View, ValueLabel>,
+        AccType,
+        AggType,
+    >>::Output;
+
+/// Type alias for the output of [min_by](struct.DataView.html#method.min_by),
+/// [max_by](struct.DataView.html#method.max_by),
+/// [first_valid_by](struct.DataView.html#method.first_valid_by), and
+/// [last_valid_by](struct.DataView.html#method.last_valid_by).
+pub type MinMaxOutputOf<View, KeyLabels, ValueLabel, AggLabel> = AggregateOutputOf<
+    View,
+    KeyLabels,
+    ValueLabel,
+    AggLabel,
+    Value<VFieldTypeOf<View, ValueLabel>>,
+    VFieldTypeOf<View, ValueLabel>,
+>;
+
+/// Type alias for the output of [first_by](struct.DataView.html#method.first_by) and
+/// [last_by](struct.DataView.html#method.last_by).
+pub type FirstLastOutputOf<View, KeyLabels, ValueLabel, AggLabel> = AggregateOutputOf<
+    View,
+    KeyLabels,
+    ValueLabel,
+    AggLabel,
+    Option<Value<VFieldTypeOf<View, ValueLabel>>>,
+    VFieldTypeOf<View, ValueLabel>,
+>;
+
+/// Trait providing a generalized form of [aggregate](struct.DataView.html#method.aggregate), in
+/// which the values of each group are accumulated into an accumulator of type `AccType`, and the
+/// accumulators of all groups are then converted into the aggregated field (with values of type
+/// `AggType`). This allows aggregations that require intermediate state (such as a mean) or that
+/// produce missing values (such as the minimum of a group without any existing values).
+pub trait AggregateWith<KeyLabels, ValueLabel, AggLabel, DType, AccType, AggType> {
+    /// Type produced by this aggregate method.
+    type Output;
+
+    /// Perform the aggregation, accumulating each group's values into a clone of `init` using `f`,
+    /// converting the accumulators (one per group, in order of first appearance of each group)
+    /// into the aggregated field using `finish`, and reporting progress to `observer`.
+    ///
+    /// # Errors
+    /// Fails with `AgnesError::Cancelled` if `observer` requests cancellation.
+    fn aggregate_with<AggFunc, FinishFunc, O>(
+        &self,
+        init: AccType,
+        f: AggFunc,
+        finish: FinishFunc,
+        observer: &mut O,
+    ) -> error::Result<Self::Output>
+    where
+        AggFunc: Fn(&mut AccType, Value<&DType>),
+        FinishFunc: FnOnce(Vec<AccType>) -> FieldData<AggType>,
+        O: Observer;
+}
+
+impl<Labels, Frames, KeyLabels, ValueLabel, AggLabel, DType, AccType, AggType>
+    AggregateWith<KeyLabels, ValueLabel, AggLabel, DType, AccType, AggType>
+    for DataView<Labels, Frames>
 where
     Self: NRows + SelectFieldByLabel<ValueLabel, DType = DType>,
     Labels: FieldList<KeyLabels, Frames> + LabelSubset<KeyLabels> + FrameIndexList,
-    <Labels as FieldList<KeyLabels, Frames>>::Output: HashIndex + PartialEqIndex,
+    <Labels as FieldList<KeyLabels, Frames>>::Output: KeyList,
     <Labels as LabelSubset<KeyLabels>>::Output: Reorder<KeyLabels>,
-    AggType: Clone,
-    // AggFunc: Fn(&mut AggType, Value<&<Self as SelectFieldByLabel<ValueLabel>>::DType>),
+    AccType: Clone,
     FieldData<AggType>: IntoStore<AggLabel>,
     <FieldData<AggType> as IntoStore<AggLabel>>::Output: IntoFrame,
     Frames: NRows + SubsetClone<<Labels as FrameIndexList>::LabelList>,
@@ -1542,7 +3389,7 @@ where
         <Frames as SubsetClone<<Labels as FrameIndexList>::LabelList>>::Output,
     >: AddFrame<<<FieldData<AggType> as IntoStore<AggLabel>>::Output as IntoFrame>::Output>,
 {
-    // output is KeyLabels, then single ValueLabel column
+    // output is KeyLabels, then single AggLabel column
     type Output = <DataView<
         <<Labels as LabelSubset<KeyLabels>>::Output as Reorder<KeyLabels>>::Output,
         <Frames as SubsetClone<<Labels as FrameIndexList>::LabelList>>::Output,
@@ -1550,38 +3397,40 @@ where
         <<FieldData<AggType> as IntoStore<AggLabel>>::Output as IntoFrame>::Output,
     >>::Output;
 
-    fn aggregate<AggFunc>(&self, init: AggType, f: AggFunc) -> Self::Output
+    fn aggregate_with<AggFunc, FinishFunc, O>(
+        &self,
+        init: AccType,
+        f: AggFunc,
+        finish: FinishFunc,
+        observer: &mut O,
+    ) -> error::Result<Self::Output>
     where
-        AggFunc: Fn(&mut AggType, Value<&DType>),
+        AggFunc: Fn(&mut AccType, Value<&DType>),
+        FinishFunc: FnOnce(Vec<AccType>) -> FieldData<AggType>,
+        O: Observer,
     {
         let fl = self.field_list::<KeyLabels>();
         let values = self.field::<ValueLabel>();
-        let mut map = HashMap::new();
-        let mut indices = vec![];
-        let mut aggregates = vec![];
-        for i in 0..self.nrows() {
-            let record = Record::new(&fl, i);
-            let aggregates_idx = map.entry(record).or_insert_with(|| {
-                indices.push(i);
-                aggregates.push(init.clone());
-                debug_assert_eq!(indices.len(), aggregates.len());
-                indices.len() - 1
-            });
-            f(
-                &mut aggregates[*aggregates_idx],
-                values.get_datum(i).unwrap(),
-            );
+        let groups = erased::group_records_observed(&fl.keys(), self.nrows(), &*observer)?;
+        let mut reporter = Reporter::new(observer, Stage::Aggregate, Some(self.nrows()));
+        reporter.check()?;
+        let mut aggregates = vec![init; groups.len()];
+        for (i, &group) in groups.record_groups.iter().enumerate() {
+            f(&mut aggregates[group], values.get_datum(i).unwrap());
+            reporter.advance(1)?;
         }
-        let agg_data: FieldData<_> = aggregates.into();
-        let agg_frame = IntoStore::<AggLabel>::into_store(agg_data).into_frame();
+        reporter.finish();
+        let indices = groups.first_indices;
+        let agg_frame = IntoStore::<AggLabel>::into_store(finish(aggregates)).into_frame();
 
         let record_frames = self.frames.subset_clone().update_permutation(&indices);
 
-        DataView {
+        Ok(DataView {
             _labels: PhantomData,
             frames: record_frames,
+            invariants: Invariants::default(),
         }
-        .add_frame(agg_frame)
+        .add_frame(agg_frame))
     }
 }
 
@@ -1598,6 +3447,7 @@ mod tests {
     #[cfg(feature = "test-utils")]
     use test_utils::*;
 
+    #[cfg(feature = "test-utils")]
     use access::DataIndex;
     use error::*;
 
@@ -1788,6 +3638,123 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn affix_colliding() {
+        label_affix![Prev, typenum::U2, prefix = "prev_"];
+
+        let dv1 = sample_emp_table().into_view();
+        let dv2 = sample_emp_table()
+            .into_view()
+            .v::<Labels![emp_table::EmpId, emp_table::EmpName]>();
+
+        let merged_dv = dv1.merge(&dv2.affix_colliding::<Prev, _, _>(&dv1)).unwrap();
+        assert_eq!(
+            merged_dv.fieldnames(),
+            vec!["EmpId", "DeptId", "EmpName", "prev_EmpId", "prev_EmpName"]
+        );
+        assert_eq!(
+            merged_dv
+                .field::<Affixed<emp_table::EmpName, Prev>>()
+                .to_vec(),
+            merged_dv.field::<emp_table::EmpName>().to_vec()
+        );
+
+        // self-join on department ID
+        let joined_dv = dv1
+            .join_affixed::<Join<emp_table::DeptId, emp_table::DeptId, Equal>, Right, _, _>(&dv1);
+        assert_eq!(
+            joined_dv.fieldnames(),
+            vec![
+                "EmpId",
+                "DeptId",
+                "EmpName",
+                "EmpId_right",
+                "DeptId_right",
+                "EmpName_right"
+            ]
+        );
+        // departments 1 and 4 have 3 and 2 employees, departments 2 and 3 have 1 each
+        assert_eq!(joined_dv.nrows(), 15);
+        assert_eq!(
+            joined_dv.field::<emp_table::DeptId>().to_vec(),
+            joined_dv
+                .field::<Affixed<emp_table::DeptId, Right>>()
+                .to_vec()
+        );
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn affix_all() {
+        label_affix![Mgr, typenum::U2, prefix = "mgr_"];
+
+        let dv1 = sample_emp_table().into_view();
+        let dv2 = sample_emp_table().into_view().affix_all::<Mgr>();
+        assert_eq!(
+            dv2.fieldnames(),
+            vec!["mgr_EmpId", "mgr_DeptId", "mgr_EmpName"]
+        );
+
+        let merged_dv = dv1.merge(&dv2).unwrap();
+        assert_eq!(merged_dv.nfields(), 6);
+        assert_eq!(
+            merged_dv.field::<Affixed<emp_table::EmpId, Mgr>>().to_vec(),
+            merged_dv.field::<emp_table::EmpId>().to_vec()
+        );
+
+        // affixed labels can be affixed again
+        let dv3 = dv2.affix_all::<Right>();
+        assert_eq!(
+            dv3.fieldnames(),
+            vec!["mgr_EmpId_right", "mgr_DeptId_right", "mgr_EmpName_right"]
+        );
+        let merged_dv = merged_dv.merge(&dv3).unwrap();
+        assert_eq!(merged_dv.nfields(), 9);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn alias() {
+        label_affix![Coworker, typenum::U2, prefix = "coworker_"];
+
+        let dv = sample_emp_table().into_view();
+        let coworkers = dv.alias::<Coworker>();
+        assert_eq!(dv.fieldnames(), vec!["EmpId", "DeptId", "EmpName"]);
+        assert_eq!(
+            coworkers.fieldnames(),
+            vec!["coworker_EmpId", "coworker_DeptId", "coworker_EmpName"]
+        );
+        assert_eq!(
+            coworkers
+                .field::<Affixed<emp_table::EmpName, Coworker>>()
+                .to_vec(),
+            dv.field::<emp_table::EmpName>().to_vec()
+        );
+
+        // self-join on department ID
+        let joined_dv = dv
+            .join::<Join<emp_table::DeptId, Affixed<emp_table::DeptId, Coworker>, Equal>, _, _>(
+                &coworkers,
+            );
+        assert_eq!(joined_dv.nfields(), 6);
+        // departments 1 and 4 have 3 and 2 employees, departments 2 and 3 have 1 each
+        assert_eq!(joined_dv.nrows(), 15);
+        let pairs = joined_dv
+            .field::<emp_table::EmpName>()
+            .iter()
+            .zip(
+                joined_dv
+                    .field::<Affixed<emp_table::EmpName, Coworker>>()
+                    .iter(),
+            )
+            .filter(|(name, _)| name == &Value::Exists(&"Sally".to_string()))
+            .map(|(_, coworker)| coworker.unwrap().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(pairs.len(), 3);
+        assert!(pairs.contains(&"Sally".to_string()));
+    }
+
     #[cfg(feature = "test-utils")]
     tablespace![
         @continue(typenum::Add1<::view::tests::emp_table3::Table>)
@@ -1883,6 +3850,62 @@ mod tests {
         assert_eq!(subdv4.nfields(), 1);
     }
 
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn drop_fields() {
+        let dv = sample_merged_emp_table();
+        assert_eq!(dv.nfields(), 6);
+
+        let subdv = dv.drop::<Labels![emp_table::DeptId, extra_emp::DidTraining]>();
+        assert_eq!(subdv.nfields(), 4);
+        assert_eq!(subdv.nrows(), dv.nrows());
+        assert_eq!(
+            subdv.fieldnames(),
+            vec!["EmpId", "EmpName", "SalaryOffset", "VacationHrs"]
+        );
+        assert_eq!(
+            subdv.field::<emp_table::EmpName>().to_vec(),
+            dv.field::<emp_table::EmpName>().to_vec()
+        );
+
+        let subdv = dv.drop::<Labels![
+            extra_emp::SalaryOffset,
+            extra_emp::DidTraining,
+            extra_emp::VacationHrs
+        ]>();
+        assert_eq!(subdv.fieldnames(), vec!["EmpId", "DeptId", "EmpName"]);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn reorder_fields() {
+        let dv = sample_merged_emp_table();
+        let reordered = dv.reorder_fields::<Labels![
+            extra_emp::VacationHrs,
+            emp_table::EmpName,
+            emp_table::EmpId,
+            extra_emp::DidTraining,
+            emp_table::DeptId,
+            extra_emp::SalaryOffset
+        ]>();
+        assert_eq!(
+            reordered.fieldnames(),
+            vec![
+                "VacationHrs",
+                "EmpName",
+                "EmpId",
+                "DidTraining",
+                "DeptId",
+                "SalaryOffset"
+            ]
+        );
+        assert_eq!(reordered.nframes(), dv.nframes());
+        assert_eq!(
+            reordered.field::<emp_table::EmpId>().to_vec(),
+            dv.field::<emp_table::EmpId>().to_vec()
+        );
+    }
+
     #[cfg(feature = "test-utils")]
     #[test]
     fn subview_merged() {
@@ -1974,6 +3997,394 @@ mod tests {
         assert_eq!(dv3.field::<EmpId>().to_vec(), vec![8u64, 9, 6, 10, 0, 2, 5]);
     }
 
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn sort_observed() {
+        use observer::Stage;
+        use test_utils::emp_table::*;
+        let dv = sample_emp_table().into_view();
+
+        let mut reports = vec![];
+        let dv = dv.sort_by_label_observed::<EmpName, _>(&mut |stage, processed, total| {
+            reports.push((stage, processed, total))
+        });
+        assert_eq!(dv.field::<EmpId>().to_vec(), vec![10u64, 5, 6, 2, 8, 9, 0]);
+        assert_eq!(
+            reports,
+            vec![(Stage::Sort, 0, Some(7)), (Stage::Sort, 7, Some(7))]
+        );
+
+        // larger views are sorted in blocks which are then merged, reporting after each step
+        use observer::REPORT_INTERVAL;
+        use store::DataStore;
+        let nrows = REPORT_INTERVAL * 3 + 5;
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<EmpId, _>(FieldData::<u64>::from_vec(
+                (0..nrows as u64)
+                    .map(|i| (i * 7919) % nrows as u64)
+                    .collect(),
+            ))
+            .into_view();
+        let mut reports = vec![];
+        let dv = dv.sort_by_label_observed::<EmpId, _>(&mut |_, processed, total| {
+            reports.push((processed, total))
+        });
+        assert_eq!(
+            dv.field::<EmpId>().to_vec(),
+            (0..nrows as u64).collect::<Vec<_>>()
+        );
+        // four blocks, merged in two passes: the start, three full blocks, two merges in the first
+        // pass, one in the second, and the finish are reported
+        let total = nrows * 3;
+        assert_eq!(reports.len(), 8);
+        assert!(reports.iter().all(|&(_, t)| t == Some(total)));
+        assert!(reports.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+        assert_eq!(reports.last(), Some(&(total, Some(total))));
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn aggregate_observed() {
+        use observer::{CancellationToken, Stage};
+        use test_utils::emp_table::*;
+        let dv = sample_emp_table().into_view();
+
+        let mut reports = vec![];
+        let agg_dv = dv
+            .aggregate_observed::<Labels![DeptId], EmpId, EmpId, _, _, _, _>(
+                0u64,
+                |accum, value| *accum += value.unwrap_or(&0),
+                &mut |stage, processed, total| reports.push((stage, processed, total)),
+            )
+            .unwrap();
+        assert_eq!(agg_dv.field::<DeptId>().to_vec(), vec![1u64, 2, 3, 4]);
+        assert_eq!(agg_dv.field::<EmpId>().to_vec(), vec![11u64, 2, 8, 19]);
+        assert_eq!(
+            reports,
+            vec![
+                (Stage::Aggregate, 0, Some(7)),
+                (Stage::Aggregate, 7, Some(7))
+            ]
+        );
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let agg_dv = dv.aggregate_observed::<Labels![DeptId], EmpId, EmpId, _, _, _, _>(
+            0u64,
+            |accum, value| *accum += value.unwrap_or(&0),
+            &mut token.clone(),
+        );
+        match agg_dv {
+            Err(AgnesError::Cancelled) => {}
+            _ => panic!("expected cancellation"),
+        }
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn aggregate_shorthand() {
+        use test_utils::emp_stats::*;
+        use test_utils::emp_table::*;
+        use test_utils::extra_emp::*;
+
+        let dv = sample_dept_vacation_table().into_view();
+
+        let sums = dv.sum_by::<Labels![DeptId], VacationHrs, VacationHrs>();
+        assert_eq!(sums.field::<DeptId>().to_vec(), vec![1u64, 2, 3]);
+        assert_eq!(
+            sums.field::<VacationHrs>().to_vec(),
+            vec![15.0f32, 9.0, 0.0]
+        );
+
+        let means = dv.mean_by::<Labels![DeptId], VacationHrs, MeanVacationHrs>();
+        assert_eq!(
+            means.field::<MeanVacationHrs>().to_value_vec(),
+            vec![Value::Exists(7.5), Value::Exists(4.5), Value::Na]
+        );
+
+        let counts = dv.count_by::<Labels![DeptId], VacationHrs, NumEmps>();
+        assert_eq!(counts.field::<NumEmps>().to_vec(), vec![2u64, 2, 0]);
+
+        let mins = dv.min_by::<Labels![DeptId], VacationHrs, VacationHrs>();
+        assert_eq!(
+            mins.field::<VacationHrs>().to_value_vec(),
+            vec![Value::Exists(3.0), Value::Exists(4.0), Value::Na]
+        );
+
+        let maxs = dv.max_by::<Labels![DeptId], VacationHrs, VacationHrs>();
+        assert_eq!(
+            maxs.field::<VacationHrs>().to_value_vec(),
+            vec![Value::Exists(12.0), Value::Exists(5.0), Value::Na]
+        );
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn first_last_by() {
+        use store::DataStore;
+        use test_utils::emp_table::*;
+        use test_utils::extra_emp::*;
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<DeptId, _>(FieldData::<u64>::from_vec(vec![1u64, 2, 1, 1, 2, 3]))
+            .push_back_field::<VacationHrs, _>(FieldData::from_field_vec(vec![
+                Value::Na,
+                Value::Exists(5.0f32),
+                Value::Exists(2.0),
+                Value::Exists(3.0),
+                Value::Na,
+                Value::Na,
+            ]))
+            .into_view();
+
+        let firsts = dv.first_by::<Labels![DeptId], VacationHrs, VacationHrs>();
+        assert_eq!(firsts.field::<DeptId>().to_vec(), vec![1u64, 2, 3]);
+        assert_eq!(
+            firsts.field::<VacationHrs>().to_value_vec(),
+            vec![Value::Na, Value::Exists(5.0), Value::Na]
+        );
+        let lasts = dv.last_by::<Labels![DeptId], VacationHrs, VacationHrs>();
+        assert_eq!(
+            lasts.field::<VacationHrs>().to_value_vec(),
+            vec![Value::Exists(3.0), Value::Na, Value::Na]
+        );
+        let firsts = dv.first_valid_by::<Labels![DeptId], VacationHrs, VacationHrs>();
+        assert_eq!(
+            firsts.field::<VacationHrs>().to_value_vec(),
+            vec![Value::Exists(2.0), Value::Exists(5.0), Value::Na]
+        );
+        let lasts = dv.last_valid_by::<Labels![DeptId], VacationHrs, VacationHrs>();
+        assert_eq!(
+            lasts.field::<VacationHrs>().to_value_vec(),
+            vec![Value::Exists(3.0), Value::Exists(5.0), Value::Na]
+        );
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn with_row_index() {
+        use test_utils::emp_stats::NumEmps as RowIdx;
+        use test_utils::emp_table::*;
+
+        let dv = sample_emp_table().into_view().sort_by_label::<EmpName>();
+        let names = dv.field::<EmpName>().to_vec();
+        let indexed = dv.with_row_index::<RowIdx>();
+        assert_eq!(
+            indexed.fieldnames(),
+            vec!["EmpId", "DeptId", "EmpName", "NumEmps"]
+        );
+        assert_eq!(
+            indexed.field::<RowIdx>().to_vec(),
+            vec![0u64, 1, 2, 3, 4, 5, 6]
+        );
+
+        // the captured ordering can be restored after further sorting
+        let resorted = indexed.sort_by_label::<EmpId>();
+        assert_ne!(resorted.field::<EmpName>().to_vec(), names);
+        let restored = resorted.sort_by_label::<RowIdx>();
+        assert_eq!(restored.field::<EmpName>().to_vec(), names);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn compact() {
+        use test_utils::emp_table::*;
+
+        let dv = sample_emp_table()
+            .into_view()
+            .sort_by_label::<EmpName>()
+            .filter::<DeptId, _>(|value: Value<&u64>| value == 1);
+        let compacted = dv.compact();
+        assert_eq!(compacted.fieldnames(), vec!["EmpId", "DeptId", "EmpName"]);
+        assert_eq!(compacted.nrows(), 3);
+        assert_eq!(
+            compacted.field::<EmpName>().to_vec(),
+            dv.field::<EmpName>().to_vec()
+        );
+        assert_eq!(compacted.field::<EmpId>().to_vec(), vec![5u64, 6, 0]);
+        assert!(compacted.memory_usage().total() < dv.memory_usage().total());
+        // the compacted view owns a new store, no longer shared with the original
+        assert_eq!(compacted.store_ref_counts(), vec![1]);
+        // records remain sorted
+        assert!(compacted.invariants.is_sorted::<EmpName>());
+
+        // fields from multiple frames are collected into a single store
+        let merged = sample_emp_table()
+            .into_view()
+            .merge(&sample_emp_table_extra().into_view())
+            .unwrap()
+            .v::<Labels![EmpId, extra_emp::DidTraining]>();
+        let compacted = merged.compact();
+        assert_eq!(compacted.store_ref_counts(), vec![1]);
+        assert_eq!(
+            compacted.field::<extra_emp::DidTraining>().to_vec(),
+            merged.field::<extra_emp::DidTraining>().to_vec()
+        );
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn optimize_access() {
+        use test_utils::emp_table::*;
+
+        // EmpId is originally sorted, so sorting by it after another field leaves an identity
+        // permutation
+        let dv = sample_emp_table()
+            .into_view()
+            .sort_by_label::<EmpName>()
+            .sort_by_label::<EmpId>();
+        assert!(dv
+            .field::<EmpId>()
+            .get_chunk(0, 7)
+            .unwrap()
+            .as_slice()
+            .is_none());
+        let dv = dv.optimize_access();
+        assert_eq!(
+            dv.field::<EmpId>().get_chunk(0, 7).unwrap().as_slice(),
+            Some(&[0u64, 2, 5, 6, 8, 9, 10][..])
+        );
+
+        // non-identity permutations are kept
+        let merged = sample_emp_table()
+            .into_view()
+            .merge(&sample_emp_table_extra().into_view())
+            .unwrap()
+            .sort_by_label::<EmpName>()
+            .filter::<DeptId, _>(|value: Value<&u64>| value != 2);
+        let names = merged.field::<EmpName>().to_vec();
+        let offsets = merged.field::<extra_emp::SalaryOffset>().to_vec();
+        let optimized = merged.optimize_access();
+        assert_eq!(optimized.field::<EmpName>().to_vec(), names);
+        assert_eq!(
+            optimized.field::<extra_emp::SalaryOffset>().to_vec(),
+            offsets
+        );
+        assert!(optimized.invariants.is_sorted::<EmpName>());
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn to_store() {
+        use test_utils::emp_table::*;
+
+        // a permuted, multi-frame view
+        let dv = sample_emp_table()
+            .into_view()
+            .merge(&sample_emp_table_extra().into_view())
+            .unwrap()
+            .sort_by_label::<EmpName>()
+            .filter::<extra_emp::DidTraining, _>(|value: Value<&bool>| value == true);
+        let store = dv.to_store();
+        assert_eq!(store.nrows(), 4);
+        assert_eq!(
+            store.field::<EmpName>().to_vec(),
+            dv.field::<EmpName>().to_vec()
+        );
+        assert_eq!(
+            store.field::<extra_emp::VacationHrs>().to_vec(),
+            dv.field::<extra_emp::VacationHrs>().to_vec()
+        );
+
+        let names = dv.field::<EmpName>().to_vec();
+        let store = dv.v::<Labels![EmpName, EmpId]>().into_store();
+        let view = store.into_view();
+        assert_eq!(view.fieldnames(), vec!["EmpName", "EmpId"]);
+        assert_eq!(view.field::<EmpName>().to_vec(), names);
+        assert_eq!(view.store_ref_counts(), vec![1]);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn explode() {
+        use store::DataStore;
+        use test_utils::dept_table::DeptName;
+        use test_utils::emp_table::*;
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<EmpId, _>(FieldData::<u64>::from_vec(vec![0u64, 1, 2, 3]))
+            .push_back_field::<EmpName, _>(FieldData::from_field_vec(vec![
+                Value::Exists("Sally;Bob".to_string()),
+                Value::Na,
+                Value::Exists("".to_string()),
+                Value::Exists("Cara;;Ann".to_string()),
+            ]))
+            .into_view();
+        let exploded = dv.explode::<EmpName, DeptName>(";");
+        assert_eq!(exploded.nrows(), 7);
+        assert_eq!(exploded.fieldnames(), vec!["EmpId", "EmpName", "DeptName"]);
+        assert_eq!(
+            exploded.field::<EmpId>().to_vec(),
+            vec![0u64, 0, 1, 2, 3, 3, 3]
+        );
+        assert_eq!(
+            exploded.field::<EmpName>().to_value_vec()[..3],
+            [
+                Value::Exists("Sally;Bob".to_string()),
+                Value::Exists("Sally;Bob".to_string()),
+                Value::Na
+            ]
+        );
+        assert_eq!(
+            exploded.field::<DeptName>().to_value_vec(),
+            vec![
+                Value::Exists("Sally".to_string()),
+                Value::Exists("Bob".to_string()),
+                Value::Na,
+                Value::Exists("".to_string()),
+                Value::Exists("Cara".to_string()),
+                Value::Exists("".to_string()),
+                Value::Exists("Ann".to_string()),
+            ]
+        );
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn regex() {
+        use error::AgnesError;
+        use store::DataStore;
+        use test_utils::dept_table::DeptName;
+        use test_utils::emp_table::*;
+
+        let dv = DataStore::<Nil>::empty()
+            .push_back_field::<EmpId, _>(FieldData::<u64>::from_vec(vec![0u64, 1, 2, 3]))
+            .push_back_field::<EmpName, _>(FieldData::from_field_vec(vec![
+                Value::Exists("sally@sales".to_string()),
+                Value::Na,
+                Value::Exists("bob".to_string()),
+                Value::Exists("cara@eng".to_string()),
+            ]))
+            .into_view();
+
+        let filtered = dv.clone().filter_regex::<EmpName>("@").unwrap();
+        assert_eq!(filtered.field::<EmpId>().to_vec(), vec![0u64, 3]);
+        let filtered = dv.clone().filter_regex::<EmpName>("^b").unwrap();
+        assert_eq!(filtered.field::<EmpId>().to_vec(), vec![2u64]);
+        match dv.clone().filter_regex::<EmpName>("(") {
+            Err(AgnesError::Regex(_)) => {}
+            _ => panic!("expected regex error"),
+        }
+
+        let extracted = dv
+            .extract_regex::<EmpName, Labels![DeptName]>(r"^\w+(?:@(\w+))?$")
+            .unwrap();
+        assert_eq!(extracted.fieldnames(), vec!["EmpId", "EmpName", "DeptName"]);
+        assert_eq!(
+            extracted.field::<DeptName>().to_value_vec(),
+            vec![
+                Value::Exists("sales".to_string()),
+                Value::Na,
+                Value::Na,
+                Value::Exists("eng".to_string()),
+            ]
+        );
+        match dv.extract_regex::<EmpName, Labels![DeptName]>(r"^\w+") {
+            Err(AgnesError::DimensionMismatch(_)) => {}
+            _ => panic!("expected dimension mismatch"),
+        }
+    }
+
     #[cfg(feature = "test-utils")]
     #[test]
     fn filter() {
@@ -2011,6 +4422,108 @@ mod tests {
         assert_eq!(dv2.field::<EmpName>().to_vec(), vec!["Louise", "Ann"]);
     }
 
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn lookup() {
+        use test_utils::emp_table::*;
+        let orig_dv = sample_emp_table().into_view();
+        assert_eq!(orig_dv.lookup::<DeptId>(&1), vec![0, 2, 3]);
+        assert_eq!(orig_dv.lookup::<DeptId>(&7), Vec::<usize>::new());
+
+        // sorted views use a binary search
+        let sorted = orig_dv.clone().sort_by_label::<DeptId>();
+        assert!(sorted.invariants.is_sorted::<DeptId>());
+        assert_eq!(sorted.lookup::<DeptId>(&1), vec![0, 1, 2]);
+        assert_eq!(sorted.lookup::<DeptId>(&4), vec![5, 6]);
+        assert_eq!(sorted.lookup::<DeptId>(&0), Vec::<usize>::new());
+        assert_eq!(sorted.lookup::<DeptId>(&7), Vec::<usize>::new());
+        // other fields still scan
+        assert_eq!(sorted.lookup::<EmpId>(&2), vec![3]);
+
+        // filtering preserves sortedness
+        let filtered = sorted.filter::<EmpId, _>(|val: Value<&u64>| val != valref![5]);
+        assert!(filtered.invariants.is_sorted::<DeptId>());
+        assert_eq!(filtered.lookup::<DeptId>(&1), vec![0, 1]);
+
+        // sorting by a comparator does not
+        let reversed = orig_dv
+            .sort_unstable_by_label::<DeptId>()
+            .sort_by_label_comparator::<DeptId, _>(|left: Value<&u64>, right: Value<&u64>| {
+                right.cmp(&left)
+            });
+        assert!(!reversed.invariants.is_sorted::<DeptId>());
+        assert_eq!(reversed.lookup::<DeptId>(&4), vec![0, 1]);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn invariants() {
+        use test_utils::emp_table::*;
+        let orig_dv = sample_emp_table().into_view();
+        assert_eq!(orig_dv.invariants, Invariants::default());
+        assert!(orig_dv.is_sorted::<EmpId>());
+        assert!(!orig_dv.is_sorted::<DeptId>());
+        assert!(orig_dv.is_unique::<EmpId>());
+        assert!(!orig_dv.is_unique::<DeptId>());
+
+        let sorted = orig_dv.clone().sort_by_label::<DeptId>();
+        assert!(sorted.is_sorted::<DeptId>());
+        assert!(!sorted.is_sorted::<EmpId>());
+        // re-sorting an already-sorted view is a no-op
+        let resorted = sorted.clone().sort_unstable_by_label::<DeptId>();
+        assert_eq!(
+            resorted.field::<EmpId>().to_vec(),
+            sorted.field::<EmpId>().to_vec()
+        );
+
+        // loaded data already in order is left as it is (keeping its frames' permutations), and
+        // recorded as sorted
+        let by_id = orig_dv.clone().sort_by_label::<EmpId>();
+        assert!(by_id.invariants.is_sorted::<EmpId>());
+        let (mut orig_perms, mut perms) = (vec![], vec![]);
+        orig_dv.frames.frame_permutations(&mut orig_perms);
+        by_id.frames.frame_permutations(&mut perms);
+        assert!(orig_perms
+            .iter()
+            .zip(perms.iter())
+            .all(|(orig, perm)| Rc::ptr_eq(orig, perm)));
+        let observed = orig_dv
+            .clone()
+            .sort_by_label_observed::<EmpId, _>(&mut NoObserver);
+        assert!(observed.invariants.is_sorted::<EmpId>());
+
+        // unique values are unique by their labels, and keep the original order
+        let depts = sorted.unique_values::<Labels![DeptId]>();
+        assert!(depts.invariants.is_unique::<Labels![DeptId]>());
+        assert!(depts.invariants.is_sorted::<DeptId>());
+        assert!(depts.is_unique::<DeptId>());
+        assert_eq!(depts.field::<DeptId>().to_vec(), vec![1u64, 2, 3, 4]);
+        assert_eq!(depts.unique_indices::<Labels![DeptId]>(), vec![0, 1, 2, 3]);
+
+        // subsets of records keep the invariants which still hold
+        let reordered = depts.subset_records(&[2, 0]);
+        assert!(!reordered.invariants.is_sorted::<DeptId>());
+        assert!(reordered.invariants.is_unique::<Labels![DeptId]>());
+        let repeated = depts.subset_records(&[0, 0, 3]);
+        assert!(repeated.invariants.is_sorted::<DeptId>());
+        assert!(!repeated.invariants.is_unique::<Labels![DeptId]>());
+
+        // joins and validation of known-sorted and known-unique views give the same results
+        let dv_dept = sample_dept_table().into_view();
+        type EmpDept = Join<DeptId, dept_table::DeptId, Equal>;
+        let joined = sorted.join::<EmpDept, _, _>(&dv_dept);
+        assert_eq!(
+            joined.field::<EmpId>().to_vec(),
+            orig_dv
+                .join::<EmpDept, _, _>(&dv_dept)
+                .field::<EmpId>()
+                .to_vec()
+        );
+        assert!(depts
+            .join_validated::<EmpDept, _, _>(&dv_dept, JoinValidation::OneToOne)
+            .is_ok());
+    }
+
     #[cfg(feature = "test-utils")]
     #[test]
     fn filter_sort() {
@@ -2069,6 +4582,42 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn top_n() {
+        use permute::sort_f32_values;
+        use test_utils::emp_table::*;
+        use test_utils::extra_emp::*;
+        let dv = sample_merged_emp_table();
+
+        let top = dv.clone().top_n::<EmpId>(2);
+        assert_eq!(top.field::<EmpName>().to_vec(), vec!["Ann", "Louise"]);
+        assert!(!top.invariants.is_sorted::<EmpId>());
+
+        // partial selection from a sorted view
+        let bottom = dv.clone().sort_by_label::<EmpName>().bottom_n::<EmpId>(3);
+        assert_eq!(
+            bottom.field::<EmpName>().to_vec(),
+            vec!["Sally", "Jamie", "Bob"]
+        );
+        assert!(bottom.is_sorted::<EmpId>());
+
+        let top = dv
+            .clone()
+            .top_n_comparator::<VacationHrs, _>(3, sort_f32_values);
+        assert_eq!(
+            top.field::<EmpName>().to_vec(),
+            vec!["Bob", "Jamie", "Sally"]
+        );
+        let bottom = dv
+            .clone()
+            .bottom_n_comparator::<VacationHrs, _>(2, sort_f32_values);
+        assert_eq!(bottom.field::<EmpName>().to_vec(), vec!["Louis", "Louise"]);
+
+        assert_eq!(dv.clone().top_n::<EmpId>(10).nrows(), 7);
+        assert_eq!(dv.top_n::<EmpId>(0).nrows(), 0);
+    }
+
     #[cfg(feature = "test-utils")]
     #[test]
     fn unique_single() {
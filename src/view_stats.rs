@@ -4,10 +4,9 @@ Functions for displaying statistics about a `DataView`.
 
 use std::fmt;
 
-use prettytable as pt;
-
 use access::{DataIndex, NRows};
 use cons::Len;
+use display::TextTable;
 use label::{StrLabels, StrTypes};
 use partial::*;
 use stats::*;
@@ -269,12 +268,8 @@ impl fmt::Display for ViewStats {
             self.nrows, self.nfields
         )?;
 
-        let mut table = pt::Table::new();
-        table.set_titles(
-            ["Field", "Type", "Min", "Max", "Sum", "Mean", "StDev"]
-                .iter()
-                .into(),
-        );
+        let mut table =
+            TextTable::new(&["Field", "Type", "Min", "Max", "Sum", "Mean", "StDev"]).borders(true);
 
         debug_assert_eq!(self.idents.len(), self.tys.len());
         debug_assert_eq!(self.idents.len(), self.mins.len());
@@ -284,24 +279,22 @@ impl fmt::Display for ViewStats {
         debug_assert_eq!(self.idents.len(), self.stdevs.len());
 
         for i in 0..self.mins.len() {
-            table.add_row(pt::row::Row::new(vec![
-                cell![self.idents[i]],
-                cell![self.tys[i]],
-                cell![self.mins[i]],
-                cell![self.maxs[i]],
-                cell![self.sums[i]],
-                cell![self.means[i]],
-                cell![self.stdevs[i]],
-            ]));
+            table.add_row(&[
+                &self.idents[i],
+                &self.tys[i],
+                &self.mins[i],
+                &self.maxs[i],
+                &self.sums[i],
+                &self.means[i],
+                &self.stdevs[i],
+            ]);
         }
 
-        table.set_format(*pt::format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
-        table.fmt(f)?;
-
-        Ok(())
+        table.fmt(f)
     }
 }
 
+#[cfg(feature = "test-utils")]
 #[cfg(test)]
 mod tests {
     use test_utils::*;
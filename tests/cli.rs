@@ -0,0 +1,141 @@
+use std::path::Path;
+use std::process::Command;
+
+fn run(args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_agnes-cli"))
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.success(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+fn data_file(filename: &str) -> String {
+    Path::new(file!())
+        .parent()
+        .unwrap()
+        .join("data")
+        .join(filename)
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+// Splits the lines of a printed table into the (trimmed) text of each cell, skipping the
+// separator line below the titles.
+fn table_rows(output: &str) -> Vec<Vec<&str>> {
+    output
+        .lines()
+        .filter(|line| !line.chars().all(|c| c == '-' || c == '+'))
+        .map(|line| line.split('|').map(|cell| cell.trim()).collect())
+        .collect()
+}
+
+#[test]
+fn cli_head() {
+    let (success, output) = run(&["head", "-n", "2", &data_file("gdp.csv")]);
+    assert!(success);
+    let rows = table_rows(&output);
+    assert_eq!(rows.len(), 3);
+    assert!(rows[0].contains(&"Country Name"));
+    assert!(rows[1].contains(&"Aruba"));
+    assert!(rows[2].contains(&"Afghanistan"));
+}
+
+#[test]
+fn cli_describe() {
+    let (success, output) = run(&["describe", &data_file("gdp.csv")]);
+    assert!(success);
+    assert!(output.contains("Records: 264"));
+    assert!(output.contains("Fields: 63"));
+    assert!(table_rows(&output)
+        .iter()
+        .any(|row| *row == ["1960", "Float", "131"]));
+}
+
+#[test]
+fn cli_filter() {
+    let (success, output) = run(&["filter", "state == \"PA\"", &data_file("sample1.csv")]);
+    assert!(success);
+    assert_eq!(
+        table_rows(&output),
+        vec![vec!["state", "val1", "val2"], vec!["PA", "54", "2.34"]]
+    );
+
+    let (success, output) = run(&["filter", "`Country Code` == \"ABW\"", &data_file("gdp.csv")]);
+    assert!(success);
+    let rows = table_rows(&output);
+    assert_eq!(rows.len(), 2);
+    assert!(rows[1].contains(&"Aruba"));
+
+    let (success, output) = run(&[
+        "filter",
+        "val1 > 50 && !(val2 < 5)",
+        &data_file("sample1.csv"),
+    ]);
+    assert!(success);
+    let rows = table_rows(&output);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1][0], "SC");
+
+    // invalid expressions, unknown fields, and comparisons with values of another kind fail
+    assert!(!run(&["filter", "state=PA", &data_file("sample1.csv")]).0);
+    assert!(!run(&["filter", "val3 == 40", &data_file("sample1.csv")]).0);
+    assert!(!run(&["filter", "val1 == \"PA\"", &data_file("sample1.csv")]).0);
+}
+
+#[test]
+fn cli_join() {
+    let (success, output) = run(&[
+        "join",
+        "state",
+        &data_file("sample1.csv"),
+        &data_file("states.csv"),
+    ]);
+    assert!(success);
+    let rows = table_rows(&output);
+    assert_eq!(rows[0], vec!["state", "val1", "val2", "region", "pop_rank"]);
+    // records are in the order of the left file; states without a match are dropped
+    assert_eq!(
+        rows[1..]
+            .iter()
+            .map(|row| (row[0], row[3]))
+            .collect::<Vec<_>>(),
+        vec![
+            ("OH", "Midwest"),
+            ("PA", "Northeast"),
+            ("CA", "West"),
+            ("NY", "Northeast")
+        ]
+    );
+
+    // the join field can be named differently in each file
+    let (success, output) = run(&[
+        "join",
+        "val1=pop_rank",
+        &data_file("sample1.csv"),
+        &data_file("states.csv"),
+    ]);
+    assert!(success);
+    let rows = table_rows(&output);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1][0], "OH");
+
+    assert!(
+        !run(&[
+            "join",
+            "region",
+            &data_file("sample1.csv"),
+            &data_file("states.csv"),
+        ])
+        .0
+    );
+}
+
+#[test]
+fn cli_usage() {
+    let (success, _) = run(&["frobnicate"]);
+    assert!(!success);
+}
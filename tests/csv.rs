@@ -1,6 +1,12 @@
 #[macro_use]
 extern crate agnes;
+extern crate csv;
 extern crate csv_sniffer;
+#[cfg(feature = "net")]
+extern crate futures_util;
+extern crate tempfile;
+#[cfg(feature = "net")]
+extern crate tokio;
 extern crate typenum;
 
 mod common;
@@ -11,6 +17,17 @@ tablespace![
         CountryCode: String,
         Year1983: f64,
     }
+    pub table provenance {
+        SourceFile: String,
+    }
+    pub table parts {
+        State: String,
+        Value: u64,
+    }
+    pub table notes {
+        State: String,
+        Note: String,
+    }
 ];
 
 #[test]
@@ -59,3 +76,888 @@ fn csv_load_test_skip() {
     assert_eq!(subdv.nfields(), 2);
     println!("{}", subdv);
 }
+
+#[test]
+fn csv_load_test_observed() {
+    use agnes::error::AgnesError;
+    use agnes::observer::{CancellationToken, Stage};
+
+    let gdp_schema = schema![
+        fieldname gdp::CountryName = "Country Name";
+        fieldname gdp::Year1983 = "1983";
+    ];
+
+    let (mut csv_rdr, _) = common::load_csv_file("gdp.csv", gdp_schema);
+    let mut reports = vec![];
+    let dv = csv_rdr
+        .read_observed(&mut |stage, processed, total| reports.push((stage, processed, total)))
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.nrows(), 264);
+    assert_eq!(
+        reports,
+        vec![(Stage::Read, 0, None), (Stage::Read, 264, None)]
+    );
+
+    let token = CancellationToken::new();
+    token.cancel();
+    match csv_rdr.read_observed(&mut token.clone()) {
+        Err(AgnesError::Cancelled) => {}
+        _ => panic!("expected cancellation"),
+    }
+}
+
+#[test]
+fn csv_load_test_sample() {
+    use agnes::access::DataIndex;
+    use agnes::select::FieldSelect;
+    use gdp::*;
+
+    let gdp_schema = schema![
+        fieldname gdp::CountryName = "Country Name";
+        fieldname gdp::Year1983 = "1983";
+    ];
+
+    let (mut csv_rdr, _) = common::load_csv_file("gdp.csv", gdp_schema);
+    let full = csv_rdr.read().unwrap().into_view();
+    let names = full.field::<CountryName>().to_vec();
+
+    let sample = csv_rdr.read_sample(10, 42).unwrap().into_view();
+    assert_eq!(sample.nrows(), 10);
+    let sampled = sample.field::<CountryName>().to_vec();
+    // sampled records are distinct and remain in source order
+    let positions = sampled
+        .iter()
+        .map(|name| names.iter().position(|n| n == name).unwrap())
+        .collect::<Vec<_>>();
+    assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+
+    // samples are reproducible for a given seed
+    let again = csv_rdr.read_sample(10, 42).unwrap().into_view();
+    assert_eq!(again.field::<CountryName>().to_vec(), sampled);
+    let other = csv_rdr.read_sample(10, 7).unwrap().into_view();
+    assert_ne!(other.field::<CountryName>().to_vec(), sampled);
+
+    // requesting more records than exist returns every record
+    let all = csv_rdr.read_sample(1000, 42).unwrap().into_view();
+    assert_eq!(all.field::<CountryName>().to_vec(), names);
+}
+
+#[cfg(feature = "net")]
+#[test]
+fn csv_load_test_async() {
+    use agnes::access::DataIndex;
+    use agnes::select::FieldSelect;
+    use agnes::source::async_csv::AsyncCsvReader;
+    use agnes::store::DataStore;
+    use futures_util::future::try_join;
+    use gdp::*;
+    use std::path::Path;
+
+    let data_dir = Path::new(file!()).parent().unwrap().join("data");
+    let gdp_schema = schema![
+        fieldname gdp::CountryName = "Country Name";
+        fieldname gdp::CountryCode = "Country Code";
+        fieldname gdp::Year1983 = "1983";
+    ];
+
+    // load both the file with preamble and the file without preamble concurrently
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let (ds_preamble, ds_nopreamble) = runtime
+        .block_on(try_join(
+            DataStore::from_async_source(AsyncCsvReader::new(
+                data_dir.join("gdp.csv"),
+                gdp_schema.clone(),
+            )),
+            DataStore::from_async_source(AsyncCsvReader::new(
+                data_dir.join("gdp.nopreamble.csv"),
+                gdp_schema.clone(),
+            )),
+        ))
+        .unwrap();
+
+    let (mut csv_rdr, _) = common::load_csv_file("gdp.csv", gdp_schema);
+    let dv_sync = csv_rdr.read().unwrap().into_view();
+
+    for dv in [ds_preamble.into_view(), ds_nopreamble.into_view()] {
+        assert_eq!(dv.nrows(), 264);
+        assert_eq!(dv.nfields(), 3);
+        assert_eq!(
+            dv.field::<CountryName>().to_vec(),
+            dv_sync.field::<CountryName>().to_vec()
+        );
+        assert_eq!(
+            dv.field::<Year1983>().to_vec(),
+            dv_sync.field::<Year1983>().to_vec()
+        );
+    }
+}
+
+#[test]
+fn csv_load_test_glob() {
+    use agnes::access::DataIndex;
+    use agnes::select::FieldSelect;
+    use agnes::source::csv::{CsvSource, MultiCsvReader};
+    use gdp::*;
+    use std::path::Path;
+
+    let data_dir = Path::new(file!()).parent().unwrap().join("data");
+    let gdp_schema = schema![
+        fieldname gdp::CountryName = "Country Name";
+        fieldname gdp::CountryCode = "Country Code";
+        fieldname gdp::Year1983 = "1983";
+    ];
+
+    // matches both 'gdp.csv' and 'gdp.nopreamble.csv'
+    let source = CsvSource::from_glob(data_dir.join("gdp*.csv").to_str().unwrap()).unwrap();
+    assert_eq!(source.sources().len(), 2);
+    let mut multi_rdr = MultiCsvReader::new(&source, gdp_schema.clone()).unwrap();
+
+    let dv = multi_rdr.read().unwrap().into_view();
+    assert_eq!(dv.nrows(), 528);
+    assert_eq!(dv.nfields(), 3);
+
+    let dv = multi_rdr
+        .read_with_provenance::<provenance::SourceFile>()
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.nrows(), 528);
+    assert_eq!(dv.nfields(), 4);
+    let source_files = dv.field::<provenance::SourceFile>().to_vec();
+    assert!(source_files[0].ends_with("gdp.csv"));
+    assert!(source_files[527].ends_with("gdp.nopreamble.csv"));
+
+    // should be the same data in both files
+    let (mut csv_rdr, _) = common::load_csv_file("gdp.csv", gdp_schema);
+    let dv_single = csv_rdr.read().unwrap().into_view();
+    let country_names = dv.field::<CountryName>().to_vec();
+    assert_eq!(country_names[..264], country_names[264..]);
+    assert_eq!(
+        country_names[..264].to_vec(),
+        dv_single.field::<CountryName>().to_vec()
+    );
+
+    assert!(CsvSource::from_glob(data_dir.join("nonexistent*.csv").to_str().unwrap()).is_err());
+}
+
+#[test]
+fn csv_load_test_glob_reordered() {
+    use agnes::access::DataIndex;
+    use agnes::select::FieldSelect;
+    use agnes::source::csv::{CsvSource, MultiCsvReader};
+    use std::fs::File;
+    use std::io::Write;
+
+    let dir = tempfile::tempdir().unwrap();
+    let contents = [
+        "state,value,other\nOH,4,a\nPA,54,b\n",
+        "other,value,state\nc,23,NH\nd,21,NC\ne,85,CA\n",
+    ];
+    for (i, contents) in contents.iter().enumerate() {
+        let mut file = File::create(dir.path().join(format!("part-{}.csv", i))).unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+    }
+
+    let source = CsvSource::from_glob(dir.path().join("part-*.csv").to_str().unwrap()).unwrap();
+    let mut multi_rdr = MultiCsvReader::new(
+        &source,
+        schema![
+            fieldname parts::State = "state";
+            fieldname parts::Value = "value";
+        ],
+    )
+    .unwrap();
+    let dv = multi_rdr.read().unwrap().into_view();
+    assert_eq!(
+        dv.field::<parts::State>().to_vec(),
+        vec!["OH", "PA", "NH", "NC", "CA"]
+    );
+    assert_eq!(dv.field::<parts::Value>().to_vec(), vec![4, 54, 23, 21, 85]);
+}
+
+#[test]
+fn csv_sniff_options() {
+    use agnes::access::DataIndex;
+    use agnes::select::FieldSelect;
+    use agnes::source::csv::{CsvReader, CsvSource, SampleSize, SniffOptions, Type};
+    use agnes::value::Value;
+    use std::fs::File;
+    use std::io::Write;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notes.csv");
+    File::create(&path)
+        .unwrap()
+        .write_all(b"state,value,score,note\nOH,4,1,a\nPA,54,2,\nNH,23,3,c\n")
+        .unwrap();
+
+    let source = CsvSource::new(path.clone()).unwrap();
+    assert_eq!(source.metadata().types[1], Type::Unsigned);
+
+    let options = SniffOptions::new()
+        .sample_size(SampleSize::All)
+        .prefer_signed(true)
+        .override_type("score", Type::Float)
+        .override_type(0usize, Type::Text);
+    let source = CsvSource::with_options(path.clone(), &options).unwrap();
+    assert_eq!(
+        source.metadata().types,
+        vec![Type::Text, Type::Signed, Type::Float, Type::Text]
+    );
+    let source =
+        CsvSource::with_options(path.clone(), &SniffOptions::new().prefer_float(true)).unwrap();
+    assert_eq!(source.metadata().types[1], Type::Float);
+    assert!(CsvSource::with_options(
+        path.clone(),
+        &SniffOptions::new().override_type("missing", Type::Text)
+    )
+    .is_err());
+    assert!(CsvSource::with_options(
+        path.clone(),
+        &SniffOptions::new().override_type(4usize, Type::Text)
+    )
+    .is_err());
+
+    // overridden types must be representable by the schema's data types when loading
+    let parts_schema = schema![
+        fieldname parts::State = "state";
+        fieldname parts::Value = "value";
+    ];
+    let load = |options: SniffOptions| {
+        let source = CsvSource::with_options(path.clone(), &options).unwrap();
+        CsvReader::new(&source, parts_schema.clone())
+            .unwrap()
+            .read()
+    };
+    let ds = load(
+        SniffOptions::new()
+            .override_type("state", Type::Text)
+            .override_type("value", Type::Unsigned),
+    )
+    .unwrap();
+    assert_eq!(ds.into_view().nrows(), 3);
+    assert!(load(SniffOptions::new().override_type("value", Type::Float)).is_err());
+    assert!(load(SniffOptions::new().override_type("value", Type::Signed)).is_err());
+    assert!(load(SniffOptions::new().override_type("state", Type::Boolean)).is_err());
+
+    let schema = schema![
+        fieldname notes::State = "state";
+        fieldname notes::Note = "note";
+    ];
+    let source = CsvSource::new(path.clone()).unwrap();
+    let dv = CsvReader::new(&source, schema.clone())
+        .unwrap()
+        .read()
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.field::<notes::Note>().get_datum(1).unwrap(), Value::Na);
+
+    let source =
+        CsvSource::with_options(path.clone(), &SniffOptions::new().empty_as_na(false)).unwrap();
+    let dv = CsvReader::new(&source, schema)
+        .unwrap()
+        .read()
+        .unwrap()
+        .into_view();
+    assert_eq!(
+        dv.field::<notes::Note>().to_value_vec(),
+        vec![
+            Value::Exists("a".to_string()),
+            Value::Exists("".to_string()),
+            Value::Exists("c".to_string())
+        ]
+    );
+}
+
+#[test]
+fn csv_load_test_from_reader() {
+    use agnes::access::DataIndex;
+    use agnes::select::FieldSelect;
+    use agnes::source::csv::{CsvReader, CsvSource};
+    use std::io::Cursor;
+
+    let data = "state,value\nOH,4\nPA,54\nNH,23\n";
+    let schema = schema![
+        fieldname parts::State = "state";
+        fieldname parts::Value = "value";
+    ];
+
+    let source = CsvSource::from_bytes(data.as_bytes()).unwrap();
+    assert_eq!(source.metadata().num_fields, 2);
+    let dv = CsvReader::new(&source, schema.clone())
+        .unwrap()
+        .read()
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.field::<parts::State>().to_vec(), vec!["OH", "PA", "NH"]);
+    assert_eq!(dv.field::<parts::Value>().to_vec(), vec![4, 54, 23]);
+
+    let mut cursor = Cursor::new(data.as_bytes().to_vec());
+    cursor.set_position(6);
+    let source = CsvSource::from_reader(cursor).unwrap();
+    let dv = CsvReader::new(&source, schema)
+        .unwrap()
+        .read_sample(2, 0)
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.nrows(), 2);
+}
+
+#[test]
+fn csv_load_test_from_str() {
+    use agnes::access::DataIndex;
+    use agnes::select::FieldSelect;
+    use agnes::value::Value;
+    use agnes::view::DataView;
+
+    let dv: parts::View = DataView::from_csv_str(
+        schema![
+            fieldname parts::State = "state";
+            fieldname parts::Value = "value";
+        ],
+        "state,value\nOH,4\nPA,\nNH,23\n",
+    )
+    .unwrap();
+    assert_eq!(dv.field::<parts::State>().to_vec(), vec!["OH", "PA", "NH"]);
+    assert_eq!(
+        dv.field::<parts::Value>().to_value_vec(),
+        vec![Value::Exists(4), Value::Na, Value::Exists(23)]
+    );
+    assert!(DataView::<_, _>::from_csv_str(
+        schema![
+            fieldname parts::State = "state";
+            fieldname parts::Value = "missing";
+        ],
+        "state,value\nOH,4\n",
+    )
+    .map(|dv: parts::View| dv.nrows())
+    .is_err());
+}
+
+#[test]
+fn csv_load_test_from_stream() {
+    use std::io::Read;
+
+    use agnes::access::DataIndex;
+    use agnes::select::FieldSelect;
+    use agnes::source::stream::{load_csv_from_reader, CsvStreamReader};
+    use agnes::value::Value;
+
+    // enough records to extend well past the sniffing sample
+    let body = (0..1000u64)
+        .map(|i| {
+            format!(
+                "S{},{}\n",
+                i,
+                if i % 10 == 3 {
+                    "".into()
+                } else {
+                    i.to_string()
+                }
+            )
+        })
+        .collect::<String>();
+    // a chained reader is not seekable, like standard input or a pipe
+    let stream = Read::chain("state,value\n".as_bytes(), body.as_bytes());
+    let schema = schema![
+        fieldname parts::State = "state";
+        fieldname parts::Value = "value";
+    ];
+
+    let dv = load_csv_from_reader(stream, schema.clone()).unwrap();
+    assert_eq!(dv.nrows(), 1000);
+    let values = dv.field::<parts::Value>().to_value_vec();
+    assert_eq!(values[0], Value::Exists(0));
+    assert_eq!(values[3], Value::Na);
+    assert_eq!(values[999], Value::Exists(999));
+    assert_eq!(dv.field::<parts::State>().to_vec()[999], "S999");
+
+    let stream = Read::chain("state,value\n".as_bytes(), "OH,4\n".as_bytes());
+    assert!(CsvStreamReader::new(stream)
+        .read(schema![fieldname parts::Value = "missing";])
+        .is_err());
+}
+
+#[test]
+fn csv_stream_filter_batched_sample() {
+    use std::io::Read;
+
+    use agnes::access::DataIndex;
+    use agnes::select::FieldSelect;
+    use agnes::source::csv::BatchControl;
+    use agnes::source::stream::CsvStreamReader;
+    use agnes::value::Value;
+
+    let body = (0..1000u64)
+        .map(|i| format!("S{},{}\n", i, i))
+        .collect::<String>();
+    let stream = || Read::chain("state,value\n".as_bytes(), body.as_bytes());
+    let schema = schema![
+        fieldname parts::State = "state";
+        fieldname parts::Value = "value";
+    ];
+
+    let dv = CsvStreamReader::new(stream())
+        .open(schema.clone())
+        .unwrap()
+        .filter::<parts::Value, _>(|value: Value<&u64>| value.map_or(false, |&v| v % 100 == 0))
+        .read()
+        .unwrap()
+        .into_view();
+    assert_eq!(
+        dv.field::<parts::Value>().to_vec(),
+        (0..10).map(|i| i * 100).collect::<Vec<u64>>()
+    );
+
+    // stopping early leaves the rest of the stream unread
+    let mut starts = vec![];
+    let ds = CsvStreamReader::new(stream())
+        .open(schema.clone())
+        .unwrap()
+        .read_batched(300, |batch, start| {
+            assert_eq!(batch.nrows(), 300);
+            starts.push(start);
+            Ok(if start == 300 {
+                BatchControl::Stop
+            } else {
+                BatchControl::Continue
+            })
+        })
+        .unwrap();
+    assert_eq!(starts, vec![0, 300]);
+    assert_eq!(ds.into_view().nrows(), 600);
+
+    let sample = |seed| {
+        CsvStreamReader::new(stream())
+            .open(schema.clone())
+            .unwrap()
+            .filter::<parts::Value, _>(|value: Value<&u64>| value.map_or(false, |&v| v >= 500))
+            .read_sample(20, seed)
+            .unwrap()
+            .into_view()
+            .field::<parts::Value>()
+            .to_vec()
+    };
+    let values = sample(7);
+    assert_eq!(values.len(), 20);
+    assert!(values.iter().all(|&v| v >= 500));
+    assert!(values.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(values, sample(7));
+}
+
+#[test]
+fn csv_load_test_encoding() {
+    use agnes::access::DataIndex;
+    use agnes::select::FieldSelect;
+    use agnes::source::csv::{CsvReader, CsvSource, SampleSize, SniffOptions, TextEncoding};
+    use agnes::source::stream::CsvStreamReader;
+
+    // Windows-1252 values are detected automatically
+    let data = b"state,note\nOH,caf\xe9\nPA,\x805 fee\n";
+    let schema = schema![
+        fieldname notes::State = "state";
+        fieldname notes::Note = "note";
+    ];
+    let source = CsvSource::from_bytes(data).unwrap();
+    assert_eq!(source.encoding().name(), "windows-1252");
+    let dv = CsvReader::new(&source, schema.clone())
+        .unwrap()
+        .read()
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.field::<notes::Note>().to_vec(), vec!["café", "€5 fee"]);
+    let utf8 = SniffOptions::new().encoding(TextEncoding::from_label("utf-8").unwrap());
+    assert!(CsvSource::with_options(data.to_vec(), &utf8).is_err());
+    // the encoding is detected from a sample of the file
+    let mut data = data.to_vec();
+    for _ in 0..1000 {
+        data.extend_from_slice(b"NY,cr\xe8me\n");
+    }
+    let options = SniffOptions::new().sample_size(SampleSize::Bytes(32));
+    let source = CsvSource::with_options(data, &options).unwrap();
+    assert_eq!(source.encoding().name(), "windows-1252");
+    let dv = CsvReader::new(&source, schema.clone())
+        .unwrap()
+        .read()
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.nrows(), 1002);
+    assert_eq!(
+        dv.field::<notes::Note>().get_datum(1001).unwrap(),
+        agnes::value::Value::Exists(&"crème".to_string())
+    );
+
+    // other encodings (here, Shift-JIS) are selected explicitly, and also apply to the headers
+    let data: &[u8] = b"\x96\xbc\x91O,\x83\x81\x83\x82\n\
+        \x93\x8c\x8b\x9e,\x8e\xf1\x93s\n\
+        \x91\xe5\x8d\xe3,\x90H\x82\xa2\x93|\x82\xea\n";
+    let sjis = TextEncoding::from_label("shift_jis").unwrap();
+    let schema = schema![
+        fieldname notes::State = "名前";
+        fieldname notes::Note = "メモ";
+    ];
+    let source =
+        CsvSource::with_options(data.to_vec(), &SniffOptions::new().encoding(sjis)).unwrap();
+    assert_eq!(source.encoding().name(), "windows-31j");
+    let dv = CsvReader::new(&source, schema.clone())
+        .unwrap()
+        .read()
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.field::<notes::State>().to_vec(), vec!["東京", "大阪"]);
+    assert_eq!(dv.field::<notes::Note>().to_vec(), vec!["首都", "食い倒れ"]);
+
+    // only a sample of the file is transcoded to sniff it, ending on a character boundary
+    let options = SniffOptions::new()
+        .encoding(sjis)
+        .sample_size(SampleSize::Bytes(36));
+    let source = CsvSource::with_options(data.to_vec(), &options).unwrap();
+    let dv = CsvReader::new(&source, schema.clone())
+        .unwrap()
+        .read()
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.field::<notes::Note>().to_vec(), vec!["首都", "食い倒れ"]);
+
+    let dv = CsvStreamReader::new(data)
+        .encoding(sjis)
+        .read(schema)
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.field::<notes::State>().to_vec(), vec!["東京", "大阪"]);
+
+    assert!(TextEncoding::from_label("not-an-encoding").is_err());
+}
+
+#[test]
+fn csv_load_test_bom_headers() {
+    use agnes::access::DataIndex;
+    use agnes::select::FieldSelect;
+    use agnes::source::csv::{CsvReader, CsvSource, HeaderNormalization, SniffOptions};
+    use agnes::source::stream::CsvStreamReader;
+
+    // byte order marks are stripped, so the first header matches
+    let data = b"\xEF\xBB\xBFstate,value\nOH,4\nPA,7\n";
+    let schema = schema![
+        fieldname parts::State = "state";
+        fieldname parts::Value = "value";
+    ];
+    let source = CsvSource::from_bytes(data).unwrap();
+    let dv = CsvReader::new(&source, schema.clone())
+        .unwrap()
+        .read()
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.field::<parts::State>().to_vec(), vec!["OH", "PA"]);
+    let dv = CsvStreamReader::new(&data[..])
+        .read(schema)
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.field::<parts::Value>().to_vec(), vec![4, 7]);
+
+    // headers only match with normalization
+    let data = b"\xEF\xBB\xBF  US State ,Total VALUE\nOH,4\nPA,7\n";
+    let schema = schema![
+        fieldname parts::State = "us_state";
+        fieldname parts::Value = "Total  Value";
+    ];
+    let source = CsvSource::from_bytes(data).unwrap();
+    assert!(CsvReader::new(&source, schema.clone()).is_err());
+
+    let normalization = HeaderNormalization::new()
+        .trim(true)
+        .case_fold(true)
+        .replace_spaces('_');
+    assert_eq!(normalization.normalize(" Total  VALUE "), "total_value");
+    let options = SniffOptions::new()
+        .normalize_headers(normalization.clone())
+        .override_type("TOTAL VALUE", csv_sniffer::Type::Unsigned);
+    let source = CsvSource::with_options(data.to_vec(), &options).unwrap();
+    let dv = CsvReader::new(&source, schema.clone())
+        .unwrap()
+        .read()
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.field::<parts::State>().to_vec(), vec!["OH", "PA"]);
+    assert_eq!(dv.field::<parts::Value>().to_vec(), vec![4, 7]);
+    let dv = CsvStreamReader::new(&data[..])
+        .normalize_headers(normalization)
+        .read(schema)
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.field::<parts::State>().to_vec(), vec!["OH", "PA"]);
+}
+
+#[test]
+fn csv_load_test_footer_ragged() {
+    use agnes::access::DataIndex;
+    use agnes::select::FieldSelect;
+    use agnes::source::csv::{CsvReader, CsvSource, RaggedRows, SniffOptions};
+    use agnes::source::stream::CsvStreamReader;
+    use agnes::value::Value;
+
+    let data = "state,value,note\nOH,4,a\nPA,7,b\nNY,2,c,extra\nWV,9\nTotal,22\n".as_bytes();
+    let schema = schema![
+        fieldname notes::State = "state";
+        fieldname notes::Note = "note";
+    ];
+    let read = |options: &SniffOptions| {
+        CsvReader::new(
+            &CsvSource::with_options(data.to_vec(), options).unwrap(),
+            schema.clone(),
+        )
+        .unwrap()
+        .read()
+        .map(|ds| ds.into_view())
+    };
+
+    // ragged rows fail by default (the sniffed sample must not contain ragged rows)
+    let options = SniffOptions::new().sample_size(csv_sniffer::SampleSize::Records(1));
+    assert!(read(&options.clone().skip_footer(1)).is_err());
+    assert!(read(&options.clone().ragged_rows(RaggedRows::Truncate)).is_err());
+
+    let dv = read(
+        &options
+            .clone()
+            .skip_footer(1)
+            .ragged_rows(RaggedRows::PadNa),
+    )
+    .unwrap();
+    assert_eq!(
+        dv.field::<notes::State>().to_vec(),
+        vec!["OH", "PA", "NY", "WV"]
+    );
+    assert_eq!(
+        dv.field::<notes::Note>().to_value_vec(),
+        vec![
+            Value::Exists("a".to_string()),
+            Value::Exists("b".to_string()),
+            Value::Exists("c".to_string()),
+            Value::Na,
+        ]
+    );
+
+    // the row limit applies after the footer is skipped
+    let dv = read(
+        &options
+            .clone()
+            .skip_footer(3)
+            .max_rows(5)
+            .ragged_rows(RaggedRows::PadNa),
+    )
+    .unwrap();
+    assert_eq!(dv.field::<notes::State>().to_vec(), vec!["OH", "PA"]);
+    let dv = read(&options.max_rows(1)).unwrap();
+    assert_eq!(dv.field::<notes::State>().to_vec(), vec!["OH"]);
+
+    // streams are sniffed from their first few kilobytes, so the ragged rows must come later
+    let mut data = "state,value,note\n".to_string();
+    for i in 0..1000 {
+        data.push_str(&format!("S{},{},n{}\n", i, i, i));
+    }
+    data.push_str("NY,2,c,extra\nWV,9\nTotal,22\n");
+    let dv = CsvStreamReader::new(data.as_bytes())
+        .skip_footer(1)
+        .ragged_rows(RaggedRows::PadNa)
+        .read(schema.clone())
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.nrows(), 1002);
+    assert_eq!(dv.field::<notes::Note>().to_value_vec()[1001], Value::Na);
+    assert!(CsvStreamReader::new(data.as_bytes())
+        .skip_footer(1)
+        .ragged_rows(RaggedRows::Truncate)
+        .read(schema.clone())
+        .is_err());
+    let dv = CsvStreamReader::new(data.as_bytes())
+        .max_rows(10)
+        .read(schema)
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.nrows(), 10);
+}
+
+#[test]
+fn csv_load_test_projection() {
+    use agnes::access::DataIndex;
+    use agnes::select::FieldSelect;
+    use agnes::source::csv::{
+        CsvReader, CsvSource, IntoCsvSrcSchema, MultiCsvReader, MultiFileSource, Projection,
+    };
+    use csv::ByteRecord;
+
+    // a wide file, of which only two columns are loaded
+    let mut data = (0..200)
+        .map(|i| format!("c{}", i))
+        .collect::<Vec<_>>()
+        .join(",");
+    data.push('\n');
+    for row in 0..20 {
+        let values = (0..200)
+            .map(|i| {
+                if i == 150 {
+                    format!("S{}", row)
+                } else {
+                    format!("{}", row * i)
+                }
+            })
+            .collect::<Vec<_>>();
+        data.push_str(&values.join(","));
+        data.push('\n');
+    }
+    let schema = schema![
+        fieldname parts::Value = "c3";
+        fieldname parts::State = "c150";
+    ];
+    let source = CsvSource::from_bytes(data.as_bytes()).unwrap();
+    let mut reader = CsvReader::new(&source, schema.clone()).unwrap();
+    let dv = reader.read().unwrap().into_view();
+    assert_eq!(dv.nrows(), 20);
+    assert_eq!(dv.field::<parts::Value>().to_vec()[7], 21);
+    assert_eq!(dv.field::<parts::State>().to_vec()[19], "S19");
+    assert_eq!(reader.read_sample(5, 1).unwrap().into_view().nrows(), 5);
+
+    // sources with differently-ordered columns are projected separately
+    let reordered = "c150,x,c3\nS99,0,297\nS98,1,294\nS97,2,291\n";
+    let multi =
+        MultiFileSource::new(vec![data.into_bytes(), reordered.as_bytes().to_vec()]).unwrap();
+    let dv = MultiCsvReader::new(&multi, schema)
+        .unwrap()
+        .read()
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.nrows(), 23);
+    assert_eq!(dv.field::<parts::Value>().to_vec()[20], 297);
+    assert_eq!(dv.field::<parts::State>().to_vec()[20], "S99");
+
+    let record = ByteRecord::from(vec!["a", "b", "c", "d"]);
+    let projection = Projection::all();
+    assert_eq!(projection.project(&record), record);
+    assert_eq!(projection.position(3), Some(3));
+    let short = ByteRecord::from(vec!["a", "b", "c"]);
+    let projection = Projection::of(
+        &schema![fieldindex parts::Value = 3; fieldindex parts::State = 1;]
+            .into_csv_src_schema(&Default::default(), &Default::default(), 4)
+            .unwrap(),
+    );
+    assert_eq!(
+        projection.project(&record),
+        ByteRecord::from(vec!["b", "d"])
+    );
+    assert_eq!(projection.project(&short), ByteRecord::from(vec!["b"]));
+    assert_eq!(projection.position(3), Some(1));
+    assert_eq!(projection.position(2), None);
+}
+
+#[test]
+fn csv_load_test_filter() {
+    use agnes::access::DataIndex;
+    use agnes::filter::between;
+    use agnes::select::FieldSelect;
+    use agnes::source::csv::{CsvReader, CsvSource};
+    use agnes::value::Value;
+
+    let data = "state,value\nOH,4\nPA,7\nNY,\nWV,9\nOH,2\nTX,12\n";
+    let schema = schema![
+        fieldname parts::State = "state";
+        fieldname parts::Value = "value";
+    ];
+    let source = CsvSource::from_bytes(data.as_bytes()).unwrap();
+    let dv = CsvReader::new(&source, schema.clone())
+        .unwrap()
+        .filter::<parts::Value, _>(between(3, 10))
+        .read()
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.field::<parts::State>().to_vec(), vec!["OH", "PA", "WV"]);
+
+    // records must match every filter; predicates can also match missing values
+    let mut reader = CsvReader::new(&source, schema.clone())
+        .unwrap()
+        .filter::<parts::State, _>(|state: Value<&String>| state.map_or(false, |s| s != "PA"))
+        .filter::<parts::Value, _>(|value: Value<&u64>| value.map_or(true, |&v| v < 3));
+    let dv = reader.read().unwrap().into_view();
+    assert_eq!(dv.field::<parts::State>().to_vec(), vec!["NY", "OH"]);
+    assert_eq!(
+        dv.field::<parts::Value>().to_value_vec(),
+        vec![Value::Na, Value::Exists(2)]
+    );
+    let dv = reader.read_sample(10, 3).unwrap().into_view();
+    assert_eq!(dv.nrows(), 2);
+
+    // values are parsed as the field's type before being tested
+    let data = "state,value\nOH,4\nPA,x\n";
+    let source = CsvSource::from_bytes(data.as_bytes()).unwrap();
+    assert!(CsvReader::new(&source, schema)
+        .unwrap()
+        .filter::<parts::Value, _>(between(0, 10))
+        .read()
+        .is_err());
+}
+
+#[test]
+fn csv_load_test_batched() {
+    use agnes::access::DataIndex;
+    use agnes::error::AgnesError;
+    use agnes::select::FieldSelect;
+    use agnes::source::csv::{BatchControl, CsvReader, CsvSource};
+    use agnes::value::Value;
+
+    let data = "state,value\nOH,4\nPA,7\nNY,1\nWV,9\nOH,2\nTX,12\nCA,3\n";
+    let schema = schema![
+        fieldname parts::State = "state";
+        fieldname parts::Value = "value";
+    ];
+    let source = CsvSource::from_bytes(data.as_bytes()).unwrap();
+
+    // batches of 3 records, with a final partial batch
+    let mut batches = vec![];
+    let dv = CsvReader::new(&source, schema.clone())
+        .unwrap()
+        .read_batched(3, |batch, start| {
+            batches.push((start, batch.field::<parts::State>().to_vec()));
+            Ok(BatchControl::Continue)
+        })
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.nrows(), 7);
+    assert_eq!(
+        batches,
+        vec![
+            (0, vec!["OH".to_string(), "PA".into(), "NY".into()]),
+            (3, vec!["WV".to_string(), "OH".into(), "TX".into()]),
+            (6, vec!["CA".to_string()]),
+        ]
+    );
+
+    // stopping early keeps the records loaded so far
+    let dv = CsvReader::new(&source, schema.clone())
+        .unwrap()
+        .read_batched(2, |batch, _| {
+            Ok(if batch.field::<parts::Value>().iter().any(|v| v == 9) {
+                BatchControl::Stop
+            } else {
+                BatchControl::Continue
+            })
+        })
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.field::<parts::Value>().to_vec(), vec![4, 7, 1, 9]);
+
+    // filters apply before batching, and callback errors abort the load
+    let mut calls = 0;
+    let result = CsvReader::new(&source, schema)
+        .unwrap()
+        .filter::<parts::State, _>(|state: Value<&String>| state.map_or(false, |s| s == "OH"))
+        .read_batched(1, |batch, start| {
+            calls += 1;
+            assert_eq!(batch.nrows(), 1);
+            if start == 1 {
+                Err(AgnesError::Validation("second OH record".into()))
+            } else {
+                Ok(BatchControl::Continue)
+            }
+        });
+    assert!(result.is_err());
+    assert_eq!(calls, 2);
+}
@@ -0,0 +1,120 @@
+#[macro_use]
+extern crate agnes;
+
+use agnes::access::DataIndex;
+use agnes::select::FieldSelect;
+use agnes::source::csv::TextEncoding;
+use agnes::source::fwf::{load_fwf, FwfReader, FwfSource};
+use agnes::value::Value;
+
+tablespace![
+    pub table station {
+        StationId: u64,
+        Name: String,
+        Elevation: f64,
+    }
+];
+
+const STATIONS: &str = "\
+ID     NAME                ELEV
+------ ------------------- -----
+000123 Mount Washington    1917.
+000456 Death Valley        -86.0\r
+000789 Unknown
+
+";
+
+#[test]
+fn fwf_load_test() {
+    let dv = load_fwf(
+        STATIONS.as_bytes().to_vec(),
+        2,
+        fwf_schema![
+            columns station::StationId = 0..6;
+            columns station::Name = 7..26;
+            columns station::Elevation = 27..32;
+        ],
+    )
+    .unwrap();
+    assert_eq!(dv.nrows(), 3);
+    assert_eq!(
+        dv.field::<station::StationId>().to_vec(),
+        vec![123u64, 456, 789]
+    );
+    assert_eq!(
+        dv.field::<station::Name>().to_vec(),
+        vec!["Mount Washington", "Death Valley", "Unknown"]
+    );
+    assert_eq!(
+        dv.field::<station::Elevation>().to_value_vec(),
+        vec![Value::Exists(1917.0), Value::Exists(-86.0), Value::Na]
+    );
+}
+
+#[test]
+fn fwf_errors() {
+    let source = FwfSource::new(STATIONS.as_bytes().to_vec()).skip_lines(2);
+    assert!(FwfReader::new(
+        &source,
+        fwf_schema![
+            columns station::StationId = 6..6;
+        ]
+    )
+    .is_err());
+
+    // names cannot be parsed as integers
+    let mut reader = FwfReader::new(
+        &source,
+        fwf_schema![
+            columns station::StationId = 7..26;
+        ],
+    )
+    .unwrap();
+    assert!(reader.read().is_err());
+}
+
+#[test]
+fn fwf_options() {
+    // KOI8-R encoded, with a single-byte Cyrillic name
+    let mut contents = b"000123 Mount Washington    1917.\n000456 ".to_vec();
+    contents.extend_from_slice(b"\xe9\xd2\xcb\xd5\xd4\xd3\xcb");
+    contents.extend_from_slice(b"             -86.0\n000789                     \n");
+    let schema = || {
+        fwf_schema![
+            columns station::StationId = 0..6;
+            columns station::Name = 7..26;
+            columns station::Elevation = 27..32;
+        ]
+    };
+
+    let source = FwfSource::new(contents.clone())
+        .encoding(TextEncoding::from_label("koi8-r").unwrap())
+        .empty_as_na(false);
+    let dv = FwfReader::new(&source, schema())
+        .unwrap()
+        .read()
+        .unwrap()
+        .into_view();
+    assert_eq!(
+        dv.field::<station::Name>().to_vec(),
+        vec!["Mount Washington", "Иркутск", ""]
+    );
+    // empty values which cannot be parsed are still missing
+    assert_eq!(
+        dv.field::<station::Elevation>().to_value_vec(),
+        vec![Value::Exists(1917.0), Value::Exists(-86.0), Value::Na]
+    );
+
+    let dv = FwfReader::new(&FwfSource::new(contents), schema())
+        .unwrap()
+        .read()
+        .unwrap()
+        .into_view();
+    assert_eq!(dv.field::<station::Name>().get_datum(2).unwrap(), Value::Na);
+
+    // invalid input for a fixed encoding fails
+    let source =
+        FwfSource::new(b"\xff\xfe".to_vec()).encoding(TextEncoding::from_label("utf-8").unwrap());
+    let mut reader = FwfReader::new(&source, schema()).unwrap();
+    assert!(reader.read().is_err());
+}
@@ -67,3 +67,46 @@ fn csv_serialize_test() {
         }]
     );
 }
+
+#[test]
+fn csv_serialize_rows_test() {
+    use sample::*;
+
+    let sample_schema = schema![
+        fieldname State = "state";
+        fieldname Value1 = "val1";
+        fieldname Value2 = "val2";
+    ];
+    let (mut csv_rdr, _) = common::load_csv_file("sample1.csv", sample_schema);
+
+    let dv = csv_rdr.read().unwrap().into_view();
+    let dv_json: serde_json::Value =
+        serde_json::from_slice(&serde_json::to_vec(&dv.serialize_rows()).unwrap()).unwrap();
+
+    assert_eq!(
+        dv_json,
+        json![[
+            { "State": "OH", "Value1": 4, "Value2": 5.03 },
+            { "State": "PA", "Value1": 54, "Value2": 2.34 },
+            { "State": "NH", "Value1": 23, "Value2": 0.42 },
+            { "State": "NC", "Value1": 21, "Value2": 0.204 },
+            { "State": "CA", "Value1": 85, "Value2": 0.32 },
+            { "State": "NY", "Value1": 32, "Value2": 3.21 },
+            { "State": "VA", "Value1": 44, "Value2": 5.66 },
+            { "State": "SC", "Value1": 89, "Value2": 9.11 }
+        ]]
+    );
+
+    // filtered views serialize only the remaining records
+    let dv = dv.filter::<Value1, _>(|val: agnes::value::Value<&u64>| val > 50);
+    let dv_json: serde_json::Value =
+        serde_json::from_slice(&serde_json::to_vec(&dv.serialize_rows()).unwrap()).unwrap();
+    assert_eq!(
+        dv_json,
+        json![[
+            { "State": "PA", "Value1": 54, "Value2": 2.34 },
+            { "State": "CA", "Value1": 85, "Value2": 0.32 },
+            { "State": "SC", "Value1": 89, "Value2": 9.11 }
+        ]]
+    );
+}
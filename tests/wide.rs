@@ -0,0 +1,110 @@
+//! Tests of views with many fields: wider than the compiler's default recursion limit supports.
+#![recursion_limit = "256"]
+
+#[macro_use]
+extern crate agnes;
+extern crate serde_json;
+
+use agnes::cons::Nil;
+use agnes::field::FieldData;
+use agnes::store::DataStore;
+
+tablespace![
+    pub table wide {
+        F0: u64, F1: u64, F2: u64, F3: u64, F4: u64, F5: u64, F6: u64, F7: u64,
+        F8: u64, F9: u64, F10: u64, F11: u64, F12: u64, F13: u64, F14: u64, F15: u64,
+        F16: u64, F17: u64, F18: u64, F19: u64, F20: u64, F21: u64, F22: u64, F23: u64,
+        F24: u64, F25: u64, F26: u64, F27: u64, F28: u64, F29: u64, F30: u64, F31: u64,
+        F32: u64, F33: u64, F34: u64, F35: u64, F36: u64, F37: u64, F38: u64, F39: u64,
+        F40: u64, F41: u64, F42: u64, F43: u64, F44: u64, F45: u64, F46: u64, F47: u64,
+        F48: u64, F49: u64, F50: u64, F51: u64, F52: u64, F53: u64, F54: u64, F55: u64,
+        F56: u64, F57: u64, F58: u64, F59: u64, F60: u64, F61: u64, F62: u64, F63: u64,
+    }
+];
+
+#[test]
+fn wide_view() {
+    let dv = DataStore::<Nil>::empty()
+        .push_back_field::<wide::F0, _>(FieldData::<u64>::from_vec(vec![0u64, 1000]))
+        .push_back_field::<wide::F1, _>(FieldData::<u64>::from_vec(vec![1u64, 1001]))
+        .push_back_field::<wide::F2, _>(FieldData::<u64>::from_vec(vec![2u64, 1002]))
+        .push_back_field::<wide::F3, _>(FieldData::<u64>::from_vec(vec![3u64, 1003]))
+        .push_back_field::<wide::F4, _>(FieldData::<u64>::from_vec(vec![4u64, 1004]))
+        .push_back_field::<wide::F5, _>(FieldData::<u64>::from_vec(vec![5u64, 1005]))
+        .push_back_field::<wide::F6, _>(FieldData::<u64>::from_vec(vec![6u64, 1006]))
+        .push_back_field::<wide::F7, _>(FieldData::<u64>::from_vec(vec![7u64, 1007]))
+        .push_back_field::<wide::F8, _>(FieldData::<u64>::from_vec(vec![8u64, 1008]))
+        .push_back_field::<wide::F9, _>(FieldData::<u64>::from_vec(vec![9u64, 1009]))
+        .push_back_field::<wide::F10, _>(FieldData::<u64>::from_vec(vec![10u64, 1010]))
+        .push_back_field::<wide::F11, _>(FieldData::<u64>::from_vec(vec![11u64, 1011]))
+        .push_back_field::<wide::F12, _>(FieldData::<u64>::from_vec(vec![12u64, 1012]))
+        .push_back_field::<wide::F13, _>(FieldData::<u64>::from_vec(vec![13u64, 1013]))
+        .push_back_field::<wide::F14, _>(FieldData::<u64>::from_vec(vec![14u64, 1014]))
+        .push_back_field::<wide::F15, _>(FieldData::<u64>::from_vec(vec![15u64, 1015]))
+        .push_back_field::<wide::F16, _>(FieldData::<u64>::from_vec(vec![16u64, 1016]))
+        .push_back_field::<wide::F17, _>(FieldData::<u64>::from_vec(vec![17u64, 1017]))
+        .push_back_field::<wide::F18, _>(FieldData::<u64>::from_vec(vec![18u64, 1018]))
+        .push_back_field::<wide::F19, _>(FieldData::<u64>::from_vec(vec![19u64, 1019]))
+        .push_back_field::<wide::F20, _>(FieldData::<u64>::from_vec(vec![20u64, 1020]))
+        .push_back_field::<wide::F21, _>(FieldData::<u64>::from_vec(vec![21u64, 1021]))
+        .push_back_field::<wide::F22, _>(FieldData::<u64>::from_vec(vec![22u64, 1022]))
+        .push_back_field::<wide::F23, _>(FieldData::<u64>::from_vec(vec![23u64, 1023]))
+        .push_back_field::<wide::F24, _>(FieldData::<u64>::from_vec(vec![24u64, 1024]))
+        .push_back_field::<wide::F25, _>(FieldData::<u64>::from_vec(vec![25u64, 1025]))
+        .push_back_field::<wide::F26, _>(FieldData::<u64>::from_vec(vec![26u64, 1026]))
+        .push_back_field::<wide::F27, _>(FieldData::<u64>::from_vec(vec![27u64, 1027]))
+        .push_back_field::<wide::F28, _>(FieldData::<u64>::from_vec(vec![28u64, 1028]))
+        .push_back_field::<wide::F29, _>(FieldData::<u64>::from_vec(vec![29u64, 1029]))
+        .push_back_field::<wide::F30, _>(FieldData::<u64>::from_vec(vec![30u64, 1030]))
+        .push_back_field::<wide::F31, _>(FieldData::<u64>::from_vec(vec![31u64, 1031]))
+        .push_back_field::<wide::F32, _>(FieldData::<u64>::from_vec(vec![32u64, 1032]))
+        .push_back_field::<wide::F33, _>(FieldData::<u64>::from_vec(vec![33u64, 1033]))
+        .push_back_field::<wide::F34, _>(FieldData::<u64>::from_vec(vec![34u64, 1034]))
+        .push_back_field::<wide::F35, _>(FieldData::<u64>::from_vec(vec![35u64, 1035]))
+        .push_back_field::<wide::F36, _>(FieldData::<u64>::from_vec(vec![36u64, 1036]))
+        .push_back_field::<wide::F37, _>(FieldData::<u64>::from_vec(vec![37u64, 1037]))
+        .push_back_field::<wide::F38, _>(FieldData::<u64>::from_vec(vec![38u64, 1038]))
+        .push_back_field::<wide::F39, _>(FieldData::<u64>::from_vec(vec![39u64, 1039]))
+        .push_back_field::<wide::F40, _>(FieldData::<u64>::from_vec(vec![40u64, 1040]))
+        .push_back_field::<wide::F41, _>(FieldData::<u64>::from_vec(vec![41u64, 1041]))
+        .push_back_field::<wide::F42, _>(FieldData::<u64>::from_vec(vec![42u64, 1042]))
+        .push_back_field::<wide::F43, _>(FieldData::<u64>::from_vec(vec![43u64, 1043]))
+        .push_back_field::<wide::F44, _>(FieldData::<u64>::from_vec(vec![44u64, 1044]))
+        .push_back_field::<wide::F45, _>(FieldData::<u64>::from_vec(vec![45u64, 1045]))
+        .push_back_field::<wide::F46, _>(FieldData::<u64>::from_vec(vec![46u64, 1046]))
+        .push_back_field::<wide::F47, _>(FieldData::<u64>::from_vec(vec![47u64, 1047]))
+        .push_back_field::<wide::F48, _>(FieldData::<u64>::from_vec(vec![48u64, 1048]))
+        .push_back_field::<wide::F49, _>(FieldData::<u64>::from_vec(vec![49u64, 1049]))
+        .push_back_field::<wide::F50, _>(FieldData::<u64>::from_vec(vec![50u64, 1050]))
+        .push_back_field::<wide::F51, _>(FieldData::<u64>::from_vec(vec![51u64, 1051]))
+        .push_back_field::<wide::F52, _>(FieldData::<u64>::from_vec(vec![52u64, 1052]))
+        .push_back_field::<wide::F53, _>(FieldData::<u64>::from_vec(vec![53u64, 1053]))
+        .push_back_field::<wide::F54, _>(FieldData::<u64>::from_vec(vec![54u64, 1054]))
+        .push_back_field::<wide::F55, _>(FieldData::<u64>::from_vec(vec![55u64, 1055]))
+        .push_back_field::<wide::F56, _>(FieldData::<u64>::from_vec(vec![56u64, 1056]))
+        .push_back_field::<wide::F57, _>(FieldData::<u64>::from_vec(vec![57u64, 1057]))
+        .push_back_field::<wide::F58, _>(FieldData::<u64>::from_vec(vec![58u64, 1058]))
+        .push_back_field::<wide::F59, _>(FieldData::<u64>::from_vec(vec![59u64, 1059]))
+        .push_back_field::<wide::F60, _>(FieldData::<u64>::from_vec(vec![60u64, 1060]))
+        .push_back_field::<wide::F61, _>(FieldData::<u64>::from_vec(vec![61u64, 1061]))
+        .push_back_field::<wide::F62, _>(FieldData::<u64>::from_vec(vec![62u64, 1062]))
+        .push_back_field::<wide::F63, _>(FieldData::<u64>::from_vec(vec![63u64, 1063]))
+        .into_view();
+    assert_eq!(dv.nrows(), 2);
+
+    let display = dv.to_string();
+    let rows = display
+        .lines()
+        .map(|line| line.split('|').map(str::trim).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    // title row, separator line, and one line for each record
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0].len(), 64);
+    assert_eq!((rows[0][0], rows[0][63]), ("F0", "F63"));
+    assert_eq!((rows[3][0], rows[3][63]), ("1000", "1063"));
+
+    let json = serde_json::to_value(&dv).unwrap();
+    assert_eq!(json.as_object().unwrap().len(), 64);
+    assert_eq!(json["F0"], serde_json::json!([0, 1000]));
+    assert_eq!(json["F63"], serde_json::json!([63, 1063]));
+}